@@ -10,6 +10,7 @@ import pkg from './package.json'
 export default [
   {
     input: {
+      app: './src/app.ts',
       fs: './src/fs.ts',
       path: './src/path.ts',
       dialog: './src/dialog.ts',
@@ -21,7 +22,10 @@ export default [
       window: './src/window.ts',
       cli: './src/cli.ts',
       notification: './src/notification.ts',
-      globalShortcut: './src/globalShortcut.ts'
+      globalShortcut: './src/globalShortcut.ts',
+      clipboard: './src/clipboard.ts',
+      process: './src/process.ts',
+      power: './src/power.ts'
     },
     treeshake: true,
     perf: true,
@@ -0,0 +1,72 @@
+import { invoke } from './tauri'
+
+declare global {
+  // eslint-disable-next-line @typescript-eslint/no-unused-vars
+  interface Window {
+    __TAURI_METADATA__: AppMetadata
+  }
+}
+
+export interface AppMetadata {
+  /**
+   * the `package > version` config
+   */
+  TAURI_APP_VERSION: string | null
+  /**
+   * the `package > productName` config
+   */
+  TAURI_APP_NAME: string | null
+  /**
+   * whether the app is a debug build
+   */
+  TAURI_DEBUG: boolean
+  /**
+   * the `features` config
+   */
+  features: Record<string, boolean>
+  /**
+   * the `build > injectedConstants` config
+   */
+  [key: string]: unknown
+}
+
+/**
+ * Gets the `build > injectedConstants` config and the app metadata.
+ * The app pages can also read them synchronously on `window.__TAURI_METADATA__`.
+ */
+async function getMetadata(): Promise<AppMetadata> {
+  return invoke<AppMetadata>({
+    __tauriModule: 'App',
+    message: {
+      cmd: 'getMetadata'
+    }
+  })
+}
+
+/**
+ * Shows the app windows and closes the startup splashscreen, once it's been shown for its `minimumDurationMs`.
+ * Call it when the app is ready; otherwise the splashscreen closes after its `timeoutMs`.
+ */
+async function closeSplashscreen(): Promise<void> {
+  return invoke<void>({
+    __tauriModule: 'App',
+    message: {
+      cmd: 'closeSplashscreen'
+    }
+  })
+}
+
+/**
+ * Gets the `features` config, e.g. to show the beta features.
+ * The app pages can also read it synchronously on `window.__TAURI_METADATA__.features`.
+ */
+async function getFeatures(): Promise<Record<string, boolean>> {
+  return invoke<Record<string, boolean>>({
+    __tauriModule: 'App',
+    message: {
+      cmd: 'getFeatures'
+    }
+  })
+}
+
+export { getMetadata, getFeatures, closeSplashscreen }
@@ -1,4 +1,5 @@
 import 'regenerator-runtime/runtime'
+import * as app from './app'
 import * as cli from './cli'
 import * as dialog from './dialog'
 import * as event from './event'
@@ -10,8 +11,12 @@ import * as tauri from './tauri'
 import * as window from './window'
 import * as notification from './notification'
 import * as globalShortcut from './globalShortcut'
+import * as clipboard from './clipboard'
+import * as process from './process'
+import * as power from './power'
 
 export {
+  app,
   cli,
   dialog,
   event,
@@ -22,5 +27,8 @@ export {
   tauri,
   window,
   notification,
-  globalShortcut
+  globalShortcut,
+  clipboard,
+  process,
+  power
 }
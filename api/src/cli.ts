@@ -3,23 +3,51 @@ import { invoke } from './tauri'
 export interface ArgMatch {
   /**
    * string if takes value
+   * number or boolean if takes a `number` or `boolean` value type
    * boolean if flag
-   * string[] or null if takes multiple values
+   * array or null if takes multiple values
    */
-  value: string | boolean | string[] | null
+  value: string | number | boolean | Array<string | number | boolean> | null
   /**
-   * number of occurrences
+   * all the values in the command line order across the occurrences, empty if flag or absent
+   */
+  values: Array<string | number | boolean>
+  /**
+   * number of occurrences, e.g. 3 for `-ddd`
    */
   occurrences: number
+  /**
+   * the command line positions of the values, or of the occurrences if flag
+   */
+  indices: number[]
+  /**
+   * `commandLine` if the arg was passed, `absent` otherwise
+   */
+  source: 'commandLine' | 'absent'
 }
 
 export interface SubcommandMatch {
   name: string
+  /**
+   * the alias the subcommand was invoked with, if it wasn't invoked with its name
+   */
+  alias: string | null
   matches: CliMatches
+  /**
+   * the raw args of an external subcommand
+   */
+  externalArgs: string[] | null
 }
 
 export interface CliMatches {
+  /**
+   * the passed args in the command line order, then the absent ones in the definition order
+   */
   args: { [name: string]: ArgMatch }
+  /**
+   * the present arg names of each group with a present arg
+   */
+  groups: { [name: string]: string[] }
   subcommand: SubcommandMatch | null
 }
 
@@ -0,0 +1,34 @@
+import { invoke } from './tauri'
+
+/**
+ * Writes the text to the clipboard, replacing its content.
+ * Requires the `tauri > allowlist > clipboard > writeText` config.
+ *
+ * @param text the text to write
+ */
+async function writeText(text: string): Promise<void> {
+  return invoke({
+    __tauriModule: 'Clipboard',
+    message: {
+      cmd: 'writeText',
+      data: text
+    }
+  })
+}
+
+/**
+ * Reads the clipboard text.
+ * Requires the `tauri > allowlist > clipboard > readText` config.
+ *
+ * @return {Promise<string | null>} promise resolving to the text, or null if the clipboard doesn't have text
+ */
+async function readText(): Promise<string | null> {
+  return invoke({
+    __tauriModule: 'Clipboard',
+    message: {
+      cmd: 'readText'
+    }
+  })
+}
+
+export { writeText, readText }
@@ -26,6 +26,10 @@ export interface SaveDialogOptions {
  * @param {boolean} [options.multiple=false]
  * @param {boolean} [options.directory=false]
  * @returns {Promise<string | string[]>} Promise resolving to the select path(s)
+ *
+ * It rejects with a `notAllowed` error if `directory` or `multiple` are set but the `tauri > allowlist > dialog` config disallows them,
+ * or if the `defaultPath` starts with a variable and `dialog > defaultPathVariables` isn't set.
+ * With `dialog > autoExtendFsScope`, the selected paths can be read with the fs API until the app exits.
  */
 async function open(
   options: OpenDialogOptions = {}
@@ -18,7 +18,10 @@ export enum BaseDirectory {
   Template,
   Video,
   Resource,
-  App
+  App,
+  AppData,
+  AppCache,
+  AppLog
 }
 
 export interface FsOptions {
@@ -1,5 +1,8 @@
 import { invoke } from './tauri'
 
+/**
+ * The client options, capped by the `tauri > allowlist > http > defaults` config.
+ */
 export interface ClientOptions {
   maxRedirections: boolean
   connectTimeout: number
@@ -50,6 +53,11 @@ export type HttpVerb =
   | 'CONNECT'
   | 'TRACE'
 
+/**
+ * The request options. The `timeout` is capped by the `tauri > allowlist > http > defaults > requestTimeoutMs` config,
+ * the `method` must be on its `allowedMethods` and the `url` and its redirects on the `tauri > allowlist > http > scope`.
+ * Otherwise the request rejects with an error object with the `urlNotInScope`, `methodNotAllowed` or `httpLimitExceeded` code.
+ */
 export interface HttpOptions {
   method: HttpVerb
   url: string
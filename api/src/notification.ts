@@ -25,6 +25,20 @@ async function requestPermission(): Promise<Permission> {
   return window.Notification.requestPermission()
 }
 
+/**
+ * @name permissionState
+ * @description Returns the notification permission: the user answer, or the `tauri > notification > defaultPermission` config.
+ * @return {Promise<Permission>}
+ */
+async function permissionState(): Promise<Permission> {
+  return invoke<Permission>({
+    __tauriModule: 'Notification',
+    message: {
+      cmd: 'notificationPermissionState'
+    }
+  })
+}
+
 function sendNotification(options: Options | string): void {
   if (typeof options === 'string') {
     // eslint-disable-next-line no-new
@@ -35,4 +49,9 @@ function sendNotification(options: Options | string): void {
   }
 }
 
-export { sendNotification, requestPermission, isPermissionGranted }
+export {
+  sendNotification,
+  requestPermission,
+  isPermissionGranted,
+  permissionState
+}
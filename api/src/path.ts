@@ -17,6 +17,54 @@ async function appDir(): Promise<string> {
   })
 }
 
+/**
+ * @name appDataDir
+ * @description Returns the path to the app data directory, named after the bundle identifier unless `tauri > paths > appDataDirName` is set. It is created on the first call.
+ * @return {Promise<string>}
+ */
+async function appDataDir(): Promise<string> {
+  return invoke<string>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'resolvePath',
+      path: '',
+      directory: BaseDirectory.AppData
+    }
+  })
+}
+
+/**
+ * @name appCacheDir
+ * @description Returns the path to the app cache directory, named after the bundle identifier unless `tauri > paths > cacheDirName` is set. It is created on the first call.
+ * @return {Promise<string>}
+ */
+async function appCacheDir(): Promise<string> {
+  return invoke<string>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'resolvePath',
+      path: '',
+      directory: BaseDirectory.AppCache
+    }
+  })
+}
+
+/**
+ * @name appLogDir
+ * @description Returns the path to the app log directory, named after the bundle identifier unless `tauri > paths > logDirName` is set. It is created on the first call.
+ * @return {Promise<string>}
+ */
+async function appLogDir(): Promise<string> {
+  return invoke<string>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'resolvePath',
+      path: '',
+      directory: BaseDirectory.AppLog
+    }
+  })
+}
+
 /**
  * @name audioDir
  * @description Returns the path to the user's audio directory.
@@ -310,6 +358,9 @@ async function resolvePath(
 
 export {
   appDir,
+  appDataDir,
+  appCacheDir,
+  appLogDir,
   audioDir,
   cacheDir,
   configDir,
@@ -0,0 +1,22 @@
+import { invoke } from './tauri'
+
+/**
+ * Keeps the system and the display awake, or lets them sleep again.
+ * The wake lock is released when the app exits.
+ * Requires the `tauri > allowlist > power > setPreventSleep` config.
+ *
+ * The `tauri > power` config emits the `idle`, `active` and `resume` events, listened with the `event` module.
+ *
+ * @param preventSleep whether the system and the display are kept awake
+ */
+async function setPreventSleep(preventSleep: boolean): Promise<void> {
+  return invoke({
+    __tauriModule: 'Power',
+    message: {
+      cmd: 'setPreventSleep',
+      preventSleep
+    }
+  })
+}
+
+export { setPreventSleep }
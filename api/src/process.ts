@@ -0,0 +1,32 @@
+import { invoke } from './tauri'
+
+/**
+ * Exits the app with the given code.
+ * Requires the `tauri > allowlist > process > exit` config.
+ *
+ * @param exitCode the exit code, defaults to 0
+ */
+async function exit(exitCode: number = 0): Promise<void> {
+  return invoke({
+    __tauriModule: 'Process',
+    message: {
+      cmd: 'exit',
+      exitCode
+    }
+  })
+}
+
+/**
+ * Relaunches the app with its original arguments, e.g. to apply an update or a settings change.
+ * Requires the `tauri > allowlist > process > relaunch` config.
+ */
+async function relaunch(): Promise<void> {
+  return invoke({
+    __tauriModule: 'Process',
+    message: {
+      cmd: 'relaunch'
+    }
+  })
+}
+
+export { exit, relaunch }
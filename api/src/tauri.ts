@@ -49,6 +49,16 @@ function transformCallback(
   return identifier
 }
 
+/**
+ * The error the APIs reject with when they aren't on the `tauri > allowlist` config.
+ */
+export interface NotAllowedError {
+  code: 'notAllowed'
+  /** The disabled API, e.g. `clipboard > readText`. */
+  api: string
+  message: string
+}
+
 /**
  * sends a message to the backend
  *
@@ -71,7 +81,8 @@ async function invoke<T>(args: any): Promise<T> {
       JSON.stringify({
         callback,
         error,
-        ...args
+        ...args,
+        __origin: window.location.origin
       })
     )
   })
@@ -367,10 +367,53 @@ class WindowManager {
       }
     })
   }
+
+  /**
+   * Sets the page zoom factor of the window, clamped to its `zoom > min` and `zoom > max` config.
+   * The factor is saved if the window remembers its state.
+   *
+   * @param {number} factor the zoom factor, `1` being the unzoomed page
+   * @returns {Promise<number>} the applied factor
+   */
+  async setZoom(factor: number): Promise<number> {
+    return invoke<number>({
+      __tauriModule: 'Window',
+      message: {
+        cmd: 'setZoom',
+        factor
+      }
+    })
+  }
+
+  /**
+   * Gets the page zoom factor of the window.
+   *
+   * @returns {Promise<number>} the zoom factor, `1` being the unzoomed page
+   */
+  async getZoom(): Promise<number> {
+    return invoke<number>({
+      __tauriModule: 'Window',
+      message: {
+        cmd: 'getZoom'
+      }
+    })
+  }
 }
 
 const manager = new WindowManager()
 
+/**
+ * The page zoom of a window.
+ */
+export interface ZoomOptions {
+  /** Whether `Ctrl` (`Cmd` on macOS) with `+`, `-` and `0` zooms the page, unless the page handles the key press itself. */
+  hotkeysEnabled?: boolean
+  /** The zoom factor the window starts with. */
+  defaultFactor?: number
+  min?: number
+  max?: number
+}
+
 export interface WindowOptions {
   url?: 'app' | string
   x?: number
@@ -389,8 +432,28 @@ export interface WindowOptions {
   visible?: boolean
   decorations?: boolean
   alwaysOnTop?: boolean
+  contextMenu?: boolean
+  rememberState?: boolean
+  zoom?: ZoomOptions
+  withGlobalTauri?: boolean
+  enableTauriApi?: boolean
+}
+
+/**
+ * The error `createWindow` rejects with when the window isn't on the `tauri > allowlist > windowScope` config.
+ */
+export interface WindowNotInScopeError {
+  code: 'windowNotInScope'
+  /** The label of the window. */
+  label: string
+  /** Whether the label or the URL isn't on the scope. */
+  reason: string
+  message: string
 }
 
+/**
+ * Creates a window. It rejects with a {@link WindowNotInScopeError} if the window isn't on the `tauri > allowlist > windowScope`.
+ */
 async function createWindow(label: string, options: WindowOptions = {}): Promise<TauriWindow> {
   await invoke({
     __tauriModule: 'Window',
@@ -7,6 +7,8 @@ use std::{
   path::Path,
 };
 
+// the build script only derives the schema, the helpers are used by the CLI
+#[allow(dead_code)]
 mod config_definition;
 
 pub fn main() -> Result<(), Box<dyn Error>> {
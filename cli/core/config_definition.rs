@@ -1,33 +1,530 @@
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{
+  de::{Deserializer, Error as DeError, MapAccess, SeqAccess, Visitor},
+  Deserialize, Serialize, Serializer,
+};
 use serde_json::Value as JsonValue;
+use url::Url;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, fmt, path::PathBuf, str::FromStr};
 
-#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
-#[serde(untagged)]
+/// A bundle target.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BundleTargetKind {
+  /// The Debian package (.deb).
+  Deb,
+  /// The AppImage bundle (.AppImage).
+  AppImage,
+  /// The RPM package (.rpm).
+  Rpm,
+  /// The Windows installer (.msi).
+  Msi,
+  /// The Windows NSIS installer (-setup.exe).
+  Nsis,
+  /// The macOS DMG image (.dmg).
+  Dmg,
+  /// The macOS app bundle (.app).
+  App,
+  /// The updater artifacts: the `app` and `appimage` bundles archived, and the signatures of the archives and the Windows installers.
+  Updater,
+}
+
+impl BundleTargetKind {
+  /// All the bundle targets.
+  pub const ALL: &'static [Self] = &[
+    Self::Deb,
+    Self::AppImage,
+    Self::Rpm,
+    Self::Msi,
+    Self::Nsis,
+    Self::Dmg,
+    Self::App,
+    Self::Updater,
+  ];
+
+  /// The target name.
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Deb => "deb",
+      Self::AppImage => "appimage",
+      Self::Rpm => "rpm",
+      Self::Msi => "msi",
+      Self::Nsis => "nsis",
+      Self::Dmg => "dmg",
+      Self::App => "app",
+      Self::Updater => "updater",
+    }
+  }
+}
+
+impl fmt::Display for BundleTargetKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+impl FromStr for BundleTargetKind {
+  type Err = String;
+
+  /// Parses a target name, ignoring its case.
+  fn from_str(name: &str) -> Result<Self, Self::Err> {
+    Self::ALL
+      .iter()
+      .find(|kind| kind.name().eq_ignore_ascii_case(name))
+      .copied()
+      .ok_or_else(|| {
+        format!(
+          "unknown bundle target `{}`, expected one of {}",
+          name,
+          Self::ALL
+            .iter()
+            .map(|kind| format!("`{}`", kind))
+            .collect::<Vec<_>>()
+            .join(", ")
+        )
+      })
+  }
+}
+
+impl Serialize for BundleTargetKind {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.name())
+  }
+}
+
+impl<'de> Deserialize<'de> for BundleTargetKind {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    name.parse().map_err(DeError::custom)
+  }
+}
+
+// target names are case insensitive, so the schema can't list them
+impl JsonSchema for BundleTargetKind {
+  fn schema_name() -> String {
+    "BundleTargetKind".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    String::json_schema(gen)
+  }
+}
+
+/// The bundle targets.
+#[derive(Debug, PartialEq, Clone)]
 pub enum BundleTarget {
-  All(Vec<String>),
+  /// All the targets supported on the current platform, `"all"`.
+  All,
+  /// A list of targets.
+  List(Vec<BundleTargetKind>),
+  /// A single target.
+  One(BundleTargetKind),
+}
+
+impl BundleTarget {
+  /// The targets to bundle, or `None` for all the targets supported on the current platform.
+  pub fn to_vec(&self) -> Option<Vec<BundleTargetKind>> {
+    match self {
+      Self::All => None,
+      Self::List(kinds) => Some(kinds.clone()),
+      Self::One(kind) => Some(vec![*kind]),
+    }
+  }
+}
+
+// the schema of the targets, which are deserialized by hand
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum BundleTargetInner {
+  List(Vec<BundleTargetKind>),
   One(String),
 }
 
+impl Serialize for BundleTarget {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Self::All => serializer.serialize_str("all"),
+      Self::List(kinds) => kinds.serialize(serializer),
+      Self::One(kind) => kind.serialize(serializer),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for BundleTarget {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    // deserialize the value first so the target errors aren't hidden by the untagged enum error
+    let value = JsonValue::deserialize(deserializer)?;
+    match value {
+      JsonValue::String(name) if name.eq_ignore_ascii_case("all") => Ok(Self::All),
+      JsonValue::String(name) => name.parse().map(Self::One).map_err(DeError::custom),
+      JsonValue::Array(names) => names
+        .into_iter()
+        .map(|name| match name {
+          JsonValue::String(name) => name.parse().map_err(DeError::custom),
+          _ => Err(DeError::custom("bundle targets must be strings")),
+        })
+        .collect::<Result<_, _>>()
+        .map(Self::List),
+      _ => Err(DeError::custom(
+        "bundle targets must be `\"all\"`, a target name or a list of target names",
+      )),
+    }
+  }
+}
+
+impl JsonSchema for BundleTarget {
+  fn schema_name() -> String {
+    "BundleTarget".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    BundleTargetInner::json_schema(gen)
+  }
+}
+
+/// The maintainer scripts of the Debian package.
+/// Each script must start with a shebang, and runs after the snippets generated by the bundler.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DebScriptsConfig {
+  /// The path to the script run before the package is installed.
+  pub preinst: Option<PathBuf>,
+  /// The path to the script run after the package is installed.
+  pub postinst: Option<PathBuf>,
+  /// The path to the script run before the package is removed.
+  pub prerm: Option<PathBuf>,
+  /// The path to the script run after the package is removed.
+  pub postrm: Option<PathBuf>,
+}
+
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct DebConfig {
   pub depends: Option<Vec<String>>,
   #[serde(default)]
   pub use_bootstrapper: bool,
+  /// Extra files to add to the package, mapping the source path to the absolute install path,
+  /// e.g. `{ "config/default.toml": "/etc/myapp/config.toml" }`.
+  pub files: Option<HashMap<PathBuf, PathBuf>>,
+  /// The package section, e.g. `utils`.
+  pub section: Option<String>,
+  /// The package priority, e.g. `optional`.
+  pub priority: Option<String>,
+  /// The path to a Debian-format changelog, installed to `/usr/share/doc/{package}/changelog.gz`.
+  pub changelog: Option<PathBuf>,
+  /// Whether the man pages of the `tauri > cli` definition are installed to `/usr/share/man/man1`.
+  #[serde(default)]
+  pub generate_man_page: bool,
+  /// The path to a handlebars template of the desktop file, rendered with the `categories`, `comment`, `exec`, `icon`, `mime_type`, `name`
+  /// and `localized_entries` variables, the last one with the `Name[xx]` and `Comment[xx]` lines of the localization.
+  /// It must keep the `Type`, `Name` and `Exec` keys of the `[Desktop Entry]` group.
+  pub desktop_template: Option<PathBuf>,
+  /// Extra keys merged into the generated desktop file, e.g. `{ "StartupWMClass": "myapp", "Keywords": "notes;todo;" }`.
+  /// Ignored if `desktopTemplate` is set.
+  pub desktop_entries: Option<HashMap<String, String>>,
+  /// The maintainer scripts of the package.
+  #[serde(default)]
+  pub scripts: DebScriptsConfig,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct OsxConfig {
+  /// The frameworks and dynamic libraries to bundle on `Contents/Frameworks`:
+  /// `.framework` directories, `.dylib` files, or framework names resolved from
+  /// `~/Library/Frameworks`, `/Library/Frameworks` and `/Network/Library/Frameworks`.
+  /// Their install names are rewritten to `@executable_path/../Frameworks`.
   pub frameworks: Option<Vec<String>>,
+  /// The minimum macOS version the app supports, `major.minor[.patch]` from `10.13`, e.g. `10.15`.
+  /// It's written to the Info.plist `LSMinimumSystemVersion`, and the app is compiled with the matching
+  /// `MACOSX_DEPLOYMENT_TARGET`, which can't be set to another version.
   pub minimum_system_version: Option<String>,
   pub exception_domain: Option<String>,
   pub license: Option<String>,
   #[serde(default)]
   pub use_bootstrapper: bool,
+  /// Custom keys to merge over the generated Info.plist, e.g. `{ "NSCameraUsageDescription": "..." }`.
+  /// An `Info.plist` file next to `tauri.conf.json` is also merged, before these keys.
+  /// `CFBundleIdentifier` always matches the bundle identifier.
+  pub info_plist: Option<HashMap<String, JsonValue>>,
+  /// The identity used to sign the app bundle and the DMG, e.g. `Developer ID Application: Company (TEAMID)`.
+  pub signing_identity: Option<String>,
+  /// The team id used on notarization, for Apple IDs that belong to multiple teams.
+  /// The `APPLE_TEAM_ID` environment variable takes precedence.
+  pub provider_short_name: Option<String>,
+  /// The path to the entitlements plist used to sign the app bundle.
+  pub entitlements: Option<PathBuf>,
+  /// Whether to sign the app bundle with the hardened runtime, required by notarization.
+  #[serde(default)]
+  pub hardened_runtime: bool,
+  /// Whether to notarize the signed app bundle and DMG with `notarytool`, stapling the ticket to them.
+  /// The credentials are read from the `APPLE_KEYCHAIN_PROFILE` environment variable, a `notarytool store-credentials` profile,
+  /// from the `APPLE_API_KEY`, `APPLE_API_ISSUER` and `APPLE_API_KEY_PATH` ones for an App Store Connect API key,
+  /// or from the `APPLE_ID`, `APPLE_PASSWORD` and `APPLE_TEAM_ID` ones, in that order.
+  /// `tauri build --skip-notarize` skips it.
+  #[serde(default)]
+  pub notarize: bool,
+  /// How many seconds the notarization waits for the notary service. Defaults to 3600.
+  pub notarize_timeout: Option<u64>,
+}
+
+/// A window size.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Size {
+  pub width: u32,
+  pub height: u32,
+}
+
+/// A position on a window.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Position {
+  pub x: u32,
+  pub y: u32,
+}
+
+/// The RPM package configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RpmConfig {
+  /// The package dependencies, e.g. `webkit2gtk3`.
+  pub depends: Option<Vec<String>>,
+  /// The package release. Defaults to `1`.
+  pub release: Option<String>,
+  /// The package epoch.
+  pub epoch: Option<u32>,
+  /// Extra files to add to the package, mapping the source path to the absolute install path,
+  /// e.g. `{ "config/default.toml": "/etc/myapp/config.toml" }`.
+  pub files: Option<HashMap<PathBuf, PathBuf>>,
+  /// The path to a handlebars template of the desktop file, rendered with the `categories`, `comment`,
+  /// `exec`, `icon`, `mime_type` and `name` variables.
+  pub desktop_template: Option<PathBuf>,
+}
+
+/// The AppImage bundle configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AppImageConfig {
+  /// Whether the GStreamer libraries and plugins are copied into the AppImage,
+  /// so the app can play audio and video on distros without them. Adds tens of megabytes to the AppImage.
+  #[serde(default)]
+  pub bundle_media_framework: bool,
+  /// Extra files to add to the AppImage, mapping the source path to the absolute path inside the AppImage,
+  /// e.g. `{ "assets/sample.ogg": "/usr/share/myapp/sample.ogg" }`.
+  pub files: Option<HashMap<PathBuf, PathBuf>>,
+}
+
+/// The DMG window configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DmgConfig {
+  /// The path to the window background image.
+  pub background: Option<PathBuf>,
+  /// The window size. Defaults to 660x400.
+  pub window_size: Option<Size>,
+  /// The position of the app icon. Defaults to (180, 170).
+  pub app_position: Option<Position>,
+  /// The position of the Applications folder link. Defaults to (480, 170).
+  pub application_folder_position: Option<Position>,
+}
+
+/// How the WebView2 runtime is installed by the Windows installer.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase", deny_unknown_fields)]
+pub enum WebviewInstallMode {
+  /// Downloads and runs the WebView2 bootstrapper at install time. Requires an internet connection.
+  DownloadBootstrapper,
+  /// Embeds the WebView2 bootstrapper in the installer. Requires an internet connection at install time.
+  EmbedBootstrapper {
+    /// The SHA-256 hash of the bootstrapper, as a hex string, e.g. the `sha256sum` of a `MicrosoftEdgeWebview2Setup.exe` you downloaded.
+    /// The build fails if the downloaded bootstrapper doesn't match it.
+    sha256: String,
+  },
+  /// Embeds the WebView2 offline installer in the installer.
+  OfflineInstaller {
+    /// The path to the offline installer executable.
+    path: PathBuf,
+  },
+  /// Ships a fixed version of the WebView2 runtime with the app, installed next to the executable.
+  FixedRuntime {
+    /// The path to the extracted fixed version runtime directory.
+    path: PathBuf,
+  },
+}
+
+/// The shortcuts created by the Windows installers.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ShortcutsConfig {
+  /// Whether the installers create a desktop shortcut. Defaults to `false`.
+  pub desktop: Option<bool>,
+  /// Whether the installers create a start menu shortcut. Defaults to `true`.
+  pub start_menu: Option<bool>,
+  /// The start menu folder of the shortcut. Defaults to the product name on the MSI installer,
+  /// and to the start menu programs root on the NSIS installer.
+  pub start_menu_folder: Option<String>,
+}
+
+/// The Windows bundle configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WindowsConfig {
+  /// How the WebView2 runtime is installed if it's missing.
+  /// Defaults to not installing it.
+  pub webview_install_mode: Option<WebviewInstallMode>,
+  /// The WiX cultures of the MSI installer, e.g. `["en-US", "de-DE", "ja-JP"]`.
+  /// One installer is built per culture, with the culture suffix on its name.
+  /// Defaults to a single `en-US` installer without the suffix.
+  pub wix_languages: Option<Vec<String>>,
+  /// The MSI ProductVersion, in the `major.minor.build` format with `major` and `minor` up to 255 and `build` up to 65535.
+  /// Defaults to the app version without its pre-release and build metadata.
+  pub msi_version: Option<String>,
+  /// The MSI UpgradeCode GUID, shared by every version of the app so a new installer replaces the installed one.
+  /// Defaults to a GUID derived from `tauri.bundle.identifier`. Set it to keep upgrading the apps that already shipped
+  /// an installer with another code; before this setting, the code was the name-based UUID of `<main binary name>.app.x64`
+  /// on the DNS namespace.
+  pub upgrade_code: Option<String>,
+  /// Whether the MSI installer can replace a newer installed version. Otherwise, installing an older version
+  /// is blocked with a message. Defaults to `false`.
+  #[serde(default)]
+  pub allow_downgrades: bool,
+  /// The shortcuts created by the installers.
+  #[serde(default)]
+  pub shortcuts: ShortcutsConfig,
+  /// WiX source files with `<Fragment>` elements compiled and linked with the MSI installer,
+  /// e.g. to write registry keys, add dialogs or register a service.
+  pub wix_fragment_paths: Option<Vec<PathBuf>>,
+  /// The ids of the fragments `<Component>` elements installed with the app.
+  pub component_refs: Option<Vec<String>>,
+  /// The ids of the fragments `<Feature>` elements included on the MSI installer.
+  pub feature_refs: Option<Vec<String>>,
+}
+
+/// Who the NSIS installer installs the app for.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum NsisInstallMode {
+  /// Installs the app for the current user, without requiring admin rights.
+  CurrentUser,
+  /// Installs the app for all the users, requiring admin rights.
+  PerMachine,
+  /// Lets the user choose on the installer, requiring admin rights for all the users.
+  Both,
+}
+
+/// The compression algorithm of the NSIS installer.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum NsisCompression {
+  /// No compression.
+  None,
+  /// The zlib algorithm, the fastest to compress.
+  Zlib,
+  /// The bzip2 algorithm.
+  Bzip2,
+  /// The LZMA algorithm, producing the smallest installers.
+  Lzma,
+}
+
+/// The NSIS installer configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NsisConfig {
+  /// Who the installer installs the app for. Defaults to `currentUser`.
+  pub install_mode: Option<NsisInstallMode>,
+  /// The path to a license shown before the installation.
+  pub license: Option<PathBuf>,
+  /// The path to the header image of the installer pages, a 150x57 bitmap.
+  pub header_image: Option<PathBuf>,
+  /// The path to the sidebar image of the welcome and finish pages, a 164x314 bitmap.
+  pub sidebar_image: Option<PathBuf>,
+  /// The installer languages, e.g. `["English", "French"]`. Defaults to English.
+  pub languages: Option<Vec<String>>,
+  /// The compression algorithm of the installer. Defaults to `lzma`.
+  pub compression: Option<NsisCompression>,
+}
+
+/// The app resources to bundle.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BundleResources {
+  /// A list of paths or glob patterns, placed on the resource directory with their relative path.
+  List(Vec<String>),
+  /// A map of paths or glob patterns to their destination on the resource directory.
+  /// Directories and glob patterns keep their structure under the destination.
+  Map(HashMap<String, String>),
+}
+
+/// The compression of the bundled resources.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourcesCompression {
+  /// The resources are bundled as is.
+  None,
+  /// The gzip algorithm, the fastest to decompress.
+  Gzip,
+  /// The brotli algorithm, producing the smallest bundles.
+  Brotli,
+}
+
+/// The app's role with respect to a file type.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub enum BundleTypeRole {
+  /// The app can read and edit the file type.
+  Editor,
+  /// The app can read the file type.
+  Viewer,
+  /// The app provides runtime services for the file type.
+  Shell,
+  /// The app declares the file type but can't open it.
+  None,
+}
+
+impl Default for BundleTypeRole {
+  fn default() -> Self {
+    Self::Editor
+  }
+}
+
+/// A file type the app can open.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FileAssociation {
+  /// The file extensions, without the leading dot.
+  pub ext: Vec<String>,
+  /// The file type name.
+  pub name: String,
+  /// The file type description.
+  pub description: Option<String>,
+  /// The file type MIME type, e.g. `text/markdown`. Defaults to `application/x-{name}`.
+  pub mime_type: Option<String>,
+  /// The app's role with respect to the file type, the macOS `CFBundleTypeRole`. Unused until the file associations are supported on macOS.
+  #[serde(default)]
+  pub role: BundleTypeRole,
+  /// The path to the file type icon.
+  pub icon: Option<String>,
+}
+
+/// The command used to sign the bundled executables and installers.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum SignCommand {
+  /// A shell command, with `%1` replaced by the quoted file path.
+  Shell(String),
+  /// A program and its arguments, with `%1` replaced by the file path.
+  Args(Vec<String>),
+}
+
+/// The deep link configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DeepLinkConfig {
+  /// The URL schemes the app handles, e.g. `myapp` for `myapp://auth/callback`.
+  /// Schemes must be lowercase and can't include the `:` separator.
+  #[serde(default)]
+  pub schemes: Vec<String>,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -35,7 +532,8 @@ pub struct OsxConfig {
 pub struct BundleConfig {
   /// Whether we should build your app with tauri-bundler or plain `cargo build`
   pub active: bool,
-  /// The bundle targets, currently supports ["deb", "osx", "msi", "appimage", "dmg"] or "all"
+  /// The bundle targets, currently supports ["deb", "appimage", "rpm", "msi", "nsis", "dmg", "app", "updater"] or "all".
+  /// "all" bundles every target supported on the current platform, except "updater", which requires the updater private key.
   pub targets: Option<BundleTarget>,
   pub name: Option<String>,
   /// The app's identifier
@@ -46,17 +544,100 @@ pub struct BundleConfig {
   /// App resources to bundle.
   /// Each resource is a path to a file or directory.
   /// Glob patterns are supported.
-  pub resources: Option<Vec<String>>,
+  /// It can also be a map of those paths to their destination on the resource directory,
+  /// e.g. `{ "assets/**/*.png": "images/", "../LICENSE": "legal/LICENSE" }`.
+  pub resources: Option<BundleResources>,
+  /// The compression of the bundled resources. Defaults to `none`.
+  /// The compressed resources are decompressed to the cache directory the first time their path is resolved.
+  pub resources_compression: Option<ResourcesCompression>,
   pub copyright: Option<String>,
+  /// The app publisher, used as the Debian package maintainer and the Windows installers manufacturer.
+  /// Defaults to the Cargo.toml authors on the Debian package and to the identifier on the Windows installers.
+  pub publisher: Option<String>,
+  /// The app homepage URL. Defaults to the Cargo.toml homepage.
+  pub homepage: Option<String>,
+  /// The path to the app license, shown by the installers and copied into the packages.
+  /// Takes precedence over `osx > license`.
+  pub license_file: Option<PathBuf>,
   pub category: Option<String>,
+  /// The app's short description, up to 80 characters on the Debian package and 255 on the MSI installer.
+  /// Defaults to the Cargo.toml `description`.
   pub short_description: Option<String>,
+  /// The app's long description. Defaults to the Cargo.toml `description`.
   pub long_description: Option<String>,
+  /// The bundle metadata of each locale, e.g. `{ "de": { "productName": "Meine App" }, "pt-BR": { ... } }`.
+  /// It localizes the desktop file entries, the RPM summary and description, the macOS `InfoPlist.strings`
+  /// and the MSI product name and comments of the matching `windows > wixLanguages` culture.
+  pub localization: Option<HashMap<String, BundleLocalization>>,
   pub script: Option<PathBuf>,
   #[serde(default)]
   pub deb: DebConfig,
   #[serde(default)]
   pub osx: OsxConfig,
+  #[serde(default)]
+  pub rpm: RpmConfig,
+  #[serde(default)]
+  pub appimage: AppImageConfig,
+  #[serde(default)]
+  pub dmg: DmgConfig,
+  #[serde(default)]
+  pub windows: WindowsConfig,
+  #[serde(default)]
+  pub nsis: NsisConfig,
+  /// External binaries to bundle, resolved with the target triple suffix,
+  /// e.g. `binaries/ffmpeg` resolves to `binaries/ffmpeg-x86_64-unknown-linux-gnu` and is bundled as `ffmpeg`.
   pub external_bin: Option<Vec<String>>,
+  /// The file types the app can open, registered on Linux and Windows.
+  /// The files the app is launched with are delivered to the app with the `open-file` event.
+  /// They aren't registered on macOS, where the files are opened through Apple Events the app can't receive yet.
+  pub file_associations: Option<Vec<FileAssociation>>,
+  /// The deep link configuration.
+  /// URLs the app is opened with are delivered to the app with the `deep-link` event,
+  /// forwarded to the running instance if there's one.
+  #[serde(default)]
+  pub deep_link: DeepLinkConfig,
+  /// The command used to sign the app binaries, the external binaries and the installers,
+  /// e.g. `"sign-tool --file %1"` or `["sign-tool", "--file", "%1"]`.
+  /// The file path is appended to the command if it doesn't include the `%1` placeholder.
+  pub sign_command: Option<SignCommand>,
+  /// The directory the artifacts are moved to once every bundle is done,
+  /// instead of the `target/<profile>/bundle/<format>` directories.
+  pub output_dir: Option<PathBuf>,
+  /// The template of the artifact file names, e.g. `{name}-{version}-{target}.{ext}`.
+  /// The placeholders are `{name}`, `{version}`, `{target}`, `{arch}`, `{profile}`, `{format}`, `{ext}`
+  /// and `{language}`, the WiX culture of the localized MSI installers.
+  /// The build fails before bundling if two artifacts get the same name.
+  pub artifact_name: Option<String>,
+}
+
+/// The bundle metadata of a locale. The unset fields fall back to the top-level ones.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BundleLocalization {
+  /// The localized app name.
+  pub product_name: Option<String>,
+  /// The localized short description.
+  pub short_description: Option<String>,
+  /// The localized long description, used on the RPM package.
+  pub long_description: Option<String>,
+  /// The comment of the desktop file and the MSI installer. Defaults to the localized short description.
+  pub comments: Option<String>,
+}
+
+/// The type of a CLI argument value, validated when the app is launched.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CliArgValueType {
+  /// Any string.
+  String,
+  /// An integer or floating point number.
+  Number,
+  /// `true` or `false`.
+  Boolean,
+  /// A file system path.
+  Path,
+  /// An absolute URL.
+  Url,
 }
 
 /// A CLI argument definition
@@ -135,6 +716,12 @@ pub struct CliArg {
   /// Allows specifying that an argument is required conditionally with the signature [arg, value]
   /// the requirement will only become valid if the `arg`'s value equals `${value}`.
   pub required_if_eq: Option<Vec<String>>,
+  /// Allows specifying that an argument is required if any of the [arg, value] conditions is met
+  /// i.e. the requirement will only become valid if one of the `arg`'s values equals its `${value}`.
+  pub required_if_eq_any: Option<Vec<(String, String)>>,
+  /// Allows specifying that an argument is required if all of the [arg, value] conditions are met
+  /// i.e. the requirement will only become valid if all of the `arg`'s values equal their `${value}`.
+  pub required_if_eq_all: Option<Vec<(String, String)>>,
   /// Requires that options use the --option=val syntax
   /// i.e. an equals between the option and associated value.
   pub require_equals: Option<bool>,
@@ -144,6 +731,38 @@ pub struct CliArg {
   /// It does not define position in the argument list as a whole. When utilized with multiple=true,
   /// only the last positional argument may be defined as multiple (i.e. the one with the highest index).
   pub index: Option<u64>,
+  /// The type of the argument value.
+  /// Invalid values fail with the usage error before the app is launched,
+  /// and the matches carry the values converted to the corresponding JSON type.
+  pub value_type: Option<CliArgValueType>,
+  /// A hidden long alias of the argument, e.g. the previous name of a renamed flag.
+  pub alias: Option<String>,
+  /// Hidden long aliases of the argument.
+  pub aliases: Option<Vec<String>>,
+  /// A hidden short alias of the argument, without the preceding -.
+  pub short_alias: Option<char>,
+  /// Long aliases of the argument shown on the help information.
+  pub visible_aliases: Option<Vec<String>>,
+  /// The order of the argument on the help information.
+  /// Arguments with the same order are sorted alphabetically.
+  pub display_order: Option<usize>,
+  /// The name of the group the argument belongs to, a shorthand for listing it on the group `args`.
+  pub group: Option<String>,
+}
+
+/// A group of CLI arguments.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CliGroup {
+  /// The unique group name.
+  pub name: String,
+  /// The names of the group arguments.
+  pub args: Option<Vec<String>>,
+  /// Whether one of the group arguments must be present.
+  pub required: Option<bool>,
+  /// Whether more than one of the group arguments can be present.
+  /// By default the group arguments are mutually exclusive.
+  pub multiple: Option<bool>,
 }
 
 /// describes a CLI configuration
@@ -164,11 +783,41 @@ pub struct CliConfig {
   after_help: Option<String>,
   /// list of args for the command
   args: Option<Vec<CliArg>>,
+  /// list of arg groups for the command
+  groups: Option<Vec<CliGroup>>,
   /// list of subcommands of this command.
   ///
   /// subcommands are effectively sub-apps, because they can contain their own arguments, subcommands, usage, etc.
   /// they also function just like the app command, in that they get their own auto generated help and usage
   subcommands: Option<HashMap<String, CliConfig>>,
+  /// the aliases the subcommand can also be invoked with, e.g. `rm` for `remove`
+  aliases: Option<Vec<String>>,
+  /// whether one of the subcommands must be used
+  subcommand_required: Option<bool>,
+  /// whether the args of the command can't be used with its subcommands
+  args_conflicts_with_subcommands: Option<bool>,
+  /// whether unknown subcommands are accepted, with their args passed through to the app
+  allow_external_subcommands: Option<bool>,
+  /// emits the matches in the previous shape, without the arg `values`, `indices` and `source`.
+  /// only read on the root command; deprecated, it will be removed on the next release
+  legacy_matches: Option<bool>,
+}
+
+impl CliConfig {
+  /// List of args for the command
+  pub fn args(&self) -> Option<&Vec<CliArg>> {
+    self.args.as_ref()
+  }
+
+  /// List of arg groups for the command
+  pub fn groups(&self) -> Option<&Vec<CliGroup>> {
+    self.groups.as_ref()
+  }
+
+  /// List of subcommands of this command
+  pub fn subcommands(&self) -> Option<&HashMap<String, CliConfig>> {
+    self.subcommands.as_ref()
+  }
 }
 
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -188,11 +837,173 @@ pub struct EmbeddedServerConfig {
   pub host: Option<String>,
   /// The embedded server port.
   /// If it's `random`, we'll generate one at runtime.
+  /// `tauri dev` picks the random port before running `beforeDevCommand`, which gets it on `TAURI_EMBEDDED_SERVER_PORT`.
+  /// The port is exposed to the frontend on `window.__TAURI_EMBEDDED_SERVER__` and to the app processes on `TAURI_EMBEDDED_SERVER_PORT`.
   pub port: Option<Port>,
 
   /// The base path of the embedded server.
   /// The path should always start and end in a forward slash, which the deserializer will ensure
   pub public_path: Option<String>,
+  /// How the assets are compressed, negotiated with the `Accept-Encoding` request header.
+  /// Defaults to `gzip`.
+  pub compression: Option<EmbeddedServerCompression>,
+  /// The response headers of the assets matching a glob pattern.
+  /// The entries are applied in order, so later entries override the headers of earlier ones.
+  pub headers: Option<Vec<AssetHeaders>>,
+  /// Whether `index.html` is served for the GET requests of missing paths without an extension,
+  /// so the client-side routes of single-page apps, e.g. `/settings/profile`, survive a reload.
+  #[serde(default)]
+  pub spa_fallback: bool,
+  /// The HTML page served with a 404 status on the GET requests of missing assets, relative to the dist directory.
+  pub not_found_page: Option<String>,
+  /// The HTML page served with a 500 status when an asset can't be read, relative to the dist directory.
+  pub error_page: Option<String>,
+  /// Allows a `host` that isn't a loopback address, exposing the assets to the network.
+  #[serde(default)]
+  pub dangerous_allow_remote_host: bool,
+  /// The requests forwarded to an upstream server instead of being served from the assets,
+  /// so the relative API requests of the frontend work like with the dev server proxy.
+  /// The longest matching path prefix wins. `build > devProxy` replaces them on the debug builds.
+  pub proxy: Option<Vec<ProxyRule>>,
+}
+
+/// A path prefix forwarded to an upstream server by the embedded server.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProxyRule {
+  /// The path prefix of the forwarded requests, e.g. `/api`, matched on whole path segments.
+  pub path: String,
+  /// The `http` or `https` URL of the upstream server, e.g. `http://localhost:3000`.
+  /// The request path is appended to its path.
+  /// The WebSocket upgrades are forwarded to the `http` upstreams.
+  pub upstream: String,
+  /// Whether the path prefix is removed from the forwarded request path, e.g. `/api/users` to `/users`.
+  #[serde(default)]
+  pub strip_prefix: bool,
+  /// The headers set on the forwarded requests; a `null` value removes the header.
+  /// The `Host` header is set to the upstream host.
+  pub request_headers: Option<HashMap<String, Option<String>>>,
+  /// The headers set on the upstream responses; a `null` value removes the header.
+  pub response_headers: Option<HashMap<String, Option<String>>>,
+}
+
+/// The response headers of the assets matching a glob pattern.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AssetHeaders {
+  /// The glob pattern matched against the asset path relative to the dist directory, e.g. `assets/*.js`.
+  pub pattern: String,
+  /// The header names and values.
+  /// The `Content-Length`, `Content-Encoding` and `Content-Range` headers are set by the server and can't be configured.
+  pub headers: HashMap<String, String>,
+}
+
+/// How the embedded server compresses the assets.
+/// Already compressed formats, e.g. images, fonts and media, are never compressed.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EmbeddedServerCompression {
+  /// The assets are sent uncompressed.
+  None,
+  /// The assets are gzip compressed at build time.
+  Gzip,
+  /// The assets are brotli compressed at build time.
+  Brotli,
+  /// The assets are brotli compressed at build time,
+  /// and gzip compressed on request for the clients that don't accept brotli.
+  Auto,
+}
+
+/// A window dimension.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WindowSize {
+  /// A number without a unit, in logical pixels since config version 2 and physical pixels before.
+  Unitless(f64),
+  /// Physical pixels, e.g. `"1200px"`.
+  Physical(f64),
+  /// Logical pixels, scaled by the monitor scale factor, e.g. `"1200lpx"`.
+  Logical(f64),
+}
+
+impl fmt::Display for WindowSize {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Unitless(size) => write!(f, "{}", size),
+      Self::Physical(size) => write!(f, "{}px", size),
+      Self::Logical(size) => write!(f, "{}lpx", size),
+    }
+  }
+}
+
+impl FromStr for WindowSize {
+  type Err = String;
+
+  fn from_str(size: &str) -> Result<Self, Self::Err> {
+    let size = size.trim();
+    let (number, unit): (&str, fn(f64) -> Self) = if let Some(number) = size.strip_suffix("lpx") {
+      (number, Self::Logical)
+    } else if let Some(number) = size.strip_suffix("px") {
+      (number, Self::Physical)
+    } else if size.ends_with('%') {
+      // the webview runtime can't read the monitors, so there's nothing to resolve a percentage against
+      return Err(format!(
+        "`{}` is not a valid window size; the `%` unit isn't supported yet, use the `px` or `lpx` unit",
+        size
+      ));
+    } else {
+      (size, Self::Unitless)
+    };
+    match number.trim().parse::<f64>() {
+      Ok(number) if number.is_finite() && number >= 0.0 => Ok(unit(number)),
+      _ => Err(format!(
+        "`{}` is not a valid window size; use a number, or a number with the `px` or `lpx` unit",
+        size
+      )),
+    }
+  }
+}
+
+impl Serialize for WindowSize {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Self::Unitless(size) => serializer.serialize_f64(*size),
+      size => serializer.serialize_str(&size.to_string()),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for WindowSize {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    match JsonValue::deserialize(deserializer)? {
+      JsonValue::Number(size) => size
+        .as_f64()
+        .map(Self::Unitless)
+        .ok_or_else(|| DeError::custom("the window size must be a number")),
+      JsonValue::String(size) => size.parse().map_err(DeError::custom),
+      _ => Err(DeError::custom(
+        "the window size must be a number or a string like `1200px` or `1200lpx`",
+      )),
+    }
+  }
+}
+
+impl JsonSchema for WindowSize {
+  fn schema_name() -> String {
+    "WindowSize".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    WindowSizeInner::json_schema(gen)
+  }
+}
+
+// the schema of the window sizes, which are deserialized by hand
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum WindowSizeInner {
+  Unitless(f64),
+  Unit(String),
 }
 
 /// The window configuration object.
@@ -207,18 +1018,20 @@ pub struct WindowConfig {
   pub x: Option<f64>,
   /// The vertical position of the window's top left corner
   pub y: Option<f64>,
-  /// The window width.
-  pub width: Option<f64>,
-  /// The window height.
-  pub height: Option<f64>,
-  /// The min window width.
-  pub min_width: Option<f64>,
-  /// The min window height.
-  pub min_height: Option<f64>,
-  /// The max window width.
-  pub max_width: Option<f64>,
-  /// The max window height.
-  pub max_height: Option<f64>,
+  /// The window width: a number, in logical pixels since config version 2 and physical pixels before,
+  /// or a string with the `px` (physical pixels) or `lpx` (logical pixels) unit.
+  /// The current webview runtime can't read the monitor scale factor yet, so both units are resolved without scaling.
+  pub width: Option<WindowSize>,
+  /// The window height, in the `width` units.
+  pub height: Option<WindowSize>,
+  /// The min window width, in the `width` units.
+  pub min_width: Option<WindowSize>,
+  /// The min window height, in the `width` units.
+  pub min_height: Option<WindowSize>,
+  /// The max window width, in the `width` units.
+  pub max_width: Option<WindowSize>,
+  /// The max window height, in the `width` units.
+  pub max_height: Option<WindowSize>,
   /// Whether the window is resizable or not.
   #[serde(default)]
   pub resizable: bool,
@@ -242,12 +1055,613 @@ pub struct WindowConfig {
   /// Whether the window should always be on top of other windows.
   #[serde(default)]
   pub always_on_top: bool,
+  /// Whether the webview shows its native context menu. Defaults to `true`.
+  pub context_menu: Option<bool>,
+  /// Whether the window is the startup splashscreen, shown first and closed once the other windows are ready,
+  /// on the `closeSplashscreen` command or after `tauri.splashscreen.timeoutMs`.
+  #[serde(default)]
+  pub splashscreen: bool,
+  /// Whether the window reopens with the position, size, maximized and fullscreen state and the zoom factor it had
+  /// when the app last ran, kept per label on the app directory. The `x`, `y`, `width`, `height` and `zoom > defaultFactor`
+  /// only apply on the first run.
+  #[serde(default)]
+  pub remember_state: bool,
+  /// The page zoom of the window. The frontend reads and changes it with `getZoom` and `setZoom`,
+  /// which need the `window` allowlist.
+  #[serde(default)]
+  pub zoom: ZoomConfig,
+  /// Overrides `build > withGlobalTauri` for the window, e.g. to keep the API modules off a window loading partner content.
+  /// The API bundle is only injected if `build > withGlobalTauri` or a window of `tauri > windows` enables it.
+  pub with_global_tauri: Option<bool>,
+  /// Whether the window gets the Tauri API and can send IPC messages. Defaults to `true`.
+  /// Without it the window has no `window.__TAURI__` object and its IPC messages are refused,
+  /// even from the domains on `tauri > security > dangerousRemoteDomainIpcAccess`.
+  pub enable_tauri_api: Option<bool>,
+  /// The feature of the `features` config the window is created with. Without it, the window is removed when the config is loaded.
+  pub enabled_when: Option<String>,
+}
+
+/// The page zoom of a window, applied with the CSS `zoom` of the page on every page the window loads.
+/// The default config leaves the page unzoomed, without handling any key.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ZoomConfig {
+  /// Whether `Ctrl` (`Cmd` on macOS) with `+`, `-` and `0` zooms the page in, out and back to `defaultFactor`.
+  /// The key presses the page handles itself, i.e. calls `preventDefault` on, are skipped,
+  /// and the `Ctrl` + wheel events are always left to the page.
+  #[serde(default)]
+  pub hotkeys_enabled: bool,
+  /// The zoom factor the window starts with, unless it remembers a saved one. `1` is the unzoomed page.
+  #[serde(default = "default_zoom_factor")]
+  pub default_factor: f64,
+  /// The minimum zoom factor; the hotkeys and `setZoom` stop there.
+  #[serde(default = "default_zoom_min")]
+  pub min: f64,
+  /// The maximum zoom factor; the hotkeys and `setZoom` stop there.
+  #[serde(default = "default_zoom_max")]
+  pub max: f64,
+}
+
+fn default_zoom_factor() -> f64 {
+  1.0
+}
+
+fn default_zoom_min() -> f64 {
+  0.25
+}
+
+fn default_zoom_max() -> f64 {
+  5.0
+}
+
+impl Default for ZoomConfig {
+  fn default() -> Self {
+    Self {
+      hotkeys_enabled: false,
+      default_factor: default_zoom_factor(),
+      min: default_zoom_min(),
+      max: default_zoom_max(),
+    }
+  }
+}
+
+/// The `tauri > windows` config.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum WindowList {
+  /// A list of windows.
+  List(Vec<WindowConfig>),
+  /// A map of window labels to their config, without the `label` field.
+  /// The windows are created on the label order.
+  Map(HashMap<String, WindowConfig>),
+}
+
+/// Deserializes the list or map of windows to a list sorted by label.
+fn deserialize_windows<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Vec<WindowConfig>, D::Error> {
+  struct WindowListVisitor;
+
+  impl<'de> Visitor<'de> for WindowListVisitor {
+    type Value = Vec<WindowConfig>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+      formatter.write_str("a list of windows or a map of window labels to their config")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+      let mut windows = Vec::new();
+      while let Some(window) = seq.next_element()? {
+        windows.push(window);
+      }
+      Ok(windows)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+      let mut windows: Vec<WindowConfig> = Vec::new();
+      while let Some(label) = map.next_key::<String>()? {
+        let mut window: WindowConfig = map.next_value()?;
+        if window.label.is_some() {
+          return Err(A::Error::custom(format!(
+            "the `{}` window can't have a `label` field, since the label is its key",
+            label
+          )));
+        }
+        if windows
+          .iter()
+          .any(|window| window.label.as_ref() == Some(&label))
+        {
+          return Err(A::Error::custom(format!(
+            "the `{}` window is defined twice",
+            label
+          )));
+        }
+        window.label = Some(label);
+        windows.push(window);
+      }
+      windows.sort_by(|a, b| a.label.cmp(&b.label));
+      Ok(windows)
+    }
+  }
+
+  deserializer.deserialize_any(WindowListVisitor)
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SecurityConfig {
   csp: Option<String>,
+  /// The remote domains allowed to send IPC messages on the listed windows.
+  /// Everything not listed keeps the IPC restricted to the app's own windows.
+  pub dangerous_remote_domain_ipc_access: Option<Vec<RemoteDomainAccessScope>>,
+  /// Whether the core JS prototypes and the `window.__TAURI__` object are frozen before the page scripts run,
+  /// so third-party scripts can't patch them. Frameworks extending the prototypes don't work with it.
+  #[serde(default)]
+  freeze_prototype: bool,
+  /// The limits of the IPC messages of each window, so a buggy or compromised frontend can't flood the app.
+  /// Exceeding a limit rejects the message and emits the `ipc-limit-exceeded` event.
+  ipc: Option<IpcConfig>,
+  /// Whether the bundled resources are checked against the manifest of their hashes embedded at build time,
+  /// refusing to start the app if one of them is missing or was modified,
+  /// or if the app wasn't built by `tauri build` so it has no manifest.
+  #[serde(default)]
+  pub verify_resources: bool,
+}
+
+/// The limits of the IPC messages of each window. The unset and `0` limits are unlimited.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IpcConfig {
+  /// The maximum size of a message, in bytes. Larger messages are rejected before they're deserialized.
+  pub max_payload_bytes: Option<u64>,
+  /// The maximum number of messages a window sends per second, allowing bursts of up to one second of messages.
+  pub max_requests_per_second: Option<u32>,
+  /// The limits of the commands, keyed by the `cmd` of the message, checked on top of the window limits.
+  pub per_command_limits: Option<HashMap<String, IpcCommandLimits>>,
+}
+
+/// The limits of the messages of a command. The unset and `0` limits are unlimited.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IpcCommandLimits {
+  /// The maximum size of a message, in bytes.
+  pub max_payload_bytes: Option<u64>,
+  /// The maximum number of messages of the command a window sends per second.
+  pub max_requests_per_second: Option<u32>,
+}
+
+/// A remote domain allowed to send IPC messages.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RemoteDomainAccessScope {
+  /// The domain of the page origin, e.g. `partner.example.com`.
+  /// Only the pages served over `https` from the domain get access, checked on every message.
+  pub domain: String,
+  /// The labels of the windows where the domain has IPC access.
+  pub windows: Vec<String>,
+  /// Whether the domain can use the Tauri API modules, subject to the allowlist.
+  #[serde(default)]
+  pub enable_tauri_api: bool,
+  /// The commands of the invoke handler the domain can call.
+  #[serde(default)]
+  pub commands: Vec<String>,
+}
+
+/// How the updater runs the Windows installer.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowsUpdateInstallMode {
+  /// Shows a basic UI with the installation progress and no prompts.
+  BasicUi,
+  /// Installs without any UI. Requires admin rights if the app was installed for all the users.
+  Quiet,
+  /// Shows the installation progress and closes the installer once it's done.
+  /// The `-setup.exe` installers run silently instead.
+  Passive,
+}
+
+/// The updater configuration on Windows.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UpdaterWindowsConfig {
+  /// How the downloaded installer is run. Defaults to `passive`.
+  pub install_mode: Option<WindowsUpdateInstallMode>,
+  /// Extra arguments passed to the installer, e.g. `/norestart`.
+  pub installer_args: Option<Vec<String>>,
+}
+
+/// The updater configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UpdaterConfig {
+  /// Whether the updater is enabled. The `updater` feature of the `tauri` crate is only compiled in when it's set.
+  #[serde(default)]
+  pub active: bool,
+  /// The updater configuration on Windows.
+  #[serde(default)]
+  pub windows: UpdaterWindowsConfig,
+  /// Whether the app is relaunched as soon as the update is installed. Defaults to `true`.
+  /// When `false`, the app relaunches itself once the user confirms it. The Windows installers don't relaunch the app.
+  pub relaunch: Option<bool>,
+  /// The URLs of the update manifests, tried in order until one responds.
+  /// The manifests can be generated with `tauri updater generate`.
+  pub endpoints: Option<Vec<String>>,
+  /// The base64 encoded minisign public key the downloaded updates are verified with.
+  /// Required when the updater is active: the updates are only installed once their signature is verified.
+  pub pubkey: Option<String>,
+  /// The proxy URL of the manifest and download requests, e.g. `http://proxy.example.com:3128`.
+  /// Defaults to the `HTTPS_PROXY` and `HTTP_PROXY` environment variables.
+  pub proxy: Option<String>,
+  /// The headers of the manifest and download requests, e.g. `{ "Authorization": "Bearer ${UPDATER_TOKEN}" }`.
+  /// Their `${VAR}` environment variables are substituted by the app when the requests are sent, so the tokens
+  /// stay out of the config and the binary. They aren't sent to the redirects to other origins.
+  #[serde(default)]
+  pub headers: HashMap<String, String>,
+}
+
+/// The action of a global shortcut.
+#[derive(Debug, PartialEq, Clone)]
+pub enum GlobalShortcutAction {
+  /// `toggleWindow:<label>`: shows the window if it's hidden, or hides it.
+  ToggleWindow(String),
+  /// `show` or `show:<label>`: shows and focuses the window, or the main window.
+  Show(Option<String>),
+  /// `hide` or `hide:<label>`: hides the window, or the main window.
+  Hide(Option<String>),
+  /// `quit`: quits the app.
+  Quit,
+  /// `emit:<event>`: emits the event to the windows, with the accelerator as payload.
+  Emit(String),
+}
+
+impl FromStr for GlobalShortcutAction {
+  type Err = String;
+
+  fn from_str(action: &str) -> Result<Self, Self::Err> {
+    let (name, argument) = match action.find(':') {
+      Some(index) => (&action[..index], Some(&action[index + 1..])),
+      None => (action, None),
+    };
+    let argument = argument.filter(|argument| !argument.is_empty());
+    match (name, argument) {
+      ("toggleWindow", Some(label)) => Ok(Self::ToggleWindow(label.to_string())),
+      ("show", label) => Ok(Self::Show(label.map(ToString::to_string))),
+      ("hide", label) => Ok(Self::Hide(label.map(ToString::to_string))),
+      ("quit", None) => Ok(Self::Quit),
+      ("emit", Some(event)) => Ok(Self::Emit(event.to_string())),
+      _ => Err(format!(
+        "`{}` is not a valid global shortcut action; use `toggleWindow:<label>`, `show`, `hide`, `quit` or `emit:<event>`",
+        action
+      )),
+    }
+  }
+}
+
+impl fmt::Display for GlobalShortcutAction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::ToggleWindow(label) => write!(f, "toggleWindow:{}", label),
+      Self::Show(None) => write!(f, "show"),
+      Self::Show(Some(label)) => write!(f, "show:{}", label),
+      Self::Hide(None) => write!(f, "hide"),
+      Self::Hide(Some(label)) => write!(f, "hide:{}", label),
+      Self::Quit => write!(f, "quit"),
+      Self::Emit(event) => write!(f, "emit:{}", event),
+    }
+  }
+}
+
+impl Serialize for GlobalShortcutAction {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&self.to_string())
+  }
+}
+
+impl<'de> Deserialize<'de> for GlobalShortcutAction {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    String::deserialize(deserializer)?
+      .parse()
+      .map_err(DeError::custom)
+  }
+}
+
+impl JsonSchema for GlobalShortcutAction {
+  fn schema_name() -> String {
+    "GlobalShortcutAction".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    String::json_schema(gen)
+  }
+}
+
+/// The single instance configuration.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum SingleInstance {
+  /// Whether the app is single instance, with the default options.
+  Enabled(bool),
+  /// The single instance options.
+  Options(SingleInstanceConfig),
+}
+
+impl SingleInstance {
+  /// Whether the app is single instance.
+  pub fn is_enabled(&self) -> bool {
+    match self {
+      Self::Enabled(enabled) => *enabled,
+      Self::Options(_) => true,
+    }
+  }
+}
+
+/// The single instance options.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SingleInstanceConfig {
+  /// Whether the running instance focuses its main window when the app is launched again. Defaults to `true`.
+  pub focus_existing: Option<bool>,
+  /// Whether the CLI args of the new instances are forwarded on the `second-instance` event. Defaults to `true`.
+  pub forward_args: Option<bool>,
+}
+
+/// The clipboard API access.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ClipboardAllowlistConfig {
+  /// Whether the frontend can read the clipboard text, including the text copied from other apps.
+  #[serde(default)]
+  pub read_text: bool,
+  /// Whether the frontend can write text to the clipboard.
+  #[serde(default)]
+  pub write_text: bool,
+  /// The feature of the `features` config the section is enabled with. Without it, its permissions are removed when the config is loaded.
+  pub enabled_when: Option<String>,
+}
+
+/// The dialog API access.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DialogAllowlistConfig {
+  /// Whether the frontend can show the open dialog.
+  #[serde(default)]
+  pub open: bool,
+  /// Whether the frontend can show the save dialog.
+  #[serde(default)]
+  pub save: bool,
+  /// Whether the frontend can show the message and ask dialogs. Defaults to `true`, they don't need a feature.
+  pub message: Option<bool>,
+  /// Whether the `defaultPath` can start with a `$HOME`-like variable, expanded like the `fsScope` paths.
+  #[serde(default)]
+  pub default_path_variables: bool,
+  /// Whether the open dialog can select directories. Defaults to `true`.
+  pub directory: Option<bool>,
+  /// Whether the open dialog can select multiple files. Defaults to `true`.
+  pub multiple: Option<bool>,
+  /// Whether the paths the user picks are added to the `fsScope` until the app exits,
+  /// so the frontend can read the files it asked the user for.
+  #[serde(default)]
+  pub auto_extend_fs_scope: bool,
+  /// The feature of the `features` config the section is enabled with. Without it, its permissions are removed when the config is loaded.
+  pub enabled_when: Option<String>,
+}
+
+/// The windows the frontend can create at runtime.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WindowScopeConfig {
+  /// The labels of the windows that can be created. The windows on `tauri > windows` can always be created,
+  /// loading their own URL or a scope URL.
+  #[serde(default)]
+  pub labels: Vec<String>,
+  /// The URLs the created windows can load, like the window `url`: `app`, an app path, or an external URL.
+  /// A `*` matches any characters, except in the host of a URL where it matches a single label, e.g. `https://*.tauri.studio/*`.
+  /// The scheme, the host and the path of a URL are matched separately, and a URL without a path only matches the root path.
+  #[serde(default)]
+  pub urls: Vec<String>,
+}
+
+/// The process API access.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProcessAllowlistConfig {
+  /// Whether the frontend can relaunch the app.
+  #[serde(default)]
+  pub relaunch: bool,
+  /// Whether the app is relaunched even if its executable is a symlink on macOS.
+  /// Dangerous: the symlink can be replaced to make the relaunch run a different binary.
+  #[serde(default)]
+  pub relaunch_dangerous_allow_symlink_macos: bool,
+  /// Whether the frontend can exit the app.
+  #[serde(default)]
+  pub exit: bool,
+  /// The feature of the `features` config the section is enabled with. Without it, its permissions are removed when the config is loaded.
+  pub enabled_when: Option<String>,
+}
+
+/// The limits of the http API requests. The per-client and per-request options can only lower them.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct HttpDefaultsConfig {
+  /// The time a request waits for the connection, in milliseconds. Defaults to no limit.
+  pub connect_timeout_ms: Option<u64>,
+  /// The time a request waits for the whole response, in milliseconds. Defaults to no limit.
+  pub request_timeout_ms: Option<u64>,
+  /// The maximum number of redirects a request follows. Defaults to `10`.
+  pub max_redirects: Option<usize>,
+  /// The methods of the requests, e.g. `["GET", "POST"]`. Defaults to any method.
+  pub allowed_methods: Option<Vec<String>>,
+}
+
+/// The http API constraints.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct HttpAllowlistConfig {
+  /// The URLs the http API can request, checked again on every redirect. A `*` matches any characters,
+  /// except in the host where it matches a single label, e.g. `https://api.example.com/*`.
+  /// The scheme, the host and the path are matched separately. Any URL can be requested if it's not set.
+  pub scope: Option<Vec<String>>,
+  /// The limits applied to every request.
+  #[serde(default)]
+  pub defaults: HttpDefaultsConfig,
+}
+
+/// The power API access.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PowerAllowlistConfig {
+  /// Whether the frontend can keep the system and the display awake with `setPreventSleep`.
+  #[serde(default)]
+  pub set_prevent_sleep: bool,
+  /// The feature of the `features` config the section is enabled with. Without it, its permissions are removed when the config is loaded.
+  pub enabled_when: Option<String>,
+}
+
+/// The allowlist configuration: the API modules the frontend can use.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistConfig {
+  /// The clipboard API access.
+  #[serde(default)]
+  pub clipboard: ClipboardAllowlistConfig,
+  /// The process API access.
+  #[serde(default)]
+  pub process: ProcessAllowlistConfig,
+  /// The power API access.
+  #[serde(default)]
+  pub power: PowerAllowlistConfig,
+  /// The http API constraints.
+  #[serde(default)]
+  pub http: HttpAllowlistConfig,
+  /// The dialog API access.
+  #[serde(default)]
+  pub dialog: DialogAllowlistConfig,
+  /// The windows the frontend can create with the `createWindow` API. Any window can be created if it's not set.
+  pub window_scope: Option<WindowScopeConfig>,
+  /// The paths the fs API can access, starting with a `$HOME`-like variable or absolute.
+  /// A `*` matches any characters of a path component, e.g. `$APPDATA/my-app/*`, and a `**` component matches
+  /// any number of components, e.g. `$APPDATA/my-app/**`. The symlinks are resolved before matching.
+  /// Any path can be accessed if it's not set.
+  pub fs_scope: Option<Vec<String>>,
+  /// The enabled API features, e.g. `readTextFile`, or `all` to enable all of them.
+  /// An entry can be enabled with a feature of the `features` config instead, e.g. `{ "enabledWhen": "beta" }`.
+  #[serde(flatten)]
+  #[schemars(with = "HashMap<String, AllowlistEntry>")]
+  pub features: HashMap<String, bool>,
+}
+
+/// An `allowlist` entry, resolved to a flag when the config is loaded.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum AllowlistEntry {
+  /// Whether the API feature is enabled.
+  Enabled(bool),
+  /// The API feature is enabled with a feature of the `features` config.
+  Gated(FeatureGate),
+}
+
+/// A reference to a feature of the `features` config.
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct FeatureGate {
+  /// The feature name.
+  enabled_when: String,
+}
+
+impl AllowlistConfig {
+  /// Whether all the API modules are enabled.
+  pub fn all(&self) -> bool {
+    *self.features.get("all").unwrap_or(&false)
+  }
+}
+
+/// The notification permission the app has before the user answers the permission prompt.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationPermission {
+  /// Notifications are allowed without prompting the user.
+  Granted,
+  /// The user is prompted when the app requests the permission.
+  Prompt,
+  /// Notifications are denied without prompting the user.
+  Denied,
+}
+
+/// The notification configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NotificationConfig {
+  /// The identifier the notifications are sent with. Defaults to `tauri.bundle.identifier`.
+  /// On macOS, notifications are only delivered when it matches the identifier of the app bundle.
+  pub identifier: Option<String>,
+  /// The notification permission before the user answers the permission prompt. Defaults to `prompt`.
+  pub default_permission: Option<NotificationPermission>,
+}
+
+/// The names of the app data, cache and log directories, created on the user directories of each platform.
+/// They default to `tauri.bundle.identifier`, so renaming the product keeps the user data.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PathsConfig {
+  /// The name of the app data directory, on the user data directory, e.g. `~/.local/share` on Linux.
+  pub app_data_dir_name: Option<String>,
+  /// The name of the app cache directory, on the user cache directory, e.g. `~/.cache` on Linux.
+  pub cache_dir_name: Option<String>,
+  /// The name of the app log directory, on `~/Library/Logs` on macOS and the user local data directory otherwise.
+  pub log_dir_name: Option<String>,
+  /// The previous bundle identifiers of the app. Their directories are moved to the current ones on startup,
+  /// unless the current ones already exist.
+  #[serde(default)]
+  pub migrate_from: Vec<String>,
+}
+
+/// The power configuration. The app gets the `idle` and `active` events when the user leaves and comes back,
+/// and the `resume` event when the system resumes after sleep.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PowerConfig {
+  /// The time without keyboard or mouse input after which the `idle` event is emitted, in seconds.
+  /// The `active` event is emitted on the next input. The events are never emitted on the platforms without an idle time,
+  /// e.g. Linux without the X11 screensaver extension.
+  pub idle_timeout_seconds: Option<u64>,
+  /// Whether the `resume` event is emitted when the system resumes after sleep, with the `suspendedSeconds`.
+  /// The sleep is noticed from the clock jump once the system is awake again, so there's no event before it sleeps.
+  #[serde(default)]
+  pub emit_resume: bool,
+  /// Whether the system and the display are kept awake from the app start, until the frontend calls `setPreventSleep(false)`
+  /// or the app exits.
+  #[serde(default)]
+  pub prevent_sleep: bool,
+}
+
+impl PowerConfig {
+  /// Whether the config needs the `power` feature of the `tauri` crate.
+  pub fn is_used(&self) -> bool {
+    self.idle_timeout_seconds.is_some() || self.emit_resume || self.prevent_sleep
+  }
+}
+
+/// The startup splashscreen configuration.
+/// The splashscreen is shown right away, while the other windows load hidden, and it's closed once the frontend calls
+/// `closeSplashscreen` or after the timeout. Closing it shows the other windows right away.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SplashscreenConfig {
+  /// The splashscreen HTML, loaded inline so it doesn't wait for the app assets.
+  /// Required unless a window is flagged with `splashscreen`.
+  pub html: Option<String>,
+  /// The splashscreen width, in the window `width` units. Defaults to `400`.
+  pub width: Option<WindowSize>,
+  /// The splashscreen height, in the window `width` units. Defaults to `300`.
+  pub height: Option<WindowSize>,
+  /// The minimum time the splashscreen is shown, in milliseconds. Defaults to `0`.
+  pub minimum_duration_ms: Option<u64>,
+  /// The time after which the other windows are shown even if the frontend didn't call `closeSplashscreen`,
+  /// e.g. because the main window failed to load, in milliseconds. Defaults to `10000`.
+  pub timeout_ms: Option<u64>,
 }
 
 /// The Tauri configuration object.
@@ -255,7 +1669,8 @@ pub struct SecurityConfig {
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct TauriConfig {
   /// The windows configuration.
-  #[serde(default)]
+  #[serde(default, deserialize_with = "deserialize_windows")]
+  #[schemars(with = "WindowList")]
   pub windows: Vec<WindowConfig>,
   /// The CLI configuration.
   pub cli: Option<CliConfig>,
@@ -264,61 +1679,382 @@ pub struct TauriConfig {
   /// The bundler configuration.
   #[serde(default)]
   pub bundle: BundleConfig,
+  /// The allowlist configuration.
   #[serde(default)]
-  pub allowlist: HashMap<String, bool>,
+  pub allowlist: AllowlistConfig,
   pub security: Option<SecurityConfig>,
+  /// The updater configuration.
+  #[serde(default)]
+  pub updater: UpdaterConfig,
+  /// Whether launching the app again forwards its args and deep links to the running instance, which gets a
+  /// `second-instance` event, instead of opening another instance. The lock is derived from `tauri.bundle.identifier`.
+  pub single_instance: Option<SingleInstance>,
+  /// The global shortcuts, registered even when the app is unfocused: a map of accelerators,
+  /// e.g. `CommandOrControl+Shift+Space`, to their action: `toggleWindow:<label>`, `show`, `hide`, `quit` or `emit:<event>`.
+  #[serde(default)]
+  pub global_shortcuts: HashMap<String, GlobalShortcutAction>,
+  /// The notification configuration.
+  #[serde(default)]
+  pub notification: NotificationConfig,
+  /// The startup splashscreen configuration.
+  pub splashscreen: Option<SplashscreenConfig>,
+  /// The app directories configuration.
+  #[serde(default)]
+  pub paths: PathsConfig,
+  /// The power configuration.
+  #[serde(default)]
+  pub power: PowerConfig,
 }
 
 /// The Build configuration object.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct BuildConfig {
-  /// the app's dev server URL, or the path to the directory containing an index.html file
+  /// the app's dev server URL, the path to the directory containing an index.html file,
+  /// or the list of the app's files for setups without a bundler
   #[serde(default = "default_dev_path")]
-  pub dev_path: String,
+  pub dev_path: AppUrl,
   /// the path to the app's dist dir. This path must contain your index.html file.
+  /// Like every path field, it's relative to the config file directory,
+  /// and it can start with the `$CONFIG_DIR`, `$PROJECT_ROOT`, `$HOME` or `$TEMP` variables;
+  /// the other path fields can also start with `$DIST_DIR`.
   #[serde(default = "default_dist_dir")]
   pub dist_dir: String,
+  /// The directory of the Rust project, relative to the project root (`--project-path`, or the folder found from the cwd).
+  /// Defaults to `src-tauri`. With it, the config file can live on the project root, e.g. `apps/desktop/tauri.conf.json`
+  /// with `"srcTauriDir": "native"`; the other paths are still relative to the config file, so such a config sets `distDir` too.
+  pub src_tauri_dir: Option<String>,
   /// a shell command to run before `tauri dev` kicks in
   pub before_dev_command: Option<String>,
+  /// How long `tauri dev` waits for the dev server URL to respond before launching the app, in milliseconds.
+  /// The debug app also waits that long to reload its windows if the dev server wasn't up when it started.
+  #[serde(default = "default_dev_server_timeout_ms")]
+  pub dev_server_timeout_ms: u64,
   /// a shell command to run before `tauri build` kicks in
   pub before_build_command: Option<String>,
+  /// a shell command to run after `tauri build` compiles the app and before it's bundled,
+  /// with the `TAURI_TARGET_TRIPLE`, `TAURI_PROFILE` and `TAURI_BINARY_PATH` environment variables
+  pub before_bundle_command: Option<String>,
+  /// The commands run on the `tauri build` and `tauri dev` lifecycle events, e.g. to upload the bundles.
+  pub hooks: Option<BuildHooksConfig>,
   /// Whether we should inject the Tauri API on `window.__TAURI__` or not.
   #[serde(default)]
   pub with_global_tauri: bool,
+  /// Constants injected into the frontend as `window.__TAURI_METADATA__` before the first page script runs,
+  /// and as environment variables of the `beforeDevCommand` and `beforeBuildCommand`,
+  /// along with `TAURI_APP_VERSION`, `TAURI_APP_NAME` and `TAURI_DEBUG`.
+  /// The strings are passed as is to the commands, the other values as JSON.
+  /// Every page script can read them, so they must not hold secrets.
+  pub injected_constants: Option<HashMap<String, JsonValue>>,
+  /// The binary used to build and run the application, e.g. `cross`. Defaults to `cargo`.
+  pub runner: Option<String>,
+  /// Features passed to `cargo` commands, e.g. `cargo build --features my-feature`.
+  pub features: Option<Vec<String>>,
+  /// Features of the `tauri` crate to enable even if the config doesn't use them, e.g. `cli` for an app that builds its CLI matches in Rust.
+  /// The CLI only enables the features of the config sections in use, e.g. `cli` if `tauri > cli` is set.
+  pub forced_features: Option<Vec<String>>,
+  /// The target triple to build against, e.g. `aarch64-unknown-linux-gnu`. Defaults to the host triple.
+  pub target: Option<String>,
+  /// The file watcher of `tauri dev`.
+  #[serde(default)]
+  pub dev_watcher: DevWatcherConfig,
+  /// The `tauri > embeddedServer > proxy` rules of the debug builds, e.g. to use a local backend.
+  /// `tauri dev` also passes the rules to the `beforeDevCommand` as JSON on the `TAURI_DEV_PROXY` environment variable,
+  /// so the dev server can apply them too.
+  pub dev_proxy: Option<Vec<ProxyRule>>,
+  /// The config overrides of the `debug` and `release` profiles, applied when the config is loaded,
+  /// so the printed config has the resolved values instead.
+  #[serde(skip_serializing)]
+  pub profiles: Option<BuildProfilesConfig>,
+}
+
+/// The lifecycle hooks. Each hook runs on the app directory with the `TAURI_HOOK` (its name), `TAURI_TARGET_TRIPLE`,
+/// `TAURI_PROFILE`, `TAURI_ARTIFACTS` (the JSON list of the artifacts) and `TAURI_PREVIOUS_EXIT_CODE`
+/// (the exit code of the step before the hook, when known) environment variables.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BuildHooksConfig {
+  /// Runs after `tauri build` compiles the app. The artifacts are the app binary path.
+  pub on_build_success: Option<BuildHook>,
+  /// Runs after `tauri build` bundles the app, e.g. to upload the bundles.
+  /// The artifacts are the `{ "format", "path" }` objects printed by `--print-artifacts-json`.
+  pub on_bundle_success: Option<BuildHook>,
+  /// Runs when `tauri dev` restarts the app after a change, with the changed path on `TAURI_CHANGED_PATH`.
+  /// The artifacts list is empty, since the app is rebuilt by `cargo run`.
+  pub on_dev_reload: Option<BuildHook>,
+}
+
+/// A lifecycle hook: the command, or the command and its options.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BuildHook {
+  /// The command. Its failures are reported without failing the build.
+  Command(String),
+  /// The command and its options.
+  Options(BuildHookOptions),
+}
+
+impl BuildHook {
+  /// The hook command.
+  pub fn command(&self) -> &str {
+    match self {
+      Self::Command(command) => command,
+      Self::Options(options) => &options.command,
+    }
+  }
+
+  /// Whether the hook failures fail the build.
+  pub fn strict(&self) -> bool {
+    match self {
+      Self::Command(_) => false,
+      Self::Options(options) => options.strict,
+    }
+  }
 }
 
-fn default_dev_path() -> String {
-  "".to_string()
+/// The command of a lifecycle hook and its options.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BuildHookOptions {
+  /// The command.
+  pub command: String,
+  /// Whether the hook failure fails the build, even though the step before it succeeded.
+  /// The failures are only reported otherwise.
+  #[serde(default)]
+  pub strict: bool,
+}
+
+/// The config overrides of the build profiles.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BuildProfilesConfig {
+  /// The overrides used by `tauri dev` and `tauri build --debug`.
+  pub debug: Option<BuildProfileConfig>,
+  /// The overrides used by `tauri build`.
+  pub release: Option<BuildProfileConfig>,
+}
+
+/// The config overrides of a build profile: the build fields, and the `tauri > security` fields on `security`.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct BuildProfileConfig {
+  /// Overrides `build > devPath`.
+  pub dev_path: Option<AppUrl>,
+  /// Overrides `build > distDir`.
+  pub dist_dir: Option<String>,
+  /// Overrides `build > beforeDevCommand`.
+  pub before_dev_command: Option<String>,
+  /// Overrides `build > beforeBuildCommand`.
+  pub before_build_command: Option<String>,
+  /// Overrides `build > beforeBundleCommand`.
+  pub before_bundle_command: Option<String>,
+  /// Overrides `build > withGlobalTauri`.
+  pub with_global_tauri: Option<bool>,
+  /// Overrides `build > runner`.
+  pub runner: Option<String>,
+  /// Overrides `build > features`.
+  pub features: Option<Vec<String>>,
+  /// Overrides `build > target`.
+  pub target: Option<String>,
+  /// Overrides the `tauri > security` fields.
+  pub security: Option<SecurityConfig>,
+}
+
+/// The `tauri dev` file watcher configuration.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DevWatcherConfig {
+  /// Gitignore-style glob patterns of the paths whose changes don't restart the app.
+  /// A pattern with a `/` is relative to the `src-tauri` directory, e.g. `src/generated/*.rs`,
+  /// and a pattern without one matches any file or directory name, e.g. `*.log` or `data`.
+  /// The `target` and `.git` directories are always ignored.
+  pub ignore: Option<Vec<String>>,
+  /// The milliseconds the watcher waits for more changes before restarting the app, so bursts of writes are coalesced.
+  /// Defaults to 1000.
+  pub debounce_ms: Option<u64>,
+}
+
+fn default_dev_path() -> AppUrl {
+  AppUrl::Path(PathBuf::new())
+}
+
+/// The `build > devPath` config.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AppUrl {
+  /// A dev server URL, e.g. `http://localhost:8080`.
+  Url(Url),
+  /// The directory of the app's files, relative to the config directory.
+  Path(PathBuf),
+  /// The app's files, relative to the config directory, for setups without a bundler.
+  Files(Vec<PathBuf>),
+}
+
+impl AppUrl {
+  /// The paths of the app's files, empty for a dev server URL.
+  pub fn paths(&self) -> Vec<&PathBuf> {
+    match self {
+      Self::Url(_) => Vec::new(),
+      Self::Path(path) => vec![path],
+      Self::Files(files) => files.iter().collect(),
+    }
+  }
+}
+
+impl fmt::Display for AppUrl {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Url(url) => write!(f, "{}", url),
+      Self::Path(path) => write!(f, "{}", path.display()),
+      Self::Files(files) => write!(
+        f,
+        "{}",
+        files
+          .iter()
+          .map(|file| file.display().to_string())
+          .collect::<Vec<_>>()
+          .join(", ")
+      ),
+    }
+  }
+}
+
+// the schema of the dev path, which is deserialized by hand
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum AppUrlInner {
+  Files(Vec<PathBuf>),
+  UrlOrPath(String),
+}
+
+impl Serialize for AppUrl {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Self::Url(url) => url.serialize(serializer),
+      Self::Path(path) => path.serialize(serializer),
+      Self::Files(files) => files.serialize(serializer),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for AppUrl {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    // only the `http` and `https` schemes are URLs, so Windows paths like `D:\http-assets` are paths
+    let value = JsonValue::deserialize(deserializer)?;
+    match value {
+      JsonValue::String(value) => {
+        let scheme = value.split("://").next().unwrap_or_default();
+        if value.contains("://")
+          && (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+        {
+          Url::parse(&value)
+            .map(Self::Url)
+            .map_err(|e| DeError::custom(format!("`{}` is not a valid URL: {}", value, e)))
+        } else {
+          Ok(Self::Path(PathBuf::from(value)))
+        }
+      }
+      JsonValue::Array(files) => files
+        .into_iter()
+        .map(|file| match file {
+          JsonValue::String(file) => Ok(PathBuf::from(file)),
+          _ => Err(DeError::custom("the devPath files must be strings")),
+        })
+        .collect::<Result<_, _>>()
+        .map(Self::Files),
+      _ => Err(DeError::custom(
+        "the devPath must be a URL, a directory path or a list of file paths",
+      )),
+    }
+  }
+}
+
+impl JsonSchema for AppUrl {
+  fn schema_name() -> String {
+    "AppUrl".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    AppUrlInner::json_schema(gen)
+  }
 }
 
 fn default_dist_dir() -> String {
   "../dist".to_string()
 }
 
-type JsonObject = HashMap<String, JsonValue>;
+fn default_dev_server_timeout_ms() -> u64 {
+  30000
+}
+
+/// The package information.
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PackageConfig {
+  /// The app name, used as the bundle name if `tauri > bundle > name` isn't set.
+  pub product_name: Option<String>,
+  /// The app version, used as the bundle version if `tauri > bundle > version` isn't set.
+  /// It can be a semver version or a path to a `Cargo.toml` or `package.json` file to read the version from,
+  /// relative to `tauri.conf.json`.
+  pub version: Option<String>,
+}
 
 /// The tauri.conf.json mapper.
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Config {
+  /// The config format version, set by `tauri migrate` so the migrations already applied are skipped.
+  pub config_version: Option<u64>,
+  /// The package information.
+  #[serde(default)]
+  pub package: PackageConfig,
   /// The Tauri configuration.
   #[serde(default)]
   pub tauri: TauriConfig,
   /// The build configuration.
   #[serde(default = "default_build")]
   pub build: BuildConfig,
-  /// The plugins config.
+  /// The plugins config, mapping the plugin name to its config value.
+  #[serde(default)]
+  pub plugins: HashMap<String, JsonValue>,
+  /// The feature flags, e.g. `{ "beta": true }`, so the builds can differ by a `--config` override.
+  /// The windows and the allowlist entries can reference them on `enabledWhen`.
+  /// The app reads them with `Config::feature_enabled` and the frontend on `window.__TAURI_METADATA__.features`.
   #[serde(default)]
-  pub plugins: HashMap<String, JsonObject>,
+  pub features: HashMap<String, bool>,
+}
+
+impl Config {
+  /// Whether the `window.__TAURI__` API bundle is injected: if `build > withGlobalTauri` is set
+  /// or a window of `tauri > windows` enables its `withGlobalTauri` override.
+  pub fn bundles_global_tauri(&self) -> bool {
+    self.build.with_global_tauri
+      || self
+        .tauri
+        .windows
+        .iter()
+        .any(|window| window.with_global_tauri == Some(true))
+  }
 }
 
-fn default_build() -> BuildConfig {
+pub fn default_build() -> BuildConfig {
   BuildConfig {
     dev_path: default_dev_path(),
     dist_dir: default_dist_dir(),
+    src_tauri_dir: None,
     before_dev_command: None,
+    dev_server_timeout_ms: default_dev_server_timeout_ms(),
     before_build_command: None,
+    before_bundle_command: None,
+    hooks: None,
     with_global_tauri: false,
+    injected_constants: None,
+    runner: None,
+    features: None,
+    forced_features: None,
+    target: None,
+    dev_watcher: Default::default(),
+    dev_proxy: None,
+    profiles: None,
   }
 }
@@ -1,29 +1,48 @@
 use tauri_bundler::{
-  build_project,
-  bundle::{bundle_project, PackageType, SettingsBuilder},
+  build_command, build_project, build_project_with_messages,
+  bundle::{
+    bundle_project, bundle_project_with_events, target_triple, tauri_config::ResourcesCompression,
+    Bundle, BundleEvent, PackageType, Settings, SettingsBuilder,
+  },
 };
 
 use crate::helpers::{
-  app_paths::{app_dir, tauri_dir},
-  config::get as get_config,
+  app_paths::{config_dir, tauri_dir},
+  config::{
+    check_identifier, check_injected_constants, check_remote_host, config_file_path,
+    deployment_target, deprecations, get as get_config, injected_constants_env,
+    parse_merge_configs, BundleTarget, BundleTargetKind, EmbeddedServerConfig,
+  },
+  events::{coded, emit, json_events, ErrorCode, Event},
   execute_with_output,
-  manifest::rewrite_manifest,
+  hooks::{self, HookContext},
+  manifest::{rewrite_manifest, validate_features},
   Logger, TauriScript,
 };
+use serde_json::{json, Value as JsonValue};
 use std::{
-  env::{set_current_dir, set_var},
-  fs::File,
+  env::{set_current_dir, set_var, var},
+  fs::{create_dir_all, write, File},
   io::Write,
-  path::PathBuf,
-  process::Command,
+  path::{Path, PathBuf},
+  time::Instant,
 };
 
 #[derive(Default)]
 pub struct Build {
   debug: bool,
   verbose: bool,
-  targets: Option<Vec<String>>,
-  config: Option<String>,
+  bundles: Option<Vec<String>>,
+  target: Option<String>,
+  config: Vec<String>,
+  features: Option<Vec<String>>,
+  deny_deprecated: bool,
+  jobs: Option<usize>,
+  fail_fast: bool,
+  force_embed: bool,
+  trace_startup: bool,
+  skip_notarize: bool,
+  print_artifacts_json: bool,
 }
 
 impl Build {
@@ -41,46 +60,165 @@ impl Build {
     self
   }
 
-  pub fn targets(mut self, targets: Vec<String>) -> Self {
-    self.targets = Some(targets);
+  pub fn bundles(mut self, bundles: Vec<String>) -> Self {
+    self.bundles = Some(bundles);
     self
   }
 
-  pub fn config(mut self, config: String) -> Self {
-    self.config.replace(config);
+  pub fn target(mut self, target: String) -> Self {
+    self.target = Some(target);
+    self
+  }
+
+  pub fn config(mut self, config: Vec<String>) -> Self {
+    self.config = config;
+    self
+  }
+
+  pub fn features(mut self, features: Vec<String>) -> Self {
+    self.features.replace(features);
+    self
+  }
+
+  pub fn deny_deprecated(mut self) -> Self {
+    self.deny_deprecated = true;
+    self
+  }
+
+  pub fn jobs(mut self, jobs: usize) -> Self {
+    self.jobs = Some(jobs);
+    self
+  }
+
+  pub fn fail_fast(mut self) -> Self {
+    self.fail_fast = true;
+    self
+  }
+
+  pub fn force_embed(mut self) -> Self {
+    self.force_embed = true;
+    self
+  }
+
+  pub fn trace_startup(mut self) -> Self {
+    self.trace_startup = true;
+    self
+  }
+
+  pub fn skip_notarize(mut self) -> Self {
+    self.skip_notarize = true;
+    self
+  }
+
+  pub fn print_artifacts_json(mut self) -> Self {
+    self.print_artifacts_json = true;
     self
   }
 
   pub fn run(self) -> crate::Result<()> {
     let logger = Logger::new("tauri:build");
-    let config = get_config(self.config.as_deref())?;
+    let merge_configs =
+      parse_merge_configs(&self.config).map_err(|e| coded(ErrorCode::Config, e))?;
+    let start = Instant::now();
+    let config = get_config(&merge_configs).map_err(|e| coded(ErrorCode::Config, e))?;
+    emit(Event::ConfigLoaded {
+      path: config_file_path(),
+    });
+    logger.timing("loading the config", start);
     let config_guard = config.lock().unwrap();
     let config_ = config_guard.as_ref().unwrap();
+    logger.config(serde_json::to_value(config_)?);
+
+    if let Some(error) = check_identifier(config_) {
+      return Err(coded(ErrorCode::Config, error));
+    }
+    if let Some(error) = check_remote_host(config_) {
+      return Err(coded(ErrorCode::Config, error));
+    }
+    // cargo compiles the app for the minimum macOS version the bundle declares
+    if let Some(target) =
+      deployment_target(config_, var("MACOSX_DEPLOYMENT_TARGET").ok().as_deref())
+        .map_err(|e| coded(ErrorCode::Config, e))?
+    {
+      set_var("MACOSX_DEPLOYMENT_TARGET", target);
+    }
+    for warning in check_injected_constants(config_) {
+      logger.warn(warning);
+    }
+    let deprecations = deprecations();
+    if self.deny_deprecated && !deprecations.is_empty() {
+      return Err(coded(
+        ErrorCode::Config,
+        format!(
+          "the config uses deprecated fields:\n{}",
+          deprecations
+            .iter()
+            .map(|deprecation| format!("- {}", deprecation))
+            .collect::<Vec<_>>()
+            .join("\n")
+        ),
+      ));
+    }
 
-    let mut settings_builder = SettingsBuilder::new().features(vec!["embedded-server".to_string()]);
+    let mut features = config_.build.features.clone().unwrap_or_default();
+    if let Some(cli_features) = self.features {
+      features.extend(cli_features);
+    }
+    validate_features(&features)?;
+    features.insert(0, "embedded-server".to_string());
+
+    let mut settings_builder = SettingsBuilder::new().features(features);
+    if let Some(runner) = &config_.build.runner {
+      settings_builder = settings_builder.runner(runner.to_string());
+    }
     if !self.debug {
       settings_builder = settings_builder.release();
     }
     if self.verbose {
       settings_builder = settings_builder.verbose();
     }
-    if let Some(names) = self.targets {
-      let mut types = vec![];
-      for name in names {
-        if name == "none" {
-          break;
-        }
-        match PackageType::from_short_name(&name) {
-          Some(package_type) => {
-            types.push(package_type);
-          }
-          None => {
-            return Err(anyhow::anyhow!(format!(
-              "Unsupported bundle format: {}",
-              name
-            )));
+    if let Some(jobs) = self.jobs {
+      settings_builder = settings_builder.jobs(jobs);
+    }
+    if self.fail_fast {
+      settings_builder = settings_builder.fail_fast();
+    }
+    if self.skip_notarize {
+      settings_builder = settings_builder.skip_notarize();
+    }
+    if let Some(target) = self.target.as_ref().or(config_.build.target.as_ref()) {
+      settings_builder = settings_builder.target(target.to_string());
+    }
+
+    let targets = match self.bundles {
+      Some(names) if names.iter().any(|name| name == "all") => None,
+      Some(names) => {
+        let mut kinds = vec![];
+        for name in names {
+          if name == "none" {
+            break;
           }
+          kinds.push(
+            name
+              .parse::<BundleTargetKind>()
+              .map_err(|e| anyhow::anyhow!(e))?,
+          );
         }
+        Some(kinds)
+      }
+      None => config_
+        .tauri
+        .bundle
+        .targets
+        .as_ref()
+        .and_then(BundleTarget::to_vec),
+    };
+    // `--bundles none` builds the app without bundling it
+    let no_bundle = matches!(&targets, Some(kinds) if kinds.is_empty());
+    if let Some(kinds) = targets {
+      let mut types = vec![];
+      for kind in kinds {
+        types.push(package_type(kind)?);
       }
       settings_builder = settings_builder.package_types(types);
     }
@@ -89,51 +227,304 @@ impl Build {
     set_current_dir(&tauri_path)?;
     set_var("TAURI_DIR", &tauri_path);
     set_var("TAURI_DIST_DIR", tauri_path.join(&config_.build.dist_dir));
+    // the bundler and the app read the config with the resolved package version
+    set_var("TAURI_CONFIG", serde_json::to_string(config_)?);
+    // the assets are embedded by the `tauri` macros, which reuse them while the dist dir doesn't change
+    set_var("TAURI_VERBOSE", self.verbose.to_string());
+    set_var("TAURI_FORCE_EMBED", self.force_embed.to_string());
 
     drop(config_guard);
-    rewrite_manifest(config.clone())?;
+    rewrite_manifest(config.clone(), self.trace_startup)?;
 
     let config_guard = config.lock().unwrap();
     let config_ = config_guard.as_ref().unwrap();
 
-    // __tauri.js
+    let settings = settings_builder.build()?;
+    // fail before the build starts if a bundle target isn't supported by the target platform
+    settings.package_types()?;
+    // and if a sidecar is missing for the selected target
+    settings.external_binaries()?;
+    // and if the DMG background image is missing
+    settings.dmg_background()?;
+    // and if the WebView2 offline installer or fixed runtime is missing
+    settings.windows_webview_install_mode()?;
+    // and if a WiX fragment is missing
+    settings.windows_wix_fragment_paths()?;
+
+    if let Some(before_build) = &config_.build.before_build_command {
+      let start = Instant::now();
+      let env = injected_constants_env(config_, self.debug);
+      let env: Vec<(&str, String)> = env
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect();
+      run_hook(&logger, before_build, &env).map_err(|e| coded(ErrorCode::BeforeCommand, e))?;
+      logger.timing("`build.beforeBuildCommand`", start);
+    }
+
+    // the dist dir may be generated by the before build command
+    check_dist_dir(
+      &tauri_path.join(&config_.build.dist_dir),
+      &config_.build.dist_dir,
+      config_.build.before_build_command.is_some(),
+    )?;
+    check_server_pages(
+      &tauri_path.join(&config_.build.dist_dir),
+      &config_.tauri.embedded_server,
+    )?;
+
+    // __tauri.js, written after the before build command so it can't be cleaned up
     let tauri_script = TauriScript::new()
-      .global_tauri(config_.build.with_global_tauri)
+      .global_tauri(config_.bundles_global_tauri())
       .get();
     let tauri_script_path = PathBuf::from(&config_.build.dist_dir).join("__tauri.js");
     let mut tauri_script_file = File::create(tauri_script_path)?;
     tauri_script_file.write_all(tauri_script.as_bytes())?;
 
-    let settings = settings_builder.build()?;
+    // the resource manifest is embedded by the `tauri` macros, so it's always written for them to see it change
+    let verify_resources = config_
+      .tauri
+      .security
+      .as_ref()
+      .map_or(false, |security| security.verify_resources);
+    let resource_manifest =
+      if verify_resources || settings.resources_compression() != ResourcesCompression::None {
+        settings.resource_manifest()?
+      } else {
+        JsonValue::Null
+      };
+    let resource_manifest_path = settings
+      .project_out_directory()
+      .join("resource-manifest.json");
+    create_dir_all(settings.project_out_directory())?;
+    write(
+      &resource_manifest_path,
+      serde_json::to_string(&resource_manifest)?,
+    )?;
+    set_var("TAURI_RESOURCE_MANIFEST", &resource_manifest_path);
 
-    if let Some(before_build) = &config_.build.before_build_command {
-      let mut cmd: Option<&str> = None;
-      let mut args: Vec<&str> = vec![];
-      for token in before_build.split(' ') {
-        if cmd.is_none() && !token.is_empty() {
-          cmd = Some(token);
-        } else {
-          args.push(token)
-        }
+    let start = Instant::now();
+    logger.command(&build_command(&settings), &[]);
+    if json_events() {
+      build_project_with_messages(&settings, &mut |message| {
+        emit(Event::CargoMessage { message })
+      })
+    } else {
+      build_project(&settings)
+    }
+    .map_err(|e| coded(ErrorCode::CargoBuild, e))?;
+    logger.timing("`cargo build`", start);
+    let build_hooks = config_.build.hooks.clone().unwrap_or_default();
+    if let Some(hook) = &build_hooks.on_build_success {
+      let (target_triple, profile) = triple_and_profile(&settings)?;
+      let context = HookContext {
+        name: "onBuildSuccess",
+        target_triple,
+        profile,
+        artifacts: json!([main_binary_path(&settings)]),
+        previous_exit_code: Some(0),
+        env: Vec::new(),
+      };
+      hooks::run_hook(&logger, hook, &context)?;
+    }
+    let mut bundles = Vec::new();
+    if config_.tauri.bundle.active && !no_bundle {
+      if let Some(before_bundle) = &config_.build.before_bundle_command {
+        let start = Instant::now();
+        run_hook(&logger, before_bundle, &hook_env(&settings)?).map_err(|e| {
+          coded(
+            ErrorCode::BeforeCommand,
+            format!(
+              "`build.beforeBundleCommand` failed, so the app wasn't bundled: {}",
+              e
+            ),
+          )
+        })?;
+        logger.timing("`build.beforeBundleCommand`", start);
       }
-
-      if let Some(cmd) = cmd {
-        logger.log(format!("Running `{}`", before_build));
-        #[cfg(target_os = "windows")]
-        let mut command = Command::new(
-          which::which(&cmd).expect(&format!("failed to find `{}` in your $PATH", cmd)),
-        );
-        #[cfg(not(target_os = "windows"))]
-        let mut command = Command::new(cmd);
-        command.args(args).current_dir(app_dir());
-        execute_with_output(&mut command)?;
+      let start = Instant::now();
+      let (target_triple, profile) = triple_and_profile(&settings)?;
+      bundles = if json_events() {
+        bundle_project_with_events(settings, &mut |event| emit(bundle_event(event)))
+      } else {
+        bundle_project(settings)
+      }
+      .map_err(|e| coded(ErrorCode::Bundle, e))?;
+      logger.timing("bundling", start);
+      if let Some(hook) = &build_hooks.on_bundle_success {
+        let context = HookContext {
+          name: "onBundleSuccess",
+          target_triple,
+          profile,
+          artifacts: artifacts_json(&bundles),
+          previous_exit_code: Some(0),
+          env: Vec::new(),
+        };
+        hooks::run_hook(&logger, hook, &context)?;
       }
     }
+    if self.print_artifacts_json {
+      println!("{}", serde_json::to_string(&artifacts_json(&bundles))?);
+    }
+    Ok(())
+  }
+}
+
+/// The artifacts printed by `--print-artifacts-json`: their bundle format and absolute path.
+fn artifacts_json(bundles: &[Bundle]) -> JsonValue {
+  bundles
+    .iter()
+    .flat_map(|bundle| {
+      bundle.bundle_paths.iter().map(move |path| {
+        json!({
+          "format": bundle.package_type.short_name(),
+          "path": path,
+        })
+      })
+    })
+    .collect()
+}
 
-    build_project(&settings)?;
-    if config_.tauri.bundle.active {
-      bundle_project(settings)?;
+/// Maps a bundler event to its JSON event.
+fn bundle_event(event: BundleEvent) -> Event {
+  match event {
+    BundleEvent::Started(package_type) => Event::BundleStarted {
+      format: package_type.short_name().to_string(),
+    },
+    BundleEvent::Finished {
+      package_type,
+      paths,
+      error,
+    } => Event::BundleFinished {
+      format: package_type.short_name().to_string(),
+      paths,
+      error,
+    },
+  }
+}
+
+/// Runs a hook command on the app directory, with the given environment variables.
+fn run_hook(logger: &Logger, hook: &str, env: &[(&str, String)]) -> crate::Result<()> {
+  if let Some(mut command) = hooks::command(hook) {
+    logger.log(format!("Running `{}`", hook));
+    command.envs(env.iter().map(|(key, value)| (key, value)));
+    logger.command(&command, env);
+    emit(Event::BeforeCommandStarted {
+      command: hook.to_string(),
+    });
+    let status = execute_with_output(&mut command)?;
+    emit(Event::BeforeCommandFinished {
+      command: hook.to_string(),
+      exit_code: status.code(),
+    });
+    if !status.success() {
+      return Err(anyhow::anyhow!("`{}` failed: {}", hook, status));
     }
+  }
+  Ok(())
+}
+
+/// The target triple and the profile of the build.
+fn triple_and_profile(settings: &Settings) -> crate::Result<(String, &'static str)> {
+  let triple = match settings.target_triple() {
+    Some(triple) => triple.to_string(),
+    None => target_triple()?,
+  };
+  let profile = if settings.is_release_build() {
+    "release"
+  } else {
+    "debug"
+  };
+  Ok((triple, profile))
+}
+
+/// The path of the app binary.
+fn main_binary_path(settings: &Settings) -> PathBuf {
+  settings
+    .binaries()
+    .iter()
+    .find(|binary| binary.main())
+    .map(|binary| settings.binary_path(binary))
+    .unwrap_or_default()
+}
+
+/// The environment variables of the before bundle command: the target triple, the profile and the app binary path.
+fn hook_env(settings: &Settings) -> crate::Result<Vec<(&'static str, String)>> {
+  let (triple, profile) = triple_and_profile(settings)?;
+  Ok(vec![
+    ("TAURI_TARGET_TRIPLE", triple),
+    ("TAURI_PROFILE", profile.to_string()),
+    (
+      "TAURI_BINARY_PATH",
+      main_binary_path(settings).to_string_lossy().into_owned(),
+    ),
+  ])
+}
+
+/// Checks that the dist dir exists and has an `index.html` file, before the assets are embedded on the app.
+fn check_dist_dir(path: &Path, dist_dir: &str, has_before_build: bool) -> crate::Result<()> {
+  let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+  let hint = if has_before_build {
+    "; make sure `build.beforeBuildCommand` writes your frontend build there"
+  } else {
+    "; build your frontend first, or set `build.beforeBuildCommand` to build it"
+  };
+  if !path.is_dir() {
+    Err(anyhow::anyhow!(
+      "`build.distDir` ({}) resolves to `{}`, which doesn't exist; it must be the directory of your frontend build, relative to the config directory `{}`{}",
+      dist_dir,
+      path.display(),
+      config_dir().display(),
+      hint
+    ))
+  } else if !path.join("index.html").is_file() {
+    Err(anyhow::anyhow!(
+      "`build.distDir` ({}) resolves to `{}`, which doesn't have an `index.html` file{}",
+      dist_dir,
+      path.display(),
+      hint
+    ))
+  } else {
     Ok(())
   }
 }
+
+/// Checks that the embedded server error pages exist on the dist dir, since they're embedded with the assets.
+fn check_server_pages(dist_dir: &Path, config: &EmbeddedServerConfig) -> crate::Result<()> {
+  for (name, page) in &[
+    ("notFoundPage", &config.not_found_page),
+    ("errorPage", &config.error_page),
+  ] {
+    if let Some(page) = page {
+      if !dist_dir.join(page).is_file() {
+        return Err(anyhow::anyhow!(
+          "`tauri.embeddedServer.{}` ({}) doesn't exist on the dist dir `{}`",
+          name,
+          page,
+          dist_dir.display()
+        ));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Whether the value is a `--bundles` value, e.g. `deb` or `all`, rather than a target triple.
+pub fn is_bundle_name(value: &str) -> bool {
+  value == "all" || value == "none" || value.parse::<BundleTargetKind>().is_ok()
+}
+
+/// Maps a bundle target to the bundler package type, failing if it can't be built on the current platform.
+fn package_type(kind: BundleTargetKind) -> crate::Result<PackageType> {
+  let name = match kind {
+    BundleTargetKind::App => "osx",
+    kind => kind.name(),
+  };
+  PackageType::from_short_name(name).ok_or_else(|| {
+    anyhow::anyhow!(
+      "the `{}` bundle target can't be built on {}",
+      kind,
+      std::env::consts::OS
+    )
+  })
+}
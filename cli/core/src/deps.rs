@@ -0,0 +1,486 @@
+use crate::{
+  helpers::{
+    app_paths::{app_dir, tauri_dir},
+    config::config_source,
+    execute_with_output,
+    migrations::{config_version, latest_version},
+    Logger,
+  },
+  info::{cargo_lock, npm_package_version},
+};
+
+use colored::Colorize;
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use toml_edit::{decorated, value, Document, Item, Value};
+
+use std::{
+  fs::{read_to_string, write},
+  path::Path,
+  process::Command,
+};
+
+/// The crates.io API, which requires a user agent.
+const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
+/// The npm registry.
+const NPM_REGISTRY: &str = "https://registry.npmjs.org";
+const USER_AGENT: &str = concat!("tauri-cli/", env!("CARGO_PKG_VERSION"));
+
+/// Where a Tauri component is declared and installed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Kind {
+  /// A dependency of the app `Cargo.toml`, installed on `Cargo.lock`.
+  Cargo,
+  /// A dependency of the app `package.json`, installed on `node_modules`.
+  Npm,
+  /// This CLI, installed with `cargo install`.
+  CargoInstall,
+}
+
+/// The Tauri components whose versions must stay in sync.
+const COMPONENTS: &[(&str, Kind)] = &[
+  ("tauri", Kind::Cargo),
+  ("tauri-api", Kind::Cargo),
+  ("tauri-cli", Kind::CargoInstall),
+  ("@tauri-apps/cli", Kind::Npm),
+  ("@tauri-apps/api", Kind::Npm),
+];
+
+/// The versions of a Tauri component.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Dependency {
+  name: &'static str,
+  kind: Kind,
+  /// The version requirement on the manifest, `None` if it isn't a direct dependency.
+  requirement: Option<String>,
+  installed: Option<String>,
+  /// The latest version semver compatible with the installed version, which can be updated to without migrating the config.
+  latest_compatible: Option<String>,
+  /// The latest version, which may be a breaking update.
+  latest: Option<String>,
+}
+
+impl Dependency {
+  /// The compatible version to update to, if it's newer than the installed version.
+  fn update(&self) -> Option<&str> {
+    let installed = Version::parse(self.installed.as_deref()?).ok()?;
+    let latest_compatible = self.latest_compatible.as_deref()?;
+    if Version::parse(latest_compatible).ok()? > installed {
+      Some(latest_compatible)
+    } else {
+      None
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct Deps {
+  json: bool,
+  offline: bool,
+  update: bool,
+}
+
+impl Deps {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn json(mut self) -> Self {
+    self.json = true;
+    self
+  }
+
+  pub fn offline(mut self) -> Self {
+    self.offline = true;
+    self
+  }
+
+  pub fn update(mut self) -> Self {
+    self.update = true;
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    let logger = Logger::new("tauri:deps");
+    // the warnings are part of the JSON output, so stdout only has the JSON object
+    let mut warnings = Vec::new();
+
+    let cargo_manifest = read_to_string(tauri_dir().join("Cargo.toml"))?;
+    let cargo_manifest: toml::Value = cargo_manifest.parse()?;
+    let package_json = read_to_string(app_dir().join("package.json"))
+      .ok()
+      .and_then(|package| serde_json::from_str::<JsonValue>(&package).ok());
+    let lock = cargo_lock();
+
+    let mut dependencies = Vec::new();
+    for &(name, kind) in COMPONENTS {
+      let (requirement, installed) = match kind {
+        Kind::Cargo => (
+          cargo_requirement(&cargo_manifest, name),
+          lock.as_ref().and_then(|lock| locked_version(lock, name)),
+        ),
+        Kind::Npm => (
+          package_json
+            .as_ref()
+            .and_then(|package| npm_requirement(package, name)),
+          npm_package_version(name),
+        ),
+        Kind::CargoInstall => (None, Some(env!("CARGO_PKG_VERSION").to_string())),
+      };
+      // the components the app doesn't use aren't looked up
+      if requirement.is_none() && installed.is_none() {
+        continue;
+      }
+      let versions = if self.offline {
+        Vec::new()
+      } else {
+        match published_versions(name, kind) {
+          Ok(versions) => versions,
+          Err(e) => {
+            warnings.push(format!(
+              "failed to fetch the `{}` versions, only the installed version is shown: {}",
+              name, e
+            ));
+            Vec::new()
+          }
+        }
+      };
+      let range = installed
+        .as_deref()
+        .and_then(|installed| VersionReq::parse(&format!("^{}", installed)).ok())
+        .or_else(|| {
+          requirement
+            .as_deref()
+            .and_then(|requirement| VersionReq::parse(requirement).ok())
+        });
+      dependencies.push(Dependency {
+        name,
+        kind,
+        latest_compatible: range.and_then(|range| {
+          versions
+            .iter()
+            .filter(|version| range.matches(version))
+            .max()
+            .map(ToString::to_string)
+        }),
+        latest: versions.iter().max().map(ToString::to_string),
+        requirement,
+        installed,
+      });
+    }
+
+    // the compatible updates never need a config migration, but an outdated config does
+    let config_version = config_source()
+      .read()
+      .map(|(_, value)| config_version(&value))
+      .unwrap_or_else(|_| latest_version());
+    if config_version < latest_version() {
+      warnings.push(format!(
+        "the config version is {}, but this CLI expects version {}; run `tauri migrate` to upgrade it",
+        config_version,
+        latest_version()
+      ));
+    }
+
+    if self.json {
+      println!(
+        "{}",
+        serde_json::to_string_pretty(&json!({
+          "dependencies": dependencies,
+          "configVersion": config_version,
+          "latestConfigVersion": latest_version(),
+          "warnings": warnings,
+        }))?
+      );
+    } else {
+      for warning in &warnings {
+        logger.warn(warning);
+      }
+      println!("\n{}", "Tauri dependencies".bold());
+      for dependency in &dependencies {
+        let installed = dependency.installed.as_deref().unwrap_or("Not installed");
+        let mut line = format!("  {} - {}", dependency.name, installed);
+        if let Some(requirement) = &dependency.requirement {
+          line.push_str(&format!(" (requires `{}`)", requirement));
+        }
+        if let Some(update) = dependency.update() {
+          line.push_str(&format!(", {} available", update).yellow().to_string());
+        }
+        if let (Some(latest), Some(latest_compatible)) =
+          (&dependency.latest, &dependency.latest_compatible)
+        {
+          if latest != latest_compatible {
+            line.push_str(&format!(", {} is a breaking update", latest));
+          }
+        }
+        println!("{}", line);
+      }
+      if !self.update && dependencies.iter().any(|dep| dep.update().is_some()) {
+        println!("\nRun `tauri deps --update` to install the compatible updates.");
+      }
+    }
+
+    if self.update {
+      update(&dependencies, &logger)?;
+    }
+    Ok(())
+  }
+}
+
+/// Gets the version requirement of a `[dependencies]` entry.
+fn cargo_requirement(manifest: &toml::Value, name: &str) -> Option<String> {
+  let dependency = manifest.get("dependencies")?.get(name)?;
+  dependency
+    .as_str()
+    .or_else(|| dependency.get("version").and_then(toml::Value::as_str))
+    .map(ToString::to_string)
+}
+
+/// Gets the version requirement of a `dependencies` or `devDependencies` entry.
+fn npm_requirement(package: &JsonValue, name: &str) -> Option<String> {
+  ["dependencies", "devDependencies"]
+    .iter()
+    .find_map(|key| package.get(key)?.get(name)?.as_str())
+    .map(ToString::to_string)
+}
+
+/// Gets the version of a package on the `Cargo.lock` file.
+fn locked_version(lock: &JsonValue, name: &str) -> Option<String> {
+  lock
+    .get("package")?
+    .as_array()?
+    .iter()
+    .find(|package| package.get("name").and_then(JsonValue::as_str) == Some(name))?
+    .get("version")?
+    .as_str()
+    .map(ToString::to_string)
+}
+
+/// Fetches the published versions from crates.io or the npm registry, without the pre-releases and yanked versions.
+fn published_versions(name: &str, kind: Kind) -> crate::Result<Vec<Version>> {
+  let url = match kind {
+    Kind::Npm => format!("{}/{}", NPM_REGISTRY, name.replace('/', "%2F")),
+    Kind::Cargo | Kind::CargoInstall => format!("{}/{}", CRATES_IO_API, name),
+  };
+  let response = ureq::get(&url)
+    .set("User-Agent", USER_AGENT)
+    .call()?
+    .into_string()?;
+  let response: JsonValue = serde_json::from_str(&response)?;
+  let versions: Vec<&str> = match kind {
+    Kind::Npm => response
+      .get("versions")
+      .and_then(JsonValue::as_object)
+      .map(|versions| versions.keys().map(String::as_str).collect())
+      .unwrap_or_default(),
+    Kind::Cargo | Kind::CargoInstall => response
+      .get("versions")
+      .and_then(JsonValue::as_array)
+      .map(|versions| {
+        versions
+          .iter()
+          .filter(|version| version.get("yanked") != Some(&JsonValue::Bool(true)))
+          .filter_map(|version| version.get("num").and_then(JsonValue::as_str))
+          .collect()
+      })
+      .unwrap_or_default(),
+  };
+  Ok(
+    versions
+      .into_iter()
+      .filter_map(|version| Version::parse(version).ok())
+      .filter(|version| !version.is_prerelease())
+      .collect(),
+  )
+}
+
+/// Rewrites the manifests to require the compatible updates and installs them.
+fn update(dependencies: &[Dependency], logger: &Logger) -> crate::Result<()> {
+  let updates: Vec<(&Dependency, &str)> = dependencies
+    .iter()
+    .filter_map(|dependency| dependency.update().map(|update| (dependency, update)))
+    .collect();
+  if updates.is_empty() {
+    logger.log("the Tauri dependencies are up to date");
+    return Ok(());
+  }
+
+  let cargo_updates: Vec<(&str, &str)> = updates
+    .iter()
+    .filter(|(dependency, _)| dependency.kind == Kind::Cargo)
+    .map(|(dependency, update)| (dependency.name, *update))
+    .collect();
+  if !cargo_updates.is_empty() {
+    let path = tauri_dir().join("Cargo.toml");
+    let manifest = read_to_string(&path)?;
+    write(&path, update_cargo_manifest(&manifest, &cargo_updates)?)?;
+    let mut args = vec!["update"];
+    for (name, _) in &cargo_updates {
+      args.push("-p");
+      args.push(name);
+    }
+    run("cargo", &args, &tauri_dir())?;
+  }
+
+  let npm_updates: Vec<(&str, &str)> = updates
+    .iter()
+    .filter(|(dependency, _)| dependency.kind == Kind::Npm)
+    .map(|(dependency, update)| (dependency.name, *update))
+    .collect();
+  if !npm_updates.is_empty() {
+    let path = app_dir().join("package.json");
+    let package = read_to_string(&path)?;
+    write(&path, update_package_json(&package, &npm_updates)?)?;
+    let package_manager = package_manager(app_dir());
+    run(package_manager, &["install"], app_dir())?;
+  }
+
+  for (dependency, update) in &updates {
+    if dependency.kind == Kind::CargoInstall {
+      logger.warn(format!(
+        "this CLI isn't updated by `tauri deps`; run `cargo install {} --version {}`",
+        dependency.name, update
+      ));
+    } else {
+      logger.log(format!("Updated {} to {}", dependency.name, update));
+    }
+  }
+  Ok(())
+}
+
+/// Sets the version requirement of the `[dependencies]` entries, keeping the manifest formatting.
+fn update_cargo_manifest(manifest: &str, updates: &[(&str, &str)]) -> crate::Result<String> {
+  let mut manifest: Document = manifest.parse()?;
+  let dependencies = manifest
+    .as_table_mut()
+    .entry("dependencies")
+    .as_table_mut()
+    .ok_or_else(|| anyhow::anyhow!("the manifest `dependencies` isn't a table"))?;
+  for (name, version) in updates {
+    let entry = dependencies.entry(name);
+    match entry {
+      Item::Value(Value::String(_)) => *entry = value(*version),
+      Item::Value(Value::InlineTable(dependency)) => {
+        // the dependencies without a version, e.g. a path, are updated on the lock file only
+        if let Some(requirement) = dependency.get_mut("version") {
+          *requirement = decorated((*version).into(), " ", "");
+        }
+      }
+      Item::Table(dependency) => {
+        if dependency.contains_key("version") {
+          dependency["version"] = value(*version);
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok(manifest.to_string_in_original_order())
+}
+
+/// Sets the version requirement of the `dependencies` and `devDependencies` entries,
+/// keeping their `^` or `~` prefix.
+fn update_package_json(package: &str, updates: &[(&str, &str)]) -> crate::Result<String> {
+  let mut package: JsonValue = serde_json::from_str(package)?;
+  for key in &["dependencies", "devDependencies"] {
+    for (name, version) in updates {
+      if let Some(requirement) = package
+        .get_mut(key)
+        .and_then(|dependencies| dependencies.get_mut(name))
+      {
+        let prefix = match requirement.as_str() {
+          Some(requirement) if requirement.starts_with('~') => "~",
+          Some(requirement)
+            if requirement
+              .chars()
+              .next()
+              .map_or(false, |c| c.is_ascii_digit()) =>
+          {
+            ""
+          }
+          _ => "^",
+        };
+        *requirement = format!("{}{}", prefix, version).into();
+      }
+    }
+  }
+  Ok(format!("{}\n", serde_json::to_string_pretty(&package)?))
+}
+
+/// The package manager of the app, from its lock file.
+fn package_manager(app_dir: &Path) -> &'static str {
+  if app_dir.join("yarn.lock").exists() {
+    "yarn"
+  } else if app_dir.join("pnpm-lock.yaml").exists() {
+    "pnpm"
+  } else {
+    "npm"
+  }
+}
+
+fn run(program: &str, args: &[&str], dir: &Path) -> crate::Result<()> {
+  // npm, yarn and pnpm are batch scripts on Windows, so they're run through the shell
+  #[cfg(windows)]
+  let mut command = {
+    let mut command = Command::new("cmd");
+    command.arg("/S").arg("/C").arg(program);
+    command
+  };
+  #[cfg(not(windows))]
+  let mut command = Command::new(program);
+  command.args(args).current_dir(dir);
+  let status = execute_with_output(&mut command)?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!(
+      "`{} {}` failed: {}",
+      program,
+      args.join(" "),
+      status
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{update_cargo_manifest, update_package_json};
+
+  #[test]
+  fn cargo_manifest() {
+    let manifest = r#"[package]
+name = "app"
+
+[dependencies]
+serde = "1.0"
+tauri = { version = "0.11", features = [ "api-all" ] }
+tauri-api = "0.7"
+"#;
+    let updated =
+      update_cargo_manifest(manifest, &[("tauri", "0.11.1"), ("tauri-api", "0.7.5")]).unwrap();
+    assert!(updated.contains(r#"tauri = { version = "0.11.1", features = [ "api-all" ] }"#));
+    assert!(updated.contains(r#"tauri-api = "0.7.5""#));
+    assert!(updated.contains(r#"serde = "1.0""#));
+  }
+
+  #[test]
+  fn package_json() {
+    let package = r#"{
+  "name": "app",
+  "dependencies": { "@tauri-apps/api": "~0.1.0" },
+  "devDependencies": { "@tauri-apps/cli": "0.14.0" }
+}"#;
+    let updated: serde_json::Value = serde_json::from_str(
+      &update_package_json(
+        package,
+        &[("@tauri-apps/api", "0.1.2"), ("@tauri-apps/cli", "0.14.1")],
+      )
+      .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(updated["dependencies"]["@tauri-apps/api"], "~0.1.2");
+    assert_eq!(updated["devDependencies"]["@tauri-apps/cli"], "0.14.1");
+  }
+}
@@ -1,40 +1,348 @@
 use crate::helpers::{
-  app_paths::{app_dir, tauri_dir},
-  config::{get as get_config, reload as reload_config},
-  manifest::rewrite_manifest,
-  Logger, TauriScript,
+  app_paths::{app_dir, config_dir, tauri_dir},
+  config::{
+    check_identifier, check_injected_constants, check_remote_host, config_file_path,
+    deployment_target, embedded_server_port, get as get_config, injected_constants_env,
+    parse_merge_configs, reload as reload_config, AppUrl, Config, DevWatcherConfig,
+    EMBEDDED_SERVER_PORT_ENV,
+  },
+  events::{coded, emit, json_events, ErrorCode, Event},
+  forward_output,
+  hooks::{run_hook, HookContext},
+  manifest::{rewrite_manifest, validate_features},
+  print_output, process, Logger, TauriScript,
 };
 
+use glob::Pattern;
 use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
 use once_cell::sync::OnceCell;
+use serde_json::Value as JsonValue;
 use shared_child::SharedChild;
+use tauri_bundler::bundle::{has_log_file, target_triple, SettingsBuilder};
+use url::{Position, Url};
 
 use std::{
-  env::{set_current_dir, set_var},
-  ffi::OsStr,
+  env::{set_current_dir, set_var, var, var_os},
   fs::{create_dir_all, File},
-  io::Write,
-  path::PathBuf,
-  process::{exit, Child, Command},
+  io::{BufRead, BufReader, Read, Write},
+  net::{SocketAddr, TcpStream},
+  path::{Path, PathBuf},
+  process::{exit, ChildStdout, Command, Stdio},
   sync::{
     mpsc::{channel, Receiver},
     Arc, Mutex,
   },
-  time::Duration,
+  thread::sleep,
+  time::{Duration, Instant},
 };
 
-static BEFORE_DEV: OnceCell<Mutex<Child>> = OnceCell::new();
+static BEFORE_DEV: OnceCell<Arc<SharedChild>> = OnceCell::new();
+
+/// The paths the watcher always ignores.
+const DEFAULT_WATCHER_IGNORE: &[&str] = &["target", ".git"];
+/// The default milliseconds the watcher waits for more changes.
+const DEFAULT_WATCHER_DEBOUNCE_MS: u64 = 1000;
+/// The initial interval between the dev server checks, doubled after each failed check.
+const DEV_SERVER_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// The maximum interval between the dev server checks.
+const DEV_SERVER_MAX_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+/// The exit code of the app when its args don't match the `tauri > cli` definition.
+const INVALID_CLI_ARGS_EXIT_CODE: i32 = 2;
+
+/// The gitignore-style patterns of the paths whose changes don't restart the app.
+struct WatcherIgnore(Vec<(Pattern, bool)>);
+
+impl WatcherIgnore {
+  fn new(config: &DevWatcherConfig) -> Self {
+    let patterns = DEFAULT_WATCHER_IGNORE
+      .iter()
+      .copied()
+      .chain(config.ignore.iter().flatten().map(String::as_str))
+      // the config validation rejects the invalid patterns
+      .filter_map(|pattern| {
+        let anchored = pattern.trim_end_matches('/').contains('/');
+        Pattern::new(pattern.trim_matches('/'))
+          .ok()
+          .map(|pattern| (pattern, anchored))
+      })
+      .collect();
+    Self(patterns)
+  }
+
+  /// Whether the path, relative to the tauri dir, is ignored.
+  /// A path is ignored if one of its parent directories is ignored.
+  fn is_ignored(&self, path: &Path) -> bool {
+    let components: Vec<String> = path
+      .components()
+      .map(|component| component.as_os_str().to_string_lossy().to_string())
+      .collect();
+    self.0.iter().any(|(pattern, anchored)| {
+      if *anchored {
+        (1..=components.len()).any(|len| pattern.matches(&components[..len].join("/")))
+      } else {
+        components
+          .iter()
+          .any(|component| pattern.matches(component))
+      }
+    })
+  }
+}
+
+/// Resolves the dev paths relative to the tauri dir.
+fn absolute_dev_path(dev_path: &AppUrl) -> AppUrl {
+  match dev_path {
+    AppUrl::Url(_) => dev_path.clone(),
+    AppUrl::Path(path) => AppUrl::Path(tauri_dir().join(path)),
+    AppUrl::Files(files) => {
+      AppUrl::Files(files.iter().map(|file| tauri_dir().join(file)).collect())
+    }
+  }
+}
+
+/// Whether the config change requires rebuilding the app.
+/// The Cargo features, the bundle settings and the dev assets are compiled into the app,
+/// while the other values are read by the app from `TAURI_CONFIG` on development, so restarting it is enough.
+fn requires_rebuild(old: &Config, new: &Config) -> bool {
+  let dev_server = |config: &Config| matches!(config.build.dev_path, AppUrl::Url(_));
+  old.package != new.package
+    || old.tauri.allowlist != new.tauri.allowlist
+    || old.tauri.cli.is_some() != new.tauri.cli.is_some()
+    || old.tauri.bundle != new.tauri.bundle
+    || old.build.features != new.build.features
+    || old.build.runner != new.build.runner
+    || old.build.dist_dir != new.build.dist_dir
+    || old.bundles_global_tauri() != new.bundles_global_tauri()
+    || (old.build.dev_path != new.build.dev_path && !(dev_server(old) && dev_server(new)))
+}
+
+/// Checks that the dev paths exist, or waits for the dev server URL to respond over HTTP,
+/// checking it with an exponential backoff until `build.devServerTimeoutMs` expires.
+fn check_dev_path(
+  dev_path: &AppUrl,
+  timeout: Duration,
+  has_before_dev: bool,
+  logger: &Logger,
+) -> crate::Result<()> {
+  match dev_path {
+    AppUrl::Url(url) => {
+      let hint = if has_before_dev {
+        "; make sure `build.beforeDevCommand` starts your dev server on that URL, or raise `build.devServerTimeoutMs`"
+      } else {
+        "; start your dev server first, or set `build.beforeDevCommand` to start it"
+      };
+      let start = Instant::now();
+      let mut interval = DEV_SERVER_RETRY_INTERVAL;
+      let mut attempt = 0;
+      loop {
+        let error = match responds(url) {
+          Ok(()) => {
+            if attempt > 0 {
+              logger.log(format!(
+                "The dev server at `{}` responded after {:.1}s",
+                url,
+                start.elapsed().as_secs_f32()
+              ));
+            }
+            return Ok(());
+          }
+          Err(error) => error,
+        };
+        if start.elapsed() >= timeout {
+          return Err(anyhow::anyhow!(
+            "the dev server at `{}` (`build.devPath`) didn't respond within {}ms, the last check failed with: {}{}",
+            url,
+            timeout.as_millis(),
+            error,
+            hint
+          ));
+        }
+        attempt += 1;
+        if attempt == 1 {
+          logger.log(format!("Waiting for the dev server at `{}`", url));
+        } else if interval == DEV_SERVER_MAX_RETRY_INTERVAL {
+          logger.log(format!(
+            "Still waiting for the dev server at `{}` ({:.0}s of {}s): {}",
+            url,
+            start.elapsed().as_secs_f32(),
+            timeout.as_secs(),
+            error
+          ));
+        }
+        sleep(interval);
+        interval = (interval * 2).min(DEV_SERVER_MAX_RETRY_INTERVAL);
+      }
+    }
+    AppUrl::Path(path) if !path.is_dir() => Err(anyhow::anyhow!(
+      "`build.devPath` resolves to `{}`, which doesn't exist; it must be a dev server URL or the directory of your frontend, relative to the config directory `{}`",
+      path.display(),
+      config_dir().display()
+    )),
+    AppUrl::Files(files) => match files.iter().find(|file| !file.is_file()) {
+      Some(file) => Err(anyhow::anyhow!(
+        "the `build.devPath` file `{}` doesn't exist; the files are relative to the config directory `{}`",
+        file.display(),
+        config_dir().display()
+      )),
+      None => Ok(()),
+    },
+    AppUrl::Path(_) => Ok(()),
+  }
+}
+
+/// Checks that the dev server accepts connections and, unless it's served over TLS, answers a HTTP request,
+/// returning the error of the last address otherwise.
+fn responds(url: &Url) -> Result<(), String> {
+  let addresses = url.socket_addrs(|| None).map_err(|e| e.to_string())?;
+  let mut error = format!("`{}` doesn't resolve to any address", url);
+  for address in addresses {
+    match request_head(url, &address) {
+      Ok(()) => return Ok(()),
+      Err(e) => error = format!("{}: {}", address, e),
+    }
+  }
+  Err(error)
+}
+
+/// Connects to the dev server address and sends a HEAD request to it.
+fn request_head(url: &Url, address: &SocketAddr) -> std::io::Result<()> {
+  let mut stream = TcpStream::connect_timeout(address, Duration::from_secs(1))?;
+  if url.scheme() == "https" {
+    return Ok(());
+  }
+  let request = format!(
+    "HEAD {} HTTP/1.0\r\nHost: {}\r\n\r\n",
+    &url[Position::BeforePath..],
+    url.host_str().unwrap_or_default()
+  );
+  let mut response = [0; 5];
+  stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+  stream.write_all(request.as_bytes())?;
+  stream.read_exact(&mut response)?;
+  if &response == b"HTTP/" {
+    Ok(())
+  } else {
+    Err(std::io::Error::new(
+      std::io::ErrorKind::InvalidData,
+      "the response isn't HTTP",
+    ))
+  }
+}
+
+/// Writes the `__tauri.js` file to the dist dir.
+fn write_tauri_script(config: &Config) -> crate::Result<()> {
+  let tauri_script = TauriScript::new()
+    .global_tauri(config.bundles_global_tauri())
+    .get();
+  let tauri_dir_path = PathBuf::from(&config.build.dist_dir);
+  let tauri_script_path = tauri_dir_path.join("__tauri.js");
+  create_dir_all(tauri_dir_path)?;
+  let mut tauri_script_file = File::create(tauri_script_path)?;
+  tauri_script_file.write_all(tauri_script.as_bytes())?;
+  Ok(())
+}
+
+/// Copies the sidecars next to the app binary so they can be spawned by their plain name.
+fn copy_sidecars() -> crate::Result<()> {
+  let settings = SettingsBuilder::new().build()?;
+  settings.copy_binaries(settings.project_out_directory())?;
+  Ok(())
+}
+
+/// Gets the path of the app binary built by the last `cargo run`, if there's one.
+fn built_app_binary() -> Option<PathBuf> {
+  let settings = SettingsBuilder::new().build().ok()?;
+  let main_binary = settings.binaries().iter().find(|binary| binary.main())?;
+  Some(settings.binary_path(main_binary)).filter(|path| path.exists())
+}
+
+/// Forwards the output of `cargo run --message-format json`:
+/// the messages until the build finishes are events, and the app output goes to stderr.
+fn forward_cargo_messages(output: ChildStdout) {
+  std::thread::spawn(move || {
+    let mut building = true;
+    for line in BufReader::new(output).lines() {
+      let line = match line {
+        Ok(line) => line,
+        Err(_) => break,
+      };
+      if building {
+        if let Ok(message) = serde_json::from_str::<JsonValue>(&line) {
+          building = message["reason"] != "build-finished";
+          emit(Event::CargoMessage { message });
+          continue;
+        }
+      }
+      print_output(&line, false);
+    }
+  });
+}
+
+/// The exit code of the app if it already exited, e.g. because it crashed or failed to compile.
+fn exit_code(process: &SharedChild) -> Option<i32> {
+  process
+    .try_wait()
+    .ok()
+    .flatten()
+    .and_then(|status| status.code())
+}
+
+/// Runs the `build > hooks > onDevReload` hook on a new thread, so it doesn't hold the file watcher.
+fn run_dev_reload_hook(config: &Config, changed_path: &Path, previous_exit_code: Option<i32>) {
+  let hook = match config
+    .build
+    .hooks
+    .as_ref()
+    .and_then(|hooks| hooks.on_dev_reload.clone())
+  {
+    Some(hook) => hook,
+    None => return,
+  };
+  let target_triple = match config.build.target.clone() {
+    Some(target) => target,
+    None => match target_triple() {
+      Ok(target_triple) => target_triple,
+      Err(e) => {
+        Logger::new("tauri:dev").warn(format!(
+          "the `build.hooks.onDevReload` hook didn't run: {}",
+          e
+        ));
+        return;
+      }
+    },
+  };
+  let changed_path = changed_path.to_string_lossy().into_owned();
+  std::thread::spawn(move || {
+    let context = HookContext {
+      name: "onDevReload",
+      target_triple,
+      profile: "debug",
+      artifacts: JsonValue::Array(Vec::new()),
+      previous_exit_code,
+      env: vec![("TAURI_CHANGED_PATH", changed_path)],
+    };
+    // a strict hook can't fail the dev session, so its failure is only reported too
+    if let Err(e) = run_hook(&Logger::new("tauri:dev"), &hook, &context) {
+      Logger::new("tauri:dev").error(e.to_string());
+    }
+  });
+}
 
 fn kill_before_dev_process() {
   if let Some(child) = BEFORE_DEV.get() {
-    let _ = child.lock().unwrap().kill();
+    let _ = child.kill();
   }
 }
 
 #[derive(Default)]
 pub struct Dev {
   exit_on_panic: bool,
-  config: Option<String>,
+  config: Vec<String>,
+  features: Option<Vec<String>>,
+  no_cli_validation: bool,
+  verbose: bool,
+  force_embed: bool,
+  trace_startup: bool,
+  args: Vec<String>,
 }
 
 impl Dev {
@@ -42,8 +350,8 @@ impl Dev {
     Default::default()
   }
 
-  pub fn config(mut self, config: String) -> Self {
-    self.config.replace(config);
+  pub fn config(mut self, config: Vec<String>) -> Self {
+    self.config = config;
     self
   }
 
@@ -52,14 +360,90 @@ impl Dev {
     self
   }
 
+  pub fn features(mut self, features: Vec<String>) -> Self {
+    self.features.replace(features);
+    self
+  }
+
+  pub fn no_cli_validation(mut self, no_cli_validation: bool) -> Self {
+    self.no_cli_validation = no_cli_validation;
+    self
+  }
+
+  pub fn verbose(mut self, verbose: bool) -> Self {
+    self.verbose = verbose;
+    self
+  }
+
+  pub fn force_embed(mut self, force_embed: bool) -> Self {
+    self.force_embed = force_embed;
+    self
+  }
+
+  pub fn trace_startup(mut self, trace_startup: bool) -> Self {
+    self.trace_startup = trace_startup;
+    self
+  }
+
+  pub fn args(mut self, args: Vec<String>) -> Self {
+    self.args = args;
+    self
+  }
+
   pub fn run(self) -> crate::Result<()> {
     let logger = Logger::new("tauri:dev");
+    // the config files are relative to the current directory
+    let merge_configs =
+      parse_merge_configs(&self.config).map_err(|e| coded(ErrorCode::Config, e))?;
     let tauri_path = tauri_dir();
     set_current_dir(&tauri_path)?;
-    let merge_config = self.config.clone();
-    let config = get_config(merge_config.as_deref())?;
+    let start = Instant::now();
+    let config = get_config(&merge_configs).map_err(|e| coded(ErrorCode::Config, e))?;
+    emit(Event::ConfigLoaded {
+      path: config_file_path(),
+    });
+    logger.timing("loading the config", start);
+    logger.config(serde_json::to_value(&*config.lock().unwrap())?);
+    if let Some(warning) = check_identifier(config.lock().unwrap().as_ref().unwrap()) {
+      logger.warn(warning);
+    }
+    if let Some(warning) = check_remote_host(config.lock().unwrap().as_ref().unwrap()) {
+      logger.warn(warning);
+    }
+    for warning in check_injected_constants(config.lock().unwrap().as_ref().unwrap()) {
+      logger.warn(warning);
+    }
+    // cargo compiles the app for the minimum macOS version the bundle declares
+    if let Some(target) = deployment_target(
+      config.lock().unwrap().as_ref().unwrap(),
+      var("MACOSX_DEPLOYMENT_TARGET").ok().as_deref(),
+    )
+    .map_err(|e| coded(ErrorCode::Config, e))?
+    {
+      set_var("MACOSX_DEPLOYMENT_TARGET", target);
+    }
     let mut process: Arc<SharedChild>;
 
+    // read before the config lock is held by the before dev command block
+    let mut before_dev_env = injected_constants_env(config.lock().unwrap().as_ref().unwrap(), true);
+    // the `build.devProxy` rules, so the dev server can forward the same paths
+    if let Some(proxy) = &config
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap()
+      .tauri
+      .embedded_server
+      .proxy
+    {
+      before_dev_env.push(("TAURI_DEV_PROXY".into(), serde_json::to_string(proxy)?));
+    }
+    // the app picks the same port, since it inherits the variable
+    if let Some(port) = embedded_server_port(config.lock().unwrap().as_ref().unwrap()) {
+      set_var(EMBEDDED_SERVER_PORT_ENV, port.to_string());
+      before_dev_env.push((EMBEDDED_SERVER_PORT_ENV.into(), port.to_string()));
+    }
+
     if let Some(before_dev) = &config
       .lock()
       .unwrap()
@@ -86,30 +470,62 @@ impl Dev {
         );
         #[cfg(not(target_os = "windows"))]
         let mut command = Command::new(cmd);
-        let child = command.args(args).current_dir(app_dir()).spawn()?;
-        BEFORE_DEV.set(Mutex::new(child)).unwrap();
+        let env: Vec<(&str, String)> = before_dev_env
+          .iter()
+          .map(|(key, value)| (key.as_str(), value.clone()))
+          .collect();
+        command
+          .args(args)
+          .envs(env.iter().map(|(key, value)| (key, value)))
+          .current_dir(app_dir());
+        if json_events() || has_log_file() {
+          command.stdout(Stdio::piped());
+        }
+        if has_log_file() {
+          command.stderr(Stdio::piped());
+        }
+        logger.command(&command, &env);
+        emit(Event::BeforeCommandStarted {
+          command: before_dev.to_string(),
+        });
+        let child =
+          SharedChild::spawn(&mut command).map_err(|e| coded(ErrorCode::BeforeCommand, e))?;
+        // it may read the terminal, so it stays on the CLI process group
+        process::track(child.id());
+        if let Some(stdout) = child.take_stdout() {
+          forward_output(stdout, false);
+        }
+        if let Some(stderr) = child.take_stderr() {
+          forward_output(stderr, true);
+        }
+        let child = Arc::new(child);
+        let child_clone = child.clone();
+        std::thread::spawn(move || {
+          if let Ok(status) = child_clone.wait() {
+            process::untrack(child_clone.id(), &status);
+          }
+        });
+        BEFORE_DEV.set(child).unwrap();
       }
     }
 
-    let dev_path = config
-      .lock()
-      .unwrap()
-      .as_ref()
-      .unwrap()
-      .build
-      .dev_path
-      .to_string();
-
-    let dev_path = if dev_path.starts_with("http") {
-      dev_path
-    } else {
-      let absolute_dev_path = tauri_dir()
-        .join(&config.lock().unwrap().as_ref().unwrap().build.dev_path)
-        .to_string_lossy()
-        .to_string();
-      (*config.lock().unwrap()).as_mut().unwrap().build.dev_path = absolute_dev_path.to_string();
-      absolute_dev_path
-    };
+    let mut dev_path = absolute_dev_path(&config.lock().unwrap().as_ref().unwrap().build.dev_path);
+    (*config.lock().unwrap()).as_mut().unwrap().build.dev_path = dev_path.clone();
+    let dev_server_timeout = Duration::from_millis(
+      config
+        .lock()
+        .unwrap()
+        .as_ref()
+        .unwrap()
+        .build
+        .dev_server_timeout_ms,
+    );
+    check_dev_path(
+      &dev_path,
+      dev_server_timeout,
+      BEFORE_DEV.get().is_some(),
+      &logger,
+    )?;
 
     set_var("TAURI_DIR", &tauri_path);
     set_var(
@@ -120,46 +536,53 @@ impl Dev {
       "TAURI_CONFIG",
       serde_json::to_string(&*config.lock().unwrap())?,
     );
+    // the app parses its args against the CLI definition on startup, like the `getMatches` API does in production
+    set_var(
+      "TAURI_CLI_ARGS_VALIDATION",
+      (!self.no_cli_validation).to_string(),
+    );
+    // the assets are embedded by the `tauri` macros, which reuse them while the dist dir doesn't change
+    set_var("TAURI_VERBOSE", self.verbose.to_string());
+    set_var("TAURI_FORCE_EMBED", self.force_embed.to_string());
+    // the app writes the trace to the temp dir, unless the variable already sets its path
+    if self.trace_startup && var_os("TAURI_TRACE_STARTUP").is_none() {
+      set_var("TAURI_TRACE_STARTUP", "1");
+    }
 
-    rewrite_manifest(config.clone())?;
+    rewrite_manifest(config.clone(), self.trace_startup)?;
 
-    // __tauri.js
-    {
-      let config_guard = config.lock().unwrap();
-      let config_ = config_guard.as_ref().unwrap();
-      let tauri_script = TauriScript::new()
-        .global_tauri(config_.build.with_global_tauri)
-        .get();
-      let tauri_dir_path = PathBuf::from(&config_.build.dist_dir);
-      let tauri_script_path = tauri_dir_path.join("__tauri.js");
-      create_dir_all(tauri_dir_path)?;
-      let mut tauri_script_file = File::create(tauri_script_path)?;
-      tauri_script_file.write_all(tauri_script.as_bytes())?;
-    }
+    write_tauri_script(config.lock().unwrap().as_ref().unwrap())?;
+    copy_sidecars()?;
+    let (mut runner, mut features) =
+      self.runner_and_features(config.lock().unwrap().as_ref().unwrap())?;
 
     let (child_wait_tx, child_wait_rx) = channel();
     let child_wait_rx = Arc::new(Mutex::new(child_wait_rx));
 
-    process = self.start_app(child_wait_rx.clone());
+    process = self.start_app(&runner, &features, child_wait_rx.clone());
 
     let (tx, rx) = channel();
 
-    let mut watcher = watcher(tx, Duration::from_secs(1)).unwrap();
+    let watcher_config = config
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap()
+      .build
+      .dev_watcher
+      .clone();
+    let mut ignore = WatcherIgnore::new(&watcher_config);
+    // the debounce is only read on startup
+    let debounce = watcher_config
+      .debounce_ms
+      .unwrap_or(DEFAULT_WATCHER_DEBOUNCE_MS);
+    let mut watcher = watcher(tx, Duration::from_millis(debounce)).unwrap();
     watcher.watch(tauri_path.join("src"), RecursiveMode::Recursive)?;
     watcher.watch(tauri_path.join("Cargo.toml"), RecursiveMode::Recursive)?;
-    watcher.watch(tauri_path.join("tauri.conf.json"), RecursiveMode::Recursive)?;
-    if !dev_path.starts_with("http") {
-      watcher.watch(
-        config
-          .lock()
-          .unwrap()
-          .as_ref()
-          .unwrap()
-          .build
-          .dev_path
-          .to_string(),
-        RecursiveMode::Recursive,
-      )?;
+    let config_path = config_file_path();
+    watcher.watch(&config_path, RecursiveMode::Recursive)?;
+    for path in dev_path.paths() {
+      watcher.watch(path, RecursiveMode::Recursive)?;
     }
 
     loop {
@@ -173,38 +596,167 @@ impl Dev {
         };
 
         if let Some(event_path) = event_path {
-          if event_path.file_name() == Some(OsStr::new("tauri.conf.json")) {
-            reload_config(merge_config.as_deref())?;
-            (*config.lock().unwrap()).as_mut().unwrap().build.dev_path = dev_path.to_string();
-            rewrite_manifest(config.clone())?;
-            set_var("TAURI_CONFIG", serde_json::to_string(&*config)?);
-          } else {
+          if event_path.file_name() == config_path.file_name() {
+            let old_config = config.lock().unwrap().clone().unwrap();
+            if let Err(e) = reload_config(&merge_configs) {
+              // keep the session running with the previous config until the file is fixed
+              logger.error(format!("{}\nkeeping the previous config", e));
+              continue;
+            }
+            emit(Event::ConfigLoaded {
+              path: config_path.clone(),
+            });
+
+            let new_dev_path =
+              absolute_dev_path(&config.lock().unwrap().as_ref().unwrap().build.dev_path);
+            (*config.lock().unwrap()).as_mut().unwrap().build.dev_path = new_dev_path.clone();
+            if new_dev_path != dev_path {
+              for path in dev_path.paths() {
+                let _ = watcher.unwatch(path);
+              }
+              for path in new_dev_path.paths() {
+                watcher.watch(path, RecursiveMode::Recursive)?;
+              }
+              dev_path = new_dev_path;
+            }
+            set_var(
+              "TAURI_CONFIG",
+              serde_json::to_string(&*config.lock().unwrap())?,
+            );
+
+            let new_config = config.lock().unwrap().clone().unwrap();
+            ignore = WatcherIgnore::new(&new_config.build.dev_watcher);
+            if requires_rebuild(&old_config, &new_config) {
+              logger.log("the config changed, rebuilding the app");
+              let rebuild = write_tauri_script(&new_config)
+                .and_then(|_| copy_sidecars())
+                .and_then(|_| self.runner_and_features(&new_config));
+              match rebuild {
+                Ok((new_runner, new_features)) => {
+                  runner = new_runner;
+                  features = new_features;
+                  // the Cargo.toml change triggers the watcher again, which restarts the app
+                  rewrite_manifest(config.clone(), self.trace_startup)?;
+                }
+                Err(e) => logger.error(e.to_string()),
+              }
+            } else {
+              logger.log("the config changed, restarting the app");
+              let previous_exit_code = exit_code(&process);
+              let _ = child_wait_tx.send(());
+              process.kill()?;
+              process = match built_app_binary() {
+                Some(binary) => {
+                  let mut command = Command::new(&binary);
+                  command.args(&self.args);
+                  self.spawn_app(command, false, child_wait_rx.clone())
+                }
+                None => self.start_app(&runner, &features, child_wait_rx.clone()),
+              };
+              run_dev_reload_hook(&new_config, &event_path, previous_exit_code);
+            }
+          } else if !ignore.is_ignored(event_path.strip_prefix(&tauri_path).unwrap_or(&event_path))
+          {
             // When tauri.conf.json is changed, rewrite_manifest will be called
             // which will trigger the watcher again
             // So the app should only be started when a file other than tauri.conf.json is changed
+            let previous_exit_code = exit_code(&process);
             let _ = child_wait_tx.send(());
             process.kill()?;
-            process = self.start_app(child_wait_rx.clone());
+            process = self.start_app(&runner, &features, child_wait_rx.clone());
+            run_dev_reload_hook(
+              config.lock().unwrap().as_ref().unwrap(),
+              &event_path,
+              previous_exit_code,
+            );
           }
         }
       }
     }
   }
 
-  fn start_app(&self, child_wait_rx: Arc<Mutex<Receiver<()>>>) -> Arc<SharedChild> {
-    let mut command = Command::new("cargo");
+  /// Gets the runner and the Cargo features of the app.
+  fn runner_and_features(&self, config: &Config) -> crate::Result<(String, Vec<String>)> {
+    let mut features = config.build.features.clone().unwrap_or_default();
+    if let Some(cli_features) = &self.features {
+      features.extend(cli_features.clone());
+    }
+    validate_features(&features)?;
+    Ok((
+      config
+        .build
+        .runner
+        .clone()
+        .unwrap_or_else(|| "cargo".to_string()),
+      features,
+    ))
+  }
+
+  fn start_app(
+    &self,
+    runner: &str,
+    features: &[String],
+    child_wait_rx: Arc<Mutex<Receiver<()>>>,
+  ) -> Arc<SharedChild> {
+    let mut command = Command::new(runner);
     command.arg("run");
-    let child = SharedChild::spawn(&mut command).expect("failed to run cargo");
+    if json_events() {
+      command.arg("--message-format=json");
+    }
+    if !features.is_empty() {
+      command.arg(format!("--features={}", features.join(" ")));
+    }
+    if !self.args.is_empty() {
+      command.arg("--").args(&self.args);
+    }
+    self.spawn_app(command, json_events(), child_wait_rx)
+  }
+
+  /// Spawns the app, or `cargo run --message-format json` if `cargo_messages` is set.
+  fn spawn_app(
+    &self,
+    mut command: Command,
+    cargo_messages: bool,
+    child_wait_rx: Arc<Mutex<Receiver<()>>>,
+  ) -> Arc<SharedChild> {
+    if json_events() || has_log_file() {
+      command.stdout(Stdio::piped());
+    }
+    if has_log_file() {
+      command.stderr(Stdio::piped());
+    }
+    Logger::new("tauri:dev").command(&command, &[]);
+    process::set_process_group(&mut command);
+    let child =
+      SharedChild::spawn(&mut command).unwrap_or_else(|_| panic!("failed to run {:?}", command));
+    process::track(child.id());
+    if let Some(stdout) = child.take_stdout() {
+      if cargo_messages {
+        forward_cargo_messages(stdout);
+      } else {
+        forward_output(stdout, false);
+      }
+    }
+    if let Some(stderr) = child.take_stderr() {
+      forward_output(stderr, true);
+    }
     let child_arc = Arc::new(child);
 
     let child_clone = child_arc.clone();
     let exit_on_panic = self.exit_on_panic;
+    let cli_validation = !self.no_cli_validation;
     std::thread::spawn(move || {
       let status = child_clone.wait().expect("failed to wait on child");
+      process::untrack(child_clone.id(), &status);
+      // the app already printed the CLI args error
+      if cli_validation && status.code() == Some(INVALID_CLI_ARGS_EXIT_CODE) {
+        kill_before_dev_process();
+        exit(INVALID_CLI_ARGS_EXIT_CODE);
+      }
       if exit_on_panic {
-        // we exit if the status is a success code (app closed) or code is 101 (compilation error)
-        // if the process wasn't killed by the file watcher
-        if (status.success() || status.code() == Some(101))
+        // we exit with the app exit code (or 101 on compilation errors) if the process wasn't killed by the file watcher,
+        // nor interrupted by a termination signal, whose handler exits once every child finished
+        if !process::interrupted()
           // `child_wait_rx` indicates that the process was killed by the file watcher
           && child_wait_rx
           .lock()
@@ -213,7 +765,8 @@ impl Dev {
           .is_err()
         {
           kill_before_dev_process();
-          exit(0);
+          // forward the app exit code, or the compilation error one
+          exit(process::exit_code(&status));
         }
       } else if status.success() {
         // if we're no exiting on panic, we only exit if the status is a success code (app closed)
@@ -225,3 +778,147 @@ impl Dev {
     child_arc
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::{
+    check_dev_path, requires_rebuild, responds, AppUrl, Config, DevWatcherConfig, Logger,
+    WatcherIgnore,
+  };
+  use serde_json::json;
+  use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::Path,
+    thread::spawn,
+    time::Duration,
+  };
+  use url::Url;
+
+  #[test]
+  fn config_changes_requiring_rebuild() {
+    let config = |value: serde_json::Value| -> Config { serde_json::from_value(value).unwrap() };
+    let old = config(json!({
+      "build": { "devPath": "http://localhost:8080", "distDir": "../dist" },
+      "tauri": { "windows": [{ "title": "app" }] }
+    }));
+
+    // the app reads these values from `TAURI_CONFIG`
+    for new in &[
+      json!({
+        "build": { "devPath": "http://localhost:8080", "distDir": "../dist" },
+        "tauri": { "windows": [{ "title": "renamed" }] }
+      }),
+      json!({
+        "build": { "devPath": "http://localhost:3000", "distDir": "../dist" },
+        "tauri": { "windows": [{ "title": "app" }] }
+      }),
+    ] {
+      assert!(!requires_rebuild(&old, &config(new.clone())), "{}", new);
+    }
+    // these are compiled into the app
+    for new in &[
+      json!({
+        "build": { "devPath": "../dist", "distDir": "../dist" },
+        "tauri": { "windows": [{ "title": "app" }] }
+      }),
+      json!({
+        "build": { "devPath": "http://localhost:8080", "distDir": "../public" },
+        "tauri": { "windows": [{ "title": "app" }] }
+      }),
+      json!({
+        "build": { "devPath": "http://localhost:8080", "distDir": "../dist" },
+        "tauri": { "windows": [{ "title": "app" }], "allowlist": { "all": true } }
+      }),
+      json!({
+        "build": { "devPath": "http://localhost:8080", "distDir": "../dist" },
+        "tauri": {
+          "windows": [{ "title": "app" }],
+          "bundle": { "active": true, "identifier": "com.tauri.app" }
+        }
+      }),
+    ] {
+      assert!(requires_rebuild(&old, &config(new.clone())), "{}", new);
+    }
+  }
+
+  #[test]
+  fn watcher_ignore() {
+    let ignore = WatcherIgnore::new(&DevWatcherConfig {
+      ignore: Some(vec!["*.log".into(), "data".into(), "src/generated/".into()]),
+      debounce_ms: None,
+    });
+    for path in &[
+      "target/debug/app",
+      "src/app.log",
+      "data/app.sqlite",
+      "src/generated/mod.rs",
+    ] {
+      assert!(ignore.is_ignored(Path::new(path)), "{}", path);
+    }
+    for path in &["src/main.rs", "Cargo.toml", "generated/mod.rs"] {
+      assert!(!ignore.is_ignored(Path::new(path)), "{}", path);
+    }
+  }
+
+  /// Serves one connection with the given response, returning the server URL.
+  fn serve_once(response: &'static [u8]) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = [0; 4];
+      stream.read_exact(&mut request).unwrap();
+      assert_eq!(&request, b"HEAD");
+      stream.write_all(response).unwrap();
+    });
+    Url::parse(&format!("http://{}/app", address)).unwrap()
+  }
+
+  #[test]
+  fn dev_server_checks() {
+    assert_eq!(responds(&serve_once(b"HTTP/1.1 200 OK\r\n\r\n")), Ok(()));
+    let error = responds(&serve_once(b"SSH-2.0-OpenSSH\r\n")).unwrap_err();
+    assert!(error.ends_with("the response isn't HTTP"), "{}", error);
+
+    // nothing listens on the port once the listener is dropped
+    let address = TcpListener::bind("127.0.0.1:0")
+      .unwrap()
+      .local_addr()
+      .unwrap();
+    let url = Url::parse(&format!("http://{}", address)).unwrap();
+    let error = responds(&url).unwrap_err();
+    assert!(error.starts_with(&address.to_string()), "{}", error);
+
+    let logger = Logger::new("tauri:dev");
+    let error = check_dev_path(
+      &AppUrl::Url(url.clone()),
+      Duration::from_millis(0),
+      true,
+      &logger,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+      error.starts_with(&format!(
+        "the dev server at `{}` (`build.devPath`) didn't respond within 0ms, the last check failed with: {}",
+        url, address
+      )),
+      "{}",
+      error
+    );
+    assert!(
+      error.ends_with("or raise `build.devServerTimeoutMs`"),
+      "{}",
+      error
+    );
+    let error = check_dev_path(&AppUrl::Url(url), Duration::from_millis(0), false, &logger)
+      .unwrap_err()
+      .to_string();
+    assert!(
+      error.ends_with("; start your dev server first, or set `build.beforeDevCommand` to start it"),
+      "{}",
+      error
+    );
+  }
+}
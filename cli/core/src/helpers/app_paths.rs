@@ -1,17 +1,53 @@
-use std::{env::current_dir, path::PathBuf};
+use std::{
+  env::current_dir,
+  path::{Path, PathBuf},
+};
 
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
+
+use super::config::config_sources;
+
+/// The default directory of the Rust project, relative to the app directory.
+const DEFAULT_TAURI_DIR: &str = "src-tauri";
+
+static PROJECT_PATH: OnceCell<PathBuf> = OnceCell::new();
+
+/// Sets the app directory from `--project-path`, so it isn't searched from the cwd.
+pub fn set_project_path(path: impl AsRef<Path>) {
+  let path = current_dir().expect("failed to read cwd").join(path);
+  let _ = PROJECT_PATH.set(path.canonicalize().unwrap_or(path));
+}
+
+/// The `build > srcTauriDir` of the first config source of the directory, if it's set.
+fn src_tauri_dir(config_dir: &Path) -> Option<String> {
+  let (_, config) = config_sources(config_dir).first()?.read().ok()?;
+  config
+    .pointer("/build/srcTauriDir")?
+    .as_str()
+    .map(ToString::to_string)
+}
+
+/// Whether the directory is a Tauri project: its `src-tauri` has a config,
+/// or it has a config itself with `build > srcTauriDir`, e.g. on a monorepo.
+fn is_app_dir(dir: &Path) -> bool {
+  !config_sources(&dir.join(DEFAULT_TAURI_DIR)).is_empty() || src_tauri_dir(dir).is_some()
+}
+
+/// Finds the app directory: the `--project-path`,
+/// or the first of the cwd and its two parents that is a Tauri project.
+pub fn find_app_dir() -> Option<PathBuf> {
+  if let Some(project_path) = PROJECT_PATH.get() {
+    return Some(project_path.clone()).filter(|dir| is_app_dir(dir));
+  }
 
-fn get_app_dir() -> PathBuf {
   let mut dir = current_dir().expect("failed to read cwd");
 
   let mut count = 0;
 
   // only go up three folders max
   while count <= 2 {
-    let test_path = dir.join("src-tauri/tauri.conf.json");
-    if test_path.exists() {
-      return dir;
+    if is_app_dir(&dir) {
+      return Some(dir);
     }
     count += 1;
     match dir.parent() {
@@ -22,14 +58,77 @@ fn get_app_dir() -> PathBuf {
     }
   }
 
-  panic!("Couldn't recognize the current folder as a Tauri project.")
+  None
+}
+
+fn get_app_dir() -> PathBuf {
+  match find_app_dir() {
+    Some(dir) => dir,
+    None => {
+      let (root, hint) = match PROJECT_PATH.get() {
+        Some(project_path) => (project_path.clone(), "the `--project-path`"),
+        None => (
+          current_dir().expect("failed to read cwd"),
+          "the current folder or its two parents",
+        ),
+      };
+      panic!(
+        "Couldn't recognize {} (`{}`) as a Tauri project: it must have a `src-tauri` folder with a config file, or a config file with `build.srcTauriDir`.",
+        hint,
+        root.display()
+      )
+    }
+  }
 }
 
+/// The app directory, the root the Rust project and the config file are resolved from.
 pub fn app_dir() -> &'static PathBuf {
   static APP_DIR: Lazy<PathBuf> = Lazy::new(get_app_dir);
   &APP_DIR
 }
 
+/// The directory of the config file: `src-tauri`, or the app directory if the config is there.
+pub fn config_dir() -> PathBuf {
+  let default_dir = app_dir().join(DEFAULT_TAURI_DIR);
+  if config_sources(&default_dir).is_empty() && src_tauri_dir(app_dir()).is_some() {
+    app_dir().clone()
+  } else {
+    default_dir
+  }
+}
+
+/// The Rust project directory: `build > srcTauriDir`, relative to the app directory, or `src-tauri`.
 pub fn tauri_dir() -> PathBuf {
-  app_dir().join("src-tauri")
+  static TAURI_DIR: Lazy<PathBuf> = Lazy::new(|| {
+    let src_tauri_dir =
+      src_tauri_dir(&config_dir()).unwrap_or_else(|| DEFAULT_TAURI_DIR.to_string());
+    app_dir().join(src_tauri_dir)
+  });
+  TAURI_DIR.clone()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{is_app_dir, src_tauri_dir};
+  use std::fs::{create_dir, write};
+
+  #[test]
+  fn src_tauri_dir_on_the_project_root() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(!is_app_dir(dir.path()));
+
+    write(
+      dir.path().join("tauri.conf.json"),
+      r#"{ "build": { "srcTauriDir": "native", "distDir": "dist" } }"#,
+    )
+    .unwrap();
+    assert!(is_app_dir(dir.path()));
+    assert_eq!(src_tauri_dir(dir.path()), Some("native".to_string()));
+
+    let dir = tempfile::tempdir().unwrap();
+    create_dir(dir.path().join("src-tauri")).unwrap();
+    write(dir.path().join("src-tauri").join("tauri.conf.json"), "{}").unwrap();
+    assert!(is_app_dir(dir.path()));
+    assert_eq!(src_tauri_dir(&dir.path().join("src-tauri")), None);
+  }
 }
@@ -1,72 +1,2951 @@
 use json_patch::merge;
 use once_cell::sync::Lazy;
+use semver::Version;
 use serde_json::Value as JsonValue;
+use toml_edit::{Document, Item};
 
 #[path = "../../config_definition.rs"]
 mod config_definition;
 pub use config_definition::*;
 
+use super::{
+  config_paths::{resolve_paths, PathVariables},
+  deprecations::{migrate, Deprecation, DEPRECATED_FIELDS},
+  Logger,
+};
+
 use std::{
-  fs::File,
-  io::BufReader,
-  process::exit,
-  sync::{Arc, Mutex},
+  collections::{HashMap, HashSet},
+  fs::read_to_string,
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpListener},
+  path::{Component, Path, PathBuf},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
 };
 
+/// The JSON config file name.
+pub const JSON_CONFIG_FILE: &str = "tauri.conf.json";
+/// The TOML config file name, used when the JSON file doesn't exist.
+pub const TOML_CONFIG_FILE: &str = "tauri.conf.toml";
+/// The npm manifest, whose `tauri` key is used when neither config file exists.
+pub const PACKAGE_JSON_FILE: &str = "package.json";
+/// The last config file looked up.
+pub const RC_CONFIG_FILE: &str = ".taurirc.json";
+/// The embedded server port of the `tauri dev` session, shared with the before dev command and the app.
+pub const EMBEDDED_SERVER_PORT_ENV: &str = "TAURI_EMBEDDED_SERVER_PORT";
+/// The identifier of the app template, which must be changed before shipping the app.
+const DEFAULT_IDENTIFIER: &str = "com.tauri.dev";
+/// The constants injected along with `build.injectedConstants`: the app version, the app name and whether it's a debug build.
+const INJECTED_METADATA: &[&str] = &["TAURI_APP_VERSION", "TAURI_APP_NAME", "TAURI_DEBUG"];
+/// The label of the window created from `tauri.splashscreen.html`.
+const SPLASHSCREEN_LABEL: &str = "splashscreen";
+/// The default `tauri.splashscreen.timeoutMs`.
+const DEFAULT_SPLASHSCREEN_TIMEOUT_MS: u64 = 10000;
+/// A valid identifier shown on the error messages.
+const IDENTIFIER_EXAMPLE: &str = "com.mycompany.myapp";
+/// The oldest `tauri.bundle.osx.minimumSystemVersion`, the first macOS version the webview supports the app on.
+const MIN_MACOS_VERSION: &str = "10.13";
+/// The environment variable enabling the permissive config mode, like `--permissive-config`.
+const PERMISSIVE_ENV: &str = "TAURI_CONFIG_PERMISSIVE";
+/// The response headers the embedded server sets itself.
+const RESERVED_HEADERS: &[&str] = &["Content-Length", "Content-Encoding", "Content-Range"];
+
+/// The accelerator modifiers, lowercase.
+const ACCELERATOR_MODIFIERS: &[&str] = &[
+  "cmd",
+  "command",
+  "super",
+  "meta",
+  "ctrl",
+  "control",
+  "cmdorctrl",
+  "commandorcontrol",
+  "alt",
+  "option",
+  "shift",
+];
+
+/// The accelerator keys with a name, lowercase. The other keys are single characters or `F1` to `F24`.
+const ACCELERATOR_KEYS: &[&str] = &[
+  "enter",
+  "return",
+  "escape",
+  "esc",
+  "tab",
+  "space",
+  "backspace",
+  "delete",
+  "insert",
+  "home",
+  "end",
+  "pageup",
+  "pagedown",
+  "up",
+  "down",
+  "left",
+  "right",
+  "plus",
+];
+
 pub type ConfigHandle = Arc<Mutex<Option<Config>>>;
 
+/// Whether `--permissive-config` was passed.
+static PERMISSIVE: AtomicBool = AtomicBool::new(false);
+/// Whether the config source is logged, set by `--verbose`.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+/// Whether the `debug` build profile overrides are applied instead of the `release` ones.
+static DEBUG_PROFILE: AtomicBool = AtomicBool::new(false);
+
+/// Enables the permissive config mode, where the unknown fields are ignored with a warning instead of failing,
+/// e.g. to read a config written for a newer CLI. The type errors still fail.
+pub fn set_permissive(permissive: bool) {
+  PERMISSIVE.store(permissive, Ordering::Relaxed);
+}
+
+/// Logs the config source when the config is loaded.
+pub fn set_verbose(verbose: bool) {
+  VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+/// Applies the `build > profiles > debug` overrides instead of the `release` ones, for `tauri dev` and `tauri build --debug`.
+pub fn set_debug_profile(debug: bool) {
+  DEBUG_PROFILE.store(debug, Ordering::Relaxed);
+}
+
+/// The name of the build profile whose overrides are applied.
+fn profile() -> &'static str {
+  if DEBUG_PROFILE.load(Ordering::Relaxed) {
+    "debug"
+  } else {
+    "release"
+  }
+}
+
+/// Whether the permissive config mode is enabled by `--permissive-config` or `TAURI_CONFIG_PERMISSIVE`.
+fn is_permissive() -> bool {
+  PERMISSIVE.load(Ordering::Relaxed)
+    || std::env::var(PERMISSIVE_ENV)
+      .map(|value| value != "0" && value != "false")
+      .unwrap_or(false)
+}
+
 fn config_handle() -> &'static ConfigHandle {
   static CONFING_HANDLE: Lazy<ConfigHandle> = Lazy::new(Default::default);
   &CONFING_HANDLE
 }
 
-/// Gets the static parsed config from `tauri.conf.json`.
-fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<ConfigHandle> {
+/// The deprecated fields used on the last loaded config.
+fn deprecations_handle() -> &'static Mutex<Vec<Deprecation>> {
+  static DEPRECATIONS: Lazy<Mutex<Vec<Deprecation>>> = Lazy::new(Default::default);
+  &DEPRECATIONS
+}
+
+/// The deprecated fields used on the loaded config, e.g. to fail the build on CI.
+pub fn deprecations() -> Vec<Deprecation> {
+  deprecations_handle().lock().unwrap().clone()
+}
+
+/// Warns about the deprecated fields, only once per field across config reloads.
+fn warn_deprecations(deprecations: &[Deprecation]) {
+  static WARNED: Lazy<Mutex<HashSet<Deprecation>>> = Lazy::new(Default::default);
+  let mut warned = WARNED.lock().unwrap();
+  for deprecation in deprecations {
+    if warned.insert(deprecation.clone()) {
+      Logger::new("tauri:config").warn(deprecation.to_string());
+    }
+  }
+}
+
+/// The config file formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+  Json,
+  Toml,
+}
+
+/// A file the config can be read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+  /// The `tauri.conf.json` file.
+  Json(PathBuf),
+  /// The `tauri.conf.toml` file.
+  Toml(PathBuf),
+  /// The `tauri` key of the nearest `package.json`.
+  PackageJson(PathBuf),
+  /// The `.taurirc.json` file.
+  Rc(PathBuf),
+}
+
+impl ConfigSource {
+  pub fn path(&self) -> &Path {
+    match self {
+      Self::Json(path) | Self::Toml(path) | Self::PackageJson(path) | Self::Rc(path) => path,
+    }
+  }
+
+  pub fn format(&self) -> ConfigFormat {
+    match self {
+      Self::Toml(_) => ConfigFormat::Toml,
+      _ => ConfigFormat::Json,
+    }
+  }
+
+  /// The name shown on the messages, e.g. `package.json#tauri`.
+  pub fn name(&self) -> String {
+    let file_name = self.path().file_name().unwrap().to_string_lossy();
+    match self {
+      Self::PackageJson(_) => format!("{}#tauri", file_name),
+      _ => file_name.to_string(),
+    }
+  }
+
+  /// The config path of a config value on the source file, used to locate the errors.
+  fn file_path(&self, path: &str) -> String {
+    match self {
+      Self::PackageJson(_) if path.is_empty() => "tauri".to_string(),
+      Self::PackageJson(_) => format!("tauri.{}", path),
+      _ => path.to_string(),
+    }
+  }
+
+  /// Reads the source file, returning its contents and the config value.
+  pub fn read(&self) -> crate::Result<(String, JsonValue)> {
+    let contents = read_to_string(self.path())?;
+    let mut value = parse_config(&contents, self.format())
+      .map_err(|e| anyhow::anyhow!("failed to parse `{}`: {}", self.path().display(), e))?;
+    if let Self::PackageJson(_) = self {
+      value = value["tauri"].take();
+    }
+    Ok((contents, value))
+  }
+}
+
+/// Finds the config sources of a `src-tauri` directory, in precedence order:
+/// `tauri.conf.json`, `tauri.conf.toml`, the nearest `package.json` if it has a `tauri` key, then `.taurirc.json`.
+pub fn config_sources(tauri_dir: &Path) -> Vec<ConfigSource> {
+  let mut sources = Vec::new();
+  if !tauri_dir.is_dir() {
+    return sources;
+  }
+  let json_path = tauri_dir.join(JSON_CONFIG_FILE);
+  if json_path.exists() {
+    sources.push(ConfigSource::Json(json_path));
+  }
+  let toml_path = tauri_dir.join(TOML_CONFIG_FILE);
+  if toml_path.exists() {
+    sources.push(ConfigSource::Toml(toml_path));
+  }
+  let package_json = tauri_dir
+    .ancestors()
+    .map(|dir| dir.join(PACKAGE_JSON_FILE))
+    .find(|path| path.exists());
+  if let Some(path) = package_json {
+    let has_tauri_key = read_to_string(&path)
+      .ok()
+      .and_then(|contents| serde_json::from_str::<JsonValue>(&contents).ok())
+      .map_or(false, |package| package.get("tauri").is_some());
+    if has_tauri_key {
+      sources.push(ConfigSource::PackageJson(path));
+    }
+  }
+  let rc_path = tauri_dir.join(RC_CONFIG_FILE);
+  if rc_path.exists() {
+    sources.push(ConfigSource::Rc(rc_path));
+  }
+  sources
+}
+
+/// Gets the source the config is read from, the first of the [`config_sources`],
+/// or `tauri.conf.json` when there isn't any.
+pub fn config_source() -> ConfigSource {
+  let config_dir = super::app_paths::config_dir();
+  config_sources(&config_dir)
+    .into_iter()
+    .next()
+    .unwrap_or_else(|| ConfigSource::Json(config_dir.join(JSON_CONFIG_FILE)))
+}
+
+/// Gets the path of the file the config is read from.
+pub fn config_file_path() -> PathBuf {
+  config_source().path().to_path_buf()
+}
+
+/// Parses the config file contents to JSON, so both formats go through the same validation and deserialization.
+pub fn parse_config(contents: &str, format: ConfigFormat) -> crate::Result<JsonValue> {
+  match format {
+    // JSON5 is a superset of JSON, allowing comments, trailing commas and unquoted keys
+    ConfigFormat::Json => Ok(json5::from_str(contents)?),
+    ConfigFormat::Toml => {
+      let config: toml::Value = toml::from_str(contents)?;
+      Ok(serde_json::to_value(config)?)
+    }
+  }
+}
+
+/// Gets the static parsed config from the first of the [`config_sources`].
+fn get_internal(merge_configs: &[JsonValue], reload: bool) -> crate::Result<ConfigHandle> {
   if !reload && config_handle().lock().unwrap().is_some() {
     return Ok(config_handle().clone());
   }
 
-  let path = super::app_paths::tauri_dir().join("tauri.conf.json");
-  let file = File::open(path)?;
-  let buf = BufReader::new(file);
-  let mut config: JsonValue = serde_json::from_reader(buf)?;
+  let config = load(merge_configs, true)?;
+  *config_handle().lock().unwrap() = Some(config);
+
+  Ok(config_handle().clone())
+}
+
+/// Reads, merges, interpolates and validates the config,
+/// then resolves its path-like fields with [`resolve_paths`] if `resolve` is set.
+fn load(merge_configs: &[JsonValue], resolve: bool) -> crate::Result<Config> {
+  let logger = Logger::new("tauri:config");
+  let config_dir = super::app_paths::config_dir();
+  let sources = config_sources(&config_dir);
+  let config_source = sources
+    .first()
+    .cloned()
+    .unwrap_or_else(|| ConfigSource::Json(config_dir.join(JSON_CONFIG_FILE)));
+  if sources.len() > 1 {
+    logger.warn(format!(
+      "multiple config sources found ({}); using `{}`",
+      sources
+        .iter()
+        .map(|source| format!("`{}`", source.name()))
+        .collect::<Vec<_>>()
+        .join(", "),
+      config_source.name()
+    ));
+  }
+  if VERBOSE.load(Ordering::Relaxed) {
+    logger.log(format!(
+      "reading the config from `{}` of the project `{}`",
+      config_source.path().display(),
+      super::app_paths::app_dir().display()
+    ));
+  }
+  let (source, mut value) = config_source.read()?;
+  apply_profile(&mut value, profile());
+  // the merged config is validated, so the overrides can't add unknown fields
+  for merge_config in merge_configs {
+    // each config's profile overrides its own values, so the later configs still win
+    let mut merge_config = merge_config.clone();
+    apply_profile(&mut merge_config, profile());
+    merge(&mut value, &merge_config);
+  }
+  // the deprecated fields are renamed before the validation, which only knows the new names
+  let deprecations = migrate(&mut value, DEPRECATED_FIELDS);
+  warn_deprecations(&deprecations);
+  *deprecations_handle().lock().unwrap() = deprecations;
+
+  let mut errors = Vec::new();
+  interpolate_env(&mut value, "", &mut errors);
+  apply_feature_gates(&mut value, &mut errors);
+  if !errors.is_empty() {
+    return Err(config_error(&config_source, &source, errors));
+  }
+
+  let mut config = check(&value).map_err(|errors| {
+    let error = config_error(&config_source, &source, errors);
+    if super::migrations::migrate(&mut value.clone()).is_empty() {
+      error
+    } else {
+      anyhow::anyhow!(
+        "{}\nthe config uses an old format; run `tauri migrate` to upgrade it",
+        error
+      )
+    }
+  })?;
+  match resolve_package_version(&config) {
+    Ok(version) => config.package.version = version,
+    Err(error) => {
+      return Err(config_error(
+        &config_source,
+        &source,
+        vec![("package.version".to_string(), error)],
+      ))
+    }
+  }
+  if let (Some(bundle_version), Some(package_version)) =
+    (&config.tauri.bundle.version, &config.package.version)
+  {
+    if bundle_version != package_version {
+      logger.warn(format!(
+        "`tauri.bundle.version` ({}) doesn't match `package.version` ({}); the bundle version is used",
+        bundle_version, package_version
+      ));
+    }
+  }
+
+  if resolve {
+    let config_dir = config_source
+      .path()
+      .parent()
+      .map(Path::to_path_buf)
+      .unwrap_or(config_dir);
+    let mut variables = PathVariables::new(config_dir, super::app_paths::app_dir());
+    let errors = resolve_paths(&mut config, &mut variables);
+    if !errors.is_empty() {
+      return Err(config_error(&config_source, &source, errors));
+    }
+  }
+
+  Ok(config)
+}
+
+/// Applies the `build > profiles` overrides of the profile: its fields override the `build` fields,
+/// and its `security` fields override the `tauri > security` fields.
+/// The profiles are kept, so they're validated like the rest of the config.
+fn apply_profile(value: &mut JsonValue, profile: &str) {
+  let mut overrides = match value.pointer(&format!("/build/profiles/{}", profile)) {
+    Some(JsonValue::Object(overrides)) => overrides.clone(),
+    // the invalid profiles are reported by the validation
+    _ => Default::default(),
+  };
+  let security = overrides.remove("security");
+  if !overrides.is_empty() {
+    merge(&mut value["build"], &JsonValue::Object(overrides));
+  }
+
+  if profile == "debug" {
+    if let Some(dev_proxy) = value.pointer("/build/devProxy").cloned() {
+      merge(
+        value,
+        &serde_json::json!({ "tauri": { "embeddedServer": { "proxy": dev_proxy } } }),
+      );
+    }
+  }
+  if let Some(security) = security {
+    if let Some(JsonValue::Object(tauri)) = value.get_mut("tauri") {
+      merge(
+        tauri.entry("security").or_insert(JsonValue::Null),
+        &security,
+      );
+    } else if value.get("tauri").is_none() {
+      value["tauri"] = serde_json::json!({ "security": security });
+    }
+  }
+}
 
-  let schema: JsonValue = serde_json::from_str(include_str!("../../schema.json"))?;
+/// Checks a config value against the config types and the JSON schema, then the constraints neither can describe,
+/// returning the (path, error) tuples of an invalid config.
+pub fn check(value: &JsonValue) -> Result<Config, Vec<(String, String)>> {
+  let mut value = value.clone();
+  // deserialize first so the errors point to the exact field and suggest the intended field name
+  let config: Config = if is_permissive() {
+    let mut ignored = Vec::new();
+    let config = deserialize_permissive(&mut value, &mut ignored);
+    for path in ignored {
+      Logger::new("tauri:config").warn(format!(
+        "ignoring the unknown field `{}` (permissive config mode)",
+        path
+      ));
+    }
+    config
+  } else {
+    deserialize(&value)
+  }
+  .map_err(|error| vec![error])?;
+  // then check the schema constraints serde doesn't enforce
+  let mut schema: JsonValue = serde_json::from_str(include_str!("../../schema.json"))
+    .expect("failed to parse the config schema");
+  // plugins without a schema keep accepting any value
+  if !config.plugins.is_empty() {
+    match super::manifest::plugin_config_schemas() {
+      Ok(plugin_schemas) => {
+        for (name, plugin_schema) in plugin_schemas {
+          schema["properties"]["plugins"]["properties"][name] = plugin_schema;
+        }
+      }
+      Err(e) => {
+        Logger::new("tauri:config").warn(format!("skipping the plugin config validation: {}", e))
+      }
+    }
+  }
   let mut scope = valico::json_schema::Scope::new();
   let schema = scope.compile_and_return(schema, false).unwrap();
-  let state = schema.validate(&config);
+  let state = schema.validate(&value);
   if !state.errors.is_empty() {
-    for error in state.errors {
-      eprintln!(
-        "`tauri.conf.json` error on `{}`: {}",
-        error
-          .get_path()
-          .chars()
-          .skip(1)
-          .collect::<String>()
-          .replace("/", " > "),
-        error.get_detail().unwrap_or_else(|| error.get_title()),
+    return Err(
+      state
+        .errors
+        .iter()
+        .map(|error| {
+          (
+            pointer_to_path(error.get_path()),
+            error
+              .get_detail()
+              .unwrap_or_else(|| error.get_title())
+              .to_string(),
+          )
+        })
+        .collect(),
+    );
+  }
+
+  let errors = validate(&config);
+  if errors.is_empty() {
+    Ok(config)
+  } else {
+    Err(errors)
+  }
+}
+
+/// Builds the error listing the (path, error) tuples of an invalid config,
+/// with the line and column of each path on the config file when it can be found.
+/// It's returned instead of exiting so `tauri dev` can keep running on invalid intermediate saves.
+fn config_error(
+  config_source: &ConfigSource,
+  source: &str,
+  errors: impl IntoIterator<Item = (String, String)>,
+) -> anyhow::Error {
+  let file_name = config_source.name();
+  // the TOML deserializer knows where the invalid value is, but the unknown keys are found more precisely
+  // than on the table it reports
+  let toml_error = match config_source {
+    ConfigSource::Toml(_) => deserialize_toml(source).err(),
+    _ => None,
+  };
+  anyhow::anyhow!(errors
+    .into_iter()
+    .map(|(path, error)| {
+      let toml_location = toml_error
+        .as_ref()
+        .filter(|(toml_path, _, _)| *toml_path == path && !error.starts_with("unknown field"))
+        .and_then(|(_, _, location)| *location);
+      match toml_location.or_else(|| locate(source, &config_source.file_path(&path))) {
+        Some((line, column)) => format!(
+          "`{}` error on `{}` (line {}, column {}): {}",
+          file_name, path, line, column, error
+        ),
+        None if path.is_empty() => format!("`{}` error: {}", file_name, error),
+        None => format!("`{}` error on `{}`: {}", file_name, path, error),
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n"))
+}
+
+/// Deserializes the config, returning the (path, error) tuple of the first invalid value.
+/// Unknown fields and variants are suggested the closest known name.
+fn deserialize(config: &JsonValue) -> Result<Config, (String, String)> {
+  serde_path_to_error::deserialize(config)
+    .map_err(|error| path_error(error.path().to_string(), error.inner().to_string()))
+}
+
+/// The (path, error, location) tuple of an invalid `tauri.conf.toml` value,
+/// where the location is the 1-based line and column the TOML deserializer reports.
+type TomlError = (String, String, Option<(usize, usize)>);
+
+/// Deserializes a `tauri.conf.toml` file directly, returning the [`TomlError`] of the first invalid value.
+fn deserialize_toml(source: &str) -> Result<Config, TomlError> {
+  serde_path_to_error::deserialize(&mut toml::Deserializer::new(source)).map_err(|error| {
+    let location = error
+      .inner()
+      .line_col()
+      .map(|(line, column)| (line + 1, column + 1));
+    // the key and the location are already on the error path and location
+    let mut message = error.inner().to_string();
+    if let Some(i) = message
+      .rfind(" for key `")
+      .or_else(|| message.rfind(" at line "))
+    {
+      message.truncate(i);
+    }
+    let (path, message) = path_error(error.path().to_string(), message);
+    (path, message, location)
+  })
+}
+
+/// Builds the (path, error) tuple of a deserialization error.
+/// Unknown fields and variants are suggested the closest known name.
+fn path_error(mut path: String, mut message: String) -> (String, String) {
+  if path == "." {
+    path.clear();
+  }
+  if let Some((kind, name, expected)) = parse_unknown(&message) {
+    // the path of an unknown field error points to its parent object
+    if kind == "field" && path.rsplit('.').next() != Some(name) {
+      path = if path.is_empty() {
+        name.to_string()
+      } else {
+        format!("{}.{}", path, name)
+      };
+    }
+    if let Some(suggestion) = suggest(name, &expected) {
+      message.push_str(&format!("; did you mean `{}`?", suggestion));
+    }
+  }
+  (path, message)
+}
+
+/// Deserializes the config removing the unknown fields, which are pushed to `ignored`.
+/// The other errors are returned like [`deserialize`].
+fn deserialize_permissive(
+  config: &mut JsonValue,
+  ignored: &mut Vec<String>,
+) -> Result<Config, (String, String)> {
+  loop {
+    match deserialize(config) {
+      Err((path, message)) if message.starts_with("unknown field") && remove(config, &path) => {
+        ignored.push(path)
+      }
+      result => return result,
+    }
+  }
+}
+
+/// Removes the value at a deserialization error path, e.g. `tauri.windows[1].titel`,
+/// returning whether it was found.
+fn remove(value: &mut JsonValue, path: &str) -> bool {
+  let mut keys = Vec::new();
+  for segment in path.split('.') {
+    let mut parts = segment.split('[');
+    if let Some(key) = parts.next().filter(|key| !key.is_empty()) {
+      keys.push(key);
+    }
+    keys.extend(parts.map(|index| index.trim_end_matches(']')));
+  }
+  let (last, parents) = match keys.split_last() {
+    Some(keys) => keys,
+    None => return false,
+  };
+  let mut value = value;
+  for key in parents {
+    value = match value {
+      JsonValue::Object(object) => match object.get_mut(*key) {
+        Some(child) => child,
+        None => return false,
+      },
+      JsonValue::Array(array) => match key
+        .parse::<usize>()
+        .ok()
+        .and_then(move |i| array.get_mut(i))
+      {
+        Some(child) => child,
+        None => return false,
+      },
+      _ => return false,
+    };
+  }
+  match value {
+    JsonValue::Object(object) => object.remove(*last).is_some(),
+    _ => false,
+  }
+}
+
+/// Parses serde's ``unknown field `name`, expected one of `a`, `b` `` messages (or `unknown variant`),
+/// returning the kind, the unknown name and the expected names.
+fn parse_unknown(message: &str) -> Option<(&str, &str, Vec<&str>)> {
+  let rest = message.strip_prefix("unknown ")?;
+  let kind = rest.split(' ').next()?;
+  let mut quoted = rest.split('`').skip(1).step_by(2);
+  let name = quoted.next()?;
+  Some((kind, name, quoted.collect()))
+}
+
+/// Finds the expected name closest to the unknown one, if it's close enough to be a typo.
+fn suggest<'a>(name: &str, expected: &[&'a str]) -> Option<&'a str> {
+  expected
+    .iter()
+    .map(|candidate| (strsim::osa_distance(name, candidate), *candidate))
+    .filter(|(distance, candidate)| *distance <= 2.max(candidate.len() / 3))
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, candidate)| candidate)
+}
+
+/// Converts a JSON pointer (`/tauri/windows/1`) to the path notation used on the error messages (`tauri.windows[1]`).
+fn pointer_to_path(pointer: &str) -> String {
+  let mut path = String::new();
+  for segment in pointer.split('/').skip(1) {
+    if segment.parse::<usize>().is_ok() {
+      path.push_str(&format!("[{}]", segment));
+    } else {
+      if !path.is_empty() {
+        path.push('.');
+      }
+      path.push_str(segment);
+    }
+  }
+  path
+}
+
+/// Finds the 1-based line and column of a config path on the source file.
+/// Each key is searched after the previous one, so this is a best effort: array indexes are skipped
+/// and values coming from `--config` or environment variables might not be found at all.
+fn locate(source: &str, path: &str) -> Option<(usize, usize)> {
+  let is_key_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+  let mut offset = None;
+  for key in path
+    .split(|c| c == '.' || c == '[')
+    .filter(|key| !key.is_empty() && !key.ends_with(']'))
+  {
+    let start = offset.map(|offset| offset + 1).unwrap_or(0);
+    let position = source[start..].match_indices(key).find_map(|(i, _)| {
+      let i = start + i;
+      let before = source[..i].chars().next_back();
+      let after = source[i + key.len()..].chars().next();
+      if before.map_or(true, |c| !is_key_char(c)) && after.map_or(true, |c| !is_key_char(c)) {
+        Some(i)
+      } else {
+        None
+      }
+    })?;
+    offset = Some(position);
+  }
+  let offset = offset?;
+  let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+  Some((
+    source[..offset].matches('\n').count() + 1,
+    source[line_start..offset].chars().count() + 1,
+  ))
+}
+
+/// Substitutes the `${VAR}` and `${VAR:-default}` environment variables on the config strings, recursively.
+/// Returns a list of (path, error) tuples for the undefined variables.
+fn interpolate_env(value: &mut JsonValue, path: &str, errors: &mut Vec<(String, String)>) {
+  // the app substitutes them when it sends the requests, so the tokens aren't embedded on the binary
+  if path == "tauri.updater.headers" {
+    return;
+  }
+  let child_path = |key: &str| {
+    if path.is_empty() {
+      key.to_string()
+    } else {
+      format!("{}.{}", path, key)
+    }
+  };
+  match value {
+    JsonValue::String(string) => match interpolate(string) {
+      Ok(interpolated) => *string = interpolated,
+      Err(error) => errors.push((path.to_string(), error)),
+    },
+    JsonValue::Array(array) => {
+      for (i, value) in array.iter_mut().enumerate() {
+        interpolate_env(value, &format!("{}[{}]", path, i), errors);
+      }
+    }
+    JsonValue::Object(object) => {
+      for (key, value) in object.iter_mut() {
+        interpolate_env(value, &child_path(key), errors);
+      }
+    }
+    _ => {}
+  }
+}
+
+/// Substitutes the environment variables on a string.
+/// `${VAR:-default}` uses the default if the variable is unset or empty, and `$${VAR}` yields a literal `${VAR}`.
+fn interpolate(value: &str) -> Result<String, String> {
+  let mut interpolated = String::new();
+  let mut rest = value;
+  while let Some(start) = rest.find('$') {
+    interpolated.push_str(&rest[..start]);
+    rest = &rest[start..];
+    if let Some(escaped) = rest.strip_prefix("$${") {
+      interpolated.push_str("${");
+      rest = escaped;
+    } else if let Some(expression) = rest.strip_prefix("${") {
+      let end = expression
+        .find('}')
+        .ok_or_else(|| format!("missing `}}` on `{}`", value))?;
+      let (name, default) = match expression[..end].find(":-") {
+        Some(i) => (&expression[..i], Some(&expression[i + 2..end])),
+        None => (&expression[..end], None),
+      };
+      match (std::env::var(name).ok(), default) {
+        (Some(variable), Some(default)) if variable.is_empty() => interpolated.push_str(default),
+        (Some(variable), _) => interpolated.push_str(&variable),
+        (None, Some(default)) => interpolated.push_str(default),
+        (None, None) => {
+          return Err(format!(
+            "the environment variable `{}` isn't set; set it or use `${{{}:-default}}`",
+            name, name
+          ))
+        }
+      }
+      rest = &expression[end + 1..];
+    } else {
+      interpolated.push('$');
+      rest = &rest[1..];
+    }
+  }
+  interpolated.push_str(rest);
+  Ok(interpolated)
+}
+
+/// The allowlist sections gated as a whole with their `enabledWhen` field.
+/// The other allowlist entries are flags, gated with a `{ "enabledWhen": "<feature>" }` object.
+const GATED_ALLOWLIST_SECTIONS: &[&str] = &["clipboard", "dialog", "process", "power"];
+
+/// Resolves the `enabledWhen` references to the `features` config: the windows with a disabled feature are removed,
+/// the allowlist sections with a disabled feature lose their permissions and the gated allowlist flags are resolved.
+/// Returns a list of (path, error) tuples for the references to undeclared features.
+fn apply_feature_gates(value: &mut JsonValue, errors: &mut Vec<(String, String)>) {
+  // the invalid `features` are reported by the validation
+  let features: HashMap<String, bool> = value
+    .get("features")
+    .and_then(|features| serde_json::from_value(features.clone()).ok())
+    .unwrap_or_default();
+  // whether the entry's feature is enabled, or `None` if it isn't gated
+  let mut enabled = |entry: &JsonValue, path: String| -> Option<bool> {
+    let feature = entry.get("enabledWhen")?.as_str()?;
+    let enabled = features.get(feature).copied();
+    if enabled.is_none() {
+      errors.push((
+        path,
+        format!(
+          "the `{}` feature isn't declared on `features`; declare it, e.g. `\"features\": {{ \"{}\": true }}`",
+          feature, feature
+        ),
+      ));
+    }
+    enabled
+  };
+
+  match value.pointer_mut("/tauri/windows") {
+    Some(JsonValue::Array(windows)) => {
+      let mut i = 0;
+      windows.retain(|window| {
+        let path = format!("tauri.windows[{}].enabledWhen", i);
+        i += 1;
+        enabled(window, path) != Some(false)
+      });
+    }
+    Some(JsonValue::Object(windows)) => {
+      let disabled: Vec<String> = windows
+        .iter()
+        .filter(|(label, window)| {
+          enabled(window, format!("tauri.windows.{}.enabledWhen", label)) == Some(false)
+        })
+        .map(|(label, _)| label.clone())
+        .collect();
+      for label in disabled {
+        windows.remove(&label);
+      }
+    }
+    _ => {}
+  }
+
+  if let Some(JsonValue::Object(allowlist)) = value.pointer_mut("/tauri/allowlist") {
+    for (key, entry) in allowlist.iter_mut() {
+      let path = format!("tauri.allowlist.{}.enabledWhen", key);
+      if GATED_ALLOWLIST_SECTIONS.contains(&key.as_str()) {
+        if enabled(entry, path) == Some(false) {
+          *entry = JsonValue::Object(Default::default());
+          // the message dialogs are allowed unless they're disabled
+          if key == "dialog" {
+            entry["message"] = JsonValue::Bool(false);
+          }
+        }
+      } else if let Some(enabled) = enabled(entry, path) {
+        *entry = JsonValue::Bool(enabled);
+      }
+    }
+  }
+}
+
+/// Validates the config values the JSON schema can't describe.
+/// Returns a list of (path, error) tuples.
+fn validate(config: &Config) -> Vec<(String, String)> {
+  let mut errors = Vec::new();
+  for (i, scheme) in config.tauri.bundle.deep_link.schemes.iter().enumerate() {
+    if !is_valid_scheme(scheme) {
+      errors.push((
+        format!("tauri.bundle.deepLink.schemes[{}]", i),
+        format!(
+          "`{}` is not a valid URL scheme; it must start with a lowercase letter and contain only lowercase letters, digits, `+`, `-` or `.`",
+          scheme
+        ),
+      ));
+    }
+  }
+  if let Some(identifier) = &config.tauri.bundle.identifier {
+    if !is_valid_identifier(identifier) {
+      errors.push((
+        "tauri.bundle.identifier".to_string(),
+        format!(
+          "`{}` is not a valid identifier; it must be in reverse domain notation, contain only alphanumeric characters, `-` or `.`, and at least one `.` separating non-empty segments, e.g. `{}`",
+          identifier, IDENTIFIER_EXAMPLE
+        ),
+      ));
+    }
+  }
+  if let Some(cli) = &config.tauri.cli {
+    validate_cli(cli, "tauri.cli", &mut errors);
+  }
+  let single_instance = config
+    .tauri
+    .single_instance
+    .as_ref()
+    .map(|single_instance| single_instance.is_enabled())
+    .unwrap_or(false);
+  if single_instance && config.tauri.bundle.identifier.is_none() {
+    errors.push((
+      "tauri.singleInstance".to_string(),
+      "the single instance lock is derived from the app identifier, so `tauri.bundle.identifier` must be set"
+        .to_string(),
+    ));
+  }
+  for (i, window) in config.tauri.windows.iter().enumerate() {
+    validate_zoom(
+      &window.zoom,
+      &format!("tauri.windows[{}].zoom", i),
+      &mut errors,
+    );
+  }
+  validate_splashscreen(&config.tauri, &mut errors);
+  validate_paths(&config.tauri.paths, &mut errors);
+  if let Some(files) = &config.tauri.bundle.deb.files {
+    validate_deb_files(files, &mut errors);
+  }
+  validate_http(&config.tauri.allowlist.http, &mut errors);
+  if config.tauri.power.idle_timeout_seconds == Some(0) {
+    errors.push((
+      "tauri.power.idleTimeoutSeconds".to_string(),
+      "the idle timeout must be at least 1 second".to_string(),
+    ));
+  }
+  let has_pubkey =
+    matches!(&config.tauri.updater.pubkey, Some(pubkey) if !pubkey.trim().is_empty());
+  if config.tauri.updater.active && !has_pubkey {
+    errors.push((
+      "tauri.updater.pubkey".to_string(),
+      "the updater requires a public key, since the updates are only installed once their signature is verified"
+        .to_string(),
+    ));
+  }
+  if let Some(proxy) = &config.tauri.updater.proxy {
+    match url::Url::parse(proxy) {
+      Ok(proxy) if proxy.has_host() => {}
+      _ => errors.push((
+        "tauri.updater.proxy".to_string(),
+        format!(
+          "`{}` must be a proxy URL, e.g. `http://proxy.example.com:3128`",
+          proxy
+        ),
+      )),
+    }
+  }
+  for (name, value) in &config.tauri.updater.headers {
+    if !is_valid_header(name, value) {
+      errors.push((
+        format!("tauri.updater.headers.{}", name),
+        format!("`{}: {}` is not a valid header", name, value),
+      ));
+    }
+  }
+  if let Some(version) = &config.tauri.bundle.osx.minimum_system_version {
+    let path = "tauri.bundle.osx.minimumSystemVersion".to_string();
+    match parse_macos_version(version) {
+      None => errors.push((
+        path,
+        format!(
+          "`{}` is not a valid macOS version; it must be `major.minor` or `major.minor.patch`, e.g. `{}`",
+          version, MIN_MACOS_VERSION
+        ),
+      )),
+      Some(parsed) if parsed < parse_macos_version(MIN_MACOS_VERSION).unwrap() => errors.push((
+        path,
+        format!(
+          "`{}` is older than macOS {}, the oldest version the apps support",
+          version, MIN_MACOS_VERSION
+        ),
+      )),
+      Some(_) => {}
+    }
+  }
+  for key in config
+    .build
+    .injected_constants
+    .iter()
+    .flatten()
+    .map(|(key, _)| key)
+  {
+    let path = format!("build.injectedConstants.{}", key);
+    if !is_valid_constant_name(key) {
+      errors.push((
+        path,
+        format!(
+          "`{}` is not a valid constant name; it must start with a letter or `_` and contain only letters, digits or `_`, so it can be an environment variable",
+          key
+        ),
+      ));
+    } else if INJECTED_METADATA.contains(&key.as_str()) {
+      errors.push((
+        path,
+        format!("`{}` is set by Tauri and can't be overridden", key),
+      ));
+    }
+  }
+  // the app creates the default `main` window when none is configured
+  let window_exists = |label: &str| {
+    if config.tauri.windows.is_empty() {
+      return label == "main";
+    }
+    config
+      .tauri
+      .windows
+      .iter()
+      .any(|window| window.label.as_deref().unwrap_or("main") == label)
+  };
+  for (accelerator, action) in &config.tauri.global_shortcuts {
+    let path = format!("tauri.globalShortcuts.{}", accelerator);
+    if let Err(e) = check_accelerator(accelerator) {
+      errors.push((
+        path.clone(),
+        format!(
+          "`{}` is not a valid accelerator: {}; use the `Modifier+Key` syntax, e.g. `CmdOrCtrl+Shift+S`",
+          accelerator, e
+        ),
+      ));
+    }
+    let label = match action {
+      GlobalShortcutAction::ToggleWindow(label) => Some(label),
+      GlobalShortcutAction::Show(label) | GlobalShortcutAction::Hide(label) => label.as_ref(),
+      _ => None,
+    };
+    if let Some(label) = label.filter(|label| !window_exists(label)) {
+      errors.push((
+        path,
+        format!("there's no window with the `{}` label", label),
+      ));
+    }
+  }
+  let remote_access = config
+    .tauri
+    .security
+    .as_ref()
+    .and_then(|security| security.dangerous_remote_domain_ipc_access.as_ref());
+  for (i, scope) in remote_access.into_iter().flatten().enumerate() {
+    let path = format!("tauri.security.dangerousRemoteDomainIpcAccess[{}]", i);
+    if host_name(&scope.domain) != Some(scope.domain.as_str()) {
+      errors.push((
+        format!("{}.domain", path),
+        format!(
+          "`{}` is not a valid domain; it must be a hostname or an IP address, without the scheme",
+          scope.domain
+        ),
+      ));
+    }
+    for (j, label) in scope.windows.iter().enumerate() {
+      let exists = config
+        .tauri
+        .windows
+        .iter()
+        .any(|window| window.label.as_deref().unwrap_or("main") == label);
+      if !exists {
+        errors.push((
+          format!("{}.windows[{}]", path, j),
+          format!("there's no window with the `{}` label", label),
+        ));
+      }
+    }
+  }
+  for (i, pattern) in config.build.dev_watcher.ignore.iter().flatten().enumerate() {
+    if let Err(e) = glob::Pattern::new(pattern.trim_matches('/')) {
+      errors.push((
+        format!("build.devWatcher.ignore[{}]", i),
+        format!("`{}` is not a valid glob pattern: {}", pattern, e),
+      ));
+    }
+  }
+  if let Some(host) = &config.tauri.embedded_server.host {
+    if host_name(host).is_none() {
+      errors.push((
+        "tauri.embeddedServer.host".to_string(),
+        format!(
+          "`{}` is not a valid host; it must be an IP address or a hostname, optionally prefixed with `http://`",
+          host
+        ),
+      ));
+    }
+  }
+  for (i, entry) in config
+    .tauri
+    .embedded_server
+    .headers
+    .iter()
+    .flatten()
+    .enumerate()
+  {
+    let path = format!("tauri.embeddedServer.headers[{}]", i);
+    if let Err(e) = glob::Pattern::new(&entry.pattern) {
+      errors.push((
+        format!("{}.pattern", path),
+        format!("`{}` is not a valid glob pattern: {}", entry.pattern, e),
+      ));
+    }
+    for (name, value) in &entry.headers {
+      if RESERVED_HEADERS
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(name))
+      {
+        errors.push((
+          format!("{}.headers.{}", path, name),
+          format!("the `{}` header is set by the embedded server", name),
+        ));
+      } else if !is_valid_header(name, value) {
+        errors.push((
+          format!("{}.headers.{}", path, name),
+          format!("`{}: {}` is not a valid header", name, value),
+        ));
+      }
+    }
+  }
+  for (i, rule) in config
+    .tauri
+    .embedded_server
+    .proxy
+    .iter()
+    .flatten()
+    .enumerate()
+  {
+    let path = format!("tauri.embeddedServer.proxy[{}]", i);
+    if !rule.path.starts_with('/') || rule.path.contains('?') {
+      errors.push((
+        format!("{}.path", path),
+        format!(
+          "`{}` must be an absolute URL path without a query, e.g. `/api`",
+          rule.path
+        ),
+      ));
+    }
+    match url::Url::parse(&rule.upstream) {
+      Ok(upstream) if matches!(upstream.scheme(), "http" | "https") && upstream.has_host() => {}
+      _ => errors.push((
+        format!("{}.upstream", path),
+        format!(
+          "`{}` must be an `http` or `https` URL, e.g. `http://localhost:3000`",
+          rule.upstream
+        ),
+      )),
+    }
+    for (key, headers) in &[
+      ("requestHeaders", &rule.request_headers),
+      ("responseHeaders", &rule.response_headers),
+    ] {
+      for (name, value) in headers.iter().flatten() {
+        if !is_valid_header(name, value.as_deref().unwrap_or_default()) {
+          errors.push((
+            format!("{}.{}.{}", path, key, name),
+            format!(
+              "`{}: {}` is not a valid header",
+              name,
+              value.as_deref().unwrap_or_default()
+            ),
+          ));
+        }
+      }
+    }
+  }
+  for (name, page) in &[
+    ("notFoundPage", &config.tauri.embedded_server.not_found_page),
+    ("errorPage", &config.tauri.embedded_server.error_page),
+  ] {
+    if let Some(page) = page {
+      if !is_dist_path(page) {
+        errors.push((
+          format!("tauri.embeddedServer.{}", name),
+          format!(
+            "`{}` must be a file path relative to the dist directory, e.g. `404.html`",
+            page
+          ),
+        ));
+      }
+    }
+  }
+  errors
+}
+
+/// Checks that the app directory names are single path components and the migrated identifiers are valid.
+fn validate_paths(paths: &PathsConfig, errors: &mut Vec<(String, String)>) {
+  for (name, dir_name) in &[
+    ("appDataDirName", &paths.app_data_dir_name),
+    ("cacheDirName", &paths.cache_dir_name),
+    ("logDirName", &paths.log_dir_name),
+  ] {
+    if let Some(dir_name) = dir_name {
+      let mut components = Path::new(dir_name).components();
+      let single = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
       );
+      if !single {
+        errors.push((
+          format!("tauri.paths.{}", name),
+          format!(
+            "`{}` must be a directory name, without path separators",
+            dir_name
+          ),
+        ));
+      }
+    }
+  }
+  for (i, identifier) in paths.migrate_from.iter().enumerate() {
+    if !is_valid_identifier(identifier) {
+      errors.push((
+        format!("tauri.paths.migrateFrom[{}]", i),
+        format!("`{}` is not a valid bundle identifier", identifier),
+      ));
     }
-    exit(1);
   }
+}
 
-  if let Some(merge_config) = merge_config {
-    let merge_config: JsonValue = serde_json::from_str(&merge_config)?;
-    merge(&mut config, &merge_config);
+/// Checks that the install paths of the `bundle > deb > files` config are absolute file paths inside the package,
+/// e.g. `/etc/myapp/config.toml`, without `.` or `..` components.
+fn validate_deb_files(files: &HashMap<PathBuf, PathBuf>, errors: &mut Vec<(String, String)>) {
+  for (src, install_path) in files {
+    let mut components = install_path.components();
+    let valid = components.next() == Some(Component::RootDir)
+      && components.clone().next().is_some()
+      && components.all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+      errors.push((
+        format!("tauri.bundle.deb.files.{}", src.display()),
+        format!(
+          "`{}` must be an absolute install path without `.` or `..` components, e.g. `/etc/myapp/config.toml`",
+          install_path.display()
+        ),
+      ));
+    }
   }
+}
 
-  let config = serde_json::from_value(config)?;
-  *config_handle().lock().unwrap() = Some(config);
+/// The methods of the `allowlist > http > defaults > allowedMethods` config.
+const HTTP_METHODS: &[&str] = &[
+  "GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS", "CONNECT", "TRACE",
+];
 
-  Ok(config_handle().clone())
+/// Checks that the http API timeouts aren't zero and the allowed methods are HTTP methods.
+fn validate_http(http: &HttpAllowlistConfig, errors: &mut Vec<(String, String)>) {
+  let defaults = &http.defaults;
+  for (name, timeout) in &[
+    ("connectTimeoutMs", defaults.connect_timeout_ms),
+    ("requestTimeoutMs", defaults.request_timeout_ms),
+  ] {
+    if *timeout == Some(0) {
+      errors.push((
+        format!("tauri.allowlist.http.defaults.{}", name),
+        "the timeout must be at least 1 millisecond".to_string(),
+      ));
+    }
+  }
+  for (i, method) in defaults.allowed_methods.iter().flatten().enumerate() {
+    if !HTTP_METHODS.contains(&method.to_uppercase().as_str()) {
+      errors.push((
+        format!("tauri.allowlist.http.defaults.allowedMethods[{}]", i),
+        format!(
+          "`{}` is not an HTTP method; expected one of: {}",
+          method,
+          HTTP_METHODS.join(", ")
+        ),
+      ));
+    }
+  }
+}
+
+/// Validates that the zoom factors are positive and the default factor is between the min and the max.
+fn validate_zoom(zoom: &ZoomConfig, path: &str, errors: &mut Vec<(String, String)>) {
+  for (name, factor) in &[
+    ("defaultFactor", zoom.default_factor),
+    ("min", zoom.min),
+    ("max", zoom.max),
+  ] {
+    if !factor.is_finite() || *factor <= 0.0 {
+      errors.push((
+        format!("{}.{}", path, name),
+        format!(
+          "the zoom factor must be a positive number, got `{}`",
+          factor
+        ),
+      ));
+    }
+  }
+  if zoom.min > zoom.max {
+    errors.push((
+      format!("{}.min", path),
+      format!(
+        "the min zoom factor `{}` is greater than the max `{}`",
+        zoom.min, zoom.max
+      ),
+    ));
+  } else if zoom.default_factor < zoom.min || zoom.default_factor > zoom.max {
+    errors.push((
+      format!("{}.defaultFactor", path),
+      format!(
+        "the default zoom factor `{}` must be between the min `{}` and the max `{}`",
+        zoom.default_factor, zoom.min, zoom.max
+      ),
+    ));
+  }
+}
+
+/// Whether a path is a file path inside the dist directory.
+fn is_dist_path(path: &str) -> bool {
+  let path = Path::new(path);
+  path.file_name().is_some()
+    && path
+      .components()
+      .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Validates the splashscreen: a single window flagged with `splashscreen` or the `tauri.splashscreen.html`,
+/// shown while the other windows load.
+fn validate_splashscreen(config: &TauriConfig, errors: &mut Vec<(String, String)>) {
+  let label = |window: &WindowConfig| window.label.clone().unwrap_or_else(|| "main".to_string());
+  let mut splashscreen: Option<usize> = None;
+  for (i, window) in config.windows.iter().enumerate() {
+    if !window.splashscreen {
+      continue;
+    }
+    if let Some(first) = splashscreen {
+      errors.push((
+        format!("tauri.windows[{}].splashscreen", i),
+        format!(
+          "only one window can be the splashscreen, and `tauri.windows[{}]` already is",
+          first
+        ),
+      ));
+      continue;
+    }
+    splashscreen = Some(i);
+  }
+  if let Some(options) = &config.splashscreen {
+    match (&splashscreen, &options.html) {
+      (Some(i), Some(_)) => errors.push((
+        "tauri.splashscreen.html".to_string(),
+        format!(
+          "the `tauri.windows[{}]` window is the splashscreen, so the HTML isn't used",
+          i
+        ),
+      )),
+      (None, None) => errors.push((
+        "tauri.splashscreen.html".to_string(),
+        "the splashscreen needs its HTML, unless a window is flagged with `splashscreen`"
+          .to_string(),
+      )),
+      (None, Some(_)) => {
+        if let Some(i) = config
+          .windows
+          .iter()
+          .position(|window| label(window) == SPLASHSCREEN_LABEL)
+        {
+          errors.push((
+            "tauri.splashscreen".to_string(),
+            format!(
+              "the splashscreen window has the `{}` label, which `tauri.windows[{}]` already uses",
+              SPLASHSCREEN_LABEL, i
+            ),
+          ));
+        }
+        splashscreen = Some(config.windows.len());
+      }
+      (Some(_), None) => {}
+    }
+    let timeout = options
+      .timeout_ms
+      .unwrap_or(DEFAULT_SPLASHSCREEN_TIMEOUT_MS);
+    if options.minimum_duration_ms.unwrap_or(0) > timeout {
+      errors.push((
+        "tauri.splashscreen.minimumDurationMs".to_string(),
+        format!(
+          "the splashscreen can't be shown for longer than its timeout (`{}`)",
+          timeout
+        ),
+      ));
+    }
+  }
+  // no windows means the default main window
+  if splashscreen.is_some()
+    && !config.windows.is_empty()
+    && config.windows.iter().all(|window| window.splashscreen)
+  {
+    errors.push((
+      "tauri.windows".to_string(),
+      "the splashscreen is shown while the other windows load, so the app needs another window"
+        .to_string(),
+    ));
+  }
+}
+
+/// Checks the `Modifier+Key` syntax of an accelerator: any unique modifiers followed by a single key.
+fn check_accelerator(accelerator: &str) -> Result<(), String> {
+  let parts: Vec<String> = accelerator
+    .split('+')
+    .map(|part| part.trim().to_lowercase())
+    .collect();
+  let (key, modifiers) = parts.split_last().expect("split yields at least one part");
+  for (i, modifier) in modifiers.iter().enumerate() {
+    if !ACCELERATOR_MODIFIERS.contains(&modifier.as_str()) {
+      return Err(format!("`{}` is not a modifier", modifier));
+    }
+    if modifiers[..i].contains(modifier) {
+      return Err(format!("the `{}` modifier is repeated", modifier));
+    }
+  }
+  let is_function_key = key
+    .strip_prefix('f')
+    .and_then(|number| number.parse::<u8>().ok())
+    .map(|number| (1..=24).contains(&number))
+    .unwrap_or(false);
+  let is_character = key.chars().count() == 1;
+  if is_function_key || is_character || ACCELERATOR_KEYS.contains(&key.as_str()) {
+    Ok(())
+  } else if ACCELERATOR_MODIFIERS.contains(&key.as_str()) {
+    Err("the last part must be a key".to_string())
+  } else {
+    Err(format!("`{}` is not a key", key))
+  }
+}
+
+/// Validates that the CLI arg groups only reference args and groups of their own command, recursively.
+fn validate_cli(cli: &CliConfig, path: &str, errors: &mut Vec<(String, String)>) {
+  let arg_names: Vec<&str> = cli
+    .args()
+    .into_iter()
+    .flatten()
+    .map(|arg| arg.name.as_str())
+    .collect();
+  // the names and aliases of an arg can't be used by another arg
+  let mut long_names = HashMap::new();
+  let mut short_names = HashMap::new();
+  for (i, arg) in cli.args().into_iter().flatten().enumerate() {
+    let long = std::iter::once(&arg.name)
+      .chain(&arg.alias)
+      .chain(arg.aliases.iter().flatten())
+      .chain(arg.visible_aliases.iter().flatten());
+    for name in long {
+      if let Some(other) = long_names.insert(name, &arg.name) {
+        errors.push((
+          format!("{}.args[{}]", path, i),
+          format!("`--{}` is already used by the `{}` arg", name, other),
+        ));
+      }
+    }
+    for short in arg.short.iter().chain(&arg.short_alias) {
+      if let Some(other) = short_names.insert(short, &arg.name) {
+        errors.push((
+          format!("{}.args[{}]", path, i),
+          format!("`-{}` is already used by the `{}` arg", short, other),
+        ));
+      }
+    }
+  }
+
+  // clap panics at runtime on conditions referencing unknown args
+  for (i, arg) in cli.args().into_iter().flatten().enumerate() {
+    let conditions = [
+      ("requiredIfEqAny", &arg.required_if_eq_any),
+      ("requiredIfEqAll", &arg.required_if_eq_all),
+    ];
+    for (field, conditions) in conditions.iter() {
+      for (j, (name, _)) in conditions.iter().flatten().enumerate() {
+        if !arg_names.contains(&name.as_str()) {
+          errors.push((
+            format!("{}.args[{}].{}[{}]", path, i, field, j),
+            format!(
+              "the `{}` arg condition references the unknown arg `{}`",
+              arg.name, name
+            ),
+          ));
+        }
+      }
+    }
+  }
+
+  let groups = cli.groups().map(Vec::as_slice).unwrap_or_default();
+  for (i, group) in groups.iter().enumerate() {
+    if arg_names.contains(&group.name.as_str()) {
+      errors.push((
+        format!("{}.groups[{}].name", path, i),
+        format!("`{}` is already the name of an arg", group.name),
+      ));
+    }
+    for (j, arg) in group.args.iter().flatten().enumerate() {
+      if !arg_names.contains(&arg.as_str()) {
+        errors.push((
+          format!("{}.groups[{}].args[{}]", path, i, j),
+          format!(
+            "the `{}` group references the unknown arg `{}`",
+            group.name, arg
+          ),
+        ));
+      }
+    }
+  }
+  for (i, arg) in cli.args().into_iter().flatten().enumerate() {
+    if let Some(group) = &arg.group {
+      if !groups.iter().any(|g| &g.name == group) {
+        errors.push((
+          format!("{}.args[{}].group", path, i),
+          format!(
+            "the `{}` arg references the unknown group `{}`; declare it on `{}.groups`",
+            arg.name, group, path
+          ),
+        ));
+      }
+    }
+  }
+  for (name, subcommand) in cli.subcommands().into_iter().flatten() {
+    validate_cli(
+      subcommand,
+      &format!("{}.subcommands.{}", path, name),
+      errors,
+    );
+  }
+}
+
+/// Checks that the identifier is set and isn't the template default,
+/// returning the error message if it's missing or unchanged.
+pub fn check_identifier(config: &Config) -> Option<String> {
+  match config.tauri.bundle.identifier.as_deref() {
+    None => Some(format!(
+      "`tauri.bundle.identifier` is missing; set it to a unique identifier for your app, e.g. `{}`",
+      IDENTIFIER_EXAMPLE
+    )),
+    Some(DEFAULT_IDENTIFIER) => Some(format!(
+      "`tauri.bundle.identifier` is the default `{}`; change it to a unique identifier for your app, e.g. `{}`",
+      DEFAULT_IDENTIFIER, IDENTIFIER_EXAMPLE
+    )),
+    Some(_) => None,
+  }
+}
+
+/// The `MACOSX_DEPLOYMENT_TARGET` the app is compiled with: the `tauri.bundle.osx.minimumSystemVersion`,
+/// so the binary can't use symbols newer than the version the Info.plist declares.
+/// Returns the error message if `env_target`, the environment variable value, is another version.
+pub fn deployment_target(
+  config: &Config,
+  env_target: Option<&str>,
+) -> Result<Option<String>, String> {
+  let version = match &config.tauri.bundle.osx.minimum_system_version {
+    Some(version) => version,
+    None => return Ok(None),
+  };
+  match env_target {
+    Some(target) if parse_macos_version(target) != parse_macos_version(version) => Err(format!(
+      "`MACOSX_DEPLOYMENT_TARGET` is `{}`, but `tauri.bundle.osx.minimumSystemVersion` is `{}`; unset the environment variable or make them match",
+      target, version
+    )),
+    _ => Ok(Some(version.clone())),
+  }
+}
+
+/// Warns about the `build.injectedConstants` that look like secrets, since every page script can read them.
+pub fn check_injected_constants(config: &Config) -> Vec<String> {
+  let mut keys: Vec<&String> = config
+    .build
+    .injected_constants
+    .iter()
+    .flatten()
+    .map(|(key, _)| key)
+    .filter(|key| crate::print_config::is_secret(key))
+    .collect();
+  keys.sort();
+  keys
+    .into_iter()
+    .map(|key| {
+      format!(
+        "`build.injectedConstants.{}` looks like a secret; the injected constants are readable by every page script, so keep secrets on the Rust side",
+        key
+      )
+    })
+    .collect()
+}
+
+/// The embedded server port of a `tauri dev` session: the `TAURI_EMBEDDED_SERVER_PORT` already set,
+/// the configured port, or a port available now for `port: "random"`.
+/// `tauri dev` exports it, so the before dev command, the app and `tauri config print --resolved` agree on it.
+pub fn embedded_server_port(config: &Config) -> Option<u16> {
+  if let Some(port) = std::env::var(EMBEDDED_SERVER_PORT_ENV)
+    .ok()
+    .and_then(|port| port.parse().ok())
+  {
+    return Some(port);
+  }
+  match config.tauri.embedded_server.port {
+    Some(Port::Value(port)) => Some(port),
+    // `random` is the default
+    _ => TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+      .and_then(|listener| listener.local_addr())
+      .map(|address| address.port())
+      .ok(),
+  }
 }
 
-pub fn get(merge_config: Option<&str>) -> crate::Result<ConfigHandle> {
-  get_internal(merge_config, false)
+/// The environment variables of the before dev and before build commands:
+/// the `build.injectedConstants`, with the strings as is and the other values as JSON, and the app metadata.
+pub fn injected_constants_env(config: &Config, debug: bool) -> Vec<(String, String)> {
+  let mut env: Vec<(String, String)> = config
+    .build
+    .injected_constants
+    .iter()
+    .flatten()
+    .map(|(key, value)| {
+      let value = match value {
+        JsonValue::String(value) => value.clone(),
+        value => value.to_string(),
+      };
+      (key.clone(), value)
+    })
+    .collect();
+  env.sort();
+  let metadata = [
+    config.package.version.clone().unwrap_or_default(),
+    config.package.product_name.clone().unwrap_or_default(),
+    debug.to_string(),
+  ];
+  env.extend(
+    INJECTED_METADATA
+      .iter()
+      .map(ToString::to_string)
+      .zip(metadata.iter().cloned()),
+  );
+  env
 }
 
-pub fn reload(merge_config: Option<&str>) -> crate::Result<()> {
-  get_internal(merge_config, true)?;
+/// Checks that the embedded server only binds to a loopback address,
+/// unless `tauri.embeddedServer.dangerousAllowRemoteHost` is set,
+/// returning the error message if it's exposed to the network.
+pub fn check_remote_host(config: &Config) -> Option<String> {
+  let embedded_server = &config.tauri.embedded_server;
+  let host = embedded_server.host.as_deref()?;
+  match host_name(host) {
+    Some(name) if !is_loopback_host(name) && !embedded_server.dangerous_allow_remote_host => {
+      Some(format!(
+        "`tauri.embeddedServer.host` is `{}`, which exposes the app assets to the network; use a loopback address such as `127.0.0.1`, or set `tauri.embeddedServer.dangerousAllowRemoteHost` to `true`",
+        host
+      ))
+    }
+    _ => None,
+  }
+}
+
+/// Resolves `package.version`, reading it from the `Cargo.toml` or `package.json` file it points to.
+/// The error message names the file the invalid version comes from.
+fn resolve_package_version(config: &Config) -> Result<Option<String>, String> {
+  let version = match &config.package.version {
+    Some(version) => version,
+    None => return Ok(None),
+  };
+  let path = Path::new(version);
+  let (version, source) = match path.file_name().and_then(|name| name.to_str()) {
+    Some("Cargo.toml") | Some("package.json") => {
+      let path = super::app_paths::config_dir().join(path);
+      (read_version_file(&path)?, path.display().to_string())
+    }
+    _ => (version.clone(), config_source().name()),
+  };
+  check_version(&version, &source)?;
+  Ok(Some(version))
+}
+
+/// Reads the version field of a `Cargo.toml` or `package.json` file.
+fn read_version_file(path: &Path) -> Result<String, String> {
+  let contents =
+    read_to_string(path).map_err(|e| format!("failed to read `{}`: {}", path.display(), e))?;
+  let version = if path.file_name().and_then(|name| name.to_str()) == Some("Cargo.toml") {
+    let manifest = contents
+      .parse::<Document>()
+      .map_err(|e| format!("failed to parse `{}`: {}", path.display(), e))?;
+    manifest
+      .as_table()
+      .get("package")
+      .and_then(Item::as_table)
+      .and_then(|package| package.get("version"))
+      .and_then(Item::as_str)
+      .map(ToString::to_string)
+  } else {
+    serde_json::from_str::<JsonValue>(&contents)
+      .map_err(|e| format!("failed to parse `{}`: {}", path.display(), e))?["version"]
+      .as_str()
+      .map(ToString::to_string)
+  };
+  version.ok_or_else(|| format!("`{}` doesn't have a version field", path.display()))
+}
+
+/// Checks that the version read from `source` is a semver version.
+fn check_version(version: &str, source: &str) -> Result<(), String> {
+  Version::parse(version).map(|_| ()).map_err(|e| {
+    format!(
+      "`{}` from `{}` is not a valid semver version: {}",
+      version, source, e
+    )
+  })
+}
+
+/// The host name of the embedded server host, e.g. `127.0.0.1` for `http://127.0.0.1`,
+/// or `None` if it isn't an IP address or a hostname.
+fn host_name(host: &str) -> Option<&str> {
+  let name = host
+    .strip_prefix("http://")
+    .or_else(|| host.strip_prefix("https://"))
+    .unwrap_or(host);
+  let is_ipv6 = name
+    .strip_prefix('[')
+    .and_then(|name| name.strip_suffix(']'))
+    .map_or(false, |name| name.parse::<Ipv6Addr>().is_ok());
+  // the top-level label can't be numeric, so invalid IPv4 addresses aren't hostnames
+  let is_hostname = name.split('.').all(|label| {
+    !label.is_empty()
+      && !label.starts_with('-')
+      && !label.ends_with('-')
+      && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+  }) && !name
+    .rsplit('.')
+    .next()
+    .map_or(true, |label| label.chars().all(|c| c.is_ascii_digit()));
+  if is_ipv6 || name.parse::<Ipv4Addr>().is_ok() || is_hostname {
+    Some(name)
+  } else {
+    None
+  }
+}
+
+/// Whether the host name is `localhost` or a loopback IP address.
+fn is_loopback_host(name: &str) -> bool {
+  let name = name.trim_start_matches('[').trim_end_matches(']');
+  name.eq_ignore_ascii_case("localhost")
+    || name
+      .parse::<IpAddr>()
+      .map_or(false, |address| address.is_loopback())
+}
+
+/// Whether the header name is an HTTP token and the value only has visible ASCII characters and spaces.
+fn is_valid_header(name: &str, value: &str) -> bool {
+  !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+    && value.chars().all(|c| c == ' ' || c.is_ascii_graphic())
+}
+
+/// Whether the constant name is also a portable environment variable name.
+fn is_valid_constant_name(name: &str) -> bool {
+  let mut chars = name.chars();
+  matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether the scheme is a lowercase RFC 3986 URL scheme, without the `:` separator.
+fn is_valid_scheme(scheme: &str) -> bool {
+  let mut chars = scheme.chars();
+  matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+    && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Parses a `major.minor[.patch]` macOS version, with the patch defaulting to `0`.
+fn parse_macos_version(version: &str) -> Option<(u64, u64, u64)> {
+  let mut parts = version.split('.').map(|part| {
+    if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+      part.parse::<u64>().ok()
+    } else {
+      None
+    }
+  });
+  let major = parts.next()??;
+  let minor = parts.next()??;
+  let patch = parts.next().unwrap_or(Some(0))?;
+  if parts.next().is_some() {
+    return None;
+  }
+  Some((major, minor, patch))
+}
+
+/// Whether the identifier is in reverse domain notation, e.g. `com.mycompany.myapp`.
+fn is_valid_identifier(identifier: &str) -> bool {
+  identifier.contains('.')
+    && identifier.split('.').all(|segment| {
+      !segment.is_empty()
+        && segment
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Parses the `--config` values, each one being inline JSON or the path to a JSON file.
+/// Later values win over the previous ones when merged.
+pub fn parse_merge_configs(values: &[String]) -> crate::Result<Vec<JsonValue>> {
+  values
+    .iter()
+    .map(|value| {
+      let (json, source) = if value.trim_start().starts_with('{') {
+        (value.clone(), "inline JSON".to_string())
+      } else {
+        let json = read_to_string(value)
+          .map_err(|e| anyhow::anyhow!("failed to read the config file `{}`: {}", value, e))?;
+        (json, format!("`{}`", value))
+      };
+      serde_json::from_str(&json)
+        .map_err(|e| anyhow::anyhow!("failed to parse the {} config: {}", source, e))
+    })
+    .collect()
+}
+
+/// Gets the config without resolving its path-like fields, as written on the config file.
+pub fn get_unresolved(merge_configs: &[JsonValue]) -> crate::Result<Config> {
+  load(merge_configs, false)
+}
+
+pub fn get(merge_configs: &[JsonValue]) -> crate::Result<ConfigHandle> {
+  get_internal(merge_configs, false)
+}
+
+pub fn reload(merge_configs: &[JsonValue]) -> crate::Result<()> {
+  get_internal(merge_configs, true)?;
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use super::{
+    apply_feature_gates, check_identifier, check_version, config_error, config_sources,
+    deployment_target, deserialize, deserialize_permissive, deserialize_toml, embedded_server_port,
+    interpolate_env, is_valid_identifier, locate, merge, parse_config, parse_merge_configs,
+    read_version_file, validate, BundleTarget, BundleTargetKind, Config, ConfigFormat,
+    ConfigSource, EMBEDDED_SERVER_PORT_ENV,
+  };
+  use std::{net::Ipv4Addr, path::PathBuf};
+
+  #[test]
+  fn env_interpolation() {
+    std::env::set_var("TAURI_TEST_APP_FLAVOR", "nightly");
+    std::env::set_var("TAURI_TEST_EMPTY", "");
+    std::env::remove_var("TAURI_TEST_UNSET");
+
+    let mut value = serde_json::json!({
+      "build": {
+        "devPath": "http://localhost:${TAURI_TEST_UNSET:-8080}",
+        "beforeDevCommand": "echo $${TAURI_TEST_APP_FLAVOR} $HOME"
+      },
+      "tauri": {
+        "bundle": { "identifier": "com.corp.${TAURI_TEST_APP_FLAVOR}" },
+        "windows": [{ "title": "${TAURI_TEST_EMPTY:-app}", "width": 800 }],
+        "updater": { "headers": { "Authorization": "${TAURI_TEST_UNSET}" } }
+      }
+    });
+    let mut errors = Vec::new();
+    interpolate_env(&mut value, "", &mut errors);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+      value,
+      serde_json::json!({
+        "build": {
+          "devPath": "http://localhost:8080",
+          "beforeDevCommand": "echo ${TAURI_TEST_APP_FLAVOR} $HOME"
+        },
+        "tauri": {
+          "bundle": { "identifier": "com.corp.nightly" },
+          "windows": [{ "title": "app", "width": 800 }],
+          // substituted by the app
+          "updater": { "headers": { "Authorization": "${TAURI_TEST_UNSET}" } }
+        }
+      })
+    );
+
+    let mut value = serde_json::json!({
+      "tauri": { "windows": [{ "title": "${TAURI_TEST_UNSET}" }, { "url": "${TAURI_TEST_EMPTY" }] }
+    });
+    let mut errors = Vec::new();
+    interpolate_env(&mut value, "", &mut errors);
+    assert_eq!(
+      errors,
+      vec![
+        (
+          "tauri.windows[0].title".to_string(),
+          "the environment variable `TAURI_TEST_UNSET` isn't set; set it or use `${TAURI_TEST_UNSET:-default}`".to_string()
+        ),
+        (
+          "tauri.windows[1].url".to_string(),
+          "missing `}` on `${TAURI_TEST_EMPTY`".to_string()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn merge_config_flags() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("ci.conf.json");
+    std::fs::write(
+      &file,
+      r#"{ "tauri": { "bundle": { "identifier": "com.corp.ci", "active": true } } }"#,
+    )
+    .unwrap();
+    let merge_configs = parse_merge_configs(&[
+      file.display().to_string(),
+      r#" {"tauri":{"bundle":{"identifier":"com.corp.nightly"}}}"#.to_string(),
+    ])
+    .unwrap();
+
+    // the later configs win
+    let mut value = serde_json::json!({ "tauri": { "bundle": { "identifier": "com.corp.app" } } });
+    for merge_config in &merge_configs {
+      merge(&mut value, merge_config);
+    }
+    let config = deserialize(&value).unwrap();
+    assert_eq!(
+      config.tauri.bundle.identifier.as_deref(),
+      Some("com.corp.nightly")
+    );
+    assert!(config.tauri.bundle.active);
+
+    // the overrides are validated like the main config
+    let unknown = parse_merge_configs(&[r#"{ "tauri": { "bundel": {} } }"#.to_string()]).unwrap();
+    merge(&mut value, &unknown[0]);
+    assert_eq!(deserialize(&value).unwrap_err().0, "tauri.bundel");
+
+    let missing = dir.path().join("missing.conf.json").display().to_string();
+    let error = parse_merge_configs(&[missing.clone()]).unwrap_err();
+    assert!(error
+      .to_string()
+      .starts_with(&format!("failed to read the config file `{}`", missing)));
+    let error = parse_merge_configs(&["{ invalid".to_string()]).unwrap_err();
+    assert!(error
+      .to_string()
+      .starts_with("failed to parse the inline JSON config"));
+  }
+
+  #[test]
+  // the sources are sorted by precedence and `package.json` needs a `tauri` key
+  fn config_source_precedence() {
+    let app_dir = std::env::temp_dir().join(format!("tauri-config-sources-{}", std::process::id()));
+    let tauri_dir = app_dir.join("src-tauri");
+    std::fs::create_dir_all(&tauri_dir).unwrap();
+    let names = |tauri_dir: &std::path::Path| {
+      config_sources(tauri_dir)
+        .iter()
+        .map(ConfigSource::name)
+        .collect::<Vec<_>>()
+    };
+
+    std::fs::write(app_dir.join("package.json"), r#"{ "name": "app" }"#).unwrap();
+    std::fs::write(tauri_dir.join(".taurirc.json"), "{}").unwrap();
+    assert_eq!(names(&tauri_dir), vec![".taurirc.json"]);
+
+    std::fs::write(
+      app_dir.join("package.json"),
+      r#"{ "name": "app", "tauri": {} }"#,
+    )
+    .unwrap();
+    std::fs::write(tauri_dir.join("tauri.conf.toml"), "").unwrap();
+    std::fs::write(tauri_dir.join("tauri.conf.json"), "{}").unwrap();
+    assert_eq!(
+      names(&tauri_dir),
+      vec![
+        "tauri.conf.json",
+        "tauri.conf.toml",
+        "package.json#tauri",
+        ".taurirc.json"
+      ]
+    );
+
+    std::fs::remove_dir_all(&app_dir).unwrap();
+  }
+
+  #[test]
+  fn clipboard_allowlist() {
+    let config: Config = serde_json::from_value(
+      parse_config(
+        r#"{ "tauri": { "allowlist": { "readTextFile": true, "clipboard": { "writeText": true } } } }"#,
+        ConfigFormat::Json,
+      )
+      .unwrap(),
+    )
+    .unwrap();
+    let allowlist = &config.tauri.allowlist;
+    assert!(!allowlist.all());
+    assert_eq!(allowlist.features.get("readTextFile"), Some(&true));
+    assert!(!allowlist.features.contains_key("clipboard"));
+    assert!(allowlist.clipboard.write_text);
+    assert!(!allowlist.clipboard.read_text);
+  }
+
+  #[test]
+  fn dev_embedded_server_port() {
+    let config = |port: &str| -> Config {
+      serde_json::from_value(
+        parse_config(
+          &format!(
+            r#"{{ "tauri": {{ "embeddedServer": {{ "port": {} }} }} }}"#,
+            port
+          ),
+          ConfigFormat::Json,
+        )
+        .unwrap(),
+      )
+      .unwrap()
+    };
+    std::env::remove_var(EMBEDDED_SERVER_PORT_ENV);
+    assert_eq!(embedded_server_port(&config("4000")), Some(4000));
+    // the default random port is available
+    let port = embedded_server_port(&config("null")).unwrap();
+    assert!(std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok());
+
+    // the port of the running session wins
+    std::env::set_var(EMBEDDED_SERVER_PORT_ENV, "5000");
+    assert_eq!(embedded_server_port(&config("4000")), Some(5000));
+    std::env::set_var(EMBEDDED_SERVER_PORT_ENV, "not a port");
+    assert_eq!(embedded_server_port(&config("4000")), Some(4000));
+    std::env::remove_var(EMBEDDED_SERVER_PORT_ENV);
+  }
+
+  #[test]
+  // an equivalent JSON and TOML document produce the same config
+  fn json_and_toml_configs_match() {
+    let json = r#"{
+      "package": { "productName": "app", "version": "0.1.0" },
+      "build": { "distDir": "../dist", "devPath": "http://localhost:8080" },
+      "tauri": {
+        "bundle": {
+          "active": true,
+          "identifier": "com.mycompany.app",
+          "targets": ["deb", "msi"],
+          "deb": { "depends": ["libssl"] }
+        },
+        "allowlist": { "all": true },
+        "windows": [{ "title": "app", "width": 800, "height": 600 }]
+      }
+    }"#;
+    let toml = r#"
+      [package]
+      productName = "app"
+      version = "0.1.0"
+
+      [build]
+      distDir = "../dist"
+      devPath = "http://localhost:8080"
+
+      [tauri.bundle]
+      active = true
+      identifier = "com.mycompany.app"
+      targets = ["deb", "msi"]
+
+      [tauri.bundle.deb]
+      depends = ["libssl"]
+
+      [tauri.allowlist]
+      all = true
+
+      [[tauri.windows]]
+      title = "app"
+      width = 800
+      height = 600
+    "#;
+
+    let json_config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let toml_config: Config =
+      serde_json::from_value(parse_config(toml, ConfigFormat::Toml).unwrap()).unwrap();
+    assert_eq!(json_config, toml_config);
+  }
+
+  #[test]
+  // the JSON config can have comments, trailing commas and unquoted keys
+  fn json5_config_matches_json() {
+    let json =
+      r#"{ "tauri": { "bundle": { "active": true, "identifier": "com.mycompany.app" } } }"#;
+    let json5 = r#"{
+      // the bundle config
+      tauri: {
+        bundle: {
+          active: true,
+          /* reverse domain */
+          identifier: "com.mycompany.app",
+        },
+      },
+    }"#;
+
+    let json_config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let json5_config: Config =
+      serde_json::from_value(parse_config(json5, ConfigFormat::Json).unwrap()).unwrap();
+    assert_eq!(json_config, json5_config);
+
+    let unknown_field = r#"{
+      // typo
+      tauri: { bundle: { identifer: "com.mycompany.app" } },
+    }"#;
+    let config = parse_config(unknown_field, ConfigFormat::Json).unwrap();
+    assert!(serde_json::from_value::<Config>(config).is_err());
+  }
+
+  #[test]
+  // the plugin configs can be any JSON value, and object configs deserialize as before
+  fn plugin_config_values() {
+    let json = r#"{
+      "plugins": {
+        "object": { "enabled": true },
+        "string": "~/.cache",
+        "array": ["assets", "docs"],
+        "number": 3,
+        "null": null
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let plugins = config.plugins;
+    assert_eq!(plugins["object"], serde_json::json!({ "enabled": true }));
+    assert_eq!(plugins["string"], serde_json::json!("~/.cache"));
+    assert_eq!(plugins["array"], serde_json::json!(["assets", "docs"]));
+    assert_eq!(plugins["number"], serde_json::json!(3));
+    assert_eq!(plugins["null"], serde_json::Value::Null);
+  }
+
+  #[test]
+  // the permissive mode ignores the unknown fields, but not the type errors
+  fn permissive_config() {
+    let mut value = serde_json::json!({
+      "tauri": {
+        "newFeature": true,
+        "windows": [{ "title": "main" }, { "title": "settings", "newWindowOption": 1 }]
+      }
+    });
+    let mut ignored = Vec::new();
+    let config = deserialize_permissive(&mut value, &mut ignored).unwrap();
+    assert_eq!(
+      ignored,
+      vec!["tauri.newFeature", "tauri.windows[1].newWindowOption"]
+    );
+    assert_eq!(config.tauri.windows.len(), 2);
+
+    let mut value =
+      serde_json::json!({ "tauri": { "newFeature": true, "windows": [{ "width": "wide" }] } });
+    let mut ignored = Vec::new();
+    let (path, _) = deserialize_permissive(&mut value, &mut ignored).unwrap_err();
+    assert_eq!(path, "tauri.windows[0].width");
+  }
+
+  #[test]
+  // unknown fields are rejected on TOML too
+  fn toml_unknown_fields_fail() {
+    let toml = r#"
+      [tauri.bundle]
+      identifer = "com.mycompany.app"
+    "#;
+    let config = parse_config(toml, ConfigFormat::Toml).unwrap();
+    assert!(serde_json::from_value::<Config>(config).is_err());
+  }
+
+  #[test]
+  // the TOML files are deserialized directly, so the invalid values are located by the TOML deserializer
+  fn toml_error_has_location() {
+    let toml = "[tauri.bundle]\nidentifier = \"com.mycompany.app\"\nactive = 3\n";
+    let (path, error, location) = deserialize_toml(toml).unwrap_err();
+    assert_eq!(path, "tauri.bundle.active");
+    assert_eq!(error, "invalid type: integer `3`, expected a boolean");
+    assert_eq!(location, Some((3, 10)));
+
+    let source = ConfigSource::Toml(PathBuf::from("tauri.conf.toml"));
+    assert_eq!(
+      config_error(&source, toml, vec![(path, error)]).to_string(),
+      "`tauri.conf.toml` error on `tauri.bundle.active` (line 3, column 10): invalid type: integer `3`, expected a boolean"
+    );
+
+    // the unknown keys are located on their own line rather than on their table
+    let toml = "[tauri.bundle]\nidentifer = \"com.mycompany.app\"\n";
+    let (path, error, _) = deserialize_toml(toml).unwrap_err();
+    assert_eq!(path, "tauri.bundle.identifer");
+    assert!(error.ends_with("did you mean `identifier`?"));
+    assert_eq!(
+      config_error(&source, toml, vec![(path, error.clone())]).to_string(),
+      format!(
+        "`tauri.conf.toml` error on `tauri.bundle.identifer` (line 2, column 1): {}",
+        error
+      )
+    );
+  }
+
+  #[test]
+  // unknown fields report their full path, location and the closest known field
+  fn unknown_field_error_has_path_and_suggestion() {
+    let json = r#"{
+      "tauri": {
+        "windows": [
+          { "title": "main" },
+          { "titel": "settings" }
+        ]
+      }
+    }"#;
+    let config = parse_config(json, ConfigFormat::Json).unwrap();
+    let (path, error) = deserialize(&config).unwrap_err();
+    assert_eq!(path, "tauri.windows[1].titel");
+    assert!(error.ends_with("did you mean `title`?"));
+    assert_eq!(locate(json, &path), Some((5, 14)));
+  }
+
+  #[test]
+  // the windows map keys are the labels, and the unknown fields report their path under the key
+  fn windows_map() {
+    let json = r#"{
+      "tauri": {
+        "windows": { "settings": { "title": "Settings" }, "main": { "title": "App" } }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let labels: Vec<Option<&str>> = config
+      .tauri
+      .windows
+      .iter()
+      .map(|window| window.label.as_deref())
+      .collect();
+    assert_eq!(labels, vec![Some("main"), Some("settings")]);
+
+    let json = r#"{ "tauri": { "windows": { "main": { "label": "main" } } } }"#;
+    let config = parse_config(json, ConfigFormat::Json).unwrap();
+    assert!(serde_json::from_value::<Config>(config).is_err());
+
+    let json = r#"{ "tauri": { "windows": { "main": { "titel": "App" } } } }"#;
+    let config = parse_config(json, ConfigFormat::Json).unwrap();
+    let (path, _) = deserialize(&config).unwrap_err();
+    assert_eq!(path, "tauri.windows.main.titel");
+  }
+
+  #[test]
+  // the CLI arg groups and conditions can't reference unknown args or groups
+  fn cli_groups_reference_known_args() {
+    let json = r#"{
+      "tauri": {
+        "cli": {
+          "args": [
+            { "name": "verbose" },
+            { "name": "quiet", "group": "output", "requiredIfEqAny": [["verbose", "1"], ["level", "1"]] },
+            { "name": "debug", "group": "logging" }
+          ],
+          "groups": [{ "name": "output", "args": ["verbose", "silent"] }]
+        }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.cli.args[1].requiredIfEqAny[1]",
+        "tauri.cli.groups[0].args[1]",
+        "tauri.cli.args[2].group"
+      ]
+    );
+  }
+
+  #[test]
+  // the deb files are installed to absolute paths inside the package
+  fn deb_install_paths() {
+    let json = r#"{
+      "tauri": {
+        "bundle": {
+          "active": true,
+          "deb": {
+            "files": {
+              "config.toml": "/etc/app/config.toml",
+              "relative.toml": "etc/app/relative.toml",
+              "parent.toml": "/usr/../../etc/parent.toml",
+              "root.toml": "/"
+            }
+          }
+        }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let mut paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    paths.sort();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.bundle.deb.files.parent.toml",
+        "tauri.bundle.deb.files.relative.toml",
+        "tauri.bundle.deb.files.root.toml"
+      ]
+    );
+  }
+
+  #[test]
+  // an arg can't use the name or alias of another arg of the same command
+  fn cli_alias_collisions_fail() {
+    let json = r#"{
+      "tauri": {
+        "cli": {
+          "args": [
+            { "name": "output", "short": "o", "alias": "out" },
+            { "name": "overwrite", "shortAlias": "o", "visibleAliases": ["out"] }
+          ],
+          "subcommands": {
+            "other": { "args": [{ "name": "output", "alias": "out" }] }
+          }
+        }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let errors: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(_, error)| error)
+      .collect();
+    assert_eq!(
+      errors,
+      vec![
+        "`--out` is already used by the `output` arg",
+        "`-o` is already used by the `output` arg"
+      ]
+    );
+  }
+
+  #[test]
+  // a single splashscreen, which has the HTML or a flagged window
+  fn splashscreen_is_validated() {
+    let validate_json = |json: &str| -> Vec<String> {
+      let config: Config =
+        serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+      validate(&config)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+    };
+    assert!(validate_json(
+      r#"{ "tauri": { "splashscreen": { "html": "<h1>Loading</h1>", "minimumDurationMs": 500 } } }"#
+    )
+    .is_empty());
+    assert_eq!(
+      validate_json(
+        r#"{
+          "tauri": {
+            "windows": [
+              { "label": "splash", "splashscreen": true },
+              { "label": "main" },
+              { "label": "loading", "splashscreen": true }
+            ],
+            "splashscreen": { "html": "<h1>Loading</h1>", "minimumDurationMs": 20000 }
+          }
+        }"#
+      ),
+      vec![
+        "tauri.windows[2].splashscreen",
+        "tauri.splashscreen.html",
+        "tauri.splashscreen.minimumDurationMs",
+      ]
+    );
+    assert_eq!(
+      validate_json(r#"{ "tauri": { "splashscreen": {} } }"#),
+      vec!["tauri.splashscreen.html"]
+    );
+    assert_eq!(
+      validate_json(r#"{ "tauri": { "windows": [{ "splashscreen": true }] } }"#),
+      vec!["tauri.windows"]
+    );
+  }
+
+  #[test]
+  // the app directory names are single components and the migrated identifiers are valid
+  fn paths_are_validated() {
+    let config: Config = serde_json::from_value(serde_json::json!({
+      "tauri": {
+        "paths": {
+          "appDataDirName": "My App",
+          "cacheDirName": "../cache",
+          "logDirName": "logs/my-app",
+          "migrateFrom": ["com.tauri.old", "old"]
+        }
+      }
+    }))
+    .unwrap();
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.paths.cacheDirName",
+        "tauri.paths.logDirName",
+        "tauri.paths.migrateFrom[1]",
+      ]
+    );
+  }
+
+  #[test]
+  // the http API timeouts can't be zero and the allowed methods must be HTTP methods
+  fn http_defaults_are_validated() {
+    let config: Config = serde_json::from_value(serde_json::json!({
+      "tauri": {
+        "allowlist": {
+          "httpRequest": true,
+          "http": {
+            "scope": ["https://api.example.com/*"],
+            "defaults": {
+              "connectTimeoutMs": 0,
+              "requestTimeoutMs": 30000,
+              "maxRedirects": 0,
+              "allowedMethods": ["get", "POST", "FETCH"]
+            }
+          }
+        }
+      }
+    }))
+    .unwrap();
+    assert!(config.tauri.allowlist.features["httpRequest"]);
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.allowlist.http.defaults.connectTimeoutMs",
+        "tauri.allowlist.http.defaults.allowedMethods[2]",
+      ]
+    );
+  }
+
+  #[test]
+  // the zoom factors must be positive and the default factor between the min and the max
+  fn window_zoom_is_validated() {
+    let config: Config = serde_json::from_value(serde_json::json!({
+      "tauri": {
+        "windows": [
+          { "label": "main", "zoom": { "hotkeysEnabled": true } },
+          { "label": "reader", "zoom": { "defaultFactor": 3, "max": 2 } },
+          { "label": "inverted", "zoom": { "min": 0, "max": -1 } }
+        ]
+      }
+    }))
+    .unwrap();
+    assert_eq!(config.tauri.windows[0].zoom.default_factor, 1.0);
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.windows[1].zoom.defaultFactor",
+        "tauri.windows[2].zoom.min",
+        "tauri.windows[2].zoom.max",
+        "tauri.windows[2].zoom.min",
+      ]
+    );
+  }
+
+  #[test]
+  fn updater_requires_pubkey() {
+    let errors = |updater: serde_json::Value| -> Vec<String> {
+      let config: Config =
+        serde_json::from_value(serde_json::json!({ "tauri": { "updater": updater } })).unwrap();
+      validate(&config)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+    };
+    assert_eq!(
+      errors(serde_json::json!({ "active": true })),
+      vec!["tauri.updater.pubkey"]
+    );
+    assert_eq!(
+      errors(serde_json::json!({ "active": true, "pubkey": " " })),
+      vec!["tauri.updater.pubkey"]
+    );
+    assert!(errors(serde_json::json!({ "active": true, "pubkey": "cHVia2V5" })).is_empty());
+    assert!(errors(serde_json::json!({ "active": false })).is_empty());
+  }
+
+  #[test]
+  // the minimum macOS version must be `major.minor[.patch]` and not older than the floor
+  fn minimum_system_version_is_validated() {
+    let errors = |version: &str| {
+      let config: Config = serde_json::from_value(serde_json::json!({
+        "tauri": { "bundle": { "active": true, "osx": { "minimumSystemVersion": version } } }
+      }))
+      .unwrap();
+      validate(&config).len()
+    };
+    assert_eq!(errors("10.15"), 0);
+    assert_eq!(errors("11.0.1"), 0);
+    assert_eq!(errors("10,13"), 1);
+    assert_eq!(errors("11"), 1);
+    assert_eq!(errors("10.15.1.2"), 1);
+    assert_eq!(errors("10.9"), 1);
+
+    let config: Config = serde_json::from_value(serde_json::json!({
+      "tauri": { "bundle": { "active": true, "osx": { "minimumSystemVersion": "10.15" } } }
+    }))
+    .unwrap();
+    assert_eq!(deployment_target(&config, None), Ok(Some("10.15".into())));
+    assert_eq!(
+      deployment_target(&config, Some("10.15.0")),
+      Ok(Some("10.15".into()))
+    );
+    assert!(deployment_target(&config, Some("11.0")).is_err());
+  }
+
+  #[test]
+  // the disabled features remove their windows and permissions, and the undeclared ones are rejected
+  fn feature_gates_are_applied() {
+    let mut value = serde_json::json!({
+      "features": { "beta": false, "internal": true },
+      "tauri": {
+        "windows": [
+          { "label": "main" },
+          { "label": "beta", "enabledWhen": "beta" },
+          { "label": "debug", "enabledWhen": "internal" }
+        ],
+        "allowlist": {
+          "readTextFile": { "enabledWhen": "internal" },
+          "writeFile": { "enabledWhen": "beta" },
+          "dialog": { "open": true, "enabledWhen": "beta" },
+          "process": { "exit": true, "enabledWhen": "internal" }
+        }
+      }
+    });
+    let mut errors = Vec::new();
+    apply_feature_gates(&mut value, &mut errors);
+    assert!(errors.is_empty());
+    let config: Config = serde_json::from_value(value).unwrap();
+    let labels: Vec<_> = config
+      .tauri
+      .windows
+      .iter()
+      .map(|window| window.label.clone().unwrap())
+      .collect();
+    assert_eq!(labels, vec!["main", "debug"]);
+    let allowlist = &config.tauri.allowlist;
+    assert_eq!(allowlist.features.get("readTextFile"), Some(&true));
+    assert_eq!(allowlist.features.get("writeFile"), Some(&false));
+    assert!(!allowlist.dialog.open);
+    assert_eq!(allowlist.dialog.message, Some(false));
+    assert!(allowlist.process.exit);
+
+    let mut value = serde_json::json!({
+      "tauri": { "windows": { "main": { "enabledWhen": "beta" } } }
+    });
+    let mut errors = Vec::new();
+    apply_feature_gates(&mut value, &mut errors);
+    let paths: Vec<_> = errors.into_iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, vec!["tauri.windows.main.enabledWhen"]);
+  }
+
+  #[test]
+  // the remote domains must be plain domains and reference known windows
+  fn remote_domain_ipc_access_is_validated() {
+    let json = r#"{
+      "tauri": {
+        "windows": [{ "label": "main" }, { "label": "partner", "url": "https://partner.example.com" }],
+        "security": {
+          "dangerousRemoteDomainIpcAccess": [
+            { "domain": "partner.example.com", "windows": ["partner"], "commands": ["sync"] },
+            { "domain": "https://partner.example.com", "windows": ["settings"] }
+          ]
+        }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.security.dangerousRemoteDomainIpcAccess[1].domain",
+        "tauri.security.dangerousRemoteDomainIpcAccess[1].windows[0]"
+      ]
+    );
+  }
+
+  #[test]
+  // the server controlled headers and invalid patterns are rejected
+  fn embedded_server_headers_are_validated() {
+    let json = r#"{
+      "tauri": {
+        "embeddedServer": {
+          "headers": [
+            { "pattern": "assets/*", "headers": { "Cache-Control": "immutable" } },
+            { "pattern": "[", "headers": { "content-length": "0" } }
+          ]
+        }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.embeddedServer.headers[1].pattern",
+        "tauri.embeddedServer.headers[1].headers.content-length"
+      ]
+    );
+  }
+
+  #[test]
+  // the error pages must be inside the dist directory
+  fn embedded_server_pages_are_validated() {
+    let json = r#"{
+      "tauri": {
+        "embeddedServer": { "notFoundPage": "errors/404.html", "errorPage": "../500.html" }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(paths, vec!["tauri.embeddedServer.errorPage"]);
+  }
+
+  #[test]
+  fn accelerators() {
+    for accelerator in &[
+      "CmdOrCtrl+Shift+S",
+      "F5",
+      "Alt+Plus",
+      "ctrl + ,",
+      "Super+PageDown",
+    ] {
+      assert_eq!(
+        super::check_accelerator(accelerator),
+        Ok(()),
+        "{}",
+        accelerator
+      );
+    }
+    for accelerator in &[
+      "",
+      "CmdOrCtrl+",
+      "Shift+Shift+A",
+      "Hyper+A",
+      "Ctrl+Shift",
+      "Ctrl+F25",
+      "Ctrl+AB",
+    ] {
+      assert!(
+        super::check_accelerator(accelerator).is_err(),
+        "{}",
+        accelerator
+      );
+    }
+  }
+
+  #[test]
+  fn global_shortcuts_are_validated() {
+    let json = r#"{
+      "tauri": {
+        "globalShortcuts": { "CmdOrCtrl+Shift+Space": "toggleWindow:main", "Ctrl+Hyper": "quit", "F9": "show:tools" }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let mut paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    paths.sort();
+    assert_eq!(
+      paths,
+      vec![
+        "tauri.globalShortcuts.Ctrl+Hyper",
+        "tauri.globalShortcuts.F9"
+      ]
+    );
+
+    let json = r#"{ "tauri": { "globalShortcuts": { "F9": "toggle" } } }"#;
+    assert!(
+      serde_json::from_value::<Config>(parse_config(json, ConfigFormat::Json).unwrap()).is_err()
+    );
+  }
+
+  #[test]
+  fn bundle_targets() {
+    let target = |value: serde_json::Value| serde_json::from_value::<BundleTarget>(value);
+    assert_eq!(target(serde_json::json!("all")).unwrap().to_vec(), None);
+    assert_eq!(target(serde_json::json!("ALL")).unwrap().to_vec(), None);
+    assert_eq!(
+      target(serde_json::json!("deb")).unwrap().to_vec(),
+      Some(vec![BundleTargetKind::Deb])
+    );
+    // the names are case insensitive
+    assert_eq!(
+      target(serde_json::json!(["AppImage", "msi"]))
+        .unwrap()
+        .to_vec(),
+      Some(vec![BundleTargetKind::AppImage, BundleTargetKind::Msi])
+    );
+    assert!(target(serde_json::json!("exe"))
+      .unwrap_err()
+      .to_string()
+      .starts_with("unknown bundle target `exe`, expected one of `deb`, `appimage`"));
+    assert!(target(serde_json::json!(["deb", 1])).is_err());
+    assert!(target(serde_json::json!(true)).is_err());
+
+    assert_eq!(
+      "NSIS".parse::<BundleTargetKind>(),
+      Ok(BundleTargetKind::Nsis)
+    );
+    assert_eq!(
+      serde_json::to_value(BundleTarget::List(vec![BundleTargetKind::App])).unwrap(),
+      serde_json::json!(["app"])
+    );
+  }
+
+  #[test]
+  fn bundle_identifier() {
+    for identifier in &[
+      "com.mycompany.myapp",
+      "com.my-company.app2",
+      "io.github.user",
+    ] {
+      assert!(is_valid_identifier(identifier), "{}", identifier);
+    }
+    for identifier in &[
+      "myapp",
+      "com..app",
+      ".com.app",
+      "com.app.",
+      "com.my_app",
+      "com.my app",
+    ] {
+      assert!(!is_valid_identifier(identifier), "{}", identifier);
+    }
+
+    let config = |json: &str| -> Config {
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap()
+    };
+    assert!(check_identifier(&config("{}"))
+      .unwrap()
+      .contains("is missing"));
+    assert!(check_identifier(&config(
+      r#"{ "tauri": { "bundle": { "active": true, "identifier": "com.tauri.dev" } } }"#
+    ))
+    .unwrap()
+    .contains("is the default"));
+    assert_eq!(
+      check_identifier(&config(
+        r#"{ "tauri": { "bundle": { "active": true, "identifier": "com.mycompany.myapp" } } }"#
+      )),
+      None
+    );
+    let errors: Vec<String> = validate(&config(
+      r#"{ "tauri": { "bundle": { "active": true, "identifier": "myapp" } } }"#,
+    ))
+    .into_iter()
+    .map(|(path, _)| path)
+    .collect();
+    assert_eq!(errors, vec!["tauri.bundle.identifier"]);
+  }
+
+  #[test]
+  fn package_version_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let cargo_toml = dir.path().join("Cargo.toml");
+    std::fs::write(
+      &cargo_toml,
+      "[package]\nname = \"app\"\nversion = \"1.2.3\"\n",
+    )
+    .unwrap();
+    assert_eq!(read_version_file(&cargo_toml).unwrap(), "1.2.3");
+    let package_json = dir.path().join("package.json");
+    std::fs::write(
+      &package_json,
+      r#"{ "name": "app", "version": "0.4.0-beta.1" }"#,
+    )
+    .unwrap();
+    assert_eq!(read_version_file(&package_json).unwrap(), "0.4.0-beta.1");
+
+    std::fs::write(&package_json, r#"{ "name": "app" }"#).unwrap();
+    assert!(read_version_file(&package_json)
+      .unwrap_err()
+      .contains("doesn't have a version field"));
+    assert!(read_version_file(&dir.path().join("missing").join("Cargo.toml")).is_err());
+
+    assert!(check_version("1.2.3", "tauri.conf.json").is_ok());
+    assert!(check_version("1.2", "Cargo.toml")
+      .unwrap_err()
+      .contains("`1.2` from `Cargo.toml` is not a valid semver version"));
+  }
+
+  #[test]
+  fn single_instance_requires_identifier() {
+    for (json, paths) in &[
+      (
+        r#"{ "tauri": { "singleInstance": true } }"#,
+        vec!["tauri.singleInstance"],
+      ),
+      (
+        r#"{ "tauri": { "singleInstance": { "focusExisting": false } } }"#,
+        vec!["tauri.singleInstance"],
+      ),
+      (r#"{ "tauri": { "singleInstance": false } }"#, vec![]),
+      (
+        r#"{
+          "tauri": {
+            "singleInstance": true,
+            "bundle": { "active": true, "identifier": "com.tauri.app" }
+          }
+        }"#,
+        vec![],
+      ),
+    ] {
+      let config: Config =
+        serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+      let errors: Vec<String> = validate(&config)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+      assert_eq!(&errors, paths);
+    }
+  }
+
+  #[test]
+  fn embedded_server_host() {
+    for (host, loopback) in &[
+      ("http://127.0.0.1", Some(true)),
+      ("localhost", Some(true)),
+      ("http://[::1]", Some(true)),
+      ("http://0.0.0.0", Some(false)),
+      ("192.168.1.10", Some(false)),
+      ("http://my-app.local", Some(false)),
+      ("http://", None),
+      ("127.0.0.1:8080", None),
+      ("http://-invalid", None),
+      ("999.0.0.1", None),
+    ] {
+      assert_eq!(
+        super::host_name(host).map(super::is_loopback_host),
+        *loopback,
+        "{}",
+        host
+      );
+    }
+  }
+
+  #[test]
+  fn build_profiles() {
+    let json = r#"{
+      "build": {
+        "distDir": "../dist",
+        "withGlobalTauri": true,
+        "profiles": {
+          "debug": { "distDir": "../dist-debug", "security": { "csp": "default-src *" } },
+          "release": { "withGlobalTauri": false, "security": { "freezePrototype": true } }
+        }
+      },
+      "tauri": { "security": { "csp": "default-src 'self'" } }
+    }"#;
+    let value = parse_config(json, ConfigFormat::Json).unwrap();
+
+    let mut release = value.clone();
+    super::apply_profile(&mut release, "release");
+    assert_eq!(release["build"]["distDir"], "../dist");
+    assert_eq!(release["build"]["withGlobalTauri"], false);
+    assert_eq!(release["tauri"]["security"]["freezePrototype"], true);
+    assert_eq!(release["tauri"]["security"]["csp"], "default-src 'self'");
+    assert!(serde_json::from_value::<Config>(release).is_ok());
+
+    let mut debug = value;
+    super::apply_profile(&mut debug, "debug");
+    assert_eq!(debug["build"]["distDir"], "../dist-debug");
+    assert_eq!(debug["build"]["withGlobalTauri"], true);
+    assert_eq!(debug["tauri"]["security"]["csp"], "default-src *");
+    let config: Config = serde_json::from_value(debug).unwrap();
+    assert_eq!(config.build.dist_dir, "../dist-debug");
+    assert!(serde_json::to_value(&config).unwrap()["build"]
+      .get("profiles")
+      .is_none());
+  }
+
+  #[test]
+  fn injected_constants() {
+    let json = r#"{
+      "package": { "productName": "app", "version": "1.2.0" },
+      "build": {
+        "injectedConstants": {
+          "API_URL": "https://example.com",
+          "FEATURES": { "beta": true },
+          "API_TOKEN": "token",
+          "TAURI_DEBUG": false,
+          "api-url": ""
+        }
+      }
+    }"#;
+    let config: Config =
+      serde_json::from_value(parse_config(json, ConfigFormat::Json).unwrap()).unwrap();
+    let mut paths: Vec<String> = validate(&config)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    paths.sort();
+    assert_eq!(
+      paths,
+      vec![
+        "build.injectedConstants.TAURI_DEBUG",
+        "build.injectedConstants.api-url"
+      ]
+    );
+    let warnings = super::check_injected_constants(&config);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("API_TOKEN"));
+
+    let env = super::injected_constants_env(&config, true);
+    let value = |name: &str| {
+      env
+        .iter()
+        .rev()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+    };
+    assert_eq!(value("API_URL"), Some("https://example.com"));
+    assert_eq!(value("FEATURES"), Some(r#"{"beta":true}"#));
+    assert_eq!(value("TAURI_APP_VERSION"), Some("1.2.0"));
+    assert_eq!(value("TAURI_APP_NAME"), Some("app"));
+    assert_eq!(value("TAURI_DEBUG"), Some("true"));
+  }
+
+  #[test]
+  fn global_tauri_overrides() {
+    let config = |value: serde_json::Value| deserialize(&value).unwrap();
+    let windows =
+      |windows: serde_json::Value| serde_json::json!({ "tauri": { "windows": windows } });
+
+    let default = config(windows(serde_json::json!([{ "label": "main" }])));
+    assert_eq!(default.tauri.windows[0].with_global_tauri, None);
+    assert_eq!(default.tauri.windows[0].enable_tauri_api, None);
+    assert!(!default.bundles_global_tauri());
+
+    // a single window enables the bundle
+    let enabled = config(windows(serde_json::json!([
+      { "label": "main" },
+      { "label": "settings", "withGlobalTauri": true, "enableTauriApi": true }
+    ])));
+    assert_eq!(enabled.tauri.windows[1].with_global_tauri, Some(true));
+    assert_eq!(enabled.tauri.windows[1].enable_tauri_api, Some(true));
+    assert!(enabled.bundles_global_tauri());
+
+    // with `build > withGlobalTauri` the bundle is injected, and skipped at runtime on the windows disabling it
+    let mut disabled = config(windows(serde_json::json!([
+      { "label": "main", "withGlobalTauri": false, "enableTauriApi": false }
+    ])));
+    assert!(!disabled.bundles_global_tauri());
+    disabled.build.with_global_tauri = true;
+    assert!(disabled.bundles_global_tauri());
+
+    assert!(
+      deserialize(&windows(serde_json::json!([{ "withGlobalTauri": "yes" }])))
+        .unwrap_err()
+        .0
+        .ends_with("withGlobalTauri")
+    );
+  }
+}
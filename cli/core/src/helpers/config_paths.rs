@@ -0,0 +1,366 @@
+use super::config::{AppUrl, BundleResources, Config, WebviewInstallMode};
+
+use std::{
+  collections::HashMap,
+  env,
+  path::{Path, PathBuf},
+};
+
+/// The variables a path-like config field can start with, e.g. `$PROJECT_ROOT/assets/icon.png`.
+pub const PATH_VARIABLES: &[&str] = &["CONFIG_DIR", "PROJECT_ROOT", "DIST_DIR", "HOME", "TEMP"];
+
+/// The directories the path variables expand to.
+pub struct PathVariables {
+  /// The directory of the config file, which the relative paths are resolved from.
+  config_dir: PathBuf,
+  /// The project root, the parent of `src-tauri`.
+  project_root: PathBuf,
+  /// The resolved `build > distDir`, unset while it's being resolved.
+  dist_dir: Option<PathBuf>,
+  home: Option<PathBuf>,
+  temp: PathBuf,
+}
+
+impl PathVariables {
+  pub fn new(config_dir: impl Into<PathBuf>, project_root: impl Into<PathBuf>) -> Self {
+    Self {
+      config_dir: config_dir.into(),
+      project_root: project_root.into(),
+      dist_dir: None,
+      home: home_dir(),
+      temp: env::temp_dir(),
+    }
+  }
+
+  /// Expands the variable the path starts with, if any.
+  fn expand(&self, path: &str) -> Result<String, String> {
+    let rest = match path.strip_prefix('$') {
+      Some(rest) => rest,
+      None => return Ok(path.to_string()),
+    };
+    let end = rest
+      .find(|c: char| c == '/' || c == '\\')
+      .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(end);
+    let directory = match name {
+      "CONFIG_DIR" => &self.config_dir,
+      "PROJECT_ROOT" => &self.project_root,
+      "DIST_DIR" => self
+        .dist_dir
+        .as_ref()
+        .ok_or_else(|| "`$DIST_DIR` is unknown: `build.distDir` isn't resolved".to_string())?,
+      "HOME" => self
+        .home
+        .as_ref()
+        .ok_or_else(|| "`$HOME` is unknown: the home directory isn't set".to_string())?,
+      "TEMP" => &self.temp,
+      _ => {
+        return Err(format!(
+          "unknown path variable `${}`; expected one of {}",
+          name,
+          PATH_VARIABLES
+            .iter()
+            .map(|variable| format!("`${}`", variable))
+            .collect::<Vec<_>>()
+            .join(", ")
+        ))
+      }
+    };
+    Ok(format!("{}{}", directory.display(), rest))
+  }
+
+  /// Expands the variable the path starts with and resolves a relative path from the config directory.
+  fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+    Ok(self.config_dir.join(self.expand(path)?))
+  }
+}
+
+fn home_dir() -> Option<PathBuf> {
+  let variable = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+  env::var_os(variable)
+    .filter(|home| !home.is_empty())
+    .map(PathBuf::from)
+}
+
+/// Resolves the path-like config fields in place, collecting the (path, error) tuples of the invalid ones.
+struct Resolver<'a> {
+  variables: &'a PathVariables,
+  errors: Vec<(String, String)>,
+}
+
+impl Resolver<'_> {
+  fn string(&mut self, value: &mut String, field: &str) {
+    match self.variables.resolve(value) {
+      Ok(path) => *value = path.display().to_string(),
+      Err(error) => self.errors.push((field.to_string(), error)),
+    }
+  }
+
+  fn path(&mut self, value: &mut PathBuf, field: &str) {
+    // non UTF-8 paths can't have variables
+    if let Some(path) = value.to_str() {
+      match self.variables.resolve(path) {
+        Ok(path) => *value = path,
+        Err(error) => self.errors.push((field.to_string(), error)),
+      }
+    }
+  }
+
+  fn optional_path(&mut self, value: &mut Option<PathBuf>, field: &str) {
+    if let Some(value) = value {
+      self.path(value, field);
+    }
+  }
+
+  fn paths(&mut self, values: &mut Option<Vec<PathBuf>>, field: &str) {
+    for (i, value) in values.iter_mut().flatten().enumerate() {
+      self.path(value, &format!("{}[{}]", field, i));
+    }
+  }
+
+  fn strings(&mut self, values: &mut Option<Vec<String>>, field: &str) {
+    for (i, value) in values.iter_mut().flatten().enumerate() {
+      self.string(value, &format!("{}[{}]", field, i));
+    }
+  }
+
+  /// Resolves the source paths of a `{ source: destination }` map, keeping the destinations.
+  fn sources(&mut self, files: &mut Option<HashMap<PathBuf, PathBuf>>, field: &str) {
+    if let Some(files) = files {
+      *files = files
+        .drain()
+        .map(|(mut source, destination)| {
+          let key = format!("{}.{}", field, source.display());
+          self.path(&mut source, &key);
+          (source, destination)
+        })
+        .collect();
+    }
+  }
+}
+
+/// Whether an `osx > frameworks` entry is a path, rather than a framework name looked up on the system.
+fn is_framework_path(framework: &str) -> bool {
+  framework.starts_with('$')
+    || framework.contains('/')
+    || framework.contains('\\')
+    || Path::new(framework).extension().map_or(false, |extension| {
+      extension == "framework" || extension == "dylib"
+    })
+}
+
+/// Resolves the path-like config fields in a single pass:
+/// the [`PATH_VARIABLES`] they start with are expanded and the relative paths are resolved from the config directory.
+/// `build > distDir` is resolved first, so the other fields can use `$DIST_DIR`.
+///
+/// The `bundle > resources` list keeps its relative paths, since they're also the resource paths on the bundle.
+///
+/// Returns the (path, error) tuples of the fields with unknown variables.
+pub fn resolve_paths(config: &mut Config, variables: &mut PathVariables) -> Vec<(String, String)> {
+  let mut dist_dir_errors = Vec::new();
+  match variables.resolve(&config.build.dist_dir) {
+    Ok(dist_dir) => {
+      config.build.dist_dir = dist_dir.display().to_string();
+      variables.dist_dir = Some(dist_dir);
+    }
+    Err(error) => dist_dir_errors.push(("build.distDir".to_string(), error)),
+  }
+
+  let mut resolver = Resolver {
+    variables,
+    errors: dist_dir_errors,
+  };
+  match &mut config.build.dev_path {
+    AppUrl::Url(_) => {}
+    AppUrl::Path(path) => resolver.path(path, "build.devPath"),
+    AppUrl::Files(files) => {
+      for (i, file) in files.iter_mut().enumerate() {
+        resolver.path(file, &format!("build.devPath[{}]", i));
+      }
+    }
+  }
+
+  let bundle = &mut config.tauri.bundle;
+  resolver.strings(&mut bundle.icon, "tauri.bundle.icon");
+  match &mut bundle.resources {
+    Some(BundleResources::List(resources)) => {
+      for (i, resource) in resources.iter_mut().enumerate() {
+        match resolver.variables.expand(resource) {
+          Ok(expanded) => *resource = expanded,
+          Err(error) => resolver
+            .errors
+            .push((format!("tauri.bundle.resources[{}]", i), error)),
+        }
+      }
+    }
+    Some(BundleResources::Map(resources)) => {
+      *resources = resources
+        .drain()
+        .map(|(mut source, destination)| {
+          let field = format!("tauri.bundle.resources.{}", source);
+          resolver.string(&mut source, &field);
+          (source, destination)
+        })
+        .collect();
+    }
+    None => {}
+  }
+  resolver.optional_path(&mut bundle.license_file, "tauri.bundle.licenseFile");
+  resolver.optional_path(&mut bundle.script, "tauri.bundle.script");
+  resolver.optional_path(&mut bundle.output_dir, "tauri.bundle.outputDir");
+  resolver.strings(&mut bundle.external_bin, "tauri.bundle.externalBin");
+  for (i, association) in bundle.file_associations.iter_mut().flatten().enumerate() {
+    if let Some(icon) = &mut association.icon {
+      resolver.string(icon, &format!("tauri.bundle.fileAssociations[{}].icon", i));
+    }
+  }
+
+  let deb = &mut bundle.deb;
+  resolver.sources(&mut deb.files, "tauri.bundle.deb.files");
+  resolver.optional_path(&mut deb.changelog, "tauri.bundle.deb.changelog");
+  resolver.optional_path(
+    &mut deb.desktop_template,
+    "tauri.bundle.deb.desktopTemplate",
+  );
+  let scripts = &mut deb.scripts;
+  resolver.optional_path(&mut scripts.preinst, "tauri.bundle.deb.scripts.preinst");
+  resolver.optional_path(&mut scripts.postinst, "tauri.bundle.deb.scripts.postinst");
+  resolver.optional_path(&mut scripts.prerm, "tauri.bundle.deb.scripts.prerm");
+  resolver.optional_path(&mut scripts.postrm, "tauri.bundle.deb.scripts.postrm");
+
+  let osx = &mut bundle.osx;
+  for (i, framework) in osx.frameworks.iter_mut().flatten().enumerate() {
+    if is_framework_path(framework) {
+      resolver.string(framework, &format!("tauri.bundle.osx.frameworks[{}]", i));
+    }
+  }
+  if let Some(license) = &mut osx.license {
+    resolver.string(license, "tauri.bundle.osx.license");
+  }
+  resolver.optional_path(&mut osx.entitlements, "tauri.bundle.osx.entitlements");
+
+  resolver.sources(&mut bundle.rpm.files, "tauri.bundle.rpm.files");
+  resolver.optional_path(
+    &mut bundle.rpm.desktop_template,
+    "tauri.bundle.rpm.desktopTemplate",
+  );
+  resolver.sources(&mut bundle.appimage.files, "tauri.bundle.appimage.files");
+  resolver.optional_path(&mut bundle.dmg.background, "tauri.bundle.dmg.background");
+
+  match &mut bundle.windows.webview_install_mode {
+    Some(WebviewInstallMode::OfflineInstaller { path })
+    | Some(WebviewInstallMode::FixedRuntime { path }) => {
+      resolver.path(path, "tauri.bundle.windows.webviewInstallMode.path")
+    }
+    _ => {}
+  }
+  resolver.paths(
+    &mut bundle.windows.wix_fragment_paths,
+    "tauri.bundle.windows.wixFragmentPaths",
+  );
+
+  let nsis = &mut bundle.nsis;
+  resolver.optional_path(&mut nsis.license, "tauri.bundle.nsis.license");
+  resolver.optional_path(&mut nsis.header_image, "tauri.bundle.nsis.headerImage");
+  resolver.optional_path(&mut nsis.sidebar_image, "tauri.bundle.nsis.sidebarImage");
+
+  resolver.errors
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{resolve_paths, PathVariables};
+  use crate::helpers::config::{AppUrl, BundleResources, Config};
+
+  use std::path::PathBuf;
+
+  fn config(value: serde_json::Value) -> Config {
+    serde_json::from_value(value).unwrap()
+  }
+
+  #[test]
+  fn resolves_variables_and_relative_paths() {
+    let mut config = config(serde_json::json!({
+      "build": { "distDir": "../dist", "devPath": "$PROJECT_ROOT/public" },
+      "tauri": {
+        "bundle": {
+          "active": true,
+          "icon": ["icons/icon.png", "$DIST_DIR/favicon.ico"],
+          "resources": ["assets/*", "$TEMP/generated.json"],
+          "licenseFile": "$CONFIG_DIR/LICENSE",
+          "deb": { "files": { "default.toml": "/etc/app/default.toml" } },
+          "osx": { "frameworks": ["Sparkle", "libs/libfoo.dylib"] }
+        }
+      }
+    }));
+    let mut variables = PathVariables::new("/app/src-tauri", "/app");
+    assert!(resolve_paths(&mut config, &mut variables).is_empty());
+
+    let config_dir = PathBuf::from("/app/src-tauri");
+    assert_eq!(
+      config.build.dist_dir,
+      config_dir.join("../dist").display().to_string()
+    );
+    assert_eq!(
+      config.build.dev_path,
+      AppUrl::Path(PathBuf::from("/app/public"))
+    );
+    assert_eq!(
+      config.tauri.bundle.icon,
+      Some(vec![
+        config_dir.join("icons/icon.png").display().to_string(),
+        format!("{}/favicon.ico", config_dir.join("../dist").display()),
+      ])
+    );
+    assert_eq!(
+      config.tauri.bundle.resources,
+      Some(BundleResources::List(vec![
+        "assets/*".to_string(),
+        format!("{}/generated.json", std::env::temp_dir().display()),
+      ]))
+    );
+    assert_eq!(
+      config.tauri.bundle.license_file,
+      Some(PathBuf::from("/app/src-tauri/LICENSE"))
+    );
+    let files = config.tauri.bundle.deb.files.unwrap();
+    assert_eq!(
+      files.get(&config_dir.join("default.toml")),
+      Some(&PathBuf::from("/etc/app/default.toml"))
+    );
+    assert_eq!(
+      config.tauri.bundle.osx.frameworks,
+      Some(vec![
+        "Sparkle".to_string(),
+        config_dir.join("libs/libfoo.dylib").display().to_string()
+      ])
+    );
+  }
+
+  #[test]
+  fn unknown_variables_are_errors() {
+    let mut config = config(serde_json::json!({
+      "build": { "distDir": "$DIST_DIR/out" },
+      "tauri": {
+        "bundle": {
+          "active": true,
+          "icon": ["icons/icon.png", "$ICONS/icon.icns"],
+          "dmg": { "background": "$ASSETS/background.png" }
+        }
+      }
+    }));
+    let mut variables = PathVariables::new("/app/src-tauri", "/app");
+    let errors: Vec<String> = resolve_paths(&mut config, &mut variables)
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect();
+    assert_eq!(
+      errors,
+      vec![
+        "build.distDir",
+        "tauri.bundle.icon[1]",
+        "tauri.bundle.dmg.background"
+      ]
+    );
+  }
+}
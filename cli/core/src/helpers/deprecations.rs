@@ -0,0 +1,154 @@
+use serde_json::Value as JsonValue;
+
+use std::fmt;
+
+/// A renamed config field, still accepted under its old name until it's removed.
+pub struct DeprecatedField {
+  /// The dot separated path of the object holding the field, e.g. `tauri.bundle`.
+  /// A `[]` suffix matches each element of an array, e.g. `tauri.windows[]`.
+  pub parent: &'static str,
+  /// The old field name.
+  pub old: &'static str,
+  /// The new field name.
+  pub new: &'static str,
+  /// The version where the old field name is removed.
+  pub removed_in: &'static str,
+}
+
+/// The renamed config fields.
+///
+/// Renaming a field also needs a `#[serde(alias = "<old>")]` attribute on the tauri-utils config field,
+/// since the app reads its config file without the CLI.
+pub const DEPRECATED_FIELDS: &[DeprecatedField] = &[];
+
+/// A deprecated field used on the config.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Deprecation {
+  /// The path of the deprecated field, e.g. `tauri.bundle.osx`.
+  pub path: String,
+  /// The path of the field replacing it.
+  pub replacement: String,
+  /// The version where the deprecated field is removed.
+  pub removed_in: &'static str,
+}
+
+impl fmt::Display for Deprecation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "`{}` is deprecated and will be removed in {}; use `{}` instead",
+      self.path, self.removed_in, self.replacement
+    )
+  }
+}
+
+/// Renames the deprecated fields of the config value, returning a notice for each one found.
+/// If both names are set, the new one wins.
+pub fn migrate(value: &mut JsonValue, fields: &[DeprecatedField]) -> Vec<Deprecation> {
+  let mut deprecations = Vec::new();
+  for field in fields {
+    let keys: Vec<&str> = field.parent.split('.').filter(|k| !k.is_empty()).collect();
+    rename(value, &keys, String::new(), field, &mut deprecations);
+  }
+  deprecations
+}
+
+fn rename(
+  value: &mut JsonValue,
+  keys: &[&str],
+  path: String,
+  field: &DeprecatedField,
+  deprecations: &mut Vec<Deprecation>,
+) {
+  let object = match value {
+    JsonValue::Object(object) => object,
+    _ => return,
+  };
+  match keys.split_first() {
+    None => {
+      if let Some(old_value) = object.remove(field.old) {
+        if !object.contains_key(field.new) {
+          object.insert(field.new.to_string(), old_value);
+        }
+        deprecations.push(Deprecation {
+          path: join(&path, field.old),
+          replacement: join(&path, field.new),
+          removed_in: field.removed_in,
+        });
+      }
+    }
+    Some((key, keys)) => match key.strip_suffix("[]") {
+      Some(key) => {
+        if let Some(JsonValue::Array(array)) = object.get_mut(key) {
+          for (i, element) in array.iter_mut().enumerate() {
+            let path = format!("{}[{}]", join(&path, key), i);
+            rename(element, keys, path, field, deprecations);
+          }
+        }
+      }
+      None => {
+        if let Some(child) = object.get_mut(*key) {
+          rename(child, keys, join(&path, key), field, deprecations);
+        }
+      }
+    },
+  }
+}
+
+fn join(path: &str, key: &str) -> String {
+  if path.is_empty() {
+    key.to_string()
+  } else {
+    format!("{}.{}", path, key)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{migrate, DeprecatedField};
+
+  #[test]
+  // the old names are renamed, and the new name wins if both are set
+  fn renames_deprecated_fields() {
+    let fields = [
+      DeprecatedField {
+        parent: "tauri.bundle",
+        old: "osx",
+        new: "macOS",
+        removed_in: "1.0.0",
+      },
+      DeprecatedField {
+        parent: "tauri.windows[]",
+        old: "fullScreen",
+        new: "fullscreen",
+        removed_in: "1.0.0",
+      },
+    ];
+    let mut value = serde_json::json!({
+      "tauri": {
+        "bundle": { "osx": { "license": "MIT" } },
+        "windows": [{ "fullscreen": true }, { "fullscreen": true, "fullScreen": false }]
+      }
+    });
+    let deprecations: Vec<String> = migrate(&mut value, &fields)
+      .iter()
+      .map(ToString::to_string)
+      .collect();
+    assert_eq!(
+      deprecations,
+      vec![
+        "`tauri.bundle.osx` is deprecated and will be removed in 1.0.0; use `tauri.bundle.macOS` instead",
+        "`tauri.windows[1].fullScreen` is deprecated and will be removed in 1.0.0; use `tauri.windows[1].fullscreen` instead"
+      ]
+    );
+    assert_eq!(
+      value,
+      serde_json::json!({
+        "tauri": {
+          "bundle": { "macOS": { "license": "MIT" } },
+          "windows": [{ "fullscreen": true }, { "fullscreen": true }]
+        }
+      })
+    );
+  }
+}
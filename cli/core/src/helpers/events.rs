@@ -0,0 +1,193 @@
+// The `--json-events` output mode of `tauri dev` and `tauri build`:
+// every event is printed to stdout as a single JSON line, and the human readable output goes to stderr.
+//
+// {"version":1,"event":"bundleStarted","format":"deb"}
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tauri_bundler::bundle::write_log;
+
+use std::{
+  fmt,
+  path::PathBuf,
+  sync::atomic::{AtomicBool, Ordering},
+};
+
+/// The version of the event schema, bumped on breaking changes to the events.
+pub const EVENTS_VERSION: u32 = 1;
+
+static JSON_EVENTS: AtomicBool = AtomicBool::new(false);
+
+/// Enables the JSON events output mode.
+pub fn set_json_events(json_events: bool) {
+  JSON_EVENTS.store(json_events, Ordering::Relaxed);
+}
+
+/// Whether the JSON events output mode is enabled.
+pub fn json_events() -> bool {
+  JSON_EVENTS.load(Ordering::Relaxed)
+}
+
+/// The code of an error event, so tools can tell the failed step without parsing the message.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+  /// The config couldn't be read or is invalid. Exits with 3.
+  Config,
+  /// A before command failed. Exits with 4.
+  BeforeCommand,
+  /// `cargo build` failed. Exits with 5.
+  CargoBuild,
+  /// A package type couldn't be bundled. Exits with 6.
+  Bundle,
+  /// A strict `build > hooks` hook failed. Exits with 7.
+  Hook,
+  /// Any other error. Exits with 1.
+  Other,
+}
+
+impl ErrorCode {
+  /// The exit code of `tauri dev` and `tauri build` when they fail with the error.
+  /// 2 is left to the usage errors, and `tauri dev` exits with the app exit code when the app exits.
+  pub fn exit_code(self) -> i32 {
+    match self {
+      Self::Config => 3,
+      Self::BeforeCommand => 4,
+      Self::CargoBuild => 5,
+      Self::Bundle => 6,
+      Self::Hook => 7,
+      Self::Other => 1,
+    }
+  }
+}
+
+/// An error tagged with its event code. It displays like the original error.
+#[derive(Debug)]
+pub struct CodedError {
+  code: ErrorCode,
+  message: String,
+}
+
+impl fmt::Display for CodedError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.message)
+  }
+}
+
+impl std::error::Error for CodedError {}
+
+/// Tags an error with its event code.
+pub fn coded(code: ErrorCode, error: impl fmt::Display) -> anyhow::Error {
+  anyhow::Error::new(CodedError {
+    code,
+    message: format!("{:#}", error),
+  })
+}
+
+/// Gets the event code of an error, `other` if it wasn't tagged.
+pub fn error_code(error: &anyhow::Error) -> ErrorCode {
+  error
+    .chain()
+    .find_map(|error| error.downcast_ref::<CodedError>())
+    .map(|error| error.code)
+    .unwrap_or(ErrorCode::Other)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum Event {
+  /// The config was loaded, or reloaded by `tauri dev`.
+  ConfigLoaded { path: PathBuf },
+  /// A before command started.
+  #[serde(rename_all = "camelCase")]
+  BeforeCommandStarted { command: String },
+  /// A before command finished. `tauri dev` doesn't wait for the before dev command, so it only reports it started.
+  #[serde(rename_all = "camelCase")]
+  BeforeCommandFinished {
+    command: String,
+    exit_code: Option<i32>,
+  },
+  /// A `build > hooks` hook started.
+  HookStarted { hook: String, command: String },
+  /// A `build > hooks` hook finished. The exit code is missing if it couldn't run.
+  #[serde(rename_all = "camelCase")]
+  HookFinished {
+    hook: String,
+    command: String,
+    exit_code: Option<i32>,
+  },
+  /// A message of `cargo --message-format json`, e.g. a compiled artifact or a compiler diagnostic.
+  CargoMessage { message: JsonValue },
+  /// The bundler of a package type started.
+  BundleStarted { format: String },
+  /// A package type finished, with its artifacts or the reason why it wasn't bundled.
+  BundleFinished {
+    format: String,
+    paths: Vec<PathBuf>,
+    error: Option<String>,
+  },
+  /// The command failed.
+  Error { code: ErrorCode, message: String },
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+  version: u32,
+  #[serde(flatten)]
+  event: &'a Event,
+}
+
+/// Serializes an event to its JSON line.
+fn to_line(event: &Event) -> String {
+  serde_json::to_string(&Envelope {
+    version: EVENTS_VERSION,
+    event,
+  })
+  .expect("failed to serialize the event")
+}
+
+/// Prints the event to stdout if the JSON events output mode is enabled.
+pub fn emit(event: Event) {
+  if json_events() {
+    let line = to_line(&event);
+    write_log(&line);
+    println!("{}", line);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{coded, error_code, to_line, ErrorCode, Event};
+
+  #[test]
+  fn event_lines() {
+    assert_eq!(
+      to_line(&Event::BundleStarted {
+        format: "deb".into()
+      }),
+      r#"{"version":1,"event":"bundleStarted","format":"deb"}"#
+    );
+    assert_eq!(
+      to_line(&Event::BeforeCommandFinished {
+        command: "yarn build".into(),
+        exit_code: Some(1)
+      }),
+      r#"{"version":1,"event":"beforeCommandFinished","command":"yarn build","exitCode":1}"#
+    );
+    assert_eq!(
+      to_line(&Event::Error {
+        code: ErrorCode::CargoBuild,
+        message: "failed".into()
+      }),
+      r#"{"version":1,"event":"error","code":"cargoBuild","message":"failed"}"#
+    );
+  }
+
+  #[test]
+  fn coded_errors() {
+    let error = coded(ErrorCode::Config, "invalid config");
+    assert_eq!(error.to_string(), "invalid config");
+    assert_eq!(error_code(&error), ErrorCode::Config);
+    assert_eq!(error_code(&anyhow::anyhow!("failed")), ErrorCode::Other);
+  }
+}
@@ -0,0 +1,153 @@
+// The `build > hooks` lifecycle hooks of `tauri build` and `tauri dev`.
+
+use super::{
+  app_paths::app_dir,
+  config::BuildHook,
+  events::{coded, emit, ErrorCode, Event},
+  execute_with_output, Logger,
+};
+
+use serde_json::Value as JsonValue;
+
+use std::process::Command;
+
+/// Creates the command of a hook, splitting its args on the spaces.
+pub fn command(hook: &str) -> Option<Command> {
+  let mut tokens = hook.split(' ');
+  let cmd = tokens.by_ref().find(|token| !token.is_empty())?;
+  #[cfg(target_os = "windows")]
+  let mut command =
+    Command::new(which::which(&cmd).expect(&format!("failed to find `{}` in your $PATH", cmd)));
+  #[cfg(not(target_os = "windows"))]
+  let mut command = Command::new(cmd);
+  command.args(tokens).current_dir(app_dir());
+  Some(command)
+}
+
+/// The context of a lifecycle hook, passed as environment variables.
+pub struct HookContext<'a> {
+  /// The hook name, e.g. `onBundleSuccess`.
+  pub name: &'a str,
+  pub target_triple: String,
+  pub profile: &'a str,
+  /// The JSON list of the artifacts.
+  pub artifacts: JsonValue,
+  /// The exit code of the step before the hook, if known.
+  pub previous_exit_code: Option<i32>,
+  /// Other environment variables, e.g. `TAURI_CHANGED_PATH`.
+  pub env: Vec<(&'static str, String)>,
+}
+
+impl HookContext<'_> {
+  fn env(&self) -> Vec<(&str, String)> {
+    let mut env = vec![
+      ("TAURI_HOOK", self.name.to_string()),
+      ("TAURI_TARGET_TRIPLE", self.target_triple.clone()),
+      ("TAURI_PROFILE", self.profile.to_string()),
+      ("TAURI_ARTIFACTS", self.artifacts.to_string()),
+    ];
+    if let Some(code) = self.previous_exit_code {
+      env.push(("TAURI_PREVIOUS_EXIT_CODE", code.to_string()));
+    }
+    env.extend(self.env.iter().cloned());
+    env
+  }
+}
+
+/// Runs a lifecycle hook. Its failure is reported, and only returned if the hook is `strict`,
+/// since the step before it already succeeded.
+pub fn run_hook(logger: &Logger, hook: &BuildHook, context: &HookContext<'_>) -> crate::Result<()> {
+  let mut command = match command(hook.command()) {
+    Some(command) => command,
+    None => return Ok(()),
+  };
+  let env = context.env();
+  command.envs(env.iter().map(|(key, value)| (key, value)));
+  logger.log(format!(
+    "Running the `build.hooks.{}` hook `{}`",
+    context.name,
+    hook.command()
+  ));
+  logger.command(&command, &env);
+  emit(Event::HookStarted {
+    hook: context.name.to_string(),
+    command: hook.command().to_string(),
+  });
+  let result = execute_with_output(&mut command);
+  emit(Event::HookFinished {
+    hook: context.name.to_string(),
+    command: hook.command().to_string(),
+    exit_code: result.as_ref().ok().and_then(|status| status.code()),
+  });
+  let error = match result {
+    Ok(status) if status.success() => return Ok(()),
+    Ok(status) => format!(
+      "the `build.hooks.{}` hook `{}` failed: {}",
+      context.name,
+      hook.command(),
+      status
+    ),
+    Err(e) => format!(
+      "the `build.hooks.{}` hook `{}` failed: {}",
+      context.name,
+      hook.command(),
+      e
+    ),
+  };
+  if hook.strict() {
+    Err(coded(ErrorCode::Hook, error))
+  } else {
+    logger.warn(error);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{BuildHook, HookContext};
+  use crate::helpers::config::BuildHooksConfig;
+  use serde_json::json;
+
+  #[test]
+  fn hooks() {
+    let hooks: BuildHooksConfig = serde_json::from_value(json!({
+      "onBuildSuccess": "node scripts/notify.js",
+      "onBundleSuccess": { "command": "node scripts/upload.js", "strict": true }
+    }))
+    .unwrap();
+    let on_build_success = hooks.on_build_success.unwrap();
+    assert_eq!(on_build_success.command(), "node scripts/notify.js");
+    assert!(!on_build_success.strict());
+    assert!(hooks.on_bundle_success.unwrap().strict());
+    assert!(
+      serde_json::from_value::<BuildHooksConfig>(json!({ "onBuildFailure": "exit" })).is_err()
+    );
+    assert!(serde_json::from_value::<BuildHook>(
+      json!({ "command": "exit", "strict": true, "cwd": "." })
+    )
+    .is_err());
+
+    let context = HookContext {
+      name: "onDevReload",
+      target_triple: "x86_64-unknown-linux-gnu".into(),
+      profile: "debug",
+      artifacts: json!([]),
+      previous_exit_code: Some(101),
+      env: vec![("TAURI_CHANGED_PATH", "src/main.rs".into())],
+    };
+    assert_eq!(
+      context.env(),
+      vec![
+        ("TAURI_HOOK", "onDevReload".to_string()),
+        (
+          "TAURI_TARGET_TRIPLE",
+          "x86_64-unknown-linux-gnu".to_string()
+        ),
+        ("TAURI_PROFILE", "debug".to_string()),
+        ("TAURI_ARTIFACTS", "[]".to_string()),
+        ("TAURI_PREVIOUS_EXIT_CODE", "101".to_string()),
+        ("TAURI_CHANGED_PATH", "src/main.rs".to_string()),
+      ]
+    );
+  }
+}
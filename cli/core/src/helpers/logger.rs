@@ -1,4 +1,51 @@
-use colored::Colorize;
+use super::events::json_events;
+use crate::print_config::{is_secret, redact};
+use colored::{ColoredString, Colorize};
+use serde_json::Value as JsonValue;
+use tauri_bundler::bundle::write_log;
+
+use std::{
+  env::vars,
+  process::Command,
+  sync::atomic::{AtomicU8, Ordering},
+  time::Instant,
+};
+
+/// Only the warnings and errors are printed, set by `-q`.
+pub const QUIET: u8 = 0;
+/// The default verbosity.
+pub const NORMAL: u8 = 1;
+/// Set by `-v`.
+pub const VERBOSE: u8 = 2;
+/// Set by `-vv`: the resolved config, the spawned commands and the phase timings are printed too.
+pub const DEBUG: u8 = 3;
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(NORMAL);
+
+/// Sets how much is printed. The log file always gets every message.
+pub fn set_verbosity(verbosity: u8) {
+  VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+/// Gets how much is printed.
+pub fn verbosity() -> u8 {
+  VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Masks the value of an environment variable if its name looks like a secret,
+/// and the secret fields of the JSON values, e.g. `TAURI_CONFIG`.
+fn redact_env(name: &str, value: &str) -> String {
+  if is_secret(&name.replace('_', "")) {
+    return "********".to_string();
+  }
+  match serde_json::from_str::<JsonValue>(value) {
+    Ok(mut json) if json.is_object() => {
+      redact(&mut json);
+      json.to_string()
+    }
+    _ => value.to_string(),
+  }
+}
 
 pub struct Logger<'a> {
   context: &'a str,
@@ -9,27 +56,103 @@ impl<'a> Logger<'a> {
     Self { context }
   }
 
+  fn print(&self, context: ColoredString, message: &str, level: u8) {
+    write_log(&format!("[{}] {}", self.context, message));
+    if verbosity() < level {
+      return;
+    }
+    // the JSON events own stdout while they're enabled
+    if json_events() {
+      eprintln!("{} {}", context, message);
+    } else {
+      println!("{} {}", context, message);
+    }
+  }
+
   pub fn log(&self, message: impl AsRef<str>) {
-    println!(
-      "{} {}",
+    self.print(
       format!("[{}]", self.context).green().bold(),
-      message.as_ref()
+      message.as_ref(),
+      NORMAL,
     );
   }
 
   pub fn warn(&self, message: impl AsRef<str>) {
-    println!(
-      "{} {}",
+    self.print(
       format!("[{}]", self.context).yellow().bold(),
-      message.as_ref()
+      message.as_ref(),
+      QUIET,
     );
   }
 
   pub fn error(&self, message: impl AsRef<str>) {
-    println!(
-      "{} {}",
+    self.print(
       format!("[{}]", self.context).red().bold(),
-      message.as_ref()
+      message.as_ref(),
+      QUIET,
+    );
+  }
+
+  /// Logs a message that is only printed with `-vv`.
+  pub fn debug(&self, message: impl AsRef<str>) {
+    self.print(
+      format!("[{}]", self.context).cyan().bold(),
+      message.as_ref(),
+      DEBUG,
+    );
+  }
+
+  /// Logs how long a phase took, e.g. `cargo build`.
+  pub fn timing(&self, phase: &str, start: Instant) {
+    self.debug(format!("{} took {:.2?}", phase, start.elapsed()));
+  }
+
+  /// Logs the resolved config, masking the secrets.
+  pub fn config(&self, mut config: JsonValue) {
+    redact(&mut config);
+    self.debug(format!(
+      "the resolved config:\n{}",
+      serde_json::to_string_pretty(&config).unwrap_or_default()
+    ));
+  }
+
+  /// Logs a spawned command line with its environment, masking the secrets.
+  pub fn command(&self, command: &Command, env: &[(&str, String)]) {
+    // the command's own variables come first, so they're kept over the inherited ones
+    let mut env: Vec<(String, String)> = env
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.clone()))
+      .chain(vars())
+      .collect();
+    env.sort_by(|(a, _), (b, _)| a.cmp(b));
+    env.dedup_by(|(a, _), (b, _)| a == b);
+    self.debug(format!(
+      "running {:?} with the environment:\n{}",
+      command,
+      env
+        .iter()
+        .map(|(name, value)| format!("  {}={}", name, redact_env(name, value)))
+        .collect::<Vec<_>>()
+        .join("\n")
+    ));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::redact_env;
+
+  #[test]
+  fn redacted_env() {
+    assert_eq!(redact_env("TAURI_PRIVATE_KEY", "key"), "********");
+    assert_eq!(redact_env("GITHUB_TOKEN", "token"), "********");
+    assert_eq!(redact_env("TAURI_DIR", "/app"), "/app");
+    assert_eq!(
+      redact_env(
+        "TAURI_CONFIG",
+        r#"{"tauri":{"bundle":{"osx":{"signingIdentity":"Developer ID"}}}}"#
+      ),
+      r#"{"tauri":{"bundle":{"osx":{"signingIdentity":"********"}}}}"#
     );
   }
 }
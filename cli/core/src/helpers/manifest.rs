@@ -1,17 +1,244 @@
-use super::{app_paths::tauri_dir, config::ConfigHandle};
+use super::{
+  app_paths::{app_dir, tauri_dir},
+  config::{AllowlistConfig, Config, ConfigHandle},
+};
 
 use convert_case::{Case, Casing};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
 use toml_edit::{Array, Document, Value};
 
 use std::{
-  fs::File,
+  collections::HashMap,
+  fs::{read_to_string, File},
   io::{Read, Write},
+  path::PathBuf,
+  process::Command,
 };
 
-pub fn rewrite_manifest(config: ConfigHandle) -> crate::Result<()> {
+#[derive(Deserialize)]
+struct CargoMetadataDependency {
+  name: String,
+  rename: Option<String>,
+  #[serde(default)]
+  optional: bool,
+}
+
+#[derive(Deserialize)]
+struct CargoMetadataPackage {
+  manifest_path: PathBuf,
+  features: HashMap<String, Vec<String>>,
+  dependencies: Vec<CargoMetadataDependency>,
+  #[serde(default)]
+  metadata: Option<CargoMetadataPackageMetadata>,
+}
+
+/// The `[package.metadata]` table.
+#[derive(Deserialize)]
+struct CargoMetadataPackageMetadata {
+  #[serde(rename = "tauri-plugin")]
+  tauri_plugin: Option<PluginMetadata>,
+}
+
+/// The `[package.metadata.tauri-plugin]` table of a plugin crate.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PluginMetadata {
+  /// The plugin name, the key of its config on the `plugins` object.
+  name: String,
+  /// The path to the JSON schema of the plugin config, relative to the plugin's Cargo.toml.
+  config_schema: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct CargoMetadata {
+  packages: Vec<CargoMetadataPackage>,
+}
+
+/// Runs `cargo metadata` on the app's Cargo.toml, with its dependencies unless `no_deps` is set.
+fn cargo_metadata(no_deps: bool) -> crate::Result<CargoMetadata> {
+  let mut command = Command::new("cargo");
+  command.args(&["metadata", "--format-version", "1"]);
+  if no_deps {
+    command.arg("--no-deps");
+  }
+  let output = command
+    .arg("--manifest-path")
+    .arg(tauri_dir().join("Cargo.toml"))
+    .output()?;
+  if !output.status.success() {
+    return Err(anyhow::anyhow!(
+      "failed to run `cargo metadata`: {}",
+      String::from_utf8_lossy(&output.stderr)
+    ));
+  }
+  Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+/// Gets the list of features declared by the app's Cargo.toml, including optional dependencies.
+fn available_features() -> crate::Result<Vec<String>> {
+  let manifest_path = tauri_dir().join("Cargo.toml");
+  let metadata = cargo_metadata(true)?;
+  let package = metadata
+    .packages
+    .into_iter()
+    .find(|package| package.manifest_path == manifest_path)
+    .ok_or_else(|| anyhow::anyhow!("failed to find the app package on `cargo metadata`"))?;
+
+  let mut features: Vec<String> = package.features.into_iter().map(|(name, _)| name).collect();
+  for dependency in package.dependencies {
+    if dependency.optional {
+      features.push(dependency.rename.unwrap_or(dependency.name));
+    }
+  }
+  features.sort();
+  Ok(features)
+}
+
+/// Gets the config JSON schemas contributed by the plugin crates the app depends on, keyed by plugin name.
+/// A plugin contributes its schema with `config-schema = "path/to/schema.json"`
+/// on the `[package.metadata.tauri-plugin]` table of its Cargo.toml.
+pub fn plugin_config_schemas() -> crate::Result<HashMap<String, JsonValue>> {
+  plugin_schemas(cargo_metadata(false)?.packages)
+}
+
+/// Reads the config JSON schemas of the plugin packages.
+fn plugin_schemas(
+  packages: Vec<CargoMetadataPackage>,
+) -> crate::Result<HashMap<String, JsonValue>> {
+  let mut schemas = HashMap::new();
+  for package in packages {
+    let plugin = match package.metadata.and_then(|metadata| metadata.tauri_plugin) {
+      Some(plugin) => plugin,
+      None => continue,
+    };
+    if let Some(config_schema) = &plugin.config_schema {
+      let path = package
+        .manifest_path
+        .parent()
+        .expect("manifest path has no parent")
+        .join(config_schema);
+      let schema: JsonValue = read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|schema| serde_json::from_str(&schema).map_err(Into::into))
+        .map_err(|e| {
+          anyhow::anyhow!(
+            "failed to read the config schema of the `{}` plugin at {}: {}",
+            plugin.name,
+            path.display(),
+            e
+          )
+        })?;
+      schemas.insert(plugin.name, schema);
+    }
+  }
+  Ok(schemas)
+}
+
+/// Ensures every feature exists on the app's Cargo.toml, so a typo fails before the build starts.
+pub fn validate_features(features: &[String]) -> crate::Result<()> {
+  if features.is_empty() {
+    return Ok(());
+  }
+  let available = available_features()?;
+  let unknown: Vec<&String> = features
+    .iter()
+    // `dependency/feature` entries are resolved by cargo itself
+    .filter(|feature| !feature.contains('/') && !available.contains(feature))
+    .collect();
+  if unknown.is_empty() {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!(
+      "unknown features {:?}; available features: {:?}",
+      unknown,
+      available
+    ))
+  }
+}
+
+/// The tauri Cargo features of the config sections in use, so the unused modules are compiled out,
+/// plus the `build > forcedFeatures`.
+fn tauri_features(config: &Config) -> Vec<String> {
+  let mut features = allowlist_features(&config.tauri.allowlist);
+  if config.tauri.cli.is_some() {
+    features.push("cli".to_string());
+  }
+  // the config shortcuts are registered with the global shortcut API
+  let global_shortcut_enabled = features
+    .iter()
+    .any(|feature| feature == "global-shortcut" || feature == "all-api");
+  if !config.tauri.global_shortcuts.is_empty() && !global_shortcut_enabled {
+    features.push("global-shortcut".to_string());
+  }
+  if config.tauri.updater.active {
+    features.push("updater".to_string());
+  }
+  // `all-api` and `power-set-prevent-sleep` enable the power monitor too
+  let power_enabled = features
+    .iter()
+    .any(|feature| feature == "power-set-prevent-sleep" || feature == "all-api");
+  if config.tauri.power.is_used() && !power_enabled {
+    features.push("power".to_string());
+  }
+  for feature in config.build.forced_features.iter().flatten() {
+    if !features.contains(feature) {
+      features.push(feature.clone());
+    }
+  }
+  features
+}
+
+/// The tauri Cargo features enabled by the allowlist.
+fn allowlist_features(allowlist: &AllowlistConfig) -> Vec<String> {
+  if allowlist.all() {
+    // the dangerous flags aren't part of `all-api`
+    let mut features = vec!["all-api".to_string()];
+    if allowlist.process.relaunch_dangerous_allow_symlink_macos {
+      features.push("process-relaunch-dangerous-allow-symlink-macos".to_string());
+    }
+    return features;
+  }
+
+  let mut features: Vec<String> = allowlist
+    .features
+    .iter()
+    .filter(|(_, enabled)| **enabled)
+    .map(|(feature, _)| feature.to_case(Case::Kebab))
+    .collect();
+  let typed_features = [
+    (allowlist.clipboard.read_text, "clipboard-read-text"),
+    (allowlist.clipboard.write_text, "clipboard-write-text"),
+    (allowlist.process.relaunch, "process-relaunch"),
+    (
+      allowlist.process.relaunch_dangerous_allow_symlink_macos,
+      "process-relaunch-dangerous-allow-symlink-macos",
+    ),
+    (allowlist.process.exit, "process-exit"),
+    (allowlist.power.set_prevent_sleep, "power-set-prevent-sleep"),
+    (allowlist.dialog.open, "open-dialog"),
+    (allowlist.dialog.save, "save-dialog"),
+  ];
+  for (enabled, feature) in typed_features.iter() {
+    if *enabled {
+      features.push(feature.to_string());
+    }
+  }
+  features
+}
+
+/// Writes the tauri features to the app manifest; `trace_startup` enables the startup tracing.
+pub fn rewrite_manifest(config: ConfigHandle, trace_startup: bool) -> crate::Result<()> {
   let manifest_path = tauri_dir().join("Cargo.toml");
   let mut manifest_str = String::new();
-  let mut manifest_file = File::open(&manifest_path)?;
+  let mut manifest_file = File::open(&manifest_path).map_err(|e| {
+    anyhow::anyhow!(
+      "failed to open `{}`, the `Cargo.toml` of the Rust project resolved from the project root `{}`: {}",
+      manifest_path.display(),
+      app_dir().display(),
+      e
+    )
+  })?;
   manifest_file.read_to_string(&mut manifest_str)?;
   let mut manifest: Document = manifest_str.parse::<Document>()?;
   let dependencies = manifest
@@ -28,20 +255,11 @@ pub fn rewrite_manifest(config: ConfigHandle) -> crate::Result<()> {
 
   if let Some(tauri) = tauri {
     let mut features: Array = Default::default();
-
-    let allowlist = &config.tauri.allowlist;
-    if *allowlist.get("all").unwrap_or(&false) {
-      features.push("all-api".to_string()).unwrap();
-    } else {
-      for (feature, enabled) in allowlist.iter() {
-        if *enabled {
-          features.push(feature.to_case(Case::Kebab)).unwrap();
-        }
-      }
+    for feature in tauri_features(config) {
+      features.push(feature).unwrap();
     }
-
-    if config.tauri.cli.is_some() {
-      features.push("cli".to_string()).unwrap();
+    if trace_startup {
+      features.push("trace-startup").unwrap();
     }
 
     match tauri {
@@ -69,3 +287,121 @@ pub fn rewrite_manifest(config: ConfigHandle) -> crate::Result<()> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{allowlist_features, plugin_schemas, tauri_features, AllowlistConfig};
+  use crate::helpers::config::Config;
+  use serde_json::json;
+  use std::fs;
+
+  #[test]
+  fn allowlist_features_are_independent() {
+    let mut allowlist = AllowlistConfig::default();
+    allowlist.features.insert("readTextFile".into(), true);
+    allowlist.features.insert("openDialog".into(), false);
+    allowlist.process.exit = true;
+    allowlist.dialog.open = true;
+    assert_eq!(
+      allowlist_features(&allowlist),
+      vec![
+        "read-text-file".to_string(),
+        "process-exit".to_string(),
+        "open-dialog".to_string()
+      ]
+    );
+
+    allowlist.features.insert("all".into(), true);
+    allowlist.process.relaunch_dangerous_allow_symlink_macos = true;
+    assert_eq!(
+      allowlist_features(&allowlist),
+      vec![
+        "all-api".to_string(),
+        "process-relaunch-dangerous-allow-symlink-macos".to_string()
+      ]
+    );
+  }
+
+  #[test]
+  fn config_features() {
+    let config: Config = serde_json::from_value(json!({})).unwrap();
+    assert!(tauri_features(&config).is_empty());
+
+    let config: Config = serde_json::from_value(json!({
+      "build": { "forcedFeatures": ["cli", "window"] },
+      "tauri": {
+        "cli": {},
+        "updater": { "active": true },
+        "globalShortcuts": { "CmdOrCtrl+Shift+K": "quit" }
+      }
+    }))
+    .unwrap();
+    assert_eq!(
+      tauri_features(&config),
+      vec!["cli", "global-shortcut", "updater", "window"]
+    );
+
+    let config: Config = serde_json::from_value(json!({
+      "tauri": { "power": { "idleTimeoutSeconds": 300 } }
+    }))
+    .unwrap();
+    assert_eq!(tauri_features(&config), vec!["power"]);
+
+    let config: Config = serde_json::from_value(json!({
+      "tauri": {
+        "power": { "preventSleep": true },
+        "allowlist": { "power": { "setPreventSleep": true } }
+      }
+    }))
+    .unwrap();
+    assert_eq!(tauri_features(&config), vec!["power-set-prevent-sleep"]);
+  }
+
+  #[test]
+  fn plugin_config_schemas() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+      dir.path().join("schema.json"),
+      r#"{ "type": "object", "required": ["port"] }"#,
+    )
+    .unwrap();
+    fs::write(dir.path().join("invalid.json"), "{").unwrap();
+    let package = |metadata: serde_json::Value| {
+      serde_json::from_value(json!({
+        "manifest_path": dir.path().join("Cargo.toml"),
+        "features": {},
+        "dependencies": [],
+        "metadata": metadata,
+      }))
+      .unwrap()
+    };
+
+    let schemas = plugin_schemas(vec![
+      package(json!({ "tauri-plugin": { "name": "sql", "config-schema": "schema.json" } })),
+      // a plugin without a schema, and a crate that isn't a plugin
+      package(json!({ "tauri-plugin": { "name": "fs" } })),
+      package(json!({ "docs": { "rs": { "all-features": true } } })),
+      package(serde_json::Value::Null),
+    ])
+    .unwrap();
+    assert_eq!(schemas.len(), 1);
+    assert_eq!(
+      schemas["sql"],
+      json!({ "type": "object", "required": ["port"] })
+    );
+
+    for schema in &["missing.json", "invalid.json"] {
+      let error = plugin_schemas(vec![package(
+        json!({ "tauri-plugin": { "name": "sql", "config-schema": schema } }),
+      )])
+      .unwrap_err()
+      .to_string();
+      assert!(
+        error.starts_with("failed to read the config schema of the `sql` plugin at "),
+        "{}",
+        error
+      );
+      assert!(error.contains(schema), "{}", error);
+    }
+  }
+}
@@ -0,0 +1,179 @@
+use super::deprecations::{migrate as rename_deprecated, DEPRECATED_FIELDS};
+
+use serde_json::Value as JsonValue;
+
+/// A config transformation for a breaking config change, applied by `tauri migrate`.
+pub struct Migration {
+  /// The config version the migration upgrades to. Each migration bumps the version by one.
+  pub version: u64,
+  /// Applies the migration, returning a description of each change.
+  pub apply: fn(&mut JsonValue) -> Vec<String>,
+}
+
+/// The config migrations, sorted by version.
+///
+/// A breaking config change adds a migration with the next version, so the configs already migrated skip it.
+pub const MIGRATIONS: &[Migration] = &[
+  Migration {
+    version: 1,
+    apply: rename_osx_target,
+  },
+  Migration {
+    version: 2,
+    apply: pin_physical_window_sizes,
+  },
+];
+
+/// The window sizes that are unitless numbers.
+const WINDOW_SIZE_FIELDS: &[&str] = &[
+  "width",
+  "height",
+  "minWidth",
+  "minHeight",
+  "maxWidth",
+  "maxHeight",
+];
+
+/// The config version of the current config format, set on the migrated configs.
+pub fn latest_version() -> u64 {
+  MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// The version of a config value, `0` if it was never migrated.
+pub fn config_version(value: &JsonValue) -> u64 {
+  value
+    .get("configVersion")
+    .and_then(JsonValue::as_u64)
+    .unwrap_or(0)
+}
+
+/// Applies the migrations newer than the config version and renames the deprecated fields,
+/// returning a description of each change. The config version isn't updated.
+pub fn migrate(value: &mut JsonValue) -> Vec<String> {
+  let version = config_version(value);
+  let mut changes = Vec::new();
+  for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+    changes.extend((migration.apply)(value));
+  }
+  for deprecation in rename_deprecated(value, DEPRECATED_FIELDS) {
+    changes.push(format!(
+      "renamed `{}` to `{}`",
+      deprecation.path, deprecation.replacement
+    ));
+  }
+  changes
+}
+
+/// The `osx` bundle target was renamed to `app`.
+fn rename_osx_target(value: &mut JsonValue) -> Vec<String> {
+  let mut changes = Vec::new();
+  let targets = match value.pointer_mut("/tauri/bundle/targets") {
+    Some(targets) => targets,
+    None => return changes,
+  };
+  let mut rename = |target: &mut JsonValue, path: String| {
+    if target
+      .as_str()
+      .map(|t| t.eq_ignore_ascii_case("osx"))
+      .unwrap_or(false)
+    {
+      *target = JsonValue::String("app".to_string());
+      changes.push(format!(
+        "renamed the `osx` bundle target to `app` on `{}`",
+        path
+      ));
+    }
+  };
+  match targets {
+    JsonValue::Array(array) => {
+      for (i, target) in array.iter_mut().enumerate() {
+        rename(target, format!("tauri.bundle.targets[{}]", i));
+      }
+    }
+    target => rename(target, "tauri.bundle.targets".to_string()),
+  }
+  changes
+}
+
+/// The unitless window sizes are logical pixels since version 2, so the old ones are pinned to physical pixels.
+fn pin_physical_window_sizes(value: &mut JsonValue) -> Vec<String> {
+  let mut changes = Vec::new();
+  let windows: Vec<(String, &mut JsonValue)> = match value.pointer_mut("/tauri/windows") {
+    Some(JsonValue::Array(windows)) => windows
+      .iter_mut()
+      .enumerate()
+      .map(|(i, window)| (format!("tauri.windows[{}]", i), window))
+      .collect(),
+    Some(JsonValue::Object(windows)) => windows
+      .iter_mut()
+      .map(|(label, window)| (format!("tauri.windows.{}", label), window))
+      .collect(),
+    _ => return changes,
+  };
+  for (path, window) in windows {
+    for field in WINDOW_SIZE_FIELDS {
+      if let Some(size) = window.get_mut(*field) {
+        if let Some(number) = size.as_f64() {
+          *size = JsonValue::String(format!("{}px", number));
+          changes.push(format!("pinned `{}.{}` to physical pixels", path, field));
+        }
+      }
+    }
+  }
+  changes
+}
+
+#[cfg(test)]
+mod test {
+  use super::{config_version, latest_version, migrate};
+
+  #[test]
+  // the migrations newer than the config version are applied
+  fn migrates_old_configs() {
+    let mut value = serde_json::json!({
+      "tauri": { "bundle": { "targets": ["deb", "OSX"] } }
+    });
+    assert_eq!(
+      migrate(&mut value),
+      vec!["renamed the `osx` bundle target to `app` on `tauri.bundle.targets[1]`"]
+    );
+    assert_eq!(
+      value,
+      serde_json::json!({ "tauri": { "bundle": { "targets": ["deb", "app"] } } })
+    );
+    assert_eq!(config_version(&value), 0);
+  }
+
+  #[test]
+  // the unitless window sizes of the old configs keep their physical pixels
+  fn pins_physical_window_sizes() {
+    let mut value = serde_json::json!({
+      "configVersion": 1,
+      "tauri": { "windows": [{ "width": 800, "height": "600lpx", "maxWidth": 1024.5 }] }
+    });
+    assert_eq!(
+      migrate(&mut value),
+      vec![
+        "pinned `tauri.windows[0].width` to physical pixels",
+        "pinned `tauri.windows[0].maxWidth` to physical pixels",
+      ]
+    );
+    assert_eq!(
+      value,
+      serde_json::json!({
+        "configVersion": 1,
+        "tauri": { "windows": [{ "width": "800px", "height": "600lpx", "maxWidth": "1024.5px" }] }
+      })
+    );
+  }
+
+  #[test]
+  // the configs on the latest version skip the migrations
+  fn skips_migrated_configs() {
+    let mut value = serde_json::json!({
+      "configVersion": latest_version(),
+      "tauri": { "bundle": { "targets": "osx" } }
+    });
+    assert!(migrate(&mut value).is_empty());
+  }
+}
@@ -1,35 +1,79 @@
 pub mod app_paths;
 pub mod config;
-mod logger;
+pub mod config_paths;
+pub mod deprecations;
+pub mod events;
+pub mod hooks;
+pub mod logger;
 pub mod manifest;
+pub mod migrations;
+pub mod process;
 mod tauri_entry;
 
 pub use logger::Logger;
 pub use tauri_entry::TauriScript;
 
+use tauri_bundler::bundle::{has_log_file, write_log};
+
 use std::{
-  io::{BufRead, BufReader},
-  process::{Command, Stdio},
+  io::{BufRead, BufReader, Read},
+  process::{Command, ExitStatus, Stdio},
+  thread::{self, JoinHandle},
 };
 
-pub fn execute_with_output(cmd: &mut Command) -> crate::Result<()> {
-  let mut child = cmd
-    .stdout(Stdio::piped())
-    .spawn()
-    .expect("failed to spawn command");
+/// Prints a line of a child process output, copying it to the log file.
+/// The stdout lines go to stderr while the JSON events own stdout, and aren't printed with `-q`.
+pub fn print_output(line: &str, stderr: bool) {
+  write_log(line);
+  if !stderr && logger::verbosity() == logger::QUIET {
+    return;
+  }
+  if stderr || events::json_events() {
+    eprintln!("{}", line);
+  } else {
+    println!("{}", line);
+  }
+}
+
+/// Prints the lines of a child process output on a new thread, with [`print_output`].
+pub fn forward_output(output: impl Read + Send + 'static, stderr: bool) -> JoinHandle<()> {
+  thread::spawn(move || {
+    for line in BufReader::new(output).lines() {
+      match line {
+        Ok(line) => print_output(&line, stderr),
+        Err(_) => break,
+      }
+    }
+  })
+}
+
+/// Runs the command, printing its stdout lines, and returns its exit status.
+/// The stderr lines are only piped to be copied to the log file.
+pub fn execute_with_output(cmd: &mut Command) -> crate::Result<ExitStatus> {
+  cmd.stdout(Stdio::piped());
+  if has_log_file() {
+    cmd.stderr(Stdio::piped());
+  }
+  process::set_process_group(cmd);
+  let mut child = cmd.spawn().expect("failed to spawn command");
+  process::track(child.id());
+  let stderr = child
+    .stderr
+    .take()
+    .map(|stderr| forward_output(stderr, true));
   {
     let stdout = child.stdout.as_mut().expect("Failed to get stdout handle");
     let reader = BufReader::new(stdout);
 
     for line in reader.lines() {
-      println!("{}", line.expect("Failed to get line"));
+      print_output(&line.expect("Failed to get line"), false);
     }
   }
+  if let Some(stderr) = stderr {
+    let _ = stderr.join();
+  }
 
   let status = child.wait()?;
-  if status.success() {
-    Ok(())
-  } else {
-    Err(anyhow::anyhow!("command failed"))
-  }
+  process::untrack(child.id(), &status);
+  Ok(status)
 }
@@ -0,0 +1,180 @@
+// The child processes of `tauri dev` and `tauri build`: their exit codes, and the termination signals forwarded to them.
+
+use once_cell::sync::Lazy;
+
+#[cfg(unix)]
+use std::time::Duration;
+use std::{
+  process::{Command, ExitStatus},
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+  },
+};
+
+/// The time the children have to exit after a forwarded signal before they're killed.
+#[cfg(unix)]
+const SIGNAL_TIMEOUT: Duration = Duration::from_secs(5);
+/// The interval between the checks of the children that are still running after a forwarded signal.
+#[cfg(unix)]
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The running children the termination signals are forwarded to.
+static CHILDREN: Lazy<Mutex<Vec<u32>>> = Lazy::new(Default::default);
+/// Whether a termination signal was forwarded to the children.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// The exit code of the children that finished after a forwarded signal: the first failure, or 0.
+static INTERRUPTED_EXIT_CODE: Lazy<Mutex<Option<i32>>> = Lazy::new(Default::default);
+
+/// The exit code of a finished child: its code, or 128 plus the number of the signal that killed it, like the shells.
+pub fn exit_code(status: &ExitStatus) -> i32 {
+  if let Some(code) = status.code() {
+    return code;
+  }
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::ExitStatusExt;
+    if let Some(signal) = status.signal() {
+      return 128 + signal;
+    }
+  }
+  1
+}
+
+/// Spawns the command on its own process group, so the forwarded signals reach its whole tree, e.g. the app `cargo run` starts.
+/// The children that may read the terminal, like the before dev command, stay on the CLI process group instead.
+pub fn set_process_group(command: &mut Command) {
+  #[cfg(unix)]
+  {
+    use std::os::unix::process::CommandExt;
+    unsafe {
+      command.pre_exec(|| {
+        if libc::setpgid(0, 0) == 0 {
+          Ok(())
+        } else {
+          Err(std::io::Error::last_os_error())
+        }
+      });
+    }
+  }
+  #[cfg(not(unix))]
+  let _ = command;
+}
+
+/// Forwards the termination signals to the child until [`untrack`] is called.
+/// Its owner must wait for it and call [`untrack`], since a forwarded signal waits for every tracked child.
+pub fn track(pid: u32) {
+  CHILDREN.lock().unwrap().push(pid);
+}
+
+/// Stops forwarding the termination signals to the child, once it finished with the status.
+pub fn untrack(pid: u32, status: &ExitStatus) {
+  let mut children = CHILDREN.lock().unwrap();
+  children.retain(|child| *child != pid);
+  if INTERRUPTED.load(Ordering::SeqCst) {
+    let mut interrupted_exit_code = INTERRUPTED_EXIT_CODE.lock().unwrap();
+    if interrupted_exit_code.map_or(true, |code| code == 0) {
+      interrupted_exit_code.replace(exit_code(status));
+    }
+  }
+}
+
+/// Whether a termination signal was forwarded to the children, so the CLI exits with their status
+/// rather than with the error of the interrupted step.
+pub fn interrupted() -> bool {
+  INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Sends the signal to the process group of each running child, or to the child if it doesn't lead a group.
+#[cfg(unix)]
+fn signal_children(signal: i32) {
+  for pid in CHILDREN.lock().unwrap().iter() {
+    let pid = *pid as libc::pid_t;
+    unsafe {
+      if libc::kill(-pid, signal) != 0 {
+        libc::kill(pid, signal);
+      }
+    }
+  }
+}
+
+/// Waits for the running children to finish after a forwarded signal, killing them after [`SIGNAL_TIMEOUT`].
+/// Returns their exit code, or 128 plus the signal number if they were killed or there weren't any.
+#[cfg(unix)]
+fn wait_children(signal: i32) -> i32 {
+  let start = std::time::Instant::now();
+  while !CHILDREN.lock().unwrap().is_empty() {
+    if start.elapsed() >= SIGNAL_TIMEOUT {
+      signal_children(libc::SIGKILL);
+      return 128 + signal;
+    }
+    std::thread::sleep(SIGNAL_POLL_INTERVAL);
+  }
+  INTERRUPTED_EXIT_CODE
+    .lock()
+    .unwrap()
+    .unwrap_or(128 + signal)
+}
+
+/// Forwards SIGINT and SIGTERM to the running children, waits for them, then exits with their exit code.
+/// On Windows, the console already sends Ctrl+C to the whole process tree.
+pub fn forward_signals() {
+  #[cfg(unix)]
+  {
+    use signal_hook::{
+      consts::{SIGINT, SIGTERM},
+      iterator::Signals,
+    };
+    let mut signals =
+      Signals::new(&[SIGINT, SIGTERM]).expect("failed to register the signal handlers");
+    std::thread::spawn(move || {
+      if let Some(signal) = signals.forever().next() {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        signal_children(signal);
+        std::process::exit(wait_children(signal));
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{exit_code, track, untrack, INTERRUPTED, INTERRUPTED_EXIT_CODE};
+  use std::sync::atomic::Ordering;
+
+  #[cfg(unix)]
+  #[test]
+  fn exit_codes() {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    assert_eq!(exit_code(&ExitStatus::from_raw(0)), 0);
+    // exited with 101
+    assert_eq!(exit_code(&ExitStatus::from_raw(101 << 8)), 101);
+    // killed by SIGKILL
+    assert_eq!(exit_code(&ExitStatus::from_raw(9)), 137);
+    // killed by SIGTERM
+    assert_eq!(exit_code(&ExitStatus::from_raw(15)), 143);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  // after a forwarded signal, the CLI exits with the first failure of the children
+  fn interrupted_exit_code() {
+    use super::wait_children;
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    // the pids of children that don't exist, so they're never signaled
+    let (first, second) = (u32::MAX - 1, u32::MAX);
+    track(first);
+    track(second);
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    untrack(first, &ExitStatus::from_raw(0));
+    assert_eq!(*INTERRUPTED_EXIT_CODE.lock().unwrap(), Some(0));
+    untrack(second, &ExitStatus::from_raw(15));
+    assert_eq!(*INTERRUPTED_EXIT_CODE.lock().unwrap(), Some(143));
+    // every child finished
+    assert_eq!(wait_children(15), 143);
+  }
+}
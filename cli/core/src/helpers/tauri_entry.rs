@@ -14,16 +14,36 @@ impl TauriScript {
   }
 
   pub fn get(self) -> String {
-    let mut scripts = Vec::new();
-    scripts.push(include_str!("../templates/tauri.js"));
-
+    let tauri_script = include_str!("../templates/tauri.js");
     if self.global_tauri {
-      scripts.insert(
-        0,
+      // the runtime sets the flag per window, skipping the bundle on the windows that disable it
+      format!(
+        "if (window.__TAURI_WITH_GLOBAL_TAURI__ !== false) {{\n{}\n}}\n\n{}",
         include_str!(concat!(env!("OUT_DIR"), "/tauri.bundle.umd.js")),
-      );
+        tauri_script
+      )
+    } else {
+      tauri_script.to_string()
     }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::TauriScript;
+
+  #[test]
+  fn global_tauri_bundle() {
+    let tauri_script = include_str!("../templates/tauri.js");
+    assert_eq!(TauriScript::new().get(), tauri_script);
 
-    scripts.join("\n\n")
+    let script = TauriScript::new().global_tauri(true).get();
+    // the bundle only runs on the windows that don't disable it
+    assert!(script.starts_with("if (window.__TAURI_WITH_GLOBAL_TAURI__ !== false) {\n"));
+    assert!(script.contains(include_str!(concat!(
+      env!("OUT_DIR"),
+      "/tauri.bundle.umd.js"
+    ))));
+    assert!(script.ends_with(&format!("\n}}\n\n{}", tauri_script)));
   }
 }
@@ -1,12 +1,291 @@
+use crate::helpers::{
+  app_paths::{app_dir, find_app_dir, tauri_dir},
+  config::get as get_config,
+};
+
+use colored::Colorize;
+use serde_json::{json, Map, Value as JsonValue};
+
+use std::{fs::read_to_string, process::Command};
+
+/// The WebView2 runtime client registry key, also read by the Windows installer.
+#[cfg(windows)]
+const WEBVIEW2_REGISTRY_KEY: &str =
+  r"HKLM\SOFTWARE\WOW6432Node\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}";
+
+/// A group of info entries, e.g. the environment versions.
+struct Section {
+  /// The key of the section on the JSON output.
+  key: &'static str,
+  /// The title of the section on the text output.
+  title: &'static str,
+  /// The entry names and values; the `null` values weren't found.
+  entries: Vec<(&'static str, JsonValue)>,
+}
+
 #[derive(Default)]
-pub struct Info;
+pub struct Info {
+  json: bool,
+}
 
 impl Info {
   pub fn new() -> Self {
     Default::default()
   }
 
+  pub fn json(mut self) -> Self {
+    self.json = true;
+    self
+  }
+
   pub fn run(self) -> crate::Result<()> {
-    unimplemented!()
+    let mut sections = vec![environment(), webview()];
+    if find_app_dir().is_some() {
+      sections.push(packages());
+      sections.push(app());
+    }
+
+    if self.json {
+      let value: Map<String, JsonValue> = sections
+        .into_iter()
+        .map(|section| {
+          let entries = section
+            .entries
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+          (section.key.to_string(), JsonValue::Object(entries))
+        })
+        .collect();
+      println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+      for section in sections {
+        println!("\n{}", section.title.bold());
+        for (name, value) in section.entries {
+          println!("  {} - {}", name, display(&value));
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+/// The operating system and the Rust and Node.js toolchain versions.
+fn environment() -> Section {
+  Section {
+    key: "environment",
+    title: "Operating System and toolchains",
+    entries: vec![
+      ("os", os_version().into()),
+      ("rustc", json!(version("rustc", &["--version"]))),
+      ("cargo", json!(version("cargo", &["--version"]))),
+      ("node", json!(version("node", &["--version"]))),
+      ("npm", json!(version("npm", &["--version"]))),
+      ("yarn", json!(version("yarn", &["--version"]))),
+    ],
+  }
+}
+
+/// The webview runtime version of the current platform.
+fn webview() -> Section {
+  #[cfg(not(any(target_os = "macos", windows)))]
+  let entries = vec![(
+    "webkit2gtk",
+    json!(version("pkg-config", &["--modversion", "webkit2gtk-4.0"])),
+  )];
+  #[cfg(target_os = "macos")]
+  let entries = vec![(
+    "safari",
+    json!(version(
+      "defaults",
+      &[
+        "read",
+        "/Applications/Safari.app/Contents/Info.plist",
+        "CFBundleShortVersionString",
+      ],
+    )),
+  )];
+  #[cfg(windows)]
+  let entries = vec![(
+    "webview2",
+    json!(
+      version("reg", &["query", WEBVIEW2_REGISTRY_KEY, "/v", "pv"])
+        .and_then(|output| output.split_whitespace().last().map(ToString::to_string))
+    ),
+  )];
+
+  Section {
+    key: "webview",
+    title: "Webview runtime",
+    entries,
+  }
+}
+
+/// The Tauri crate versions from the `Cargo.lock` file and the JS package versions from `node_modules`.
+fn packages() -> Section {
+  let lock = cargo_lock();
+  let crate_version = |name: &str| lock_version(lock.as_ref(), name);
+
+  Section {
+    key: "packages",
+    title: "Tauri packages",
+    entries: vec![
+      ("tauri", crate_version("tauri")),
+      ("tauri-api", crate_version("tauri-api")),
+      ("tauri-cli", env!("CARGO_PKG_VERSION").into()),
+      (
+        "@tauri-apps/cli",
+        json!(npm_package_version("@tauri-apps/cli")),
+      ),
+      (
+        "@tauri-apps/api",
+        json!(npm_package_version("@tauri-apps/api")),
+      ),
+    ],
+  }
+}
+
+/// The key fields of the app config.
+fn app() -> Section {
+  let entries = match get_config(&[]) {
+    Ok(config) => {
+      let config_guard = config.lock().unwrap();
+      let config_ = config_guard.as_ref().unwrap();
+      let dist_dir = &config_.build.dist_dir;
+      vec![
+        ("identifier", json!(config_.tauri.bundle.identifier)),
+        ("distDir", dist_dir.clone().into()),
+        ("distDirExists", tauri_dir().join(dist_dir).exists().into()),
+        ("devPath", json!(config_.build.dev_path)),
+        ("bundleActive", config_.tauri.bundle.active.into()),
+        ("bundleTargets", json!(config_.tauri.bundle.targets)),
+      ]
+    }
+    Err(e) => vec![("error", e.to_string().into())],
+  };
+
+  Section {
+    key: "app",
+    title: "App",
+    entries,
+  }
+}
+
+/// The operating system name and version.
+fn os_version() -> String {
+  #[cfg(not(any(target_os = "macos", windows)))]
+  let release = version("uname", &["-r"]);
+  #[cfg(target_os = "macos")]
+  let release = version("sw_vers", &["-productVersion"]);
+  #[cfg(windows)]
+  let release = version("cmd", &["/C", "ver"]);
+
+  match release {
+    Some(release) => format!("{} {}", std::env::consts::OS, release),
+    None => std::env::consts::OS.to_string(),
+  }
+}
+
+/// Runs the command and returns its trimmed output, or `None` if it isn't installed or fails.
+fn version(program: &str, args: &[&str]) -> Option<String> {
+  // npm and yarn are batch scripts on Windows, so they're run through the shell
+  #[cfg(windows)]
+  let output = Command::new("cmd")
+    .arg("/S")
+    .arg("/C")
+    .arg(program)
+    .args(args)
+    .output();
+  #[cfg(not(windows))]
+  let output = Command::new(program).args(args).output();
+
+  output
+    .ok()
+    .filter(|output| output.status.success())
+    .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .filter(|output| !output.is_empty())
+}
+
+/// Reads the `Cargo.lock` file of the app, which may be on a parent workspace directory.
+pub(crate) fn cargo_lock() -> Option<JsonValue> {
+  let tauri_dir = tauri_dir();
+  let path = tauri_dir
+    .ancestors()
+    .map(|dir| dir.join("Cargo.lock"))
+    .find(|path| path.exists())?;
+  let lock: toml::Value = read_to_string(path).ok()?.parse().ok()?;
+  serde_json::to_value(lock).ok()
+}
+
+/// The version of a crate on the `Cargo.lock` file, or `null` if it isn't locked.
+fn lock_version(lock: Option<&JsonValue>, name: &str) -> JsonValue {
+  lock
+    .and_then(|lock| lock.get("package"))
+    .and_then(JsonValue::as_array)
+    .and_then(|packages| {
+      packages
+        .iter()
+        .find(|package| package.get("name").and_then(JsonValue::as_str) == Some(name))
+    })
+    .and_then(|package| package.get("version"))
+    .cloned()
+    .unwrap_or_default()
+}
+
+/// Reads the version of a JS package installed on the app `node_modules`.
+pub(crate) fn npm_package_version(name: &str) -> Option<String> {
+  let path = app_dir()
+    .join("node_modules")
+    .join(name)
+    .join("package.json");
+  let package: JsonValue = serde_json::from_str(&read_to_string(path).ok()?).ok()?;
+  package
+    .get("version")
+    .and_then(JsonValue::as_str)
+    .map(ToString::to_string)
+}
+
+/// Formats an entry value for the text output.
+fn display(value: &JsonValue) -> String {
+  match value {
+    JsonValue::Null => "Not found".to_string(),
+    JsonValue::String(value) => value.clone(),
+    JsonValue::Array(values) => values.iter().map(display).collect::<Vec<_>>().join(", "),
+    value => value.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{display, lock_version};
+  use serde_json::{json, Value as JsonValue};
+
+  #[test]
+  fn locked_crate_versions() {
+    let lock: toml::Value = r#"
+[[package]]
+name = "tauri"
+version = "0.11.1"
+
+[[package]]
+name = "tauri-api"
+version = "0.7.5"
+"#
+    .parse()
+    .unwrap();
+    let lock = serde_json::to_value(lock).unwrap();
+    assert_eq!(lock_version(Some(&lock), "tauri"), json!("0.11.1"));
+    assert_eq!(lock_version(Some(&lock), "tauri-api"), json!("0.7.5"));
+    assert_eq!(lock_version(Some(&lock), "tauri-updater"), JsonValue::Null);
+    assert_eq!(lock_version(Some(&json!({})), "tauri"), JsonValue::Null);
+    assert_eq!(lock_version(None, "tauri"), JsonValue::Null);
+  }
+
+  #[test]
+  fn text_output() {
+    assert_eq!(display(&JsonValue::Null), "Not found");
+    assert_eq!(display(&json!("1.49.0")), "1.49.0");
+    assert_eq!(display(&json!(["deb", "appimage"])), "deb, appimage");
+    assert_eq!(display(&json!(true)), "true");
   }
 }
@@ -1,6 +1,122 @@
-#![allow(dead_code)]
+use crate::helpers::{
+  config::{
+    check, config_sources, AppUrl, BuildConfig, BundleConfig, BundleTarget, Config, PackageConfig,
+    TauriConfig, WindowConfig, WindowSize, JSON_CONFIG_FILE,
+  },
+  migrations::latest_version,
+  Logger,
+};
 
-use std::{convert::TryFrom, path::PathBuf};
+use convert_case::{Case, Casing};
+use serde_json::Value as JsonValue;
+
+use std::{
+  convert::TryFrom,
+  fs::{create_dir_all, write},
+  io::{stdin, stdout, Write},
+  path::{Path, PathBuf},
+};
+
+/// The default dev server URL.
+const DEFAULT_DEV_PATH: &str = "http://localhost:8080";
+/// The default dist dir, relative to `src-tauri`.
+const DEFAULT_DIST_DIR: &str = "../dist";
+/// The bundle icons of the template.
+const BUNDLE_ICONS: &[&str] = &[
+  "icons/32x32.png",
+  "icons/128x128.png",
+  "icons/128x128@2x.png",
+  "icons/icon.icns",
+  "icons/icon.ico",
+];
+
+/// The `src-tauri` template files, except the Cargo.toml which is rendered.
+const TEMPLATE_FILES: &[(&str, &[u8])] = &[
+  (
+    "src/main.rs",
+    include_bytes!("../../tauri.js/templates/src-tauri/src/main.rs"),
+  ),
+  (
+    "src/cmd.rs",
+    include_bytes!("../../tauri.js/templates/src-tauri/src/cmd.rs"),
+  ),
+  (
+    "src/build.rs",
+    include_bytes!("../../tauri.js/templates/src-tauri/src/build.rs"),
+  ),
+  (
+    ".gitignore",
+    include_bytes!("../../tauri.js/templates/src-tauri/_gitignore"),
+  ),
+  (
+    "rustfmt.toml",
+    include_bytes!("../../tauri.js/templates/src-tauri/rustfmt.toml"),
+  ),
+  (
+    "icons/32x32.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/32x32.png"),
+  ),
+  (
+    "icons/128x128.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/128x128.png"),
+  ),
+  (
+    "icons/128x128@2x.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/128x128@2x.png"),
+  ),
+  (
+    "icons/icon.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/icon.png"),
+  ),
+  (
+    "icons/icon.icns",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/icon.icns"),
+  ),
+  (
+    "icons/icon.ico",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/icon.ico"),
+  ),
+  (
+    "icons/Square30x30Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square30x30Logo.png"),
+  ),
+  (
+    "icons/Square44x44Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square44x44Logo.png"),
+  ),
+  (
+    "icons/Square71x71Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square71x71Logo.png"),
+  ),
+  (
+    "icons/Square89x89Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square89x89Logo.png"),
+  ),
+  (
+    "icons/Square107x107Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square107x107Logo.png"),
+  ),
+  (
+    "icons/Square142x142Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square142x142Logo.png"),
+  ),
+  (
+    "icons/Square150x150Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square150x150Logo.png"),
+  ),
+  (
+    "icons/Square284x284Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square284x284Logo.png"),
+  ),
+  (
+    "icons/Square310x310Logo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/Square310x310Logo.png"),
+  ),
+  (
+    "icons/StoreLogo.png",
+    include_bytes!("../../tauri.js/templates/src-tauri/icons/StoreLogo.png"),
+  ),
+];
 
 pub enum ForceType {
   All,
@@ -22,10 +138,12 @@ impl TryFrom<&str> for ForceType {
 
 pub struct Init {
   force: Option<ForceType>,
+  ci: bool,
   directory: PathBuf,
   tauri_path: Option<PathBuf>,
   app_name: Option<String>,
   window_title: Option<String>,
+  identifier: Option<String>,
   dist_dir: Option<String>,
   dev_path: Option<String>,
 }
@@ -34,10 +152,12 @@ impl Default for Init {
   fn default() -> Self {
     Self {
       force: None,
+      ci: false,
       directory: std::env::current_dir().expect("failed to read cwd"),
       tauri_path: None,
       app_name: None,
       window_title: None,
+      identifier: None,
       dist_dir: None,
       dev_path: None,
     }
@@ -54,6 +174,12 @@ impl Init {
     self
   }
 
+  /// Skips the prompts, using the flags or the defaults.
+  pub fn ci(mut self) -> Self {
+    self.ci = true;
+    self
+  }
+
   pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
     self.directory = directory.into();
     self
@@ -74,6 +200,11 @@ impl Init {
     self
   }
 
+  pub fn identifier(mut self, identifier: impl Into<String>) -> Self {
+    self.identifier = Some(identifier.into());
+    self
+  }
+
   pub fn dist_dir(mut self, dist_dir: impl Into<String>) -> Self {
     self.dist_dir = Some(dist_dir.into());
     self
@@ -85,6 +216,243 @@ impl Init {
   }
 
   pub fn run(self) -> crate::Result<()> {
-    unimplemented!()
+    let logger = Logger::new("tauri:init");
+    let tauri_dir = self.directory.join("src-tauri");
+
+    let force_config = matches!(self.force, Some(ForceType::All) | Some(ForceType::Config));
+    let force_template = matches!(self.force, Some(ForceType::All) | Some(ForceType::Template));
+    if let Some(source) = config_sources(&tauri_dir).first() {
+      if !force_config {
+        return Err(anyhow::anyhow!(
+          "{} already exists; run `tauri init --force conf` to overwrite it",
+          source.path().display()
+        ));
+      }
+    }
+    // `--force conf` keeps the existing Cargo project
+    let scaffold_template = force_template || !tauri_dir.join("Cargo.toml").exists();
+    if !scaffold_template && !force_config {
+      return Err(anyhow::anyhow!(
+        "{} already has a Cargo project; run `tauri init --force template` to overwrite it",
+        tauri_dir.display()
+      ));
+    }
+
+    let default_app_name = self
+      .directory
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| "tauri-app".to_string());
+    let app_name = self.prompt("What is your app name?", &self.app_name, &default_app_name)?;
+    let window_title = self.prompt(
+      "What should the window title be?",
+      &self.window_title,
+      &app_name,
+    )?;
+    let identifier = self.prompt(
+      "What is your app identifier?",
+      &self.identifier,
+      &format!("com.{}.app", app_name.to_case(Case::Kebab)),
+    )?;
+    let dev_path = self.prompt(
+      "What is the URL of your dev server?",
+      &self.dev_path,
+      DEFAULT_DEV_PATH,
+    )?;
+    let dist_dir = self.prompt(
+      "Where are your web assets (relative to src-tauri) built?",
+      &self.dist_dir,
+      DEFAULT_DIST_DIR,
+    )?;
+
+    let config = config_value(&app_name, &window_title, &identifier, &dev_path, &dist_dir)?;
+
+    if scaffold_template {
+      write_template(&tauri_dir, &app_name, self.tauri_path.as_deref())?;
+      logger.log(format!("Successfully wrote {}", tauri_dir.display()));
+    }
+
+    create_dir_all(&tauri_dir)?;
+    write(
+      tauri_dir.join(JSON_CONFIG_FILE),
+      serde_json::to_string_pretty(&config)?,
+    )?;
+    logger.log(format!("Successfully wrote {}", JSON_CONFIG_FILE));
+
+    Ok(())
+  }
+
+  /// Gets the answer to a prompt, the flag value if it's set or the default on `--ci`.
+  fn prompt(&self, question: &str, value: &Option<String>, default: &str) -> crate::Result<String> {
+    if let Some(value) = value {
+      return Ok(value.clone());
+    }
+    if self.ci {
+      return Ok(default.to_string());
+    }
+    print!("{} ({}) ", question, default);
+    stdout().flush()?;
+    let mut answer = String::new();
+    stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    if answer.is_empty() {
+      Ok(default.to_string())
+    } else {
+      Ok(answer.to_string())
+    }
+  }
+}
+
+/// Builds the config with the config types and serializes it without the unset values,
+/// failing if it doesn't pass the config validation.
+fn config_value(
+  app_name: &str,
+  window_title: &str,
+  identifier: &str,
+  dev_path: &str,
+  dist_dir: &str,
+) -> crate::Result<JsonValue> {
+  let dev_path: AppUrl = serde_json::from_value(JsonValue::String(dev_path.to_string()))?;
+
+  let mut tauri = TauriConfig {
+    windows: vec![WindowConfig {
+      title: Some(window_title.to_string()),
+      width: Some(WindowSize::Unitless(800.0)),
+      height: Some(WindowSize::Unitless(600.0)),
+      ..Default::default()
+    }],
+    bundle: BundleConfig {
+      active: true,
+      targets: Some(BundleTarget::All),
+      identifier: Some(identifier.to_string()),
+      icon: Some(BUNDLE_ICONS.iter().map(|icon| icon.to_string()).collect()),
+      ..Default::default()
+    },
+    ..Default::default()
+  };
+  tauri.allowlist.features.insert("all".to_string(), true);
+
+  let config = Config {
+    config_version: Some(latest_version()),
+    package: PackageConfig {
+      product_name: Some(app_name.to_string()),
+      version: Some("0.1.0".to_string()),
+    },
+    tauri,
+    build: BuildConfig {
+      dev_path,
+      dist_dir: dist_dir.to_string(),
+      ..crate::helpers::config::default_build()
+    },
+    plugins: Default::default(),
+    features: Default::default(),
+  };
+
+  let mut value = serde_json::to_value(&config)?;
+  remove_unset(&mut value);
+  check(&value).map_err(|errors| {
+    anyhow::anyhow!(
+      "the generated config is invalid:\n{}",
+      errors
+        .into_iter()
+        .map(|(path, error)| format!("`{}`: {}", path, error))
+        .collect::<Vec<_>>()
+        .join("\n")
+    )
+  })?;
+  Ok(value)
+}
+
+/// Removes the `null` values and the objects left empty, which are the defaults.
+fn remove_unset(value: &mut JsonValue) {
+  if let JsonValue::Object(object) = value {
+    for value in object.values_mut() {
+      remove_unset(value);
+    }
+    object.retain(|_, value| match value {
+      JsonValue::Null => false,
+      JsonValue::Object(object) => !object.is_empty(),
+      _ => true,
+    });
+  } else if let JsonValue::Array(array) = value {
+    for value in array {
+      remove_unset(value);
+    }
+  }
+}
+
+/// Writes the `src-tauri` Cargo project.
+fn write_template(
+  tauri_dir: &Path,
+  app_name: &str,
+  tauri_path: Option<&Path>,
+) -> crate::Result<()> {
+  for (path, contents) in TEMPLATE_FILES {
+    let path = tauri_dir.join(path);
+    create_dir_all(path.parent().expect("template file has no parent"))?;
+    write(path, contents)?;
+  }
+
+  let tauri_dep = match tauri_path {
+    Some(tauri_path) => {
+      let tauri_path = if tauri_path.is_absolute() {
+        tauri_path.join("tauri")
+      } else {
+        // relative to the cwd, which is the parent of src-tauri
+        Path::new("..").join(tauri_path).join("tauri")
+      };
+      format!(
+        "{{ path = \"{}\" }}",
+        tauri_path.display().to_string().replace('\\', "/")
+      )
+    }
+    None => format!("{{ version = \"{}\" }}", tauri_version()),
+  };
+  // the template is shared with the JS CLI, which renders it with EJS
+  let manifest = include_str!("../../tauri.js/templates/src-tauri/Cargo.toml");
+  let start = manifest.find("<%=").expect("missing tauri dependency tag");
+  let end = manifest[start..]
+    .find("%>")
+    .expect("missing tauri dependency tag")
+    + start
+    + 2;
+  let manifest = format!("{}{}{}", &manifest[..start], tauri_dep, &manifest[end..])
+    .replace("\"app\"", &format!("\"{}\"", app_name.to_case(Case::Kebab)));
+  write(tauri_dir.join("Cargo.toml"), manifest)?;
+
+  Ok(())
+}
+
+/// The `major.minor` version of the tauri crate this CLI was built with.
+fn tauri_version() -> String {
+  let manifest: toml::Value =
+    toml::from_str(include_str!("../../../tauri/Cargo.toml")).expect("invalid tauri Cargo.toml");
+  let version = manifest["package"]["version"]
+    .as_str()
+    .expect("missing tauri version");
+  version[..version.rfind('.').unwrap_or(version.len())].to_string()
+}
+
+#[cfg(test)]
+mod test {
+  use super::config_value;
+
+  #[test]
+  // the scaffolded config passes the build validation and only has the set values
+  fn generated_config_is_valid() {
+    let config = config_value(
+      "My App",
+      "My App",
+      "com.my-app.app",
+      "http://localhost:3000",
+      "../build",
+    )
+    .unwrap();
+    assert_eq!(config["package"]["productName"], "My App");
+    assert_eq!(config["build"]["devPath"], "http://localhost:3000/");
+    assert_eq!(config["build"]["distDir"], "../build");
+    assert_eq!(config["tauri"]["windows"][0]["title"], "My App");
+    assert_eq!(config["tauri"]["bundle"]["identifier"], "com.my-app.app");
+    assert!(config["tauri"]["windows"][0].get("x").is_none());
   }
 }
@@ -1,28 +1,70 @@
 pub use anyhow::Result;
 use clap::{crate_version, load_yaml, App, AppSettings, ArgMatches};
-use std::convert::TryInto;
+use helpers::logger::{set_verbosity, DEBUG, NORMAL, QUIET, VERBOSE};
+use std::{
+  convert::TryInto,
+  path::{Path, PathBuf},
+  process::exit,
+};
+use tauri_bundler::bundle::{set_log_file, write_log};
 
 mod build;
+mod deps;
 mod dev;
 mod helpers;
 mod info;
 mod init;
+mod man;
+mod migrate;
+mod print_config;
+mod updater;
 
 pub use helpers::Logger;
 
+/// Enables the permissive config mode if `--permissive-config` was passed,
+/// and sets the project root if `--project-path` was passed.
+fn set_config_mode(matches: &ArgMatches) {
+  helpers::config::set_permissive(matches.is_present("permissive-config"));
+  if let Some(project_path) = matches.value_of("project-path") {
+    helpers::app_paths::set_project_path(project_path);
+  }
+}
+
+/// Sets the verbosity from `-v`, `-vv` and `-q`, and the log file from `--log-file`.
+/// Returns the log file path.
+fn set_logging(matches: &ArgMatches) -> Result<Option<PathBuf>> {
+  let verbosity = if matches.is_present("quiet") {
+    QUIET
+  } else {
+    (NORMAL as u64 + matches.occurrences_of("verbose")).min(DEBUG as u64) as u8
+  };
+  set_verbosity(verbosity);
+  helpers::config::set_verbose(verbosity >= VERBOSE);
+  let log_file = matches.value_of("log-file").map(PathBuf::from);
+  if let Some(log_file) = &log_file {
+    set_log_file(log_file)?;
+  }
+  Ok(log_file)
+}
+
 fn init_command(matches: &ArgMatches) -> Result<()> {
   let force = matches.value_of("force");
   let directory = matches.value_of("directory");
-  let tauri_path = matches.value_of("tauri_path");
-  let app_name = matches.value_of("app_name");
-  let window_title = matches.value_of("window_title");
-  let dist_dir = matches.value_of("dist_dir");
-  let dev_path = matches.value_of("dev_path");
+  let ci = matches.is_present("ci");
+  let tauri_path = matches.value_of("tauri-path");
+  let app_name = matches.value_of("app-name");
+  let window_title = matches.value_of("window-title");
+  let identifier = matches.value_of("identifier");
+  let dist_dir = matches.value_of("dist-dir");
+  let dev_path = matches.value_of("dev-path");
 
   let mut init_runner = init::Init::new();
   if let Some(force) = force {
     init_runner = init_runner.force(force.try_into()?);
   }
+  if ci {
+    init_runner = init_runner.ci();
+  }
   if let Some(directory) = directory {
     init_runner = init_runner.directory(directory);
   }
@@ -35,34 +77,97 @@ fn init_command(matches: &ArgMatches) -> Result<()> {
   if let Some(window_title) = window_title {
     init_runner = init_runner.window_title(window_title);
   }
+  if let Some(identifier) = identifier {
+    init_runner = init_runner.identifier(identifier);
+  }
   if let Some(dist_dir) = dist_dir {
     init_runner = init_runner.dist_dir(dist_dir);
   }
   if let Some(dev_path) = dev_path {
-    init_runner = init_runner.directory(dev_path);
+    init_runner = init_runner.dev_path(dev_path);
   }
 
   init_runner.run()
 }
 
 fn dev_command(matches: &ArgMatches) -> Result<()> {
+  set_config_mode(matches);
+  helpers::config::set_debug_profile(true);
+  helpers::events::set_json_events(matches.is_present("json-events"));
   let exit_on_panic = matches.is_present("exit-on-panic");
-  let config = matches.value_of("config");
+  let config = matches.values_of_lossy("config");
+  let features = matches.values_of_lossy("features");
+  let no_cli_validation = matches.is_present("no-cli-validation");
+  let verbose = matches.is_present("verbose");
+  let force_embed = matches.is_present("force-embed");
+  let trace_startup = matches.is_present("trace-startup");
+  let args = matches.values_of_lossy("args");
 
-  let mut dev_runner = dev::Dev::new().exit_on_panic(exit_on_panic);
+  let mut dev_runner = dev::Dev::new()
+    .exit_on_panic(exit_on_panic)
+    .no_cli_validation(no_cli_validation)
+    .verbose(verbose)
+    .force_embed(force_embed)
+    .trace_startup(trace_startup);
 
   if let Some(config) = config {
-    dev_runner = dev_runner.config(config.to_string());
+    dev_runner = dev_runner.config(config);
+  }
+  if let Some(features) = features {
+    dev_runner = dev_runner.features(features);
+  }
+  if let Some(args) = args {
+    dev_runner = dev_runner.args(args);
   }
 
   dev_runner.run()
 }
 
 fn build_command(matches: &ArgMatches) -> Result<()> {
+  set_config_mode(matches);
   let debug = matches.is_present("debug");
+  helpers::config::set_debug_profile(debug);
+  helpers::events::set_json_events(matches.is_present("json-events"));
   let verbose = matches.is_present("verbose");
-  let targets = matches.values_of_lossy("target");
-  let config = matches.value_of("config");
+  let deny_deprecated = matches.is_present("deny-deprecated");
+  let mut bundles = matches.values_of_lossy("bundles");
+  let target = match matches.values_of_lossy("target") {
+    Some(values) => {
+      // `--target` was the bundle types flag before `--bundles`
+      let (deprecated_bundles, mut triples): (Vec<String>, Vec<String>) = values
+        .into_iter()
+        .partition(|value| build::is_bundle_name(value));
+      if !deprecated_bundles.is_empty() {
+        let message = format!(
+          "passing the bundle types to `--target` is deprecated, use `--bundles {}` instead",
+          deprecated_bundles.join(" ")
+        );
+        if deny_deprecated {
+          return Err(anyhow::anyhow!(message));
+        }
+        Logger::new("tauri:build").warn(message);
+        bundles
+          .get_or_insert_with(Vec::new)
+          .extend(deprecated_bundles);
+      }
+      if triples.len() > 1 {
+        return Err(anyhow::anyhow!(
+          "only one target triple can be passed to `--target`, got {}",
+          triples.join(", ")
+        ));
+      }
+      triples.pop()
+    }
+    None => None,
+  };
+  let config = matches.values_of_lossy("config");
+  let features = matches.values_of_lossy("features");
+  let jobs = matches.value_of("jobs");
+  let fail_fast = matches.is_present("fail-fast");
+  let force_embed = matches.is_present("force-embed");
+  let trace_startup = matches.is_present("trace-startup");
+  let skip_notarize = matches.is_present("skip-notarize");
+  let print_artifacts_json = matches.is_present("print-artifacts-json");
 
   let mut build_runner = build::Build::new();
   if debug {
@@ -71,18 +176,207 @@ fn build_command(matches: &ArgMatches) -> Result<()> {
   if verbose {
     build_runner = build_runner.verbose();
   }
-  if let Some(targets) = targets {
-    build_runner = build_runner.targets(targets);
+  if let Some(bundles) = bundles {
+    build_runner = build_runner.bundles(bundles);
+  }
+  if let Some(target) = target {
+    build_runner = build_runner.target(target);
   }
   if let Some(config) = config {
-    build_runner = build_runner.config(config.to_string());
+    build_runner = build_runner.config(config);
+  }
+  if let Some(features) = features {
+    build_runner = build_runner.features(features);
+  }
+  if deny_deprecated {
+    build_runner = build_runner.deny_deprecated();
+  }
+  if let Some(jobs) = jobs {
+    let jobs = jobs
+      .parse::<usize>()
+      .ok()
+      .filter(|jobs| *jobs > 0)
+      .ok_or_else(|| anyhow::anyhow!("invalid number of jobs: {}", jobs))?;
+    build_runner = build_runner.jobs(jobs);
+  }
+  if fail_fast {
+    build_runner = build_runner.fail_fast();
+  }
+  if force_embed {
+    build_runner = build_runner.force_embed();
+  }
+  if trace_startup {
+    build_runner = build_runner.trace_startup();
+  }
+  if skip_notarize {
+    build_runner = build_runner.skip_notarize();
+  }
+  if print_artifacts_json {
+    build_runner = build_runner.print_artifacts_json();
   }
 
   build_runner.run()
 }
 
-fn info_command() -> Result<()> {
-  info::Info::new().run()
+fn print_config_command(matches: &ArgMatches) -> Result<()> {
+  set_config_mode(matches);
+  helpers::config::set_debug_profile(matches.is_present("debug"));
+  let config = matches.values_of_lossy("config");
+  let format = matches.value_of("format");
+  let path = matches.value_of("path");
+  let redact = matches.is_present("redact");
+  let resolved = matches.is_present("resolved");
+
+  let mut print_runner = print_config::PrintConfig::new();
+  if let Some(config) = config {
+    print_runner = print_runner.config(config);
+  }
+  if let Some(format) = format {
+    print_runner = print_runner.format(format.parse()?);
+  }
+  if let Some(path) = path {
+    print_runner = print_runner.path(path.to_string());
+  }
+  if redact {
+    print_runner = print_runner.redact();
+  }
+  if resolved {
+    print_runner = print_runner.resolved();
+  }
+
+  print_runner.run()
+}
+
+fn print_schema_command(matches: &ArgMatches) -> Result<()> {
+  let format = matches.value_of("format");
+  print_config::print_schema(match format {
+    Some(format) => format.parse()?,
+    None => Default::default(),
+  })
+}
+
+fn updater_generate_command(matches: &ArgMatches) -> Result<()> {
+  set_config_mode(matches);
+  let artifacts = matches.values_of("artifact");
+  let base_url = matches.value_of("base-url");
+  let version = matches.value_of("version");
+  let notes = matches.value_of("notes");
+  let pub_date = matches.value_of("pub-date");
+  let private_key_path = matches.value_of("private-key-path");
+  let output = matches.value_of("output");
+
+  let mut generate_runner = updater::Generate::new();
+  if let Some(artifacts) = artifacts {
+    generate_runner = generate_runner.artifacts(
+      artifacts
+        .map(str::parse)
+        .collect::<Result<Vec<updater::Artifact>>>()?,
+    );
+  }
+  if let Some(base_url) = base_url {
+    generate_runner = generate_runner.base_url(base_url.to_string());
+  }
+  if let Some(version) = version {
+    generate_runner = generate_runner.version(version.to_string());
+  }
+  if let Some(notes) = notes {
+    generate_runner = generate_runner.notes(notes.to_string());
+  }
+  if let Some(pub_date) = pub_date {
+    generate_runner = generate_runner.pub_date(pub_date.to_string());
+  }
+  if let Some(private_key_path) = private_key_path {
+    generate_runner = generate_runner.private_key_path(private_key_path.into());
+  }
+  if let Some(output) = output {
+    generate_runner = generate_runner.output(output.into());
+  }
+
+  generate_runner.run()
+}
+
+fn man_command(matches: &ArgMatches) -> Result<()> {
+  set_config_mode(matches);
+  let output = matches.value_of("output");
+
+  let mut man_runner = man::Man::new();
+  if let Some(output) = output {
+    man_runner = man_runner.output(output.into());
+  }
+
+  man_runner.run()
+}
+
+fn migrate_command(matches: &ArgMatches) -> Result<()> {
+  let dry_run = matches.is_present("dry-run");
+
+  let mut migrate_runner = migrate::Migrate::new();
+  if dry_run {
+    migrate_runner = migrate_runner.dry_run();
+  }
+
+  migrate_runner.run()
+}
+
+fn deps_command(matches: &ArgMatches) -> Result<()> {
+  let json = matches.is_present("json");
+  let offline = matches.is_present("offline");
+  let update = matches.is_present("update");
+
+  let mut deps_runner = deps::Deps::new();
+  if json {
+    deps_runner = deps_runner.json();
+  }
+  if offline {
+    deps_runner = deps_runner.offline();
+  }
+  if update {
+    deps_runner = deps_runner.update();
+  }
+
+  deps_runner.run()
+}
+
+fn info_command(matches: &ArgMatches) -> Result<()> {
+  set_config_mode(matches);
+  let json = matches.is_present("json");
+
+  let mut info_runner = info::Info::new();
+  if json {
+    info_runner = info_runner.json();
+  }
+
+  info_runner.run()
+}
+
+/// Reports the error of `tauri dev` or `tauri build`: as an event if the JSON events output mode is enabled,
+/// and on the log file, whose path is printed so it can be included on bug reports.
+/// It exits with the exit code of the error code, so the scripts can tell the failures apart.
+/// After a termination signal, it's left to the signal handler to exit once the children finished.
+fn finish(result: Result<()>, log_file: Option<&Path>) {
+  // the step failed because its children were interrupted, and the signal handler exits with their exit code
+  if helpers::process::interrupted() {
+    loop {
+      std::thread::park();
+    }
+  }
+  if let Err(error) = result {
+    let code = helpers::events::error_code(&error);
+    helpers::events::emit(helpers::events::Event::Error {
+      code,
+      message: format!("{:#}", error),
+    });
+    write_log(&format!("error: {:#}", error));
+    // the same format as returning the error from `main`
+    eprintln!("Error: {:?}", error);
+    if let Some(log_file) = log_file {
+      eprintln!(
+        "\nThe full log was written to `{}`; include it when reporting a bug.",
+        log_file.display()
+      );
+    }
+    exit(code.exit_code());
+  }
 }
 
 fn main() -> Result<()> {
@@ -98,11 +392,31 @@ fn main() -> Result<()> {
   if let Some(matches) = matches.subcommand_matches("init") {
     init_command(&matches)?;
   } else if let Some(matches) = matches.subcommand_matches("dev") {
-    dev_command(&matches)?;
+    let log_file = set_logging(&matches)?;
+    helpers::process::forward_signals();
+    finish(dev_command(&matches), log_file.as_deref());
   } else if let Some(matches) = matches.subcommand_matches("build") {
-    build_command(&matches)?;
-  } else if matches.subcommand_matches("info").is_some() {
-    info_command()?;
+    let log_file = set_logging(&matches)?;
+    helpers::process::forward_signals();
+    finish(build_command(&matches), log_file.as_deref());
+  } else if let Some(matches) = matches.subcommand_matches("config") {
+    if let Some(matches) = matches.subcommand_matches("print") {
+      print_config_command(&matches)?;
+    } else if let Some(matches) = matches.subcommand_matches("schema") {
+      print_schema_command(&matches)?;
+    }
+  } else if let Some(matches) = matches.subcommand_matches("updater") {
+    if let Some(matches) = matches.subcommand_matches("generate") {
+      updater_generate_command(&matches)?;
+    }
+  } else if let Some(matches) = matches.subcommand_matches("man") {
+    man_command(&matches)?;
+  } else if let Some(matches) = matches.subcommand_matches("deps") {
+    deps_command(&matches)?;
+  } else if let Some(matches) = matches.subcommand_matches("info") {
+    info_command(&matches)?;
+  } else if let Some(matches) = matches.subcommand_matches("migrate") {
+    migrate_command(&matches)?;
   }
 
   Ok(())
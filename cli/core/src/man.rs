@@ -0,0 +1,74 @@
+use crate::helpers::{app_paths::tauri_dir, config::get as get_config, Logger};
+
+use tauri_bundler::bundle::{man_pages, tauri_config::CliConfig};
+use toml_edit::{Document, Item};
+
+use std::{
+  fs::{create_dir_all, read_to_string, write},
+  path::PathBuf,
+};
+
+#[derive(Default)]
+pub struct Man {
+  output: Option<PathBuf>,
+}
+
+impl Man {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn output(mut self, output: PathBuf) -> Self {
+    self.output.replace(output);
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    let logger = Logger::new("tauri:man");
+    let config = get_config(&[])?;
+    let config_guard = config.lock().unwrap();
+    let config_ = config_guard.as_ref().unwrap();
+
+    let cli = config_.tauri.cli.as_ref().ok_or_else(|| {
+      anyhow::anyhow!("the man pages are generated from the `tauri.cli` config, which isn't set")
+    })?;
+    // the bundler renders its own copy of the CLI definition
+    let cli: CliConfig = serde_json::from_value(serde_json::to_value(cli)?)?;
+    let (name, version) = package_info(config_.package.version.clone())?;
+
+    let output = self.output.unwrap_or_else(|| PathBuf::from("."));
+    create_dir_all(&output)?;
+    for page in man_pages(&name, &version, &cli) {
+      let path = output.join(page.file_name());
+      write(&path, page.contents)?;
+      logger.log(format!("Wrote {}", path.display()));
+    }
+    Ok(())
+  }
+}
+
+/// Reads the binary name and version from the app manifest; the resolved `package.version` config wins.
+fn package_info(version: Option<String>) -> crate::Result<(String, String)> {
+  let manifest_path = tauri_dir().join("Cargo.toml");
+  let manifest = read_to_string(&manifest_path)?
+    .parse::<Document>()
+    .map_err(|e| anyhow::anyhow!("failed to parse `{}`: {}", manifest_path.display(), e))?;
+  let package = manifest
+    .as_table()
+    .get("package")
+    .and_then(Item::as_table)
+    .ok_or_else(|| anyhow::anyhow!("`{}` doesn't have a package", manifest_path.display()))?;
+  let name = package
+    .get("name")
+    .and_then(Item::as_str)
+    .ok_or_else(|| anyhow::anyhow!("`{}` doesn't have a package name", manifest_path.display()))?;
+  let version = version
+    .or_else(|| {
+      package
+        .get("version")
+        .and_then(Item::as_str)
+        .map(ToString::to_string)
+    })
+    .unwrap_or_default();
+  Ok((name.to_string(), version))
+}
@@ -0,0 +1,123 @@
+use crate::helpers::{
+  config::{config_source, ConfigFormat, ConfigSource},
+  migrations::{config_version, latest_version, migrate},
+  Logger,
+};
+
+use serde_json::Value as JsonValue;
+
+use std::fs::write;
+
+#[derive(Default)]
+pub struct Migrate {
+  dry_run: bool,
+}
+
+impl Migrate {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn dry_run(mut self) -> Self {
+    self.dry_run = true;
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    let logger = Logger::new("tauri:migrate");
+    let config_source = config_source();
+    let path = config_source.path();
+    // the config isn't deserialized, so the fields unknown to this version don't fail
+    let (source, mut value) = config_source.read()?;
+    if !value.is_object() {
+      return Err(anyhow::anyhow!("{} isn't an object", config_source.name()));
+    }
+
+    let mut changes = migrate(&mut value);
+    let version = config_version(&value);
+    if version < latest_version() {
+      value["configVersion"] = JsonValue::from(latest_version());
+      changes.push(format!(
+        "set `configVersion` from {} to {}",
+        version,
+        latest_version()
+      ));
+    }
+    if changes.is_empty() {
+      logger.log("the config is up to date");
+      return Ok(());
+    }
+
+    let migrated = match (&config_source, config_source.format()) {
+      // the other `package.json` fields are kept as they are
+      (ConfigSource::PackageJson(_), _) => {
+        let mut package: JsonValue = serde_json::from_str(&source)?;
+        package["tauri"] = value;
+        format!("{}\n", serde_json::to_string_pretty(&package)?)
+      }
+      (_, ConfigFormat::Json) => format!("{}\n", serde_json::to_string_pretty(&value)?),
+      (_, ConfigFormat::Toml) => toml::to_string_pretty(&toml::Value::try_from(&value)?)?,
+    };
+    for change in &changes {
+      logger.log(change);
+    }
+    if self.dry_run {
+      print!("{}", diff(&source, &migrated));
+      logger.log(format!("dry run: {} wasn't changed", path.display()));
+    } else {
+      write(&path, migrated)?;
+      logger.log(format!("migrated {}", path.display()));
+    }
+    Ok(())
+  }
+}
+
+/// A line diff of the config files, with `-` on the removed lines and `+` on the added lines.
+fn diff(old: &str, new: &str) -> String {
+  let old: Vec<&str> = old.lines().collect();
+  let new: Vec<&str> = new.lines().collect();
+  // the length of the longest common subsequence of old[i..] and new[j..]
+  let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      lcs[i][j] = if old[i] == new[j] {
+        lcs[i + 1][j + 1] + 1
+      } else {
+        lcs[i + 1][j].max(lcs[i][j + 1])
+      };
+    }
+  }
+
+  let mut output = String::new();
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() || j < new.len() {
+    if i < old.len() && j < new.len() && old[i] == new[j] {
+      output.push_str(&format!(" {}\n", old[i]));
+      i += 1;
+      j += 1;
+    } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+      output.push_str(&format!("-{}\n", old[i]));
+      i += 1;
+    } else {
+      output.push_str(&format!("+{}\n", new[j]));
+      j += 1;
+    }
+  }
+  output
+}
+
+#[cfg(test)]
+mod test {
+  use super::diff;
+
+  #[test]
+  fn line_diff() {
+    assert_eq!(
+      diff(
+        "{\n  \"a\": 1,\n  \"b\": 2\n}",
+        "{\n  \"a\": 1,\n  \"b\": 3\n}"
+      ),
+      " {\n   \"a\": 1,\n-  \"b\": 2\n+  \"b\": 3\n }\n"
+    );
+  }
+}
@@ -0,0 +1,264 @@
+use crate::helpers::config::{
+  get as get_config, get_unresolved, parse_merge_configs, EMBEDDED_SERVER_PORT_ENV,
+};
+
+use serde_json::Value as JsonValue;
+
+use std::{env::var, mem::take, str::FromStr};
+
+/// The mask of the redacted values.
+const REDACTED: &str = "********";
+
+/// The output format of the config.
+pub enum Format {
+  Json,
+  Toml,
+}
+
+impl Default for Format {
+  fn default() -> Self {
+    Self::Json
+  }
+}
+
+impl FromStr for Format {
+  type Err = anyhow::Error;
+
+  fn from_str(format: &str) -> crate::Result<Self> {
+    match format {
+      "json" => Ok(Self::Json),
+      "toml" => Ok(Self::Toml),
+      _ => Err(anyhow::anyhow!("unknown config format `{}`", format)),
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct PrintConfig {
+  config: Vec<String>,
+  format: Format,
+  path: Option<String>,
+  redact: bool,
+  resolved: bool,
+}
+
+impl PrintConfig {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn config(mut self, config: Vec<String>) -> Self {
+    self.config = config;
+    self
+  }
+
+  pub fn format(mut self, format: Format) -> Self {
+    self.format = format;
+    self
+  }
+
+  pub fn path(mut self, path: String) -> Self {
+    self.path.replace(path);
+    self
+  }
+
+  pub fn redact(mut self) -> Self {
+    self.redact = true;
+    self
+  }
+
+  pub fn resolved(mut self) -> Self {
+    self.resolved = true;
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    // load the config exactly like `tauri build`, keeping the paths as written unless `--resolved` is set
+    let merge_configs = parse_merge_configs(&self.config)?;
+    let mut value = if self.resolved {
+      let config = get_config(&merge_configs)?;
+      let config_guard = config.lock().unwrap();
+      let mut value = serde_json::to_value(config_guard.as_ref().unwrap())?;
+      // the port of the running `tauri dev` session, e.g. when printed by the before dev command
+      if let Some(port) = var(EMBEDDED_SERVER_PORT_ENV)
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+      {
+        value["tauri"]["embeddedServer"]["port"] = port.into();
+      }
+      value
+    } else {
+      serde_json::to_value(get_unresolved(&merge_configs)?)?
+    };
+
+    if let Some(path) = &self.path {
+      value = select(value, path)?;
+    }
+    if self.redact {
+      redact(&mut value);
+    }
+
+    print(value, &self.format, self.path.as_deref())
+  }
+}
+
+/// Prints the config JSON schema, e.g. for the editors validating `tauri.conf.toml` with a TOML schema.
+pub fn print_schema(format: Format) -> crate::Result<()> {
+  let schema: JsonValue = serde_json::from_str(include_str!("../schema.json"))?;
+  print(schema, &format, None)
+}
+
+/// Prints a config value, or the value at `path`, in the output format.
+fn print(mut value: JsonValue, format: &Format, path: Option<&str>) -> crate::Result<()> {
+  match format {
+    Format::Json => println!("{}", serde_json::to_string_pretty(&value)?),
+    Format::Toml => {
+      // TOML doesn't have null values, so the unset fields are omitted
+      remove_nulls(&mut value);
+      if !value.is_object() {
+        return Err(anyhow::anyhow!(
+          "only objects can be printed as TOML; use `--format json` to print `{}`",
+          path.unwrap_or_default()
+        ));
+      }
+      let value = toml::Value::try_from(value)?;
+      print!("{}", toml::to_string_pretty(&value)?);
+    }
+  }
+  Ok(())
+}
+
+/// Selects the value at the dot separated path, e.g. `tauri.bundle.deb`.
+fn select(value: JsonValue, path: &str) -> crate::Result<JsonValue> {
+  path.split('.').try_fold(value, |value, key| match value {
+    JsonValue::Object(mut object) => object
+      .remove(key)
+      .ok_or_else(|| anyhow::anyhow!("the config doesn't have a `{}` value", path)),
+    JsonValue::Array(mut array) => key
+      .parse::<usize>()
+      .ok()
+      .filter(|index| *index < array.len())
+      .map(|index| array.swap_remove(index))
+      .ok_or_else(|| anyhow::anyhow!("the config doesn't have a `{}` value", path)),
+    _ => Err(anyhow::anyhow!(
+      "the config doesn't have a `{}` value",
+      path
+    )),
+  })
+}
+
+/// Whether the field holds a secret, e.g. a token, a password or a signing identity.
+pub(crate) fn is_secret(key: &str) -> bool {
+  let key = key.to_lowercase();
+  ["token", "password", "secret", "identity", "privatekey"]
+    .iter()
+    .any(|secret| key.contains(secret))
+}
+
+/// Masks the values of the secret fields.
+pub(crate) fn redact(value: &mut JsonValue) {
+  match value {
+    JsonValue::Object(object) => {
+      for (key, value) in object.iter_mut() {
+        if is_secret(key) && !value.is_null() {
+          *value = JsonValue::String(REDACTED.to_string());
+        } else {
+          redact(value);
+        }
+      }
+    }
+    JsonValue::Array(array) => array.iter_mut().for_each(redact),
+    _ => {}
+  }
+}
+
+/// Removes the null values, recursively.
+fn remove_nulls(value: &mut JsonValue) {
+  match value {
+    JsonValue::Object(object) => {
+      *object = take(object)
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .collect();
+      object.values_mut().for_each(remove_nulls);
+    }
+    JsonValue::Array(array) => {
+      array.retain(|value| !value.is_null());
+      array.iter_mut().for_each(remove_nulls);
+    }
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{redact, remove_nulls, select, REDACTED};
+  use serde_json::json;
+
+  #[test]
+  fn select_path() {
+    let config = json!({
+      "tauri": {
+        "bundle": { "deb": { "depends": ["libc6"] } },
+        "windows": [{ "label": "main" }, { "label": "splash" }]
+      }
+    });
+    assert_eq!(
+      select(config.clone(), "tauri.bundle.deb").unwrap(),
+      json!({ "depends": ["libc6"] })
+    );
+    assert_eq!(
+      select(config.clone(), "tauri.windows.1.label").unwrap(),
+      json!("splash")
+    );
+    for path in &[
+      "tauri.bundle.rpm",
+      "tauri.windows.2",
+      "tauri.windows.main",
+      "tauri.windows.0.label.name",
+    ] {
+      assert_eq!(
+        select(config.clone(), path).unwrap_err().to_string(),
+        format!("the config doesn't have a `{}` value", path)
+      );
+    }
+  }
+
+  #[test]
+  fn redact_secrets() {
+    let mut config = json!({
+      "tauri": {
+        "bundle": { "macOS": { "signingIdentity": "Developer ID", "appleIdPassword": null } },
+        "updater": { "endpoints": [{ "url": "https://tauri.studio", "authToken": "abc" }] },
+        "windows": [{ "title": "app" }]
+      }
+    });
+    redact(&mut config);
+    assert_eq!(
+      config,
+      json!({
+        "tauri": {
+          "bundle": { "macOS": { "signingIdentity": REDACTED, "appleIdPassword": null } },
+          "updater": { "endpoints": [{ "url": "https://tauri.studio", "authToken": REDACTED }] },
+          "windows": [{ "title": "app" }]
+        }
+      })
+    );
+  }
+
+  #[test]
+  fn toml_omits_nulls() {
+    let mut config = json!({
+      "build": { "devPath": null, "distDir": "../dist" },
+      "tauri": { "windows": [null, { "title": null, "width": 800 }] }
+    });
+    remove_nulls(&mut config);
+    assert_eq!(
+      config,
+      json!({
+        "build": { "distDir": "../dist" },
+        "tauri": { "windows": [{ "width": 800 }] }
+      })
+    );
+  }
+}
@@ -124,7 +124,10 @@ if (!String.prototype.startsWith) {
                 callback: callback,
                 error: error,
               },
-              args
+              args,
+              {
+                __origin: window.location.origin,
+              }
             )
           )
         );
@@ -137,7 +140,10 @@ if (!String.prototype.startsWith) {
                   callback: callback,
                   error: error,
                 },
-                args
+                args,
+                {
+                  __origin: window.location.origin,
+                }
               )
             )
           );
@@ -0,0 +1,318 @@
+use crate::helpers::{config::get as get_config, Logger};
+
+use anyhow::Context;
+use minisign::{SecretKey, SecretKeyBox};
+use serde::Serialize;
+
+use std::{
+  collections::BTreeMap,
+  env::var,
+  fs::{read_to_string, write, File},
+  io::BufReader,
+  path::{Path, PathBuf},
+  str::FromStr,
+};
+
+/// The private key, as the minisign secret key file or its base64 encoding.
+const PRIVATE_KEY_ENV: &str = "TAURI_PRIVATE_KEY";
+/// The path of the private key file, if `--private-key-path` isn't set.
+const PRIVATE_KEY_PATH_ENV: &str = "TAURI_PRIVATE_KEY_PATH";
+/// The password of the private key.
+const KEY_PASSWORD_ENV: &str = "TAURI_KEY_PASSWORD";
+
+/// The platform keys of the manifest, as read by the updater.
+const TARGETS: &[&str] = &["darwin", "linux", "win32", "win64"];
+
+/// An artifact of the manifest, passed as `TARGET=PATH`.
+pub struct Artifact {
+  target: String,
+  path: PathBuf,
+}
+
+impl FromStr for Artifact {
+  type Err = anyhow::Error;
+
+  fn from_str(artifact: &str) -> crate::Result<Self> {
+    let mut parts = artifact.splitn(2, '=');
+    match (parts.next(), parts.next()) {
+      (Some(target), Some(path)) if TARGETS.contains(&target) && !path.is_empty() => Ok(Self {
+        target: target.to_string(),
+        path: PathBuf::from(path),
+      }),
+      (Some(target), Some(_)) if !TARGETS.contains(&target) => Err(anyhow::anyhow!(
+        "unknown updater target `{}`, expected one of: {}",
+        target,
+        TARGETS.join(", ")
+      )),
+      _ => Err(anyhow::anyhow!(
+        "invalid artifact `{}`, expected `TARGET=PATH`",
+        artifact
+      )),
+    }
+  }
+}
+
+/// The release of a platform.
+#[derive(Debug, Serialize)]
+struct PlatformRelease {
+  url: String,
+  signature: String,
+}
+
+/// The static JSON manifest served by the `tauri > updater > endpoints`.
+#[derive(Debug, Serialize)]
+struct Manifest {
+  version: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  notes: Option<String>,
+  pub_date: String,
+  platforms: BTreeMap<String, PlatformRelease>,
+}
+
+#[derive(Default)]
+pub struct Generate {
+  artifacts: Vec<Artifact>,
+  base_url: Option<String>,
+  version: Option<String>,
+  notes: Option<String>,
+  pub_date: Option<String>,
+  private_key_path: Option<PathBuf>,
+  output: Option<PathBuf>,
+}
+
+impl Generate {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn artifacts(mut self, artifacts: Vec<Artifact>) -> Self {
+    self.artifacts = artifacts;
+    self
+  }
+
+  pub fn base_url(mut self, base_url: String) -> Self {
+    self.base_url.replace(base_url);
+    self
+  }
+
+  pub fn version(mut self, version: String) -> Self {
+    self.version.replace(version);
+    self
+  }
+
+  pub fn notes(mut self, notes: String) -> Self {
+    self.notes.replace(notes);
+    self
+  }
+
+  pub fn pub_date(mut self, pub_date: String) -> Self {
+    self.pub_date.replace(pub_date);
+    self
+  }
+
+  pub fn private_key_path(mut self, private_key_path: PathBuf) -> Self {
+    self.private_key_path.replace(private_key_path);
+    self
+  }
+
+  pub fn output(mut self, output: PathBuf) -> Self {
+    self.output.replace(output);
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    let logger = Logger::new("tauri:updater");
+    let version = match self.version {
+      Some(version) => version,
+      None => {
+        let config = get_config(&[])?;
+        let config_guard = config.lock().unwrap();
+        let config_ = config_guard.as_ref().unwrap();
+        config_
+          .tauri
+          .bundle
+          .version
+          .clone()
+          .or_else(|| config_.package.version.clone())
+          .ok_or_else(|| {
+            anyhow::anyhow!("the config doesn't have a `package > version`; pass `--version`")
+          })?
+      }
+    };
+    let pub_date = self
+      .pub_date
+      .unwrap_or_else(|| chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    let base_url = self
+      .base_url
+      .ok_or_else(|| anyhow::anyhow!("the artifacts base URL is required"))?;
+    let secret_key = secret_key(self.private_key_path.as_deref())?;
+
+    let manifest = manifest(
+      version,
+      self.notes,
+      pub_date,
+      &base_url,
+      &self.artifacts,
+      &secret_key,
+    )?;
+    let json = serde_json::to_string_pretty(&manifest)?;
+    match self.output {
+      Some(output) => {
+        write(&output, json)?;
+        logger.log(format!("Wrote {}", output.display()));
+      }
+      None => println!("{}", json),
+    }
+    Ok(())
+  }
+}
+
+/// Reads the private key from `--private-key-path`, `TAURI_PRIVATE_KEY_PATH` or `TAURI_PRIVATE_KEY`,
+/// decrypting it with `TAURI_KEY_PASSWORD`.
+fn secret_key(path: Option<&Path>) -> crate::Result<SecretKey> {
+  let path = path
+    .map(Path::to_path_buf)
+    .or_else(|| var(PRIVATE_KEY_PATH_ENV).ok().map(PathBuf::from));
+  let key = match path {
+    Some(path) => read_to_string(&path)
+      .with_context(|| format!("failed to read the private key `{}`", path.display()))?,
+    None => var(PRIVATE_KEY_ENV).map_err(|_| {
+      anyhow::anyhow!(
+        "the private key is required; pass `--private-key-path` or set `{}`",
+        PRIVATE_KEY_ENV
+      )
+    })?,
+  };
+  decode_secret_key(&key, var(KEY_PASSWORD_ENV).ok())
+}
+
+/// Decodes the minisign secret key file, or its base64 encoding.
+fn decode_secret_key(key: &str, password: Option<String>) -> crate::Result<SecretKey> {
+  let key = key.trim();
+  let key = if key.starts_with("untrusted comment:") {
+    key.to_string()
+  } else {
+    String::from_utf8(base64::decode(key).context("the private key isn't valid base64")?)?
+  };
+  // minisign prompts for the password when it's `None`; an unencrypted key has an empty one
+  SecretKeyBox::from_string(&key)
+    .and_then(|key| key.into_secret_key(Some(password.unwrap_or_default())))
+    .map_err(|e| anyhow::anyhow!("failed to decode the private key: {}", e))
+}
+
+/// Signs the artifact, returning the base64 encoded minisign signature.
+fn sign_artifact(secret_key: &SecretKey, path: &Path) -> crate::Result<String> {
+  let file = File::open(path)
+    .with_context(|| format!("failed to read the artifact `{}`", path.display()))?;
+  let signature = minisign::sign(None, secret_key, BufReader::new(file), None, None)
+    .map_err(|e| anyhow::anyhow!("failed to sign `{}`: {}", path.display(), e))?;
+  Ok(base64::encode(signature.into_string()))
+}
+
+fn manifest(
+  version: String,
+  notes: Option<String>,
+  pub_date: String,
+  base_url: &str,
+  artifacts: &[Artifact],
+  secret_key: &SecretKey,
+) -> crate::Result<Manifest> {
+  semver::Version::parse(version.trim_start_matches('v'))
+    .with_context(|| format!("the version `{}` isn't a semver version", version))?;
+  chrono::DateTime::parse_from_rfc3339(&pub_date)
+    .with_context(|| format!("the publish date `{}` isn't a RFC 3339 date", pub_date))?;
+  if artifacts.is_empty() {
+    return Err(anyhow::anyhow!("at least one artifact is required"));
+  }
+
+  let mut platforms = BTreeMap::new();
+  for artifact in artifacts {
+    let file_name = artifact
+      .path
+      .file_name()
+      .and_then(|name| name.to_str())
+      .ok_or_else(|| anyhow::anyhow!("invalid artifact path `{}`", artifact.path.display()))?;
+    let release = PlatformRelease {
+      url: format!("{}/{}", base_url.trim_end_matches('/'), file_name),
+      signature: sign_artifact(secret_key, &artifact.path)?,
+    };
+    if platforms.insert(artifact.target.clone(), release).is_some() {
+      return Err(anyhow::anyhow!(
+        "the `{}` target has more than one artifact",
+        artifact.target
+      ));
+    }
+  }
+
+  Ok(Manifest {
+    version,
+    notes,
+    pub_date,
+    platforms,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{decode_secret_key, manifest, Artifact};
+  use tauri_updater::{
+    updater::manifest::{verify_signature, Manifest},
+    Error,
+  };
+
+  use std::fs::{create_dir_all, read, remove_dir_all, write};
+
+  #[test]
+  fn round_trip() {
+    let dir = std::env::temp_dir().join(format!("tauri-updater-manifest-{}", std::process::id()));
+    create_dir_all(&dir).unwrap();
+    let artifact = dir.join("app_1.1.0_amd64.AppImage.tar.gz");
+    write(&artifact, b"the built app").unwrap();
+
+    let keypair = minisign::KeyPair::generate_encrypted_keypair(Some(String::new())).unwrap();
+    let secret_key = base64::encode(keypair.sk.to_box(None).unwrap().into_string());
+    let pubkey = base64::encode(keypair.pk.to_box().unwrap().into_string());
+
+    let json = serde_json::to_string(
+      &manifest(
+        "1.1.0".into(),
+        Some("Fixes the crash on startup".into()),
+        "2021-02-01T12:00:00Z".into(),
+        "https://example.com/releases/",
+        &[format!("linux={}", artifact.display())
+          .parse::<Artifact>()
+          .unwrap()],
+        &decode_secret_key(&secret_key, None).unwrap(),
+      )
+      .unwrap(),
+    )
+    .unwrap();
+
+    let manifest = Manifest::parse(&json).unwrap();
+    let release = manifest.release("1.0.0", "linux").unwrap().unwrap();
+    assert_eq!(
+      release.download_url,
+      "https://example.com/releases/app_1.1.0_amd64.AppImage.tar.gz"
+    );
+    let signature = release.signature.unwrap();
+    verify_signature(&read(&artifact).unwrap(), &signature, &pubkey).unwrap();
+    assert!(verify_signature(b"a tampered app", &signature, &pubkey).is_err());
+    match manifest
+      .release("1.2.0", "linux")
+      .unwrap_err()
+      .downcast::<Error>()
+    {
+      Ok(Error::Downgrade { .. }) => {}
+      e => panic!("unexpected error {:?}", e),
+    }
+
+    remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn artifacts() {
+    assert!("win64=app.msi.zip".parse::<Artifact>().is_ok());
+    assert!("windows=app.msi.zip".parse::<Artifact>().is_err());
+    assert!("app.msi.zip".parse::<Artifact>().is_err());
+  }
+}
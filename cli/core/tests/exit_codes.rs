@@ -0,0 +1,216 @@
+// The exit codes of `tauri dev` and `tauri build`, one per failure kind.
+
+use std::{
+  fs,
+  path::Path,
+  process::{Child, Command},
+  thread::sleep,
+  time::{Duration, Instant},
+};
+
+/// The config of a project that builds, with the bundle config of the test.
+const CONFIG: &str = r#"{
+  "build": { "distDir": "../dist", "devPath": "../dist" },
+  "tauri": { "bundle": { "active": true, "identifier": "com.tauri.exit-codes" } }
+}"#;
+
+fn write_project(dir: &Path, config: &str) {
+  let tauri_dir = dir.join("src-tauri");
+  fs::create_dir_all(tauri_dir.join("src")).unwrap();
+  fs::create_dir_all(dir.join("dist")).unwrap();
+  fs::write(dir.join("dist").join("index.html"), "<html></html>").unwrap();
+  fs::write(tauri_dir.join("tauri.conf.json"), config).unwrap();
+  // `tauri build` enables the `embedded-server` feature
+  fs::write(
+    tauri_dir.join("Cargo.toml"),
+    "[package]\nname = \"app\"\nversion = \"0.1.0\"\ndescription = \"An app\"\nedition = \"2018\"\n\n[dependencies]\n\n[features]\nembedded-server = []\n",
+  )
+  .unwrap();
+  write_main(dir, "fn main() {}");
+}
+
+fn write_main(dir: &Path, main: &str) {
+  fs::write(dir.join("src-tauri").join("src").join("main.rs"), main).unwrap();
+}
+
+fn build(dir: &Path, bundles: &str) -> Option<i32> {
+  Command::new(env!("CARGO_BIN_EXE_cargo-tauri"))
+    .args(&["tauri", "build", "--bundles", bundles, "--project-path"])
+    .arg(dir)
+    .output()
+    .unwrap()
+    .status
+    .code()
+}
+
+fn dev(dir: &Path, args: &[&str]) -> Child {
+  Command::new(env!("CARGO_BIN_EXE_cargo-tauri"))
+    .args(&["tauri", "dev", "--project-path"])
+    .arg(dir)
+    .args(args)
+    .spawn()
+    .unwrap()
+}
+
+#[test]
+fn config_failure() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(dir.path(), r#"{ "build": { "unknownField": true } }"#);
+  assert_eq!(build(dir.path(), "none"), Some(3));
+
+  // the identifier is missing
+  write_project(dir.path(), "{}");
+  assert_eq!(build(dir.path(), "none"), Some(3));
+}
+
+#[test]
+fn before_command_failure() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(
+    dir.path(),
+    r#"{
+      "build": { "beforeBuildCommand": "false" },
+      "tauri": { "bundle": { "active": true, "identifier": "com.tauri.exit-codes" } }
+    }"#,
+  );
+  assert_eq!(build(dir.path(), "none"), Some(4));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn before_bundle_command_failure() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(
+    dir.path(),
+    r#"{
+      "build": { "distDir": "../dist", "devPath": "../dist", "beforeBundleCommand": "false" },
+      "tauri": { "bundle": { "active": true, "identifier": "com.tauri.exit-codes" } }
+    }"#,
+  );
+  // the hook only runs when the app is bundled
+  assert_eq!(build(dir.path(), "none"), Some(0));
+  assert_eq!(build(dir.path(), "deb"), Some(4));
+}
+
+#[test]
+fn cargo_build_failure() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(dir.path(), CONFIG);
+  write_main(dir.path(), "fn main() { let _: u32 = \"not a number\"; }");
+  assert_eq!(build(dir.path(), "none"), Some(5));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn bundle_failure() {
+  let dir = tempfile::tempdir().unwrap();
+  // the app builds, but its resource doesn't exist
+  write_project(
+    dir.path(),
+    r#"{
+      "build": { "distDir": "../dist", "devPath": "../dist" },
+      "tauri": {
+        "bundle": {
+          "active": true,
+          "identifier": "com.tauri.exit-codes",
+          "resources": ["missing.txt"]
+        }
+      }
+    }"#,
+  );
+  assert_eq!(build(dir.path(), "deb"), Some(6));
+}
+
+#[test]
+fn app_exit_code() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(dir.path(), CONFIG);
+  write_main(dir.path(), "fn main() { std::process::exit(42) }");
+  let mut dev = dev(dir.path(), &["--exit-on-panic"]);
+  assert_eq!(dev.wait().unwrap().code(), Some(42));
+}
+
+#[test]
+fn passthrough_args() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(dir.path(), CONFIG);
+  let args_path = dir.path().join("args.txt");
+  // the app records its args and the validation flag, then exits with the given code
+  let write_app = |code: i32| {
+    write_main(
+      dir.path(),
+      &format!(
+        "fn main() {{ let args: Vec<String> = std::env::args().skip(1).collect(); std::fs::write({:?}, format!(\"{{:?}} {{}}\", args, std::env::var(\"TAURI_CLI_ARGS_VALIDATION\").unwrap())).unwrap(); std::process::exit({}) }}",
+        args_path, code
+      ),
+    )
+  };
+
+  // the invalid args exit code stops `tauri dev` even without `--exit-on-panic`
+  write_app(2);
+  let mut invalid = dev(dir.path(), &["--", "--flag", "value"]);
+  assert_eq!(invalid.wait().unwrap().code(), Some(2));
+  assert_eq!(
+    fs::read_to_string(&args_path).unwrap(),
+    r#"["--flag", "value"] true"#
+  );
+
+  write_app(0);
+  let mut unvalidated = dev(dir.path(), &["--no-cli-validation", "--", "--unknown"]);
+  assert_eq!(unvalidated.wait().unwrap().code(), Some(0));
+  assert_eq!(
+    fs::read_to_string(&args_path).unwrap(),
+    r#"["--unknown"] false"#
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn signals_are_forwarded() {
+  let dir = tempfile::tempdir().unwrap();
+  write_project(dir.path(), CONFIG);
+  let pid_path = dir.path().join("app.pid");
+  write_main(
+    dir.path(),
+    &format!(
+      "fn main() {{ std::fs::write({:?}, std::process::id().to_string()).unwrap(); loop {{ std::thread::sleep(std::time::Duration::from_secs(1)); }} }}",
+      pid_path
+    ),
+  );
+  let mut dev = dev(dir.path(), &[]);
+
+  // wait for the app to compile and start
+  let start = Instant::now();
+  let app_pid = loop {
+    if let Ok(pid) = fs::read_to_string(&pid_path) {
+      if !pid.is_empty() {
+        break pid;
+      }
+    }
+    assert!(
+      start.elapsed() < Duration::from_secs(300),
+      "the app didn't start"
+    );
+    sleep(Duration::from_millis(100));
+  };
+
+  let kill = |signal: &str, pid: &str| {
+    Command::new("kill")
+      .args(&[signal, pid])
+      .status()
+      .unwrap()
+      .success()
+  };
+  assert!(kill("-TERM", &dev.id().to_string()));
+  // the app was terminated by the forwarded SIGTERM, and the CLI exits with its status
+  assert_eq!(dev.wait().unwrap().code(), Some(128 + 15));
+  // the app is reaped once the CLI exits
+  let start = Instant::now();
+  while kill("-0", &app_pid) {
+    assert!(
+      start.elapsed() < Duration::from_secs(5),
+      "the app is still running"
+    );
+    sleep(Duration::from_millis(100));
+  }
+}
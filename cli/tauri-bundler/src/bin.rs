@@ -66,6 +66,18 @@ fn run() -> crate::Result<()> {
             .multiple(true)
             .help("Which features to build"),
         )
+        .arg(
+          Arg::with_name("jobs")
+            .long("jobs")
+            .short("j")
+            .value_name("N")
+            .help("Maximum number of bundles to produce at the same time"),
+        )
+        .arg(
+          Arg::with_name("fail-fast")
+            .long("fail-fast")
+            .help("Skip the remaining bundles when one of them fails"),
+        )
         .arg(
           Arg::with_name("version")
             .long("version")
@@ -104,6 +116,17 @@ fn run() -> crate::Result<()> {
       if let Some(features) = matches.values_of_lossy("features") {
         settings_builder = settings_builder.features(features);
       }
+      if let Some(jobs) = matches.value_of("jobs") {
+        let jobs = jobs
+          .parse::<usize>()
+          .ok()
+          .filter(|jobs| *jobs > 0)
+          .ok_or_else(|| crate::Error::GenericError(format!("invalid number of jobs: {}", jobs)))?;
+        settings_builder = settings_builder.jobs(jobs);
+      }
+      if matches.is_present("fail-fast") {
+        settings_builder = settings_builder.fail_fast();
+      }
 
       settings_builder
         .build()
@@ -1,17 +1,23 @@
 mod appimage_bundle;
+mod artifacts;
 mod category;
 mod common;
 mod deb_bundle;
 mod dmg_bundle;
 mod ios_bundle;
+mod man_page;
 #[cfg(target_os = "windows")]
 mod msi_bundle;
+#[cfg(target_os = "windows")]
+mod nsis_bundle;
 mod osx_bundle;
+mod osx_sign;
 mod path_utils;
 mod platform;
 mod rpm_bundle;
 mod settings;
 pub mod tauri_config;
+mod updater_bundle;
 #[cfg(target_os = "windows")]
 mod wix;
 
@@ -21,40 +27,118 @@ use std::process::Command;
 use tauri_config::get as get_tauri_config;
 
 pub use self::{
-  common::{print_error, print_info},
+  common::{has_log_file, print_error, print_info, set_log_file, write_log},
+  man_page::{man_pages, ManPage},
+  platform::target_triple,
   settings::{PackageType, Settings, SettingsBuilder},
 };
-use common::print_finished;
+pub(crate) use common::forward_to_log;
+use common::{print_finished, BundleSummary};
+
+use std::{
+  ffi::OsStr,
+  panic::{catch_unwind, AssertUnwindSafe},
+  path::{Path, PathBuf},
+  sync::{mpsc, Arc},
+  thread,
+};
+
+/// The artifacts of a bundled package type.
+#[derive(Debug)]
+pub struct Bundle {
+  /// The package type.
+  pub package_type: PackageType,
+  /// The paths of the artifacts, moved to the output directory if one is configured.
+  pub bundle_paths: Vec<PathBuf>,
+}
 
-use std::path::PathBuf;
+/// The progress of [`bundle_project_with_events`], reported on the calling thread.
+#[derive(Debug)]
+pub enum BundleEvent {
+  /// The bundler of the package type started.
+  Started(PackageType),
+  /// The package type finished, with its final artifact paths or the reason why it wasn't bundled.
+  Finished {
+    /// The package type.
+    package_type: PackageType,
+    /// The paths of the artifacts, moved to the output directory if one is configured.
+    paths: Vec<PathBuf>,
+    /// The reason why the package type wasn't bundled.
+    error: Option<String>,
+  },
+}
 
 /// Bundles the project.
-/// Returns the list of paths where the bundles can be found.
-pub fn bundle_project(settings: Settings) -> crate::Result<Vec<PathBuf>> {
-  let mut paths = Vec::new();
-  let package_types = settings.package_types()?;
-  for package_type in &package_types {
-    let mut bundle_paths = match package_type {
-      PackageType::OsxBundle => {
-        if package_types.clone().iter().any(|&t| t == PackageType::Dmg) {
-          vec![]
-        } else {
-          osx_bundle::bundle_project(&settings)?
-        }
-      }
-      PackageType::IosBundle => ios_bundle::bundle_project(&settings)?,
-      #[cfg(target_os = "windows")]
-      PackageType::WindowsMsi => msi_bundle::bundle_project(&settings)?,
-      PackageType::Deb => deb_bundle::bundle_project(&settings)?,
-      PackageType::Rpm => rpm_bundle::bundle_project(&settings)?,
-      PackageType::AppImage => appimage_bundle::bundle_project(&settings)?,
-      PackageType::Dmg => dmg_bundle::bundle_project(&settings)?,
-    };
-    paths.append(&mut bundle_paths);
+/// Returns the bundles and the paths where their artifacts can be found.
+///
+/// The package types are bundled concurrently, bounded by [`Settings::jobs`],
+/// and a package type is only started once the ones it depends on are bundled.
+/// A failing package type doesn't stop the others unless [`Settings::is_fail_fast`] is set.
+pub fn bundle_project(settings: Settings) -> crate::Result<Vec<Bundle>> {
+  bundle_project_with_events(settings, &mut |_| {})
+}
+
+/// Bundles the project like [`bundle_project`], reporting when each package type starts and finishes.
+/// The finished events are reported once every bundle is done, since the artifacts are moved at the end.
+pub fn bundle_project_with_events(
+  settings: Settings,
+  on_event: &mut dyn FnMut(BundleEvent),
+) -> crate::Result<Vec<Bundle>> {
+  let package_types = bundle_order(&settings.package_types()?);
+  // fail on a missing license, DMG background, WiX fragment, notarization credential,
+  // updater private key or conflicting artifact names before any package is written
+  settings.license_file()?;
+  if package_types.contains(&PackageType::Dmg) {
+    settings.dmg_background()?;
+  }
+  if settings.osx_notarize()
+    && (package_types.contains(&PackageType::OsxBundle)
+      || package_types.contains(&PackageType::Dmg))
+  {
+    osx_sign::check_notarize(&settings)?;
+  }
+  #[cfg(target_os = "windows")]
+  {
+    if package_types.contains(&PackageType::WindowsMsi) {
+      settings.windows_wix_fragment_paths()?;
+    }
+  }
+  if package_types.contains(&PackageType::Updater) {
+    updater_bundle::secret_key()?;
   }
+  artifacts::check_artifact_names(&settings, &package_types)?;
 
   settings.copy_resources(settings.project_out_directory())?;
   settings.copy_binaries(settings.project_out_directory())?;
+  sign_binaries(&settings)?;
+
+  let settings = Arc::new(settings);
+  let mut bundles = run_bundlers(settings.clone(), &package_types, on_event);
+  // the artifacts are only moved once every bundle is done, since some are the input of others
+  let mut moved = Vec::new();
+  for bundle in bundles.iter_mut().filter(|bundle| bundle.error.is_none()) {
+    let paths = std::mem::take(&mut bundle.paths);
+    let result = if bundle.package_type == PackageType::Updater {
+      // the updater artifacts follow the bundles they're generated from
+      artifacts::move_updater_artifacts(paths, &moved)
+    } else {
+      artifacts::move_artifacts(&settings, bundle.package_type, paths.clone()).map(|new_paths| {
+        moved.extend(paths.into_iter().zip(new_paths.iter().cloned()));
+        new_paths
+      })
+    };
+    match result {
+      Ok(paths) => bundle.paths = paths,
+      Err(e) => bundle.error = Some(e.to_string()),
+    }
+  }
+  for bundle in &bundles {
+    on_event(BundleEvent::Finished {
+      package_type: bundle.package_type,
+      paths: bundle.paths.clone(),
+      error: bundle.error.clone(),
+    });
+  }
 
   #[cfg(windows)]
   {
@@ -70,7 +154,7 @@ pub fn bundle_project(settings: Settings) -> crate::Result<Vec<PathBuf>> {
 
       let output_str = String::from_utf8_lossy(&exempt_output.stdout).to_lowercase();
       if !output_str.contains("win32webviewhost_cw5n1h2txyewy") {
-        println!("Running Loopback command");
+        print_info("Running Loopback command")?;
         runas::Command::new("powershell")
           .args(&[
             "CheckNetIsolation LoopbackExempt -a -n=\"Microsoft.Win32WebViewHost_cw5n1h2txyewy\"",
@@ -82,11 +166,216 @@ pub fn bundle_project(settings: Settings) -> crate::Result<Vec<PathBuf>> {
     }
   }
 
-  print_finished(&paths)?;
+  print_finished(&bundles)?;
+
+  let failed: Vec<&str> = bundles
+    .iter()
+    .filter(|bundle| bundle.error.is_some())
+    .map(|bundle| bundle.package_type.short_name())
+    .collect();
+  if failed.is_empty() {
+    Ok(
+      bundles
+        .into_iter()
+        .map(|bundle| Bundle {
+          package_type: bundle.package_type,
+          bundle_paths: bundle.paths,
+        })
+        .collect(),
+    )
+  } else {
+    Err(crate::Error::GenericError(format!(
+      "failed to bundle {}",
+      failed.join(", ")
+    )))
+  }
+}
 
+/// Adds the dependencies of the package types, sorting them so every package type comes after its dependencies.
+fn bundle_order(package_types: &[PackageType]) -> Vec<PackageType> {
+  fn visit(package_type: PackageType, package_types: &[PackageType], order: &mut Vec<PackageType>) {
+    if !order.contains(&package_type) {
+      for dependency in package_type.dependencies(package_types) {
+        visit(dependency, package_types, order);
+      }
+      order.push(package_type);
+    }
+  }
+  let mut order = Vec::new();
+  for package_type in package_types {
+    visit(*package_type, package_types, &mut order);
+  }
+  order
+}
+
+/// Runs the bundlers of the package types on a pool of threads, in dependency order.
+/// Returns the summary of each package type, in the given order.
+fn run_bundlers(
+  settings: Arc<Settings>,
+  package_types: &[PackageType],
+  on_event: &mut dyn FnMut(BundleEvent),
+) -> Vec<BundleSummary> {
+  let jobs = settings.jobs().unwrap_or(package_types.len()).max(1);
+  let (tx, rx) = mpsc::channel();
+  let mut pending = package_types.to_vec();
+  // the errors are sent across threads as messages
+  let mut finished: Vec<(PackageType, Result<Vec<PathBuf>, String>)> = Vec::new();
+  let mut running = 0;
+  let mut cancelled = false;
+
+  loop {
+    if !cancelled {
+      let mut index = 0;
+      while index < pending.len() {
+        let package_type = pending[index];
+        let dependencies = package_type.dependencies(package_types);
+        let failed_dependency = dependencies.iter().find(|dependency| {
+          finished
+            .iter()
+            .any(|(finished_type, result)| finished_type == *dependency && result.is_err())
+        });
+        if let Some(dependency) = failed_dependency {
+          pending.remove(index);
+          finished.push((
+            package_type,
+            Err(format!(
+              "skipped since the {} bundle failed",
+              dependency.short_name()
+            )),
+          ));
+          continue;
+        }
+        let ready = dependencies.iter().all(|dependency| {
+          finished
+            .iter()
+            .any(|(finished_type, _)| finished_type == dependency)
+        });
+        if ready && running < jobs {
+          pending.remove(index);
+          running += 1;
+          on_event(BundleEvent::Started(package_type));
+          // the artifacts of the dependencies, e.g. the bundles the updater archives
+          let inputs: Vec<PathBuf> = finished
+            .iter()
+            .filter(|(finished_type, _)| dependencies.contains(finished_type))
+            .filter_map(|(_, result)| result.as_ref().ok())
+            .flatten()
+            .cloned()
+            .collect();
+          let settings = settings.clone();
+          let tx = tx.clone();
+          thread::spawn(move || {
+            common::set_log_prefix(package_type.short_name());
+            let result = catch_unwind(AssertUnwindSafe(|| {
+              bundle_package(&settings, package_type, &inputs).map_err(|e| e.to_string())
+            }))
+            .unwrap_or_else(|_| Err("the bundler panicked".to_string()));
+            let _ = tx.send((package_type, result));
+          });
+        } else {
+          index += 1;
+        }
+      }
+    }
+
+    if running == 0 {
+      break;
+    }
+    let (package_type, result) = rx.recv().expect("the bundler threads hung up");
+    running -= 1;
+    if let Err(e) = &result {
+      let _ = common::print_warning(&format!(
+        "failed to bundle {}: {}",
+        package_type.short_name(),
+        e
+      ));
+      if settings.is_fail_fast() {
+        cancelled = true;
+      }
+    }
+    finished.push((package_type, result));
+  }
+
+  for package_type in pending {
+    finished.push((
+      package_type,
+      Err("skipped since another bundle failed".to_string()),
+    ));
+  }
+
+  package_types
+    .iter()
+    .map(|package_type| {
+      let index = finished
+        .iter()
+        .position(|(finished_type, _)| finished_type == package_type)
+        .expect("every package type is finished");
+      let (_, result) = finished.swap_remove(index);
+      let (paths, error) = match result {
+        Ok(paths) => (paths, None),
+        Err(e) => (Vec::new(), Some(e)),
+      };
+      BundleSummary {
+        package_type: *package_type,
+        paths,
+        error,
+      }
+    })
+    .collect()
+}
+
+/// Bundles and signs a single package type, given the artifacts of the package types it depends on.
+fn bundle_package(
+  settings: &Settings,
+  package_type: PackageType,
+  inputs: &[PathBuf],
+) -> crate::Result<Vec<PathBuf>> {
+  let paths = match package_type {
+    PackageType::OsxBundle => osx_bundle::bundle_project(settings)?,
+    PackageType::IosBundle => ios_bundle::bundle_project(settings)?,
+    #[cfg(target_os = "windows")]
+    PackageType::WindowsMsi => msi_bundle::bundle_project(settings)?,
+    #[cfg(target_os = "windows")]
+    PackageType::Nsis => nsis_bundle::bundle_project(settings)?,
+    PackageType::Deb => deb_bundle::bundle_project(settings)?,
+    PackageType::Rpm => rpm_bundle::bundle_project(settings)?,
+    PackageType::AppImage => appimage_bundle::bundle_project(settings)?,
+    PackageType::Dmg => dmg_bundle::bundle_project(settings)?,
+    PackageType::Updater => updater_bundle::bundle_project(inputs)?,
+  };
+  for path in &paths {
+    if is_signable_bundle(path) {
+      settings.sign(path)?;
+    }
+  }
   Ok(paths)
 }
 
+/// Signs the app binaries and the external binaries copied to the project out directory.
+/// The main binary is signed first, followed by the other binaries and the external binaries in the configured order.
+fn sign_binaries(settings: &Settings) -> crate::Result<()> {
+  if settings.sign_command().is_none() {
+    return Ok(());
+  }
+  let mut binaries: Vec<_> = settings.binaries().iter().collect();
+  binaries.sort_by_key(|bin| !bin.main());
+  for bin in binaries {
+    settings.sign(&settings.binary_path(bin))?;
+  }
+  for (_, name) in settings.external_binaries()? {
+    settings.sign(&settings.project_out_directory().join(name))?;
+  }
+  Ok(())
+}
+
+/// Whether the bundle is an installer or executable file that should be signed.
+fn is_signable_bundle(path: &Path) -> bool {
+  matches!(
+    path.extension().and_then(OsStr::to_str),
+    Some("msi") | Some("exe") | Some("dmg") | Some("AppImage")
+  )
+}
+
 /// Check to see if there are icons in the settings struct
 pub fn check_icons(settings: &Settings) -> crate::Result<bool> {
   // make a peekable iterator of the icon_files
@@ -99,3 +388,37 @@ pub fn check_icons(settings: &Settings) -> crate::Result<bool> {
     Ok(true)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{bundle_order, PackageType};
+
+  #[test]
+  fn dependencies_are_bundled_first() {
+    assert_eq!(
+      bundle_order(&[PackageType::Dmg]),
+      vec![PackageType::OsxBundle, PackageType::Dmg]
+    );
+    assert_eq!(
+      bundle_order(&[PackageType::Dmg, PackageType::OsxBundle]),
+      vec![PackageType::OsxBundle, PackageType::Dmg]
+    );
+    assert_eq!(
+      bundle_order(&[PackageType::Rpm, PackageType::Deb]),
+      vec![PackageType::Rpm, PackageType::Deb]
+    );
+    // the updater waits for the requested bundles it can install
+    assert_eq!(
+      bundle_order(&[
+        PackageType::Updater,
+        PackageType::Deb,
+        PackageType::AppImage
+      ]),
+      vec![
+        PackageType::AppImage,
+        PackageType::Updater,
+        PackageType::Deb
+      ]
+    );
+  }
+}
@@ -7,7 +7,7 @@ use lazy_static::lazy_static;
 use std::{
   collections::BTreeMap,
   fs::{remove_dir_all, write},
-  path::PathBuf,
+  path::{Component, Path, PathBuf},
   process::{Command, Stdio},
 };
 
@@ -38,12 +38,15 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   let arch = match settings.binary_arch() {
     "x86" => "i386",
     "x86_64" => "amd64",
+    "aarch64" => "arm64",
+    "arm" => "armhf",
     other => other,
   };
   let package_dir = settings.project_out_directory().join("bundle/appimage_deb");
 
   // generate deb_folder structure
-  deb_bundle::generate_data(settings, &package_dir)?;
+  let data_dir = deb_bundle::generate_data(settings, &package_dir)?;
+  copy_custom_files(&settings.appimage_files(), &data_dir)?;
 
   let output_path = settings.project_out_directory().join("bundle/appimage");
   if output_path.exists() {
@@ -52,10 +55,11 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   std::fs::create_dir_all(output_path.clone())?;
   let app_dir_path = output_path.join(format!("{}.AppDir", settings.main_binary_name()));
   let appimage_filename = format!(
-    "{}_{}_{}.AppImage",
+    "{}_{}_{}{}.AppImage",
     settings.main_binary_name(),
     settings.version_string(),
-    arch
+    arch,
+    settings.artifact_suffix()
   );
   let appimage_path = output_path.join(&appimage_filename);
   path_utils::create(app_dir_path, true)?;
@@ -67,6 +71,9 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   sh_map.insert("app_name", settings.main_binary_name());
   sh_map.insert("app_name_uppercase", &upcase_app_name);
   sh_map.insert("appimage_filename", &appimage_filename);
+  if settings.appimage_bundle_media_framework() {
+    sh_map.insert("bundle_media_framework", "true");
+  }
 
   // initialize shell script template.
   let temp = HANDLEBARS.render("appimage", &sh_map)?;
@@ -104,3 +111,81 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   remove_dir_all(&package_dir)?;
   Ok(vec![appimage_path])
 }
+
+/// Copies the extra files to their path inside the AppImage.
+/// Fails if a path is relative, has `.` or `..` components or is already written by the bundler.
+fn copy_custom_files(files: &[(&PathBuf, &PathBuf)], data_dir: &Path) -> crate::Result<()> {
+  for (src, appimage_path) in files {
+    let mut components = appimage_path.components();
+    let valid = components.next() == Some(Component::RootDir)
+      && components.all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+      return Err(crate::Error::GenericError(format!(
+        "the AppImage path {} of {} must be absolute, without `.` or `..` components",
+        appimage_path.display(),
+        src.display()
+      )));
+    }
+    let dest = data_dir.join(appimage_path.strip_prefix("/")?);
+    if dest.exists() {
+      return Err(crate::Error::GenericError(format!(
+        "{} can't be added to {} since the AppImage already has a file at that path",
+        src.display(),
+        appimage_path.display()
+      )));
+    }
+    common::copy_file(src, &dest)?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{copy_custom_files, HANDLEBARS};
+  use std::{collections::BTreeMap, fs, path::PathBuf};
+
+  #[test]
+  fn custom_files_are_added_inside_the_appimage() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("model.bin");
+    fs::write(&src, "model").unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(data_dir.join("usr/bin")).unwrap();
+    fs::write(data_dir.join("usr/bin/app"), "app").unwrap();
+
+    let appimage_path = PathBuf::from("/usr/share/app/model.bin");
+    copy_custom_files(&[(&src, &appimage_path)], &data_dir).unwrap();
+    assert_eq!(
+      fs::read_to_string(data_dir.join("usr/share/app/model.bin")).unwrap(),
+      "model"
+    );
+
+    // the bundler already wrote the binary
+    let binary_path = PathBuf::from("/usr/bin/app");
+    assert!(copy_custom_files(&[(&src, &binary_path)], &data_dir).is_err());
+    for appimage_path in &["usr/share/relative.bin", "/usr/../../escaped.bin"] {
+      let appimage_path = PathBuf::from(appimage_path);
+      assert!(copy_custom_files(&[(&src, &appimage_path)], &data_dir).is_err());
+    }
+    assert!(!tmp.path().join("escaped.bin").exists());
+  }
+
+  #[test]
+  // the media framework is only bundled when enabled, since it adds tens of megabytes
+  fn media_framework_script() {
+    let mut data = BTreeMap::new();
+    data.insert("app_name", "app");
+    data.insert("app_name_uppercase", "APP");
+    data.insert("appimage_filename", "app_1.0.0_amd64.AppImage");
+    let script = HANDLEBARS.render("appimage", &data).unwrap();
+    assert!(!script.contains("gstreamer"));
+    assert!(script.contains("wget -q -4 -O AppRun"));
+
+    data.insert("bundle_media_framework", "true");
+    let script = HANDLEBARS.render("appimage", &data).unwrap();
+    assert!(script.contains("cp -a \"$gst_pluginsdir\"/. usr/lib/gstreamer-1.0/"));
+    assert!(script.contains("export GST_PLUGIN_SYSTEM_PATH_1_0=\"$HERE/usr/lib/gstreamer-1.0\""));
+    assert!(script.contains("exec \"$HERE/usr/bin/app\" \"$@\""));
+    assert!(!script.contains("wget -q -4 -O AppRun"));
+  }
+}
@@ -0,0 +1,317 @@
+// The artifacts are moved to the `bundle > outputDir` directory and renamed with the
+// `bundle > artifactName` template once every package type is bundled, since some bundles
+// are the input of others, e.g. the .app bundle of the DMG.
+//
+// "{name}-{version}-{target}.{ext}" => foobar-1.2.3-x86_64-unknown-linux-gnu.deb
+
+use super::{common, platform, PackageType, Settings};
+
+use std::{
+  collections::HashMap,
+  fs,
+  path::{Path, PathBuf},
+};
+
+/// The placeholders of the artifact name template.
+const PLACEHOLDERS: &[&str] = &[
+  "name", "version", "target", "arch", "profile", "format", "ext", "language",
+];
+
+/// Gets the extension of the artifacts of the package type.
+fn default_extension(package_type: PackageType) -> &'static str {
+  match package_type {
+    PackageType::OsxBundle | PackageType::IosBundle => "app",
+    #[cfg(target_os = "windows")]
+    PackageType::WindowsMsi => "msi",
+    #[cfg(target_os = "windows")]
+    PackageType::Nsis => "exe",
+    PackageType::Deb => "deb",
+    PackageType::Rpm => "rpm",
+    PackageType::AppImage => "AppImage",
+    PackageType::Dmg => "dmg",
+    PackageType::Updater => "sig",
+  }
+}
+
+/// Gets the language of each artifact of the package type, in the order the bundler generates them.
+/// Only the MSI bundler generates an artifact per language.
+fn languages(settings: &Settings, package_type: PackageType) -> Vec<Option<&str>> {
+  #[cfg(target_os = "windows")]
+  {
+    if package_type == PackageType::WindowsMsi && !settings.windows_wix_languages().is_empty() {
+      return settings
+        .windows_wix_languages()
+        .iter()
+        .map(|language| Some(language.as_str()))
+        .collect();
+    }
+  }
+  #[cfg(not(target_os = "windows"))]
+  let _ = (settings, package_type);
+  vec![None]
+}
+
+/// Renders the artifact name template, failing on unknown or unclosed placeholders.
+fn render(template: &str, vars: &HashMap<&str, String>) -> crate::Result<String> {
+  let mut name = String::new();
+  let mut rest = template;
+  while let Some(start) = rest.find('{') {
+    name.push_str(&rest[..start]);
+    let end = rest[start..].find('}').ok_or_else(|| {
+      crate::Error::GenericError(format!(
+        "unclosed placeholder on the `bundle > artifactName` template `{}`",
+        template
+      ))
+    })?;
+    let placeholder = &rest[start + 1..start + end];
+    let value = vars.get(placeholder).ok_or_else(|| {
+      crate::Error::GenericError(format!(
+        "unknown placeholder `{{{}}}` on the `bundle > artifactName` template; expected one of {}",
+        placeholder,
+        PLACEHOLDERS
+          .iter()
+          .map(|placeholder| format!("`{{{}}}`", placeholder))
+          .collect::<Vec<_>>()
+          .join(", ")
+      ))
+    })?;
+    name.push_str(value);
+    rest = &rest[start + end + 1..];
+  }
+  name.push_str(rest);
+  Ok(name)
+}
+
+/// Gets the placeholder values of an artifact.
+fn vars(
+  settings: &Settings,
+  package_type: PackageType,
+  extension: &str,
+  language: Option<&str>,
+) -> crate::Result<HashMap<&'static str, String>> {
+  let target = match settings.target_triple() {
+    Some(triple) => triple.to_string(),
+    None => platform::target_triple()?,
+  };
+  let profile = if settings.is_release_build() {
+    "release"
+  } else {
+    "debug"
+  };
+  let mut vars = HashMap::new();
+  vars.insert("name", settings.main_binary_name().replace(".exe", ""));
+  vars.insert("version", settings.version_string().to_string());
+  vars.insert("target", target);
+  vars.insert("arch", settings.binary_arch().to_string());
+  vars.insert("profile", profile.to_string());
+  vars.insert("format", package_type.short_name().to_string());
+  vars.insert("ext", extension.to_string());
+  vars.insert("language", language.unwrap_or_default().to_string());
+  Ok(vars)
+}
+
+/// Checks the artifact name template before anything is bundled,
+/// failing if it's invalid or if the artifacts of different package types get the same name.
+pub fn check_artifact_names(
+  settings: &Settings,
+  package_types: &[PackageType],
+) -> crate::Result<()> {
+  let template = match settings.artifact_name() {
+    Some(template) => template,
+    None => return Ok(()),
+  };
+  let mut names: HashMap<String, PackageType> = HashMap::new();
+  // the updater artifacts are named after the bundles they're generated from
+  for package_type in package_types
+    .iter()
+    .filter(|package_type| **package_type != PackageType::Updater)
+  {
+    for language in languages(settings, *package_type) {
+      let name = render(
+        template,
+        &vars(
+          settings,
+          *package_type,
+          default_extension(*package_type),
+          language,
+        )?,
+      )?;
+      if let Some(other) = names.insert(name.clone(), *package_type) {
+        return Err(crate::Error::GenericError(if other == *package_type {
+          format!(
+            "the {} artifacts are all named `{}`; add `{{language}}` to the `bundle > artifactName` template",
+            package_type.short_name(),
+            name
+          )
+        } else {
+          format!(
+            "the {} and {} artifacts are both named `{}`; add `{{format}}` or `{{ext}}` to the `bundle > artifactName` template",
+            other.short_name(),
+            package_type.short_name(),
+            name
+          )
+        }));
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Moves the artifacts of the package type to the output directory, renaming them with the artifact name template.
+/// Returns the final artifact paths.
+pub fn move_artifacts(
+  settings: &Settings,
+  package_type: PackageType,
+  paths: Vec<PathBuf>,
+) -> crate::Result<Vec<PathBuf>> {
+  let output_dir = settings.output_dir()?;
+  if output_dir.is_none() && settings.artifact_name().is_none() {
+    return Ok(paths);
+  }
+  let languages = languages(settings, package_type);
+  let mut moved = Vec::new();
+  for (i, path) in paths.into_iter().enumerate() {
+    let name = match settings.artifact_name() {
+      Some(template) => {
+        let extension = path
+          .extension()
+          .and_then(|extension| extension.to_str())
+          .unwrap_or_else(|| default_extension(package_type));
+        let language = languages.get(i).copied().flatten();
+        render(
+          template,
+          &vars(settings, package_type, extension, language)?,
+        )?
+      }
+      None => path
+        .file_name()
+        .expect("the artifact has a file name")
+        .to_string_lossy()
+        .to_string(),
+    };
+    let dir = match &output_dir {
+      Some(output_dir) => output_dir.clone(),
+      None => path
+        .parent()
+        .expect("the artifact has a parent directory")
+        .to_path_buf(),
+    };
+    let destination = dir.join(name);
+    if destination != path {
+      common::print_progress("Moving", &destination.display().to_string())?;
+      move_artifact(&path, &destination)?;
+    }
+    moved.push(destination);
+  }
+  Ok(moved)
+}
+
+/// Moves the updater artifacts next to the moved bundles they're generated from, named after them,
+/// e.g. `foobar.AppImage.tar.gz` follows `foobar.AppImage`. Returns the final artifact paths.
+pub fn move_updater_artifacts(
+  paths: Vec<PathBuf>,
+  moved_bundles: &[(PathBuf, PathBuf)],
+) -> crate::Result<Vec<PathBuf>> {
+  let mut moved = Vec::new();
+  for path in paths {
+    let name = path
+      .file_name()
+      .expect("the artifact has a file name")
+      .to_string_lossy()
+      .to_string();
+    let destination = moved_bundles.iter().find_map(|(from, to)| {
+      let from_name = from.file_name()?.to_string_lossy();
+      let suffix = name.strip_prefix(from_name.as_ref())?;
+      if suffix.starts_with('.') {
+        let mut to_name = to.file_name()?.to_os_string();
+        to_name.push(suffix);
+        Some(to.with_file_name(to_name))
+      } else {
+        None
+      }
+    });
+    match destination {
+      Some(destination) if destination != path => {
+        common::print_progress("Moving", &destination.display().to_string())?;
+        move_artifact(&path, &destination)?;
+        moved.push(destination);
+      }
+      _ => moved.push(path),
+    }
+  }
+  Ok(moved)
+}
+
+/// Moves a file or directory, replacing the artifact of a previous build.
+/// Falls back to copying it when the destination is on another file system.
+fn move_artifact(from: &Path, to: &Path) -> crate::Result<()> {
+  if to.is_dir() {
+    fs::remove_dir_all(to)?;
+  } else if to.exists() {
+    fs::remove_file(to)?;
+  }
+  if let Some(parent) = to.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  if fs::rename(from, to).is_err() {
+    if from.is_dir() {
+      common::copy_dir(from, to)?;
+      fs::remove_dir_all(from)?;
+    } else {
+      common::copy_file(from, to)?;
+      fs::remove_file(from)?;
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{move_updater_artifacts, render};
+  use std::{collections::HashMap, fs};
+
+  #[test]
+  fn artifact_name_template() {
+    let mut vars = HashMap::new();
+    vars.insert("name", "app".to_string());
+    vars.insert("version", "1.0.0".to_string());
+    vars.insert("target", "x86_64-unknown-linux-gnu".to_string());
+    vars.insert("ext", "deb".to_string());
+    assert_eq!(
+      render("{name}-{version}-{target}.{ext}", &vars).unwrap(),
+      "app-1.0.0-x86_64-unknown-linux-gnu.deb"
+    );
+    assert_eq!(render("app.deb", &vars).unwrap(), "app.deb");
+    assert!(render("{name}-{commit}.{ext}", &vars).is_err());
+    assert!(render("{name.{ext}", &vars).is_err());
+  }
+
+  #[test]
+  fn updater_artifacts_follow_their_bundle() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out");
+    let archive = dir.path().join("app.AppImage.tar.gz");
+    let signature = dir.path().join("app.AppImage.tar.gz.sig");
+    let other = dir.path().join("other.sig");
+    for path in &[&archive, &signature, &other] {
+      fs::write(path, b"").unwrap();
+    }
+    let moved = move_updater_artifacts(
+      vec![archive, signature, other.clone()],
+      &[(
+        dir.path().join("app.AppImage"),
+        out.join("app-1.0.0.AppImage"),
+      )],
+    )
+    .unwrap();
+    assert_eq!(
+      moved,
+      vec![
+        out.join("app-1.0.0.AppImage.tar.gz"),
+        out.join("app-1.0.0.AppImage.tar.gz.sig"),
+        other
+      ]
+    );
+    assert!(moved.iter().all(|path| path.exists()));
+  }
+}
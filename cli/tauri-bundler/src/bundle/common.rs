@@ -1,10 +1,15 @@
+use super::{tauri_config::SignCommand, PackageType};
 use crate::Settings;
+use lazy_static::lazy_static;
 use std::{
+  cell::RefCell,
   ffi::OsStr,
   fs::{self, File},
-  io::{self, BufRead, BufReader, BufWriter, Write},
+  io::{self, BufRead, BufReader, BufWriter, Read, Write},
   path::{Component, Path, PathBuf},
   process::{Command, Stdio},
+  sync::Mutex,
+  thread::{self, JoinHandle},
 };
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
@@ -142,6 +147,72 @@ pub fn resource_relpath(path: &Path) -> PathBuf {
   dest
 }
 
+thread_local! {
+  // the bundlers run on their own threads, so their output is prefixed with the package type
+  static LOG_PREFIX: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Prefixes the messages printed by the current thread with the given package type name.
+pub fn set_log_prefix(name: &str) {
+  LOG_PREFIX.with(|prefix| *prefix.borrow_mut() = Some(format!("[{}] ", name)));
+}
+
+/// Gets the prefix of the messages printed by the current thread.
+fn log_prefix() -> String {
+  LOG_PREFIX.with(|prefix| prefix.borrow().clone().unwrap_or_default())
+}
+
+lazy_static! {
+  // shared by the CLI and the bundler threads, so the lines are written in order
+  static ref LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+}
+
+/// Copies every printed message and the full output of the spawned commands to the file,
+/// each line prefixed with its timestamp.
+pub fn set_log_file(path: &Path) -> crate::Result<()> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+  *LOG_FILE.lock().unwrap() = Some(File::create(path)?);
+  Ok(())
+}
+
+/// Whether a log file was set with [`set_log_file`].
+pub fn has_log_file() -> bool {
+  LOG_FILE.lock().unwrap().is_some()
+}
+
+/// Writes the line to the log file, if there's one.
+pub fn write_log(line: &str) {
+  if let Some(file) = LOG_FILE.lock().unwrap().as_mut() {
+    // failing to log must not fail the build
+    let _ = writeln!(
+      file,
+      "[{}] {}",
+      chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+      line
+    );
+  }
+}
+
+/// Reads the lines of a child process output on a new thread, writing them to the log file
+/// and printing them to stderr if `print` is set.
+pub fn forward_to_log(output: impl Read + Send + 'static, print: bool) -> JoinHandle<()> {
+  let prefix = log_prefix();
+  thread::spawn(move || {
+    for line in BufReader::new(output).lines() {
+      let line = match line {
+        Ok(line) => line,
+        Err(_) => break,
+      };
+      write_log(&format!("{}{}", prefix, line));
+      if print {
+        eprintln!("{}{}", prefix, line);
+      }
+    }
+  })
+}
+
 /// Prints a message to stderr, in the same format that `cargo` uses,
 /// indicating that we are creating a bundle with the given filename.
 pub fn print_bundling(filename: &str) -> crate::Result<()> {
@@ -150,25 +221,112 @@ pub fn print_bundling(filename: &str) -> crate::Result<()> {
 
 /// Prints a message to stderr, in the same format that `cargo` uses,
 /// indicating that we have finished the the given bundles.
-pub fn print_finished(output_paths: &[PathBuf]) -> crate::Result<()> {
-  let pluralised = if output_paths.len() == 1 {
-    "bundle"
+pub fn print_finished(bundles: &[BundleSummary]) -> crate::Result<()> {
+  let built = bundles
+    .iter()
+    .filter(|bundle| bundle.error.is_none())
+    .count();
+  let pluralised = if built == 1 { "bundle" } else { "bundles" };
+  let failed = bundles.len() - built;
+  let msg = if failed == 0 {
+    format!("{} {}:", built, pluralised)
   } else {
-    "bundles"
+    format!("{} {}, {} failed:", built, pluralised, failed)
   };
-  let msg = format!("{} {} at:", output_paths.len(), pluralised);
   print_progress("Finished", &msg)?;
-  for path in output_paths {
-    println!("        {}", path.display());
+  // stdout is left to the machine-readable output, e.g. `--print-artifacts-json`
+  for line in summary_table(bundles) {
+    write_log(&line);
+    eprintln!("        {}", line);
   }
   Ok(())
 }
 
+/// The outcome of a package type, printed on the bundle summary.
+pub struct BundleSummary {
+  /// The package type.
+  pub package_type: PackageType,
+  /// The generated artifacts.
+  pub paths: Vec<PathBuf>,
+  /// The reason why the package type wasn't bundled.
+  pub error: Option<String>,
+}
+
+/// Formats the bundle summary as a table of the package type, the artifact size and its path.
+fn summary_table(bundles: &[BundleSummary]) -> Vec<String> {
+  let mut rows = Vec::new();
+  for bundle in bundles {
+    let name = bundle.package_type.short_name();
+    match &bundle.error {
+      Some(error) => rows.push((name, "-".to_string(), format!("failed: {}", error))),
+      None => {
+        for path in &bundle.paths {
+          rows.push((
+            name,
+            format_size(artifact_size(path)),
+            path.display().to_string(),
+          ));
+        }
+      }
+    }
+  }
+  let name_width = rows
+    .iter()
+    .map(|(name, _, _)| name.len())
+    .max()
+    .unwrap_or(0);
+  let size_width = rows
+    .iter()
+    .map(|(_, size, _)| size.len())
+    .max()
+    .unwrap_or(0);
+  rows
+    .into_iter()
+    .map(|(name, size, path)| {
+      format!(
+        "{:name_width$}  {:>size_width$}  {}",
+        name,
+        size,
+        path,
+        name_width = name_width,
+        size_width = size_width
+      )
+    })
+    .collect()
+}
+
+/// Gets the size of the artifact, adding up the files of bundles that are directories, e.g. the .app.
+fn artifact_size(path: &Path) -> u64 {
+  walkdir::WalkDir::new(path)
+    .into_iter()
+    .filter_map(Result::ok)
+    .filter_map(|entry| entry.metadata().ok())
+    .filter(|metadata| metadata.is_file())
+    .map(|metadata| metadata.len())
+    .sum()
+}
+
+/// Formats a size in bytes with a binary unit.
+fn format_size(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+  if bytes < 1024 {
+    return format!("{} B", bytes);
+  }
+  let mut size = bytes as f64 / 1024.0;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
+  }
+  format!("{:.1} {}", size, UNITS[unit])
+}
+
 /// Prints a formatted bundle progress to stderr.
-fn print_progress(step: &str, msg: &str) -> crate::Result<()> {
+pub fn print_progress(step: &str, msg: &str) -> crate::Result<()> {
   let mut output = StandardStream::stderr(ColorChoice::Always);
   let _ = output.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true));
-  write!(output, "    {}", step)?;
+  write_log(&format!("{}{} {}", log_prefix(), step, msg));
+  write!(output, "{}    {}", log_prefix(), step)?;
   output.reset()?;
   writeln!(output, " {}", msg)?;
   output.flush()?;
@@ -179,7 +337,8 @@ fn print_progress(step: &str, msg: &str) -> crate::Result<()> {
 pub fn print_warning(message: &str) -> crate::Result<()> {
   let mut output = StandardStream::stderr(ColorChoice::Always);
   let _ = output.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)).set_bold(true));
-  write!(output, "warning:")?;
+  write_log(&format!("{}warning: {}", log_prefix(), message));
+  write!(output, "{}warning:", log_prefix())?;
   output.reset()?;
   writeln!(output, " {}", message)?;
   output.flush()?;
@@ -190,7 +349,8 @@ pub fn print_warning(message: &str) -> crate::Result<()> {
 pub fn print_info(message: &str) -> crate::Result<()> {
   let mut output = StandardStream::stderr(ColorChoice::Always);
   let _ = output.set_color(ColorSpec::new().set_fg(Some(Color::Green)).set_bold(true));
-  write!(output, "info:")?;
+  write_log(&format!("{}info: {}", log_prefix(), message));
+  write!(output, "{}info:", log_prefix())?;
   output.reset()?;
   writeln!(output, " {}", message)?;
   output.flush()?;
@@ -201,6 +361,7 @@ pub fn print_info(message: &str) -> crate::Result<()> {
 pub fn print_error(error: &anyhow::Error) -> crate::Result<()> {
   let mut output = StandardStream::stderr(ColorChoice::Always);
   let _ = output.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true));
+  write_log(&format!("error: {:#}", error));
   write!(output, "error:")?;
   output.reset()?;
   let _ = output.set_color(ColorSpec::new().set_bold(true));
@@ -218,24 +379,37 @@ pub fn print_error(error: &anyhow::Error) -> crate::Result<()> {
 }
 
 pub fn execute_with_verbosity(cmd: &mut Command, settings: &Settings) -> crate::Result<()> {
-  let stdio_config = if settings.is_verbose() {
+  // the log file gets the full output, even if it isn't printed
+  let stdio_config = if settings.is_verbose() || has_log_file() {
     Stdio::piped
   } else {
     Stdio::null
   };
+  write_log(&format!("{}running {:?}", log_prefix(), cmd));
   let mut child = cmd
     .stdout(stdio_config())
     .stderr(stdio_config())
     .spawn()
     .expect("failed to spawn command");
-  if settings.is_verbose() {
-    let stdout = child.stdout.as_mut().expect("Failed to get stdout handle");
+  let stderr = child
+    .stderr
+    .take()
+    .map(|stderr| forward_to_log(stderr, settings.is_verbose()));
+  if let Some(stdout) = child.stdout.take() {
     let reader = BufReader::new(stdout);
 
+    let prefix = log_prefix();
     for line in reader.lines() {
-      println!("{}", line.expect("Failed to get line"));
+      let line = line.expect("Failed to get line");
+      write_log(&format!("{}{}", prefix, line));
+      if settings.is_verbose() {
+        eprintln!("{}{}", prefix, line);
+      }
     }
   }
+  if let Some(stderr) = stderr {
+    let _ = stderr.join();
+  }
 
   let status = child.wait()?;
   if status.success() {
@@ -245,11 +419,91 @@ pub fn execute_with_verbosity(cmd: &mut Command, settings: &Settings) -> crate::
   }
 }
 
+/// Runs the sign command on the given file.
+/// The command output is streamed to the CLI output.
+pub fn sign_file(command: &SignCommand, path: &Path) -> crate::Result<()> {
+  print_progress("Signing", &path.display().to_string())?;
+  let path = path.display().to_string();
+  let mut cmd = match command {
+    SignCommand::Shell(command) => {
+      let quoted_path = format!("\"{}\"", path);
+      let command = if command.contains("%1") {
+        command.replace("%1", &quoted_path)
+      } else {
+        format!("{} {}", command, quoted_path)
+      };
+      let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+      } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+      };
+      cmd.arg(command);
+      cmd
+    }
+    SignCommand::Args(args) => {
+      let (program, args) = args
+        .split_first()
+        .ok_or_else(|| crate::Error::GenericError("the sign command is empty".into()))?;
+      let mut cmd = Command::new(program);
+      if args.iter().any(|arg| arg.contains("%1")) {
+        cmd.args(args.iter().map(|arg| arg.replace("%1", &path)));
+      } else {
+        cmd.args(args).arg(&path);
+      }
+      cmd
+    }
+  };
+
+  let status = cmd
+    .stdout(Stdio::inherit())
+    .stderr(Stdio::inherit())
+    .status()?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(crate::Error::GenericError(format!(
+      "failed to sign {}: the sign command exited with {}",
+      path, status
+    )))
+  }
+}
+
 #[cfg(test)]
 mod tests {
-  use super::{copy_dir, create_file, is_retina, resource_relpath, symlink_file};
+  use super::{
+    copy_dir, create_file, format_size, is_retina, resource_relpath, sign_file, summary_table,
+    symlink_file, BundleSummary, SignCommand,
+  };
+  use crate::bundle::PackageType;
   use std::{io::Write, path::PathBuf};
 
+  #[cfg(not(windows))]
+  #[test]
+  fn sign_commands() {
+    let tmp = tempfile::tempdir().expect("Unable to create temp dir");
+    let path = tmp.path().join("app with spaces");
+    std::fs::write(&path, "app").expect("Unable to write file");
+
+    // the path is appended to the command, quoted
+    sign_file(&SignCommand::Shell("touch".into()), &path).expect("Failed to sign");
+    // or replaces the `%1` placeholder
+    sign_file(&SignCommand::Shell("cp %1 %1.sig".into()), &path).expect("Failed to sign");
+    assert!(tmp.path().join("app with spaces.sig").is_file());
+    sign_file(
+      &SignCommand::Args(vec!["cp".into(), "%1".into(), "%1.args-sig".into()]),
+      &path,
+    )
+    .expect("Failed to sign");
+    assert!(tmp.path().join("app with spaces.args-sig").is_file());
+
+    assert!(sign_file(&SignCommand::Args(vec!["false".into()]), &path).is_err());
+    assert!(sign_file(&SignCommand::Args(Vec::new()), &path).is_err());
+  }
+
   #[test]
   fn create_file_with_parent_dirs() {
     let tmp = tempfile::tempdir().expect("Unable to create temp dir");
@@ -314,6 +568,39 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bundle_summary_table() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let deb = tmp.path().join("app_1.0.0_amd64.deb");
+    std::fs::write(&deb, vec![0; 2048]).expect("Unable to write file");
+    let rows = summary_table(&[
+      BundleSummary {
+        package_type: PackageType::Deb,
+        paths: vec![deb.clone()],
+        error: None,
+      },
+      BundleSummary {
+        package_type: PackageType::AppImage,
+        paths: vec![],
+        error: Some("mksquashfs failed".into()),
+      },
+    ]);
+    assert_eq!(
+      rows,
+      vec![
+        format!("deb       2.0 KiB  {}", deb.display()),
+        "appimage        -  failed: mksquashfs failed".to_string(),
+      ]
+    );
+  }
+
+  #[test]
+  fn bundle_sizes() {
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+  }
+
   #[test]
   fn retina_icon_paths() {
     assert!(!is_retina("data/icons/512x512.png"));
@@ -5,12 +5,17 @@
 //     control.tar.gz          # Contains files controlling the installation:
 //         control                  # Basic package metadata
 //         md5sums                  # Checksums for files in data.tar.gz below
-//         postinst                 # Post-installation script (optional)
+//         preinst                  # Pre-installation script (optional)
+//         postinst                 # Post-installation script
 //         prerm                    # Pre-uninstallation script (optional)
+//         postrm                   # Post-uninstallation script
 //     data.tar.gz             # Contains files to be installed:
 //         usr/bin/foobar                            # Binary executable file
 //         usr/share/applications/foobar.desktop     # Desktop file (for apps)
 //         usr/share/icons/hicolor/...               # Icon files (for apps)
+//         usr/share/mime/packages/foobar.xml        # MIME types (for file associations)
+//         usr/share/doc/foobar/changelog.gz         # Changelog (optional)
+//         usr/share/man/man1/foobar.1.gz            # Man pages (optional)
 //         usr/lib/foobar/...                        # Other resource files
 //
 // For cargo-bundle, we put bundle resource files under /usr/lib/package_name/,
@@ -18,21 +23,25 @@
 // metadata, as well as generating the md5sums file.  Currently we do not
 // generate postinst or prerm files.
 
-use super::common;
+use super::{
+  common, man_pages,
+  tauri_config::{get as get_tauri_config, BundleLocalization},
+};
 use crate::Settings;
 
 use anyhow::Context;
+use handlebars::Handlebars;
 use image::{self, png::PngDecoder, GenericImageView, ImageDecoder};
 use libflate::gzip;
 use std::process::{Command, Stdio};
 use walkdir::WalkDir;
 
 use std::{
-  collections::BTreeSet,
+  collections::{BTreeMap, BTreeSet, HashMap},
   ffi::OsStr,
   fs::{self, File},
   io::{self, Write},
-  path::{Path, PathBuf},
+  path::{Component, Path, PathBuf},
 };
 
 /// Bundles the project.
@@ -41,13 +50,16 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   let arch = match settings.binary_arch() {
     "x86" => "i386",
     "x86_64" => "amd64",
+    "aarch64" => "arm64",
+    "arm" => "armhf",
     other => other,
   };
   let package_base_name = format!(
-    "{}_{}_{}",
+    "{}_{}_{}{}",
     settings.main_binary_name(),
     settings.version_string(),
-    arch
+    arch,
+    settings.artifact_suffix()
   );
   let package_name = format!("{}.deb", package_base_name);
   common::print_bundling(&package_name)?;
@@ -66,6 +78,8 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   generate_control_file(settings, arch, &control_dir, &data_dir)
     .with_context(|| "Failed to create control file")?;
   generate_md5sums(&control_dir, &data_dir).with_context(|| "Failed to create md5sums file")?;
+  generate_maintainer_scripts(&settings.debian_scripts(), &control_dir)
+    .with_context(|| "Failed to create maintainer scripts")?;
 
   // Generate `debian-binary` file; see
   // http://www.tldp.org/HOWTO/Debian-Binary-Package-Building-HOWTO/x60.html#AEN66
@@ -105,7 +119,22 @@ pub fn generate_data(settings: &Settings, package_dir: &Path) -> crate::Result<P
     .with_context(|| "Failed to copy external binaries")?;
 
   generate_icon_files(settings, &data_dir).with_context(|| "Failed to create icon files")?;
-  generate_desktop_file(settings, &data_dir).with_context(|| "Failed to create desktop file")?;
+  match settings.debian_desktop_template() {
+    Some(template) => generate_desktop_file_from_template(
+      settings,
+      template,
+      settings.debian_use_bootstrapper(),
+      &data_dir,
+    ),
+    None => generate_desktop_file(
+      settings,
+      settings.debian_use_bootstrapper(),
+      settings.debian_desktop_entries(),
+      &data_dir,
+    ),
+  }
+  .with_context(|| "Failed to create desktop file")?;
+  generate_mime_file(settings, &data_dir).with_context(|| "Failed to create MIME type file")?;
 
   let use_bootstrapper = settings.debian_use_bootstrapper();
   if use_bootstrapper {
@@ -113,6 +142,22 @@ pub fn generate_data(settings: &Settings, package_dir: &Path) -> crate::Result<P
       .with_context(|| "Failed to generate bootstrap file")?;
   }
 
+  generate_changelog_file(settings, &data_dir)
+    .with_context(|| "Failed to create changelog file")?;
+  if let Some(license) = settings.license_file()? {
+    let dest_path = data_dir
+      .join("usr/share/doc")
+      .join(package_name(settings))
+      .join("copyright");
+    common::copy_file(&license, &dest_path).with_context(|| "Failed to copy license file")?;
+  }
+  if settings.debian_generate_man_page() {
+    generate_man_pages(settings, &data_dir).with_context(|| "Failed to create man pages")?;
+  }
+  // the extra files are copied last so they can't overwrite the files written by the bundler
+  copy_custom_files(&settings.debian_files(), &data_dir)
+    .with_context(|| "Failed to copy custom files")?;
+
   Ok(data_dir)
 }
 
@@ -178,38 +223,339 @@ exit 0",
 }
 
 /// Generate the application desktop file and store it under the `data_dir`.
-fn generate_desktop_file(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+/// The extra entries are merged over the generated keys.
+pub fn generate_desktop_file(
+  settings: &Settings,
+  use_bootstrapper: bool,
+  extra_entries: Option<&HashMap<String, String>>,
+  data_dir: &Path,
+) -> crate::Result<()> {
   let bin_name = settings.main_binary_name();
-  let desktop_file_name = format!("{}.desktop", bin_name);
-  let desktop_file_path = data_dir
-    .join("usr/share/applications")
-    .join(desktop_file_name);
-  let file = &mut common::create_file(&desktop_file_path)?;
   // For more information about the format of this file, see
   // https://developer.gnome.org/integration-guide/stable/desktop-files.html.en
-  writeln!(file, "[Desktop Entry]")?;
-  writeln!(file, "Encoding=UTF-8")?;
+  let mut entries = BTreeMap::new();
+  entries.insert("Encoding".to_string(), "UTF-8".to_string());
   if let Some(category) = settings.app_category() {
-    writeln!(file, "Categories={}", category.gnome_desktop_categories())?;
+    entries.insert(
+      "Categories".to_string(),
+      category.gnome_desktop_categories().to_string(),
+    );
   }
   if !settings.short_description().is_empty() {
-    writeln!(file, "Comment={}", settings.short_description())?;
+    entries.insert(
+      "Comment".to_string(),
+      settings.short_description().to_string(),
+    );
   }
-  let use_bootstrapper = settings.debian_use_bootstrapper();
-  writeln!(
-    file,
-    "Exec={}",
+  entries.insert("Exec".to_string(), desktop_exec(settings, use_bootstrapper));
+  entries.insert("Icon".to_string(), bin_name.to_string());
+  let mime_types = desktop_mime_types(settings);
+  if !mime_types.is_empty() {
+    entries.insert("MimeType".to_string(), mime_types);
+  }
+  entries.insert("Name".to_string(), settings.bundle_name().to_string());
+  entries.insert("Terminal".to_string(), "false".to_string());
+  entries.insert("Type".to_string(), "Application".to_string());
+  entries.insert("Version".to_string(), settings.version_string().to_string());
+  entries.extend(localized_desktop_entries(&settings.localization()));
+
+  let contents = desktop_file_contents(entries, extra_entries);
+  write_desktop_file(settings, &contents, data_dir)
+}
+
+/// The `[Desktop Entry]` group of the entries, with the extra entries merged over them.
+fn desktop_file_contents(
+  mut entries: BTreeMap<String, String>,
+  extra_entries: Option<&HashMap<String, String>>,
+) -> String {
+  if let Some(extra_entries) = extra_entries {
+    for (key, value) in extra_entries {
+      entries.insert(key.clone(), value.clone());
+    }
+  }
+
+  let mut contents = String::from("[Desktop Entry]\n");
+  for (key, value) in entries {
+    contents.push_str(&format!("{}={}\n", key, value));
+  }
+  contents
+}
+
+/// Renders the desktop file template and stores it under the `data_dir`.
+pub fn generate_desktop_file_from_template(
+  settings: &Settings,
+  template: &Path,
+  use_bootstrapper: bool,
+  data_dir: &Path,
+) -> crate::Result<()> {
+  let mut handlebars = Handlebars::new();
+  handlebars
+    .register_template_file("desktop", template)
+    .map_err(|e| {
+      crate::Error::GenericError(format!(
+        "failed to read the desktop template {}: {}",
+        template.display(),
+        e
+      ))
+    })?;
+
+  let mut data = BTreeMap::new();
+  data.insert(
+    "categories",
+    settings
+      .app_category()
+      .map(|category| category.gnome_desktop_categories().to_string())
+      .unwrap_or_default(),
+  );
+  data.insert("comment", settings.short_description().to_string());
+  data.insert("exec", desktop_exec(settings, use_bootstrapper));
+  data.insert("icon", settings.main_binary_name().to_string());
+  data.insert("mime_type", desktop_mime_types(settings));
+  data.insert("name", settings.bundle_name().to_string());
+  data.insert(
+    "localized_entries",
+    localized_desktop_entries(&settings.localization())
+      .into_iter()
+      .map(|(key, value)| format!("{}={}", key, value))
+      .collect::<Vec<_>>()
+      .join("\n"),
+  );
+
+  let contents = handlebars.render("desktop", &data)?;
+  write_desktop_file(settings, &contents, data_dir)
+}
+
+/// Converts a BCP 47 like locale to the POSIX form of the desktop files and RPM spec files, e.g. `pt-BR` to `pt_BR`.
+pub fn posix_locale(locale: &str) -> String {
+  locale.replace('-', "_")
+}
+
+/// Returns the `Name[xx]` and `Comment[xx]` entries of the localized bundle names and comments.
+/// The locales without a name or comment use the default entries.
+fn localized_desktop_entries(
+  localization: &BTreeMap<&str, &BundleLocalization>,
+) -> Vec<(String, String)> {
+  let mut entries = Vec::new();
+  for (locale, localization) in localization {
+    let locale = posix_locale(locale);
+    if let Some(name) = &localization.product_name {
+      entries.push((format!("Name[{}]", locale), name.clone()));
+    }
+    let comment = localization
+      .comments
+      .as_ref()
+      .or_else(|| localization.short_description.as_ref());
+    if let Some(comment) = comment {
+      entries.push((format!("Comment[{}]", locale), comment.clone()));
+    }
+  }
+  entries
+}
+
+/// Validates the desktop file and writes it to `usr/share/applications` under the `data_dir`.
+fn write_desktop_file(settings: &Settings, contents: &str, data_dir: &Path) -> crate::Result<()> {
+  check_desktop_file(contents)?;
+
+  let desktop_file_path = data_dir
+    .join("usr/share/applications")
+    .join(format!("{}.desktop", settings.main_binary_name()));
+  let file = &mut common::create_file(&desktop_file_path)?;
+  file.write_all(contents.as_bytes())?;
+  Ok(())
+}
+
+/// Fails with the file contents if the `[Desktop Entry]` group misses a mandatory key.
+fn check_desktop_file(contents: &str) -> crate::Result<()> {
+  let mut group = None;
+  let mut keys = BTreeSet::new();
+  for line in contents.lines().map(str::trim) {
+    if line.starts_with('[') && line.ends_with(']') {
+      group = Some(line);
+    } else if group == Some("[Desktop Entry]") {
+      if let Some(index) = line.find('=') {
+        if !line[index + 1..].trim().is_empty() {
+          keys.insert(line[..index].trim());
+        }
+      }
+    }
+  }
+  let missing: Vec<&str> = ["Type", "Name", "Exec"]
+    .iter()
+    .copied()
+    .filter(|key| !keys.contains(key))
+    .collect();
+  if !missing.is_empty() {
+    return Err(crate::Error::GenericError(format!(
+      "the desktop file is missing the {} keys of the [Desktop Entry] group:\n{}",
+      missing.join(", "),
+      contents
+    )));
+  }
+  Ok(())
+}
+
+/// Returns the `Exec` value of the desktop file, with the field code of the files or URLs the app opens.
+pub fn desktop_exec(settings: &Settings, use_bootstrapper: bool) -> String {
+  let bin_name = settings.main_binary_name();
+  format!(
+    "{}{}",
     if use_bootstrapper {
       format!("__{}-bootstrapper", bin_name)
     } else {
       bin_name.to_string()
+    },
+    // deep links need the URLs, which also include the local files as `file://` URLs
+    if !settings.deep_link_schemes().is_empty() {
+      " %U"
+    } else if !settings.file_associations().is_empty() {
+      " %F"
+    } else {
+      ""
     }
+  )
+}
+
+/// Returns the `MimeType` value of the desktop file: the file association MIME types and the deep link scheme handlers.
+pub fn desktop_mime_types(settings: &Settings) -> String {
+  settings
+    .file_associations()
+    .iter()
+    .map(|association| association.mime_type())
+    .chain(
+      settings
+        .deep_link_schemes()
+        .iter()
+        .map(|scheme| format!("x-scheme-handler/{}", scheme)),
+    )
+    .map(|mime_type| format!("{};", mime_type))
+    .collect()
+}
+
+/// Generates the shared-mime-info package describing the app's file associations,
+/// along with the icons of the associated MIME types.
+pub fn generate_mime_file(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+  let file_associations = settings.file_associations();
+  if file_associations.is_empty() {
+    return Ok(());
+  }
+
+  let mime_file_path = data_dir
+    .join("usr/share/mime/packages")
+    .join(format!("{}.xml", settings.main_binary_name()));
+  let file = &mut common::create_file(&mime_file_path)?;
+  // For more information about the format of this file, see
+  // https://specifications.freedesktop.org/shared-mime-info-spec/shared-mime-info-spec-latest.html
+  writeln!(file, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+  writeln!(
+    file,
+    "<mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">"
   )?;
-  writeln!(file, "Icon={}", bin_name)?;
-  writeln!(file, "Name={}", settings.bundle_name())?;
-  writeln!(file, "Terminal=false")?;
-  writeln!(file, "Type=Application")?;
-  writeln!(file, "Version={}", settings.version_string())?;
+  for association in file_associations {
+    let mime_type = association.mime_type();
+    writeln!(file, "  <mime-type type=\"{}\">", mime_type)?;
+    writeln!(
+      file,
+      "    <comment>{}</comment>",
+      association
+        .description
+        .as_ref()
+        .unwrap_or(&association.name)
+    )?;
+    for ext in &association.ext {
+      writeln!(file, "    <glob pattern=\"*.{}\"/>", ext)?;
+    }
+    writeln!(file, "  </mime-type>")?;
+
+    if let Some(icon) = &association.icon {
+      let icon_path = PathBuf::from(icon);
+      let extension = icon_path
+        .extension()
+        .and_then(OsStr::to_str)
+        .unwrap_or("png");
+      let size_dir = if extension == "svg" {
+        "scalable"
+      } else {
+        "256x256"
+      };
+      // icons of MIME types are looked up by the MIME type name, with `/` replaced by `-`
+      let dest_path = data_dir
+        .join("usr/share/icons/hicolor")
+        .join(size_dir)
+        .join("mimetypes")
+        .join(format!("{}.{}", mime_type.replace('/', "-"), extension));
+      common::copy_file(&icon_path, &dest_path)?;
+    }
+  }
+  writeln!(file, "</mime-info>")?;
+  Ok(())
+}
+
+/// Returns the debian package name.
+pub fn package_name(settings: &Settings) -> String {
+  str::replace(settings.bundle_name(), " ", "-").to_ascii_lowercase()
+}
+
+/// Compresses the changelog to `usr/share/doc/{package}/changelog.gz`.
+fn generate_changelog_file(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+  if let Some(changelog_path) = settings.debian_changelog() {
+    let mut src_file = File::open(changelog_path)?;
+    let dest_path = data_dir
+      .join("usr/share/doc")
+      .join(package_name(settings))
+      .join("changelog.gz");
+    let dest_file = common::create_file(&dest_path)?;
+    let mut gzip_encoder = gzip::Encoder::new(dest_file)?;
+    io::copy(&mut src_file, &mut gzip_encoder)?;
+    let mut dest_file = gzip_encoder.finish().into_result()?;
+    dest_file.flush()?;
+  }
+  Ok(())
+}
+
+/// Generates the gzipped man pages of the CLI definition and stores them under `usr/share/man/man1`.
+fn generate_man_pages(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+  let cli = get_tauri_config()?.tauri.cli.ok_or_else(|| {
+    crate::Error::GenericError(
+      "`tauri.bundle.deb.generateManPage` requires the `tauri.cli` config".to_string(),
+    )
+  })?;
+  for page in man_pages(settings.main_binary_name(), settings.version_string(), &cli) {
+    let dest_path = data_dir
+      .join("usr/share/man/man1")
+      .join(format!("{}.gz", page.file_name()));
+    let dest_file = common::create_file(&dest_path)?;
+    let mut gzip_encoder = gzip::Encoder::new(dest_file)?;
+    gzip_encoder.write_all(page.contents.as_bytes())?;
+    let mut dest_file = gzip_encoder.finish().into_result()?;
+    dest_file.flush()?;
+  }
+  Ok(())
+}
+
+/// Copies the extra files to their install path.
+/// Fails if an install path is relative, leaves the package with `..` or is already written by the bundler.
+fn copy_custom_files(files: &[(&PathBuf, &PathBuf)], data_dir: &Path) -> crate::Result<()> {
+  for (src, install_path) in files {
+    let mut components = install_path.components();
+    let valid = components.next() == Some(Component::RootDir)
+      && components.all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+      return Err(crate::Error::GenericError(format!(
+        "the install path {} of {} must be absolute, without `.` or `..` components",
+        install_path.display(),
+        src.display()
+      )));
+    }
+    let dest = data_dir.join(install_path.strip_prefix("/")?);
+    if dest.exists() {
+      return Err(crate::Error::GenericError(format!(
+        "{} can't be installed to {} since the bundle already has a file at that path",
+        src.display(),
+        install_path.display()
+      )));
+    }
+    common::copy_file(src, &dest)?;
+  }
   Ok(())
 }
 
@@ -224,18 +570,21 @@ fn generate_control_file(
   // https://www.debian.org/doc/debian-policy/ch-controlfields.html
   let dest_path = control_dir.join("control");
   let mut file = common::create_file(&dest_path)?;
-  writeln!(
-    &mut file,
-    "Package: {}",
-    str::replace(settings.bundle_name(), " ", "-").to_ascii_lowercase()
-  )?;
+  writeln!(&mut file, "Package: {}", package_name(settings))?;
   writeln!(&mut file, "Version: {}", settings.version_string())?;
+  if let Some(section) = settings.debian_section() {
+    writeln!(&mut file, "Section: {}", section)?;
+  }
+  if let Some(priority) = settings.debian_priority() {
+    writeln!(&mut file, "Priority: {}", priority)?;
+  }
   writeln!(&mut file, "Architecture: {}", arch)?;
   writeln!(&mut file, "Installed-Size: {}", total_dir_size(data_dir)?)?;
-  let authors = settings
-    .authors_comma_separated()
-    .unwrap_or_else(String::new);
-  writeln!(&mut file, "Maintainer: {}", authors)?;
+  let maintainer = match settings.publisher() {
+    Some(publisher) => publisher.to_string(),
+    None => settings.authors_comma_separated().unwrap_or_default(),
+  };
+  writeln!(&mut file, "Maintainer: {}", maintainer)?;
   if !settings.homepage_url().is_empty() {
     writeln!(&mut file, "Homepage: {}", settings.homepage_url())?;
   }
@@ -264,6 +613,91 @@ fn generate_control_file(
   Ok(())
 }
 
+/// The snippet generated by the bundler on the `postinst` and `postrm` scripts,
+/// refreshing the caches of the installed desktop file, MIME types and icons.
+const REFRESH_CACHES_SNIPPET: &str = "\
+if command -v update-desktop-database >/dev/null 2>&1; then
+  update-desktop-database -q /usr/share/applications || true
+fi
+if command -v update-mime-database >/dev/null 2>&1; then
+  update-mime-database /usr/share/mime || true
+fi
+if command -v gtk-update-icon-cache >/dev/null 2>&1; then
+  gtk-update-icon-cache -q -t -f /usr/share/icons/hicolor || true
+fi
+";
+
+/// Merges the bundler snippet of a maintainer script with the user script.
+/// The snippet runs first, under the shebang of the user script.
+/// Returns `None` if the package doesn't need the script.
+fn merge_maintainer_script(
+  name: &str,
+  user_script: Option<&Path>,
+) -> crate::Result<Option<String>> {
+  let snippet = match name {
+    "postinst" | "postrm" => Some(REFRESH_CACHES_SNIPPET),
+    _ => None,
+  };
+  let user_script = match user_script {
+    Some(path) => {
+      let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read the {} script {}", name, path.display()))?;
+      if !contents.starts_with("#!") {
+        return Err(crate::Error::GenericError(format!(
+          "the {} script {} must start with a shebang, e.g. `#!/bin/sh`",
+          name,
+          path.display()
+        )));
+      }
+      Some(contents)
+    }
+    None => None,
+  };
+
+  let (shebang, body) = match user_script {
+    Some(ref contents) => {
+      let mut lines = contents.splitn(2, '\n');
+      (
+        lines.next().unwrap_or_default(),
+        lines.next().unwrap_or_default(),
+      )
+    }
+    None if snippet.is_some() => ("#!/bin/sh", ""),
+    None => return Ok(None),
+  };
+  let mut script = format!("{}\n", shebang);
+  if let Some(snippet) = snippet {
+    script.push_str("# generated by the bundler\n");
+    script.push_str(snippet);
+  }
+  if !body.is_empty() {
+    if snippet.is_some() {
+      script.push('\n');
+    }
+    script.push_str(body);
+  }
+  Ok(Some(script))
+}
+
+/// Writes the maintainer scripts to the `control_dir`, executable.
+fn generate_maintainer_scripts(
+  scripts: &[(&str, Option<&PathBuf>)],
+  control_dir: &Path,
+) -> crate::Result<()> {
+  for (name, user_script) in scripts {
+    if let Some(script) = merge_maintainer_script(name, user_script.map(|path| path.as_path()))? {
+      let path = control_dir.join(name);
+      create_file_with_data(&path, &script)?;
+      #[cfg(unix)]
+      {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+      }
+    }
+  }
+  Ok(())
+}
+
 /// Create an `md5sums` file in the `control_dir` containing the MD5 checksums
 /// for each file within the `data_dir`.
 fn generate_md5sums(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
@@ -293,13 +727,13 @@ fn generate_md5sums(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
 
 /// Copy the bundle's resource files into an appropriate directory under the
 /// `data_dir`.
-fn transfer_resource_files(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
+pub fn transfer_resource_files(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
   let resource_dir = data_dir.join("usr/lib").join(settings.main_binary_name());
   settings.copy_resources(&resource_dir)
 }
 
 /// Generate the icon files and store them under the `data_dir`.
-fn generate_icon_files(settings: &Settings, data_dir: &PathBuf) -> crate::Result<()> {
+pub fn generate_icon_files(settings: &Settings, data_dir: &PathBuf) -> crate::Result<()> {
   let base_dir = data_dir.join("usr/share/icons/hicolor");
   let get_dest_path = |width: u32, height: u32, is_high_density: bool| {
     base_dir.join(format!(
@@ -427,3 +861,175 @@ fn create_archive(srcs: Vec<PathBuf>, dest: &Path) -> crate::Result<()> {
   builder.into_inner()?.flush()?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    check_desktop_file, copy_custom_files, desktop_file_contents, generate_maintainer_scripts,
+    localized_desktop_entries, tar_and_gzip_dir,
+  };
+  use crate::bundle::tauri_config::BundleLocalization;
+  use libflate::gzip;
+  use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+  };
+
+  fn read_archive(path: &Path) -> HashMap<String, (String, u32)> {
+    let decoder = gzip::Decoder::new(fs::File::open(path).unwrap()).unwrap();
+    let mut archive = tar::Archive::new(decoder);
+    let mut files = HashMap::new();
+    for entry in archive.entries().unwrap() {
+      let mut entry = entry.unwrap();
+      let name = entry.path().unwrap().to_string_lossy().to_string();
+      let mode = entry.header().mode().unwrap();
+      let mut contents = String::new();
+      entry.read_to_string(&mut contents).unwrap();
+      files.insert(name, (contents, mode));
+    }
+    files
+  }
+
+  #[test]
+  fn control_archive_contains_merged_scripts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let postinst = tmp.path().join("postinst.sh");
+    fs::write(&postinst, "#!/bin/bash\ngroupadd -f myapp\n").unwrap();
+    let prerm = tmp.path().join("prerm.sh");
+    fs::write(
+      &prerm,
+      "#!/bin/sh\nsystemctl --global disable myapp.service\n",
+    )
+    .unwrap();
+
+    let control_dir = tmp.path().join("control");
+    fs::create_dir_all(&control_dir).unwrap();
+    generate_maintainer_scripts(
+      &[
+        ("preinst", None),
+        ("postinst", Some(&postinst)),
+        ("prerm", Some(&prerm)),
+        ("postrm", None),
+      ],
+      &control_dir,
+    )
+    .unwrap();
+
+    let files = read_archive(&tar_and_gzip_dir(&control_dir).unwrap());
+    assert!(!files.contains_key("preinst"));
+
+    let (postinst, _mode) = &files["postinst"];
+    assert!(postinst.starts_with("#!/bin/bash\n# generated by the bundler\n"));
+    let snippet_index = postinst.find("update-desktop-database").unwrap();
+    let user_index = postinst.find("groupadd -f myapp").unwrap();
+    assert!(snippet_index < user_index);
+
+    let (prerm, _mode) = &files["prerm"];
+    assert_eq!(
+      prerm,
+      "#!/bin/sh\nsystemctl --global disable myapp.service\n"
+    );
+
+    let (postrm, _mode) = &files["postrm"];
+    assert!(postrm.starts_with("#!/bin/sh\n# generated by the bundler\n"));
+    assert!(postrm.contains("gtk-update-icon-cache"));
+
+    #[cfg(unix)]
+    for name in &["postinst", "prerm", "postrm"] {
+      assert_eq!(files[*name].1 & 0o777, 0o755);
+    }
+  }
+
+  #[test]
+  fn desktop_file_entries() {
+    let mut entries = BTreeMap::new();
+    entries.insert("Exec".to_string(), "app".to_string());
+    entries.insert("Name".to_string(), "App".to_string());
+    entries.insert("Type".to_string(), "Application".to_string());
+    let mut extra_entries = HashMap::new();
+    extra_entries.insert("StartupWMClass".to_string(), "app-main".to_string());
+    extra_entries.insert("Name".to_string(), "My App".to_string());
+    // the extra entries are merged over the generated keys
+    let contents = desktop_file_contents(entries, Some(&extra_entries));
+    assert_eq!(
+      contents,
+      "[Desktop Entry]\nExec=app\nName=My App\nStartupWMClass=app-main\nType=Application\n"
+    );
+    assert!(check_desktop_file(&contents).is_ok());
+
+    // the keys must be set on the `[Desktop Entry]` group
+    let error = check_desktop_file(
+      "[Desktop Entry]\nType=Application\nName=\n\n[Desktop Action new]\nExec=app --new\n",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("missing the Name, Exec keys of the [Desktop Entry] group"));
+  }
+
+  #[test]
+  fn custom_files_are_installed_inside_the_package() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("config.toml");
+    fs::write(&src, "key = 1\n").unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(data_dir.join("usr/bin")).unwrap();
+    fs::write(data_dir.join("usr/bin/app"), "app").unwrap();
+
+    let install_path = PathBuf::from("/etc/app/config.toml");
+    copy_custom_files(&[(&src, &install_path)], &data_dir).unwrap();
+    assert_eq!(
+      fs::read_to_string(data_dir.join("etc/app/config.toml")).unwrap(),
+      "key = 1\n"
+    );
+
+    // the bundler already wrote the binary
+    let binary_path = PathBuf::from("/usr/bin/app");
+    assert!(copy_custom_files(&[(&src, &binary_path)], &data_dir).is_err());
+    for install_path in &["etc/app/relative.toml", "/usr/../../escaped.toml"] {
+      let install_path = PathBuf::from(install_path);
+      assert!(copy_custom_files(&[(&src, &install_path)], &data_dir).is_err());
+    }
+    assert!(!tmp.path().join("escaped.toml").exists());
+  }
+
+  #[test]
+  fn user_script_requires_shebang() {
+    let tmp = tempfile::tempdir().unwrap();
+    let postinst = tmp.path().join("postinst.sh");
+    fs::write(&postinst, "groupadd -f myapp\n").unwrap();
+    let control_dir = tmp.path().join("control");
+    assert!(generate_maintainer_scripts(&[("postinst", Some(&postinst))], &control_dir).is_err());
+  }
+
+  #[test]
+  fn localized_desktop_entries_fall_back() {
+    let de = BundleLocalization {
+      product_name: Some("Meine App".into()),
+      short_description: Some("Eine App".into()),
+      ..Default::default()
+    };
+    let pt_br = BundleLocalization {
+      short_description: Some("Um app".into()),
+      comments: Some("Abre os arquivos".into()),
+      ..Default::default()
+    };
+    let fr = BundleLocalization {
+      long_description: Some("Une longue description".into()),
+      ..Default::default()
+    };
+    let mut localization = BTreeMap::new();
+    localization.insert("de", &de);
+    localization.insert("pt-BR", &pt_br);
+    localization.insert("fr", &fr);
+    assert_eq!(
+      localized_desktop_entries(&localization),
+      vec![
+        ("Name[de]".to_string(), "Meine App".to_string()),
+        ("Comment[de]".to_string(), "Eine App".to_string()),
+        ("Comment[pt_BR]".to_string(), "Abre os arquivos".to_string()),
+      ]
+    );
+  }
+}
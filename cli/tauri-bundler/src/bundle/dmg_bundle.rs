@@ -1,4 +1,4 @@
-use super::{common, osx_bundle};
+use super::{common, osx_sign};
 use crate::Settings;
 
 use anyhow::Context;
@@ -11,28 +11,28 @@ use std::{
 };
 
 /// Bundles the project.
+/// The .app bundle must have been generated already.
 /// Returns a vector of PathBuf that shows where the DMG was created.
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
-  // generate the .app bundle
-  osx_bundle::bundle_project(settings)?;
+  let background = settings.dmg_background()?;
 
   // get the target path
   let output_path = settings.project_out_directory().join("bundle/dmg");
   let package_base_name = format!(
-    "{}_{}_{}",
+    "{}_{}_{}{}",
     settings.main_binary_name(),
     settings.version_string(),
     match settings.binary_arch() {
       "x86_64" => "x64",
       other => other,
-    }
+    },
+    settings.artifact_suffix()
   );
   let dmg_name = format!("{}.dmg", &package_base_name);
   let dmg_path = output_path.join(&dmg_name);
 
   let bundle_name = &format!("{}.app", &package_base_name);
   let bundle_dir = settings.project_out_directory().join("bundle/osx");
-  let bundle_path = bundle_dir.join(&bundle_name.clone());
 
   let support_directory_path = output_path.join("support");
   if output_path.exists() {
@@ -77,6 +77,22 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     .output()
     .expect("Failed to chmod script");
 
+  let window_size = settings.dmg_window_size();
+  let app_position = settings.dmg_app_position();
+  let application_folder_position = settings.dmg_application_folder_position();
+  let window_width = window_size.width.to_string();
+  let window_height = window_size.height.to_string();
+  let app_x = app_position.x.to_string();
+  let app_y = app_position.y.to_string();
+  let application_folder_x = application_folder_position.x.to_string();
+  let application_folder_y = application_folder_position.y.to_string();
+  let background = background.map(|path| path.display().to_string());
+  // the bundle license takes precedence over the `osx > license` config
+  let license = match settings.license_file()? {
+    Some(path) => Some(path.display().to_string()),
+    None => settings.osx_license().map(|path| path.to_string()),
+  };
+
   let mut args = vec![
     "--volname",
     &package_base_name,
@@ -84,19 +100,24 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     "../../../../icons/icon.icns",
     "--icon",
     &bundle_name,
-    "180",
-    "170",
+    &app_x,
+    &app_y,
     "--app-drop-link",
-    "480",
-    "170",
+    &application_folder_x,
+    &application_folder_y,
     "--window-size",
-    "660",
-    "400",
+    &window_width,
+    &window_height,
     "--hide-extension",
     &bundle_name,
   ];
 
-  if let Some(license_path) = settings.osx_license() {
+  if let Some(background) = &background {
+    args.push("--background");
+    args.push(background);
+  }
+
+  if let Some(license_path) = &license {
     args.push("--eula");
     args.push(license_path);
   }
@@ -129,5 +150,8 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   })?;
 
   fs::rename(bundle_dir.join(dmg_name), dmg_path.clone())?;
-  Ok(vec![bundle_path, dmg_path])
+  if let Some(identity) = settings.osx_signing_identity() {
+    osx_sign::sign_dmg(&dmg_path, identity, settings)?;
+  }
+  Ok(vec![dmg_path])
 }
@@ -40,9 +40,8 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   fs::create_dir_all(&bundle_dir)
     .with_context(|| format!("Failed to create bundle directory at {:?}", bundle_dir))?;
 
-  for src in settings.resource_files() {
-    let src = src?;
-    let dest = bundle_dir.join(common::resource_relpath(&src));
+  for (src, target) in settings.resource_files()? {
+    let dest = bundle_dir.join(target);
     common::copy_file(&src, &dest)
       .with_context(|| format!("Failed to copy resource file {:?}", src))?;
   }
@@ -0,0 +1,236 @@
+// Renders the CLI definition of the app (the `tauri > cli` config) as roff man pages.
+//
+// The command gets a `<name>.1` page and each subcommand gets its own `<name>-<subcommand>.1` page,
+// referenced on the SUBCOMMANDS section of its parent.
+
+use super::tauri_config::{CliArg, CliConfig};
+
+/// A rendered man page.
+pub struct ManPage {
+  /// The page name, e.g. `app` or `app-subcommand`.
+  pub name: String,
+  /// The roff source of the page.
+  pub contents: String,
+}
+
+impl ManPage {
+  /// The file name of the page on the section 1 of the manual.
+  pub fn file_name(&self) -> String {
+    format!("{}.1", self.name)
+  }
+}
+
+/// Renders the man pages of the command and its subcommands.
+pub fn man_pages(name: &str, version: &str, cli: &CliConfig) -> Vec<ManPage> {
+  let mut pages = Vec::new();
+  render(&[name], version, cli, &mut pages);
+  pages
+}
+
+/// Renders the page of a command, then the pages of its subcommands.
+fn render(command: &[&str], version: &str, cli: &CliConfig, pages: &mut Vec<ManPage>) {
+  let name = command.join("-");
+  let mut page = format!(
+    ".TH \"{}\" 1 \"\" \"{} {}\" \"User Commands\"\n",
+    escape(&name.to_uppercase()),
+    escape(command[0]),
+    escape(version)
+  );
+
+  page.push_str(".SH NAME\n");
+  match &cli.description {
+    Some(description) => page.push_str(&format!("{} \\- {}\n", escape(&name), escape(description))),
+    None => page.push_str(&format!("{}\n", escape(&name))),
+  }
+
+  let args: Vec<&CliArg> = cli.args.iter().flatten().collect();
+  let mut positionals: Vec<&CliArg> = args
+    .iter()
+    .copied()
+    .filter(|arg| arg.index.is_some())
+    .collect();
+  positionals.sort_by_key(|arg| arg.index);
+  let options: Vec<&CliArg> = args
+    .iter()
+    .copied()
+    .filter(|arg| arg.index.is_none())
+    .collect();
+  let mut subcommands: Vec<(&String, &CliConfig)> = cli.subcommands.iter().flatten().collect();
+  subcommands.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+  page.push_str(".SH SYNOPSIS\n");
+  page.push_str(&format!("\\fB{}\\fR [OPTIONS]", escape(&command.join(" "))));
+  for arg in &positionals {
+    let value = value_name(arg);
+    if arg.required.unwrap_or(false) {
+      page.push_str(&format!(" {}", value));
+    } else {
+      page.push_str(&format!(" [{}]", value));
+    }
+  }
+  if !subcommands.is_empty() {
+    page.push_str(" [SUBCOMMAND]");
+  }
+  page.push('\n');
+
+  let description = cli.long_description.as_ref().or(cli.description.as_ref());
+  if cli.before_help.is_some() || description.is_some() {
+    page.push_str(".SH DESCRIPTION\n");
+    for text in cli.before_help.iter().chain(description) {
+      page.push_str(".PP\n");
+      page.push_str(&escape(text));
+      page.push('\n');
+    }
+  }
+
+  if !positionals.is_empty() {
+    page.push_str(".SH ARGUMENTS\n");
+    for arg in positionals {
+      page.push_str(&format!(".TP\n{}\n", value_name(arg)));
+      render_arg_details(arg, &mut page);
+    }
+  }
+
+  page.push_str(".SH OPTIONS\n");
+  for arg in options {
+    let mut flags = Vec::new();
+    if let Some(short) = arg.short {
+      flags.push(format!("\\fB\\-{}\\fR", escape(&short.to_string())));
+    }
+    flags.push(format!("\\fB\\-\\-{}\\fR", escape(&arg.name)));
+    for alias in arg.visible_aliases.iter().flatten() {
+      flags.push(format!("\\fB\\-\\-{}\\fR", escape(alias)));
+    }
+    let mut line = flags.join(", ");
+    if arg.takes_value.unwrap_or(false) {
+      line.push(' ');
+      line.push_str(&value_name(arg));
+    }
+    page.push_str(&format!(".TP\n{}\n", line));
+    render_arg_details(arg, &mut page);
+  }
+  page.push_str(".TP\n\\fB\\-h\\fR, \\fB\\-\\-help\\fR\nPrints help information\n");
+  if command.len() == 1 {
+    page.push_str(".TP\n\\fB\\-V\\fR, \\fB\\-\\-version\\fR\nPrints version information\n");
+  }
+
+  if !subcommands.is_empty() {
+    page.push_str(".SH SUBCOMMANDS\n");
+    for (subcommand_name, subcommand) in &subcommands {
+      page.push_str(&format!(".TP\n\\fB{}\\fR\n", escape(subcommand_name)));
+      if let Some(description) = &subcommand.description {
+        page.push_str(&escape(description));
+        page.push('\n');
+      }
+      page.push_str(&format!(
+        "See \\fB{}\\-{}\\fR(1).\n",
+        escape(&name),
+        escape(subcommand_name)
+      ));
+    }
+  }
+
+  if let Some(after_help) = &cli.after_help {
+    page.push_str(".SH NOTES\n");
+    page.push_str(&escape(after_help));
+    page.push('\n');
+  }
+
+  if command.len() > 1 {
+    page.push_str(&format!(
+      ".SH SEE ALSO\n\\fB{}\\fR(1)\n",
+      escape(&command[..command.len() - 1].join("-"))
+    ));
+  }
+
+  pages.push(ManPage {
+    name,
+    contents: page,
+  });
+
+  for (subcommand_name, subcommand) in subcommands {
+    let mut subcommand_path = command.to_vec();
+    subcommand_path.push(subcommand_name);
+    render(&subcommand_path, version, subcommand, pages);
+  }
+}
+
+/// Renders the description, possible values and requirement of an arg.
+fn render_arg_details(arg: &CliArg, page: &mut String) {
+  if let Some(description) = arg.long_description.as_ref().or(arg.description.as_ref()) {
+    page.push_str(&escape(description));
+    page.push('\n');
+  }
+  if let Some(possible_values) = &arg.possible_values {
+    page.push_str(&format!(
+      ".br\nPossible values: {}\n",
+      possible_values
+        .iter()
+        .map(|value| format!("\\fI{}\\fR", escape(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    ));
+  }
+  if arg.required.unwrap_or(false) && arg.index.is_none() {
+    page.push_str(".br\nRequired.\n");
+  }
+}
+
+/// The value placeholder of an arg, e.g. `<file>...`.
+fn value_name(arg: &CliArg) -> String {
+  let mut value = format!("\\fI<{}>\\fR", escape(&arg.name));
+  if arg.multiple.unwrap_or(false) {
+    value.push_str("...");
+  }
+  value
+}
+
+/// Escapes text for roff: backslashes, hyphens and lines starting with a control character.
+fn escape(text: &str) -> String {
+  text
+    .lines()
+    .map(|line| {
+      let line = line.replace('\\', "\\e").replace('-', "\\-");
+      if line.starts_with('.') || line.starts_with('\'') {
+        format!("\\&{}", line)
+      } else {
+        line
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{man_pages, CliConfig};
+
+  #[test]
+  // the command and each subcommand get a page
+  fn renders_subcommand_pages() {
+    let cli: CliConfig = serde_json::from_str(
+      r#"{
+        "description": "does things",
+        "args": [
+          { "name": "out-dir", "short": "o", "takesValue": true, "possibleValues": ["a", "b"] },
+          { "name": "file", "index": 1, "required": true }
+        ],
+        "subcommands": { "update": { "description": "updates" } },
+        "afterHelp": ".hidden"
+      }"#,
+    )
+    .unwrap();
+    let pages = man_pages("app", "1.0.0", &cli);
+    let names: Vec<String> = pages.iter().map(|page| page.file_name()).collect();
+    assert_eq!(names, vec!["app.1", "app-update.1"]);
+
+    let page = &pages[0].contents;
+    assert!(page.contains("app \\- does things"));
+    assert!(page.contains("\\fBapp\\fR [OPTIONS] \\fI<file>\\fR [SUBCOMMAND]"));
+    assert!(page.contains("\\fB\\-o\\fR, \\fB\\-\\-out\\-dir\\fR \\fI<out\\-dir>\\fR"));
+    assert!(page.contains("Possible values: \\fIa\\fR, \\fIb\\fR"));
+    assert!(page.contains("See \\fBapp\\-update\\fR(1)."));
+    assert!(page.contains(".SH NOTES\n\\&.hidden"));
+    assert!(pages[1].contents.contains(".SH SEE ALSO\n\\fBapp\\fR(1)"));
+  }
+}
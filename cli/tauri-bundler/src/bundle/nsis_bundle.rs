@@ -0,0 +1,384 @@
+// The NSIS installer is compiled by `makensis` from a generated script,
+// which installs the app files to the install directory:
+//
+// foobar_1.2.3_x64-setup.exe
+//     foobar.exe                      # Main binary executable file
+//     *.exe, *.dll                    # Other binaries and the DLLs next to the main binary
+//     ...                             # Resource files, on their relative paths
+//     WebView2Runtime/...             # The WebView2 fixed runtime, if configured
+//     uninstall.exe                   # Generated by the installer
+
+use super::{
+  common,
+  settings::Settings,
+  tauri_config::{NsisCompression, NsisInstallMode, WebviewInstallMode},
+  wix,
+};
+
+use handlebars::{to_json, Handlebars};
+use lazy_static::lazy_static;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  fs::{create_dir_all, write},
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+// setup for the installer.nsi template file using handlebars.
+lazy_static! {
+  static ref HANDLEBARS: Handlebars<'static> = {
+    let mut handlebars = Handlebars::new();
+
+    handlebars
+      .register_template_string("installer.nsi", include_str!("templates/installer.nsi"))
+      .map_err(|e| e.to_string())
+      .expect("Failed to setup handlebar template");
+    handlebars
+  };
+}
+
+/// A file installed by the NSIS installer.
+#[derive(Serialize)]
+struct InstallerFile {
+  /// The directory relative to the install directory, e.g. `\resources`, or empty for the install directory itself.
+  dir: String,
+  /// The file name.
+  name: String,
+  /// The path to the file on this machine.
+  source: String,
+  /// The path relative to the install directory, e.g. `\resources\data.json`.
+  path: String,
+}
+
+/// Returns the path to `makensis`, searching the `PATH` and the default NSIS install directories.
+pub fn makensis_path() -> Option<PathBuf> {
+  let on_path = Command::new("makensis")
+    .arg("/VERSION")
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map(|status| status.success())
+    .unwrap_or(false);
+  if on_path {
+    return Some(PathBuf::from("makensis"));
+  }
+
+  ["ProgramFiles(x86)", "ProgramFiles"]
+    .iter()
+    .filter_map(|var| std::env::var_os(var))
+    .map(|dir| PathBuf::from(dir).join("NSIS").join("makensis.exe"))
+    .find(|path| path.exists())
+}
+
+/// Runs all of the commands to build the NSIS installer.
+/// Returns a vector of PathBuf that shows where the installer was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let makensis = makensis_path().ok_or_else(|| {
+    crate::Error::GenericError(
+      "the nsis bundle requires `makensis`; install NSIS from https://nsis.sourceforge.io and try again"
+        .to_string(),
+    )
+  })?;
+
+  let arch = match settings.binary_arch() {
+    "x86_64" => "x64",
+    "x86" => "x86",
+    target => {
+      return Err(crate::Error::ArchError(format!(
+        "unsupported target: {}",
+        target
+      )))
+    }
+  };
+
+  let installer_name = format!(
+    "{}_{}_{}{}-setup.exe",
+    settings.main_binary_name().replace(".exe", ""),
+    settings.version_string(),
+    arch,
+    settings.artifact_suffix()
+  );
+  common::print_bundling(&installer_name)?;
+  let output_path = settings.project_out_directory().join("bundle/nsis");
+  create_dir_all(&output_path)?;
+  let installer_path = output_path.join(&installer_name);
+
+  let mut data = BTreeMap::new();
+  data.insert("product_name", to_json(settings.bundle_name()));
+  data.insert("version", to_json(settings.version_string()));
+  data.insert(
+    "product_version",
+    to_json(product_version(settings.version_string())),
+  );
+  data.insert(
+    "manufacturer",
+    to_json(
+      settings
+        .publisher()
+        .unwrap_or_else(|| settings.bundle_identifier()),
+    ),
+  );
+  if !settings.homepage_url().is_empty() {
+    data.insert("homepage", to_json(settings.homepage_url()));
+  }
+  data.insert("identifier", to_json(settings.bundle_identifier()));
+  data.insert("app_exe_name", to_json(settings.main_binary_name()));
+  data.insert("out_file", to_json(&installer_path));
+  data.insert("x64", to_json(arch == "x64"));
+
+  let (execution_level, choose_install_mode) = match settings.nsis_install_mode() {
+    NsisInstallMode::CurrentUser => ("Standard", false),
+    NsisInstallMode::PerMachine => ("Admin", false),
+    NsisInstallMode::Both => ("Highest", true),
+  };
+  data.insert("execution_level", to_json(execution_level));
+  data.insert("choose_install_mode", to_json(choose_install_mode));
+
+  let compression = match settings.nsis_compression() {
+    NsisCompression::None => None,
+    NsisCompression::Zlib => Some("zlib"),
+    NsisCompression::Bzip2 => Some("bzip2"),
+    NsisCompression::Lzma => Some("lzma"),
+  };
+  if let Some(compression) = compression {
+    data.insert("compression", to_json(compression));
+  }
+
+  let cwd = std::env::current_dir()?;
+  data.insert("icon_path", to_json(wix::copy_icon(settings)?));
+  let license_file = settings.license_file()?;
+  match settings.nsis_license() {
+    Some(license) => {
+      data.insert("license", to_json(cwd.join(license)));
+    }
+    None => {
+      if let Some(license) = &license_file {
+        data.insert("license", to_json(license));
+      }
+    }
+  }
+  if let Some(header_image) = settings.nsis_header_image() {
+    data.insert("header_image", to_json(cwd.join(header_image)));
+  }
+  if let Some(sidebar_image) = settings.nsis_sidebar_image() {
+    data.insert("sidebar_image", to_json(cwd.join(sidebar_image)));
+  }
+  data.insert("languages", to_json(settings.nsis_languages()));
+  data.insert(
+    "start_menu_shortcut",
+    to_json(settings.windows_start_menu_shortcut()),
+  );
+  if let Some(folder) = settings.windows_start_menu_folder() {
+    data.insert("start_menu_folder", to_json(folder));
+  }
+  data.insert(
+    "desktop_shortcut",
+    to_json(settings.windows_desktop_shortcut()),
+  );
+
+  let mut files = generate_files_data(settings)?;
+  if let Some(license) = &license_file {
+    let name = license.file_name().expect("No file name");
+    files.push(installer_file(license, Path::new(name)));
+  }
+  let install_webview2 = match settings.windows_webview_install_mode()? {
+    Some(WebviewInstallMode::DownloadBootstrapper) => {
+      data.insert("download_webview2_bootstrapper", to_json(true));
+      data.insert(
+        "webview2_bootstrapper_url",
+        to_json(wix::WEBVIEW2_BOOTSTRAPPER_URL),
+      );
+      true
+    }
+    Some(WebviewInstallMode::EmbedBootstrapper { sha256 }) => {
+      let bootstrapper_path = wix::download_webview2_bootstrapper(settings, sha256)?;
+      data.insert("webview2_installer_path", to_json(bootstrapper_path));
+      true
+    }
+    Some(WebviewInstallMode::OfflineInstaller { path }) => {
+      data.insert("webview2_installer_path", to_json(cwd.join(path)));
+      true
+    }
+    Some(WebviewInstallMode::FixedRuntime { path }) => {
+      let runtime_dir = cwd.join(path);
+      for entry in WalkDir::new(&runtime_dir) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+          let relative = Path::new(wix::WEBVIEW2_FIXED_RUNTIME_DIR)
+            .join(entry.path().strip_prefix(&runtime_dir)?);
+          files.push(installer_file(entry.path(), &relative));
+        }
+      }
+      false
+    }
+    None => false,
+  };
+  data.insert("install_webview2", to_json(install_webview2));
+
+  data.insert("directories", to_json(&uninstall_directories(&files)));
+  data.insert("files", to_json(&files));
+
+  let script_path = output_path.join("installer.nsi");
+  write(&script_path, HANDLEBARS.render("installer.nsi", &data)?)?;
+
+  common::print_info("running makensis")?;
+  let mut cmd = Command::new(makensis);
+  cmd
+    .arg("/NOCD")
+    .arg("/INPUTCHARSET")
+    .arg("UTF8")
+    .arg(&script_path);
+  common::execute_with_verbosity(&mut cmd, settings).map_err(|_| {
+    crate::Error::ShellScriptError(format!(
+      "error running makensis{}",
+      if settings.is_verbose() {
+        ""
+      } else {
+        ", try running with --verbose to see command output"
+      }
+    ))
+  })?;
+
+  Ok(vec![installer_path])
+}
+
+/// Converts the version to the `X.X.X.X` format required by `VIProductVersion`,
+/// dropping the pre-release and build metadata.
+fn product_version(version: &str) -> String {
+  let numeric = version.split(|c| c == '-' || c == '+').next().unwrap_or("");
+  let mut parts: Vec<u64> = numeric
+    .split('.')
+    .map(|part| part.parse().unwrap_or(0))
+    .take(4)
+    .collect();
+  parts.resize(4, 0);
+  parts
+    .iter()
+    .map(|part| part.to_string())
+    .collect::<Vec<_>>()
+    .join(".")
+}
+
+/// Describes a file installed at `relative` (a path relative to the install directory).
+fn installer_file(source: &Path, relative: &Path) -> InstallerFile {
+  let path = relative
+    .components()
+    .map(|component| format!("\\{}", component.as_os_str().to_string_lossy()))
+    .collect::<String>();
+  let name = relative
+    .file_name()
+    .expect("failed to extract file name")
+    .to_string_lossy()
+    .to_string();
+  let dir = path[..path.len() - name.len() - 1].to_string();
+  InstallerFile {
+    dir,
+    name,
+    source: source.display().to_string(),
+    path,
+  }
+}
+
+/// The directories of the installed files and their parents, deepest first,
+/// so they're empty when they're removed on uninstall.
+fn uninstall_directories(files: &[InstallerFile]) -> Vec<&str> {
+  let directories: BTreeSet<&str> = files
+    .iter()
+    .flat_map(|file| {
+      file
+        .dir
+        .match_indices('\\')
+        .map(move |(index, _)| &file.dir[..index])
+        .chain(std::iter::once(file.dir.as_str()))
+    })
+    .filter(|dir| !dir.is_empty())
+    .collect();
+  let mut directories: Vec<&str> = directories.into_iter().collect();
+  directories.sort_by_key(|dir| std::cmp::Reverse(dir.matches('\\').count()));
+  directories
+}
+
+/// Generates the data of the binaries, DLLs and resources installed by the installer.
+fn generate_files_data(settings: &Settings) -> crate::Result<Vec<InstallerFile>> {
+  let mut files = Vec::new();
+  let cwd = std::env::current_dir()?;
+
+  for bin in settings.binaries() {
+    files.push(installer_file(
+      &settings.binary_path(bin),
+      Path::new(bin.name()),
+    ));
+  }
+
+  for (_, filename) in settings.external_binaries()? {
+    // the copy on the project out directory, which might be signed
+    let source = cwd.join(settings.project_out_directory().join(&filename));
+    files.push(installer_file(&source, Path::new(&filename)));
+  }
+
+  for dll in glob::glob(
+    settings
+      .project_out_directory()
+      .join("*.dll")
+      .to_string_lossy()
+      .to_string()
+      .as_str(),
+  )? {
+    let path = dll?;
+    let filename = path
+      .file_name()
+      .expect("failed to extract dll filename")
+      .to_os_string();
+    files.push(installer_file(&path, Path::new(&filename)));
+  }
+
+  for (src, target) in settings.resource_files()? {
+    files.push(installer_file(&cwd.join(src), &target));
+  }
+
+  Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{installer_file, product_version, uninstall_directories};
+  use std::path::Path;
+
+  #[test]
+  fn product_versions() {
+    assert_eq!(product_version("1.2.3"), "1.2.3.0");
+    assert_eq!(product_version("1.2.3-beta.4+build5"), "1.2.3.0");
+    assert_eq!(product_version("1.2"), "1.2.0.0");
+    assert_eq!(product_version("1.2.3.4.5"), "1.2.3.4");
+    assert_eq!(product_version("1.x.3"), "1.0.3.0");
+  }
+
+  #[test]
+  fn installed_files_and_directories() {
+    let source = Path::new(r"C:\app\target\release\app.exe");
+    let binary = installer_file(source, Path::new("app.exe"));
+    assert_eq!(binary.dir, "");
+    assert_eq!(binary.name, "app.exe");
+    assert_eq!(binary.path, r"\app.exe");
+
+    let resource = installer_file(source, Path::new("resources/data/config.json"));
+    assert_eq!(resource.dir, r"\resources\data");
+    assert_eq!(resource.name, "config.json");
+    assert_eq!(resource.path, r"\resources\data\config.json");
+
+    let files = vec![
+      binary,
+      resource,
+      installer_file(source, Path::new("resources/icon.png")),
+      installer_file(source, Path::new("WebView2Runtime/msedge.dll")),
+    ];
+    let directories = uninstall_directories(&files);
+    assert_eq!(directories[0], r"\resources\data");
+    let mut parents = directories[1..].to_vec();
+    parents.sort_unstable();
+    assert_eq!(parents, vec![r"\WebView2Runtime", r"\resources"]);
+  }
+}
@@ -8,16 +8,18 @@
 //             foobar_helper   # A helper application, possibly provitidng a CLI
 //         Resources      # Data files such as images, sounds, translations and nib files
 //             en.lproj        # Folder containing english translation strings/data
-//         Frameworks     # A directory containing private frameworks (shared libraries)
+//         Frameworks     # A directory containing private frameworks and dylibs (shared libraries)
 //         ...            # Any other optional files the developer wants to place here
 //
 // See https://developer.apple.com/go/?id=bundle-structure for a full
 // explanation.
 //
-// Currently, cargo-bundle does not support Frameworks, nor does it support placing arbitrary
-// files into the `Contents` directory of the bundle.
+// The bundled frameworks and dylibs get `@executable_path/../Frameworks` install names,
+// and the binaries loading them are rewritten with `install_name_tool`.
+//
+// Currently, cargo-bundle does not support placing arbitrary files into the `Contents` directory of the bundle.
 
-use super::common;
+use super::{common, osx_sign, tauri_config::BundleLocalization};
 use crate::Settings;
 
 use anyhow::Context;
@@ -25,6 +27,7 @@ use image::{self, GenericImageView};
 
 use std::{
   cmp::min,
+  collections::{BTreeMap, BTreeSet},
   ffi::OsStr,
   fs::{self, File},
   io::{self, prelude::*, BufWriter},
@@ -36,13 +39,14 @@ use std::{
 /// Returns a vector of PathBuf that shows where the .app was created.
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   let package_base_name = format!(
-    "{}_{}_{}",
+    "{}_{}_{}{}",
     settings.main_binary_name(),
     settings.version_string(),
     match settings.binary_arch() {
       "x86_64" => "x64",
       other => other,
-    }
+    },
+    settings.artifact_suffix()
   );
   let app_bundle_name = format!("{}.app", package_base_name);
   common::print_bundling(&app_bundle_name)?;
@@ -68,25 +72,49 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   let bundle_icon_file: Option<PathBuf> =
     { create_icns_file(&resources_dir, settings).with_context(|| "Failed to create app icon")? };
 
+  if !settings.file_associations().is_empty() {
+    common::print_warning(
+      "the file associations aren't registered on macOS, since the app can't receive the files opened through Apple Events",
+    )?;
+  }
+
   create_info_plist(&bundle_directory, bundle_icon_file, settings)
     .with_context(|| "Failed to create Info.plist")?;
 
-  copy_frameworks_to_bundle(&bundle_directory, settings)
+  create_info_plist_strings(&resources_dir, settings)
+    .with_context(|| "Failed to create the localized InfoPlist.strings")?;
+
+  let libraries = copy_frameworks_to_bundle(&bundle_directory, settings)
     .with_context(|| "Failed to bundle frameworks")?;
 
   settings.copy_resources(&resources_dir)?;
 
+  if let Some(license) = settings.license_file()? {
+    common::copy_file(
+      &license,
+      &resources_dir.join(license.file_name().expect("No file name")),
+    )
+    .with_context(|| "Failed to copy license file")?;
+  }
+
   settings
     .copy_binaries(&bin_dir)
     .with_context(|| "Failed to copy external binaries")?;
 
   copy_binaries_to_bundle(&bundle_directory, settings)?;
 
+  fix_install_names(&bundle_directory, &libraries, settings)
+    .with_context(|| "Failed to fix the install names of the frameworks")?;
+
   let use_bootstrapper = settings.osx_use_bootstrapper();
   if use_bootstrapper {
     create_bootstrapper(&bundle_directory, settings)
       .with_context(|| "Failed to create OSX bootstrapper")?;
   }
+
+  if let Some(identity) = settings.osx_signing_identity() {
+    osx_sign::sign_bundle(&app_bundle_path, identity, settings)?;
+  }
   Ok(vec![app_bundle_path])
 }
 
@@ -154,6 +182,31 @@ exit 0",
   Ok(())
 }
 
+// Creates the `InfoPlist.strings` of the localized bundle names on the `.lproj` folder of each locale.
+fn create_info_plist_strings(resources_dir: &Path, settings: &Settings) -> crate::Result<()> {
+  for (locale, localization) in settings.localization() {
+    if let Some(contents) = info_plist_strings(localization) {
+      let lproj_dir = resources_dir.join(format!("{}.lproj", locale));
+      fs::create_dir_all(&lproj_dir)?;
+      fs::write(lproj_dir.join("InfoPlist.strings"), contents)?;
+    }
+  }
+  Ok(())
+}
+
+// Renders the `InfoPlist.strings` of a locale, `None` if it doesn't localize the bundle name.
+fn info_plist_strings(localization: &BundleLocalization) -> Option<String> {
+  let name = localization
+    .product_name
+    .as_ref()?
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"");
+  Some(format!(
+    "CFBundleDisplayName = \"{0}\";\nCFBundleName = \"{0}\";\n",
+    name
+  ))
+}
+
 // Creates the Info.plist file.
 fn create_info_plist(
   bundle_dir: &Path,
@@ -161,124 +214,139 @@ fn create_info_plist(
   settings: &Settings,
 ) -> crate::Result<()> {
   let build_number = chrono::Utc::now().format("%Y%m%d.%H%M%S");
-  let file = &mut common::create_file(&bundle_dir.join("Info.plist"))?;
   let use_bootstrapper = settings.osx_use_bootstrapper();
-  write!(
-    file,
-    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
-     <!DOCTYPE plist PUBLIC \"-//Apple Computer//DTD PLIST 1.0//EN\" \
-     \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
-     <plist version=\"1.0\">\n\
-     <dict>\n"
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleDevelopmentRegion</key>\n  \
-     <string>English</string>\n"
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleDisplayName</key>\n  <string>{}</string>\n",
-    settings.bundle_name()
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleExecutable</key>\n  <string>{}</string>\n",
+  let mut plist = plist::Dictionary::new();
+  plist.insert("CFBundleDevelopmentRegion".into(), "English".into());
+  plist.insert("CFBundleDisplayName".into(), settings.bundle_name().into());
+  plist.insert(
+    "CFBundleExecutable".into(),
     if use_bootstrapper {
       "__bootstrapper"
     } else {
       settings.main_binary_name()
     }
-  )?;
+    .into(),
+  );
   if let Some(path) = bundle_icon_file {
-    write!(
-      file,
-      "  <key>CFBundleIconFile</key>\n  <string>{}</string>\n",
-      path.file_name().expect("No file name").to_string_lossy()
-    )?;
+    plist.insert(
+      "CFBundleIconFile".into(),
+      path
+        .file_name()
+        .expect("No file name")
+        .to_string_lossy()
+        .to_string()
+        .into(),
+    );
   }
-  write!(
-    file,
-    "  <key>CFBundleIdentifier</key>\n  <string>{}</string>\n",
-    settings.bundle_identifier()
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleInfoDictionaryVersion</key>\n  \
-     <string>6.0</string>\n"
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleName</key>\n  <string>{}</string>\n",
-    settings.bundle_name()
-  )?;
-  write!(
-    file,
-    "  <key>CFBundlePackageType</key>\n  <string>APPL</string>\n"
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleShortVersionString</key>\n  <string>{}</string>\n",
-    settings.version_string()
-  )?;
-  write!(
-    file,
-    "  <key>CFBundleVersion</key>\n  <string>{}</string>\n",
-    build_number
-  )?;
-  write!(file, "  <key>CSResourcesFileMapped</key>\n  <true/>\n")?;
+  plist.insert(
+    "CFBundleIdentifier".into(),
+    settings.bundle_identifier().into(),
+  );
+  plist.insert("CFBundleInfoDictionaryVersion".into(), "6.0".into());
+  let localization = settings.localization();
+  if !localization.is_empty() {
+    plist.insert(
+      "CFBundleLocalizations".into(),
+      plist::Value::Array(localization.keys().map(|locale| (*locale).into()).collect()),
+    );
+  }
+  plist.insert("CFBundleName".into(), settings.bundle_name().into());
+  plist.insert("CFBundlePackageType".into(), "APPL".into());
+  plist.insert(
+    "CFBundleShortVersionString".into(),
+    settings.version_string().into(),
+  );
+  let deep_link_schemes = settings.deep_link_schemes();
+  if !deep_link_schemes.is_empty() {
+    let mut url_type = plist::Dictionary::new();
+    url_type.insert(
+      "CFBundleURLName".into(),
+      settings.bundle_identifier().into(),
+    );
+    url_type.insert(
+      "CFBundleURLSchemes".into(),
+      plist::Value::Array(
+        deep_link_schemes
+          .iter()
+          .map(|scheme| scheme.as_str().into())
+          .collect(),
+      ),
+    );
+    plist.insert(
+      "CFBundleURLTypes".into(),
+      plist::Value::Array(vec![url_type.into()]),
+    );
+  }
+  plist.insert("CFBundleVersion".into(), build_number.to_string().into());
+  plist.insert("CSResourcesFileMapped".into(), true.into());
   if let Some(category) = settings.app_category() {
-    write!(
-      file,
-      "  <key>LSApplicationCategoryType</key>\n  \
-       <string>{}</string>\n",
-      category.osx_application_category_type()
-    )?;
+    plist.insert(
+      "LSApplicationCategoryType".into(),
+      category.osx_application_category_type().into(),
+    );
   }
   if let Some(version) = settings.osx_minimum_system_version() {
-    write!(
-      file,
-      "  <key>LSMinimumSystemVersion</key>\n  \
-       <string>{}</string>\n",
-      version
-    )?;
+    plist.insert("LSMinimumSystemVersion".into(), version.into());
   }
-  write!(file, "  <key>LSRequiresCarbon</key>\n  <true/>\n")?;
-  write!(file, "  <key>NSHighResolutionCapable</key>\n  <true/>\n")?;
+  plist.insert("LSRequiresCarbon".into(), true.into());
+  plist.insert("NSHighResolutionCapable".into(), true.into());
   if let Some(copyright) = settings.copyright_string() {
-    write!(
-      file,
-      "  <key>NSHumanReadableCopyright</key>\n  \
-       <string>{}</string>\n",
-      copyright
-    )?;
+    plist.insert("NSHumanReadableCopyright".into(), copyright.into());
   }
 
   if let Some(exception_domain) = settings.exception_domain() {
-    write!(
-      file,
-      "  <key>NSAppTransportSecurity</key>\n  \
-      <dict>\n  \
-          <key>NSExceptionDomains</key>\n  \
-          <dict>\n  \
-              <key>{}</key>\n  \
-              <dict>\n  \
-                  <key>NSExceptionAllowsInsecureHTTPLoads</key>\n  \
-                  <true/>\n  \
-                  <key>NSIncludesSubdomains</key>\n  \
-                  <true/>\n  \
-              </dict>\n  \
-          </dict>\n  \
-      </dict>",
-      exception_domain
-    )?;
+    let mut domain = plist::Dictionary::new();
+    domain.insert("NSExceptionAllowsInsecureHTTPLoads".into(), true.into());
+    domain.insert("NSIncludesSubdomains".into(), true.into());
+    let mut exception_domains = plist::Dictionary::new();
+    exception_domains.insert(exception_domain.to_string(), domain.into());
+    let mut app_transport_security = plist::Dictionary::new();
+    app_transport_security.insert("NSExceptionDomains".into(), exception_domains.into());
+    plist.insert(
+      "NSAppTransportSecurity".into(),
+      app_transport_security.into(),
+    );
   }
 
-  write!(file, "</dict>\n</plist>\n")?;
-  file.flush()?;
+  // the user keys win over the generated ones, except for the bundle identifier
+  for user_plist in settings.osx_info_plist()? {
+    merge_plist(&mut plist, user_plist);
+  }
+  let identifier = settings.bundle_identifier();
+  if let Some(user_identifier) = plist
+    .get("CFBundleIdentifier")
+    .and_then(plist::Value::as_string)
+  {
+    if user_identifier != identifier {
+      common::print_warning(
+        format!(
+          "ignoring the Info.plist CFBundleIdentifier `{}` since it differs from the bundle identifier `{}`",
+          user_identifier, identifier
+        )
+        .as_str(),
+      )?;
+    }
+  }
+  plist.insert("CFBundleIdentifier".into(), identifier.into());
+
+  plist::Value::Dictionary(plist).to_file_xml(bundle_dir.join("Info.plist"))?;
   Ok(())
 }
 
+// Deep merges the `other` dictionary into `plist`, with the `other` values winning.
+fn merge_plist(plist: &mut plist::Dictionary, other: plist::Dictionary) {
+  for (key, value) in other {
+    match (plist.get_mut(&key), value) {
+      (Some(plist::Value::Dictionary(current)), plist::Value::Dictionary(value)) => {
+        merge_plist(current, value)
+      }
+      (_, value) => {
+        plist.insert(key, value);
+      }
+    }
+  }
+}
+
 // Copies the framework under `{src_dir}/{framework}.framework` to `{dest_dir}/{framework}.framework`.
 fn copy_framework_from(dest_dir: &Path, framework: &str, src_dir: &Path) -> crate::Result<bool> {
   let src_name = format!("{}.framework", framework);
@@ -291,29 +359,58 @@ fn copy_framework_from(dest_dir: &Path, framework: &str, src_dir: &Path) -> crat
   }
 }
 
-// Copies the OSX bundle frameworks to the .app
-fn copy_frameworks_to_bundle(bundle_directory: &Path, settings: &Settings) -> crate::Result<()> {
+// Returns the path of a framework binary relative to the Frameworks directory, e.g. `Foo.framework/Foo`.
+fn framework_binary(framework_dir_name: &OsStr) -> PathBuf {
+  let name = Path::new(framework_dir_name)
+    .file_stem()
+    .expect("Couldn't get framework name");
+  Path::new(framework_dir_name).join(name)
+}
+
+// Copies the OSX bundle frameworks and dylibs to the .app
+// Returns the paths of the copied library binaries, relative to the Frameworks directory.
+fn copy_frameworks_to_bundle(
+  bundle_directory: &Path,
+  settings: &Settings,
+) -> crate::Result<Vec<PathBuf>> {
   let frameworks = settings.osx_frameworks();
+  let mut libraries = Vec::new();
   if frameworks.is_empty() {
-    return Ok(());
+    return Ok(libraries);
   }
   let dest_dir = bundle_directory.join("Frameworks");
-  fs::create_dir_all(&bundle_directory)
+  fs::create_dir_all(&dest_dir)
     .with_context(|| format!("Failed to create Frameworks directory at {:?}", dest_dir))?;
   for framework in frameworks.iter() {
-    if framework.ends_with(".framework") {
+    if framework.ends_with(".framework") || framework.ends_with(".dylib") {
       let src_path = PathBuf::from(framework);
       let src_name = src_path
         .file_name()
         .expect("Couldn't get framework filename");
-      common::copy_dir(&src_path, &dest_dir.join(&src_name))?;
+      if !src_path.exists() {
+        return Err(crate::Error::GenericError(format!(
+          "Could not locate framework: {}",
+          framework
+        )));
+      }
+      if framework.ends_with(".dylib") {
+        common::copy_file(&src_path, &dest_dir.join(&src_name))?;
+        libraries.push(PathBuf::from(src_name));
+      } else {
+        common::copy_dir(&src_path, &dest_dir.join(&src_name))?;
+        libraries.push(framework_binary(src_name));
+      }
       continue;
     } else if framework.contains('/') {
       return Err(crate::Error::GenericError(format!(
-        "Framework path should have .framework extension: {}",
+        "Framework path should have .framework or .dylib extension: {}",
         framework
       )));
     }
+    libraries.push(framework_binary(OsStr::new(&format!(
+      "{}.framework",
+      framework
+    ))));
     if let Some(home_dir) = dirs_next::home_dir() {
       if copy_framework_from(&dest_dir, framework, &home_dir.join("Library/Frameworks/"))? {
         continue;
@@ -333,6 +430,170 @@ fn copy_frameworks_to_bundle(bundle_directory: &Path, settings: &Settings) -> cr
       framework
     )));
   }
+  Ok(libraries)
+}
+
+// Runs `otool` with the given flag and returns the lines of its output after the file name.
+fn otool(flag: &str, path: &Path) -> crate::Result<Vec<String>> {
+  let output = Command::new("otool").arg(flag).arg(path).output()?;
+  if !output.status.success() {
+    return Err(crate::Error::ShellScriptError(format!(
+      "error running otool {} on {}: {}",
+      flag,
+      path.display(),
+      String::from_utf8_lossy(&output.stderr)
+    )));
+  }
+  Ok(
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .skip(1)
+      .map(|line| line.trim().to_string())
+      .filter(|line| !line.is_empty())
+      .collect(),
+  )
+}
+
+// Returns the install names of the libraries the binary loads, parsed from `otool -L`.
+fn load_commands(path: &Path) -> crate::Result<Vec<String>> {
+  Ok(
+    otool("-L", path)?
+      .into_iter()
+      .map(|line| match line.find(" (") {
+        Some(index) => line[..index].to_string(),
+        None => line,
+      })
+      .collect(),
+  )
+}
+
+// Returns whether the install name points to a library shipped with macOS or relative to the loading binary.
+fn is_system_library(install_name: &str) -> bool {
+  install_name.starts_with("/System/")
+    || install_name.starts_with("/usr/lib/")
+    || install_name.starts_with('@')
+}
+
+// Checks that the bundled libraries don't depend on each other circularly,
+// nor on libraries that aren't bundled or shipped with macOS.
+//
+// `dependencies` maps the binaries and the install names of the bundled libraries to the install names they load.
+// Fails with the dependency chain from the app binary.
+fn check_dependencies(
+  binaries: &[String],
+  dependencies: &BTreeMap<String, Vec<String>>,
+) -> crate::Result<()> {
+  fn visit(
+    node: &str,
+    dependencies: &BTreeMap<String, Vec<String>>,
+    chain: &mut Vec<String>,
+    checked: &mut BTreeSet<String>,
+  ) -> crate::Result<()> {
+    chain.push(node.to_string());
+    for dependency in dependencies.get(node).into_iter().flatten() {
+      if dependency == node {
+        // the library's own install name
+        continue;
+      }
+      if chain.contains(dependency) {
+        return Err(crate::Error::GenericError(format!(
+          "circular framework dependency: {} -> {}",
+          chain.join(" -> "),
+          dependency
+        )));
+      }
+      if dependencies.contains_key(dependency) {
+        if !checked.contains(dependency) {
+          visit(dependency, dependencies, chain, checked)?;
+        }
+      } else if chain.len() > 1 && !is_system_library(dependency) {
+        // the app binaries can load other libraries, but the bundled ones can't
+        return Err(crate::Error::GenericError(format!(
+          "missing framework dependency: {} -> {}; add it to `tauri > bundle > osx > frameworks`",
+          chain.join(" -> "),
+          dependency
+        )));
+      }
+    }
+    chain.pop();
+    checked.insert(node.to_string());
+    Ok(())
+  }
+
+  let mut checked = BTreeSet::new();
+  for binary in binaries {
+    visit(binary, dependencies, &mut Vec::new(), &mut checked)?;
+  }
+  Ok(())
+}
+
+// Rewrites the install names of the bundled libraries to `@executable_path/../Frameworks`,
+// along with the load commands of the app binaries and libraries referencing them.
+fn fix_install_names(
+  bundle_directory: &Path,
+  libraries: &[PathBuf],
+  settings: &Settings,
+) -> crate::Result<()> {
+  if libraries.is_empty() {
+    return Ok(());
+  }
+  let frameworks_dir = bundle_directory.join("Frameworks");
+  let bin_dir = bundle_directory.join("MacOS");
+
+  // the install names the binaries load the libraries with, mapped to the bundle install names
+  let mut install_names = BTreeMap::new();
+  let mut files = Vec::new();
+  for library in libraries {
+    let path = frameworks_dir.join(library);
+    let bundle_install_name = format!("@executable_path/../Frameworks/{}", library.display());
+    let install_name = otool("-D", &path)?
+      .into_iter()
+      .next()
+      .unwrap_or_else(|| bundle_install_name.clone());
+    install_names.insert(install_name.clone(), bundle_install_name);
+    files.push((install_name, path));
+  }
+  let binaries: Vec<String> = settings
+    .binaries()
+    .iter()
+    .map(|bin| bin.name().to_string())
+    .collect();
+  for bin in &binaries {
+    files.push((bin.clone(), bin_dir.join(bin)));
+  }
+
+  let mut dependencies = BTreeMap::new();
+  for (name, path) in &files {
+    dependencies.insert(name.clone(), load_commands(path)?);
+  }
+  check_dependencies(&binaries, &dependencies)?;
+
+  for (name, path) in &files {
+    let mut cmd = Command::new("install_name_tool");
+    if let Some(bundle_install_name) = install_names.get(name) {
+      cmd.arg("-id").arg(bundle_install_name);
+    }
+    let mut changed = false;
+    for dependency in &dependencies[name] {
+      if dependency == name {
+        continue;
+      }
+      if let Some(bundle_install_name) = install_names.get(dependency) {
+        cmd.arg("-change").arg(dependency).arg(bundle_install_name);
+        changed = true;
+      }
+    }
+    if !changed && !install_names.contains_key(name) {
+      continue;
+    }
+    cmd.arg(path);
+    common::execute_with_verbosity(&mut cmd, settings).map_err(|_| {
+      crate::Error::ShellScriptError(format!(
+        "error running install_name_tool on {}",
+        path.display()
+      ))
+    })?;
+  }
   Ok(())
 }
 
@@ -437,3 +698,84 @@ fn make_icns_image(img: image::DynamicImage) -> io::Result<icns::Image> {
   };
   icns::Image::from_data(pixel_format, img.width(), img.height(), img.to_bytes())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{check_dependencies, info_plist_strings};
+  use crate::bundle::tauri_config::BundleLocalization;
+  use std::collections::BTreeMap;
+
+  fn graph(edges: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+    edges
+      .iter()
+      .map(|(node, dependencies)| {
+        (
+          node.to_string(),
+          dependencies.iter().map(|d| d.to_string()).collect(),
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn accepts_bundled_and_system_dependencies() {
+    let dependencies = graph(&[
+      (
+        "app",
+        &["/usr/local/lib/libfoo.dylib", "/usr/lib/libSystem.B.dylib"],
+      ),
+      (
+        "/usr/local/lib/libfoo.dylib",
+        &[
+          "/usr/local/lib/libfoo.dylib",
+          "@rpath/Bar.framework/Bar",
+          "/System/Library/Frameworks/Cocoa.framework/Cocoa",
+        ],
+      ),
+      ("@rpath/Bar.framework/Bar", &["/usr/lib/libc++.1.dylib"]),
+    ]);
+    assert!(check_dependencies(&["app".to_string()], &dependencies).is_ok());
+  }
+
+  #[test]
+  fn reports_missing_dependency_chain() {
+    let dependencies = graph(&[
+      ("app", &["/usr/local/lib/libfoo.dylib"]),
+      ("/usr/local/lib/libfoo.dylib", &["/opt/lib/libbar.dylib"]),
+    ]);
+    let error = check_dependencies(&["app".to_string()], &dependencies)
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("app -> /usr/local/lib/libfoo.dylib -> /opt/lib/libbar.dylib"));
+  }
+
+  #[test]
+  fn reports_circular_dependency_chain() {
+    let dependencies = graph(&[
+      ("app", &["libfoo.dylib"]),
+      ("libfoo.dylib", &["libbar.dylib"]),
+      ("libbar.dylib", &["libfoo.dylib"]),
+    ]);
+    let error = check_dependencies(&["app".to_string()], &dependencies)
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("app -> libfoo.dylib -> libbar.dylib -> libfoo.dylib"));
+  }
+
+  #[test]
+  fn localized_info_plist_strings() {
+    let localization = BundleLocalization {
+      product_name: Some("Mon \"App\"".into()),
+      ..Default::default()
+    };
+    assert_eq!(
+      info_plist_strings(&localization).unwrap(),
+      "CFBundleDisplayName = \"Mon \\\"App\\\"\";\nCFBundleName = \"Mon \\\"App\\\"\";\n"
+    );
+    assert!(info_plist_strings(&BundleLocalization {
+      short_description: Some("Une app".into()),
+      ..Default::default()
+    })
+    .is_none());
+  }
+}
@@ -0,0 +1,468 @@
+// Code signing and notarization of the macOS .app bundle and DMG.
+//
+// The bundle is signed with `codesign`: the frameworks are signed first, then the app bundle
+// itself with `--deep` so the nested binaries are signed too.
+// With `osx > notarize`, the signed .app bundle and DMG are submitted to the Apple notary service
+// with `xcrun notarytool` and the ticket is stapled to them. The credentials are only read from the
+// environment, never from the config, in order of preference: the keychain profile `APPLE_KEYCHAIN_PROFILE`
+// stored with `xcrun notarytool store-credentials` (and its keychain `APPLE_KEYCHAIN`), the App Store Connect
+// API key `APPLE_API_KEY`, `APPLE_API_ISSUER` and `APPLE_API_KEY_PATH`, or `APPLE_ID`, `APPLE_PASSWORD`
+// (an app-specific password) and `APPLE_TEAM_ID`. The password is passed to `notarytool` as an argument,
+// where the other users of the machine can see it, so the keychain profile is recommended.
+
+use super::common;
+use crate::Settings;
+
+use serde_json::Value as JsonValue;
+
+use std::{
+  env,
+  ffi::OsString,
+  fs,
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+/// The maximum number of notarization issues printed when the notary service rejects a bundle.
+const MAX_LOG_ISSUES: usize = 10;
+
+/// The notary service credentials.
+pub enum NotarizeAuth {
+  /// A keychain profile stored with `notarytool store-credentials`, on the given keychain or the default one.
+  KeychainProfile {
+    profile: String,
+    keychain: Option<PathBuf>,
+  },
+  /// An Apple ID with an app-specific password.
+  AppleId {
+    apple_id: String,
+    password: String,
+    team_id: String,
+  },
+  /// An App Store Connect API key.
+  ApiKey {
+    key_id: String,
+    issuer: String,
+    key_path: PathBuf,
+  },
+}
+
+impl NotarizeAuth {
+  /// Reads the credentials from the environment.
+  /// The team id of an Apple ID defaults to the `osx > providerShortName` config.
+  pub fn from_env(settings: &Settings) -> crate::Result<Self> {
+    if let Ok(profile) = env::var("APPLE_KEYCHAIN_PROFILE") {
+      return Ok(Self::KeychainProfile {
+        profile,
+        keychain: env::var_os("APPLE_KEYCHAIN").map(PathBuf::from),
+      });
+    }
+    if let (Ok(key_id), Ok(issuer)) = (env::var("APPLE_API_KEY"), env::var("APPLE_API_ISSUER")) {
+      let key_path = env::var_os("APPLE_API_KEY_PATH").ok_or_else(|| {
+        crate::Error::GenericError(
+          "the notarization with an API key requires the `APPLE_API_KEY_PATH` environment variable"
+            .into(),
+        )
+      })?;
+      return Ok(Self::ApiKey {
+        key_id,
+        issuer,
+        key_path: key_path.into(),
+      });
+    }
+    if let (Ok(apple_id), Ok(password)) = (env::var("APPLE_ID"), env::var("APPLE_PASSWORD")) {
+      let team_id = env::var("APPLE_TEAM_ID")
+        .ok()
+        .or_else(|| settings.osx_provider_short_name().map(String::from))
+        .ok_or_else(|| {
+          crate::Error::GenericError(
+            "the notarization with an Apple ID requires the `APPLE_TEAM_ID` environment variable"
+              .into(),
+          )
+        })?;
+      return Ok(Self::AppleId {
+        apple_id,
+        password,
+        team_id,
+      });
+    }
+    Err(crate::Error::GenericError(
+      "`osx > notarize` requires the `APPLE_KEYCHAIN_PROFILE`, the `APPLE_API_KEY`, `APPLE_API_ISSUER` and `APPLE_API_KEY_PATH` or the `APPLE_ID`, `APPLE_PASSWORD` and `APPLE_TEAM_ID` environment variables; use `--skip-notarize` to skip it"
+        .into(),
+    ))
+  }
+
+  /// The `notarytool` arguments of the credentials.
+  fn args(&self) -> Vec<OsString> {
+    match self {
+      Self::KeychainProfile { profile, keychain } => {
+        let mut args = vec!["--keychain-profile".into(), profile.into()];
+        if let Some(keychain) = keychain {
+          args.push("--keychain".into());
+          args.push(keychain.into());
+        }
+        args
+      }
+      Self::AppleId {
+        apple_id,
+        password,
+        team_id,
+      } => vec![
+        "--apple-id".into(),
+        apple_id.into(),
+        "--password".into(),
+        password.into(),
+        "--team-id".into(),
+        team_id.into(),
+      ],
+      Self::ApiKey {
+        key_id,
+        issuer,
+        key_path,
+      } => vec![
+        "--key-id".into(),
+        key_id.into(),
+        "--issuer".into(),
+        issuer.into(),
+        "--key".into(),
+        key_path.into(),
+      ],
+    }
+  }
+}
+
+/// Checks that the bundles can be notarized before anything is bundled:
+/// they must be signed, and the credentials must be set.
+pub fn check_notarize(settings: &Settings) -> crate::Result<()> {
+  if settings.osx_signing_identity().is_none() {
+    return Err(crate::Error::GenericError(
+      "`osx > notarize` requires the bundle to be signed with `osx > signingIdentity`".into(),
+    ));
+  }
+  NotarizeAuth::from_env(settings).map(|_| ())
+}
+
+/// Signs the .app bundle with the configured identity, then notarizes it if `osx > notarize` is set.
+pub fn sign_bundle(
+  app_bundle_path: &Path,
+  identity: &str,
+  settings: &Settings,
+) -> crate::Result<()> {
+  let frameworks_dir = app_bundle_path.join("Contents/Frameworks");
+  if frameworks_dir.exists() {
+    let mut frameworks: Vec<_> = fs::read_dir(&frameworks_dir)?
+      .map(|entry| entry.map(|entry| entry.path()))
+      .collect::<Result<_, _>>()?;
+    frameworks.sort();
+    for framework in frameworks {
+      sign(&framework, identity, settings, false)?;
+    }
+  }
+  sign(app_bundle_path, identity, settings, true)?;
+
+  if settings.osx_notarize() {
+    notarize(app_bundle_path, settings)?;
+  }
+  Ok(())
+}
+
+/// Signs the DMG with the configured identity, then notarizes it if `osx > notarize` is set.
+pub fn sign_dmg(dmg_path: &Path, identity: &str, settings: &Settings) -> crate::Result<()> {
+  common::print_info(
+    format!(
+      "signing {} with identity \"{}\"",
+      dmg_path.display(),
+      identity
+    )
+    .as_str(),
+  )?;
+  run(
+    "codesign",
+    Command::new("codesign")
+      .args(&["--force", "--timestamp", "--sign", identity])
+      .arg(dmg_path),
+  )?;
+
+  if settings.osx_notarize() {
+    notarize(dmg_path, settings)?;
+  }
+  Ok(())
+}
+
+/// Signs a path with `codesign`.
+fn sign(path: &Path, identity: &str, settings: &Settings, deep: bool) -> crate::Result<()> {
+  common::print_info(
+    format!("signing {} with identity \"{}\"", path.display(), identity).as_str(),
+  )?;
+  run(
+    "codesign",
+    Command::new("codesign").args(codesign_args(
+      path,
+      identity,
+      deep,
+      settings.osx_hardened_runtime(),
+      settings.osx_entitlements().map(PathBuf::as_path),
+    )),
+  )?;
+  Ok(())
+}
+
+/// The `codesign` arguments of a path.
+fn codesign_args(
+  path: &Path,
+  identity: &str,
+  deep: bool,
+  hardened_runtime: bool,
+  entitlements: Option<&Path>,
+) -> Vec<OsString> {
+  let mut args: Vec<OsString> = vec![
+    "--force".into(),
+    "--timestamp".into(),
+    "--sign".into(),
+    identity.into(),
+  ];
+  if deep {
+    args.push("--deep".into());
+  }
+  if hardened_runtime {
+    args.push("--options".into());
+    args.push("runtime".into());
+  }
+  if let Some(entitlements) = entitlements {
+    args.push("--entitlements".into());
+    args.push(entitlements.into());
+  }
+  args.push(path.into());
+  args
+}
+
+/// Submits the .app bundle or DMG to the notary service, waits for the result and staples the ticket.
+fn notarize(path: &Path, settings: &Settings) -> crate::Result<()> {
+  let auth = NotarizeAuth::from_env(settings)?;
+
+  // the notary service only accepts zip, dmg and pkg files
+  let is_app = path
+    .extension()
+    .map_or(false, |extension| extension == "app");
+  let submission_path = if is_app {
+    let zip_path = path.with_extension("zip");
+    run(
+      "ditto",
+      Command::new("ditto")
+        .args(&["-c", "-k", "--keepParent"])
+        .arg(path)
+        .arg(&zip_path),
+    )?;
+    zip_path
+  } else {
+    path.to_path_buf()
+  };
+
+  common::print_info(format!("notarizing {}", path.display()).as_str())?;
+  let output = Command::new("xcrun")
+    .args(&["notarytool", "submit"])
+    .arg(&submission_path)
+    .args(auth.args())
+    .args(&["--wait", "--output-format", "json", "--timeout"])
+    .arg(format!("{}s", settings.osx_notarize_timeout().as_secs()))
+    .output();
+  if is_app {
+    fs::remove_file(&submission_path)?;
+  }
+  let output = output?;
+  let (id, status) =
+    submission_status(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+      crate::Error::GenericError(format!(
+        "failed to submit {} for notarization:\n{}{}",
+        path.display(),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+      ))
+    })?;
+
+  if status != "Accepted" {
+    // the log explains why the bundle was rejected
+    let log = Command::new("xcrun")
+      .args(&["notarytool", "log", &id])
+      .args(auth.args())
+      .output()?;
+    return Err(crate::Error::GenericError(format!(
+      "the notarization of {} finished with the `{}` status (submission {}):\n{}",
+      path.display(),
+      status,
+      id,
+      log_excerpt(&String::from_utf8_lossy(&log.stdout))
+    )));
+  }
+
+  run(
+    "xcrun stapler staple",
+    Command::new("xcrun").args(&["stapler", "staple"]).arg(path),
+  )?;
+  Ok(())
+}
+
+/// Reads the submission id and status of the `notarytool submit --output-format json` output.
+fn submission_status(output: &str) -> Option<(String, String)> {
+  let output: JsonValue = serde_json::from_str(output.trim()).ok()?;
+  Some((
+    output["id"].as_str()?.to_string(),
+    output["status"].as_str()?.to_string(),
+  ))
+}
+
+/// Formats the issues of the `notarytool log` output, falling back to the raw log.
+fn log_excerpt(log: &str) -> String {
+  let issues = serde_json::from_str::<JsonValue>(log)
+    .ok()
+    .and_then(|log| log["issues"].as_array().cloned())
+    .unwrap_or_default();
+  if issues.is_empty() {
+    return log.trim().to_string();
+  }
+  let mut excerpt: Vec<String> = issues
+    .iter()
+    .take(MAX_LOG_ISSUES)
+    .map(|issue| {
+      format!(
+        "- {}: {}",
+        issue["path"].as_str().unwrap_or("(unknown path)"),
+        issue["message"].as_str().unwrap_or("(no message)")
+      )
+    })
+    .collect();
+  if issues.len() > MAX_LOG_ISSUES {
+    excerpt.push(format!("and {} more issues", issues.len() - MAX_LOG_ISSUES));
+  }
+  excerpt.join("\n")
+}
+
+/// Runs the command, failing with its verbatim output if it exits with an error.
+fn run(name: &str, cmd: &mut Command) -> crate::Result<Output> {
+  let output = cmd.output()?;
+  if output.status.success() {
+    Ok(output)
+  } else {
+    Err(crate::Error::GenericError(format!(
+      "failed to run {}:\n{}{}",
+      name,
+      String::from_utf8_lossy(&output.stdout),
+      String::from_utf8_lossy(&output.stderr)
+    )))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{codesign_args, log_excerpt, run, submission_status, NotarizeAuth};
+  use std::path::{Path, PathBuf};
+
+  #[test]
+  fn codesign_arguments() {
+    assert_eq!(
+      codesign_args(
+        Path::new("app.app/Contents/Frameworks/lib.framework"),
+        "Developer ID Application: Tauri",
+        false,
+        false,
+        None
+      ),
+      vec![
+        "--force",
+        "--timestamp",
+        "--sign",
+        "Developer ID Application: Tauri",
+        "app.app/Contents/Frameworks/lib.framework"
+      ]
+    );
+    assert_eq!(
+      codesign_args(
+        Path::new("app.app"),
+        "Developer ID Application: Tauri",
+        true,
+        true,
+        Some(Path::new("entitlements.plist"))
+      ),
+      vec![
+        "--force",
+        "--timestamp",
+        "--sign",
+        "Developer ID Application: Tauri",
+        "--deep",
+        "--options",
+        "runtime",
+        "--entitlements",
+        "entitlements.plist",
+        "app.app"
+      ]
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn failed_commands_print_their_output() {
+    assert!(run("true", &mut std::process::Command::new("true")).is_ok());
+    let error = run(
+      "sh",
+      std::process::Command::new("sh").args(&["-c", "echo invalid identity; exit 1"]),
+    )
+    .unwrap_err();
+    assert!(error
+      .to_string()
+      .contains("failed to run sh:\ninvalid identity\n"));
+  }
+
+  #[test]
+  fn notarization_credentials() {
+    let profile = NotarizeAuth::KeychainProfile {
+      profile: "notary".into(),
+      keychain: None,
+    };
+    assert_eq!(profile.args(), vec!["--keychain-profile", "notary"]);
+    let profile = NotarizeAuth::KeychainProfile {
+      profile: "notary".into(),
+      keychain: Some(PathBuf::from("/tmp/ci.keychain-db")),
+    };
+    assert_eq!(
+      profile.args(),
+      vec![
+        "--keychain-profile",
+        "notary",
+        "--keychain",
+        "/tmp/ci.keychain-db"
+      ]
+    );
+    let api_key = NotarizeAuth::ApiKey {
+      key_id: "KEY".into(),
+      issuer: "ISSUER".into(),
+      key_path: PathBuf::from("AuthKey_KEY.p8"),
+    };
+    assert_eq!(
+      api_key.args(),
+      vec![
+        "--key-id",
+        "KEY",
+        "--issuer",
+        "ISSUER",
+        "--key",
+        "AuthKey_KEY.p8"
+      ]
+    );
+  }
+
+  #[test]
+  fn notarization_output() {
+    assert_eq!(
+      submission_status(r#"{"id":"2efe2717","status":"Invalid","message":"Processing complete"}"#),
+      Some(("2efe2717".to_string(), "Invalid".to_string()))
+    );
+    assert_eq!(submission_status("Error: HTTP status code: 401"), None);
+    assert_eq!(
+      log_excerpt(
+        r#"{"status":"Invalid","issues":[{"severity":"error","path":"app.zip/app.app/Contents/MacOS/app","message":"The binary is not signed."}]}"#
+      ),
+      "- app.zip/app.app/Contents/MacOS/app: The binary is not signed."
+    );
+    assert_eq!(log_excerpt("not found\n"), "not found");
+  }
+}
@@ -1,9 +1,330 @@
+// The RPM package is built by `rpmbuild` from a generated spec file,
+// with the same files as the Debian package:
+//
+// foobar-1.2.3-1.x86_64.rpm
+//     usr/bin/foobar                            # Binary executable file
+//     usr/share/applications/foobar.desktop     # Desktop file (for apps)
+//     usr/share/icons/hicolor/...               # Icon files (for apps)
+//     usr/share/mime/packages/foobar.xml        # MIME types (for file associations)
+//     usr/lib/foobar/...                        # Other resource files
+
+use super::{common, deb_bundle};
 use crate::Settings;
 
-use std::path::PathBuf;
+use anyhow::Context;
+use walkdir::WalkDir;
+
+use std::{
+  fs,
+  io::Write,
+  path::{Component, Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+/// Returns whether `rpmbuild` is installed.
+pub fn is_available() -> bool {
+  Command::new("rpmbuild")
+    .arg("--version")
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .map(|status| status.success())
+    .unwrap_or(false)
+}
 
 /// Bundles the project.
-/// Not implemented yet.
-pub fn bundle_project(_settings: &Settings) -> crate::Result<Vec<PathBuf>> {
-  unimplemented!();
+/// Returns a vector of PathBuf that shows where the RPM was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  if !is_available() {
+    return Err(crate::Error::GenericError(
+      "the rpm bundle requires `rpmbuild`; install the `rpm-build` package and try again"
+        .to_string(),
+    ));
+  }
+
+  let arch = match settings.binary_arch() {
+    "x86" => "i386",
+    "arm" => "armhfp",
+    other => other,
+  };
+  let name = deb_bundle::package_name(settings);
+  let version = rpm_version(settings.version_string());
+  let package_base_name = format!(
+    "{}-{}-{}.{}{}",
+    name,
+    version,
+    settings.rpm_release(),
+    arch,
+    settings.artifact_suffix()
+  );
+  let package_name = format!("{}.rpm", package_base_name);
+  common::print_bundling(&package_name)?;
+  let base_dir = settings.project_out_directory().join("bundle/rpm");
+  let package_dir = base_dir.join(&package_base_name);
+  if package_dir.exists() {
+    fs::remove_dir_all(&package_dir)
+      .with_context(|| format!("Failed to remove old {}", package_base_name))?;
+  }
+  let package_path = base_dir.join(&package_name);
+
+  let data_dir = generate_data(settings, &package_dir)
+    .with_context(|| "Failed to build data folders and files")?;
+  let spec_path = package_dir.join(format!("{}.spec", name));
+  generate_spec_file(settings, &name, &version, &data_dir, &spec_path)
+    .with_context(|| "Failed to create spec file")?;
+
+  let mut cmd = Command::new("rpmbuild");
+  cmd
+    .arg("-bb")
+    .arg(&spec_path)
+    .arg("--target")
+    .arg(arch)
+    .arg("--define")
+    .arg(format!(
+      "_topdir {}",
+      package_dir.join("rpmbuild").display()
+    ))
+    .arg("--define")
+    .arg(format!("_rpmdir {}", base_dir.display()))
+    .arg("--define")
+    .arg(format!(
+      "_rpmfilename %{{NAME}}-%{{VERSION}}-%{{RELEASE}}.%{{ARCH}}{}.rpm",
+      settings.artifact_suffix()
+    ));
+  common::execute_with_verbosity(&mut cmd, settings).map_err(|_| {
+    crate::Error::ShellScriptError(format!(
+      "error running rpmbuild{}",
+      if settings.is_verbose() {
+        ""
+      } else {
+        ", try running with --verbose to see command output"
+      }
+    ))
+  })?;
+
+  Ok(vec![package_path])
+}
+
+/// Generates the files of the package, with the same layout as the Debian package.
+fn generate_data(settings: &Settings, package_dir: &Path) -> crate::Result<PathBuf> {
+  let data_dir = package_dir.join("data");
+  let bin_dir = data_dir.join("usr/bin");
+
+  for bin in settings.binaries() {
+    let bin_path = settings.binary_path(bin);
+    common::copy_file(&bin_path, &bin_dir.join(bin.name()))
+      .with_context(|| format!("Failed to copy binary from {:?}", bin_path))?;
+  }
+
+  deb_bundle::transfer_resource_files(settings, &data_dir)
+    .with_context(|| "Failed to copy resource files")?;
+
+  settings
+    .copy_binaries(&bin_dir)
+    .with_context(|| "Failed to copy external binaries")?;
+
+  deb_bundle::generate_icon_files(settings, &data_dir)
+    .with_context(|| "Failed to create icon files")?;
+  match settings.rpm_desktop_template() {
+    Some(template) => {
+      deb_bundle::generate_desktop_file_from_template(settings, template, false, &data_dir)
+    }
+    None => deb_bundle::generate_desktop_file(settings, false, None, &data_dir),
+  }
+  .with_context(|| "Failed to create desktop file")?;
+  deb_bundle::generate_mime_file(settings, &data_dir)
+    .with_context(|| "Failed to create MIME type file")?;
+
+  // the extra files are copied last so they can't overwrite the files written by the bundler
+  copy_custom_files(&settings.rpm_files(), &data_dir)
+    .with_context(|| "Failed to copy custom files")?;
+
+  Ok(data_dir)
+}
+
+/// Copies the extra files to their install path.
+/// Fails if an install path is relative, has `.` or `..` components or is already written by the bundler.
+fn copy_custom_files(files: &[(&PathBuf, &PathBuf)], data_dir: &Path) -> crate::Result<()> {
+  for (src, install_path) in files {
+    let mut components = install_path.components();
+    let valid = components.next() == Some(Component::RootDir)
+      && components.all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+      return Err(crate::Error::GenericError(format!(
+        "the install path {} of {} must be absolute, without `.` or `..` components",
+        install_path.display(),
+        src.display()
+      )));
+    }
+    let dest = data_dir.join(install_path.strip_prefix("/")?);
+    if dest.exists() {
+      return Err(crate::Error::GenericError(format!(
+        "{} can't be installed to {} since the bundle already has a file at that path",
+        src.display(),
+        install_path.display()
+      )));
+    }
+    common::copy_file(src, &dest)?;
+  }
+  Ok(())
+}
+
+/// The RPM version of the package version:
+/// `-` separates the version from the release, and `~` sorts the pre-releases before the release.
+fn rpm_version(version: &str) -> String {
+  version.replace('-', "~")
+}
+
+/// The `%files` entries of the files of the `data_dir`, sorted by path.
+fn spec_files(data_dir: &Path) -> crate::Result<Vec<String>> {
+  let mut files = Vec::new();
+  for entry in WalkDir::new(data_dir).sort_by(|a, b| a.file_name().cmp(b.file_name())) {
+    let entry = entry?;
+    if entry.file_type().is_dir() {
+      continue;
+    }
+    let path = Path::new("/").join(entry.path().strip_prefix(data_dir)?);
+    // `%` starts a macro on the spec file
+    files.push(format!(
+      "\"{}\"",
+      path.display().to_string().replace('%', "%%")
+    ));
+  }
+  Ok(files)
+}
+
+/// Generates the spec file, which installs the files of the `data_dir`.
+fn generate_spec_file(
+  settings: &Settings,
+  name: &str,
+  version: &str,
+  data_dir: &Path,
+  spec_path: &Path,
+) -> crate::Result<()> {
+  // For more information about the format of this file, see
+  // https://rpm-software-management.github.io/rpm/manual/spec.html
+  let file = &mut common::create_file(spec_path)?;
+  // the binaries are already built, so there's no debug info to extract
+  writeln!(file, "%global debug_package %{{nil}}")?;
+  writeln!(file, "Name: {}", name)?;
+  writeln!(file, "Version: {}", version)?;
+  writeln!(file, "Release: {}", settings.rpm_release())?;
+  if let Some(epoch) = settings.rpm_epoch() {
+    writeln!(file, "Epoch: {}", epoch)?;
+  }
+  let summary = settings.short_description();
+  writeln!(
+    file,
+    "Summary: {}",
+    if summary.is_empty() {
+      settings.bundle_name()
+    } else {
+      summary
+    }
+  )?;
+  for (locale, localization) in settings.localization() {
+    if let Some(summary) = &localization.short_description {
+      writeln!(
+        file,
+        "Summary({}): {}",
+        deb_bundle::posix_locale(locale),
+        summary
+      )?;
+    }
+  }
+  writeln!(
+    file,
+    "License: {}",
+    settings.license().unwrap_or("Unspecified")
+  )?;
+  if !settings.homepage_url().is_empty() {
+    writeln!(file, "URL: {}", settings.homepage_url())?;
+  }
+  for dependency in settings.rpm_dependencies() {
+    writeln!(file, "Requires: {}", dependency)?;
+  }
+  writeln!(file)?;
+  writeln!(file, "%description")?;
+  writeln!(
+    file,
+    "{}",
+    settings
+      .long_description()
+      .unwrap_or_else(|| settings.short_description())
+  )?;
+  for (locale, localization) in settings.localization() {
+    let description = localization
+      .long_description
+      .as_ref()
+      .or_else(|| localization.short_description.as_ref());
+    if let Some(description) = description {
+      writeln!(file)?;
+      writeln!(file, "%description -l {}", deb_bundle::posix_locale(locale))?;
+      writeln!(file, "{}", description)?;
+    }
+  }
+  writeln!(file)?;
+  writeln!(file, "%install")?;
+  writeln!(file, "mkdir -p %{{buildroot}}")?;
+  writeln!(file, "cp -a \"{}\"/. %{{buildroot}}/", data_dir.display())?;
+  writeln!(file)?;
+  writeln!(file, "%files")?;
+  for entry in spec_files(data_dir)? {
+    writeln!(file, "{}", entry)?;
+  }
+  file.flush()?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{copy_custom_files, rpm_version, spec_files};
+  use std::{fs, path::PathBuf};
+
+  #[test]
+  fn versions() {
+    assert_eq!(rpm_version("1.2.3"), "1.2.3");
+    assert_eq!(rpm_version("1.2.3-beta.1"), "1.2.3~beta.1");
+  }
+
+  #[test]
+  fn spec_file_entries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(data_dir.join("usr/bin")).unwrap();
+    fs::create_dir_all(data_dir.join("usr/lib/app/empty")).unwrap();
+    fs::write(data_dir.join("usr/bin/app"), "app").unwrap();
+    fs::write(data_dir.join("usr/lib/app/100%.txt"), "").unwrap();
+    // the directories aren't listed, and `%` is escaped
+    assert_eq!(
+      spec_files(&data_dir).unwrap(),
+      vec![r#""/usr/bin/app""#, r#""/usr/lib/app/100%%.txt""#]
+    );
+  }
+
+  #[test]
+  fn custom_files_are_installed_inside_the_package() {
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("config.toml");
+    fs::write(&src, "key = 1\n").unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(data_dir.join("usr/bin")).unwrap();
+    fs::write(data_dir.join("usr/bin/app"), "app").unwrap();
+
+    let install_path = PathBuf::from("/etc/app/config.toml");
+    copy_custom_files(&[(&src, &install_path)], &data_dir).unwrap();
+    assert_eq!(
+      fs::read_to_string(data_dir.join("etc/app/config.toml")).unwrap(),
+      "key = 1\n"
+    );
+
+    // the bundler already wrote the binary
+    let binary_path = PathBuf::from("/usr/bin/app");
+    assert!(copy_custom_files(&[(&src, &binary_path)], &data_dir).is_err());
+    for install_path in &["etc/app/relative.toml", "/usr/../../escaped.toml"] {
+      let install_path = PathBuf::from(install_path);
+      assert!(copy_custom_files(&[(&src, &install_path)], &data_dir).is_err());
+    }
+    assert!(!tmp.path().join("escaped.toml").exists());
+  }
 }
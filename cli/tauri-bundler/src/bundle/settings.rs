@@ -1,14 +1,25 @@
-use super::category::AppCategory;
-use crate::bundle::{common, platform::target_triple};
+use super::{
+  category::AppCategory,
+  tauri_config::{
+    BundleLocalization, BundleResources, FileAssociation, NsisCompression, NsisInstallMode,
+    Position, ResourcesCompression, SignCommand, Size, WebviewInstallMode,
+  },
+};
+#[cfg(target_os = "windows")]
+use crate::bundle::nsis_bundle;
+use crate::bundle::{common, platform::target_triple, rpm_bundle};
 
 use serde::Deserialize;
+use sha2::Digest;
 use target_build_utils::TargetInfo;
 
 use std::{
-  collections::HashMap,
-  fs::File,
-  io::Read,
-  path::{Path, PathBuf},
+  collections::{BTreeMap, HashMap},
+  fs::{self, File},
+  io::{self, Read, Write},
+  path::{Component, Path, PathBuf},
+  sync::Once,
+  time::Duration,
 };
 
 /// The type of the package we're bundling.
@@ -21,6 +32,9 @@ pub enum PackageType {
   /// The Windows bundle (.msi).
   #[cfg(target_os = "windows")]
   WindowsMsi,
+  /// The Windows NSIS installer (-setup.exe).
+  #[cfg(target_os = "windows")]
+  Nsis,
   /// The Linux Debian package bundle (.deb).
   Deb,
   /// The Linux RPM bundle (.rpm).
@@ -29,11 +43,13 @@ pub enum PackageType {
   AppImage,
   /// The macOS DMG bundle (.dmg).
   Dmg,
+  /// The updater archives and signatures of the update bundles (.tar.gz, .sig).
+  Updater,
 }
 
 impl PackageType {
   /// Maps a short name to a PackageType.
-  /// Possible values are "deb", "ios", "msi", "osx", "rpm", "appimage", "dmg".
+  /// Possible values are "deb", "ios", "msi", "nsis", "osx", "rpm", "appimage", "dmg", "updater".
   pub fn from_short_name(name: &str) -> Option<PackageType> {
     // Other types we may eventually want to support: apk.
     match name {
@@ -41,10 +57,13 @@ impl PackageType {
       "ios" => Some(PackageType::IosBundle),
       #[cfg(target_os = "windows")]
       "msi" => Some(PackageType::WindowsMsi),
+      #[cfg(target_os = "windows")]
+      "nsis" => Some(PackageType::Nsis),
       "osx" => Some(PackageType::OsxBundle),
       "rpm" => Some(PackageType::Rpm),
       "appimage" => Some(PackageType::AppImage),
       "dmg" => Some(PackageType::Dmg),
+      "updater" => Some(PackageType::Updater),
       _ => None,
     }
   }
@@ -57,10 +76,13 @@ impl PackageType {
       PackageType::IosBundle => "ios",
       #[cfg(target_os = "windows")]
       PackageType::WindowsMsi => "msi",
+      #[cfg(target_os = "windows")]
+      PackageType::Nsis => "nsis",
       PackageType::OsxBundle => "osx",
       PackageType::Rpm => "rpm",
       PackageType::AppImage => "appimage",
       PackageType::Dmg => "dmg",
+      PackageType::Updater => "updater",
     }
   }
 
@@ -68,6 +90,34 @@ impl PackageType {
   pub fn all() -> &'static [PackageType] {
     ALL_PACKAGE_TYPES
   }
+
+  /// Gets the package types that must be bundled before this one, out of the requested package types.
+  pub fn dependencies(self, package_types: &[PackageType]) -> Vec<PackageType> {
+    match self {
+      // the DMG wraps the .app bundle
+      PackageType::Dmg => vec![PackageType::OsxBundle],
+      // the updater archives and signs the requested bundles it can install
+      PackageType::Updater => package_types
+        .iter()
+        .copied()
+        .filter(|package_type| UPDATER_PACKAGE_TYPES.contains(package_type))
+        .collect(),
+      _ => Vec::new(),
+    }
+  }
+}
+
+// the package types are resolved more than once, e.g. to validate them before the build
+static MISSING_TOOL_WARNING: Once = Once::new();
+
+/// Returns the external tool required to build the package type, if it isn't installed.
+fn missing_tool(package_type: PackageType) -> Option<&'static str> {
+  match package_type {
+    PackageType::Rpm if !rpm_bundle::is_available() => Some("rpmbuild"),
+    #[cfg(target_os = "windows")]
+    PackageType::Nsis if nsis_bundle::makensis_path().is_none() => Some("makensis"),
+    _ => None,
+  }
 }
 
 const ALL_PACKAGE_TYPES: &[PackageType] = &[
@@ -75,10 +125,23 @@ const ALL_PACKAGE_TYPES: &[PackageType] = &[
   PackageType::IosBundle,
   #[cfg(target_os = "windows")]
   PackageType::WindowsMsi,
+  #[cfg(target_os = "windows")]
+  PackageType::Nsis,
   PackageType::OsxBundle,
   PackageType::Rpm,
   PackageType::Dmg,
   PackageType::AppImage,
+  PackageType::Updater,
+];
+
+/// The package types the updater can install.
+const UPDATER_PACKAGE_TYPES: &[PackageType] = &[
+  PackageType::OsxBundle,
+  PackageType::AppImage,
+  #[cfg(target_os = "windows")]
+  PackageType::WindowsMsi,
+  #[cfg(target_os = "windows")]
+  PackageType::Nsis,
 ];
 
 /// The bundle settings of the BuildArtifact we're bundling.
@@ -98,9 +161,19 @@ struct BundleSettings {
   /// each item can be a path to a file or a path to a folder.
   ///
   /// supports glob patterns.
-  resources: Option<Vec<String>>,
+  ///
+  /// it can also be a map of those paths to their destination on the resource directory.
+  resources: Option<BundleResources>,
+  /// the compression of the bundled resources.
+  resources_compression: Option<ResourcesCompression>,
   /// the app's copyright.
   copyright: Option<String>,
+  /// the app's publisher.
+  publisher: Option<String>,
+  /// the app's homepage URL.
+  homepage: Option<String>,
+  /// the path to the app's license.
+  license_file: Option<PathBuf>,
   /// the app's category.
   category: Option<AppCategory>,
   /// the app's short description.
@@ -118,6 +191,28 @@ struct BundleSettings {
   ///
   /// without it, you can't run some applications installed by the user.
   deb_use_bootstrapper: Option<bool>,
+  /// extra files to add to the debian package, mapping the source path to the absolute install path.
+  deb_files: Option<HashMap<PathBuf, PathBuf>>,
+  /// the debian package section, e.g. `utils`.
+  deb_section: Option<String>,
+  /// the debian package priority, e.g. `optional`.
+  deb_priority: Option<String>,
+  /// the path to the Debian-format changelog of the package.
+  deb_changelog: Option<PathBuf>,
+  /// whether the man pages of the CLI definition should be added to the debian package or not.
+  deb_generate_man_page: Option<bool>,
+  /// the path to the handlebars template of the debian package desktop file.
+  deb_desktop_template: Option<PathBuf>,
+  /// extra keys merged into the generated desktop file of the debian package.
+  deb_desktop_entries: Option<HashMap<String, String>>,
+  /// the path to the debian package script run before the package is installed.
+  deb_preinst: Option<PathBuf>,
+  /// the path to the debian package script run after the package is installed.
+  deb_postinst: Option<PathBuf>,
+  /// the path to the debian package script run before the package is removed.
+  deb_prerm: Option<PathBuf>,
+  /// the path to the debian package script run after the package is removed.
+  deb_postrm: Option<PathBuf>,
   /// Mac OS X frameworks that need to be bundled with the app.
   ///
   /// Each string can either be the name of a framework (without the `.framework` extension, e.g. `"SDL2"`),
@@ -129,8 +224,9 @@ struct BundleSettings {
   ///
   /// - embedding the correct rpath in your binary (e.g. by running `install_name_tool -add_rpath "@executable_path/../Frameworks" path/to/binary` after compiling)
   osx_frameworks: Option<Vec<String>>,
-  /// A version string indicating the minimum Mac OS X version that the bundled app supports (e.g. `"10.11"`).
-  /// If you are using this config field, you may also want have your `build.rs` script emit `cargo:rustc-env=MACOSX_DEPLOYMENT_TARGET=10.11`.
+  /// A version string indicating the minimum Mac OS X version that the bundled app supports (e.g. `"10.13"`),
+  /// written to the `LSMinimumSystemVersion` of the Info.plist.
+  /// The Tauri CLI compiles the app with the matching `MACOSX_DEPLOYMENT_TARGET`.
   osx_minimum_system_version: Option<String>,
   /// The path to the LICENSE file for macOS apps.
   /// Currently only used by the dmg bundle.
@@ -141,6 +237,77 @@ struct BundleSettings {
   ///
   /// without it, you can't run some applications installed by the user.
   osx_use_bootstrapper: Option<bool>,
+  /// Custom keys to merge over the generated Info.plist of the macOS .app bundle.
+  osx_info_plist: Option<HashMap<String, serde_json::Value>>,
+  /// The identity used to sign the macOS .app bundle.
+  /// The bundle is left unsigned if it's not set.
+  osx_signing_identity: Option<String>,
+  /// The provider short name used on notarization, for Apple IDs that belong to multiple teams.
+  osx_provider_short_name: Option<String>,
+  /// The path to the entitlements plist used to sign the macOS .app bundle.
+  osx_entitlements: Option<PathBuf>,
+  /// Whether the macOS .app bundle should be signed with the hardened runtime or not.
+  osx_hardened_runtime: Option<bool>,
+  /// Whether the signed .app bundle and DMG are notarized.
+  osx_notarize: Option<bool>,
+  /// How many seconds the notarization waits for the result of the notary service.
+  osx_notarize_timeout: Option<u64>,
+  /// the list of RPM dependencies.
+  rpm_depends: Option<Vec<String>>,
+  /// the RPM package release.
+  rpm_release: Option<String>,
+  /// the RPM package epoch.
+  rpm_epoch: Option<u32>,
+  /// extra files to add to the RPM package, mapping the source path to the absolute install path.
+  rpm_files: Option<HashMap<PathBuf, PathBuf>>,
+  /// the path to the handlebars template of the RPM package desktop file.
+  rpm_desktop_template: Option<PathBuf>,
+  /// whether the GStreamer libraries and plugins should be copied into the AppImage or not.
+  appimage_bundle_media_framework: Option<bool>,
+  /// extra files to add to the AppImage, mapping the source path to the absolute path inside the AppImage.
+  appimage_files: Option<HashMap<PathBuf, PathBuf>>,
+  /// The path to the background image of the DMG window.
+  dmg_background: Option<PathBuf>,
+  /// The size of the DMG window.
+  dmg_window_size: Option<Size>,
+  /// The position of the app icon on the DMG window.
+  dmg_app_position: Option<Position>,
+  /// The position of the Applications folder link on the DMG window.
+  dmg_application_folder_position: Option<Position>,
+  /// How the WebView2 runtime is installed by the Windows installer.
+  windows_webview_install_mode: Option<WebviewInstallMode>,
+  /// The WiX cultures of the MSI installers, one installer per culture.
+  windows_wix_languages: Option<Vec<String>>,
+  /// The MSI ProductVersion, overriding the one derived from the app version.
+  windows_msi_version: Option<String>,
+  /// The MSI UpgradeCode, overriding the one derived from the bundle identifier.
+  windows_upgrade_code: Option<String>,
+  /// Whether the MSI installer can replace a newer installed version.
+  windows_allow_downgrades: Option<bool>,
+  /// The WiX source files compiled and linked with the MSI installer sources.
+  windows_wix_fragment_paths: Option<Vec<PathBuf>>,
+  /// The ids of the fragments components included on the main feature of the MSI installer.
+  windows_component_refs: Option<Vec<String>>,
+  /// The ids of the fragments features included on the MSI installer.
+  windows_feature_refs: Option<Vec<String>>,
+  /// Whether the Windows installers create a desktop shortcut.
+  windows_desktop_shortcut: Option<bool>,
+  /// Whether the Windows installers create a start menu shortcut.
+  windows_start_menu_shortcut: Option<bool>,
+  /// The start menu folder of the Windows installers shortcut.
+  windows_start_menu_folder: Option<String>,
+  /// who the NSIS installer installs the app for.
+  nsis_install_mode: Option<NsisInstallMode>,
+  /// the path to the license shown by the NSIS installer.
+  nsis_license: Option<PathBuf>,
+  /// the path to the header image of the NSIS installer pages.
+  nsis_header_image: Option<PathBuf>,
+  /// the path to the sidebar image of the NSIS installer welcome and finish pages.
+  nsis_sidebar_image: Option<PathBuf>,
+  /// the languages of the NSIS installer.
+  nsis_languages: Option<Vec<String>>,
+  /// the compression algorithm of the NSIS installer.
+  nsis_compression: Option<NsisCompression>,
   // Bundles for other binaries/examples:
   /// Configuration map for the possible [bin] apps to bundle.
   bin: Option<HashMap<String, BundleSettings>>,
@@ -159,6 +326,18 @@ struct BundleSettings {
   ///
   /// This allows communication to the outside world e.g. a web server you're shipping.
   exception_domain: Option<String>,
+  /// The file types the app can open.
+  file_associations: Option<Vec<FileAssociation>>,
+  /// The URL schemes the app handles.
+  deep_link_schemes: Option<Vec<String>>,
+  /// The command used to sign the bundled executables and installers.
+  sign_command: Option<SignCommand>,
+  /// The directory the artifacts are moved to.
+  output_dir: Option<PathBuf>,
+  /// The template of the artifact file names, e.g. `{name}-{version}-{target}.{ext}`.
+  artifact_name: Option<String>,
+  /// The bundle metadata of each locale.
+  localization: Option<HashMap<String, BundleLocalization>>,
 }
 
 /// The `metadata` section of the package configuration.
@@ -187,6 +366,8 @@ struct PackageSettings {
   description: String,
   /// the package's homepage.
   homepage: Option<String>,
+  /// the package's license.
+  license: Option<String>,
   /// the package's authors.
   authors: Option<Vec<String>>,
   /// the package's metadata.
@@ -231,9 +412,11 @@ pub struct BundleBinary {
 }
 
 impl BundleBinary {
-  pub fn new(name: String, main: bool) -> Self {
+  /// Creates a new binary definition.
+  /// `windows` determines whether the `.exe` extension is appended to the name.
+  pub fn new(name: String, main: bool, windows: bool) -> Self {
     Self {
-      name: if cfg!(windows) {
+      name: if windows {
         format!("{}.exe", name)
       } else {
         name
@@ -252,7 +435,6 @@ impl BundleBinary {
     &self.name
   }
 
-  #[cfg(windows)]
   pub fn main(&self) -> bool {
     self.main
   }
@@ -271,12 +453,20 @@ pub struct Settings {
   target: Option<(String, TargetInfo)>,
   /// the features to use to build the app with `cargo build --features foo bar`.
   features: Option<Vec<String>>,
+  /// the binary used to build the app, e.g. `cross`. Defaults to `cargo`.
+  runner: Option<String>,
   /// the directory where the bundles will be placed.
   project_out_directory: PathBuf,
   /// whether we should build the app with release mode or not.
   is_release: bool,
   /// whether or not to enable verbose logging
   is_verbose: bool,
+  /// the maximum number of package types bundled at the same time.
+  jobs: Option<usize>,
+  /// whether the remaining package types should be skipped when one of them fails.
+  is_fail_fast: bool,
+  /// whether the notarization is skipped even if it's enabled on the config.
+  skip_notarize: bool,
   /// the bundle settings.
   bundle_settings: BundleSettings,
   /// the binaries to bundle.
@@ -310,8 +500,12 @@ pub struct SettingsBuilder {
   target_triple: Option<String>,
   release: bool,
   verbose: bool,
+  jobs: Option<usize>,
+  fail_fast: bool,
+  skip_notarize: bool,
   package_types: Option<Vec<PackageType>>,
   features: Option<Vec<String>>,
+  runner: Option<String>,
 }
 
 impl SettingsBuilder {
@@ -334,6 +528,21 @@ impl SettingsBuilder {
     self
   }
 
+  pub fn jobs(mut self, jobs: usize) -> Self {
+    self.jobs = Some(jobs);
+    self
+  }
+
+  pub fn fail_fast(mut self) -> Self {
+    self.fail_fast = true;
+    self
+  }
+
+  pub fn skip_notarize(mut self) -> Self {
+    self.skip_notarize = true;
+    self
+  }
+
   pub fn package_types(mut self, package_types: Vec<PackageType>) -> Self {
     self.package_types = Some(package_types);
     self
@@ -344,6 +553,11 @@ impl SettingsBuilder {
     self
   }
 
+  pub fn runner(mut self, runner: String) -> Self {
+    self.runner = Some(runner);
+    self
+  }
+
   /// Builds a Settings from the CLI args.
   ///
   /// Package settings will be read from Cargo.toml.
@@ -369,8 +583,16 @@ impl SettingsBuilder {
       None
     };
     let target_dir = Settings::get_target_dir(&workspace_dir, &target, self.release)?;
+    let is_windows_target = match target {
+      Some((_, ref info)) => info.target_os() == "windows",
+      None => cfg!(windows),
+    };
     let bundle_settings = match tauri_config {
-      Ok(config) => merge_settings(BundleSettings::default(), config.tauri.bundle),
+      Ok(config) => merge_settings(
+        BundleSettings::default(),
+        config.tauri.bundle,
+        config.package,
+      ),
       Err(e) => {
         let error_message = e.to_string();
         if !error_message.contains("No such file or directory") {
@@ -404,6 +626,7 @@ impl SettingsBuilder {
           BundleBinary::new(
             binary.name.clone(),
             binary.name.as_str() == package.name || binary.name.as_str() == default_run,
+            is_windows_target,
           )
           .set_src_path(binary.path),
         )
@@ -421,7 +644,11 @@ impl SettingsBuilder {
               || path.ends_with(bin.src_path.as_ref().unwrap_or(&"".to_string()))
           });
           if !bin_exists {
-            binaries.push(BundleBinary::new(name.to_string_lossy().to_string(), false))
+            binaries.push(BundleBinary::new(
+              name.to_string_lossy().to_string(),
+              false,
+              is_windows_target,
+            ))
           }
         }
       }
@@ -429,7 +656,11 @@ impl SettingsBuilder {
 
     if let Some(default_run) = package.default_run.as_ref() {
       if !binaries.iter().any(|bin| bin.name.as_str() == default_run) {
-        binaries.push(BundleBinary::new(default_run.to_string(), true));
+        binaries.push(BundleBinary::new(
+          default_run.to_string(),
+          true,
+          is_windows_target,
+        ));
       }
     }
 
@@ -439,19 +670,48 @@ impl SettingsBuilder {
       }
     }
 
-    let bundle_settings = parse_external_bin(bundle_settings)?;
-
-    Ok(Settings {
+    let settings = Settings {
       package,
       package_types: self.package_types,
       target,
       features: self.features,
+      runner: self.runner,
       is_release: self.release,
       is_verbose: self.verbose,
+      jobs: self.jobs,
+      is_fail_fast: self.fail_fast,
+      skip_notarize: self.skip_notarize,
       project_out_directory: target_dir,
       binaries,
       bundle_settings,
+    };
+    settings.validate_localization()?;
+    settings.validate_descriptions()?;
+    Ok(settings)
+  }
+}
+
+/// Whether the locale is a BCP 47 like language tag, e.g. `de`, `pt-BR` or `zh_Hant_TW`.
+fn is_valid_locale(locale: &str) -> bool {
+  let mut subtags = locale.split(|c| c == '-' || c == '_');
+  let language = subtags.next().unwrap_or_default();
+  (2..=3).contains(&language.len())
+    && language.chars().all(|c| c.is_ascii_alphabetic())
+    && subtags.all(|subtag| {
+      (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
     })
+}
+
+/// Returns the maximum length of the short description on a package type,
+/// if the package limits it.
+fn short_description_max_length(package_type: PackageType) -> Option<usize> {
+  match package_type {
+    // the synopsis of the control file
+    PackageType::Deb => Some(80),
+    // the `ARPCOMMENTS` property
+    #[cfg(target_os = "windows")]
+    PackageType::WindowsMsi => Some(255),
+    _ => None,
   }
 }
 
@@ -565,7 +825,7 @@ impl Settings {
   }
 
   /// If a list of package types was specified by the command-line, returns
-  /// that list filtered by the current target OS available targets.
+  /// that list, failing if any of them isn't available for the current target OS.
   ///
   /// If a target triple was specified by the
   /// command-line, returns the native package type(s) for that target.
@@ -582,9 +842,9 @@ impl Settings {
     let platform_types = match target_os {
       "macos" => vec![PackageType::OsxBundle, PackageType::Dmg],
       "ios" => vec![PackageType::IosBundle],
-      "linux" => vec![PackageType::Deb, PackageType::AppImage],
+      "linux" => vec![PackageType::Deb, PackageType::AppImage, PackageType::Rpm],
       #[cfg(target_os = "windows")]
-      "windows" => vec![PackageType::WindowsMsi],
+      "windows" => vec![PackageType::WindowsMsi, PackageType::Nsis],
       os => {
         return Err(crate::Error::GenericError(format!(
           "Native {} bundles not yet supported.",
@@ -593,20 +853,48 @@ impl Settings {
       }
     };
     if let Some(package_types) = &self.package_types {
-      let mut types = vec![];
-      for package_type in package_types {
-        let package_type = *package_type;
-        if platform_types
-          .clone()
-          .into_iter()
-          .any(|t| t == package_type)
-        {
-          types.push(package_type);
-        }
+      // the updater bundles the first package type of the target it can install, unless one is requested
+      let updater_type = platform_types
+        .iter()
+        .copied()
+        .find(|package_type| UPDATER_PACKAGE_TYPES.contains(package_type));
+      let unsupported: Vec<&str> = package_types
+        .iter()
+        .filter(|package_type| {
+          !(platform_types.contains(*package_type)
+            || **package_type == PackageType::Updater && updater_type.is_some())
+        })
+        .map(PackageType::short_name)
+        .collect();
+      if !unsupported.is_empty() {
+        return Err(crate::Error::GenericError(format!(
+          "bundle types not supported by the `{}` target: {}",
+          target_os,
+          unsupported.join(", ")
+        )));
+      }
+      let mut package_types = package_types.clone();
+      if package_types.contains(&PackageType::Updater)
+        && PackageType::Updater.dependencies(&package_types).is_empty()
+      {
+        package_types.extend(updater_type);
       }
-      Ok(types)
+      Ok(package_types)
     } else {
-      Ok(platform_types)
+      // the bundles built with external tools are skipped if the tool isn't installed
+      let (available, missing): (Vec<PackageType>, Vec<PackageType>) = platform_types
+        .into_iter()
+        .partition(|package_type| missing_tool(*package_type).is_none());
+      MISSING_TOOL_WARNING.call_once(|| {
+        for package_type in &missing {
+          let _ = common::print_warning(&format!(
+            "skipping the {} bundle since `{}` isn't installed",
+            package_type.short_name(),
+            missing_tool(*package_type).unwrap_or_default()
+          ));
+        }
+      });
+      Ok(available)
     }
   }
 
@@ -625,17 +913,42 @@ impl Settings {
     self.features.to_owned()
   }
 
+  /// Returns the binary used to build the app, defaulting to `cargo`.
+  pub fn runner(&self) -> &str {
+    self.runner.as_deref().unwrap_or("cargo")
+  }
+
   /// Returns true if the bundle is being compiled in release mode, false if
   /// it's being compiled in debug mode.
   pub fn is_release_build(&self) -> bool {
     self.is_release
   }
 
+  /// Returns the suffix of the artifact names, marking the debug builds.
+  pub fn artifact_suffix(&self) -> &str {
+    if self.is_release {
+      ""
+    } else {
+      "-debug"
+    }
+  }
+
   /// Returns true if verbose logging is enabled
   pub fn is_verbose(&self) -> bool {
     self.is_verbose
   }
 
+  /// Returns the maximum number of package types bundled at the same time.
+  /// Defaults to bundling all of them at once.
+  pub fn jobs(&self) -> Option<usize> {
+    self.jobs
+  }
+
+  /// Returns true if the remaining package types should be skipped when one of them fails.
+  pub fn is_fail_fast(&self) -> bool {
+    self.is_fail_fast
+  }
+
   /// Returns the bundle name, which is either package.metadata.bundle.name or package.name
   pub fn bundle_name(&self) -> &str {
     self
@@ -658,21 +971,114 @@ impl Settings {
     }
   }
 
-  /// Returns an iterator over the resource files to be included in this
-  /// bundle.
-  pub fn resource_files(&self) -> ResourcePaths<'_> {
+  /// Returns the resource files to be included in this bundle,
+  /// along with their path relative to the bundle resource directory.
+  ///
+  /// With `resourcesCompression`, the files are compressed to the `compressed-resources` folder of the out directory
+  /// and bundled with the compression extension, e.g. `data/strings.json.br`.
+  pub fn resource_files(&self) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
+    let targets = self.uncompressed_resource_files()?;
+    match self.resources_compression() {
+      ResourcesCompression::None => Ok(targets),
+      compression => compress_resource_targets(
+        targets,
+        compression,
+        &self.project_out_directory.join("compressed-resources"),
+      ),
+    }
+  }
+
+  fn uncompressed_resource_files(&self) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
     match self.bundle_settings.resources {
-      Some(ref paths) => ResourcePaths::new(paths.as_slice(), true),
-      None => ResourcePaths::new(&[], true),
+      Some(ref resources) => resource_targets(resources),
+      None => Ok(vec![]),
+    }
+  }
+
+  /// Returns the compression of the bundled resources.
+  pub fn resources_compression(&self) -> ResourcesCompression {
+    self
+      .bundle_settings
+      .resources_compression
+      .unwrap_or(ResourcesCompression::None)
+  }
+
+  /// Returns the manifest of the resource files, embedded in the app so it can check them:
+  /// their compression and the SHA-256 hash of their content, keyed by their `/` separated path on the resource directory.
+  pub fn resource_manifest(&self) -> crate::Result<serde_json::Value> {
+    let mut files = serde_json::Map::new();
+    for (src, dest) in self.uncompressed_resource_files()? {
+      let key = dest
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+      let content = fs::read(&src)?;
+      files.insert(key, format!("{:x}", sha2::Sha256::digest(&content)).into());
     }
+    Ok(serde_json::json!({
+      "compression": self.resources_compression(),
+      "files": files,
+    }))
   }
 
-  /// Returns an iterator over the external binaries to be included in this
-  /// bundle.
-  pub fn external_binaries(&self) -> ResourcePaths<'_> {
+  /// Returns the external binaries to be included in this bundle,
+  /// along with the file name they must have on the bundle.
+  ///
+  /// Each binary is resolved to the file with the target triple suffix, e.g. `binaries/ffmpeg-x86_64-unknown-linux-gnu`,
+  /// which is renamed to its plain name (e.g. `ffmpeg`) on the bundle.
+  ///
+  /// Fails if a binary doesn't exist for the current target.
+  pub fn external_binaries(&self) -> crate::Result<Vec<(PathBuf, String)>> {
+    let (target_triple, is_windows_target) = match self.target {
+      Some((ref triple, ref info)) => (triple.clone(), info.target_os() == "windows"),
+      None => (target_triple()?, cfg!(windows)),
+    };
     match self.bundle_settings.external_bin {
-      Some(ref paths) => ResourcePaths::new(paths.as_slice(), true),
-      None => ResourcePaths::new(&[], true),
+      Some(ref paths) => external_binaries(paths, &target_triple, is_windows_target),
+      None => Ok(Vec::new()),
+    }
+  }
+
+  /// Returns the file types the app can open.
+  pub fn file_associations(&self) -> &[FileAssociation] {
+    match self.bundle_settings.file_associations {
+      Some(ref associations) => associations.as_slice(),
+      None => &[],
+    }
+  }
+
+  /// Returns the URL schemes the app handles.
+  pub fn deep_link_schemes(&self) -> &[String] {
+    match self.bundle_settings.deep_link_schemes {
+      Some(ref schemes) => schemes.as_slice(),
+      None => &[],
+    }
+  }
+
+  /// Returns the command used to sign the bundled executables and installers.
+  pub fn sign_command(&self) -> Option<&SignCommand> {
+    self.bundle_settings.sign_command.as_ref()
+  }
+
+  /// Returns the directory the artifacts are moved to, relative to the current directory.
+  pub fn output_dir(&self) -> crate::Result<Option<PathBuf>> {
+    match self.bundle_settings.output_dir {
+      Some(ref output_dir) => Ok(Some(std::env::current_dir()?.join(output_dir))),
+      None => Ok(None),
+    }
+  }
+
+  /// Returns the template of the artifact file names.
+  pub fn artifact_name(&self) -> Option<&str> {
+    self.bundle_settings.artifact_name.as_deref()
+  }
+
+  /// Runs the sign command on the given file, if one is configured.
+  pub fn sign(&self, path: &Path) -> crate::Result<()> {
+    match self.sign_command() {
+      Some(command) => common::sign_file(command, path),
+      None => Ok(()),
     }
   }
 
@@ -682,27 +1088,24 @@ impl Settings {
   }
 
   /// Copies external binaries to a path.
+  ///
+  /// The bundles use the copies on the project out directory when they exist, since those might be signed.
   pub fn copy_binaries(&self, path: &Path) -> crate::Result<()> {
-    for src in self.external_binaries() {
-      let src = src?;
-      let dest = path.join(
+    for (src, name) in self.external_binaries()? {
+      let staged = self.project_out_directory.join(&name);
+      let src = if path != self.project_out_directory && staged.exists() {
+        staged
+      } else {
         src
-          .file_name()
-          .expect("failed to extract external binary filename"),
-      );
-      common::copy_file(&src, &dest)?;
+      };
+      common::copy_file(&src, &path.join(name))?;
     }
     Ok(())
   }
 
   /// Copies resources to a path.
   pub fn copy_resources(&self, path: &Path) -> crate::Result<()> {
-    for src in self.resource_files() {
-      let src = src?;
-      let dest = path.join(common::resource_relpath(&src));
-      common::copy_file(&src, &dest)?;
-    }
-    Ok(())
+    copy_resource_targets(&self.resource_files()?, path)
   }
 
   /// Returns the version string of the bundle, which is either package.metadata.version or package.version.
@@ -737,9 +1140,33 @@ impl Settings {
     }
   }
 
-  /// Returns the package's homepage URL, defaulting to "" if not defined.
+  /// Returns the app's publisher.
+  pub fn publisher(&self) -> Option<&str> {
+    self.bundle_settings.publisher.as_deref()
+  }
+
+  /// Returns the app's homepage URL, falling back to the package's homepage and defaulting to "" if not defined.
   pub fn homepage_url(&self) -> &str {
-    &self.package.homepage.as_deref().unwrap_or("")
+    self
+      .bundle_settings
+      .homepage
+      .as_deref()
+      .or_else(|| self.package.homepage.as_deref())
+      .unwrap_or("")
+  }
+
+  /// Returns the absolute path to the app's license.
+  /// Fails if the file doesn't exist.
+  pub fn license_file(&self) -> crate::Result<Option<PathBuf>> {
+    match self.bundle_settings.license_file {
+      Some(ref license) => resolve_license_file(&std::env::current_dir()?, license).map(Some),
+      None => Ok(None),
+    }
+  }
+
+  /// Returns the package's license, if defined.
+  pub fn license(&self) -> Option<&str> {
+    self.package.license.as_deref()
   }
 
   /// Returns the app's category.
@@ -756,9 +1183,110 @@ impl Settings {
       .unwrap_or(&self.package.description)
   }
 
-  /// Returns the app's long description.
+  /// Returns the app's long description, falling back to the package's description.
   pub fn long_description(&self) -> Option<&str> {
-    self.bundle_settings.long_description.as_deref()
+    match self.bundle_settings.long_description {
+      Some(ref description) => Some(description.as_str()),
+      None if !self.package.description.is_empty() => Some(self.package.description.as_str()),
+      None => None,
+    }
+  }
+
+  /// Returns the bundle metadata of each locale, sorted by locale.
+  pub fn localization(&self) -> BTreeMap<&str, &BundleLocalization> {
+    self
+      .bundle_settings
+      .localization
+      .iter()
+      .flatten()
+      .map(|(locale, localization)| (locale.as_str(), localization))
+      .collect()
+  }
+
+  /// Returns the bundle metadata of the locale, or of its language if no locale matches,
+  /// e.g. the `de` metadata for `de-DE`.
+  pub fn localization_for(&self, locale: &str) -> Option<&BundleLocalization> {
+    let localization = self.bundle_settings.localization.as_ref()?;
+    let normalize = |locale: &str| locale.replace('_', "-").to_lowercase();
+    let locale = normalize(locale);
+    let language = locale.split('-').next().unwrap_or_default().to_string();
+    localization
+      .iter()
+      .find(|(l, _)| normalize(l) == locale)
+      .or_else(|| localization.iter().find(|(l, _)| normalize(l) == language))
+      .map(|(_, localization)| localization)
+  }
+
+  /// Validates the locales and the single line fields of the localized bundle metadata.
+  fn validate_localization(&self) -> crate::Result<()> {
+    for (locale, localization) in self.localization() {
+      if !is_valid_locale(locale) {
+        return Err(crate::Error::GenericError(format!(
+          "invalid locale `{}` on `tauri > bundle > localization`, expected a language code with optional subtags, e.g. `de` or `pt-BR`",
+          locale
+        )));
+      }
+      let fields = [
+        ("productName", &localization.product_name),
+        ("shortDescription", &localization.short_description),
+        ("comments", &localization.comments),
+      ];
+      for (field, value) in fields.iter() {
+        if value.as_deref().map_or(false, |value| value.contains('\n')) {
+          return Err(crate::Error::GenericError(format!(
+            "`tauri > bundle > localization > {} > {}` must be a single line",
+            locale, field
+          )));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Validates the short description against the limits of the packages being bundled.
+  fn validate_descriptions(&self) -> crate::Result<()> {
+    let short_description = self.short_description().trim();
+    let length = short_description.chars().count();
+    for package_type in self.package_types()? {
+      if package_type == PackageType::Deb {
+        if short_description.is_empty() {
+          return Err(crate::Error::GenericError(
+            "the deb package requires a short description; set `tauri > bundle > shortDescription` or the Cargo.toml `description`".to_string(),
+          ));
+        }
+        if short_description.contains('\n') {
+          return Err(crate::Error::GenericError(
+            "the short description of the deb package must be a single line".to_string(),
+          ));
+        }
+      }
+      if let Some(max_length) = short_description_max_length(package_type) {
+        if length > max_length {
+          return Err(crate::Error::GenericError(format!(
+            "the short description is {} characters long, but the {} package allows up to {}",
+            length,
+            package_type.short_name(),
+            max_length
+          )));
+        }
+        for (locale, localization) in self.localization() {
+          let length = localization
+            .short_description
+            .as_deref()
+            .map_or(0, |description| description.trim().chars().count());
+          if length > max_length {
+            return Err(crate::Error::GenericError(format!(
+              "the `{}` short description is {} characters long, but the {} package allows up to {}",
+              locale,
+              length,
+              package_type.short_name(),
+              max_length
+            )));
+          }
+        }
+      }
+    }
+    Ok(())
   }
 
   /// Returns the dependencies of the debian bundle.
@@ -774,6 +1302,107 @@ impl Settings {
     self.bundle_settings.deb_use_bootstrapper.unwrap_or(false)
   }
 
+  /// Returns the extra files of the debian bundle, sorted by install path.
+  pub fn debian_files(&self) -> Vec<(&PathBuf, &PathBuf)> {
+    let mut files: Vec<(&PathBuf, &PathBuf)> = match self.bundle_settings.deb_files {
+      Some(ref files) => files.iter().collect(),
+      None => Vec::new(),
+    };
+    files.sort_by(|(_, a), (_, b)| a.cmp(b));
+    files
+  }
+
+  /// Returns the section of the debian bundle.
+  pub fn debian_section(&self) -> Option<&str> {
+    self.bundle_settings.deb_section.as_deref()
+  }
+
+  /// Returns the priority of the debian bundle.
+  pub fn debian_priority(&self) -> Option<&str> {
+    self.bundle_settings.deb_priority.as_deref()
+  }
+
+  /// Returns the path to the changelog of the debian bundle.
+  pub fn debian_changelog(&self) -> Option<&PathBuf> {
+    self.bundle_settings.deb_changelog.as_ref()
+  }
+
+  /// Returns whether the man pages of the CLI definition should be added to the debian bundle or not.
+  pub fn debian_generate_man_page(&self) -> bool {
+    self.bundle_settings.deb_generate_man_page.unwrap_or(false)
+  }
+
+  /// Returns the path to the desktop file template of the debian bundle.
+  pub fn debian_desktop_template(&self) -> Option<&PathBuf> {
+    self.bundle_settings.deb_desktop_template.as_ref()
+  }
+
+  /// Returns the extra keys of the desktop file of the debian bundle.
+  pub fn debian_desktop_entries(&self) -> Option<&HashMap<String, String>> {
+    self.bundle_settings.deb_desktop_entries.as_ref()
+  }
+
+  /// Returns the maintainer scripts of the debian bundle, by script name.
+  pub fn debian_scripts(&self) -> [(&'static str, Option<&PathBuf>); 4] {
+    [
+      ("preinst", self.bundle_settings.deb_preinst.as_ref()),
+      ("postinst", self.bundle_settings.deb_postinst.as_ref()),
+      ("prerm", self.bundle_settings.deb_prerm.as_ref()),
+      ("postrm", self.bundle_settings.deb_postrm.as_ref()),
+    ]
+  }
+
+  /// Returns the dependencies of the RPM package.
+  pub fn rpm_dependencies(&self) -> &[String] {
+    match self.bundle_settings.rpm_depends {
+      Some(ref dependencies) => dependencies.as_slice(),
+      None => &[],
+    }
+  }
+
+  /// Returns the release of the RPM package, defaulting to `1`.
+  pub fn rpm_release(&self) -> &str {
+    self.bundle_settings.rpm_release.as_deref().unwrap_or("1")
+  }
+
+  /// Returns the epoch of the RPM package.
+  pub fn rpm_epoch(&self) -> Option<u32> {
+    self.bundle_settings.rpm_epoch
+  }
+
+  /// Returns the extra files of the RPM package, sorted by install path.
+  pub fn rpm_files(&self) -> Vec<(&PathBuf, &PathBuf)> {
+    let mut files: Vec<(&PathBuf, &PathBuf)> = match self.bundle_settings.rpm_files {
+      Some(ref files) => files.iter().collect(),
+      None => Vec::new(),
+    };
+    files.sort_by(|(_, a), (_, b)| a.cmp(b));
+    files
+  }
+
+  /// Returns the path to the desktop file template of the RPM package.
+  pub fn rpm_desktop_template(&self) -> Option<&PathBuf> {
+    self.bundle_settings.rpm_desktop_template.as_ref()
+  }
+
+  /// Returns whether the GStreamer libraries and plugins should be copied into the AppImage or not.
+  pub fn appimage_bundle_media_framework(&self) -> bool {
+    self
+      .bundle_settings
+      .appimage_bundle_media_framework
+      .unwrap_or(false)
+  }
+
+  /// Returns the extra files of the AppImage, sorted by their path inside the AppImage.
+  pub fn appimage_files(&self) -> Vec<(&PathBuf, &PathBuf)> {
+    let mut files: Vec<(&PathBuf, &PathBuf)> = match self.bundle_settings.appimage_files {
+      Some(ref files) => files.iter().collect(),
+      None => Vec::new(),
+    };
+    files.sort_by(|(_, a), (_, b)| a.cmp(b));
+    files
+  }
+
   /// Returns the frameworks to bundle with the macOS .app
   pub fn osx_frameworks(&self) -> &[String] {
     match self.bundle_settings.osx_frameworks {
@@ -796,31 +1425,434 @@ impl Settings {
   pub fn osx_use_bootstrapper(&self) -> bool {
     self.bundle_settings.osx_use_bootstrapper.unwrap_or(false)
   }
+
+  /// Returns the custom Info.plist dictionaries to merge over the generated one, in merge order:
+  /// the `Info.plist` file next to `tauri.conf.json`, then the `infoPlist` config.
+  pub fn osx_info_plist(&self) -> crate::Result<Vec<plist::Dictionary>> {
+    let mut dictionaries = Vec::new();
+    let tauri_dir = match std::env::var_os("TAURI_DIR") {
+      Some(tauri_dir) => PathBuf::from(tauri_dir),
+      None => std::env::current_dir()?,
+    };
+    let info_plist_path = tauri_dir.join("Info.plist");
+    if info_plist_path.exists() {
+      match plist::Value::from_file(&info_plist_path)? {
+        plist::Value::Dictionary(dictionary) => dictionaries.push(dictionary),
+        _ => {
+          return Err(crate::Error::GenericError(format!(
+            "{} must be a dictionary",
+            info_plist_path.display()
+          )))
+        }
+      }
+    }
+    if let Some(ref info_plist) = self.bundle_settings.osx_info_plist {
+      let mut dictionary = plist::Dictionary::new();
+      for (key, value) in info_plist {
+        if let Some(value) = json_to_plist(value) {
+          dictionary.insert(key.clone(), value);
+        }
+      }
+      dictionaries.push(dictionary);
+    }
+    Ok(dictionaries)
+  }
+
+  /// Returns the identity used to sign the macOS .app bundle.
+  pub fn osx_signing_identity(&self) -> Option<&str> {
+    self.bundle_settings.osx_signing_identity.as_deref()
+  }
+
+  /// Returns the provider short name used on notarization.
+  pub fn osx_provider_short_name(&self) -> Option<&str> {
+    self.bundle_settings.osx_provider_short_name.as_deref()
+  }
+
+  /// Returns the path to the entitlements plist of the macOS .app bundle.
+  pub fn osx_entitlements(&self) -> Option<&PathBuf> {
+    self.bundle_settings.osx_entitlements.as_ref()
+  }
+
+  /// Returns whether the macOS .app bundle should be signed with the hardened runtime or not.
+  pub fn osx_hardened_runtime(&self) -> bool {
+    self.bundle_settings.osx_hardened_runtime.unwrap_or(false)
+  }
+
+  /// Returns whether the signed .app bundle and DMG are notarized, unless it's skipped with `--skip-notarize`.
+  pub fn osx_notarize(&self) -> bool {
+    !self.skip_notarize && self.bundle_settings.osx_notarize.unwrap_or(false)
+  }
+
+  /// Returns how long the notarization waits for the result of the notary service, defaulting to an hour.
+  pub fn osx_notarize_timeout(&self) -> Duration {
+    Duration::from_secs(self.bundle_settings.osx_notarize_timeout.unwrap_or(3600))
+  }
+
+  /// Returns how the WebView2 runtime is installed by the Windows installer.
+  /// Fails if the offline installer or the fixed runtime doesn't exist.
+  pub fn windows_webview_install_mode(&self) -> crate::Result<Option<&WebviewInstallMode>> {
+    let mode = self.bundle_settings.windows_webview_install_mode.as_ref();
+    match mode {
+      Some(WebviewInstallMode::OfflineInstaller { path }) if !path.is_file() => {
+        Err(crate::Error::GenericError(format!(
+          "WebView2 offline installer {} not found",
+          path.display()
+        )))
+      }
+      Some(WebviewInstallMode::FixedRuntime { path }) if !path.is_dir() => {
+        Err(crate::Error::GenericError(format!(
+          "WebView2 fixed runtime directory {} not found",
+          path.display()
+        )))
+      }
+      _ => Ok(mode),
+    }
+  }
+
+  /// Returns the WiX cultures of the MSI installers, empty for the default single `en-US` installer.
+  pub fn windows_wix_languages(&self) -> &[String] {
+    match self.bundle_settings.windows_wix_languages {
+      Some(ref languages) => languages.as_slice(),
+      None => &[],
+    }
+  }
+
+  /// Returns the MSI ProductVersion override.
+  pub fn windows_msi_version(&self) -> Option<&str> {
+    self.bundle_settings.windows_msi_version.as_deref()
+  }
+
+  /// Returns the MSI UpgradeCode override.
+  pub fn windows_upgrade_code(&self) -> Option<&str> {
+    self.bundle_settings.windows_upgrade_code.as_deref()
+  }
+
+  /// Returns whether the MSI installer can replace a newer installed version, defaulting to `false`.
+  pub fn windows_allow_downgrades(&self) -> bool {
+    self
+      .bundle_settings
+      .windows_allow_downgrades
+      .unwrap_or(false)
+  }
+
+  /// Returns the absolute paths of the WiX fragments, failing if one of them is missing.
+  pub fn windows_wix_fragment_paths(&self) -> crate::Result<Vec<PathBuf>> {
+    let current_dir = std::env::current_dir()?;
+    let mut paths = Vec::new();
+    for fragment in self
+      .bundle_settings
+      .windows_wix_fragment_paths
+      .iter()
+      .flatten()
+    {
+      let path = current_dir.join(fragment);
+      if !path.is_file() {
+        return Err(crate::Error::GenericError(format!(
+          "WiX fragment {} not found",
+          path.display()
+        )));
+      }
+      paths.push(path);
+    }
+    Ok(paths)
+  }
+
+  /// Returns the ids of the fragments components included on the main feature of the MSI installer.
+  pub fn windows_component_refs(&self) -> &[String] {
+    match self.bundle_settings.windows_component_refs {
+      Some(ref refs) => refs.as_slice(),
+      None => &[],
+    }
+  }
+
+  /// Returns the ids of the fragments features included on the MSI installer.
+  pub fn windows_feature_refs(&self) -> &[String] {
+    match self.bundle_settings.windows_feature_refs {
+      Some(ref refs) => refs.as_slice(),
+      None => &[],
+    }
+  }
+
+  /// Returns whether the Windows installers create a desktop shortcut, defaulting to `false`.
+  pub fn windows_desktop_shortcut(&self) -> bool {
+    self
+      .bundle_settings
+      .windows_desktop_shortcut
+      .unwrap_or(false)
+  }
+
+  /// Returns whether the Windows installers create a start menu shortcut, defaulting to `true`.
+  pub fn windows_start_menu_shortcut(&self) -> bool {
+    self
+      .bundle_settings
+      .windows_start_menu_shortcut
+      .unwrap_or(true)
+  }
+
+  /// Returns the start menu folder of the Windows installers shortcut.
+  pub fn windows_start_menu_folder(&self) -> Option<&str> {
+    self.bundle_settings.windows_start_menu_folder.as_deref()
+  }
+
+  /// Returns who the NSIS installer installs the app for, defaulting to the current user.
+  pub fn nsis_install_mode(&self) -> NsisInstallMode {
+    self
+      .bundle_settings
+      .nsis_install_mode
+      .unwrap_or(NsisInstallMode::CurrentUser)
+  }
+
+  /// Returns the path to the license shown by the NSIS installer.
+  pub fn nsis_license(&self) -> Option<&PathBuf> {
+    self.bundle_settings.nsis_license.as_ref()
+  }
+
+  /// Returns the path to the header image of the NSIS installer pages.
+  pub fn nsis_header_image(&self) -> Option<&PathBuf> {
+    self.bundle_settings.nsis_header_image.as_ref()
+  }
+
+  /// Returns the path to the sidebar image of the NSIS installer welcome and finish pages.
+  pub fn nsis_sidebar_image(&self) -> Option<&PathBuf> {
+    self.bundle_settings.nsis_sidebar_image.as_ref()
+  }
+
+  /// Returns the languages of the NSIS installer, defaulting to English.
+  pub fn nsis_languages(&self) -> Vec<String> {
+    self
+      .bundle_settings
+      .nsis_languages
+      .clone()
+      .filter(|languages| !languages.is_empty())
+      .unwrap_or_else(|| vec!["English".to_string()])
+  }
+
+  /// Returns the compression algorithm of the NSIS installer, defaulting to LZMA.
+  pub fn nsis_compression(&self) -> NsisCompression {
+    self
+      .bundle_settings
+      .nsis_compression
+      .unwrap_or(NsisCompression::Lzma)
+  }
+
+  /// Returns the absolute path to the background image of the DMG window.
+  /// Fails if the image doesn't exist.
+  pub fn dmg_background(&self) -> crate::Result<Option<PathBuf>> {
+    match self.bundle_settings.dmg_background {
+      Some(ref background) => {
+        let path = std::env::current_dir()?.join(background);
+        if path.is_file() {
+          Ok(Some(path))
+        } else {
+          Err(crate::Error::GenericError(format!(
+            "DMG background image {} not found",
+            path.display()
+          )))
+        }
+      }
+      None => Ok(None),
+    }
+  }
+
+  /// Returns the size of the DMG window.
+  pub fn dmg_window_size(&self) -> Size {
+    self.bundle_settings.dmg_window_size.unwrap_or(Size {
+      width: 660,
+      height: 400,
+    })
+  }
+
+  /// Returns the position of the app icon on the DMG window.
+  pub fn dmg_app_position(&self) -> Position {
+    self
+      .bundle_settings
+      .dmg_app_position
+      .unwrap_or(Position { x: 180, y: 170 })
+  }
+
+  /// Returns the position of the Applications folder link on the DMG window.
+  pub fn dmg_application_folder_position(&self) -> Position {
+    self
+      .bundle_settings
+      .dmg_application_folder_position
+      .unwrap_or(Position { x: 480, y: 170 })
+  }
 }
 
-/// Parses the external binaries to bundle, adding the target triple suffix to each of them.
-fn parse_external_bin(bundle_settings: BundleSettings) -> crate::Result<BundleSettings> {
-  let target_triple = target_triple()?;
-  let mut win_paths = Vec::new();
-  let external_bin = match bundle_settings.external_bin {
-    Some(paths) => {
-      for curr_path in paths.iter() {
-        win_paths.push(format!(
-          "{}-{}{}",
-          curr_path,
-          target_triple,
-          if cfg!(windows) { ".exe" } else { "" }
-        ));
+/// Converts a JSON value to a plist value, keeping its type.
+/// Returns `None` for `null`, which has no plist representation.
+fn json_to_plist(value: &serde_json::Value) -> Option<plist::Value> {
+  match value {
+    serde_json::Value::Null => None,
+    serde_json::Value::Bool(value) => Some((*value).into()),
+    serde_json::Value::Number(number) => match number.as_i64() {
+      Some(integer) => Some(integer.into()),
+      None => number.as_f64().map(Into::into),
+    },
+    serde_json::Value::String(value) => Some(value.as_str().into()),
+    serde_json::Value::Array(values) => Some(plist::Value::Array(
+      values.iter().filter_map(json_to_plist).collect(),
+    )),
+    serde_json::Value::Object(map) => {
+      let mut dictionary = plist::Dictionary::new();
+      for (key, value) in map {
+        if let Some(value) = json_to_plist(value) {
+          dictionary.insert(key.clone(), value);
+        }
       }
-      Some(win_paths)
+      Some(dictionary.into())
     }
-    None => Some(vec![]),
-  };
+  }
+}
 
-  Ok(BundleSettings {
-    external_bin,
-    ..bundle_settings
-  })
+/// Resolves the external binaries to their file with the target triple suffix, along with their plain file name.
+fn external_binaries(
+  paths: &[String],
+  target_triple: &str,
+  is_windows_target: bool,
+) -> crate::Result<Vec<(PathBuf, String)>> {
+  let extension = if is_windows_target { ".exe" } else { "" };
+  let mut binaries = Vec::new();
+  for path in paths {
+    let src = PathBuf::from(format!("{}-{}{}", path, target_triple, extension));
+    if !src.exists() {
+      return Err(crate::Error::GenericError(format!(
+        "external binary `{}` not found for the `{}` target: expected a file at `{}`",
+        path,
+        target_triple,
+        src.display()
+      )));
+    }
+    let name = Path::new(path)
+      .file_name()
+      .expect("failed to extract external binary filename")
+      .to_string_lossy()
+      .to_string();
+    binaries.push((src, format!("{}{}", name, extension)));
+  }
+  Ok(binaries)
+}
+
+/// Resolves the resources to bundle to a list of (source path, destination path) tuples.
+///
+/// On the list form, each file keeps its relative path (see `common::resource_relpath`).
+/// On the map form, directories and glob patterns keep their structure under the destination directory,
+/// while a single file is placed at the destination path, or inside it if the destination ends with `/`.
+fn resource_targets(resources: &BundleResources) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
+  let mut targets = Vec::new();
+  match resources {
+    BundleResources::List(patterns) => {
+      for src in ResourcePaths::new(patterns.as_slice(), true) {
+        let src = src?;
+        let dest = common::resource_relpath(&src);
+        targets.push((src, dest));
+      }
+    }
+    BundleResources::Map(map) => {
+      let mut entries: Vec<(&String, &String)> = map.iter().collect();
+      entries.sort();
+      for (pattern, target) in entries {
+        let target_path = PathBuf::from(target);
+        if !target_path
+          .components()
+          .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+          return Err(crate::Error::GenericError(format!(
+            "resource destination `{}` must be a relative path inside the resource directory",
+            target
+          )));
+        }
+        let pattern_path = Path::new(pattern);
+        if is_glob_pattern(pattern) || pattern_path.is_dir() {
+          // the path up to the first glob component; files keep their structure relative to it
+          let base: PathBuf = pattern_path
+            .components()
+            .filter(|c| *c != Component::CurDir)
+            .take_while(|c| !is_glob_pattern(&c.as_os_str().to_string_lossy()))
+            .collect();
+          for src in ResourcePaths::new(std::slice::from_ref(pattern), true) {
+            let src = src?;
+            let relative_src: PathBuf = src
+              .components()
+              .filter(|c| *c != Component::CurDir)
+              .collect();
+            let dest = target_path.join(relative_src.strip_prefix(&base)?);
+            targets.push((src, dest));
+          }
+        } else {
+          let src = pattern_path.to_path_buf();
+          let dest = if target.ends_with('/') || target.is_empty() {
+            target_path.join(src.file_name().ok_or_else(|| {
+              crate::Error::GenericError(format!("invalid resource path `{}`", pattern))
+            })?)
+          } else {
+            target_path
+          };
+          targets.push((src, dest));
+        }
+      }
+    }
+  }
+  Ok(targets)
+}
+
+/// Whether the given path contains glob special characters or not.
+fn is_glob_pattern(path: &str) -> bool {
+  path.contains(|c| c == '*' || c == '?' || c == '[')
+}
+
+/// Copies the resolved resource files to a path.
+fn copy_resource_targets(targets: &[(PathBuf, PathBuf)], path: &Path) -> crate::Result<()> {
+  for (src, dest) in targets {
+    common::copy_file(src, path.join(dest))?;
+  }
+  Ok(())
+}
+
+/// Compresses the resolved resource files to a directory, skipping the ones compressed since they last changed.
+/// Returns the compressed files along with their destination, with the compression extension.
+fn compress_resource_targets(
+  targets: Vec<(PathBuf, PathBuf)>,
+  compression: ResourcesCompression,
+  dir: &Path,
+) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
+  let extension = match compression.extension() {
+    Some(extension) => extension,
+    None => return Ok(targets),
+  };
+  let mut compressed = Vec::new();
+  for (src, dest) in targets {
+    let mut dest = dest.into_os_string();
+    dest.push(".");
+    dest.push(extension);
+    let dest = PathBuf::from(dest);
+    let staged = dir.join(&dest);
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    let up_to_date = matches!(
+      (modified(&staged), modified(&src)),
+      (Ok(staged), Ok(src)) if staged >= src
+    );
+    if !up_to_date {
+      let mut src_file = File::open(&src)?;
+      let staged_file = common::create_file(&staged)?;
+      match compression {
+        ResourcesCompression::Gzip => {
+          let mut encoder = libflate::gzip::Encoder::new(staged_file)?;
+          io::copy(&mut src_file, &mut encoder)?;
+          encoder.finish().into_result()?.flush()?;
+        }
+        ResourcesCompression::Brotli => {
+          let mut encoder = brotli::CompressorWriter::new(staged_file, 4096, 11, 22);
+          io::copy(&mut src_file, &mut encoder)?;
+          // finishes the stream
+          encoder.into_inner().flush()?;
+        }
+        ResourcesCompression::None => unreachable!(),
+      }
+    }
+    compressed.push((staged, dest));
+  }
+  Ok(compressed)
 }
 
 /// Returns the first Option with a value, or None if both are None.
@@ -833,23 +1865,68 @@ fn options_value<T>(first: Option<T>, second: Option<T>) -> Option<T> {
 }
 
 /// Merges the bundle settings from Cargo.toml and tauri.conf.json
+/// The bundle name and version fall back to the `package` config.
+/// Resolves the license file path against the directory, failing if the file doesn't exist.
+fn resolve_license_file(dir: &Path, license: &Path) -> crate::Result<PathBuf> {
+  let path = dir.join(license);
+  if path.is_file() {
+    Ok(path)
+  } else {
+    Err(crate::Error::GenericError(format!(
+      "license file {} not found",
+      path.display()
+    )))
+  }
+}
+
 fn merge_settings(
   bundle_settings: BundleSettings,
   config: crate::bundle::tauri_config::BundleConfig,
+  package: crate::bundle::tauri_config::PackageConfig,
 ) -> BundleSettings {
   BundleSettings {
-    name: options_value(config.name, bundle_settings.name),
+    name: options_value(
+      config.name,
+      options_value(package.product_name, bundle_settings.name),
+    ),
     identifier: options_value(config.identifier, bundle_settings.identifier),
     icon: options_value(config.icon, bundle_settings.icon),
-    version: options_value(config.version, bundle_settings.version),
+    version: options_value(
+      config.version,
+      options_value(package.version, bundle_settings.version),
+    ),
     resources: options_value(config.resources, bundle_settings.resources),
+    resources_compression: options_value(
+      config.resources_compression,
+      bundle_settings.resources_compression,
+    ),
     copyright: options_value(config.copyright, bundle_settings.copyright),
+    publisher: options_value(config.publisher, bundle_settings.publisher),
+    homepage: options_value(config.homepage, bundle_settings.homepage),
+    license_file: options_value(config.license_file, bundle_settings.license_file),
     category: options_value(config.category, bundle_settings.category),
     short_description: options_value(config.short_description, bundle_settings.short_description),
     long_description: options_value(config.long_description, bundle_settings.long_description),
     script: options_value(config.script, bundle_settings.script),
     deb_depends: options_value(config.deb.depends, bundle_settings.deb_depends),
     deb_use_bootstrapper: Some(config.deb.use_bootstrapper),
+    deb_files: options_value(config.deb.files, bundle_settings.deb_files),
+    deb_section: options_value(config.deb.section, bundle_settings.deb_section),
+    deb_priority: options_value(config.deb.priority, bundle_settings.deb_priority),
+    deb_changelog: options_value(config.deb.changelog, bundle_settings.deb_changelog),
+    deb_generate_man_page: Some(config.deb.generate_man_page),
+    deb_desktop_template: options_value(
+      config.deb.desktop_template,
+      bundle_settings.deb_desktop_template,
+    ),
+    deb_desktop_entries: options_value(
+      config.deb.desktop_entries,
+      bundle_settings.deb_desktop_entries,
+    ),
+    deb_preinst: options_value(config.deb.scripts.preinst, bundle_settings.deb_preinst),
+    deb_postinst: options_value(config.deb.scripts.postinst, bundle_settings.deb_postinst),
+    deb_prerm: options_value(config.deb.scripts.prerm, bundle_settings.deb_prerm),
+    deb_postrm: options_value(config.deb.scripts.postrm, bundle_settings.deb_postrm),
     osx_frameworks: options_value(config.osx.frameworks, bundle_settings.osx_frameworks),
     osx_minimum_system_version: options_value(
       config.osx.minimum_system_version,
@@ -857,11 +1934,99 @@ fn merge_settings(
     ),
     osx_license: options_value(config.osx.license, bundle_settings.osx_license),
     osx_use_bootstrapper: Some(config.osx.use_bootstrapper),
+    osx_info_plist: options_value(config.osx.info_plist, bundle_settings.osx_info_plist),
+    osx_signing_identity: options_value(
+      config.osx.signing_identity,
+      bundle_settings.osx_signing_identity,
+    ),
+    osx_provider_short_name: options_value(
+      config.osx.provider_short_name,
+      bundle_settings.osx_provider_short_name,
+    ),
+    osx_entitlements: options_value(config.osx.entitlements, bundle_settings.osx_entitlements),
+    osx_hardened_runtime: Some(config.osx.hardened_runtime),
+    osx_notarize: Some(config.osx.notarize),
+    osx_notarize_timeout: options_value(
+      config.osx.notarize_timeout,
+      bundle_settings.osx_notarize_timeout,
+    ),
+    windows_webview_install_mode: options_value(
+      config.windows.webview_install_mode,
+      bundle_settings.windows_webview_install_mode,
+    ),
+    windows_wix_languages: options_value(
+      config.windows.wix_languages,
+      bundle_settings.windows_wix_languages,
+    ),
+    windows_msi_version: options_value(
+      config.windows.msi_version,
+      bundle_settings.windows_msi_version,
+    ),
+    windows_upgrade_code: options_value(
+      config.windows.upgrade_code,
+      bundle_settings.windows_upgrade_code,
+    ),
+    windows_allow_downgrades: Some(config.windows.allow_downgrades),
+    windows_wix_fragment_paths: options_value(
+      config.windows.wix_fragment_paths,
+      bundle_settings.windows_wix_fragment_paths,
+    ),
+    windows_component_refs: options_value(
+      config.windows.component_refs,
+      bundle_settings.windows_component_refs,
+    ),
+    windows_feature_refs: options_value(
+      config.windows.feature_refs,
+      bundle_settings.windows_feature_refs,
+    ),
+    windows_desktop_shortcut: options_value(
+      config.windows.shortcuts.desktop,
+      bundle_settings.windows_desktop_shortcut,
+    ),
+    windows_start_menu_shortcut: options_value(
+      config.windows.shortcuts.start_menu,
+      bundle_settings.windows_start_menu_shortcut,
+    ),
+    windows_start_menu_folder: options_value(
+      config.windows.shortcuts.start_menu_folder,
+      bundle_settings.windows_start_menu_folder,
+    ),
+    nsis_install_mode: options_value(config.nsis.install_mode, bundle_settings.nsis_install_mode),
+    nsis_license: options_value(config.nsis.license, bundle_settings.nsis_license),
+    nsis_header_image: options_value(config.nsis.header_image, bundle_settings.nsis_header_image),
+    nsis_sidebar_image: options_value(
+      config.nsis.sidebar_image,
+      bundle_settings.nsis_sidebar_image,
+    ),
+    nsis_languages: options_value(config.nsis.languages, bundle_settings.nsis_languages),
+    nsis_compression: options_value(config.nsis.compression, bundle_settings.nsis_compression),
+    rpm_depends: options_value(config.rpm.depends, bundle_settings.rpm_depends),
+    rpm_release: options_value(config.rpm.release, bundle_settings.rpm_release),
+    rpm_epoch: options_value(config.rpm.epoch, bundle_settings.rpm_epoch),
+    rpm_files: options_value(config.rpm.files, bundle_settings.rpm_files),
+    rpm_desktop_template: options_value(
+      config.rpm.desktop_template,
+      bundle_settings.rpm_desktop_template,
+    ),
+    appimage_bundle_media_framework: Some(config.appimage.bundle_media_framework),
+    appimage_files: options_value(config.appimage.files, bundle_settings.appimage_files),
+    dmg_background: options_value(config.dmg.background, bundle_settings.dmg_background),
+    dmg_window_size: options_value(config.dmg.window_size, bundle_settings.dmg_window_size),
+    dmg_app_position: options_value(config.dmg.app_position, bundle_settings.dmg_app_position),
+    dmg_application_folder_position: options_value(
+      config.dmg.application_folder_position,
+      bundle_settings.dmg_application_folder_position,
+    ),
     external_bin: options_value(config.external_bin, bundle_settings.external_bin),
     exception_domain: options_value(
       config.osx.exception_domain,
       bundle_settings.exception_domain,
     ),
+    file_associations: options_value(config.file_associations, bundle_settings.file_associations),
+    deep_link_schemes: options_value(config.deep_link.schemes, bundle_settings.deep_link_schemes),
+    output_dir: options_value(config.output_dir, bundle_settings.output_dir),
+    artifact_name: options_value(config.artifact_name, bundle_settings.artifact_name),
+    localization: options_value(config.localization, bundle_settings.localization),
     ..bundle_settings
   }
 }
@@ -961,7 +2126,17 @@ impl<'a> Iterator for ResourcePaths<'a> {
 
 #[cfg(test)]
 mod tests {
-  use super::{AppCategory, BundleSettings, CargoSettings};
+  use super::{
+    compress_resource_targets, copy_resource_targets, external_binaries, is_valid_locale,
+    json_to_plist, merge_settings, resolve_license_file, resource_targets, AppCategory,
+    BundleResources, BundleSettings, CargoSettings, PackageSettings, PackageType, Position,
+    ResourcesCompression, Settings, Size, TargetInfo,
+  };
+  use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+  };
 
   #[test]
   fn parse_cargo_toml() {
@@ -1006,7 +2181,10 @@ mod tests {
     assert_eq!(bundle.version, None);
     assert_eq!(
       bundle.resources,
-      Some(vec!["data".to_string(), "foo/bar".to_string()])
+      Some(BundleResources::List(vec![
+        "data".to_string(),
+        "foo/bar".to_string()
+      ]))
     );
     assert_eq!(bundle.category, Some(AppCategory::PuzzleGame));
     assert_eq!(
@@ -1074,4 +2252,342 @@ mod tests {
       .expect("Failed to get baz bundle settings");
     assert_eq!(baz_settings.name, Some("Baz Example".to_string()));
   }
+
+  #[test]
+  // the tauri config takes precedence over the Cargo.toml metadata
+  fn publisher_homepage_and_license() {
+    let toml_str = "\
+            [package]\n\
+            name = \"example\"\n\
+            version = \"0.1.0\"\n\
+            description = \"An example application.\"\n\
+            \n\
+            [package.metadata.bundle]\n\
+            publisher = \"Jane Doe\"\n\
+            homepage = \"https://example.com\"\n\
+            license_file = \"LICENSE\"\n";
+    let cargo_settings: CargoSettings = toml::from_str(toml_str).expect("Failed to read from toml");
+    let bundle = cargo_settings
+      .package
+      .and_then(|package| package.metadata)
+      .and_then(|metadata| metadata.bundle)
+      .expect("Failed to get bundle settings");
+    let config = crate::bundle::tauri_config::BundleConfig {
+      publisher: Some("Example Corp".to_string()),
+      license_file: Some(PathBuf::from("legal/EULA.rtf")),
+      ..Default::default()
+    };
+    let merged = merge_settings(bundle, config, Default::default());
+    assert_eq!(merged.publisher.as_deref(), Some("Example Corp"));
+    assert_eq!(merged.homepage.as_deref(), Some("https://example.com"));
+    assert_eq!(merged.license_file, Some(PathBuf::from("legal/EULA.rtf")));
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("LICENSE"), "MIT").unwrap();
+    assert_eq!(
+      resolve_license_file(dir.path(), Path::new("LICENSE")).unwrap(),
+      dir.path().join("LICENSE")
+    );
+    // the missing files and the directories fail before the packages are built
+    for license in &["MISSING", "."] {
+      let error = resolve_license_file(dir.path(), Path::new(license))
+        .unwrap_err()
+        .to_string();
+      assert!(error.contains("license file"), "{}", error);
+      assert!(error.contains("not found"), "{}", error);
+    }
+  }
+
+  #[test]
+  fn parse_dmg_window() {
+    let toml_str = "\
+            [package]\n\
+            name = \"example\"\n\
+            version = \"0.1.0\"\n\
+            description = \"An example application.\"\n\
+            \n\
+            [package.metadata.bundle]\n\
+            dmg_background = \"assets/background.png\"\n\
+            dmg_window_size = { width = 800, height = 500 }\n\
+            dmg_app_position = { x = 200, y = 250 }\n";
+    let cargo_settings: CargoSettings = toml::from_str(toml_str).expect("Failed to read from toml");
+    let bundle = cargo_settings
+      .package
+      .and_then(|package| package.metadata)
+      .and_then(|metadata| metadata.bundle)
+      .expect("Failed to get bundle settings");
+    assert_eq!(
+      bundle.dmg_background,
+      Some(PathBuf::from("assets/background.png"))
+    );
+    assert_eq!(
+      bundle.dmg_window_size,
+      Some(Size {
+        width: 800,
+        height: 500
+      })
+    );
+    assert_eq!(bundle.dmg_app_position, Some(Position { x: 200, y: 250 }));
+    assert_eq!(bundle.dmg_application_folder_position, None);
+
+    // a position needs both coordinates
+    let toml_str = "\
+            [package]\n\
+            name = \"example\"\n\
+            version = \"0.1.0\"\n\
+            description = \"An example application.\"\n\
+            \n\
+            [package.metadata.bundle]\n\
+            dmg_app_position = { x = 200 }\n";
+    assert!(toml::from_str::<CargoSettings>(toml_str).is_err());
+  }
+
+  #[test]
+  fn info_plist_values() {
+    let value = serde_json::json!({
+      "LSUIElement": true,
+      "LSMinimumSystemVersion": "10.13",
+      "NSHighResolutionCapable": null,
+      "Version": 3,
+      "Scale": 1.5,
+      "CFBundleURLTypes": [{ "CFBundleURLSchemes": ["app", null] }]
+    });
+    let dictionary = json_to_plist(&value)
+      .and_then(plist::Value::into_dictionary)
+      .expect("Failed to convert the JSON object");
+    assert_eq!(dictionary.get("LSUIElement"), Some(&true.into()));
+    assert_eq!(
+      dictionary.get("LSMinimumSystemVersion"),
+      Some(&"10.13".into())
+    );
+    // null has no plist representation
+    assert!(!dictionary.contains_key("NSHighResolutionCapable"));
+    assert_eq!(
+      dictionary.get("Version"),
+      Some(&plist::Value::Integer(3.into()))
+    );
+    assert_eq!(dictionary.get("Scale"), Some(&plist::Value::Real(1.5)));
+    let url_types = dictionary
+      .get("CFBundleURLTypes")
+      .and_then(plist::Value::as_array)
+      .expect("Failed to get the array");
+    let schemes = url_types[0]
+      .as_dictionary()
+      .and_then(|url_type| url_type.get("CFBundleURLSchemes"))
+      .and_then(plist::Value::as_array)
+      .expect("Failed to get the nested array");
+    assert_eq!(schemes, &vec![plist::Value::from("app")]);
+    assert_eq!(json_to_plist(&serde_json::Value::Null), None);
+  }
+
+  #[test]
+  fn resources_map_layout() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    for file in &[
+      "assets/icons/a.png",
+      "assets/b.png",
+      "assets/c.txt",
+      "LICENSE",
+    ] {
+      let mut file =
+        super::common::create_file(&tmp.path().join(file)).expect("Unable to create file");
+      writeln!(file, "Hello, world!").expect("Unable to write to file");
+    }
+
+    let mut map = HashMap::new();
+    map.insert(
+      tmp.path().join("assets/**/*.png").display().to_string(),
+      "images/".to_string(),
+    );
+    map.insert(
+      tmp.path().join("LICENSE").display().to_string(),
+      "legal/LICENSE".to_string(),
+    );
+    let targets =
+      resource_targets(&BundleResources::Map(map)).expect("Failed to resolve resources");
+
+    let out = tmp.path().join("out");
+    copy_resource_targets(&targets, &out).expect("Failed to copy resources");
+    assert!(out.join("images/icons/a.png").is_file());
+    assert!(out.join("images/b.png").is_file());
+    assert!(!out.join("images/c.txt").exists());
+    assert!(out.join("legal/LICENSE").is_file());
+    assert_eq!(targets.len(), 3);
+  }
+
+  #[test]
+  fn resources_compression() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let src = tmp.path().join("data.json");
+    std::fs::write(&src, "{ \"hello\": \"world\" }").expect("Unable to write to file");
+    let targets = vec![(src, PathBuf::from("data/data.json"))];
+    let out = tmp.path().join("compressed");
+    let compressed = compress_resource_targets(targets, ResourcesCompression::Gzip, &out)
+      .expect("Failed to compress resources");
+    assert_eq!(compressed[0].0, out.join("data/data.json.gz"));
+    assert_eq!(compressed[0].1, PathBuf::from("data/data.json.gz"));
+
+    let mut content = String::new();
+    libflate::gzip::Decoder::new(std::fs::File::open(&compressed[0].0).unwrap())
+      .unwrap()
+      .read_to_string(&mut content)
+      .unwrap();
+    assert_eq!(content, "{ \"hello\": \"world\" }");
+  }
+
+  #[test]
+  fn resources_map_rejects_escaping_destinations() {
+    for target in &["../outside", "/absolute/path"] {
+      let mut map = HashMap::new();
+      map.insert("LICENSE".to_string(), target.to_string());
+      assert!(resource_targets(&BundleResources::Map(map)).is_err());
+    }
+  }
+
+  #[test]
+  fn external_binaries_per_target() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let binary = tmp.path().join("binaries/ffmpeg");
+    let binary_path = binary.display().to_string();
+    super::common::create_file(
+      &tmp
+        .path()
+        .join("binaries/ffmpeg-x86_64-pc-windows-msvc.exe"),
+    )
+    .expect("Unable to create file");
+
+    let binaries = external_binaries(&[binary_path.clone()], "x86_64-pc-windows-msvc", true)
+      .expect("Failed to resolve the external binaries");
+    assert_eq!(
+      binaries,
+      vec![(
+        tmp
+          .path()
+          .join("binaries/ffmpeg-x86_64-pc-windows-msvc.exe"),
+        "ffmpeg.exe".to_string()
+      )]
+    );
+    // there's no binary for the Linux target
+    assert!(external_binaries(&[binary_path], "x86_64-unknown-linux-gnu", false).is_err());
+  }
+
+  #[test]
+  fn locales() {
+    for locale in &["de", "pt-BR", "pt_BR", "zh-Hant-TW", "fil"] {
+      assert!(is_valid_locale(locale), "{}", locale);
+    }
+    for locale in &["", "d", "german", "pt-", "de-DE.UTF-8", "12"] {
+      assert!(!is_valid_locale(locale), "{}", locale);
+    }
+  }
+
+  fn linux_settings(
+    description: &str,
+    bundle_settings: BundleSettings,
+    package_types: Vec<PackageType>,
+  ) -> Settings {
+    Settings {
+      package: PackageSettings {
+        name: "app".to_string(),
+        version: "1.0.0".to_string(),
+        description: description.to_string(),
+        homepage: None,
+        license: None,
+        authors: None,
+        metadata: None,
+        default_run: None,
+      },
+      package_types: Some(package_types),
+      target: Some((
+        "x86_64-unknown-linux-gnu".to_string(),
+        TargetInfo::from_str("x86_64-unknown-linux-gnu").unwrap(),
+      )),
+      features: None,
+      runner: None,
+      project_out_directory: PathBuf::from("target"),
+      is_release: true,
+      is_verbose: false,
+      jobs: None,
+      is_fail_fast: false,
+      skip_notarize: false,
+      bundle_settings,
+      binaries: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn descriptions() {
+    // the descriptions fall back to the package description
+    let settings = linux_settings(
+      "The package description.",
+      BundleSettings::default(),
+      vec![PackageType::Deb],
+    );
+    assert_eq!(settings.short_description(), "The package description.");
+    assert_eq!(
+      settings.long_description(),
+      Some("The package description.")
+    );
+    assert!(settings.validate_descriptions().is_ok());
+
+    let settings = linux_settings(
+      "The package description.",
+      BundleSettings {
+        short_description: Some("The short description.".to_string()),
+        long_description: Some("The long description.".to_string()),
+        ..Default::default()
+      },
+      vec![PackageType::Deb],
+    );
+    assert_eq!(settings.short_description(), "The short description.");
+    assert_eq!(settings.long_description(), Some("The long description."));
+
+    let settings = linux_settings("", BundleSettings::default(), vec![PackageType::AppImage]);
+    assert_eq!(settings.long_description(), None);
+    // only the deb package requires a short description
+    assert!(settings.validate_descriptions().is_ok());
+    let settings = linux_settings("", BundleSettings::default(), vec![PackageType::Deb]);
+    assert!(settings
+      .validate_descriptions()
+      .unwrap_err()
+      .to_string()
+      .contains("the deb package requires a short description"));
+
+    let settings = linux_settings(
+      "two\nlines",
+      BundleSettings::default(),
+      vec![PackageType::Deb],
+    );
+    assert!(settings
+      .validate_descriptions()
+      .unwrap_err()
+      .to_string()
+      .contains("must be a single line"));
+
+    // the synopsis of the deb package is limited to 80 characters, counting the characters and not the bytes
+    let settings = linux_settings(
+      &"é".repeat(80),
+      BundleSettings::default(),
+      vec![PackageType::Deb],
+    );
+    assert!(settings.validate_descriptions().is_ok());
+    let settings = linux_settings(
+      &"é".repeat(81),
+      BundleSettings::default(),
+      vec![PackageType::Deb],
+    );
+    assert!(settings
+      .validate_descriptions()
+      .unwrap_err()
+      .to_string()
+      .contains(
+        "the short description is 81 characters long, but the deb package allows up to 80"
+      ));
+    let settings = linux_settings(
+      &"a".repeat(81),
+      BundleSettings::default(),
+      vec![PackageType::Rpm],
+    );
+    assert!(settings.validate_descriptions().is_ok());
+  }
 }
@@ -1,8 +1,17 @@
 use super::category::AppCategory;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-use std::fs;
+use std::{collections::HashMap, fs};
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "scripts", rename_all = "camelCase")]
+pub struct DebScriptsConfig {
+  pub preinst: Option<PathBuf>,
+  pub postinst: Option<PathBuf>,
+  pub prerm: Option<PathBuf>,
+  pub postrm: Option<PathBuf>,
+}
 
 #[derive(PartialEq, Deserialize, Clone, Debug, Default)]
 #[serde(tag = "deb", rename_all = "camelCase")]
@@ -10,6 +19,16 @@ pub struct DebConfig {
   pub depends: Option<Vec<String>>,
   #[serde(default)]
   pub use_bootstrapper: bool,
+  pub files: Option<HashMap<PathBuf, PathBuf>>,
+  pub section: Option<String>,
+  pub priority: Option<String>,
+  pub changelog: Option<PathBuf>,
+  #[serde(default)]
+  pub generate_man_page: bool,
+  pub desktop_template: Option<PathBuf>,
+  pub desktop_entries: Option<HashMap<String, String>>,
+  #[serde(default)]
+  pub scripts: DebScriptsConfig,
 }
 
 #[derive(PartialEq, Deserialize, Clone, Debug, Default)]
@@ -21,6 +40,245 @@ pub struct OsxConfig {
   pub license: Option<String>,
   #[serde(default)]
   pub use_bootstrapper: bool,
+  pub info_plist: Option<HashMap<String, serde_json::Value>>,
+  pub signing_identity: Option<String>,
+  pub provider_short_name: Option<String>,
+  pub entitlements: Option<PathBuf>,
+  #[serde(default)]
+  pub hardened_runtime: bool,
+  #[serde(default)]
+  pub notarize: bool,
+  pub notarize_timeout: Option<u64>,
+}
+
+/// How the WebView2 runtime is installed by the Windows installer.
+#[derive(PartialEq, Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum WebviewInstallMode {
+  /// Downloads and runs the WebView2 bootstrapper at install time.
+  DownloadBootstrapper,
+  /// Embeds the WebView2 bootstrapper with the given SHA-256 hash in the installer.
+  EmbedBootstrapper { sha256: String },
+  /// Embeds the given WebView2 offline installer in the installer.
+  OfflineInstaller { path: PathBuf },
+  /// Ships the given WebView2 fixed version runtime directory with the app.
+  FixedRuntime { path: PathBuf },
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "shortcuts", rename_all = "camelCase")]
+pub struct ShortcutsConfig {
+  pub desktop: Option<bool>,
+  pub start_menu: Option<bool>,
+  pub start_menu_folder: Option<String>,
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "windows", rename_all = "camelCase")]
+pub struct WindowsConfig {
+  pub webview_install_mode: Option<WebviewInstallMode>,
+  pub wix_languages: Option<Vec<String>>,
+  pub msi_version: Option<String>,
+  pub upgrade_code: Option<String>,
+  #[serde(default)]
+  pub allow_downgrades: bool,
+  #[serde(default)]
+  pub shortcuts: ShortcutsConfig,
+  pub wix_fragment_paths: Option<Vec<PathBuf>>,
+  pub component_refs: Option<Vec<String>>,
+  pub feature_refs: Option<Vec<String>>,
+}
+
+/// A window size.
+#[derive(PartialEq, Deserialize, Clone, Copy, Debug)]
+pub struct Size {
+  pub width: u32,
+  pub height: u32,
+}
+
+/// A position on a window.
+#[derive(PartialEq, Deserialize, Clone, Copy, Debug)]
+pub struct Position {
+  pub x: u32,
+  pub y: u32,
+}
+
+/// Who the NSIS installer installs the app for.
+#[derive(PartialEq, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum NsisInstallMode {
+  /// The current user, without requiring admin rights.
+  CurrentUser,
+  /// All the users, requiring admin rights.
+  PerMachine,
+  /// Lets the user choose on the installer.
+  Both,
+}
+
+/// The compression algorithm of the NSIS installer.
+#[derive(PartialEq, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum NsisCompression {
+  None,
+  Zlib,
+  Bzip2,
+  Lzma,
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "nsis", rename_all = "camelCase")]
+pub struct NsisConfig {
+  pub install_mode: Option<NsisInstallMode>,
+  pub license: Option<PathBuf>,
+  pub header_image: Option<PathBuf>,
+  pub sidebar_image: Option<PathBuf>,
+  pub languages: Option<Vec<String>>,
+  pub compression: Option<NsisCompression>,
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "rpm", rename_all = "camelCase")]
+pub struct RpmConfig {
+  pub depends: Option<Vec<String>>,
+  pub release: Option<String>,
+  pub epoch: Option<u32>,
+  pub files: Option<HashMap<PathBuf, PathBuf>>,
+  pub desktop_template: Option<PathBuf>,
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "appimage", rename_all = "camelCase")]
+pub struct AppImageConfig {
+  #[serde(default)]
+  pub bundle_media_framework: bool,
+  pub files: Option<HashMap<PathBuf, PathBuf>>,
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "dmg", rename_all = "camelCase")]
+pub struct DmgConfig {
+  pub background: Option<PathBuf>,
+  pub window_size: Option<Size>,
+  pub app_position: Option<Position>,
+  pub application_folder_position: Option<Position>,
+}
+
+/// The app resources to bundle.
+#[derive(PartialEq, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum BundleResources {
+  /// A list of paths or glob patterns, placed on the resource directory with their relative path.
+  List(Vec<String>),
+  /// A map of paths or glob patterns to their destination on the resource directory.
+  Map(HashMap<String, String>),
+}
+
+/// The compression of the bundled resources.
+#[derive(PartialEq, Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourcesCompression {
+  None,
+  Gzip,
+  Brotli,
+}
+
+impl ResourcesCompression {
+  /// The extension appended to the compressed resource files.
+  pub fn extension(self) -> Option<&'static str> {
+    match self {
+      Self::None => None,
+      Self::Gzip => Some("gz"),
+      Self::Brotli => Some("br"),
+    }
+  }
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "deepLink", rename_all = "camelCase")]
+pub struct DeepLinkConfig {
+  pub schemes: Option<Vec<String>>,
+}
+
+/// The bundle metadata of a locale; the unset fields fall back to the top-level ones.
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleLocalization {
+  pub product_name: Option<String>,
+  pub short_description: Option<String>,
+  pub long_description: Option<String>,
+  pub comments: Option<String>,
+}
+
+/// The command used to sign the bundled executables and installers.
+#[derive(PartialEq, Deserialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum SignCommand {
+  /// A shell command, with `%1` replaced by the quoted file path.
+  Shell(String),
+  /// A program and its arguments, with `%1` replaced by the file path.
+  Args(Vec<String>),
+}
+
+/// The app's role with respect to a file type (macOS `CFBundleTypeRole`).
+#[derive(PartialEq, Deserialize, Clone, Copy, Debug)]
+pub enum BundleTypeRole {
+  /// The app can read and edit the file type.
+  Editor,
+  /// The app can read the file type.
+  Viewer,
+  /// The app provides runtime services for the file type.
+  Shell,
+  /// The app declares the file type but can't open it.
+  None,
+}
+
+impl Default for BundleTypeRole {
+  fn default() -> Self {
+    Self::Editor
+  }
+}
+
+impl BundleTypeRole {
+  /// The `CFBundleTypeRole` value of this role.
+  pub fn name(self) -> &'static str {
+    match self {
+      Self::Editor => "Editor",
+      Self::Viewer => "Viewer",
+      Self::Shell => "Shell",
+      Self::None => "None",
+    }
+  }
+}
+
+/// A file type the app can open.
+#[derive(PartialEq, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAssociation {
+  /// The file extensions, without the leading dot.
+  pub ext: Vec<String>,
+  /// The file type name.
+  pub name: String,
+  /// The file type description.
+  pub description: Option<String>,
+  /// The file type MIME type, e.g. `text/markdown`.
+  pub mime_type: Option<String>,
+  /// The app's role with respect to the file type.
+  #[serde(default)]
+  pub role: BundleTypeRole,
+  /// The path to the file type icon.
+  pub icon: Option<String>,
+}
+
+impl FileAssociation {
+  /// The MIME type of the association, defaulting to `application/x-{name}`.
+  pub fn mime_type(&self) -> String {
+    self.mime_type.clone().unwrap_or_else(|| {
+      format!(
+        "application/x-{}",
+        self.name.to_lowercase().replace(' ', "-")
+      )
+    })
+  }
 }
 
 #[derive(PartialEq, Deserialize, Clone, Debug, Default)]
@@ -30,17 +288,66 @@ pub struct BundleConfig {
   pub identifier: Option<String>,
   pub icon: Option<Vec<String>>,
   pub version: Option<String>,
-  pub resources: Option<Vec<String>>,
+  pub resources: Option<BundleResources>,
+  pub resources_compression: Option<ResourcesCompression>,
   pub copyright: Option<String>,
+  pub publisher: Option<String>,
+  pub homepage: Option<String>,
+  pub license_file: Option<PathBuf>,
   pub category: Option<AppCategory>,
   pub short_description: Option<String>,
   pub long_description: Option<String>,
+  pub localization: Option<HashMap<String, BundleLocalization>>,
   pub script: Option<PathBuf>,
   #[serde(default)]
   pub deb: DebConfig,
   #[serde(default)]
   pub osx: OsxConfig,
+  #[serde(default)]
+  pub rpm: RpmConfig,
+  #[serde(default)]
+  pub appimage: AppImageConfig,
+  #[serde(default)]
+  pub dmg: DmgConfig,
+  #[serde(default)]
+  pub windows: WindowsConfig,
+  #[serde(default)]
+  pub nsis: NsisConfig,
   pub external_bin: Option<Vec<String>>,
+  pub file_associations: Option<Vec<FileAssociation>>,
+  #[serde(default)]
+  pub deep_link: DeepLinkConfig,
+  pub sign_command: Option<SignCommand>,
+  pub output_dir: Option<PathBuf>,
+  pub artifact_name: Option<String>,
+}
+
+/// A CLI argument, with the fields rendered on the man pages.
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliArg {
+  pub short: Option<char>,
+  pub name: String,
+  pub description: Option<String>,
+  pub long_description: Option<String>,
+  pub takes_value: Option<bool>,
+  pub multiple: Option<bool>,
+  pub possible_values: Option<Vec<String>>,
+  pub required: Option<bool>,
+  pub index: Option<u64>,
+  pub visible_aliases: Option<Vec<String>>,
+}
+
+/// A CLI command, with the fields rendered on the man pages.
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "cli", rename_all = "camelCase")]
+pub struct CliConfig {
+  pub description: Option<String>,
+  pub long_description: Option<String>,
+  pub before_help: Option<String>,
+  pub after_help: Option<String>,
+  pub args: Option<Vec<CliArg>>,
+  pub subcommands: Option<HashMap<String, CliConfig>>,
 }
 
 #[derive(PartialEq, Deserialize, Clone, Debug, Default)]
@@ -48,11 +355,22 @@ pub struct BundleConfig {
 pub struct TauriConfig {
   #[serde(default)]
   pub bundle: BundleConfig,
+  pub cli: Option<CliConfig>,
+}
+
+#[derive(PartialEq, Deserialize, Clone, Debug, Default)]
+#[serde(tag = "package", rename_all = "camelCase")]
+pub struct PackageConfig {
+  pub product_name: Option<String>,
+  /// The resolved semver version.
+  pub version: Option<String>,
 }
 
 #[derive(PartialEq, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
+  #[serde(default)]
+  pub package: PackageConfig,
   #[serde(default)]
   pub tauri: TauriConfig,
 }
@@ -65,9 +383,43 @@ pub fn get() -> crate::Result<Config> {
     }
     None => match std::env::var_os("TAURI_DIR") {
       Some(tauri_dir) => {
-        let tauri_dir_str = tauri_dir.into_string().expect("failed to read TAURI_DIR");
-        let json = &fs::read_to_string(format!("{}{}", tauri_dir_str, "/tauri.conf.json"))?;
-        Ok(serde_json::from_str(json)?)
+        let tauri_dir = std::path::PathBuf::from(tauri_dir);
+        // the sources are looked up in the CLI order
+        let json_path = tauri_dir.join("tauri.conf.json");
+        let toml_path = tauri_dir.join("tauri.conf.toml");
+        if !json_path.exists() && toml_path.exists() {
+          return Ok(toml::from_str(&fs::read_to_string(toml_path)?)?);
+        }
+        let package_json = tauri_dir
+          .ancestors()
+          .map(|dir| dir.join("package.json"))
+          .find(|path| path.exists());
+        let mut candidates = vec![(json_path, None)];
+        if let Some(package_json) = package_json {
+          candidates.push((package_json, Some("tauri")));
+        }
+        candidates.push((tauri_dir.join(".taurirc.json"), None));
+        for (path, key) in candidates {
+          if !path.exists() {
+            continue;
+          }
+          // the JSON config can have comments, trailing commas and unquoted keys
+          let mut config: serde_json::Value = json5::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| {
+              crate::Error::GenericError(format!("failed to parse {}: {}", path.display(), e))
+            })?;
+          if let Some(key) = key {
+            config = config[key].take();
+            if config.is_null() {
+              continue;
+            }
+          }
+          return Ok(serde_json::from_value(config)?);
+        }
+        Err(crate::Error::GenericError(format!(
+          "no tauri config found on {}",
+          tauri_dir.display()
+        )))
       }
       None => Err(crate::Error::EnvironmentError),
     },
@@ -0,0 +1,177 @@
+// The updater artifacts are generated from the bundles the updater installs,
+// and signed with the private key of the `tauri > updater > pubkey`:
+//
+// foobar.app.tar.gz, foobar.app.tar.gz.sig               # macOS: the .app bundle, archived
+// foobar_1.2.3_amd64.AppImage.tar.gz, ...tar.gz.sig       # Linux: the AppImage, archived
+// foobar_1.2.3_x64.msi.sig, foobar_1.2.3_x64-setup.exe.sig # Windows: the installers, downloaded as is
+
+use super::common;
+
+use anyhow::Context;
+use libflate::gzip;
+use minisign::{SecretKey, SecretKeyBox};
+
+use std::{
+  env::var,
+  ffi::OsStr,
+  fs::{read_to_string, File},
+  io::{BufReader, Write},
+  path::{Path, PathBuf},
+};
+
+/// The private key, as the minisign secret key file or its base64 encoding.
+const PRIVATE_KEY_ENV: &str = "TAURI_PRIVATE_KEY";
+/// The path of the private key file, if `TAURI_PRIVATE_KEY` isn't set.
+const PRIVATE_KEY_PATH_ENV: &str = "TAURI_PRIVATE_KEY_PATH";
+/// The password of the private key.
+const KEY_PASSWORD_ENV: &str = "TAURI_KEY_PASSWORD";
+
+/// Bundles the updater artifacts of the given bundles.
+/// Returns a vector of PathBuf that shows where the archives and signatures were created.
+pub fn bundle_project(bundles: &[PathBuf]) -> crate::Result<Vec<PathBuf>> {
+  let secret_key = secret_key()?;
+  let mut paths = Vec::new();
+  for bundle in bundles {
+    // the Windows installers are run as downloaded, the other bundles replace the app
+    let artifact = if is_installer(bundle) {
+      bundle.clone()
+    } else {
+      let archive = archive(bundle)?;
+      paths.push(archive.clone());
+      archive
+    };
+    paths.push(sign(&secret_key, &artifact)?);
+  }
+  Ok(paths)
+}
+
+/// Reads the private key from `TAURI_PRIVATE_KEY` or `TAURI_PRIVATE_KEY_PATH`,
+/// decrypting it with `TAURI_KEY_PASSWORD`.
+pub fn secret_key() -> crate::Result<SecretKey> {
+  let key = match (var(PRIVATE_KEY_ENV), var(PRIVATE_KEY_PATH_ENV)) {
+    (Ok(key), _) => key,
+    (Err(_), Ok(path)) => {
+      read_to_string(&path).with_context(|| format!("failed to read the private key `{}`", path))?
+    }
+    _ => {
+      return Err(crate::Error::GenericError(format!(
+        "the updater bundle requires the private key; set `{}` or `{}`",
+        PRIVATE_KEY_ENV, PRIVATE_KEY_PATH_ENV
+      )))
+    }
+  };
+  decode_secret_key(&key, var(KEY_PASSWORD_ENV).ok())
+}
+
+/// Decodes the minisign secret key file, or its base64 encoding.
+fn decode_secret_key(key: &str, password: Option<String>) -> crate::Result<SecretKey> {
+  let key = key.trim();
+  let key = if key.starts_with("untrusted comment:") {
+    key.to_string()
+  } else {
+    base64::decode(key)
+      .ok()
+      .and_then(|key| String::from_utf8(key).ok())
+      .ok_or_else(|| crate::Error::GenericError("the private key isn't valid base64".to_string()))?
+  };
+  // minisign prompts for the password when it's `None`; an unencrypted key has an empty one
+  SecretKeyBox::from_string(&key)
+    .and_then(|key| key.into_secret_key(Some(password.unwrap_or_default())))
+    .map_err(|e| crate::Error::GenericError(format!("failed to decode the private key: {}", e)))
+}
+
+fn is_installer(path: &Path) -> bool {
+  matches!(
+    path.extension().and_then(OsStr::to_str),
+    Some("msi") | Some("exe")
+  )
+}
+
+/// Archives the bundle on a `.tar.gz` file next to it, keeping its file name, e.g. `foobar.app.tar.gz`.
+fn archive(bundle: &Path) -> crate::Result<PathBuf> {
+  let name = bundle
+    .file_name()
+    .expect("the bundle has a file name")
+    .to_os_string();
+  let mut archive_name = name.clone();
+  archive_name.push(".tar.gz");
+  let archive_path = bundle.with_file_name(archive_name);
+  common::print_bundling(&archive_path.display().to_string())?;
+
+  let gzip_encoder = gzip::Encoder::new(common::create_file(&archive_path)?)?;
+  let mut tar_builder = tar::Builder::new(gzip_encoder);
+  // the .app bundle frameworks link their current version
+  tar_builder.follow_symlinks(false);
+  if bundle.is_dir() {
+    tar_builder.append_dir_all(&name, bundle)?;
+  } else {
+    tar_builder.append_path_with_name(bundle, &name)?;
+  }
+  let mut archive_file = tar_builder.into_inner()?.finish().into_result()?;
+  archive_file.flush()?;
+  Ok(archive_path)
+}
+
+/// Writes the base64 encoded minisign signature of the artifact on a `.sig` file next to it,
+/// the `signature` of the updater manifest.
+fn sign(secret_key: &SecretKey, artifact: &Path) -> crate::Result<PathBuf> {
+  let file = File::open(artifact)?;
+  let signature =
+    minisign::sign(None, secret_key, BufReader::new(file), None, None).map_err(|e| {
+      crate::Error::GenericError(format!("failed to sign `{}`: {}", artifact.display(), e))
+    })?;
+  let mut signature_name = artifact
+    .file_name()
+    .expect("the artifact has a file name")
+    .to_os_string();
+  signature_name.push(".sig");
+  let signature_path = artifact.with_file_name(signature_name);
+  let mut signature_file = common::create_file(&signature_path)?;
+  signature_file.write_all(base64::encode(signature.into_string()).as_bytes())?;
+  signature_file.flush()?;
+  Ok(signature_path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{archive, decode_secret_key, sign};
+  use libflate::gzip;
+  use minisign::{KeyPair, SignatureBox};
+  use std::{fs, io::Read};
+
+  #[test]
+  fn archives_and_signs() {
+    let dir = tempfile::tempdir().unwrap();
+    let bundle = dir.path().join("app_1.0.0_amd64.AppImage");
+    fs::write(&bundle, b"the app").unwrap();
+
+    let archive_path = archive(&bundle).unwrap();
+    assert_eq!(
+      archive_path,
+      dir.path().join("app_1.0.0_amd64.AppImage.tar.gz")
+    );
+    let decoder = gzip::Decoder::new(fs::File::open(&archive_path).unwrap()).unwrap();
+    let mut archive = tar::Archive::new(decoder);
+    let mut entry = archive.entries().unwrap().next().unwrap().unwrap();
+    assert_eq!(
+      entry.path().unwrap().to_str(),
+      Some("app_1.0.0_amd64.AppImage")
+    );
+    let mut contents = String::new();
+    entry.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "the app");
+
+    let keypair = KeyPair::generate_encrypted_keypair(Some(String::new())).unwrap();
+    let key = base64::encode(keypair.sk.to_box(None).unwrap().into_string());
+    let secret_key = decode_secret_key(&key, None).unwrap();
+    let signature_path = sign(&secret_key, &archive_path).unwrap();
+    assert_eq!(
+      signature_path,
+      dir.path().join("app_1.0.0_amd64.AppImage.tar.gz.sig")
+    );
+    let signature = base64::decode(fs::read(&signature_path).unwrap()).unwrap();
+    assert!(SignatureBox::from_string(&String::from_utf8(signature).unwrap()).is_ok());
+
+    assert!(decode_secret_key("not a key", None).is_err());
+  }
+}
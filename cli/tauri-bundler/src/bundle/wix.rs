@@ -2,6 +2,7 @@ use super::{
   common,
   path_utils::{copy_file, FileOpts},
   settings::Settings,
+  tauri_config::WebviewInstallMode,
 };
 
 use handlebars::{to_json, Handlebars};
@@ -14,10 +15,10 @@ use zip::ZipArchive;
 
 use std::{
   collections::BTreeMap,
-  fs::{create_dir_all, remove_dir_all, write, File},
+  fs::{create_dir_all, read, read_dir, remove_dir_all, write, File},
   io::{Cursor, Read, Write},
   path::{Path, PathBuf},
-  process::{Command, Stdio},
+  process::Command,
 };
 
 // URLS for the WIX toolchain.  Can be used for crossplatform compilation.
@@ -25,6 +26,52 @@ pub const WIX_URL: &str =
   "https://github.com/wixtoolset/wix3/releases/download/wix3112rtm/wix311-binaries.zip";
 pub const WIX_SHA256: &str = "2c1888d5d1dba377fc7fa14444cf556963747ff9a0a289a3599cf09da03b9e2e";
 
+// URL of the WebView2 runtime bootstrapper.
+pub const WEBVIEW2_BOOTSTRAPPER_URL: &str = "https://go.microsoft.com/fwlink/p/?LinkId=2124703";
+// The directory of the WebView2 fixed runtime on the installation directory.
+pub const WEBVIEW2_FIXED_RUNTIME_DIR: &str = "WebView2Runtime";
+
+// The WiX cultures the MSI installer can be localized to, with their language id and codepage.
+// These are the cultures of the WixUIExtension localization files.
+const WIX_CULTURES: &[(&str, u16, u16)] = &[
+  ("ar-SA", 1025, 1256),
+  ("bg-BG", 1026, 1251),
+  ("ca-ES", 1027, 1252),
+  ("cs-CZ", 1029, 1250),
+  ("da-DK", 1030, 1252),
+  ("de-DE", 1031, 1252),
+  ("el-GR", 1032, 1253),
+  ("en-US", 1033, 1252),
+  ("es-ES", 3082, 1252),
+  ("et-EE", 1061, 1257),
+  ("fi-FI", 1035, 1252),
+  ("fr-FR", 1036, 1252),
+  ("he-IL", 1037, 1255),
+  ("hr-HR", 1050, 1250),
+  ("hu-HU", 1038, 1250),
+  ("it-IT", 1040, 1252),
+  ("ja-JP", 1041, 932),
+  ("ko-KR", 1042, 949),
+  ("lt-LT", 1063, 1257),
+  ("lv-LV", 1062, 1257),
+  ("nb-NO", 1044, 1252),
+  ("nl-NL", 1043, 1252),
+  ("pl-PL", 1045, 1250),
+  ("pt-BR", 1046, 1252),
+  ("pt-PT", 2070, 1252),
+  ("ro-RO", 1048, 1250),
+  ("ru-RU", 1049, 1251),
+  ("sk-SK", 1051, 1250),
+  ("sl-SI", 1060, 1250),
+  ("sv-SE", 1053, 1252),
+  ("th-TH", 1054, 874),
+  ("tr-TR", 1055, 1254),
+  ("uk-UA", 1058, 1251),
+  ("zh-CN", 2052, 936),
+  ("zh-HK", 3076, 950),
+  ("zh-TW", 1028, 950),
+];
+
 // For Cross Platform Complilation.
 
 // const VC_REDIST_X86_URL: &str =
@@ -72,6 +119,25 @@ struct Binary {
   id: String,
   /// the binary path.
   path: String,
+  /// the binary file name on the installation directory.
+  name: String,
+}
+
+/// A file association to register with WIX.
+#[derive(Serialize)]
+struct FileAssociation {
+  /// the ProgId of the file type.
+  prog_id: String,
+  /// the file type description.
+  description: String,
+  /// the file extensions.
+  ext: Vec<String>,
+  /// the file type MIME type.
+  mime_type: String,
+  /// the id of the file holding the file type icon.
+  icon_id: String,
+  /// the file type icon path, if it's not the app executable.
+  icon_path: Option<String>,
 }
 
 /// A Resource file to bundle with WIX.
@@ -143,7 +209,7 @@ impl ResourceDirectory {
 
 /// Copies the icon to the binary path, under the `resources` folder,
 /// and returns the path to the file.
-fn copy_icon(settings: &Settings) -> crate::Result<PathBuf> {
+pub fn copy_icon(settings: &Settings) -> crate::Result<PathBuf> {
   let base_dir = settings.project_out_directory();
 
   let resource_dir = base_dir.join("resources");
@@ -164,6 +230,81 @@ fn copy_icon(settings: &Settings) -> crate::Result<PathBuf> {
   Ok(icon_target_path)
 }
 
+/// Downloads the WebView2 bootstrapper to the resources folder near the installer.
+/// The bootstrapper link always serves the latest version, so its hash is pinned by the config:
+/// the downloaded or previously downloaded bootstrapper must match it.
+pub fn download_webview2_bootstrapper(settings: &Settings, hash: &str) -> crate::Result<PathBuf> {
+  let resource_dir = settings.project_out_directory().join("resources");
+  create_dir_all(&resource_dir)?;
+  let bootstrapper_path = resource_dir.join("MicrosoftEdgeWebview2Setup.exe");
+  let cached = bootstrapper_path.exists() && verify_hash(&read(&bootstrapper_path)?, hash).is_ok();
+  if !cached {
+    let data = download_and_verify(WEBVIEW2_BOOTSTRAPPER_URL, hash)?;
+    write(&bootstrapper_path, data)?;
+  }
+  Ok(bootstrapper_path)
+}
+
+/// Generates the WIX directory tree of the WebView2 fixed runtime.
+/// Returns the XML string and the ids of its components.
+fn generate_fixed_runtime_data(path: &Path) -> crate::Result<(String, Vec<String>)> {
+  let mut component_ids = Vec::new();
+  let wix_string =
+    generate_fixed_runtime_directory(path, path, WEBVIEW2_FIXED_RUNTIME_DIR, &mut component_ids)?;
+  Ok((wix_string, component_ids))
+}
+
+/// Generates the WIX directory of a WebView2 fixed runtime folder, recursively.
+fn generate_fixed_runtime_directory(
+  root: &Path,
+  directory: &Path,
+  name: &str,
+  component_ids: &mut Vec<String>,
+) -> crate::Result<String> {
+  let relative_path = directory.strip_prefix(root)?.display().to_string();
+  let directory_id = format!(
+    "WebView2Runtime_{}",
+    generate_guid(format!("{}/{}", WEBVIEW2_FIXED_RUNTIME_DIR, relative_path).as_bytes())
+      .to_simple()
+  );
+  let mut wix_string = format!(r#"<Directory Id="{}" Name="{}">"#, directory_id, name);
+
+  let mut entries = read_dir(directory)?
+    .map(|entry| entry.map(|entry| entry.path()))
+    .collect::<Result<Vec<_>, _>>()?;
+  entries.sort();
+  for entry in entries {
+    let entry_name = entry
+      .file_name()
+      .expect("failed to extract WebView2 runtime file name")
+      .to_string_lossy()
+      .to_string();
+    if entry.is_dir() {
+      wix_string.push_str(&generate_fixed_runtime_directory(
+        root,
+        &entry,
+        &entry_name,
+        component_ids,
+      )?);
+    } else {
+      let relative_path = entry.strip_prefix(root)?.display().to_string();
+      let guid =
+        generate_guid(format!("{}/{}", WEBVIEW2_FIXED_RUNTIME_DIR, relative_path).as_bytes());
+      let id = format!("WebView2Runtime_{}", guid.to_simple());
+      wix_string.push_str(&format!(
+        r#"<Component Id="{id}" Guid="{guid}" Win64="$(var.Win64)"><File Id="{id}" Source="{path}" KeyPath="yes"/></Component>"#,
+        id = id,
+        guid = guid,
+        path = entry.display()
+      ));
+      component_ids.push(id);
+    }
+  }
+
+  wix_string.push_str("</Directory>");
+  Ok(wix_string)
+}
+
 /// Function used to download Wix and VC_REDIST. Checks SHA256 to verify the download.
 fn download_and_verify(url: &str, hash: &str) -> crate::Result<Vec<u8>> {
   common::print_info(format!("Downloading {}", url).as_str())?;
@@ -173,22 +314,27 @@ fn download_and_verify(url: &str, hash: &str) -> crate::Result<Vec<u8>> {
   let data: Vec<u8> = response.bytes()?;
 
   common::print_info("validating hash")?;
+  verify_hash(&data, hash)?;
+  Ok(data)
+}
 
+/// Checks the SHA256 of the data against the expected hex encoded hash.
+fn verify_hash(data: &[u8], hash: &str) -> crate::Result<()> {
   let mut hasher = sha2::Sha256::new();
-  hasher.update(&data);
+  hasher.update(data);
 
-  let url_hash = hasher.finalize().to_vec();
+  let data_hash = hasher.finalize().to_vec();
   let expected_hash = hex::decode(hash)?;
 
-  if expected_hash == url_hash {
-    Ok(data)
+  if expected_hash == data_hash {
+    Ok(())
   } else {
     Err(crate::Error::HashError)
   }
 }
 
-/// The installer directory of the app.
-fn app_installer_dir(settings: &Settings) -> crate::Result<PathBuf> {
+/// The installer path of the app, with the culture suffix if it's localized.
+fn app_installer_dir(settings: &Settings, culture: Option<&str>) -> crate::Result<PathBuf> {
   let arch = match settings.binary_arch() {
     "x86" => "x86",
     "x86_64" => "x64",
@@ -200,12 +346,16 @@ fn app_installer_dir(settings: &Settings) -> crate::Result<PathBuf> {
     }
   };
 
-  let package_base_name = format!(
+  let mut package_base_name = format!(
     "{}_{}_{}",
     settings.main_binary_name().replace(".exe", ""),
     settings.version_string(),
     arch
   );
+  if let Some(culture) = culture {
+    package_base_name.push_str(&format!("_{}", culture));
+  }
+  package_base_name.push_str(settings.artifact_suffix());
 
   Ok(
     settings
@@ -245,7 +395,35 @@ fn generate_package_guid(settings: &Settings) -> Uuid {
   generate_guid(settings.bundle_identifier().as_bytes())
 }
 
+/// Returns the MSI UpgradeCode: the `upgradeCode` override, or a GUID derived from the bundle identifier,
+/// so it's the same on every machine and release.
+fn upgrade_code(identifier: &str, upgrade_code: Option<&str>) -> crate::Result<String> {
+  let derived = generate_guid(format!("{}/upgrade-code", identifier).as_bytes()).to_string();
+  match upgrade_code {
+    Some(code) => Uuid::parse_str(code.trim_start_matches('{').trim_end_matches('}'))
+      .map(|code| code.to_string())
+      .map_err(|_| {
+        crate::Error::GenericError(format!(
+          "the MSI upgrade code `{}` on `tauri > bundle > windows > upgradeCode` isn't a GUID, e.g. `{}`",
+          code, derived
+        ))
+      }),
+    None => Ok(derived),
+  }
+}
+
 /// Generates a GUID.
+/// The GUID of the start menu shortcut component.
+/// The component moves to a new folder with a new GUID, so the major upgrade removes the old shortcut.
+fn start_menu_shortcut_guid(identifier: &str, folder: &str) -> Uuid {
+  generate_guid(format!("{}/start-menu-shortcut/{}", identifier, folder).as_bytes())
+}
+
+/// The GUID of the desktop shortcut component.
+fn desktop_shortcut_guid(identifier: &str) -> Uuid {
+  generate_guid(format!("{}/desktop-shortcut", identifier).as_bytes())
+}
+
 fn generate_guid(key: &[u8]) -> Uuid {
   let namespace = Uuid::from_bytes(UUID_NAMESPACE);
   Uuid::new_v5(&namespace, key)
@@ -314,12 +492,55 @@ pub fn get_and_extract_wix(path: &Path) -> crate::Result<()> {
 //   }
 // }
 
-/// Runs the Candle.exe executable for Wix. Candle parses the wxs file and generates the code for building the installer.
+/// Gets the errors of the candle or light output, which point to the file and line of the failing source,
+/// e.g. `C:\app\registry.wxs(12) : error CNDL0104 : Not a valid source file`.
+fn wix_errors(output: &str) -> Vec<&str> {
+  output
+    .lines()
+    .map(str::trim)
+    .filter(|line| line.contains(" : error "))
+    .collect()
+}
+
+/// Runs candle or light, failing with the errors of its output.
+fn run_wix_tool(cmd: &mut Command, tool: &str, settings: &Settings) -> crate::Result<()> {
+  let output = cmd.output()?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  for line in stdout
+    .lines()
+    .chain(String::from_utf8_lossy(&output.stderr).lines())
+  {
+    common::write_log(line);
+    if settings.is_verbose() {
+      eprintln!("{}", line);
+    }
+  }
+  if output.status.success() {
+    return Ok(());
+  }
+  let errors = wix_errors(&stdout);
+  Err(crate::Error::ShellScriptError(if errors.is_empty() {
+    format!(
+      "error running {}{}",
+      tool,
+      if settings.is_verbose() {
+        ""
+      } else {
+        ", try running with --verbose to see command output"
+      }
+    )
+  } else {
+    format!("error running {}:\n{}", tool, errors.join("\n"))
+  }))
+}
+
+/// Runs the Candle.exe executable for Wix. Candle parses the wxs file and generates the `{name}.wixobj` code for building the installer.
 fn run_candle(
   settings: &Settings,
   wix_toolset_path: &Path,
   build_path: &Path,
-  wxs_file_name: &str,
+  wxs_path: &Path,
+  name: &str,
 ) -> crate::Result<()> {
   let arch = match settings.binary_arch() {
     "x86_64" => "x64",
@@ -341,7 +562,9 @@ fn run_candle(
   let args = vec![
     "-arch".to_string(),
     arch.to_string(),
-    wxs_file_name.to_string(),
+    wxs_path.display().to_string(),
+    "-out".to_string(),
+    format!("{}.wixobj", name),
     format!(
       "-dSourceDir={}",
       settings.binary_path(main_binary).display()
@@ -349,33 +572,21 @@ fn run_candle(
   ];
 
   let candle_exe = wix_toolset_path.join("candle.exe");
-  common::print_info(format!("running candle for {}", wxs_file_name).as_str())?;
+  common::print_info(format!("running candle for {}", wxs_path.display()).as_str())?;
 
   let mut cmd = Command::new(&candle_exe);
-  cmd
-    .args(&args)
-    .stdout(Stdio::piped())
-    .current_dir(build_path);
-
-  common::print_info("running candle.exe")?;
-  common::execute_with_verbosity(&mut cmd, &settings).map_err(|_| {
-    crate::Error::ShellScriptError(format!(
-      "error running candle.exe{}",
-      if settings.is_verbose() {
-        ""
-      } else {
-        ", try running with --verbose to see command output"
-      }
-    ))
-  })
+  cmd.args(&args).current_dir(build_path);
+
+  run_wix_tool(&mut cmd, "candle.exe", settings)
 }
 
 /// Runs the Light.exe file. Light takes the generated code from Candle and produces an MSI Installer.
 fn run_light(
   wix_toolset_path: &Path,
   build_path: &Path,
-  wixobjs: &[&str],
+  wixobjs: &[String],
   output_path: &Path,
+  culture: Option<&str>,
   settings: &Settings,
 ) -> crate::Result<PathBuf> {
   let light_exe = wix_toolset_path.join("light.exe");
@@ -386,41 +597,102 @@ fn run_light(
     "-o".to_string(),
     output_path.display().to_string(),
   ];
-
-  for p in wixobjs {
-    args.push((*p).to_string());
+  if let Some(culture) = culture {
+    args.push(format!("-cultures:{}", culture));
   }
 
+  args.extend(wixobjs.iter().cloned());
+
   let mut cmd = Command::new(&light_exe);
-  cmd
-    .args(&args)
-    .stdout(Stdio::piped())
-    .current_dir(build_path);
+  cmd.args(&args).current_dir(build_path);
 
   common::print_info(format!("running light to produce {}", output_path.display()).as_str())?;
-  common::execute_with_verbosity(&mut cmd, &settings)
-    .map(|_| output_path.to_path_buf())
-    .map_err(|_| {
-      crate::Error::ShellScriptError(format!(
-        "error running light.exe{}",
-        if settings.is_verbose() {
-          ""
-        } else {
-          ", try running with --verbose to see command output"
-        }
-      ))
-    })
+  run_wix_tool(&mut cmd, "light.exe", settings).map(|_| output_path.to_path_buf())
 }
 
 // fn get_icon_data() -> crate::Result<()> {
 //   Ok(())
 // }
 
-// Entry point for bundling and creating the MSI installer. For now the only supported platform is Windows x64.
+/// Converts the app version to the `major.minor.build` format of the MSI ProductVersion,
+/// dropping its pre-release and build metadata, or validates the `msiVersion` override.
+fn msi_version(app_version: &str, msi_version: Option<&str>) -> crate::Result<String> {
+  let (version, is_override) = match msi_version {
+    Some(version) => (version, true),
+    None => (app_version, false),
+  };
+  let invalid = |reason: &str| {
+    crate::Error::GenericError(format!(
+      "the MSI version `{}` {}; set a `major.minor.build` version on `tauri > bundle > windows > msiVersion`",
+      version, reason
+    ))
+  };
+
+  let numeric = version
+    .split(|c| c == '-' || c == '+')
+    .next()
+    .unwrap_or_default();
+  if numeric != version {
+    if is_override {
+      return Err(invalid("can't have pre-release or build metadata"));
+    }
+    common::print_warning(&format!(
+      "the MSI version ignores the pre-release and build metadata of `{}`, so it's `{}`",
+      version, numeric
+    ))?;
+  }
+
+  let parts = numeric
+    .split('.')
+    .map(|part| part.parse::<u64>())
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|_| invalid("must only have numeric parts"))?;
+  if parts.len() != 3 {
+    return Err(invalid("must have exactly three parts"));
+  }
+  if parts[0] > 255 || parts[1] > 255 || parts[2] > 65535 {
+    return Err(invalid(
+      "is out of range, the major and minor parts must be up to 255 and the build part up to 65535",
+    ));
+  }
+
+  Ok(format!("{}.{}.{}", parts[0], parts[1], parts[2]))
+}
+
+/// Resolves the configured WiX cultures to their language id and codepage.
+/// Returns the default `en-US` language, without a culture, if none is configured.
+fn wix_languages(languages: &[String]) -> crate::Result<Vec<(Option<&'static str>, u16, u16)>> {
+  if languages.is_empty() {
+    return Ok(vec![(None, 1033, 1252)]);
+  }
+  let mut resolved = Vec::new();
+  for language in languages {
+    match WIX_CULTURES
+      .iter()
+      .find(|(culture, _, _)| culture.eq_ignore_ascii_case(language))
+    {
+      Some((culture, id, codepage)) => resolved.push((Some(*culture), *id, *codepage)),
+      None => {
+        return Err(crate::Error::GenericError(format!(
+          "unknown WiX culture `{}` on `tauri > bundle > windows > wixLanguages`, expected one of: {}",
+          language,
+          WIX_CULTURES
+            .iter()
+            .map(|(culture, _, _)| *culture)
+            .collect::<Vec<_>>()
+            .join(", ")
+        )))
+      }
+    }
+  }
+  Ok(resolved)
+}
+
+// Entry point for bundling and creating the MSI installers, one per configured culture. For now the only supported platform is Windows x64.
 pub fn build_wix_app_installer(
   settings: &Settings,
   wix_toolset_path: &Path,
-) -> crate::Result<PathBuf> {
+) -> crate::Result<Vec<PathBuf>> {
   let arch = match settings.binary_arch() {
     "x86_64" => "x64",
     "x86" => "x86",
@@ -432,6 +704,10 @@ pub fn build_wix_app_installer(
     }
   };
 
+  let languages = wix_languages(settings.windows_wix_languages())?;
+  let version = msi_version(settings.version_string(), settings.windows_msi_version())?;
+  let fragment_paths = settings.windows_wix_fragment_paths()?;
+
   // target only supports x64.
   common::print_info(format!("Target: {}", arch).as_str())?;
 
@@ -442,27 +718,102 @@ pub fn build_wix_app_installer(
 
   let mut data = BTreeMap::new();
 
-  if crate::bundle::tauri_config::get().is_ok() {
+  let embedded_server = crate::bundle::tauri_config::get().is_ok();
+  if embedded_server {
     data.insert("embedded_server", to_json(true));
   }
 
-  data.insert("product_name", to_json(settings.bundle_name()));
-  data.insert("version", to_json(settings.version_string()));
-  let manufacturer = settings.bundle_identifier().to_string();
-  data.insert("manufacturer", to_json(manufacturer.as_str()));
-  let upgrade_code = Uuid::new_v5(
-    &Uuid::NAMESPACE_DNS,
-    format!("{}.app.x64", &settings.main_binary_name()).as_bytes(),
-  )
-  .to_string();
+  let install_webview2 = match settings.windows_webview_install_mode()? {
+    Some(WebviewInstallMode::DownloadBootstrapper) => {
+      data.insert("download_webview2_bootstrapper", to_json(true));
+      data.insert(
+        "webview2_bootstrapper_url",
+        to_json(WEBVIEW2_BOOTSTRAPPER_URL),
+      );
+      true
+    }
+    Some(WebviewInstallMode::EmbedBootstrapper { sha256 }) => {
+      let bootstrapper_path = download_webview2_bootstrapper(settings, sha256)?;
+      data.insert("webview2_installer_path", to_json(bootstrapper_path));
+      true
+    }
+    Some(WebviewInstallMode::OfflineInstaller { path }) => {
+      let installer_path = std::env::current_dir()?.join(path);
+      data.insert("webview2_installer_path", to_json(installer_path));
+      true
+    }
+    Some(WebviewInstallMode::FixedRuntime { path }) => {
+      let (fixed_runtime, component_ids) =
+        generate_fixed_runtime_data(&std::env::current_dir()?.join(path))?;
+      data.insert("webview2_fixed_runtime", to_json(fixed_runtime));
+      data.insert(
+        "webview2_fixed_runtime_component_ids",
+        to_json(component_ids),
+      );
+      false
+    }
+    None => false,
+  };
+  data.insert("install_webview2", to_json(install_webview2));
+  data.insert("component_refs", to_json(settings.windows_component_refs()));
+  data.insert("feature_refs", to_json(settings.windows_feature_refs()));
+  data.insert(
+    "install_execute_sequence",
+    to_json(embedded_server || install_webview2),
+  );
 
+  // the major upgrades compare this version, so it must be the same normalized one on every release
+  data.insert("version", to_json(&version));
+  let manufacturer = settings
+    .publisher()
+    .unwrap_or_else(|| settings.bundle_identifier())
+    .to_string();
+  data.insert("manufacturer", to_json(manufacturer.as_str()));
+  if !settings.homepage_url().is_empty() {
+    data.insert("homepage", to_json(settings.homepage_url()));
+  }
+  if let Some(license) = settings.license_file()? {
+    // the license dialog only shows RTF documents
+    if license.extension().map_or(false, |ext| ext == "rtf") {
+      data.insert("license_rtf", to_json(&license));
+    }
+    data.insert("license_file", to_json(&license));
+    data.insert(
+      "license_file_name",
+      to_json(license.file_name().expect("No file name").to_string_lossy()),
+    );
+  }
+  // the installers of every version share the upgrade code, so they replace each other
+  let upgrade_code = upgrade_code(
+    settings.bundle_identifier(),
+    settings.windows_upgrade_code(),
+  )?;
   data.insert("upgrade_code", to_json(&upgrade_code.as_str()));
+  data.insert(
+    "allow_downgrades",
+    to_json(settings.windows_allow_downgrades()),
+  );
 
   let path_guid = generate_package_guid(settings).to_string();
   data.insert("path_component_guid", to_json(&path_guid.as_str()));
 
-  let shortcut_guid = generate_package_guid(settings).to_string();
-  data.insert("shortcut_guid", to_json(&shortcut_guid.as_str()));
+  if settings.windows_start_menu_shortcut() {
+    let start_menu_folder = settings
+      .windows_start_menu_folder()
+      .unwrap_or_else(|| settings.bundle_name());
+    data.insert("start_menu_shortcut", to_json(true));
+    data.insert("start_menu_folder", to_json(start_menu_folder));
+    let shortcut_guid = start_menu_shortcut_guid(settings.bundle_identifier(), start_menu_folder);
+    data.insert(
+      "start_menu_shortcut_guid",
+      to_json(shortcut_guid.to_string()),
+    );
+  }
+  if settings.windows_desktop_shortcut() {
+    let shortcut_guid = desktop_shortcut_guid(settings.bundle_identifier());
+    data.insert("desktop_shortcut", to_json(true));
+    data.insert("desktop_shortcut_guid", to_json(shortcut_guid.to_string()));
+  }
 
   let app_exe_name = settings.main_binary_name().to_string();
   data.insert("app_exe_name", to_json(&app_exe_name));
@@ -472,6 +823,11 @@ pub fn build_wix_app_installer(
   let binaries_json = to_json(&binaries);
   data.insert("binaries", binaries_json);
 
+  let file_associations = generate_file_associations_data(&settings)?;
+  data.insert("file_associations", to_json(&file_associations));
+
+  data.insert("deep_link_schemes", to_json(settings.deep_link_schemes()));
+
   let resources = generate_resource_data(&settings)?;
   let mut resources_wix_string = String::from("");
   let mut files_ids = Vec::new();
@@ -500,34 +856,116 @@ pub fn build_wix_app_installer(
 
   data.insert("icon_path", to_json(icon_path));
 
-  let temp = HANDLEBARS.render("main.wxs", &data)?;
-
   if output_path.exists() {
     remove_dir_all(&output_path)?;
   }
 
-  create_dir_all(&output_path)?;
+  let mut targets = Vec::new();
+  for (culture, language, codepage) in languages {
+    data.insert("language", to_json(language));
+    data.insert("codepage", to_json(codepage));
+    // the `tauri > bundle > localization` of the culture, falling back to the top-level fields
+    let localization = culture.and_then(|culture| settings.localization_for(culture));
+    let product_name = localization
+      .and_then(|localization| localization.product_name.as_deref())
+      .unwrap_or_else(|| settings.bundle_name());
+    data.insert("product_name", to_json(product_name));
+    let comments = localization
+      .and_then(|localization| {
+        localization
+          .comments
+          .as_deref()
+          .or_else(|| localization.short_description.as_deref())
+      })
+      .unwrap_or_else(|| settings.short_description())
+      .trim();
+    if comments.is_empty() {
+      data.remove("comments");
+    } else {
+      data.insert("comments", to_json(comments));
+    }
 
-  let main_wxs_path = output_path.join("main.wxs");
-  write(&main_wxs_path, temp)?;
+    let temp = HANDLEBARS.render("main.wxs", &data)?;
 
-  let input_basenames = vec!["main"];
+    // each culture is compiled on its own folder since the language is set on the sources
+    let build_path = match culture {
+      Some(culture) => output_path.join(culture),
+      None => output_path.clone(),
+    };
+    create_dir_all(&build_path)?;
+
+    let main_wxs_path = build_path.join("main.wxs");
+    write(&main_wxs_path, temp)?;
+
+    run_candle(
+      settings,
+      &wix_toolset_path,
+      &build_path,
+      &main_wxs_path,
+      "main",
+    )?;
+    let mut wixobjs = vec!["main.wixobj".to_string()];
+    // the fragments are compiled from their own path, so the errors point to the source file
+    for (i, fragment_path) in fragment_paths.iter().enumerate() {
+      let name = format!("fragment{}", i);
+      run_candle(
+        settings,
+        &wix_toolset_path,
+        &build_path,
+        fragment_path,
+        &name,
+      )?;
+      wixobjs.push(format!("{}.wixobj", name));
+    }
 
-  for basename in &input_basenames {
-    let wxs = format!("{}.wxs", basename);
-    run_candle(settings, &wix_toolset_path, &output_path, &wxs)?;
+    let target = run_light(
+      &wix_toolset_path,
+      &build_path,
+      &wixobjs,
+      &app_installer_dir(&settings, culture)?,
+      culture,
+      &settings,
+    )?;
+    targets.push(target);
   }
 
-  let wixobjs = vec!["main.wixobj"];
-  let target = run_light(
-    &wix_toolset_path,
-    &output_path,
-    &wixobjs,
-    &app_installer_dir(&settings)?,
-    &settings,
-  )?;
+  Ok(targets)
+}
 
-  Ok(target)
+/// Generates the data required for the file associations registration.
+fn generate_file_associations_data(settings: &Settings) -> crate::Result<Vec<FileAssociation>> {
+  let regex = Regex::new(r"[^\w\d\.]")?;
+  let cwd = std::env::current_dir()?;
+  let product = regex.replace_all(settings.bundle_name(), "").to_string();
+  let mut file_associations = Vec::new();
+  for association in settings.file_associations() {
+    let name = regex.replace_all(&association.name, "").to_string();
+    let (icon_id, icon_path) = match &association.icon {
+      Some(icon) => (
+        format!("FileAssociationIcon_{}", name),
+        Some(
+          cwd
+            .join(icon)
+            .into_os_string()
+            .into_string()
+            .expect("failed to read file association icon path"),
+        ),
+      ),
+      None => ("Path".to_string(), None),
+    };
+    file_associations.push(FileAssociation {
+      prog_id: format!("{}.{}", product, name),
+      description: association
+        .description
+        .clone()
+        .unwrap_or_else(|| association.name.clone()),
+      ext: association.ext.clone(),
+      mime_type: association.mime_type(),
+      icon_id,
+      icon_path,
+    });
+  }
+  Ok(file_associations)
 }
 
 /// Generates the data required for the external binaries and extra binaries bundling.
@@ -535,25 +973,19 @@ fn generate_binaries_data(settings: &Settings) -> crate::Result<Vec<Binary>> {
   let mut binaries = Vec::new();
   let regex = Regex::new(r"[^\w\d\.]")?;
   let cwd = std::env::current_dir()?;
-  for src in settings.external_binaries() {
-    let src = src?;
-    let filename = src
-      .file_name()
-      .expect("failed to extract external binary filename")
-      .to_os_string()
-      .into_string()
-      .expect("failed to convert external binary filename to string");
-
+  for (_, filename) in settings.external_binaries()? {
     let guid = generate_guid(filename.as_bytes()).to_string();
 
     binaries.push(Binary {
       guid,
+      // the copy on the project out directory, which might be signed
       path: cwd
-        .join(src)
+        .join(settings.project_out_directory().join(&filename))
         .into_os_string()
         .into_string()
         .expect("failed to read external binary path"),
       id: regex.replace_all(&filename, "").to_string(),
+      name: filename,
     });
   }
 
@@ -569,6 +1001,7 @@ fn generate_binaries_data(settings: &Settings) -> crate::Result<Vec<Binary>> {
           .into_string()
           .expect("failed to read binary path"),
         id: regex.replace_all(&filename, "").to_string(),
+        name: filename.to_string(),
       })
     }
   }
@@ -615,10 +1048,8 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
     );
   }
 
-  for src in settings.resource_files() {
-    let src = src?;
-
-    let filename = src
+  for (src, target) in settings.resource_files()? {
+    let filename = target
       .file_name()
       .expect("failed to extract resource filename")
       .to_os_string()
@@ -637,14 +1068,8 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
       id: regex.replace_all(&filename, "").to_string(),
     };
 
-    // split the resource path directories
-    let mut directories = src
-      .components()
-      .filter(|component| {
-        let comp = component.as_os_str();
-        comp != "." && comp != ".."
-      })
-      .collect::<Vec<_>>();
+    // split the resource destination directories
+    let mut directories = target.components().collect::<Vec<_>>();
     directories.truncate(directories.len() - 1);
     // transform the directory structure to a chained vec structure
     for directory in directories {
@@ -698,3 +1123,157 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
 
   Ok(resources)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    desktop_shortcut_guid, generate_fixed_runtime_data, msi_version, start_menu_shortcut_guid,
+    upgrade_code, verify_hash, wix_errors, wix_languages, HANDLEBARS,
+  };
+  use handlebars::to_json;
+  use std::fs;
+
+  #[test]
+  fn fixed_runtime_components() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::create_dir_all(tmp.path().join("EBWebView")).unwrap();
+    fs::write(tmp.path().join("msedgewebview2.exe"), "").unwrap();
+    fs::write(tmp.path().join("EBWebView").join("resources.pak"), "").unwrap();
+
+    let (wix_string, component_ids) = generate_fixed_runtime_data(tmp.path()).unwrap();
+    assert_eq!(component_ids.len(), 2);
+    assert_ne!(component_ids[0], component_ids[1]);
+    assert!(wix_string.starts_with(r#"<Directory Id="WebView2Runtime_"#));
+    assert!(wix_string.contains(r#"Name="WebView2Runtime">"#));
+    assert!(wix_string.contains(r#"Name="EBWebView">"#));
+    for id in &component_ids {
+      assert!(wix_string.contains(&format!(r#"<Component Id="{}""#, id)));
+    }
+    // the ids only depend on the relative paths, so the upgrades replace the same components
+    assert_eq!(
+      generate_fixed_runtime_data(tmp.path()).unwrap().1,
+      component_ids
+    );
+  }
+
+  #[test]
+  fn cultures() {
+    // the default installer isn't localized
+    assert_eq!(wix_languages(&[]).unwrap(), vec![(None, 1033, 1252)]);
+    let languages = ["en-US", "de-de", "ja-JP"]
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      wix_languages(&languages).unwrap(),
+      vec![
+        (Some("en-US"), 1033, 1252),
+        (Some("de-DE"), 1031, 1252),
+        (Some("ja-JP"), 1041, 932),
+      ]
+    );
+    let error = wix_languages(&["xx-XX".to_string()])
+      .unwrap_err()
+      .to_string();
+    assert!(error.contains("unknown WiX culture `xx-XX`"));
+    assert!(error.contains("expected one of: ar-SA, "));
+  }
+
+  #[test]
+  fn msi_versions() {
+    assert_eq!(msi_version("1.2.3", None).unwrap(), "1.2.3");
+    // the metadata is dropped with a warning
+    assert_eq!(msi_version("1.2.3-beta.4+build5", None).unwrap(), "1.2.3");
+    assert_eq!(msi_version("255.255.65535", None).unwrap(), "255.255.65535");
+    for (version, reason) in &[
+      ("256.0.0", "is out of range"),
+      ("1.256.0", "is out of range"),
+      ("1.2.65536", "is out of range"),
+      ("1.2", "must have exactly three parts"),
+      ("1.2.3.4", "must have exactly three parts"),
+      ("1.x.3", "must only have numeric parts"),
+    ] {
+      let error = msi_version(version, None).unwrap_err().to_string();
+      assert!(
+        error.contains(&format!("the MSI version `{}` {}", version, reason)),
+        "{}",
+        error
+      );
+    }
+
+    // the override replaces the app version, but can't have metadata
+    assert_eq!(msi_version("1.2.3-beta.4", Some("1.2.4")).unwrap(), "1.2.4");
+    assert!(msi_version("1.2.3", Some("1.2.4-beta"))
+      .unwrap_err()
+      .to_string()
+      .contains("can't have pre-release or build metadata"));
+  }
+
+  #[test]
+  fn shortcuts() {
+    let guid = start_menu_shortcut_guid("com.tauri.app", "Tauri");
+    assert_eq!(guid, start_menu_shortcut_guid("com.tauri.app", "Tauri"));
+    assert_ne!(
+      guid,
+      start_menu_shortcut_guid("com.tauri.app", "Tauri Apps")
+    );
+    assert_ne!(guid, start_menu_shortcut_guid("com.tauri.other", "Tauri"));
+    assert_ne!(guid, desktop_shortcut_guid("com.tauri.app"));
+
+    // only the enabled shortcuts are installed, and removed on uninstall
+    let mut data = std::collections::BTreeMap::new();
+    data.insert("product_name", to_json("App"));
+    let wxs = HANDLEBARS.render("main.wxs", &data).unwrap();
+    assert!(!wxs.contains("ApplicationProgramsFolder"));
+    assert!(!wxs.contains("ApplicationDesktopShortcut"));
+
+    data.insert("start_menu_shortcut", to_json(true));
+    data.insert("start_menu_folder", to_json("Tauri Apps"));
+    data.insert("desktop_shortcut", to_json(true));
+    let wxs = HANDLEBARS.render("main.wxs", &data).unwrap();
+    assert!(wxs.contains(r#"<Directory Id="ApplicationProgramsFolder" Name="Tauri Apps"/>"#));
+    assert!(wxs.contains(r#"<RemoveFolder Id="ApplicationProgramsFolder" On="uninstall"/>"#));
+    assert!(wxs.contains(r#"<ComponentRef Id="ApplicationShortcut" />"#));
+    assert!(wxs.contains(r#"<ComponentRef Id="ApplicationDesktopShortcut" />"#));
+  }
+
+  #[test]
+  fn hash_verification() {
+    // the SHA-256 of `abc`
+    let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    assert!(verify_hash(b"abc", hash).is_ok());
+    assert!(verify_hash(b"abd", hash).is_err());
+    assert!(verify_hash(b"abc", &hash.to_uppercase()).is_ok());
+    assert!(verify_hash(b"abc", "not hex").is_err());
+  }
+
+  #[test]
+  fn fragment_errors() {
+    let output = "Windows Installer XML Toolset Compiler version 3.11.2.4516
+registry.wxs
+C:\\app\\registry.wxs(12) : error CNDL0104 : Not a valid source file; detail: unexpected end of file.
+C:\\app\\registry.wxs(3) : warning CNDL1008 : The DefaultLanguage attribute is deprecated.";
+    assert_eq!(
+      wix_errors(output),
+      vec!["C:\\app\\registry.wxs(12) : error CNDL0104 : Not a valid source file; detail: unexpected end of file."]
+    );
+  }
+
+  #[test]
+  fn upgrade_code_is_derived_from_the_identifier() {
+    let code = upgrade_code("com.tauri.app", None).unwrap();
+    assert_eq!(code, upgrade_code("com.tauri.app", None).unwrap());
+    assert_ne!(code, upgrade_code("com.tauri.other", None).unwrap());
+
+    let shipped = "5D5B4F8E-A8E8-4C8F-9E5A-1E4E2C1B3A6D";
+    assert_eq!(
+      upgrade_code("com.tauri.app", Some(shipped)).unwrap(),
+      shipped.to_lowercase()
+    );
+    assert_eq!(
+      upgrade_code("com.tauri.app", Some(&format!("{{{}}}", shipped))).unwrap(),
+      shipped.to_lowercase()
+    );
+    assert!(upgrade_code("com.tauri.app", Some("not-a-guid")).is_err());
+  }
+}
@@ -34,6 +34,8 @@ pub enum Error {
   HandleBarsError(#[from] handlebars::RenderError),
   #[error("`{0}`")]
   JsonError(#[from] serde_json::error::Error),
+  #[error("`{0}`")]
+  PlistError(#[from] plist::Error),
   #[cfg(windows)]
   #[error("`{0}`")]
   RegexError(#[from] regex::Error),
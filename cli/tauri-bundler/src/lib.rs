@@ -2,29 +2,90 @@ pub mod bundle;
 mod error;
 pub use error::{Error, Result};
 
-use bundle::Settings;
-use std::process;
+use bundle::{forward_to_log, has_log_file, write_log, Settings};
+use std::{
+  io::{BufRead, BufReader},
+  process::{self, Stdio},
+};
 
-// Runs `cargo build` to make sure the binary file is up-to-date.
-pub fn build_project(settings: &Settings) -> crate::Result<()> {
-  let mut args = vec!["build".to_string()];
+/// Gets the `cargo build` (or the configured runner) command of the app.
+pub fn build_command(settings: &Settings) -> process::Command {
+  let mut command = process::Command::new(settings.runner());
+  command.arg("build");
 
   if let Some(triple) = settings.target_triple() {
-    args.push(format!("--target={}", triple));
+    command.arg(format!("--target={}", triple));
   }
 
   if settings.is_release_build() {
-    args.push("--release".to_string());
+    command.arg("--release");
   }
 
   if let Some(features) = settings.build_features() {
-    args.push(format!("--features={}", features.join(" ")));
+    command.arg(format!("--features={}", features.join(" ")));
+  }
+
+  command
+}
+
+// Runs `cargo build` (or the configured runner) to make sure the binary file is up-to-date.
+pub fn build_project(settings: &Settings) -> crate::Result<()> {
+  run_build(settings, build_command(settings), None)
+}
+
+// Runs `cargo build` like `build_project`, with `--message-format json`,
+// passing each message to the callback. Lines that aren't JSON are printed to stderr.
+pub fn build_project_with_messages(
+  settings: &Settings,
+  on_message: &mut dyn FnMut(serde_json::Value),
+) -> crate::Result<()> {
+  let mut command = build_command(settings);
+  command.arg("--message-format=json");
+  let on_line: &mut dyn FnMut(String) = &mut |line| match serde_json::from_str(&line) {
+    Ok(message) => on_message(message),
+    Err(_) => eprintln!("{}", line),
+  };
+  run_build(settings, command, Some(on_line))
+}
+
+// Runs the build command, passing its stdout lines to the callback.
+// The output is piped and copied to the log file if there's one.
+fn run_build(
+  settings: &Settings,
+  mut command: process::Command,
+  mut on_line: Option<&mut dyn FnMut(String)>,
+) -> crate::Result<()> {
+  let log = has_log_file();
+  if on_line.is_some() || log {
+    command.stdout(Stdio::piped());
+  }
+  if log {
+    command.stderr(Stdio::piped());
+  }
+  let mut child = command.spawn()?;
+  let stderr = child
+    .stderr
+    .take()
+    .map(|stderr| forward_to_log(stderr, true));
+  if let Some(stdout) = child.stdout.take() {
+    for line in BufReader::new(stdout).lines() {
+      let line = line?;
+      write_log(&line);
+      match on_line.as_mut() {
+        Some(on_line) => on_line(line),
+        None => println!("{}", line),
+      }
+    }
+  }
+  if let Some(stderr) = stderr {
+    let _ = stderr.join();
   }
 
-  let status = process::Command::new("cargo").args(args).status()?;
+  let status = child.wait()?;
   if !status.success() {
     return Err(crate::Error::GenericError(format!(
-      "Result of `cargo build` operation was unsuccessful: {}",
+      "Result of `{} build` operation was unsuccessful: {}",
+      settings.runner(),
       status
     )));
   }
@@ -9,13 +9,18 @@ async function run() {
     await installDependencies()
   } else if (choice === 'update') {
     await updateDependencies()
-  } else {
+  } else if (choice === '-h' || choice === '--help') {
     console.log(`
     Description
       Tauri dependency management script
     Usage
       $ tauri deps [install|update]
+      $ tauri deps [--update] [--offline] [--json]
   `)
+  } else {
+    // the version report and the compatible updates are run by the Rust CLI
+    const { runOnRustCli } = require('../dist/helpers/rust-cli')
+    runOnRustCli('deps', process.argv.slice(2))
   }
 }
 
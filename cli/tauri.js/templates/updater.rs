@@ -10,6 +10,7 @@ fn update() -> Result<(), String> {
         version: github_release.tag.trim_start_matches('v').to_string(),
         download_url: github_release_asset.download_url,
         asset_name: github_release_asset.name,
+        signature: None,
       };
 
       let status = tauri::updater::Update::configure()
@@ -1,41 +1,77 @@
-use crate::config::{CliArg, CliConfig, Config};
+use crate::config::{CliArg, CliArgValueType, CliConfig, CliGroup, Config};
 
-use clap::{App, Arg, ArgMatches, ErrorKind};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, ErrorKind};
+use indexmap::IndexMap;
 use serde::Serialize;
-use serde_json::Value;
+use serde_json::{json, Map, Number, Value};
 use std::collections::HashMap;
 
 #[macro_use]
 mod macros;
 
+/// Where the value of an arg match comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArgSource {
+  /// The arg was passed on the command line.
+  CommandLine,
+  /// The arg wasn't passed, so its value is `false`.
+  Absent,
+}
+
+impl Default for ArgSource {
+  fn default() -> Self {
+    Self::Absent
+  }
+}
+
 /// The resolution of a arg match.
 #[derive(Default, Debug, Serialize)]
 pub struct ArgData {
   /// The value of the arg.
   /// - Value::Bool if it's a flag,
   /// - Value::Array if it's multiple,
+  /// - Value::Number or Value::Bool if it has a `number` or `boolean` value type,
   /// - Value::String if it has value,
   /// - Value::Null otherwise.
   pub value: Value,
+  /// All the values of the arg, in the command line order across its occurrences,
+  /// with the JSON type of its value type. Empty if it's a flag or it's absent.
+  /// e.g. `./app --arg 1 --arg 2 3` results in `[1, 2, 3]`.
+  pub values: Vec<Value>,
   /// The number of occurrences of the arg.
-  /// e.g. `./app --arg 1 --arg 2 --arg 2 3 4` results in three occurrences.
+  /// e.g. `./app --arg 1 --arg 2 --arg 2 3 4` results in three occurrences, and `./app -ddd` in three as well.
   pub occurrences: u64,
+  /// The positions of the arg values on the command line, or of the flag occurrences if it's a flag.
+  pub indices: Vec<usize>,
+  /// Where the value comes from.
+  pub source: ArgSource,
 }
 
 /// The matched subcommand.
 #[derive(Default, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SubcommandMatches {
   /// The subcommand name.
   pub name: String,
+  /// The alias the subcommand was invoked with, if it wasn't invoked with its name.
+  pub alias: Option<String>,
   /// The subcommand arg matches.
   pub matches: Matches,
+  /// The raw args of an external subcommand, allowed with `allowExternalSubcommands`.
+  pub external_args: Option<Vec<String>>,
 }
 
 /// The arg matches of a command.
+///
+/// A matched subcommand is always set, even if it was invoked with no args.
 #[derive(Default, Debug, Serialize)]
 pub struct Matches {
-  /// Data structure mapping each found arg with its resolution.
-  pub args: HashMap<String, ArgData>,
+  /// Data structure mapping each arg with its resolution.
+  /// The passed args come first in the command line order, then the absent ones in the definition order.
+  pub args: IndexMap<String, ArgData>,
+  /// Data structure mapping each group with a present arg to the names of its present args.
+  pub groups: HashMap<String, Vec<String>>,
   /// The matched subcommand if found.
   pub subcommand: Option<Box<SubcommandMatches>>,
 }
@@ -47,8 +83,33 @@ impl Matches {
   }
 
   /// Sets the subcommand matches.
-  pub(crate) fn set_subcommand(&mut self, name: String, matches: Matches) {
-    self.subcommand = Some(Box::new(SubcommandMatches { name, matches }));
+  pub(crate) fn set_subcommand(&mut self, subcommand: SubcommandMatches) {
+    self.subcommand = Some(Box::new(subcommand));
+  }
+
+  /// The matches in the previous shape, with only the `value` and `occurrences` of the args,
+  /// emitted with `tauri > cli > legacyMatches`.
+  pub fn to_legacy(&self) -> Value {
+    let args: Map<String, Value> = self
+      .args
+      .iter()
+      .map(|(name, arg)| {
+        (
+          name.clone(),
+          json!({ "value": arg.value, "occurrences": arg.occurrences }),
+        )
+      })
+      .collect();
+    json!({
+      "args": args,
+      "groups": self.groups,
+      "subcommand": self.subcommand.as_ref().map(|subcommand| json!({
+        "name": subcommand.name,
+        "alias": subcommand.alias,
+        "matches": subcommand.matches.to_legacy(),
+        "externalArgs": subcommand.external_args,
+      })),
+    })
   }
 }
 
@@ -65,8 +126,17 @@ pub fn get_matches(config: &Config) -> crate::Result<Matches> {
     .unwrap_or(&crate_description!().to_string())
     .to_string();
   let app = get_app(crate_name!(), Some(&about), cli);
-  match app.try_get_matches() {
-    Ok(matches) => Ok(get_matches_internal(cli, &matches)),
+  match app
+    .try_get_matches()
+    .and_then(|matches| check_required_if_eq_all(cli, &matches).map(|_| matches))
+  {
+    Ok(matches) => {
+      let args: Vec<String> = std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+      Ok(get_matches_internal(cli, &matches, &args))
+    }
     Err(e) => match e.kind {
       ErrorKind::DisplayHelp => {
         let mut matches = Matches::default();
@@ -75,16 +145,21 @@ pub fn get_matches(config: &Config) -> crate::Result<Matches> {
           "help".to_string(),
           ArgData {
             value: Value::String(help_text),
-            occurrences: 0,
+            source: ArgSource::CommandLine,
+            ..Default::default()
           },
         );
         Ok(matches)
       }
       ErrorKind::DisplayVersion => {
         let mut matches = Matches::default();
-        matches
-          .args
-          .insert("version".to_string(), Default::default());
+        matches.args.insert(
+          "version".to_string(),
+          ArgData {
+            source: ArgSource::CommandLine,
+            ..Default::default()
+          },
+        );
         Ok(matches)
       }
       _ => Err(e.into()),
@@ -92,18 +167,78 @@ pub fn get_matches(config: &Config) -> crate::Result<Matches> {
   }
 }
 
-fn get_matches_internal(config: &CliConfig, matches: &ArgMatches) -> Matches {
+/// Checks the `requiredIfEqAll` conditions of the matched command and subcommands, which clap doesn't support:
+/// the arg is required if every arg of the conditions has its value.
+fn check_required_if_eq_all(config: &CliConfig, matches: &ArgMatches) -> Result<(), clap::Error> {
+  for arg in config.args().into_iter().flatten() {
+    let conditions = match &arg.required_if_eq_all {
+      Some(conditions) if !conditions.is_empty() => conditions,
+      _ => continue,
+    };
+    let required = conditions.iter().all(|(name, value)| {
+      matches
+        .values_of(name)
+        .map_or(false, |mut values| values.any(|v| v == value))
+    });
+    if required && !matches.is_present(&arg.name) {
+      return Err(clap::Error::with_description(
+        format!(
+          "The argument '--{}' is required when {}",
+          arg.name,
+          conditions
+            .iter()
+            .map(|(name, value)| format!("'--{}' is '{}'", name, value))
+            .collect::<Vec<_>>()
+            .join(" and ")
+        ),
+        ErrorKind::MissingRequiredArgument,
+      ));
+    }
+  }
+  if let Some((name, subcommand_matches)) = matches.subcommand() {
+    if let Some(subcommand) = config
+      .subcommands()
+      .and_then(|subcommands| subcommands.get(name))
+    {
+      check_required_if_eq_all(subcommand, subcommand_matches)?;
+    }
+  }
+  Ok(())
+}
+
+/// Maps the clap matches of the command, whose own args (after its name) are `args`.
+fn get_matches_internal(config: &CliConfig, matches: &ArgMatches, args: &[String]) -> Matches {
   let mut cli_matches = Matches::default();
   map_matches(config, matches, &mut cli_matches);
 
   if let Some((subcommand_name, subcommand_matches)) = matches.subcommand() {
-    let mut subcommand_cli_matches = Matches::default();
-    map_matches(
-      config.subcommands().unwrap().get(subcommand_name).unwrap(),
-      subcommand_matches,
-      &mut subcommand_cli_matches,
-    );
-    cli_matches.set_subcommand(subcommand_name.to_string(), subcommand_cli_matches);
+    let subcommand = match config
+      .subcommands()
+      .and_then(|subcommands| subcommands.get(subcommand_name))
+    {
+      Some(subcommand) => {
+        let (alias, subcommand_args) =
+          used_alias(config, subcommand_name, subcommand.aliases(), args);
+        SubcommandMatches {
+          name: subcommand_name.to_string(),
+          alias,
+          matches: get_matches_internal(subcommand, subcommand_matches, subcommand_args),
+          external_args: None,
+        }
+      }
+      // not a configured subcommand, so it's an external one
+      None => SubcommandMatches {
+        name: subcommand_name.to_string(),
+        external_args: Some(
+          subcommand_matches
+            .values_of("")
+            .map(|values| values.map(ToString::to_string).collect())
+            .unwrap_or_default(),
+        ),
+        ..Default::default()
+      },
+    };
+    cli_matches.set_subcommand(subcommand);
   }
 
   cli_matches
@@ -111,33 +246,154 @@ fn get_matches_internal(config: &CliConfig, matches: &ArgMatches) -> Matches {
 
 fn map_matches(config: &CliConfig, matches: &ArgMatches, cli_matches: &mut Matches) {
   if let Some(args) = config.args() {
-    for arg in args {
-      let occurrences = matches.occurrences_of(arg.name.clone());
-      let value = if occurrences == 0 || !arg.takes_value.unwrap_or(false) {
-        Value::Bool(occurrences > 0)
-      } else if arg.multiple.unwrap_or(false) {
-        matches
-          .values_of(arg.name.clone())
-          .map(|v| {
-            let mut values = Vec::new();
-            for value in v {
-              values.push(Value::String(value.to_string()));
-            }
-            Value::Array(values)
-          })
-          .unwrap_or(Value::Null)
-      } else {
-        matches
-          .value_of(arg.name.clone())
-          .map(|v| Value::String(v.to_string()))
-          .unwrap_or(Value::Null)
-      };
-
-      cli_matches.set_arg(arg.name.clone(), ArgData { value, occurrences });
+    let mut args: Vec<(&str, ArgData)> = args
+      .iter()
+      .map(|arg| (arg.name.as_str(), arg_data(arg, matches)))
+      .collect();
+    // a stable sort, so the absent args keep the definition order
+    args.sort_by_key(|(_, data)| data.indices.first().copied().unwrap_or(usize::MAX));
+    for (name, data) in args {
+      cli_matches.set_arg(name.to_string(), data);
+    }
+  }
+  if let Some(groups) = config.groups() {
+    for group in groups {
+      let present: Vec<String> = group_args(config, group)
+        .into_iter()
+        .filter(|arg| matches.is_present(arg))
+        .map(ToString::to_string)
+        .collect();
+      if !present.is_empty() {
+        cli_matches.groups.insert(group.name.clone(), present);
+      }
     }
   }
 }
 
+/// Resolves the match of an arg.
+fn arg_data(arg: &CliArg, matches: &ArgMatches) -> ArgData {
+  let name = arg.name.as_str();
+  let occurrences = matches.occurrences_of(name);
+  if occurrences == 0 {
+    return ArgData {
+      value: Value::Bool(false),
+      ..Default::default()
+    };
+  }
+  let values: Vec<Value> = matches
+    .values_of(name)
+    .map(|values| {
+      values
+        .map(|value| coerce_value(value, arg.value_type))
+        .collect()
+    })
+    .unwrap_or_default();
+  let value = if !arg.takes_value.unwrap_or(false) {
+    Value::Bool(true)
+  } else if arg.multiple.unwrap_or(false) {
+    Value::Array(values.clone())
+  } else {
+    values.first().cloned().unwrap_or(Value::Null)
+  };
+  ArgData {
+    value,
+    values,
+    occurrences,
+    indices: matches
+      .indices_of(name)
+      .map(Iterator::collect)
+      .unwrap_or_default(),
+    source: ArgSource::CommandLine,
+  }
+}
+
+/// Finds the alias the subcommand was invoked with on the command's own args,
+/// returning it if it isn't the subcommand name, and the subcommand's own args.
+/// clap only reports the subcommand name, so the args are scanned up to `--`, skipping the option values:
+/// the first other arg naming the subcommand is the one clap matched.
+fn used_alias<'a>(
+  config: &CliConfig,
+  name: &str,
+  aliases: Option<&Vec<String>>,
+  args: &'a [String],
+) -> (Option<String>, &'a [String]) {
+  let mut i = 0;
+  while i < args.len() && args[i] != "--" {
+    let arg = &args[i];
+    i += 1;
+    if let Some(long) = arg.strip_prefix("--") {
+      if !long.contains('=') {
+        i += long_option(config, long).map_or(0, separate_values);
+      }
+    } else if let Some(shorts) = arg.strip_prefix('-').filter(|shorts| !shorts.is_empty()) {
+      // `-abc` are flags up to the first option, whose value is the rest or the next arg
+      for (position, short) in shorts.char_indices() {
+        if let Some(option) = short_option(config, short).filter(|option| takes_value(option)) {
+          if position + short.len_utf8() == shorts.len() {
+            i += separate_values(option);
+          }
+          break;
+        }
+      }
+    } else if arg == name || aliases.map_or(false, |aliases| aliases.contains(arg)) {
+      let alias = Some(arg.clone()).filter(|arg| arg != name);
+      return (alias, &args[i..]);
+    }
+  }
+  (None, &[])
+}
+
+/// The option of the `--long` arg, by its name or its long aliases.
+fn long_option<'a>(config: &'a CliConfig, long: &str) -> Option<&'a CliArg> {
+  config.args()?.iter().find(|arg| {
+    arg.name == long
+      || arg.alias.as_deref() == Some(long)
+      || arg
+        .aliases
+        .iter()
+        .chain(&arg.visible_aliases)
+        .flatten()
+        .any(|alias| alias == long)
+  })
+}
+
+/// The option of the `-s` arg, by its short name or its short alias.
+fn short_option(config: &CliConfig, short: char) -> Option<&CliArg> {
+  config
+    .args()?
+    .iter()
+    .find(|arg| arg.short == Some(short) || arg.short_alias == Some(short))
+}
+
+fn takes_value(arg: &CliArg) -> bool {
+  arg.takes_value.unwrap_or(false)
+}
+
+/// The number of values of the option passed as the next args, rather than as `--option=value`.
+fn separate_values(arg: &CliArg) -> usize {
+  if takes_value(arg) && !arg.require_equals.unwrap_or(false) {
+    arg.number_of_values.unwrap_or(1) as usize
+  } else {
+    0
+  }
+}
+
+/// The names of the group args, listed on the group or referencing it with their `group` option.
+fn group_args<'a>(config: &'a CliConfig, group: &'a CliGroup) -> Vec<&'a str> {
+  let mut args: Vec<&str> = group
+    .args
+    .iter()
+    .flatten()
+    .map(|arg| arg.as_str())
+    .collect();
+  for arg in config.args().into_iter().flatten() {
+    if arg.group.as_ref() == Some(&group.name) && !args.contains(&arg.name.as_str()) {
+      args.push(&arg.name);
+    }
+  }
+  args
+}
+
 fn get_app<'a>(name: &str, about: Option<&'a String>, config: &'a CliConfig) -> App<'a> {
   let mut app = App::new(name)
     .author(crate_authors!())
@@ -163,9 +419,33 @@ fn get_app<'a>(name: &str, about: Option<&'a String>, config: &'a CliConfig) ->
     }
   }
 
+  if let Some(groups) = config.groups() {
+    for group in groups {
+      let clap_group = ArgGroup::new(group.name.as_str())
+        .args(&group_args(config, group))
+        .required(group.required.unwrap_or(false))
+        .multiple(group.multiple.unwrap_or(false));
+      app = app.group(clap_group);
+    }
+  }
+
+  if config.subcommand_required() {
+    app = app.setting(AppSettings::SubcommandRequired);
+  }
+  if config.args_conflicts_with_subcommands() {
+    app = app.setting(AppSettings::ArgsNegateSubcommands);
+  }
+  if config.allow_external_subcommands() {
+    app = app.setting(AppSettings::AllowExternalSubcommands);
+  }
+
   if let Some(subcommands) = config.subcommands() {
     for (subcommand_name, subcommand) in subcommands {
-      let clap_subcommand = get_app(&subcommand_name, subcommand.description(), subcommand);
+      let mut clap_subcommand = get_app(&subcommand_name, subcommand.description(), subcommand);
+      if let Some(aliases) = subcommand.aliases() {
+        let aliases: Vec<&str> = aliases.iter().map(|alias| alias.as_str()).collect();
+        clap_subcommand = clap_subcommand.aliases(&aliases);
+      }
       app = app.subcommand(clap_subcommand);
     }
   }
@@ -174,10 +454,13 @@ fn get_app<'a>(name: &str, about: Option<&'a String>, config: &'a CliConfig) ->
 }
 
 fn get_arg<'a>(arg_name: &'a str, arg: &'a CliArg) -> Arg<'a> {
-  let mut clap_arg = Arg::new(arg_name).long(arg_name);
-
-  if let Some(short) = arg.short {
-    clap_arg = clap_arg.short(short);
+  let mut clap_arg = Arg::new(arg_name);
+  // the positional args can't have a long nor a short name
+  if arg.index.is_none() {
+    clap_arg = clap_arg.long(arg_name);
+    if let Some(short) = arg.short {
+      clap_arg = clap_arg.short(short);
+    }
   }
 
   clap_arg = bind_string_arg!(arg, clap_arg, description, about);
@@ -204,8 +487,233 @@ fn get_arg<'a>(arg_name: &'a str, arg: &'a CliArg) -> Arg<'a> {
   clap_arg = bind_string_slice_arg!(arg, clap_arg, requires_all);
   clap_arg = bind_if_arg!(arg, clap_arg, requires_if);
   clap_arg = bind_if_arg!(arg, clap_arg, required_if_eq);
+  clap_arg = bind_if_pairs_arg!(arg, clap_arg, required_if_eq_any);
+  // `required_if_eq_all` is checked by `check_required_if_eq_all`
   clap_arg = bind_value_arg!(arg, clap_arg, require_equals);
   clap_arg = bind_value_arg!(arg, clap_arg, index);
+  clap_arg = bind_string_arg!(arg, clap_arg, alias, alias);
+  clap_arg = bind_string_slice_arg!(arg, clap_arg, aliases);
+  clap_arg = bind_value_arg!(arg, clap_arg, short_alias);
+  clap_arg = bind_string_slice_arg!(arg, clap_arg, visible_aliases);
+  clap_arg = bind_value_arg!(arg, clap_arg, display_order);
+
+  if let Some(value_type) = arg.value_type {
+    clap_arg = clap_arg.validator(move |value| validate_value(value, value_type));
+  }
 
   clap_arg
 }
+
+/// Validates a value against the arg value type.
+fn validate_value(value: &str, value_type: CliArgValueType) -> Result<(), String> {
+  match value_type {
+    CliArgValueType::String => Ok(()),
+    CliArgValueType::Number => parse_number(value)
+      .map(|_| ())
+      .ok_or_else(|| format!("`{}` is not a number", value)),
+    CliArgValueType::Boolean => value
+      .parse::<bool>()
+      .map(|_| ())
+      .map_err(|_| format!("`{}` is not a boolean; use `true` or `false`", value)),
+    CliArgValueType::Path if value.is_empty() => Err("the path can't be empty".to_string()),
+    CliArgValueType::Path => Ok(()),
+    CliArgValueType::Url => reqwest::Url::parse(value)
+      .map(|_| ())
+      .map_err(|e| format!("`{}` is not a valid URL: {}", value, e)),
+  }
+}
+
+/// Parses an integer or a finite floating point number.
+fn parse_number(value: &str) -> Option<Number> {
+  value
+    .parse::<i64>()
+    .map(Number::from)
+    .or_else(|_| value.parse::<u64>().map(Number::from))
+    .ok()
+    .or_else(|| value.parse::<f64>().ok().and_then(Number::from_f64))
+}
+
+/// Converts a validated value to the JSON type of the arg value type.
+fn coerce_value(value: &str, value_type: Option<CliArgValueType>) -> Value {
+  match value_type {
+    Some(CliArgValueType::Number) => parse_number(value).map(Value::Number),
+    Some(CliArgValueType::Boolean) => value.parse().ok().map(Value::Bool),
+    _ => None,
+  }
+  .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+  use super::{
+    check_required_if_eq_all, coerce_value, get_app, get_matches_internal, validate_value, Matches,
+  };
+  use crate::config::{CliArgValueType, CliConfig};
+  use serde_json::{json, Value};
+
+  fn parse(config: &CliConfig, args: &[&str]) -> Matches {
+    let app = get_app("app", None, config);
+    let matches = app.try_get_matches_from(args).unwrap();
+    let args: Vec<String> = args[1..].iter().map(ToString::to_string).collect();
+    get_matches_internal(config, &matches, &args)
+  }
+
+  #[test]
+  // values are validated and converted to the JSON type of the value type
+  fn typed_values() {
+    assert_eq!(coerce_value("42", Some(CliArgValueType::Number)), json!(42));
+    assert_eq!(
+      coerce_value("-1.5", Some(CliArgValueType::Number)),
+      json!(-1.5)
+    );
+    assert_eq!(
+      coerce_value("true", Some(CliArgValueType::Boolean)),
+      json!(true)
+    );
+    assert_eq!(coerce_value("42", None), Value::String("42".to_string()));
+
+    assert!(validate_value("4x", CliArgValueType::Number).is_err());
+    assert!(validate_value("NaN", CliArgValueType::Number).is_err());
+    assert!(validate_value("yes", CliArgValueType::Boolean).is_err());
+    assert!(validate_value("", CliArgValueType::Path).is_err());
+    assert!(validate_value("localhost", CliArgValueType::Url).is_err());
+    assert!(validate_value("https://tauri.studio", CliArgValueType::Url).is_ok());
+  }
+
+  #[test]
+  fn repeated_options() {
+    let config: CliConfig = serde_json::from_value(json!({
+      "args": [
+        { "name": "verbose", "short": "v", "multipleOccurrences": true },
+        { "name": "port", "takesValue": true, "multiple": true, "valueType": "number" },
+        { "name": "release" }
+      ]
+    }))
+    .unwrap();
+    let matches = parse(&config, &["app", "--port", "1", "-v", "-v", "--port", "2"]);
+    let names: Vec<&String> = matches.args.keys().collect();
+    assert_eq!(names, vec!["port", "verbose", "release"]);
+    assert_eq!(
+      serde_json::to_value(&matches).unwrap(),
+      json!({
+        "args": {
+          "port": { "value": [1, 2], "values": [1, 2], "occurrences": 2, "indices": [2, 6], "source": "commandLine" },
+          "verbose": { "value": true, "values": [], "occurrences": 2, "indices": [3, 4], "source": "commandLine" },
+          "release": { "value": false, "values": [], "occurrences": 0, "indices": [], "source": "absent" }
+        },
+        "groups": {},
+        "subcommand": null
+      })
+    );
+    assert_eq!(
+      matches.to_legacy()["args"]["port"],
+      json!({ "value": [1, 2], "occurrences": 2 })
+    );
+  }
+
+  #[test]
+  fn nested_subcommands() {
+    let config: CliConfig = serde_json::from_value(json!({
+      "subcommands": {
+        "remote": {
+          "subcommands": {
+            "add": { "args": [{ "name": "name", "index": 1, "takesValue": true }] },
+            "list": {}
+          }
+        }
+      }
+    }))
+    .unwrap();
+    let matches =
+      serde_json::to_value(parse(&config, &["app", "remote", "add", "origin"])).unwrap();
+    assert_eq!(matches["subcommand"]["name"], "remote");
+    let add = &matches["subcommand"]["matches"]["subcommand"];
+    assert_eq!(add["name"], "add");
+    assert_eq!(add["matches"]["args"]["name"]["values"], json!(["origin"]));
+    assert_eq!(add["matches"]["args"]["name"]["source"], "commandLine");
+    assert_eq!(add["matches"]["subcommand"], Value::Null);
+
+    // invoked with no args
+    let matches = parse(&config, &["app", "remote", "list"]);
+    let list = matches.subcommand.unwrap().matches.subcommand.unwrap();
+    assert_eq!(list.name, "list");
+    assert!(list.matches.args.is_empty());
+  }
+
+  #[test]
+  // the alias is the subcommand arg clap matched, not an option value nor an arg of another command
+  fn subcommand_aliases() {
+    let config: CliConfig = serde_json::from_value(json!({
+      "args": [{ "name": "profile", "short": "p", "takesValue": true }],
+      "subcommands": {
+        "remove": {
+          "aliases": ["rm"],
+          "args": [{ "name": "target", "index": 1, "takesValue": true }]
+        },
+        "remote": {
+          "subcommands": { "remove": { "aliases": ["rm"] } }
+        }
+      }
+    }))
+    .unwrap();
+
+    let matches = parse(&config, &["app", "--profile", "rm", "rm", "origin"]);
+    let remove = matches.subcommand.unwrap();
+    assert_eq!(remove.name, "remove");
+    assert_eq!(remove.alias.as_deref(), Some("rm"));
+
+    // the aliases on the option values and on the subcommand args aren't the invocation
+    let cases: &[&[&str]] = &[
+      &["app", "-p", "rm", "remove", "rm"],
+      &["app", "-prm", "remove", "rm"],
+      &["app", "--profile=rm", "remove", "rm"],
+    ];
+    for args in cases {
+      let remove = parse(&config, args).subcommand.unwrap();
+      assert_eq!(remove.name, "remove");
+      assert_eq!(remove.alias, None);
+    }
+    let remove = parse(&config, &["app", "remove", "--", "rm"])
+      .subcommand
+      .unwrap();
+    assert_eq!(remove.alias, None);
+
+    // the nested subcommand alias is found after its parent
+    let remote = parse(&config, &["app", "remote", "rm"]).subcommand.unwrap();
+    assert_eq!(remote.alias, None);
+    let remove = remote.matches.subcommand.unwrap();
+    assert_eq!(remove.name, "remove");
+    assert_eq!(remove.alias.as_deref(), Some("rm"));
+  }
+
+  #[test]
+  // the arg is required when every condition is met
+  fn required_if_eq_all() {
+    let config: CliConfig = serde_json::from_value(json!({
+      "args": [
+        { "name": "format", "takesValue": true },
+        { "name": "target", "takesValue": true },
+        {
+          "name": "output",
+          "takesValue": true,
+          "requiredIfEqAll": [["format", "json"], ["target", "file"]]
+        }
+      ]
+    }))
+    .unwrap();
+    let check = |args: &[&str]| {
+      let matches = get_app("app", None, &config)
+        .try_get_matches_from(args)
+        .unwrap();
+      check_required_if_eq_all(&config, &matches)
+    };
+
+    assert!(check(&["app", "--format", "json", "--target", "stdout"]).is_ok());
+    assert!(check(&["app", "--format", "json"]).is_ok());
+    assert!(
+      check(&["app", "--format", "json", "--target", "file", "--output", "out.json"]).is_ok()
+    );
+    let error = check(&["app", "--format", "json", "--target", "file"]).unwrap_err();
+    assert_eq!(error.kind, clap::ErrorKind::MissingRequiredArgument);
+  }
+}
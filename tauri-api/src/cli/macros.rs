@@ -3,7 +3,7 @@ macro_rules! bind_string_arg {
     let arg = $arg;
     let mut clap_arg = $clap_arg;
     if let Some(value) = &arg.$arg_name {
-      clap_arg = clap_arg.$clap_field(value);
+      clap_arg = clap_arg.$clap_field(value.as_str());
     }
     clap_arg
   }};
@@ -43,3 +43,15 @@ macro_rules! bind_if_arg {
     clap_arg
   }};
 }
+
+macro_rules! bind_if_pairs_arg {
+  ($arg:expr, $clap_arg:expr, $field:ident) => {{
+    let arg = $arg;
+    let mut clap_arg = $clap_arg;
+    if let Some(value) = &arg.$field {
+      let v: Vec<(&str, &str)> = value.iter().map(|(a, b)| (&**a, &**b)).collect();
+      clap_arg = clap_arg.$field(&v);
+    }
+    clap_arg
+  }};
+}
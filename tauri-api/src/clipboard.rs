@@ -0,0 +1,28 @@
+use arboard::Clipboard;
+
+/// Reads the clipboard text. Resolves to `None` if the clipboard doesn't have text.
+///
+/// # Example
+/// ```no_run
+/// if let Some(text) = tauri_api::clipboard::read_text().unwrap() {
+///   println!("copied: {}", text);
+/// }
+/// ```
+pub fn read_text() -> crate::Result<Option<String>> {
+  match Clipboard::new()?.get_text() {
+    Ok(text) => Ok(Some(text)),
+    Err(arboard::Error::ContentNotAvailable) => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Writes the text to the clipboard, replacing its content.
+///
+/// # Example
+/// ```no_run
+/// tauri_api::clipboard::write_text("Hello from Tauri").unwrap();
+/// ```
+pub fn write_text(text: impl Into<String>) -> crate::Result<()> {
+  Clipboard::new()?.set_text(text.into())?;
+  Ok(())
+}
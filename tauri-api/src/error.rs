@@ -31,6 +31,15 @@ pub enum Error {
   /// Failed to serialize header value as string.
   #[error("failed to convert response header value to string")]
   HttpHeaderToString(#[from] reqwest::header::ToStrError),
+  /// The URL or a redirect URL isn't on the `tauri > allowlist > http > scope`.
+  #[error("the URL `{0}` isn't on the http scope (https://tauri.studio/docs/api/config#tauri.allowlist.http.scope)")]
+  HttpUrlNotInScope(String),
+  /// The method isn't on the `tauri > allowlist > http > defaults > allowedMethods`.
+  #[error("the `{0}` method isn't allowed (https://tauri.studio/docs/api/config#tauri.allowlist.http.defaults)")]
+  HttpMethodNotAllowed(String),
+  /// The request exceeded a `tauri > allowlist > http > defaults` limit: the limit name.
+  #[error("the request exceeded the `{0}` limit (https://tauri.studio/docs/api/config#tauri.allowlist.http.defaults)")]
+  HttpLimitExceeded(String),
   /// HTTP form to must be an object.
   #[error("http form must be an object")]
   InvalidHttpForm,
@@ -50,6 +59,9 @@ pub enum Error {
   #[cfg(feature = "notification")]
   #[error("{0}")]
   Notification(#[from] notify_rust::error::Error),
+  /// The executable is a symlink, so it isn't relaunched.
+  #[error("the executable {0} is a symlink")]
+  SymlinkedExecutable(std::path::PathBuf),
   /// failed to detect the current platform.
   #[error("failed to detect platform: {0}")]
   FailedToDetectPlatform(String),
@@ -61,4 +73,12 @@ pub enum Error {
   #[cfg(feature = "global-shortcut")]
   #[error("shortcut error: {0}")]
   Shortcut(#[from] tauri_hotkey::Error),
+  /// Clipboard error.
+  #[cfg(feature = "clipboard")]
+  #[error("clipboard error: {0}")]
+  Clipboard(#[from] arboard::Error),
+  /// The wake lock couldn't be acquired.
+  #[cfg(feature = "power")]
+  #[error("wake lock error: {0}")]
+  WakeLock(String),
 }
@@ -1,10 +1,101 @@
 use bytes::Bytes;
-use reqwest::{header::HeaderName, redirect::Policy, Method};
+use reqwest::{header::HeaderName, redirect::Policy, Method, Url};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+use crate::config::{url_pattern_match, HttpAllowlistConfig};
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+
+/// The redirects a request follows if neither the config nor the client limit them.
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// The limits of the requests, from the `tauri > allowlist > http` config.
+/// The client and request options can only lower them.
+#[derive(Debug, Clone, Default)]
+pub struct RequestLimits {
+  /// The URLs the requests and their redirects can reach, or `None` for any URL.
+  pub scope: Option<Vec<String>>,
+  /// The time a request waits for the connection.
+  pub connect_timeout: Option<Duration>,
+  /// The time a request waits for the whole response.
+  pub request_timeout: Option<Duration>,
+  /// The maximum number of redirects a request follows.
+  pub max_redirects: Option<usize>,
+  /// The methods of the requests, or `None` for any method.
+  pub allowed_methods: Option<Vec<String>>,
+}
+
+impl RequestLimits {
+  /// The limits of the `tauri > allowlist > http` config.
+  pub fn from_config(config: &HttpAllowlistConfig) -> Self {
+    let defaults = &config.defaults;
+    Self {
+      scope: config.scope.clone(),
+      connect_timeout: defaults.connect_timeout_ms.map(Duration::from_millis),
+      request_timeout: defaults.request_timeout_ms.map(Duration::from_millis),
+      max_redirects: defaults.max_redirects,
+      allowed_methods: defaults.allowed_methods.clone(),
+    }
+  }
+
+  fn allows_url(&self, url: &Url) -> bool {
+    match &self.scope {
+      Some(scope) => scope
+        .iter()
+        .any(|pattern| url_pattern_match(pattern, url.as_str())),
+      None => true,
+    }
+  }
+
+  fn allows_method(&self, method: &str) -> bool {
+    match &self.allowed_methods {
+      Some(methods) => methods
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(method)),
+      None => true,
+    }
+  }
+}
+
+/// The value of an option capped by its limit.
+fn capped<T: Ord>(value: Option<T>, limit: Option<T>) -> Option<T> {
+  match (value, limit) {
+    (Some(value), Some(limit)) => Some(value.min(limit)),
+    (value, limit) => value.or(limit),
+  }
+}
+
+/// The redirect policy failures, read back from the request error.
+#[derive(Debug, thiserror::Error)]
+enum RedirectError {
+  #[error("the redirect URL `{0}` isn't on the http scope")]
+  NotInScope(String),
+  #[error("the request exceeded the {0} redirects limit")]
+  TooManyRedirects(usize),
+}
+
+/// Maps the timeouts and the redirect policy failures to their own errors.
+fn request_error(error: reqwest::Error) -> crate::Error {
+  if error.is_timeout() {
+    let limit = if error.is_connect() {
+      "connectTimeoutMs"
+    } else {
+      "requestTimeoutMs"
+    };
+    return crate::Error::HttpLimitExceeded(limit.to_string());
+  }
+  let redirect_error =
+    std::error::Error::source(&error).and_then(|source| source.downcast_ref::<RedirectError>());
+  match redirect_error {
+    Some(RedirectError::NotInScope(url)) => crate::Error::HttpUrlNotInScope(url.clone()),
+    Some(RedirectError::TooManyRedirects(_)) => {
+      crate::Error::HttpLimitExceeded("maxRedirects".to_string())
+    }
+    None => error.into(),
+  }
+}
 
 /// Client builder.
 #[derive(Default, Deserialize)]
@@ -36,33 +127,70 @@ impl ClientBuilder {
 
   /// Builds the ClientOptions.
   pub fn build(self) -> crate::Result<Client> {
-    let mut client_builder = reqwest::Client::builder();
+    self.build_with_limits(RequestLimits::default())
+  }
 
-    if let Some(max_redirections) = self.max_redirections {
-      client_builder = client_builder.redirect(Policy::limited(max_redirections))
-    }
+  /// Builds the client, capping its options with the limits, which also apply to its requests.
+  /// The redirects are checked against the limits scope.
+  pub fn build_with_limits(self, limits: RequestLimits) -> crate::Result<Client> {
+    let limits = Arc::new(limits);
+    let mut client_builder = reqwest::Client::builder();
 
-    if let Some(connect_timeout) = self.connect_timeout {
-      client_builder = client_builder.connect_timeout(Duration::from_secs(connect_timeout));
+    let max_redirects =
+      capped(self.max_redirections, limits.max_redirects).unwrap_or(DEFAULT_MAX_REDIRECTS);
+    let policy_limits = limits.clone();
+    client_builder = client_builder.redirect(Policy::custom(move |attempt| {
+      if attempt.previous().len() > max_redirects {
+        attempt.error(RedirectError::TooManyRedirects(max_redirects))
+      } else if !policy_limits.allows_url(attempt.url()) {
+        let url = attempt.url().to_string();
+        attempt.error(RedirectError::NotInScope(url))
+      } else {
+        attempt.follow()
+      }
+    }));
+
+    let connect_timeout = capped(
+      self.connect_timeout.map(Duration::from_secs),
+      limits.connect_timeout,
+    );
+    if let Some(connect_timeout) = connect_timeout {
+      client_builder = client_builder.connect_timeout(connect_timeout);
     }
 
     let client = client_builder.build()?;
-    Ok(Client(client))
+    Ok(Client { client, limits })
   }
 }
 
 /// The HTTP client.
 #[derive(Clone)]
-pub struct Client(reqwest::Client);
+pub struct Client {
+  client: reqwest::Client,
+  limits: Arc<RequestLimits>,
+}
 
 impl Client {
   /// Executes an HTTP request
   ///
   /// The response will be transformed to String,
   /// If reading the response as binary, the byte array will be serialized using serde_json
+  ///
+  /// * Errors:
+  ///     * HttpMethodNotAllowed - The method isn't on the allowed methods of the limits
+  ///     * HttpUrlNotInScope - The URL or a redirect URL isn't on the scope of the limits
+  ///     * HttpLimitExceeded - The request timed out or exceeded the redirects limit
   pub async fn send(&self, request: HttpRequestBuilder) -> crate::Result<Response> {
+    if !self.limits.allows_method(&request.method) {
+      return Err(crate::Error::HttpMethodNotAllowed(request.method));
+    }
     let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
-    let mut request_builder = self.0.request(method, &request.url);
+    let mut request_builder = match Url::parse(&request.url) {
+      Ok(url) if self.limits.allows_url(&url) => self.client.request(method, url),
+      // reqwest reports the invalid URL
+      Err(_) if self.limits.scope.is_none() => self.client.request(method, &request.url),
+      _ => return Err(crate::Error::HttpUrlNotInScope(request.url)),
+    };
 
     if let Some(query) = request.query {
       request_builder = request_builder.query(&query);
@@ -75,15 +203,19 @@ impl Client {
       }
     }
 
-    if let Some(timeout) = request.timeout {
-      request_builder = request_builder.timeout(Duration::from_secs(timeout));
+    let timeout = capped(
+      request.timeout.map(Duration::from_secs),
+      self.limits.request_timeout,
+    );
+    if let Some(timeout) = timeout {
+      request_builder = request_builder.timeout(timeout);
     }
 
-    let response = if let Some(body) = request.body {
-      match body {
-        Body::Bytes(data) => request_builder.body(Bytes::from(data)).send().await?,
-        Body::Text(text) => request_builder.body(Bytes::from(text)).send().await?,
-        Body::Json(json) => request_builder.json(&json).send().await?,
+    if let Some(body) = request.body {
+      request_builder = match body {
+        Body::Bytes(data) => request_builder.body(Bytes::from(data)),
+        Body::Text(text) => request_builder.body(Bytes::from(text)),
+        Body::Json(json) => request_builder.json(&json),
         Body::Form(form_body) => {
           let mut form = Vec::new();
           for (name, part) in form_body.0 {
@@ -93,12 +225,11 @@ impl Client {
               FormPart::Text(text) => form.push((name, text)),
             }
           }
-          request_builder.form(&form).send().await?
+          request_builder.form(&form)
         }
-      }
-    } else {
-      request_builder.send().await?
-    };
+      };
+    }
+    let response = request_builder.send().await.map_err(request_error)?;
 
     let response = response.error_for_status()?;
     Ok(Response(
@@ -278,3 +409,36 @@ pub struct ResponseData {
   headers: HashMap<String, String>,
   data: Value,
 }
+
+#[cfg(test)]
+mod test {
+  use super::{capped, RequestLimits};
+  use crate::config::HttpAllowlistConfig;
+  use reqwest::Url;
+
+  #[test]
+  fn request_limits() {
+    assert_eq!(capped(Some(30), Some(10)), Some(10));
+    assert_eq!(capped(Some(5), Some(10)), Some(5));
+    assert_eq!(capped(None, Some(10)), Some(10));
+    assert_eq!(capped(Some(30), None), Some(30));
+
+    let config: HttpAllowlistConfig = serde_json::from_value(serde_json::json!({
+      "scope": ["https://api.example.com/*"],
+      "defaults": { "allowedMethods": ["GET", "post"] }
+    }))
+    .unwrap();
+    let limits = RequestLimits::from_config(&config);
+    assert!(limits.allows_url(&Url::parse("https://api.example.com").unwrap()));
+    assert!(!limits.allows_url(&Url::parse("https://api.example.com.evil.com/").unwrap()));
+    // the `*` can't match across the host and the path
+    assert!(!limits.allows_url(&Url::parse("https://evil.com/api.example.com/x").unwrap()));
+    assert!(!limits.allows_url(&Url::parse("https://api.example.com@evil.com/x").unwrap()));
+    assert!(limits.allows_method("POST"));
+    assert!(!limits.allows_method("DELETE"));
+
+    let limits = RequestLimits::default();
+    assert!(limits.allows_url(&Url::parse("http://localhost:8080/").unwrap()));
+    assert!(limits.allows_method("DELETE"));
+  }
+}
@@ -13,6 +13,8 @@ pub mod file;
 pub mod http;
 /// The file system path operations API.
 pub mod path;
+/// The process API module allows you to relaunch and exit the app.
+pub mod process;
 /// The RPC module includes utilities to send messages to the JS layer of the webview.
 pub mod rpc;
 /// TCP ports access API.
@@ -34,6 +36,14 @@ extern crate clap;
 #[cfg(feature = "global-shortcut")]
 pub mod shortcuts;
 
+/// The clipboard API module.
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+
+/// The idle time and wake lock API module.
+#[cfg(feature = "power")]
+pub mod power;
+
 /// The desktop notifications API module.
 #[cfg(feature = "notification")]
 pub mod notification;
@@ -55,5 +65,6 @@ pub mod private {
     fn raw_config() -> &'static str;
     fn assets() -> &'static crate::assets::Assets;
     fn raw_tauri_script() -> &'static str;
+    fn raw_resource_manifest() -> Option<&'static str>;
   }
 }
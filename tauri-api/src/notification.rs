@@ -54,7 +54,16 @@ impl Notification {
   }
 
   /// Shows the notification.
+  ///
+  /// On macOS, notifications are only delivered to bundled apps,
+  /// so an unbundled binary (e.g. in development) shows them with `osascript` instead, attributed to Script Editor.
   pub fn show(self) -> crate::Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+      if !is_bundled() {
+        return self.show_with_osascript();
+      }
+    }
     let mut notification = notify_rust::Notification::new();
     if let Some(body) = self.body {
       notification.body(&body);
@@ -80,4 +89,54 @@ impl Notification {
     notification.show()?;
     Ok(())
   }
+
+  #[cfg(target_os = "macos")]
+  fn show_with_osascript(self) -> crate::Result<()> {
+    let script = format!(
+      "display notification {} with title {}",
+      applescript_string(self.body.as_deref().unwrap_or_default()),
+      applescript_string(self.title.as_deref().unwrap_or_default())
+    );
+    let status = std::process::Command::new("osascript")
+      .arg("-e")
+      .arg(script)
+      .status()?;
+    if status.success() {
+      Ok(())
+    } else {
+      Err(crate::Error::Command(format!(
+        "osascript failed to show the notification: {}",
+        status
+      )))
+    }
+  }
+}
+
+// whether the current executable runs from an app bundle, i.e. `<name>.app/Contents/MacOS/<exe>`
+#[cfg(target_os = "macos")]
+fn is_bundled() -> bool {
+  std::env::current_exe()
+    .ok()
+    .and_then(|exe| exe.parent().map(|dir| dir.ends_with("Contents/MacOS")))
+    .unwrap_or(false)
+}
+
+// quotes a string as an AppleScript string literal
+#[cfg(any(target_os = "macos", test))]
+fn applescript_string(value: &str) -> String {
+  format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+  use super::applescript_string;
+
+  #[test]
+  fn quotes_applescript_strings() {
+    assert_eq!(applescript_string("New message"), "\"New message\"");
+    assert_eq!(
+      applescript_string(r#"say "hi" \ bye"#),
+      r#""say \"hi\" \\ bye""#
+    );
+  }
 }
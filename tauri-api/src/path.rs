@@ -1,7 +1,23 @@
-use std::path::{Path, PathBuf};
+use std::{
+  collections::HashSet,
+  fs,
+  io::Write,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
 
+use once_cell::sync::{Lazy, OnceCell};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use crate::{config::PathsConfig, resources::ResourceManifest};
+
+/// The manifest of the bundled resources, embedded at build time.
+static RESOURCE_MANIFEST: OnceCell<ResourceManifest> = OnceCell::new();
+/// The app directories, resolved from `tauri > paths` when the app starts.
+static APP_DIRS: OnceCell<AppDirs> = OnceCell::new();
+/// The resources decompressed to the cache directory on this run.
+static MATERIALIZED_RESOURCES: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
 /// A Base Directory to use.
 /// The base directory is the optional root of a FS operation.
 /// If informed by the API call, all paths will be relative to the path of the given directory.
@@ -47,6 +63,12 @@ pub enum BaseDirectory {
   /// The default App config directory.
   /// Resolves to ${CONFIG_DIR}/${APP_NAME}
   App,
+  /// The app data directory, see [`AppDirs`].
+  AppData,
+  /// The app cache directory, see [`AppDirs`].
+  AppCache,
+  /// The app log directory, see [`AppDirs`].
+  AppLog,
 }
 
 /// Resolves the path with the optional base directory.
@@ -77,8 +99,11 @@ pub fn resolve_path<P: AsRef<Path>>(path: P, dir: Option<BaseDirectory>) -> crat
       BaseDirectory::Runtime => runtime_dir(),
       BaseDirectory::Template => template_dir(),
       BaseDirectory::Video => video_dir(),
-      BaseDirectory::Resource => resource_dir(),
+      BaseDirectory::Resource => return resolve_resource(path),
       BaseDirectory::App => app_dir(),
+      BaseDirectory::AppData => Some(app_data_dir()?),
+      BaseDirectory::AppCache => Some(app_cache_dir()?),
+      BaseDirectory::AppLog => Some(app_log_dir()?),
     };
     if let Some(mut base_dir_path_value) = base_dir_path {
       base_dir_path_value.push(path);
@@ -95,6 +120,47 @@ pub fn resolve_path<P: AsRef<Path>>(path: P, dir: Option<BaseDirectory>) -> crat
   }
 }
 
+/// Expands the variable the path starts with, e.g. `$LOCALAPPDATA/my-app`.
+///
+/// The `$APPDATA`, `$LOCALAPPDATA`, `$CONFIG`, `$CACHE`, `$HOME` and `$APP` variables resolve to the user directories
+/// on every platform; the other variables are read from the environment.
+/// Relative paths are resolved from the executable directory, so portable installs keep their data next to the app.
+pub fn expand_variables<P: AsRef<Path>>(path: P) -> crate::Result<PathBuf> {
+  let path = path.as_ref();
+  let mut components = path.components();
+  let variable = components
+    .next()
+    .and_then(|component| component.as_os_str().to_str())
+    .and_then(|component| component.strip_prefix('$'));
+  let variable = match variable {
+    Some(variable) => variable,
+    None if path.is_absolute() => return Ok(path.to_path_buf()),
+    None => {
+      let exe = std::env::current_exe()?;
+      let exe_dir = exe
+        .parent()
+        .ok_or_else(|| crate::Error::Path("unable to determine the executable directory".into()))?;
+      return Ok(exe_dir.join(path));
+    }
+  };
+  let dir = match variable {
+    "APPDATA" => data_dir(),
+    "LOCALAPPDATA" => local_data_dir(),
+    "CONFIG" => config_dir(),
+    "CACHE" => cache_dir(),
+    "HOME" => home_dir(),
+    "APP" => app_dir(),
+    _ => std::env::var_os(variable).map(PathBuf::from),
+  };
+  match dir {
+    Some(dir) => Ok(dir.join(components.as_path())),
+    None => Err(crate::Error::Path(format!(
+      "unable to resolve the `${}` variable",
+      variable
+    ))),
+  }
+}
+
 /// Returns the path to the user's audio directory.
 pub fn audio_dir() -> Option<PathBuf> {
   dirs_next::audio_dir()
@@ -180,6 +246,79 @@ pub fn resource_dir() -> Option<PathBuf> {
   crate::platform::resource_dir().ok()
 }
 
+/// Sets the manifest of the bundled resources, embedded at build time.
+#[doc(hidden)]
+pub fn set_resource_manifest(manifest: ResourceManifest) {
+  let _ = RESOURCE_MANIFEST.set(manifest);
+}
+
+/// Resolves the path of a resource.
+///
+/// The resources compressed with `tauri > bundle > resourcesCompression` are decompressed to the cache directory
+/// the first time they're resolved, and checked against the hash embedded at build time,
+/// so the returned path can be read like any other file.
+pub fn resolve_resource<P: AsRef<Path>>(path: P) -> crate::Result<PathBuf> {
+  let path = path.as_ref();
+  let resource_dir =
+    resource_dir().ok_or_else(|| crate::Error::Path("unable to determine base dir path".into()))?;
+  let manifest = match RESOURCE_MANIFEST.get() {
+    Some(manifest) if manifest.compression.extension().is_some() && manifest.contains(path) => {
+      manifest
+    }
+    _ => return Ok(resource_dir.join(path)),
+  };
+  let cache_dir = app_cache_dir()
+    .ok()
+    .or_else(|| {
+      cache_dir()
+        .zip(app_name().ok())
+        .map(|(dir, app_name)| dir.join(app_name))
+    })
+    .map(|dir| dir.join("resources"))
+    .ok_or_else(|| crate::Error::Path("unable to determine the cache directory".into()))?;
+  let materialized = cache_dir.join(path);
+
+  let mut materialized_resources = MATERIALIZED_RESOURCES.lock().unwrap();
+  if materialized_resources.contains(path) {
+    return Ok(materialized);
+  }
+  // the file decompressed on a previous run is reused if it still matches, e.g. it's from the same app version
+  let up_to_date = fs::read(&materialized)
+    .map(|content| manifest.matches(path, &content))
+    .unwrap_or(false);
+  if !up_to_date {
+    let content = manifest.read(&resource_dir, path)?;
+    if !manifest.matches(path, &content) {
+      return Err(crate::Error::Path(format!(
+        "the `{}` resource doesn't match the bundled one",
+        path.display()
+      )));
+    }
+    let dir = materialized
+      .parent()
+      .ok_or_else(|| crate::Error::Path("invalid resource path".into()))?;
+    fs::create_dir_all(dir)?;
+    // written to a temporary file first, so an interrupted write isn't mistaken for the resource
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(&content)?;
+    file.persist(&materialized).map_err(|e| e.error)?;
+  }
+  materialized_resources.insert(path.to_path_buf());
+  Ok(materialized)
+}
+
+/// Checks the bundled resources against the manifest embedded at build time,
+/// returning the paths of the missing and modified ones, or `None` if the app doesn't have a manifest.
+pub fn verify_resources() -> crate::Result<Option<Vec<String>>> {
+  let manifest = match RESOURCE_MANIFEST.get() {
+    Some(manifest) => manifest,
+    None => return Ok(None),
+  };
+  let resource_dir =
+    resource_dir().ok_or_else(|| crate::Error::Path("unable to determine base dir path".into()))?;
+  Ok(Some(manifest.verify(&resource_dir)))
+}
+
 fn app_name() -> crate::Result<String> {
   let exe = std::env::current_exe()?;
   let app_name = exe
@@ -201,3 +340,170 @@ pub fn app_dir() -> Option<PathBuf> {
     }
   })
 }
+
+/// The app data, cache and log directories, named after the bundle identifier unless `tauri > paths` overrides them,
+/// so they don't change with the product name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppDirs {
+  /// The app data directory, on the user data directory.
+  pub app_data_dir: PathBuf,
+  /// The app cache directory, on the user cache directory.
+  pub cache_dir: PathBuf,
+  /// The app log directory: on `~/Library/Logs` on macOS, the `logs` directory of the app local data directory otherwise.
+  pub log_dir: PathBuf,
+}
+
+impl AppDirs {
+  /// Resolves the directories of the app with the given bundle identifier, without creating them.
+  /// The executable name is used if the identifier isn't set.
+  pub fn resolve(config: &PathsConfig, identifier: &str) -> crate::Result<Self> {
+    let default_name = if identifier.is_empty() {
+      app_name()?
+    } else {
+      identifier.to_string()
+    };
+    let name = |name: &Option<String>| name.clone().unwrap_or_else(|| default_name.clone());
+    Self::with_names(
+      &name(&config.app_data_dir_name),
+      &name(&config.cache_dir_name),
+      &name(&config.log_dir_name),
+    )
+  }
+
+  fn with_names(
+    app_data_dir_name: &str,
+    cache_dir_name: &str,
+    log_dir_name: &str,
+  ) -> crate::Result<Self> {
+    let missing =
+      |dir: &str| crate::Error::Path(format!("unable to determine the {} directory", dir));
+    Ok(Self {
+      app_data_dir: data_dir()
+        .ok_or_else(|| missing("data"))?
+        .join(app_data_dir_name),
+      cache_dir: cache_dir()
+        .ok_or_else(|| missing("cache"))?
+        .join(cache_dir_name),
+      log_dir: log_dir_named(log_dir_name).ok_or_else(|| missing("log"))?,
+    })
+  }
+
+  /// Moves the directories of the `migrateFrom` identifiers to the current directories that don't exist yet,
+  /// so changing the identifier keeps the user data.
+  pub fn migrate(&self, config: &PathsConfig) -> crate::Result<()> {
+    for identifier in &config.migrate_from {
+      let old = Self::with_names(identifier, identifier, identifier)?;
+      let dirs = [
+        (&old.app_data_dir, &self.app_data_dir),
+        (&old.cache_dir, &self.cache_dir),
+        (&old.log_dir, &self.log_dir),
+      ];
+      for (old_dir, new_dir) in dirs.iter() {
+        if old_dir.is_dir() && !new_dir.exists() {
+          if let Some(parent) = new_dir.parent() {
+            fs::create_dir_all(parent)?;
+          }
+          fs::rename(old_dir, new_dir)?;
+        }
+      }
+    }
+    Ok(())
+  }
+}
+
+/// The log directory with the given name.
+fn log_dir_named(name: &str) -> Option<PathBuf> {
+  if cfg!(target_os = "macos") {
+    home_dir().map(|dir| dir.join("Library").join("Logs").join(name))
+  } else {
+    local_data_dir().map(|dir| dir.join(name).join("logs"))
+  }
+}
+
+/// Sets the app directories, resolved when the app starts.
+#[doc(hidden)]
+pub fn set_app_dirs(dirs: AppDirs) {
+  let _ = APP_DIRS.set(dirs);
+}
+
+/// Returns the app directories, or `None` before the app starts.
+pub fn app_dirs() -> Option<&'static AppDirs> {
+  APP_DIRS.get()
+}
+
+/// Returns the path to the app data directory, creating it on first use.
+pub fn app_data_dir() -> crate::Result<PathBuf> {
+  created_app_dir(|dirs| &dirs.app_data_dir)
+}
+
+/// Returns the path to the app cache directory, creating it on first use.
+pub fn app_cache_dir() -> crate::Result<PathBuf> {
+  created_app_dir(|dirs| &dirs.cache_dir)
+}
+
+/// Returns the path to the app log directory, creating it on first use.
+pub fn app_log_dir() -> crate::Result<PathBuf> {
+  created_app_dir(|dirs| &dirs.log_dir)
+}
+
+/// Creates the app directory if it doesn't exist, only accessible by the current user on Unix.
+fn created_app_dir(dir: impl Fn(&AppDirs) -> &PathBuf) -> crate::Result<PathBuf> {
+  let dirs = APP_DIRS
+    .get()
+    .ok_or_else(|| crate::Error::Path("the app directories aren't resolved yet".into()))?;
+  let dir = dir(dirs);
+  if !dir.is_dir() {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)?;
+  }
+  Ok(dir.clone())
+}
+
+#[cfg(test)]
+mod test {
+  use super::{expand_variables, AppDirs};
+  use crate::config::PathsConfig;
+  use std::path::PathBuf;
+
+  #[test]
+  fn expands_variables() {
+    std::env::set_var("TAURI_TEST_DATA_DIR", "/var/lib/my-app");
+    assert_eq!(
+      expand_variables("$TAURI_TEST_DATA_DIR/webview").unwrap(),
+      PathBuf::from("/var/lib/my-app/webview")
+    );
+    assert_eq!(
+      expand_variables("$HOME/.my-app").unwrap(),
+      super::home_dir().unwrap().join(".my-app")
+    );
+    assert!(expand_variables("$TAURI_TEST_UNSET_DIR/webview").is_err());
+
+    let exe_dir = std::env::current_exe()
+      .unwrap()
+      .parent()
+      .unwrap()
+      .to_path_buf();
+    assert_eq!(expand_variables("data").unwrap(), exe_dir.join("data"));
+  }
+
+  #[test]
+  fn app_dirs() {
+    let config = PathsConfig {
+      cache_dir_name: Some("my-app".into()),
+      ..Default::default()
+    };
+    let dirs = AppDirs::resolve(&config, "com.tauri.app").unwrap();
+    assert_eq!(
+      dirs.app_data_dir,
+      super::data_dir().unwrap().join("com.tauri.app")
+    );
+    assert_eq!(dirs.cache_dir, super::cache_dir().unwrap().join("my-app"));
+    assert!(dirs
+      .log_dir
+      .components()
+      .any(|component| component.as_os_str() == "com.tauri.app"));
+  }
+}
@@ -0,0 +1,45 @@
+use nosleep::{NoSleep, NoSleepType};
+
+use std::time::Duration;
+
+/// The time since the user last used the keyboard or the mouse.
+/// Resolves to `None` if the platform doesn't expose it, e.g. on Linux without the X11 screensaver extension.
+///
+/// # Example
+/// ```no_run
+/// if let Some(idle_time) = tauri_api::power::idle_time() {
+///   println!("idle for {}s", idle_time.as_secs());
+/// }
+/// ```
+pub fn idle_time() -> Option<Duration> {
+  user_idle::UserIdle::get_time()
+    .ok()
+    .map(|idle| Duration::from_secs(idle.as_seconds()))
+}
+
+/// Keeps the system and the display awake until it's dropped.
+///
+/// # Example
+/// ```no_run
+/// let wake_lock = tauri_api::power::WakeLock::acquire().unwrap();
+/// // the system doesn't sleep while the video plays
+/// drop(wake_lock);
+/// ```
+pub struct WakeLock(NoSleep);
+
+impl WakeLock {
+  /// Prevents the system and the display from sleeping.
+  pub fn acquire() -> crate::Result<Self> {
+    let mut no_sleep = NoSleep::new().map_err(|e| crate::Error::WakeLock(e.to_string()))?;
+    no_sleep
+      .start(NoSleepType::PreventUserIdleDisplaySleep)
+      .map_err(|e| crate::Error::WakeLock(e.to_string()))?;
+    Ok(Self(no_sleep))
+  }
+}
+
+impl Drop for WakeLock {
+  fn drop(&mut self) {
+    let _ = self.0.stop();
+  }
+}
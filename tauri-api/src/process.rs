@@ -0,0 +1,44 @@
+use std::{
+  env,
+  path::PathBuf,
+  process::{exit, Command},
+};
+
+/// Gets the path of the current binary, the one relaunched by [`restart`].
+///
+/// On Linux, it's the AppImage file when running from an AppImage, since its extracted binary is removed on exit.
+///
+/// On macOS, the executable path is rejected when it's a symlink, since the symlink could be replaced
+/// to relaunch a different binary, unless `allow_symlink_macos` is set.
+pub fn current_binary(allow_symlink_macos: bool) -> crate::Result<PathBuf> {
+  #[cfg(target_os = "linux")]
+  {
+    if let Some(app_image) = env::var_os("APPIMAGE") {
+      return Ok(PathBuf::from(app_image));
+    }
+  }
+
+  let exe = env::current_exe()?;
+  #[cfg(target_os = "macos")]
+  {
+    if !allow_symlink_macos && std::fs::symlink_metadata(&exe)?.file_type().is_symlink() {
+      return Err(crate::Error::SymlinkedExecutable(exe));
+    }
+  }
+  #[cfg(not(target_os = "macos"))]
+  let _ = allow_symlink_macos;
+  Ok(exe)
+}
+
+/// Restarts the app: runs the current binary with the original arguments and environment,
+/// then exits this process with `exit_code`.
+///
+/// # Example
+/// ```no_run
+/// tauri_api::process::restart(0, false).unwrap();
+/// ```
+pub fn restart(exit_code: i32, allow_symlink_macos: bool) -> crate::Result<()> {
+  let binary = current_binary(allow_symlink_macos)?;
+  Command::new(binary).args(env::args_os().skip(1)).spawn()?;
+  exit(exit_code)
+}
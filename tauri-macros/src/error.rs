@@ -11,6 +11,8 @@ pub(crate) enum Error {
   IncludeDirEmptyFilename,
   ConfigDir,
   Serde(PathBuf, serde_json::Error),
+  Toml(PathBuf, toml::de::Error),
+  Json5(PathBuf, json5::Error),
   Io(PathBuf, IoError),
 }
 
@@ -35,6 +37,16 @@ impl Error {
         error,
         path.display()
       ),
+      Toml(path, error) => format!(
+        "{} encountered for {} during tauri-macros",
+        error,
+        path.display()
+      ),
+      Json5(path, error) => format!(
+        "{} encountered for {} during tauri-macros",
+        error,
+        path.display()
+      ),
       Io(path, error) => format!(
         "{:?} encountered for {} during tauri-macros",
         error.kind(),
@@ -63,6 +75,10 @@ impl Error {
         fn raw_tauri_script() -> &'static str {
           unimplemented!()
         }
+
+        fn raw_resource_manifest() -> Option<&'static str> {
+          unimplemented!()
+        }
       }
     }
   }
@@ -1,15 +1,22 @@
-use crate::{error::Error, include_dir::IncludeDir, DEFAULT_CONFIG_FILE};
+use crate::{
+  error::Error,
+  include_dir::{fingerprint, AssetCache, IncludeDir},
+  DEFAULT_CONFIG_FILE, DEFAULT_TOML_CONFIG_FILE, PACKAGE_JSON_FILE, RC_CONFIG_FILE,
+};
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::{
   collections::HashSet,
   env::var,
-  fs::File,
-  io::BufReader,
+  ffi::OsStr,
+  fs::read_to_string,
   path::{Path, PathBuf},
 };
 use syn::{DeriveInput, Lit::Str, Meta::NameValue, MetaNameValue};
-use tauri_utils::{assets::AssetCompression, config::Config};
+use tauri_utils::{
+  assets::AssetCompression,
+  config::{Config, EmbeddedServerCompression},
+};
 
 pub(crate) fn load_context(input: DeriveInput) -> Result<TokenStream, Error> {
   let name = input.ident;
@@ -31,13 +38,25 @@ pub(crate) fn load_context(input: DeriveInput) -> Result<TokenStream, Error> {
     .map(PathBuf::from)
     .map_err(|_| Error::EnvCargoManifestDir)?;
 
-  let full_config_path = Path::new(&manifest).join(config_file_path);
-  let config = get_config(&full_config_path)?;
-  let config_dir = full_config_path.parent().ok_or(Error::ConfigDir)?;
+  let full_config_path = match config_path_attr {
+    Some(_) => Path::new(&manifest).join(config_file_path),
+    None => find_config(&manifest),
+  };
+  let (config, raw_config) = get_config(&full_config_path)?;
+  // the config paths are relative to the crate, even when the config comes from a parent `package.json`
+  let config_dir = if is_package_json(&full_config_path) {
+    manifest.as_path()
+  } else {
+    full_config_path.parent().ok_or(Error::ConfigDir)?
+  };
   let dist_dir = config_dir.join(config.build.dist_dir);
 
   // generate the assets into a perfect hash function
-  let assets = generate_asset_map(&dist_dir)?;
+  let assets = generate_asset_map(
+    &dist_dir,
+    config.tauri.embedded_server.compression,
+    &config.tauri.embedded_server.public_path,
+  )?;
 
   let tauri_script_path = dist_dir.join("__tauri.js");
 
@@ -45,15 +64,27 @@ pub(crate) fn load_context(input: DeriveInput) -> Result<TokenStream, Error> {
   let tauri_config_path = full_config_path.display().to_string();
   let tauri_script_path = tauri_script_path.display().to_string();
 
+  // the resource manifest written by the CLI, if the resources are compressed or verified
+  let raw_resource_manifest = match var("TAURI_RESOURCE_MANIFEST") {
+    Ok(path) if Path::new(&path).exists() => quote! {
+      match include_str!(#path) {
+        "null" => None,
+        manifest => Some(manifest),
+      }
+    },
+    _ => quote!(None),
+  };
+
   Ok(quote! {
       impl ::tauri::api::private::AsTauriContext for #name {
           fn config_path() -> &'static std::path::Path {
               std::path::Path::new(#tauri_config_path)
           }
 
-          /// Make the file a dependency for the compiler
           fn raw_config() -> &'static str {
-            include_str!(#tauri_config_path)
+            // make the file a dependency for the compiler
+            const _: &str = include_str!(#tauri_config_path);
+            #raw_config
           }
 
           fn assets() -> &'static ::tauri::api::assets::Assets {
@@ -66,29 +97,126 @@ pub(crate) fn load_context(input: DeriveInput) -> Result<TokenStream, Error> {
           fn raw_tauri_script() -> &'static str {
             include_str!(#tauri_script_path)
           }
+
+          fn raw_resource_manifest() -> Option<&'static str> {
+            #raw_resource_manifest
+          }
       }
   })
 }
 
-fn get_config(path: &Path) -> Result<Config, Error> {
+/// Finds the config file like the CLI does: `tauri.conf.json`, `tauri.conf.toml`,
+/// the nearest `package.json` if it has a `tauri` key, then `.taurirc.json`,
+/// then a config on a parent folder whose `build > srcTauriDir` is the crate, e.g. on a monorepo.
+fn find_config(manifest: &Path) -> PathBuf {
+  let package_json = manifest
+    .ancestors()
+    .map(|dir| dir.join(PACKAGE_JSON_FILE))
+    .find(|path| path.exists())
+    .filter(|path| {
+      read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .map_or(false, |package| package.get("tauri").is_some())
+    });
+  vec![
+    Some(manifest.join(DEFAULT_CONFIG_FILE)),
+    Some(manifest.join(DEFAULT_TOML_CONFIG_FILE)),
+    package_json,
+    Some(manifest.join(RC_CONFIG_FILE)),
+  ]
+  .into_iter()
+  .flatten()
+  .find(|path| path.exists())
+  .or_else(|| find_root_config(manifest))
+  .unwrap_or_else(|| manifest.join(DEFAULT_CONFIG_FILE))
+}
+
+/// Finds the config on a parent folder of the crate whose `build > srcTauriDir` resolves to it.
+fn find_root_config(manifest: &Path) -> Option<PathBuf> {
+  let manifest = manifest.canonicalize().ok()?;
+  manifest
+    .ancestors()
+    .skip(1)
+    .flat_map(|dir| {
+      vec![
+        dir.join(DEFAULT_CONFIG_FILE),
+        dir.join(DEFAULT_TOML_CONFIG_FILE),
+      ]
+    })
+    .find(|path| {
+      let src_tauri_dir = read_to_string(path).ok().and_then(|contents| {
+        let config: serde_json::Value = if path.extension() == Some(OsStr::new("toml")) {
+          serde_json::to_value(toml::from_str::<toml::Value>(&contents).ok()?).ok()?
+        } else {
+          json5::from_str(&contents).ok()?
+        };
+        config
+          .pointer("/build/srcTauriDir")?
+          .as_str()
+          .map(ToString::to_string)
+      });
+      match (src_tauri_dir, path.parent()) {
+        (Some(src_tauri_dir), Some(dir)) => {
+          dir.join(src_tauri_dir).canonicalize().ok().as_ref() == Some(&manifest)
+        }
+        _ => false,
+      }
+    })
+}
+
+fn is_package_json(path: &Path) -> bool {
+  path.file_name() == Some(OsStr::new(PACKAGE_JSON_FILE))
+}
+
+/// Gets the config and its raw JSON.
+/// The CLI passes the resolved config (merged, interpolated) on `TAURI_CONFIG`, so the app embeds it instead of the file.
+fn get_config(path: &Path) -> Result<(Config, String), Error> {
   match var("TAURI_CONFIG") {
-    Ok(custom_config) => {
-      serde_json::from_str(&custom_config).map_err(|e| Error::Serde("TAURI_CONFIG".into(), e))
-    }
+    Ok(custom_config) => serde_json::from_str(&custom_config)
+      .map(|config| (config, custom_config))
+      .map_err(|e| Error::Serde("TAURI_CONFIG".into(), e)),
     Err(_) => {
-      let file = File::open(&path).map_err(|e| Error::Io(path.into(), e))?;
-      let reader = BufReader::new(file);
-      serde_json::from_reader(reader).map_err(|e| Error::Serde(path.into(), e))
+      let mut raw_config = read_to_string(&path).map_err(|e| Error::Io(path.into(), e))?;
+      // the app parses the raw config as plain JSON
+      if path.extension() == Some(OsStr::new("toml")) {
+        let config: toml::Value =
+          toml::from_str(&raw_config).map_err(|e| Error::Toml(path.into(), e))?;
+        raw_config = serde_json::to_string(&config).map_err(|e| Error::Serde(path.into(), e))?;
+      } else {
+        // the JSON config can have comments, trailing commas and unquoted keys
+        let mut config: serde_json::Value =
+          json5::from_str(&raw_config).map_err(|e| Error::Json5(path.into(), e))?;
+        if is_package_json(path) {
+          config = config["tauri"].take();
+        }
+        raw_config = serde_json::to_string(&config).map_err(|e| Error::Serde(path.into(), e))?;
+      }
+      serde_json::from_str(&raw_config)
+        .map(|config| (config, raw_config))
+        .map_err(|e| Error::Serde(path.into(), e))
     }
   }
 }
 
+/// Whether the flag environment variable, set by the CLI, is enabled.
+fn env_flag(name: &str) -> bool {
+  var(name).map_or(false, |value| value == "true")
+}
+
 /// Generates a perfect hash function from `phf` of the assets in dist directory
 ///
+/// The asset map is cached on the `OUT_DIR` and only generated again when the content of the dist directory
+/// or the embedding settings change, or when `TAURI_FORCE_EMBED` is set by `--force-embed`.
+///
 /// The `TokenStream` produced by this function expects to have `phf` and
 /// `phf_map` paths available. Make sure to `use` these so the macro has access to them.
 /// It also expects `AssetCompression` to be in path.
-fn generate_asset_map(dist: &Path) -> Result<TokenStream, Error> {
+fn generate_asset_map(
+  dist: &Path,
+  compression: EmbeddedServerCompression,
+  public_path: &str,
+) -> Result<TokenStream, Error> {
   let mut inline_assets = HashSet::new();
   if let Ok(assets) = std::env::var("TAURI_INLINED_ASSETS") {
     assets
@@ -100,8 +228,56 @@ fn generate_asset_map(dist: &Path) -> Result<TokenStream, Error> {
       })
   }
 
-  IncludeDir::new(&dist)
-    .dir(&dist, AssetCompression::Gzip)?
+  // the `auto` compression gzips the brotli assets on request
+  let compression = match compression {
+    EmbeddedServerCompression::None => AssetCompression::None,
+    EmbeddedServerCompression::Gzip => AssetCompression::Gzip,
+    EmbeddedServerCompression::Brotli | EmbeddedServerCompression::Auto => AssetCompression::Brotli,
+  };
+
+  let mut inlined: Vec<String> = inline_assets
+    .iter()
+    .map(|path| path.display().to_string())
+    .collect();
+  inlined.sort();
+  let cache = AssetCache::new(fingerprint(
+    dist,
+    &[
+      ("version", env!("CARGO_PKG_VERSION").to_string()),
+      ("dist", dist.display().to_string()),
+      ("compression", format!("{:?}", compression)),
+      ("publicPath", public_path.to_string()),
+      ("inlined", inlined.join("|")),
+      // the embedded modification times
+      (
+        "sourceDateEpoch",
+        var("SOURCE_DATE_EPOCH").unwrap_or_default(),
+      ),
+    ],
+  )?)?;
+  // the macro output is shown by cargo, so it's logged like the CLI does
+  let verbose = env_flag("TAURI_VERBOSE");
+
+  if !env_flag("TAURI_FORCE_EMBED") {
+    if let Some(assets) = cache.load() {
+      if verbose {
+        eprintln!(
+          "info: the assets of {} didn't change, reusing the embedded assets",
+          dist.display()
+        );
+      }
+      return Ok(assets);
+    }
+  }
+  if verbose {
+    eprintln!("info: embedding the assets of {}", dist.display());
+  }
+
+  cache.clear()?;
+  let assets = IncludeDir::new(&dist)
+    .dir(&dist, compression)?
     .set_filter(inline_assets)?
-    .build()
+    .build()?;
+  cache.store(&assets)?;
+  Ok(assets)
 }
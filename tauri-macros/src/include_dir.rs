@@ -1,20 +1,134 @@
 use crate::error::Error;
+use brotli::CompressorReader;
 use flate2::bufread::GzEncoder;
 use proc_macro2::TokenStream;
 use quote::{quote, TokenStreamExt};
+use sha2::{Digest, Sha256};
 use std::{
   collections::{HashMap, HashSet},
   env::var,
-  fs::{canonicalize, create_dir_all, File},
-  io::{BufReader, BufWriter},
+  fs::{canonicalize, create_dir_all, metadata, read, read_to_string, remove_dir_all, write, File},
+  io::{BufReader, BufWriter, Read},
   path::{Path, PathBuf},
+  time::UNIX_EPOCH,
 };
 use tauri_utils::assets::{AssetCompression, Assets};
 use walkdir::WalkDir;
 
+/// The extensions of the formats that are already compressed, so they're embedded as is.
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+  "png", "jpg", "jpeg", "gif", "webp", "avif", "woff", "woff2", "mp3", "mp4", "webm", "ogg", "zip",
+  "gz", "br",
+];
+
+/// The file storing the fingerprint of the assets the cached asset map was generated from.
+const FINGERPRINT_FILE: &str = "fingerprint";
+/// The file storing the cached asset map.
+const ASSET_MAP_FILE: &str = "assets.rs";
+
+/// The modification time of an asset file, in seconds since the Unix epoch,
+/// or the `SOURCE_DATE_EPOCH` of the build so the reproducible builds embed the same time.
+fn modified(path: &Path) -> Result<u64, Error> {
+  if let Some(epoch) = var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|epoch| epoch.trim().parse().ok())
+  {
+    return Ok(epoch);
+  }
+  let modified = metadata(path)
+    .and_then(|metadata| metadata.modified())
+    .map_err(|e| Error::Io(path.to_path_buf(), e))?;
+  Ok(
+    modified
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default(),
+  )
+}
+
+/// The directory where the compressed assets and the asset map are cached.
+fn cache_dir() -> Result<PathBuf, Error> {
+  var("OUT_DIR")
+    .map_err(|_| Error::EnvOutDir)
+    .and_then(|out| canonicalize(&out).map_err(|e| Error::Io(PathBuf::from(out), e)))
+    .map(|out| out.join(".tauri-assets"))
+}
+
+/// Hashes the content of the files in the dist directory and the inputs of the asset map, e.g. the compression.
+///
+/// The modification times are ignored, so a touched but identical file keeps the fingerprint
+/// and the modification time it was embedded with.
+pub(crate) fn fingerprint(dist: &Path, inputs: &[(&str, String)]) -> Result<String, Error> {
+  let mut hasher = Sha256::new();
+  for (name, value) in inputs {
+    hasher.update(format!("{}={}\n", name, value));
+  }
+  let mut files = Vec::new();
+  for entry in WalkDir::new(dist).follow_links(true) {
+    let entry = entry.map_err(|e| Error::Io(dist.into(), e.into()))?;
+    if !entry.file_type().is_dir() {
+      files.push(entry.into_path());
+    }
+  }
+  files.sort();
+  for path in files {
+    let content = read(&path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    hasher.update(format!(
+      "{}:{:x}\n",
+      path.display(),
+      Sha256::digest(&content)
+    ));
+  }
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The asset map generated for a fingerprint, so unchanged assets aren't compressed again on every rebuild.
+pub(crate) struct AssetCache {
+  dir: PathBuf,
+  fingerprint: String,
+}
+
+impl AssetCache {
+  pub fn new(fingerprint: String) -> Result<Self, Error> {
+    Ok(Self {
+      dir: cache_dir()?,
+      fingerprint,
+    })
+  }
+
+  /// Gets the cached asset map if it was generated from the same fingerprint.
+  pub fn load(&self) -> Option<TokenStream> {
+    let fingerprint = read_to_string(self.dir.join(FINGERPRINT_FILE)).ok()?;
+    if fingerprint != self.fingerprint {
+      return None;
+    }
+    read_to_string(self.dir.join(ASSET_MAP_FILE))
+      .ok()?
+      .parse()
+      .ok()
+  }
+
+  /// Removes the cached asset map and compressed assets, so the removed assets don't linger.
+  pub fn clear(&self) -> Result<(), Error> {
+    if self.dir.exists() {
+      remove_dir_all(&self.dir).map_err(|e| Error::Io(self.dir.clone(), e))?;
+    }
+    Ok(())
+  }
+
+  pub fn store(&self, assets: &TokenStream) -> Result<(), Error> {
+    create_dir_all(&self.dir).map_err(|e| Error::Io(self.dir.clone(), e))?;
+    let asset_map = self.dir.join(ASSET_MAP_FILE);
+    write(&asset_map, assets.to_string()).map_err(|e| Error::Io(asset_map, e))?;
+    // the fingerprint is written last, so an interrupted build doesn't leave a valid cache
+    let fingerprint = self.dir.join(FINGERPRINT_FILE);
+    write(&fingerprint, &self.fingerprint).map_err(|e| Error::Io(fingerprint, e))
+  }
+}
+
 enum Asset {
   Identity(PathBuf),
-  Compressed(PathBuf, PathBuf),
+  Compressed(PathBuf, PathBuf, AssetCompression),
 }
 
 pub(crate) struct IncludeDir {
@@ -44,21 +158,31 @@ impl IncludeDir {
     let relative = self.relative(&path)?;
     let key = Assets::format_key(&relative);
 
+    let already_compressed = path
+      .extension()
+      .and_then(|extension| extension.to_str())
+      .map_or(false, |extension| {
+        COMPRESSED_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+      });
+
     let asset = match comp {
       AssetCompression::None => Asset::Identity(path),
-      AssetCompression::Gzip => {
-        let cache = var("OUT_DIR")
-          .map_err(|_| Error::EnvOutDir)
-          .and_then(|out| canonicalize(&out).map_err(|e| Error::Io(PathBuf::from(out), e)))
-          .map(|out| out.join(".tauri-assets"))?;
+      _ if already_compressed => Asset::Identity(path),
+      AssetCompression::Gzip | AssetCompression::Brotli => {
+        let cache = cache_dir()?;
 
         // normalize path separators
         let relative: PathBuf = relative.components().collect();
         let cache = cache.join(relative);
 
-        // append .br extension to filename
+        // append the compression extension to filename
         let filename = cache.file_name().ok_or(Error::IncludeDirEmptyFilename)?;
-        let filename = format!("{}.br", filename.to_string_lossy());
+        let extension = if comp == AssetCompression::Gzip {
+          "gz"
+        } else {
+          "br"
+        };
+        let filename = format!("{}.{}", filename.to_string_lossy(), extension);
 
         // remove filename from cache
         let cache = cache.parent().ok_or(Error::IncludeDirCacheDir)?;
@@ -72,7 +196,11 @@ impl IncludeDir {
         // open original asset path
         let reader = File::open(&path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
         let reader = BufReader::new(reader);
-        let mut reader = GzEncoder::new(reader, flate2::Compression::best());
+        let mut reader: Box<dyn Read> = if comp == AssetCompression::Gzip {
+          Box::new(GzEncoder::new(reader, flate2::Compression::best()))
+        } else {
+          Box::new(CompressorReader::new(reader, 4096, 11, 22))
+        };
 
         // open cache path
         let writer =
@@ -81,7 +209,7 @@ impl IncludeDir {
 
         std::io::copy(&mut reader, &mut writer).map_err(|e| Error::Io(path.to_path_buf(), e))?;
 
-        Asset::Compressed(path, cache_file)
+        Asset::Compressed(path, cache_file, comp)
       }
     };
 
@@ -129,23 +257,36 @@ impl IncludeDir {
         continue;
       }
 
+      // the hash of the original file, so it's the same for every compression
+      let source = match &asset {
+        Asset::Identity(path) | Asset::Compressed(path, _, _) => path,
+      };
+      let content = read(source).map_err(|e| Error::Io(source.to_path_buf(), e))?;
+      let hash = format!("{:x}", Sha256::digest(&content));
+      let modified = modified(source)?;
+
       let value = match asset {
         Asset::Identity(path) => {
           let path = path.display().to_string();
           quote! {
-            (AssetCompression::None, include_bytes!(#path))
+            (AssetCompression::None, #hash, #modified, include_bytes!(#path))
           }
         }
-        Asset::Compressed(path, cache) => {
+        Asset::Compressed(path, cache, comp) => {
           let path = path.display().to_string();
           let cache = cache.display().to_string();
+          let comp = if comp == AssetCompression::Gzip {
+            quote!(AssetCompression::Gzip)
+          } else {
+            quote!(AssetCompression::Brotli)
+          };
           quote! {
             {
               // make compiler check asset file for re-run.
               // rely on dead code elimination to remove it from target binary
               const _: &[u8] = include_bytes!(#path);
 
-              (AssetCompression::Gzip, include_bytes!(#cache))
+              (#comp, #hash, #modified, include_bytes!(#cache))
             }
           }
         }
@@ -163,3 +304,42 @@ impl IncludeDir {
     })
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::{
+    fs::{create_dir_all, write},
+    path::Path,
+  };
+
+  fn fingerprint(dist: &Path, inputs: &[(&str, String)]) -> String {
+    super::fingerprint(dist, inputs)
+      .unwrap_or_else(|_| panic!("failed to fingerprint the dist dir"))
+  }
+
+  #[test]
+  fn fingerprint_ignores_touched_files() {
+    let dist = tempfile::tempdir().expect("unable to create tempdir");
+    create_dir_all(dist.path().join("assets")).expect("unable to create dir");
+    write(dist.path().join("index.html"), "<html></html>").expect("unable to write file");
+    write(dist.path().join("assets/app.js"), "console.log(1)").expect("unable to write file");
+    let inputs = [("compression", "Brotli".to_string())];
+    let original = fingerprint(dist.path(), &inputs);
+
+    // rewriting the same content updates the modification time only
+    write(dist.path().join("index.html"), "<html></html>").expect("unable to write file");
+    assert_eq!(fingerprint(dist.path(), &inputs), original);
+
+    write(dist.path().join("index.html"), "<html><body></body></html>")
+      .expect("unable to write file");
+    let changed = fingerprint(dist.path(), &inputs);
+    assert_ne!(changed, original);
+
+    write(dist.path().join("assets/app.css"), "body {}").expect("unable to write file");
+    let added = fingerprint(dist.path(), &inputs);
+    assert_ne!(added, changed);
+
+    let inputs = [("compression", "Gzip".to_string())];
+    assert_ne!(fingerprint(dist.path(), &inputs), added);
+  }
+}
@@ -7,6 +7,12 @@ mod expand;
 mod include_dir;
 
 const DEFAULT_CONFIG_FILE: &str = "tauri.conf.json";
+/// The TOML config file, used when the default one doesn't exist.
+const DEFAULT_TOML_CONFIG_FILE: &str = "tauri.conf.toml";
+/// The npm manifest, whose `tauri` key is used when neither config file exists.
+const PACKAGE_JSON_FILE: &str = "package.json";
+/// The last config file looked up.
+const RC_CONFIG_FILE: &str = ".taurirc.json";
 
 #[proc_macro_derive(FromTauriContext, attributes(config_path))]
 pub fn load_context(ast: TokenStream) -> TokenStream {
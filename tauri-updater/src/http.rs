@@ -1,26 +1,188 @@
-use attohttpc;
+use attohttpc::{
+  header::{HeaderName, HeaderValue, LOCATION},
+  ProxySettings, StatusCode,
+};
 use serde::Serialize;
+use tauri_utils::config::UpdaterConfig;
+use url::Url;
 
-use std::io::{BufWriter, Write};
+use std::{
+  collections::HashMap,
+  io::{BufWriter, Write},
+};
 
 pub(crate) mod link_value;
 
+/// The redirects followed by the update requests.
+const MAX_REDIRECTS: usize = 10;
+
+/// The proxy and headers of the update requests, from the `tauri > updater` config.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+  /// The proxy of the requests. Defaults to the `HTTPS_PROXY` and `HTTP_PROXY` environment variables.
+  proxy: Option<Url>,
+  /// The headers, only sent to the origin of the requested URL.
+  headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl RequestOptions {
+  /// Parses the proxy URL and the headers, substituting the `${VAR}` environment variables of their values,
+  /// so the tokens stay out of the config.
+  ///
+  /// * Errors:
+  ///     * InvalidConfig - Invalid proxy URL, header or undefined environment variable
+  pub fn new(proxy: Option<&str>, headers: &HashMap<String, String>) -> crate::Result<Self> {
+    let proxy = match proxy {
+      Some(proxy) => Some(Url::parse(proxy).map_err(|e| {
+        crate::Error::InvalidConfig(format!("`updater > proxy` `{}` isn't a URL: {}", proxy, e))
+      })?),
+      None => None,
+    };
+    let mut parsed_headers = Vec::new();
+    let mut names: Vec<&String> = headers.keys().collect();
+    names.sort();
+    for name in names {
+      let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+        crate::Error::InvalidConfig(format!(
+          "`updater > headers > {}` isn't a header name",
+          name
+        ))
+      })?;
+      let value = interpolate_env(&headers[name]).map_err(|variable| {
+        crate::Error::InvalidConfig(format!(
+          "the environment variable `{}` of `updater > headers > {}` isn't set",
+          variable, name
+        ))
+      })?;
+      let mut value = HeaderValue::from_str(&value).map_err(|_| {
+        crate::Error::InvalidConfig(format!(
+          "`updater > headers > {}` isn't a valid header value",
+          name
+        ))
+      })?;
+      // keeps the tokens out of the debug output
+      value.set_sensitive(true);
+      parsed_headers.push((header_name, value));
+    }
+    Ok(Self {
+      proxy,
+      headers: parsed_headers,
+    })
+  }
+
+  /// The options of the `tauri > updater` config.
+  pub fn from_config(config: &UpdaterConfig) -> crate::Result<Self> {
+    Self::new(config.proxy.as_deref(), &config.headers)
+  }
+}
+
+/// Substitutes the `${VAR}` environment variables of the value, returning the name of the first undefined one.
+fn interpolate_env(value: &str) -> Result<String, String> {
+  let mut interpolated = String::new();
+  let mut rest = value;
+  while let Some(start) = rest.find("${") {
+    interpolated.push_str(&rest[..start]);
+    let expression = &rest[start + 2..];
+    let end = match expression.find('}') {
+      Some(end) => end,
+      None => {
+        rest = &rest[start..];
+        break;
+      }
+    };
+    let name = &expression[..end];
+    interpolated.push_str(&std::env::var(name).map_err(|_| name.to_string())?);
+    rest = &expression[end + 1..];
+  }
+  interpolated.push_str(rest);
+  Ok(interpolated)
+}
+
+/// Maps the TLS and proxy authentication failures to their own errors, so they can be told apart from the network ones.
+fn request_error(error: attohttpc::Error) -> anyhow::Error {
+  match error.kind() {
+    attohttpc::ErrorKind::Tls(e) => crate::Error::Tls(e.to_string()).into(),
+    attohttpc::ErrorKind::ConnectError { status_code, .. }
+      if *status_code == StatusCode::PROXY_AUTHENTICATION_REQUIRED =>
+    {
+      crate::Error::ProxyAuthenticationRequired.into()
+    }
+    _ => error.into(),
+  }
+}
+
 pub fn get(url: String) -> crate::Result<attohttpc::Response> {
   let response = attohttpc::get(url).send()?;
 
   Ok(response)
 }
 
+/// Sends a GET request with the proxy and headers of the options, following the redirects.
+/// The headers aren't sent to the redirect targets on another origin.
+///
+/// * Errors:
+///     * Tls - The TLS handshake failed, e.g. because of an untrusted certificate
+///     * ProxyAuthenticationRequired - The proxy responded with `407 Proxy Authentication Required`
+pub fn get_with_options(url: &str, options: &RequestOptions) -> crate::Result<attohttpc::Response> {
+  let mut url = Url::parse(url).map_err(|e| crate::Error::Network(format!("`{}`: {}", url, e)))?;
+  let origin = url.origin();
+  for _ in 0..=MAX_REDIRECTS {
+    let mut request = attohttpc::get(url.as_str()).follow_redirects(false);
+    if let Some(proxy) = &options.proxy {
+      request = request.proxy_settings(
+        ProxySettings::builder()
+          .http_proxy(proxy.clone())
+          .https_proxy(proxy.clone())
+          .build(),
+      );
+    }
+    if url.origin() == origin {
+      for (name, value) in &options.headers {
+        request = request.header(name.clone(), value.clone());
+      }
+    }
+    let response = request.send().map_err(request_error)?;
+    let status = response.status();
+    if status == StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+      return Err(crate::Error::ProxyAuthenticationRequired.into());
+    }
+    let location = match response.headers().get(LOCATION) {
+      Some(location) if status.is_redirection() => location,
+      _ => return Ok(response),
+    };
+    let location = location
+      .to_str()
+      .ok()
+      .and_then(|location| url.join(location).ok())
+      .ok_or_else(|| {
+        crate::Error::Network(format!("`{}` has an invalid redirect location", url))
+      })?;
+    url = location;
+  }
+  Err(
+    crate::Error::Network(format!(
+      "`{}` redirected more than {} times",
+      url, MAX_REDIRECTS
+    ))
+    .into(),
+  )
+}
+
 pub fn post_as_json<T: Serialize>(url: String, payload: &T) -> crate::Result<attohttpc::Response> {
   let response = attohttpc::post(url).json(payload)?.send()?;
 
   Ok(response)
 }
 
-pub fn download<T: Write>(url: String, dest: T, _display_progress: bool) -> crate::Result<()> {
+pub fn download<T: Write>(
+  url: String,
+  dest: T,
+  _display_progress: bool,
+  options: &RequestOptions,
+) -> crate::Result<()> {
   set_ssl_vars!();
 
-  let resp = get(url)?;
+  let resp = get_with_options(&url, options)?;
 
   if !resp.status().is_success() {
     return Err(crate::Error::Download(resp.status()).into());
@@ -30,3 +192,46 @@ pub fn download<T: Write>(url: String, dest: T, _display_progress: bool) -> crat
   resp.write_to(file)?;
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{interpolate_env, RequestOptions};
+  use crate::Error;
+
+  use std::collections::HashMap;
+
+  #[test]
+  fn header_interpolation() {
+    std::env::set_var("TAURI_UPDATER_TEST_TOKEN", "secret");
+    assert_eq!(
+      interpolate_env("Bearer ${TAURI_UPDATER_TEST_TOKEN}"),
+      Ok("Bearer secret".to_string())
+    );
+    assert_eq!(
+      interpolate_env("no variables $ {}"),
+      Ok("no variables $ {}".to_string())
+    );
+    assert_eq!(
+      interpolate_env("${TAURI_UPDATER_TEST_UNDEFINED}"),
+      Err("TAURI_UPDATER_TEST_UNDEFINED".to_string())
+    );
+
+    let mut headers = HashMap::new();
+    headers.insert(
+      "Authorization".to_string(),
+      "Bearer ${TAURI_UPDATER_TEST_TOKEN}".to_string(),
+    );
+    let options = RequestOptions::new(Some("http://proxy.example.com:3128"), &headers).unwrap();
+    assert_eq!(options.headers[0].1, "Bearer secret");
+
+    headers.insert("Invalid Name".to_string(), "value".to_string());
+    match RequestOptions::new(None, &headers)
+      .unwrap_err()
+      .downcast::<Error>()
+      .unwrap()
+    {
+      Error::InvalidConfig(_) => {}
+      e => panic!("unexpected error {:?}", e),
+    }
+  }
+}
@@ -14,6 +14,22 @@ pub enum Error {
   Updater,
   #[error("Network Error:{0}")]
   Network(String),
+  #[error("TLS Error:{0}")]
+  Tls(String),
+  #[error("The proxy requires authentication (407 Proxy Authentication Required)")]
+  ProxyAuthenticationRequired,
   #[error("Config Error:{0} required")]
   Config(String),
+  #[error("Invalid updater config:{0}")]
+  InvalidConfig(String),
+  #[error("Invalid update manifest:{0}")]
+  InvalidManifest(String),
+  #[error("The update manifest version {latest} is older than the current version {current}")]
+  Downgrade { current: String, latest: String },
+  #[error("The update manifest doesn't have a release for `{target}`, only for: {available}")]
+  TargetNotFound { target: String, available: String },
+  #[error("Unknown update manifest target `{0}`, expected one of: darwin, linux, win32, win64")]
+  UnknownTarget(String),
+  #[error("Signature Error:{0}")]
+  Signature(String),
 }
@@ -1,12 +1,16 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-use crate::http;
+use crate::http::{self, RequestOptions};
 
 use tauri_api::file::{Extract, Move};
+use tauri_utils::config::{UpdaterConfig, WindowsUpdateInstallMode};
 
 pub mod github;
+pub mod manifest;
 
 /// Status returned after updating
 ///
@@ -15,6 +19,9 @@ pub mod github;
 pub enum Status {
   UpToDate(String),
   Updated(String),
+  /// The Windows installer of the release is running,
+  /// and the app must exit so it can replace the app files.
+  Installing(String),
 }
 impl Status {
   /// Return the version tag
@@ -23,6 +30,7 @@ impl Status {
     match *self {
       UpToDate(ref s) => s,
       Updated(ref s) => s,
+      Installing(ref s) => s,
     }
   }
 
@@ -41,6 +49,14 @@ impl Status {
       _ => false,
     }
   }
+
+  /// Returns `true` if `Status::Installing`
+  pub fn installing(&self) -> bool {
+    match *self {
+      Status::Installing(_) => true,
+      _ => false,
+    }
+  }
 }
 
 #[derive(Clone, Debug)]
@@ -48,6 +64,8 @@ pub struct Release {
   pub version: String,
   pub asset_name: String,
   pub download_url: String,
+  /// The base64 encoded minisign signature of the download, required to install it.
+  pub signature: Option<String>,
 }
 
 #[derive(Debug)]
@@ -59,6 +77,12 @@ pub struct UpdateBuilder {
   show_download_progress: bool,
   show_output: bool,
   current_version: Option<String>,
+  windows_install_mode: WindowsUpdateInstallMode,
+  installer_args: Vec<String>,
+  relaunch: bool,
+  pubkey: Option<String>,
+  proxy: Option<String>,
+  headers: HashMap<String, String>,
 }
 impl UpdateBuilder {
   /// Initialize a new builder, defaulting the `bin_install_path` to the current
@@ -75,9 +99,26 @@ impl UpdateBuilder {
       show_download_progress: false,
       show_output: true,
       current_version: None,
+      windows_install_mode: WindowsUpdateInstallMode::default(),
+      installer_args: Vec::new(),
+      relaunch: true,
+      pubkey: None,
+      proxy: None,
+      headers: HashMap::new(),
     })
   }
 
+  /// Applies the `tauri > updater` configuration.
+  pub fn config(&mut self, config: &UpdaterConfig) -> &mut Self {
+    self.windows_install_mode = config.windows.install_mode;
+    self.installer_args = config.windows.installer_args.clone();
+    self.relaunch = config.relaunch;
+    self.pubkey = config.pubkey.clone();
+    self.proxy = config.proxy.clone();
+    self.headers = config.headers.clone();
+    self
+  }
+
   pub fn release(&mut self, release: Release) -> &mut Self {
     self.release = Some(release);
     self
@@ -151,10 +192,51 @@ impl UpdateBuilder {
     self
   }
 
+  /// Set how the downloaded `.msi` or `-setup.exe` installer is run on Windows, defaults to `Passive`.
+  pub fn windows_install_mode(&mut self, mode: WindowsUpdateInstallMode) -> &mut Self {
+    self.windows_install_mode = mode;
+    self
+  }
+
+  /// Set extra arguments passed to the downloaded `.msi` or `-setup.exe` installer on Windows.
+  pub fn installer_args(&mut self, args: Vec<String>) -> &mut Self {
+    self.installer_args = args;
+    self
+  }
+
+  /// Toggle relaunching the app once the update is installed, defaults to `true`.
+  /// The new app is started when `update` returns, and the app exits itself afterwards.
+  /// When `false`, call `updater::relaunch` once the user confirms it.
+  /// The Windows installers don't relaunch the app: the app exits on `Status::Installing` so they can replace it.
+  pub fn relaunch(&mut self, relaunch: bool) -> &mut Self {
+    self.relaunch = relaunch;
+    self
+  }
+
+  /// Set the base64 encoded minisign public key the download is verified with, required to build the `Update`.
+  pub fn pubkey(&mut self, pubkey: &str) -> &mut Self {
+    self.pubkey = Some(pubkey.to_owned());
+    self
+  }
+
+  /// Set the proxy the download goes through, defaults to the `HTTPS_PROXY` and `HTTP_PROXY` environment variables.
+  pub fn proxy(&mut self, proxy: &str) -> &mut Self {
+    self.proxy = Some(proxy.to_owned());
+    self
+  }
+
+  /// Add a header to the download request, e.g. `Authorization`.
+  /// Its `${VAR}` environment variables are substituted, and it isn't sent to the redirects to other origins.
+  pub fn header(&mut self, name: &str, value: &str) -> &mut Self {
+    self.headers.insert(name.to_owned(), value.to_owned());
+    self
+  }
+
   /// Confirm config and create a ready-to-use `Update`
   ///
   /// * Errors:
   ///     * Config - Invalid `Update` configuration
+  ///     * InvalidConfig - Invalid proxy URL or header
   pub fn build(&self) -> crate::Result<Update> {
     Ok(Update {
       release: if let Some(ref release) = self.release {
@@ -184,6 +266,15 @@ impl UpdateBuilder {
       },
       show_download_progress: self.show_download_progress,
       show_output: self.show_output,
+      windows_install_mode: self.windows_install_mode,
+      installer_args: self.installer_args.clone(),
+      relaunch: self.relaunch,
+      pubkey: if let Some(ref pubkey) = self.pubkey {
+        pubkey.to_owned()
+      } else {
+        return Err(crate::Error::Config("`pubkey`".into()).into());
+      },
+      request_options: RequestOptions::new(self.proxy.as_deref(), &self.headers)?,
     })
   }
 }
@@ -198,6 +289,11 @@ pub struct Update {
   bin_path_in_archive: PathBuf,
   show_download_progress: bool,
   show_output: bool,
+  windows_install_mode: WindowsUpdateInstallMode,
+  installer_args: Vec<String>,
+  relaunch: bool,
+  pubkey: String,
+  request_options: RequestOptions,
 }
 impl Update {
   /// Initialize a new `Update` builder
@@ -247,7 +343,45 @@ impl Update {
       self.release.download_url.clone(),
       &mut tmp_archive,
       self.show_download_progress,
+      &self.request_options,
     )?;
+    // the installer can't open the file while we hold it
+    drop(tmp_archive);
+
+    // nothing is installed nor extracted before the download is verified
+    self.print_flush("Verifying signature... ")?;
+    let signature = self
+      .release
+      .signature
+      .as_ref()
+      .ok_or_else(|| crate::Error::Signature("the release doesn't have a signature".into()))?;
+    manifest::verify_signature(&fs::read(&tmp_archive_path)?, signature, &self.pubkey)?;
+    self.println("Done");
+
+    let installer = if cfg!(windows) && has_extension(&tmp_archive_path, "msi") {
+      let mut command = Command::new("msiexec.exe");
+      command
+        .arg("/i")
+        .arg(&tmp_archive_path)
+        .args(self.windows_install_mode.msiexec_args());
+      Some(command)
+    } else if cfg!(windows) && has_extension(&tmp_archive_path, "exe") {
+      // the NSIS installer
+      let mut command = Command::new(&tmp_archive_path);
+      command.args(self.windows_install_mode.nsis_args());
+      Some(command)
+    } else {
+      None
+    };
+    if let Some(mut installer) = installer {
+      self.print_flush("Starting installer... ")?;
+      // the installer can't replace the app files while the app runs, so it isn't waited for,
+      // and it's left on the temporary directory since it's still reading it
+      installer.args(&self.installer_args).spawn()?;
+      tmp_dir.into_path();
+      self.println("Done");
+      return Ok(Status::Installing(self.release.version));
+    }
 
     self.print_flush("Extracting archive... ")?;
     Extract::from_source(&tmp_archive_path)
@@ -261,6 +395,31 @@ impl Update {
       .replace_using_temp(&tmp_file)
       .to_dest(&self.bin_install_path)?;
     self.println("Done");
+    self.finish()
+  }
+
+  /// Starts the new app if configured to, otherwise returns the update status
+  /// so the app can call `relaunch` later.
+  fn finish(self) -> crate::Result<Status> {
+    if self.relaunch {
+      relaunch(&self.bin_install_path)?;
+    }
     Ok(Status::Updated(self.release.version))
   }
 }
+
+/// Starts the app from `bin_install_path` with the current arguments.
+/// The caller exits the current process afterwards, once it's done cleaning up.
+pub fn relaunch(bin_install_path: &Path) -> crate::Result<()> {
+  Command::new(bin_install_path)
+    .args(env::args_os().skip(1))
+    .spawn()?;
+  Ok(())
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+  path
+    .extension()
+    .map(|ext| ext.eq_ignore_ascii_case(extension))
+    .unwrap_or(false)
+}
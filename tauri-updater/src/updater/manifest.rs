@@ -0,0 +1,309 @@
+// The static JSON manifest served by the `updater > endpoints`, generated by `tauri updater generate`:
+//
+// {
+//   "version": "1.0.1",
+//   "notes": "Fixes the crash on startup",
+//   "pub_date": "2021-02-01T12:00:00Z",
+//   "platforms": {
+//     "linux": { "url": "https://example.com/app_1.0.1_amd64.AppImage", "signature": "dW50cnVzdGVk..." }
+//   }
+// }
+//
+// The signatures are the base64 encoded minisign signatures of the artifacts,
+// verified with the base64 encoded minisign public key of `updater > pubkey`.
+
+use super::Release;
+use crate::http::{self, RequestOptions};
+
+use minisign_verify::{PublicKey, Signature};
+use semver::Version;
+use serde::Deserialize;
+
+use std::{cmp::Ordering, collections::BTreeMap};
+
+/// The platform keys of the manifest.
+pub const TARGETS: &[&str] = &["darwin", "linux", "win32", "win64"];
+
+/// The platform key of the running app.
+pub fn current_target() -> Option<&'static str> {
+  if cfg!(target_os = "macos") {
+    Some("darwin")
+  } else if cfg!(target_os = "linux") {
+    Some("linux")
+  } else if cfg!(all(windows, target_pointer_width = "64")) {
+    Some("win64")
+  } else if cfg!(windows) {
+    Some("win32")
+  } else {
+    None
+  }
+}
+
+/// The release of a platform.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlatformRelease {
+  /// The download URL of the artifact.
+  pub url: String,
+  /// The base64 encoded minisign signature of the artifact.
+  pub signature: String,
+}
+
+/// The update manifest.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+  /// The semver version of the release, optionally prefixed with `v`.
+  pub version: String,
+  /// The release notes.
+  #[serde(default)]
+  pub notes: Option<String>,
+  /// The RFC 3339 publish date of the release.
+  #[serde(default)]
+  pub pub_date: Option<String>,
+  /// The release of each platform.
+  pub platforms: BTreeMap<String, PlatformRelease>,
+}
+
+impl Manifest {
+  /// Parses a manifest, rejecting the unknown fields and targets, the invalid versions, dates,
+  /// URLs and signatures, so a broken manifest fails the update check instead of the download.
+  pub fn parse(json: &str) -> crate::Result<Self> {
+    let manifest: Self =
+      serde_json::from_str(json).map_err(|e| crate::Error::InvalidManifest(e.to_string()))?;
+    manifest.semver()?;
+    if let Some(pub_date) = &manifest.pub_date {
+      chrono::DateTime::parse_from_rfc3339(pub_date).map_err(|e| {
+        crate::Error::InvalidManifest(format!(
+          "`pub_date` `{}` isn't a RFC 3339 date: {}",
+          pub_date, e
+        ))
+      })?;
+    }
+    if manifest.platforms.is_empty() {
+      return Err(crate::Error::InvalidManifest("`platforms` is empty".into()).into());
+    }
+    for (target, release) in &manifest.platforms {
+      if !TARGETS.contains(&target.as_str()) {
+        return Err(crate::Error::UnknownTarget(target.clone()).into());
+      }
+      if !(release.url.starts_with("https://") || release.url.starts_with("http://")) {
+        return Err(
+          crate::Error::InvalidManifest(format!(
+            "`platforms > {} > url` `{}` isn't a HTTP URL",
+            target, release.url
+          ))
+          .into(),
+        );
+      }
+      decode_base64(&release.signature).map_err(|e| {
+        crate::Error::InvalidManifest(format!(
+          "`platforms > {} > signature` isn't a base64 minisign signature: {}",
+          target, e
+        ))
+      })?;
+    }
+    Ok(manifest)
+  }
+
+  fn semver(&self) -> crate::Result<Version> {
+    Version::parse(self.version.trim_start_matches('v')).map_err(|e| {
+      crate::Error::InvalidManifest(format!(
+        "`version` `{}` isn't a semver version: {}",
+        self.version, e
+      ))
+      .into()
+    })
+  }
+
+  /// Gets the release of the target if it's newer than `current_version`, `None` if the app is up to date.
+  ///
+  /// * Errors:
+  ///     * Downgrade - The manifest version is older than `current_version`
+  ///     * TargetNotFound - The manifest doesn't have a release for the target
+  pub fn release(&self, current_version: &str, target: &str) -> crate::Result<Option<Release>> {
+    let current = Version::parse(current_version.trim_start_matches('v'))?;
+    let latest = self.semver()?;
+    match latest.cmp(&current) {
+      Ordering::Less => {
+        return Err(
+          crate::Error::Downgrade {
+            current: current.to_string(),
+            latest: latest.to_string(),
+          }
+          .into(),
+        )
+      }
+      Ordering::Equal => return Ok(None),
+      Ordering::Greater => {}
+    }
+    let platform = self
+      .platforms
+      .get(target)
+      .ok_or_else(|| crate::Error::TargetNotFound {
+        target: target.to_string(),
+        available: self
+          .platforms
+          .keys()
+          .cloned()
+          .collect::<Vec<_>>()
+          .join(", "),
+      })?;
+    let asset_name = platform
+      .url
+      .split('?')
+      .next()
+      .and_then(|url| url.rsplit('/').next())
+      .filter(|name| !name.is_empty())
+      .unwrap_or("update")
+      .to_string();
+    Ok(Some(Release {
+      version: latest.to_string(),
+      asset_name,
+      download_url: platform.url.clone(),
+      signature: Some(platform.signature.clone()),
+    }))
+  }
+}
+
+/// Fetches the manifest of the first endpoint that responds and gets the release of the running platform,
+/// `None` if the app is up to date. The requests are sent with the proxy and headers of the options.
+/// An invalid manifest fails right away instead of falling back to the next endpoint.
+///
+/// * Errors:
+///     * Tls, ProxyAuthenticationRequired - No endpoint responded, and one failed with this error
+///     * Network - No endpoint responded
+pub fn check_endpoints(
+  endpoints: &[String],
+  current_version: &str,
+  options: &RequestOptions,
+) -> crate::Result<Option<Release>> {
+  set_ssl_vars!();
+  let target = current_target()
+    .ok_or_else(|| crate::Error::Config("a platform supported by the updater".into()))?;
+  if endpoints.is_empty() {
+    return Err(crate::Error::Config("`updater > endpoints`".into()).into());
+  }
+  let mut failures = Vec::new();
+  // the TLS and proxy authentication failures are reported over the generic network error
+  let mut typed_failure = None;
+  for endpoint in endpoints {
+    let response = match http::get_with_options(endpoint, options) {
+      Ok(response) => response,
+      Err(e) => {
+        failures.push(format!("{}: {}", endpoint, e));
+        let typed = matches!(
+          e.downcast_ref::<crate::Error>(),
+          Some(crate::Error::Tls(_)) | Some(crate::Error::ProxyAuthenticationRequired)
+        );
+        if typed && typed_failure.is_none() {
+          typed_failure = Some(e);
+        }
+        continue;
+      }
+    };
+    if !response.status().is_success() {
+      failures.push(format!("{}: status {}", endpoint, response.status()));
+      continue;
+    }
+    let manifest = Manifest::parse(&response.text()?)?;
+    return manifest.release(current_version, target);
+  }
+  if let Some(failure) = typed_failure {
+    return Err(failure);
+  }
+  Err(crate::Error::Network(format!("no endpoint responded: {}", failures.join("; "))).into())
+}
+
+fn decode_base64(value: &str) -> crate::Result<String> {
+  let decoded = base64::decode(value).map_err(|e| crate::Error::Signature(e.to_string()))?;
+  String::from_utf8(decoded).map_err(|e| crate::Error::Signature(e.to_string()).into())
+}
+
+/// Verifies the data against its base64 encoded minisign signature and the base64 encoded public key.
+pub fn verify_signature(data: &[u8], signature: &str, pubkey: &str) -> crate::Result<()> {
+  let pubkey = PublicKey::decode(&decode_base64(pubkey)?)
+    .map_err(|e| crate::Error::Signature(format!("invalid public key: {}", e)))?;
+  let signature = Signature::decode(&decode_base64(signature)?)
+    .map_err(|e| crate::Error::Signature(format!("invalid signature: {}", e)))?;
+  pubkey
+    .verify(data, &signature, false)
+    .map_err(|e| crate::Error::Signature(e.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Manifest;
+  use crate::Error;
+
+  fn manifest(version: &str, target: &str) -> String {
+    format!(
+      r#"{{
+        "version": "{}",
+        "notes": "Fixes the crash on startup",
+        "pub_date": "2021-02-01T12:00:00Z",
+        "platforms": {{
+          "{}": {{ "url": "https://example.com/app.AppImage.tar.gz?token=1", "signature": "c2lnbmF0dXJl" }}
+        }}
+      }}"#,
+      version, target
+    )
+  }
+
+  fn error(result: crate::Result<impl std::fmt::Debug>) -> Error {
+    result
+      .unwrap_err()
+      .downcast::<Error>()
+      .expect("not an updater error")
+  }
+
+  #[test]
+  fn releases() {
+    let manifest = Manifest::parse(&manifest("v1.1.0", "linux")).unwrap();
+    let release = manifest.release("1.0.0", "linux").unwrap().unwrap();
+    assert_eq!(release.version, "1.1.0");
+    assert_eq!(release.asset_name, "app.AppImage.tar.gz");
+    assert_eq!(release.signature.as_deref(), Some("c2lnbmF0dXJl"));
+    assert!(manifest.release("1.1.0", "linux").unwrap().is_none());
+  }
+
+  #[test]
+  fn rejected_releases() {
+    let manifest = Manifest::parse(&manifest("1.1.0", "linux")).unwrap();
+    match error(manifest.release("1.2.0", "linux")) {
+      Error::Downgrade { current, latest } => {
+        assert_eq!(current, "1.2.0");
+        assert_eq!(latest, "1.1.0");
+      }
+      e => panic!("unexpected error {:?}", e),
+    }
+    match error(manifest.release("1.0.0", "darwin")) {
+      Error::TargetNotFound { target, available } => {
+        assert_eq!(target, "darwin");
+        assert_eq!(available, "linux");
+      }
+      e => panic!("unexpected error {:?}", e),
+    }
+  }
+
+  #[test]
+  fn invalid_manifests() {
+    match error(Manifest::parse(&manifest("1.1.0", "macos"))) {
+      Error::UnknownTarget(target) => assert_eq!(target, "macos"),
+      e => panic!("unexpected error {:?}", e),
+    }
+    for json in &[
+      manifest("latest", "linux"),
+      manifest("1.1.0", "linux").replace("2021-02-01T12:00:00Z", "yesterday"),
+      manifest("1.1.0", "linux").replace("https://", "ftp://"),
+      manifest("1.1.0", "linux").replace("c2lnbmF0dXJl", "not base64!"),
+      manifest("1.1.0", "linux").replace("\"notes\"", "\"changelog\""),
+      r#"{ "version": "1.1.0", "platforms": {} }"#.to_string(),
+    ] {
+      match error(Manifest::parse(json)) {
+        Error::InvalidManifest(_) => {}
+        e => panic!("unexpected error {:?} for {}", e, json),
+      }
+    }
+  }
+}
@@ -1,9 +1,10 @@
 //! Assets handled by Tauri during compile time and runtime.
 
+use brotli::Decompressor;
 use flate2::read::{GzDecoder, GzEncoder};
 pub use phf;
 use std::{
-  io::Read,
+  io::{self, Read},
   path::{Component, Path, PathBuf},
 };
 
@@ -15,6 +16,9 @@ pub enum AssetCompression {
 
   /// Compressed with (gzip)[https://crates.io/crates/flate2]
   Gzip,
+
+  /// Compressed with (brotli)[https://crates.io/crates/brotli]
+  Brotli,
 }
 
 /// How the embedded asset should be fetched from `Assets`
@@ -31,12 +35,17 @@ pub enum AssetFetch {
 
 /// Runtime access to the included files
 pub struct Assets {
-  inner: phf::Map<&'static str, (AssetCompression, &'static [u8])>,
+  inner: phf::Map<&'static str, (AssetCompression, &'static str, u64, &'static [u8])>,
 }
 
 impl Assets {
-  /// Create `Assets` container from `phf::Map`
-  pub const fn new(map: phf::Map<&'static str, (AssetCompression, &'static [u8])>) -> Self {
+  /// Create `Assets` container from `phf::Map`.
+  ///
+  /// Each asset is stored with its compression, the hex SHA-256 hash of its uncompressed content,
+  /// its modification time in seconds since the Unix epoch and its content.
+  pub const fn new(
+    map: phf::Map<&'static str, (AssetCompression, &'static str, u64, &'static [u8])>,
+  ) -> Self {
     Self { inner: map }
   }
 
@@ -80,6 +89,47 @@ impl Assets {
     }
   }
 
+  /// Gets the embedded asset as stored on the binary, with its compression.
+  pub fn get_raw(&self, path: impl Into<PathBuf>) -> Option<(AssetCompression, &'static [u8])> {
+    let key = Self::format_key(path);
+    let &(compression, _, _, content) = self.inner.get(&*key)?;
+    Some((compression, content))
+  }
+
+  /// Gets the hash of the uncompressed content of an embedded asset, computed when it was embedded.
+  pub fn hash(&self, path: impl Into<PathBuf>) -> Option<&'static str> {
+    let key = Self::format_key(path);
+    self.inner.get(&*key).map(|&(_, hash, _, _)| hash)
+  }
+
+  /// Gets the modification time of an embedded asset file when it was embedded, in seconds since the Unix epoch.
+  /// It's the `SOURCE_DATE_EPOCH` of the build if it was set, so the reproducible builds embed the same time.
+  pub fn modified(&self, path: impl Into<PathBuf>) -> Option<u64> {
+    let key = Self::format_key(path);
+    self.inner.get(&*key).map(|&(_, _, modified, _)| modified)
+  }
+
+  /// Gets the decompressed size of an embedded asset.
+  ///
+  /// The size of gzip assets is read from the gzip trailer, so it wraps for assets larger than 4 GiB.
+  /// Brotli doesn't store the size, so brotli assets are decompressed to count it.
+  pub fn size(&self, path: impl Into<PathBuf>) -> Option<u64> {
+    let key = Self::format_key(path);
+    let &(compression, _, _, content) = self.inner.get(&*key)?;
+    match compression {
+      AssetCompression::None => Some(content.len() as u64),
+      AssetCompression::Gzip => {
+        let trailer = content.get(content.len().checked_sub(4)?..)?;
+        let mut size = [0; 4];
+        size.copy_from_slice(trailer);
+        Some(u32::from_le_bytes(size) as u64)
+      }
+      AssetCompression::Brotli => {
+        io::copy(&mut Decompressor::new(content, 4096), &mut io::sink()).ok()
+      }
+    }
+  }
+
   /// Get embedded asset, automatically handling compression.
   pub fn get(
     &self,
@@ -89,7 +139,7 @@ impl Assets {
     use self::{AssetCompression::*, AssetFetch::*};
 
     let key = Self::format_key(path);
-    let &(compression, content) = self.inner.get(&*key)?;
+    let &(compression, _, _, content) = self.inner.get(&*key)?;
     Some(match (compression, fetch) {
       // content is already in compression format expected
       (_, Identity) | (None, Decompress) | (Gzip, Compress) => (Box::new(content), compression),
@@ -100,11 +150,24 @@ impl Assets {
         (Box::new(compressor), Gzip)
       }
 
+      // content is brotli compressed, but fetched with gzip compression
+      (Brotli, Compress) => {
+        let compressor = GzEncoder::new(
+          Decompressor::new(content, 4096),
+          flate2::Compression::new(6),
+        );
+        (Box::new(compressor), Gzip)
+      }
+
       // content is compressed, but fetched with decompression
       (Gzip, Decompress) => {
         let decompressor = GzDecoder::new(content);
         (Box::new(decompressor), None)
       }
+      (Brotli, Decompress) => {
+        let decompressor = Decompressor::new(content, 4096);
+        (Box::new(decompressor), None)
+      }
     })
   }
 }
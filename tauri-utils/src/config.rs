@@ -1,10 +1,14 @@
 use serde::{
-  de::{Deserializer, Error as DeError, Visitor},
+  de::{Deserializer, Error as DeError, MapAccess, SeqAccess, Visitor},
   Deserialize,
 };
 use serde_json::Value as JsonValue;
+use url::Url;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+/// The config version from which the window sizes without a unit are logical pixels instead of physical pixels.
+pub const LOGICAL_WINDOW_SIZE_VERSION: u64 = 2;
 
 /// The window webview URL options.
 #[derive(PartialEq, Debug, Clone)]
@@ -48,6 +52,88 @@ impl<'de> Deserialize<'de> for WindowUrl {
   }
 }
 
+/// A window dimension.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum WindowSize {
+  /// A number without a unit, in logical pixels since config version 2 and physical pixels before.
+  Unitless(f64),
+  /// Physical pixels, e.g. `"1200px"`.
+  Physical(f64),
+  /// Logical pixels, scaled by the monitor scale factor, e.g. `"1200lpx"`.
+  Logical(f64),
+}
+
+impl WindowSize {
+  /// Resolves the dimension to physical pixels, with the monitor scale factor.
+  /// The unitless dimensions are logical pixels.
+  pub fn to_physical(self, scale_factor: f64) -> f64 {
+    match self {
+      Self::Physical(size) => size,
+      Self::Unitless(size) | Self::Logical(size) => size * scale_factor,
+    }
+  }
+
+  /// Gives the unitless dimension the unit of the config version.
+  fn pin_unit(&mut self, config_version: u64) {
+    if let Self::Unitless(size) = *self {
+      *self = if config_version < LOGICAL_WINDOW_SIZE_VERSION {
+        Self::Physical(size)
+      } else {
+        Self::Logical(size)
+      };
+    }
+  }
+}
+
+impl From<f64> for WindowSize {
+  fn from(size: f64) -> Self {
+    Self::Unitless(size)
+  }
+}
+
+impl FromStr for WindowSize {
+  type Err = String;
+
+  fn from_str(size: &str) -> Result<Self, Self::Err> {
+    let size = size.trim();
+    let (number, unit): (&str, fn(f64) -> Self) = if let Some(number) = size.strip_suffix("lpx") {
+      (number, Self::Logical)
+    } else if let Some(number) = size.strip_suffix("px") {
+      (number, Self::Physical)
+    } else if size.ends_with('%') {
+      // the webview runtime can't read the monitors, so there's nothing to resolve a percentage against
+      return Err(format!(
+        "`{}` is not a valid window size; the `%` unit isn't supported yet, use the `px` or `lpx` unit",
+        size
+      ));
+    } else {
+      (size, Self::Unitless)
+    };
+    match number.trim().parse::<f64>() {
+      Ok(number) if number.is_finite() && number >= 0.0 => Ok(unit(number)),
+      _ => Err(format!(
+        "`{}` is not a valid window size; use a number, or a number with the `px` or `lpx` unit",
+        size
+      )),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for WindowSize {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    match JsonValue::deserialize(deserializer)? {
+      JsonValue::Number(size) => size
+        .as_f64()
+        .map(Self::Unitless)
+        .ok_or_else(|| DeError::custom("the window size must be a number")),
+      JsonValue::String(size) => size.parse().map_err(DeError::custom),
+      _ => Err(DeError::custom(
+        "the window size must be a number or a string like `1200px` or `1200lpx`",
+      )),
+    }
+  }
+}
+
 /// The window configuration object.
 #[derive(PartialEq, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -62,20 +148,22 @@ pub struct WindowConfig {
   pub x: Option<f64>,
   /// The vertical position of the window's top left corner
   pub y: Option<f64>,
-  /// The window width.
+  /// The window width: a number, in logical pixels since config version 2 and physical pixels before,
+  /// or a string with the `px` (physical pixels) or `lpx` (logical pixels) unit.
+  /// The current webview runtime can't read the monitor scale factor yet, so both units are resolved without scaling.
   #[serde(default = "default_width")]
-  pub width: f64,
-  /// The window height.
+  pub width: WindowSize,
+  /// The window height, in the `width` units.
   #[serde(default = "default_height")]
-  pub height: f64,
-  /// The min window width.
-  pub min_width: Option<f64>,
-  /// The min window height.
-  pub min_height: Option<f64>,
-  /// The max window width.
-  pub max_width: Option<f64>,
-  /// The max window height.
-  pub max_height: Option<f64>,
+  pub height: WindowSize,
+  /// The min window width, in the `width` units.
+  pub min_width: Option<WindowSize>,
+  /// The min window height, in the `width` units.
+  pub min_height: Option<WindowSize>,
+  /// The max window width, in the `width` units.
+  pub max_width: Option<WindowSize>,
+  /// The max window height, in the `width` units.
+  pub max_height: Option<WindowSize>,
   /// Whether the window is resizable or not.
   #[serde(default = "default_resizable")]
   pub resizable: bool,
@@ -100,18 +188,35 @@ pub struct WindowConfig {
   /// Whether the window should always be on top of other windows.
   #[serde(default)]
   pub always_on_top: bool,
+  /// Whether the webview shows its native context menu. Defaults to `true`.
+  pub context_menu: Option<bool>,
+  /// Whether the window is the startup splashscreen, shown while the other windows load.
+  #[serde(default)]
+  pub splashscreen: bool,
+  /// Whether the window restores its saved position, size, maximized and fullscreen state and zoom factor.
+  #[serde(default)]
+  pub remember_state: bool,
+  /// The page zoom of the window.
+  #[serde(default)]
+  pub zoom: ZoomConfig,
+  /// Overrides `build > withGlobalTauri` for the window.
+  pub with_global_tauri: Option<bool>,
+  /// Whether the window gets the Tauri API and its IPC messages are handled. Defaults to `true`.
+  pub enable_tauri_api: Option<bool>,
+  /// The feature of the `features` config the window is created with.
+  pub enabled_when: Option<String>,
 }
 
 fn default_window_label() -> String {
   "main".to_string()
 }
 
-fn default_width() -> f64 {
-  800f64
+fn default_width() -> WindowSize {
+  WindowSize::Unitless(800f64)
 }
 
-fn default_height() -> f64 {
-  600f64
+fn default_height() -> WindowSize {
+  WindowSize::Unitless(600f64)
 }
 
 fn default_resizable() -> bool {
@@ -151,10 +256,66 @@ impl Default for WindowConfig {
       visible: default_visible(),
       decorations: default_decorations(),
       always_on_top: false,
+      context_menu: None,
+      splashscreen: false,
+      remember_state: false,
+      zoom: ZoomConfig::default(),
+      with_global_tauri: None,
+      enable_tauri_api: None,
+      enabled_when: None,
+    }
+  }
+}
+
+/// The page zoom of a window.
+#[derive(PartialEq, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoomConfig {
+  /// Whether `Ctrl` (`Cmd` on macOS) with `+`, `-` and `0` zooms the page in, out and back to `defaultFactor`.
+  /// The key presses the page handles itself are skipped.
+  #[serde(default)]
+  pub hotkeys_enabled: bool,
+  /// The zoom factor the window starts with, unless it remembers a saved one.
+  #[serde(default = "default_zoom_factor")]
+  pub default_factor: f64,
+  /// The minimum zoom factor.
+  #[serde(default = "default_zoom_min")]
+  pub min: f64,
+  /// The maximum zoom factor.
+  #[serde(default = "default_zoom_max")]
+  pub max: f64,
+}
+
+fn default_zoom_factor() -> f64 {
+  1.0
+}
+
+fn default_zoom_min() -> f64 {
+  0.25
+}
+
+fn default_zoom_max() -> f64 {
+  5.0
+}
+
+impl Default for ZoomConfig {
+  fn default() -> Self {
+    Self {
+      hotkeys_enabled: false,
+      default_factor: default_zoom_factor(),
+      min: default_zoom_min(),
+      max: default_zoom_max(),
     }
   }
 }
 
+impl ZoomConfig {
+  /// Clamps the factor to `min` and `max`.
+  pub fn clamp(&self, factor: f64) -> f64 {
+    factor.max(self.min).min(self.max)
+  }
+}
+
 /// The embedded server port.
 #[derive(PartialEq, Debug, Deserialize)]
 pub enum Port {
@@ -164,6 +325,28 @@ pub enum Port {
   Random,
 }
 
+/// How the embedded server compresses the assets.
+/// Already compressed formats, e.g. images, fonts and media, are never compressed.
+#[derive(PartialEq, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum EmbeddedServerCompression {
+  /// The assets are sent uncompressed.
+  None,
+  /// The assets are gzip compressed at build time.
+  Gzip,
+  /// The assets are brotli compressed at build time.
+  Brotli,
+  /// The assets are brotli compressed at build time,
+  /// and gzip compressed on request for the clients that don't accept brotli.
+  Auto,
+}
+
+impl Default for EmbeddedServerCompression {
+  fn default() -> Self {
+    Self::Gzip
+  }
+}
+
 /// The embeddedServer configuration object.
 #[derive(PartialEq, Deserialize, Debug)]
 #[serde(tag = "embeddedServer", rename_all = "camelCase")]
@@ -173,6 +356,7 @@ pub struct EmbeddedServerConfig {
   pub host: String,
   /// The embedded server port.
   /// If it's `random`, we'll generate one at runtime.
+  /// The port is exposed to the frontend on `window.__TAURI_EMBEDDED_SERVER__` and to the app processes on `TAURI_EMBEDDED_SERVER_PORT`.
   #[serde(default = "default_port", deserialize_with = "port_deserializer")]
   pub port: Port,
 
@@ -183,6 +367,56 @@ pub struct EmbeddedServerConfig {
     deserialize_with = "public_path_deserializer"
   )]
   pub public_path: String,
+  /// How the assets are compressed, negotiated with the `Accept-Encoding` request header.
+  #[serde(default)]
+  pub compression: EmbeddedServerCompression,
+  /// The response headers of the assets matching a glob pattern.
+  /// The entries are applied in order, so later entries override the headers of earlier ones.
+  #[serde(default)]
+  pub headers: Vec<AssetHeaders>,
+  /// Whether `index.html` is served for the GET requests of missing paths without an extension,
+  /// so the client-side routes of single-page apps, e.g. `/settings/profile`, survive a reload.
+  #[serde(default)]
+  pub spa_fallback: bool,
+  /// The HTML page served with a 404 status on the GET requests of missing assets, relative to the dist directory.
+  pub not_found_page: Option<String>,
+  /// The HTML page served with a 500 status when an asset can't be read, relative to the dist directory.
+  pub error_page: Option<String>,
+  /// Allows a `host` that isn't a loopback address, exposing the assets to the network.
+  #[serde(default)]
+  pub dangerous_allow_remote_host: bool,
+  /// The requests forwarded to an upstream server instead of being served from the assets.
+  #[serde(default)]
+  pub proxy: Vec<ProxyRule>,
+}
+
+/// A path prefix forwarded to an upstream server by the embedded server.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyRule {
+  /// The path prefix of the forwarded requests, matched on whole path segments.
+  pub path: String,
+  /// The `http` or `https` URL of the upstream server, validated by the CLI.
+  pub upstream: String,
+  /// Whether the path prefix is removed from the forwarded request path.
+  #[serde(default)]
+  pub strip_prefix: bool,
+  /// The headers set on the forwarded requests; a `None` value removes the header.
+  #[serde(default)]
+  pub request_headers: HashMap<String, Option<String>>,
+  /// The headers set on the upstream responses; a `None` value removes the header.
+  #[serde(default)]
+  pub response_headers: HashMap<String, Option<String>>,
+}
+
+/// The response headers of the assets matching a glob pattern.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetHeaders {
+  /// The glob pattern matched against the asset path relative to the dist directory, e.g. `assets/*.js`.
+  pub pattern: String,
+  /// The header names and values.
+  pub headers: HashMap<String, String>,
 }
 
 fn default_host() -> String {
@@ -203,6 +437,13 @@ impl Default for EmbeddedServerConfig {
       host: default_host(),
       port: default_port(),
       public_path: default_public_path(),
+      compression: Default::default(),
+      headers: Vec::new(),
+      spa_fallback: false,
+      not_found_page: None,
+      error_page: None,
+      dangerous_allow_remote_host: false,
+      proxy: Vec::new(),
     }
   }
 }
@@ -284,6 +525,22 @@ where
   deserializer.deserialize_any(PublicPathDeserializer {})
 }
 
+/// The type of a CLI argument value.
+#[derive(PartialEq, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum CliArgValueType {
+  /// Any string.
+  String,
+  /// An integer or floating point number.
+  Number,
+  /// `true` or `false`.
+  Boolean,
+  /// A file system path.
+  Path,
+  /// An absolute URL.
+  Url,
+}
+
 /// A CLI argument definition
 #[derive(PartialEq, Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase")]
@@ -360,6 +617,12 @@ pub struct CliArg {
   /// Allows specifying that an argument is required conditionally with the signature [arg, value]
   /// the requirement will only become valid if the `arg`'s value equals `${value}`.
   pub required_if_eq: Option<Vec<String>>,
+  /// Allows specifying that an argument is required if any of the [arg, value] conditions is met
+  /// i.e. the requirement will only become valid if one of the `arg`'s values equals its `${value}`.
+  pub required_if_eq_any: Option<Vec<(String, String)>>,
+  /// Allows specifying that an argument is required if all of the [arg, value] conditions are met
+  /// i.e. the requirement will only become valid if all of the `arg`'s values equal their `${value}`.
+  pub required_if_eq_all: Option<Vec<(String, String)>>,
   /// Requires that options use the --option=val syntax
   /// i.e. an equals between the option and associated value.
   pub require_equals: Option<bool>,
@@ -369,6 +632,37 @@ pub struct CliArg {
   /// It does not define position in the argument list as a whole. When utilized with multiple=true,
   /// only the last positional argument may be defined as multiple (i.e. the one with the highest index).
   pub index: Option<u64>,
+  /// The type of the argument value.
+  /// Invalid values fail with the usage error and the matches carry the values converted to the corresponding JSON type.
+  pub value_type: Option<CliArgValueType>,
+  /// A hidden long alias of the argument, e.g. the previous name of a renamed flag.
+  pub alias: Option<String>,
+  /// Hidden long aliases of the argument.
+  pub aliases: Option<Vec<String>>,
+  /// A hidden short alias of the argument, without the preceding -.
+  pub short_alias: Option<char>,
+  /// Long aliases of the argument shown on the help information.
+  pub visible_aliases: Option<Vec<String>>,
+  /// The order of the argument on the help information.
+  /// Arguments with the same order are sorted alphabetically.
+  pub display_order: Option<usize>,
+  /// The name of the group the argument belongs to, a shorthand for listing it on the group `args`.
+  pub group: Option<String>,
+}
+
+/// A group of CLI arguments.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CliGroup {
+  /// The unique group name.
+  pub name: String,
+  /// The names of the group arguments.
+  pub args: Option<Vec<String>>,
+  /// Whether one of the group arguments must be present.
+  pub required: Option<bool>,
+  /// Whether more than one of the group arguments can be present.
+  /// By default the group arguments are mutually exclusive.
+  pub multiple: Option<bool>,
 }
 
 /// The CLI root command definition.
@@ -380,7 +674,13 @@ pub struct CliConfig {
   before_help: Option<String>,
   after_help: Option<String>,
   args: Option<Vec<CliArg>>,
+  groups: Option<Vec<CliGroup>>,
   subcommands: Option<HashMap<String, CliConfig>>,
+  aliases: Option<Vec<String>>,
+  subcommand_required: Option<bool>,
+  args_conflicts_with_subcommands: Option<bool>,
+  allow_external_subcommands: Option<bool>,
+  legacy_matches: Option<bool>,
 }
 
 impl CliConfig {
@@ -389,11 +689,41 @@ impl CliConfig {
     self.args.as_ref()
   }
 
+  /// List of arg groups for the command
+  pub fn groups(&self) -> Option<&Vec<CliGroup>> {
+    self.groups.as_ref()
+  }
+
   /// List of subcommands of this command
   pub fn subcommands(&self) -> Option<&HashMap<String, CliConfig>> {
     self.subcommands.as_ref()
   }
 
+  /// The aliases the subcommand can also be invoked with.
+  pub fn aliases(&self) -> Option<&Vec<String>> {
+    self.aliases.as_ref()
+  }
+
+  /// Whether one of the subcommands must be used.
+  pub fn subcommand_required(&self) -> bool {
+    self.subcommand_required.unwrap_or(false)
+  }
+
+  /// Whether the args of the command can't be used with its subcommands.
+  pub fn args_conflicts_with_subcommands(&self) -> bool {
+    self.args_conflicts_with_subcommands.unwrap_or(false)
+  }
+
+  /// Whether unknown subcommands are accepted, with their args passed through.
+  pub fn allow_external_subcommands(&self) -> bool {
+    self.allow_external_subcommands.unwrap_or(false)
+  }
+
+  /// Whether the matches are emitted in the previous shape, without the arg `values`, `indices` and `source`.
+  pub fn legacy_matches(&self) -> bool {
+    self.legacy_matches.unwrap_or(false)
+  }
+
   /// Command description which will be shown on the help information.
   pub fn description(&self) -> Option<&String> {
     self.description.as_ref()
@@ -419,32 +749,614 @@ impl CliConfig {
   }
 }
 
+/// A file type the app can open.
+#[derive(PartialEq, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAssociation {
+  /// The file extensions, without the leading dot.
+  pub ext: Vec<String>,
+  /// The file type name.
+  pub name: String,
+}
+
+/// The deep link configuration.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkConfig {
+  /// The URL schemes the app handles, e.g. `myapp` for `myapp://auth/callback`.
+  #[serde(default)]
+  pub schemes: Vec<String>,
+}
+
 /// The bundler configuration object.
 #[derive(PartialEq, Deserialize, Debug)]
 #[serde(tag = "bundle", rename_all = "camelCase")]
 pub struct BundleConfig {
   /// The bundle identifier.
   pub identifier: String,
+  /// The file types the app can open. Ignored on macOS.
+  #[serde(default)]
+  pub file_associations: Vec<FileAssociation>,
+  /// The deep link configuration.
+  #[serde(default)]
+  pub deep_link: DeepLinkConfig,
 }
 
 impl Default for BundleConfig {
   fn default() -> Self {
     Self {
       identifier: String::from(""),
+      file_associations: Vec::new(),
+      deep_link: DeepLinkConfig::default(),
+    }
+  }
+}
+
+/// The allowlist configuration read at runtime. The API flags are read by the CLI, which enables the matching features.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistConfig {
+  /// The windows the frontend can create with the `createWindow` API. Any window can be created if it's not set.
+  #[serde(default)]
+  pub window_scope: Option<WindowScope>,
+  /// The paths the fs API can access, starting with a `$HOME`-like variable or absolute.
+  /// A `*` matches any characters of a path component, e.g. `$APPDATA/my-app/*`, and a `**` component matches
+  /// any number of components, e.g. `$APPDATA/my-app/**`. The symlinks are resolved before matching.
+  /// Any path can be accessed if it's not set.
+  #[serde(default)]
+  pub fs_scope: Option<Vec<String>>,
+  /// The dialog API constraints.
+  #[serde(default)]
+  pub dialog: DialogAllowlistConfig,
+  /// The http API constraints.
+  #[serde(default)]
+  pub http: HttpAllowlistConfig,
+}
+
+/// The http API constraints.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpAllowlistConfig {
+  /// The URLs the http API can request, checked again on every redirect.
+  /// A `*` matches any characters, except in the host where it matches a single label, e.g. `https://api.example.com/*`.
+  /// The scheme, the host and the path are matched separately, see [`url_pattern_match`].
+  /// Any URL can be requested if it's not set.
+  #[serde(default)]
+  pub scope: Option<Vec<String>>,
+  /// The limits applied to every request.
+  #[serde(default)]
+  pub defaults: HttpDefaultsConfig,
+}
+
+/// The limits of the http API requests. The per-client and per-request options can only lower them.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpDefaultsConfig {
+  /// The time a request waits for the connection, in milliseconds.
+  pub connect_timeout_ms: Option<u64>,
+  /// The time a request waits for the whole response, in milliseconds.
+  pub request_timeout_ms: Option<u64>,
+  /// The maximum number of redirects a request follows.
+  pub max_redirects: Option<usize>,
+  /// The methods of the requests. Any method if it's not set.
+  pub allowed_methods: Option<Vec<String>>,
+}
+
+/// The dialog API constraints.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogAllowlistConfig {
+  /// Whether the frontend can show the message and ask dialogs. Defaults to `true`.
+  #[serde(default)]
+  pub message: Option<bool>,
+  /// Whether the `defaultPath` can start with a `$HOME`-like variable, expanded like the `fsScope` paths.
+  #[serde(default)]
+  pub default_path_variables: bool,
+  /// Whether the open dialog can select directories. Defaults to `true`.
+  #[serde(default)]
+  pub directory: Option<bool>,
+  /// Whether the open dialog can select multiple files. Defaults to `true`.
+  #[serde(default)]
+  pub multiple: Option<bool>,
+  /// Whether the picked paths are added to the `fsScope` until the app exits.
+  #[serde(default)]
+  pub auto_extend_fs_scope: bool,
+}
+
+impl DialogAllowlistConfig {
+  /// Whether the frontend can show the message and ask dialogs.
+  pub fn allows_message(&self) -> bool {
+    self.message.unwrap_or(true)
+  }
+
+  /// Whether the open dialog can select directories.
+  pub fn allows_directory(&self) -> bool {
+    self.directory.unwrap_or(true)
+  }
+
+  /// Whether the open dialog can select multiple files.
+  pub fn allows_multiple(&self) -> bool {
+    self.multiple.unwrap_or(true)
+  }
+}
+
+/// The windows the frontend can create at runtime.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowScope {
+  /// The labels of the windows that can be created, besides the ones on `tauri > windows`.
+  #[serde(default)]
+  pub labels: Vec<String>,
+  /// The URLs the created windows can load: `app`, an app path, or an external URL.
+  /// A `*` matches any characters, except in the host of a URL where it matches a single label,
+  /// e.g. `https://*.tauri.studio/*`. See [`url_pattern_match`].
+  #[serde(default)]
+  pub urls: Vec<WindowUrl>,
+}
+
+impl WindowScope {
+  /// Whether a created window can load the URL.
+  pub fn allows_url(&self, url: &WindowUrl) -> bool {
+    self.urls.iter().any(|pattern| match (pattern, url) {
+      (WindowUrl::App, WindowUrl::App) => true,
+      (WindowUrl::Custom(pattern), WindowUrl::Custom(url)) => url_pattern_match(pattern, url),
+      _ => false,
+    })
+  }
+}
+
+/// Whether the text matches the pattern, where `*` matches any characters.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+  let mut parts = pattern.split('*');
+  let first = parts.next().unwrap_or_default();
+  if !text.starts_with(first) {
+    return false;
+  }
+  let mut rest = &text[first.len()..];
+  let parts: Vec<&str> = parts.collect();
+  let (last, middle) = match parts.split_last() {
+    Some(split) => split,
+    // no `*`
+    None => return rest.is_empty(),
+  };
+  for part in middle {
+    match rest.find(part) {
+      Some(index) => rest = &rest[index + part.len()..],
+      None => return false,
     }
   }
+  rest.ends_with(last)
+}
+
+/// Whether the URL matches the pattern, matching the scheme, the host and the path separately,
+/// so a `*` can't match across them: `https://*.tauri.studio/*` doesn't match `https://evil.com/.tauri.studio/x`.
+/// In the host, a `*` matches a single label, or any host if it's the whole host.
+/// A pattern without a path only matches the root path. The patterns without a scheme, e.g. app paths,
+/// are matched with [`wildcard_match`].
+pub fn url_pattern_match(pattern: &str, url: &str) -> bool {
+  let scheme_end = match pattern.find("://") {
+    Some(index) => index,
+    None => return wildcard_match(pattern, url),
+  };
+  let url = match Url::parse(url) {
+    Ok(url) => url,
+    Err(_) => return false,
+  };
+  let scheme_pattern = pattern[..scheme_end].to_ascii_lowercase();
+  let rest = &pattern[scheme_end + 3..];
+  let (host_pattern, path_pattern) = match rest.find('/') {
+    Some(index) => rest.split_at(index),
+    None => (rest, "/"),
+  };
+  // the host and the explicit port, without the user info
+  let host = &url[url::Position::BeforeHost..url::Position::AfterPort];
+  let host_pattern = host_pattern.to_ascii_lowercase();
+  let host_matches = host_pattern == "*" || {
+    let labels: Vec<&str> = host.split('.').collect();
+    let pattern_labels: Vec<&str> = host_pattern.split('.').collect();
+    labels.len() == pattern_labels.len()
+      && pattern_labels
+        .iter()
+        .zip(&labels)
+        .all(|(pattern, label)| wildcard_match(pattern, label))
+  };
+  wildcard_match(&scheme_pattern, url.scheme())
+    && host_matches
+    && wildcard_match(path_pattern, &url[url::Position::BeforePath..])
 }
 
 fn default_window_config() -> Vec<WindowConfig> {
   vec![Default::default()]
 }
 
+/// Deserializes the list of windows, or the map of window labels to their config sorted by label.
+fn deserialize_windows<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Vec<WindowConfig>, D::Error> {
+  struct WindowListVisitor;
+
+  impl<'de> Visitor<'de> for WindowListVisitor {
+    type Value = Vec<WindowConfig>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      formatter.write_str("a list of windows or a map of window labels to their config")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+      let mut windows = Vec::new();
+      while let Some(window) = seq.next_element()? {
+        windows.push(window);
+      }
+      Ok(windows)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+      let mut windows: Vec<WindowConfig> = Vec::new();
+      while let Some(label) = map.next_key::<String>()? {
+        let mut window: WindowConfig = map.next_value()?;
+        window.label = label;
+        windows.push(window);
+      }
+      windows.sort_by(|a, b| a.label.cmp(&b.label));
+      Ok(windows)
+    }
+  }
+
+  deserializer.deserialize_any(WindowListVisitor)
+}
+
+/// The security configuration.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityConfig {
+  /// The remote domains allowed to send IPC messages on the listed windows.
+  #[serde(default)]
+  pub dangerous_remote_domain_ipc_access: Vec<RemoteDomainAccessScope>,
+  /// Whether the core JS prototypes and the `window.__TAURI__` object are frozen before the page scripts run.
+  #[serde(default)]
+  pub freeze_prototype: bool,
+  /// The limits of the IPC messages of each window.
+  #[serde(default)]
+  pub ipc: IpcConfig,
+  /// Whether the bundled resources are checked against the manifest embedded at build time before the app starts.
+  #[serde(default)]
+  pub verify_resources: bool,
+}
+
+/// The limits of the IPC messages of each window. The unset and `0` limits are unlimited.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcConfig {
+  /// The maximum size of a message, in bytes.
+  pub max_payload_bytes: Option<u64>,
+  /// The maximum number of messages a window sends per second.
+  pub max_requests_per_second: Option<u32>,
+  /// The limits of the commands, keyed by the `cmd` of the message, checked on top of the window limits.
+  #[serde(default)]
+  pub per_command_limits: HashMap<String, IpcCommandLimits>,
+}
+
+/// The limits of the messages of a command. The unset and `0` limits are unlimited.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IpcCommandLimits {
+  /// The maximum size of a message, in bytes.
+  pub max_payload_bytes: Option<u64>,
+  /// The maximum number of messages of the command a window sends per second.
+  pub max_requests_per_second: Option<u32>,
+}
+
+/// A remote domain allowed to send IPC messages.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteDomainAccessScope {
+  /// The domain of the page origin, e.g. `partner.example.com`.
+  /// Only the pages served over `https` from the domain get access, checked on every message.
+  pub domain: String,
+  /// The labels of the windows where the domain has IPC access.
+  pub windows: Vec<String>,
+  /// Whether the domain can use the Tauri API modules, subject to the allowlist.
+  #[serde(default)]
+  pub enable_tauri_api: bool,
+  /// The commands of the invoke handler the domain can call.
+  #[serde(default)]
+  pub commands: Vec<String>,
+}
+
+/// How the updater runs the Windows installer.
+#[derive(PartialEq, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowsUpdateInstallMode {
+  /// Shows a basic UI with the installation progress and no prompts.
+  BasicUi,
+  /// Installs without any UI.
+  Quiet,
+  /// Shows the installation progress and closes the installer once it's done.
+  /// The `-setup.exe` installers run silently instead.
+  Passive,
+}
+
+impl Default for WindowsUpdateInstallMode {
+  fn default() -> Self {
+    Self::Passive
+  }
+}
+
+impl WindowsUpdateInstallMode {
+  /// The `msiexec` arguments of the install mode.
+  pub fn msiexec_args(&self) -> &'static [&'static str] {
+    match self {
+      Self::BasicUi => &["/qb+"],
+      Self::Quiet => &["/quiet"],
+      Self::Passive => &["/passive"],
+    }
+  }
+
+  /// The arguments of the NSIS `-setup.exe` installers for the install mode.
+  /// They have no basic UI nor passive mode: they show their pages or run silently.
+  pub fn nsis_args(&self) -> &'static [&'static str] {
+    match self {
+      Self::BasicUi => &[],
+      Self::Quiet | Self::Passive => &["/S"],
+    }
+  }
+}
+
+/// The updater configuration on Windows.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdaterWindowsConfig {
+  /// How the downloaded installer is run.
+  #[serde(default)]
+  pub install_mode: WindowsUpdateInstallMode,
+  /// Extra arguments passed to the installer.
+  #[serde(default)]
+  pub installer_args: Vec<String>,
+}
+
+/// The updater configuration.
+#[derive(PartialEq, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdaterConfig {
+  /// Whether the updater is enabled.
+  #[serde(default)]
+  pub active: bool,
+  /// The updater configuration on Windows.
+  #[serde(default)]
+  pub windows: UpdaterWindowsConfig,
+  /// Whether the app is relaunched as soon as the update is installed, except by the Windows installers.
+  #[serde(default = "default_relaunch")]
+  pub relaunch: bool,
+  /// The URLs of the update manifests, tried in order until one responds.
+  #[serde(default)]
+  pub endpoints: Vec<String>,
+  /// The base64 encoded minisign public key the downloaded updates are verified with.
+  /// Required when the updater is active: the updates are only installed once their signature is verified.
+  pub pubkey: Option<String>,
+  /// The proxy of the update requests. Defaults to the `HTTPS_PROXY` and `HTTP_PROXY` environment variables.
+  pub proxy: Option<String>,
+  /// The headers of the update requests, with `${VAR}` environment variables substituted when they're sent.
+  #[serde(default)]
+  pub headers: HashMap<String, String>,
+}
+
+fn default_relaunch() -> bool {
+  true
+}
+
+impl Default for UpdaterConfig {
+  fn default() -> Self {
+    Self {
+      active: false,
+      windows: UpdaterWindowsConfig::default(),
+      relaunch: default_relaunch(),
+      endpoints: Vec::new(),
+      pubkey: None,
+      proxy: None,
+      headers: HashMap::new(),
+    }
+  }
+}
+
+/// The action of a global shortcut.
+#[derive(PartialEq, Debug, Clone)]
+pub enum GlobalShortcutAction {
+  /// `toggleWindow:<label>`: shows the window if it's hidden, or hides it.
+  ToggleWindow(String),
+  /// `show` or `show:<label>`: shows and focuses the window, or the main window.
+  Show(Option<String>),
+  /// `hide` or `hide:<label>`: hides the window, or the main window.
+  Hide(Option<String>),
+  /// `quit`: quits the app.
+  Quit,
+  /// `emit:<event>`: emits the event to the windows, with the accelerator as payload.
+  Emit(String),
+}
+
+impl FromStr for GlobalShortcutAction {
+  type Err = String;
+
+  fn from_str(action: &str) -> Result<Self, Self::Err> {
+    let (name, argument) = match action.find(':') {
+      Some(index) => (&action[..index], Some(&action[index + 1..])),
+      None => (action, None),
+    };
+    let argument = argument.filter(|argument| !argument.is_empty());
+    match (name, argument) {
+      ("toggleWindow", Some(label)) => Ok(Self::ToggleWindow(label.to_string())),
+      ("show", label) => Ok(Self::Show(label.map(ToString::to_string))),
+      ("hide", label) => Ok(Self::Hide(label.map(ToString::to_string))),
+      ("quit", None) => Ok(Self::Quit),
+      ("emit", Some(event)) => Ok(Self::Emit(event.to_string())),
+      _ => Err(format!(
+        "`{}` is not a valid global shortcut action; use `toggleWindow:<label>`, `show`, `hide`, `quit` or `emit:<event>`",
+        action
+      )),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for GlobalShortcutAction {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    String::deserialize(deserializer)?
+      .parse()
+      .map_err(DeError::custom)
+  }
+}
+
+/// The single instance configuration.
+#[derive(PartialEq, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SingleInstanceConfig {
+  /// Whether the running instance focuses its main window when the app is launched again.
+  #[serde(default = "default_focus_existing")]
+  pub focus_existing: bool,
+  /// Whether the CLI args of the new instances are forwarded on the `second-instance` event.
+  #[serde(default = "default_forward_args")]
+  pub forward_args: bool,
+}
+
+fn default_focus_existing() -> bool {
+  true
+}
+
+fn default_forward_args() -> bool {
+  true
+}
+
+impl Default for SingleInstanceConfig {
+  fn default() -> Self {
+    Self {
+      focus_existing: default_focus_existing(),
+      forward_args: default_forward_args(),
+    }
+  }
+}
+
+/// Deserializes the single instance config, `true` enabling it with the default options.
+fn deserialize_single_instance<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Option<SingleInstanceConfig>, D::Error> {
+  match JsonValue::deserialize(deserializer)? {
+    JsonValue::Bool(true) => Ok(Some(SingleInstanceConfig::default())),
+    JsonValue::Bool(false) | JsonValue::Null => Ok(None),
+    value => serde_json::from_value(value)
+      .map(Some)
+      .map_err(DeError::custom),
+  }
+}
+
+/// The notification permission the app has before the user answers the permission prompt.
+#[derive(PartialEq, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationPermission {
+  /// Notifications are allowed without prompting the user.
+  Granted,
+  /// The user is prompted when the app requests the permission.
+  Prompt,
+  /// Notifications are denied without prompting the user.
+  Denied,
+}
+
+impl Default for NotificationPermission {
+  fn default() -> Self {
+    Self::Prompt
+  }
+}
+
+/// The notification configuration.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationConfig {
+  /// The identifier the notifications are sent with. Defaults to the bundle identifier.
+  pub identifier: Option<String>,
+  /// The notification permission before the user answers the permission prompt.
+  #[serde(default)]
+  pub default_permission: NotificationPermission,
+}
+
+/// The names of the app directories, created on the user data, cache and log directories.
+#[derive(PartialEq, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PathsConfig {
+  /// The name of the app data directory. Defaults to the bundle identifier.
+  pub app_data_dir_name: Option<String>,
+  /// The name of the app cache directory. Defaults to the bundle identifier.
+  pub cache_dir_name: Option<String>,
+  /// The name of the app log directory. Defaults to the bundle identifier.
+  pub log_dir_name: Option<String>,
+  /// The previous bundle identifiers, whose directories are moved to the current ones if they don't exist yet.
+  #[serde(default)]
+  pub migrate_from: Vec<String>,
+}
+
+/// The power configuration: the idle and resume events and the wake lock.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerConfig {
+  /// The time without user input after which the `idle` event is emitted, in seconds.
+  pub idle_timeout_seconds: Option<u64>,
+  /// Whether the `resume` event is emitted when the system resumes after sleep.
+  #[serde(default)]
+  pub emit_resume: bool,
+  /// Whether the system and the display are kept awake from the app start.
+  #[serde(default)]
+  pub prevent_sleep: bool,
+}
+
+/// The startup splashscreen configuration.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SplashscreenConfig {
+  /// The splashscreen HTML, unless a window is flagged with `splashscreen`.
+  pub html: Option<String>,
+  /// The splashscreen width.
+  #[serde(default = "default_splashscreen_width")]
+  pub width: WindowSize,
+  /// The splashscreen height.
+  #[serde(default = "default_splashscreen_height")]
+  pub height: WindowSize,
+  /// The minimum time the splashscreen is shown, in milliseconds.
+  #[serde(default)]
+  pub minimum_duration_ms: u64,
+  /// The time after which the other windows are shown even if the frontend didn't close the splashscreen, in milliseconds.
+  #[serde(default = "default_splashscreen_timeout")]
+  pub timeout_ms: u64,
+}
+
+fn default_splashscreen_width() -> WindowSize {
+  WindowSize::Unitless(400f64)
+}
+
+fn default_splashscreen_height() -> WindowSize {
+  WindowSize::Unitless(300f64)
+}
+
+fn default_splashscreen_timeout() -> u64 {
+  10000
+}
+
+impl Default for SplashscreenConfig {
+  fn default() -> Self {
+    Self {
+      html: None,
+      width: default_splashscreen_width(),
+      height: default_splashscreen_height(),
+      minimum_duration_ms: 0,
+      timeout_ms: default_splashscreen_timeout(),
+    }
+  }
+}
+
 /// The Tauri configuration object.
 #[derive(PartialEq, Deserialize, Debug)]
 #[serde(tag = "tauri", rename_all = "camelCase")]
 pub struct TauriConfig {
   /// The window configuration.
-  #[serde(default = "default_window_config")]
+  #[serde(
+    default = "default_window_config",
+    deserialize_with = "deserialize_windows"
+  )]
   pub windows: Vec<WindowConfig>,
   /// The embeddedServer configuration.
   #[serde(default)]
@@ -455,6 +1367,72 @@ pub struct TauriConfig {
   /// The bundler configuration.
   #[serde(default)]
   pub bundle: BundleConfig,
+  /// The security configuration.
+  #[serde(default)]
+  pub security: SecurityConfig,
+  /// The updater configuration.
+  #[serde(default)]
+  pub updater: UpdaterConfig,
+  /// The single instance configuration. When set, launching the app again forwards the launch to the running instance.
+  #[serde(default, deserialize_with = "deserialize_single_instance")]
+  pub single_instance: Option<SingleInstanceConfig>,
+  /// The global shortcuts: a map of accelerators, e.g. `CommandOrControl+Shift+Space`, to their action.
+  #[serde(default)]
+  pub global_shortcuts: HashMap<String, GlobalShortcutAction>,
+  /// The notification configuration.
+  #[serde(default)]
+  pub notification: NotificationConfig,
+  /// The startup splashscreen configuration.
+  #[serde(default)]
+  pub splashscreen: Option<SplashscreenConfig>,
+  /// The allowlist configuration.
+  #[serde(default)]
+  pub allowlist: AllowlistConfig,
+  /// The app directories configuration.
+  #[serde(default)]
+  pub paths: PathsConfig,
+  /// The power configuration.
+  #[serde(default)]
+  pub power: PowerConfig,
+}
+
+impl TauriConfig {
+  /// Checks that the frontend can create the window: any window if there's no `allowlist > windowScope`,
+  /// otherwise the windows of the scope labels and the ones on `tauri > windows`, loading a scope URL or,
+  /// for the static windows, their own URL. Returns the reason otherwise.
+  pub fn check_window_scope(&self, label: &str, url: &WindowUrl) -> Result<(), String> {
+    let scope = match &self.allowlist.window_scope {
+      Some(scope) => scope,
+      None => return Ok(()),
+    };
+    let static_window = self.windows.iter().find(|window| window.label == label);
+    if static_window.is_none() && !scope.labels.iter().any(|scoped| scoped == label) {
+      return Err(format!(
+        "the `{}` label isn't on `tauri > allowlist > windowScope > labels`",
+        label
+      ));
+    }
+    if scope.allows_url(url) || static_window.map_or(false, |window| &window.url == url) {
+      Ok(())
+    } else {
+      Err(format!(
+        "the URL `{}` isn't on `tauri > allowlist > windowScope > urls`",
+        match url {
+          WindowUrl::App => "app",
+          WindowUrl::Custom(url) => url,
+        }
+      ))
+    }
+  }
+
+  /// The identifier the notifications are sent with: the `notification > identifier` override or the bundle identifier.
+  pub fn notification_identifier(&self) -> &str {
+    self
+      .notification
+      .identifier
+      .as_deref()
+      .unwrap_or(&self.bundle.identifier)
+  }
 }
 
 impl Default for TauriConfig {
@@ -464,6 +1442,15 @@ impl Default for TauriConfig {
       embedded_server: EmbeddedServerConfig::default(),
       cli: None,
       bundle: BundleConfig::default(),
+      security: SecurityConfig::default(),
+      updater: UpdaterConfig::default(),
+      single_instance: None,
+      global_shortcuts: HashMap::new(),
+      notification: NotificationConfig::default(),
+      splashscreen: None,
+      allowlist: AllowlistConfig::default(),
+      paths: PathsConfig::default(),
+      power: PowerConfig::default(),
     }
   }
 }
@@ -474,14 +1461,70 @@ impl Default for TauriConfig {
 pub struct BuildConfig {
   /// the devPath config.
   #[serde(default = "default_dev_path")]
-  pub dev_path: String,
+  pub dev_path: AppUrl,
   /// the dist config.
   #[serde(default = "default_dist_path")]
   pub dist_dir: String,
+  /// the injectedConstants config.
+  #[serde(default)]
+  pub injected_constants: HashMap<String, JsonValue>,
+  /// How long the debug app waits for the dev server to reload its windows if it wasn't up when the app started.
+  #[serde(default = "default_dev_server_timeout_ms")]
+  pub dev_server_timeout_ms: u64,
+  /// Whether the windows get the API modules on `window.__TAURI__`, unless they override it.
+  #[serde(default)]
+  pub with_global_tauri: bool,
+}
+
+fn default_dev_server_timeout_ms() -> u64 {
+  30000
 }
 
-fn default_dev_path() -> String {
-  "http://localhost:8080".to_string()
+fn default_dev_path() -> AppUrl {
+  AppUrl::Url(Url::parse("http://localhost:8080").unwrap())
+}
+
+/// The devPath config.
+#[derive(PartialEq, Debug, Clone)]
+pub enum AppUrl {
+  /// A dev server URL, e.g. `http://localhost:8080`.
+  Url(Url),
+  /// The directory of the app's files.
+  Path(PathBuf),
+  /// The app's files, for setups without a bundler.
+  Files(Vec<PathBuf>),
+}
+
+impl<'de> Deserialize<'de> for AppUrl {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    // only the `http` and `https` schemes are URLs, so Windows paths like `D:\http-assets` are paths
+    let value = JsonValue::deserialize(deserializer)?;
+    match value {
+      JsonValue::String(value) => {
+        let scheme = value.split("://").next().unwrap_or_default();
+        if value.contains("://")
+          && (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+        {
+          Url::parse(&value)
+            .map(Self::Url)
+            .map_err(|e| DeError::custom(format!("`{}` is not a valid URL: {}", value, e)))
+        } else {
+          Ok(Self::Path(PathBuf::from(value)))
+        }
+      }
+      JsonValue::Array(files) => files
+        .into_iter()
+        .map(|file| match file {
+          JsonValue::String(file) => Ok(PathBuf::from(file)),
+          _ => Err(DeError::custom("the devPath files must be strings")),
+        })
+        .collect::<Result<_, _>>()
+        .map(Self::Files),
+      _ => Err(DeError::custom(
+        "the devPath must be a URL, a directory path or a list of file paths",
+      )),
+    }
+  }
 }
 
 fn default_dist_path() -> String {
@@ -493,14 +1536,30 @@ impl Default for BuildConfig {
     Self {
       dev_path: default_dev_path(),
       dist_dir: default_dist_path(),
+      injected_constants: HashMap::new(),
+      dev_server_timeout_ms: default_dev_server_timeout_ms(),
+      with_global_tauri: false,
     }
   }
 }
 
+/// The package configuration.
+#[derive(PartialEq, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageConfig {
+  /// The app name.
+  pub product_name: Option<String>,
+  /// The app version, resolved by the CLI.
+  pub version: Option<String>,
+}
+
 /// The tauri.conf.json mapper.
 #[derive(Debug, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
+  /// The package configuration.
+  #[serde(default)]
+  pub package: PackageConfig,
   /// The Tauri configuration.
   #[serde(default)]
   pub tauri: TauriConfig,
@@ -510,20 +1569,66 @@ pub struct Config {
   /// The plugins config.
   #[serde(default)]
   pub plugins: PluginConfig,
+  /// The version of the config format, set by `tauri migrate`.
+  #[serde(default)]
+  pub config_version: u64,
+  /// The feature flags.
+  #[serde(default)]
+  pub features: HashMap<String, bool>,
 }
 
-/// The plugin configs holds a HashMap mapping a plugin name to its configuration object.
+impl Config {
+  /// Whether the feature of the `features` config is enabled. The undeclared features are disabled.
+  pub fn feature_enabled(&self, feature: &str) -> bool {
+    self.features.get(feature).copied().unwrap_or(false)
+  }
+
+  /// Removes the windows whose `enabledWhen` feature is disabled.
+  /// The CLI already removes them, but the config embedded without it still references the features.
+  pub fn apply_feature_gates(&mut self) {
+    let features = &self.features;
+    self.tauri.windows.retain(|window| {
+      window.enabled_when.as_ref().map_or(true, |feature| {
+        features.get(feature).copied().unwrap_or(false)
+      })
+    });
+  }
+
+  /// Gives the window sizes without a unit the unit of the config version,
+  /// so the configs older than version 2 keep their physical pixel sizes.
+  pub fn pin_window_size_units(&mut self) {
+    let config_version = self.config_version;
+    for window in &mut self.tauri.windows {
+      for size in vec![&mut window.width, &mut window.height]
+        .into_iter()
+        .chain(window.min_width.as_mut())
+        .chain(window.min_height.as_mut())
+        .chain(window.max_width.as_mut())
+        .chain(window.max_height.as_mut())
+      {
+        size.pin_unit(config_version);
+      }
+    }
+    if let Some(splashscreen) = &mut self.tauri.splashscreen {
+      splashscreen.width.pin_unit(config_version);
+      splashscreen.height.pin_unit(config_version);
+    }
+  }
+}
+
+/// The plugin configs holds a HashMap mapping a plugin name to its configuration value,
+/// usually an object but any JSON value is accepted.
 #[derive(Debug, Clone, Default, PartialEq, Deserialize)]
 pub struct PluginConfig(HashMap<String, JsonValue>);
 
 impl PluginConfig {
-  /// Gets a plugin configuration.
-  pub fn get<S: AsRef<str>>(&self, plugin_name: S) -> String {
+  /// Gets a plugin configuration, an empty object if the plugin has no configuration.
+  pub fn get<S: AsRef<str>>(&self, plugin_name: S) -> JsonValue {
     self
       .0
       .get(plugin_name.as_ref())
-      .map(|config| config.to_string())
-      .unwrap_or_else(|| "{}".to_string())
+      .cloned()
+      .unwrap_or_else(|| JsonValue::Object(Default::default()))
   }
 }
 
@@ -533,6 +1638,187 @@ mod test {
 
   // TODO: create a test that compares a config to a json config
 
+  #[test]
+  // only the http and https schemes are dev server URLs
+  fn dev_path_variants() {
+    let dev_path = |json: &str| serde_json::from_str::<AppUrl>(json).unwrap();
+    assert_eq!(
+      dev_path(r#""https://localhost:3000""#),
+      AppUrl::Url(Url::parse("https://localhost:3000").unwrap())
+    );
+    assert_eq!(
+      dev_path(r#""D:\\http-assets""#),
+      AppUrl::Path(PathBuf::from("D:\\http-assets"))
+    );
+    assert_eq!(
+      dev_path(r#""../dist""#),
+      AppUrl::Path(PathBuf::from("../dist"))
+    );
+    assert_eq!(
+      dev_path(r#"["../index.html", "../main.js"]"#),
+      AppUrl::Files(vec![
+        PathBuf::from("../index.html"),
+        PathBuf::from("../main.js")
+      ])
+    );
+    assert!(serde_json::from_str::<AppUrl>(r#""http://""#).is_err());
+  }
+
+  #[test]
+  // the windows can be a map of labels to their config
+  fn windows_map() {
+    let config: Config = serde_json::from_str(
+      r#"{ "tauri": { "windows": { "settings": { "title": "Settings" }, "main": { "width": 1024 } } } }"#,
+    )
+    .unwrap();
+    let windows = config.tauri.windows;
+    let labels: Vec<&str> = windows.iter().map(|window| window.label.as_str()).collect();
+    assert_eq!(labels, vec!["main", "settings"]);
+    assert_eq!(windows[0].width, WindowSize::Unitless(1024f64));
+    assert_eq!(windows[1].title, "Settings");
+  }
+
+  #[test]
+  fn global_shortcut_actions() {
+    for (action, expected) in &[
+      (
+        "toggleWindow:main",
+        GlobalShortcutAction::ToggleWindow("main".into()),
+      ),
+      ("show", GlobalShortcutAction::Show(None)),
+      (
+        "hide:tools",
+        GlobalShortcutAction::Hide(Some("tools".into())),
+      ),
+      ("quit", GlobalShortcutAction::Quit),
+      ("emit:capture", GlobalShortcutAction::Emit("capture".into())),
+    ] {
+      assert_eq!(&action.parse::<GlobalShortcutAction>().unwrap(), expected);
+    }
+    for action in &["toggleWindow", "quit:now", "emit:", "focus"] {
+      assert!(
+        action.parse::<GlobalShortcutAction>().is_err(),
+        "{}",
+        action
+      );
+    }
+  }
+
+  #[test]
+  fn notification_identifier() {
+    let mut config = TauriConfig::default();
+    config.bundle.identifier = "com.tauri.app".into();
+    config.notification = serde_json::from_value(serde_json::json!({
+      "defaultPermission": "granted"
+    }))
+    .unwrap();
+    assert_eq!(config.notification_identifier(), "com.tauri.app");
+    assert_eq!(
+      config.notification.default_permission,
+      NotificationPermission::Granted
+    );
+
+    config.notification.identifier = Some("com.tauri.dev".into());
+    assert_eq!(config.notification_identifier(), "com.tauri.dev");
+  }
+
+  #[test]
+  // `true` enables the single instance mode with the default options
+  fn single_instance_variants() {
+    let single_instance = |json: JsonValue| {
+      serde_json::from_value::<TauriConfig>(serde_json::json!({ "singleInstance": json }))
+        .unwrap()
+        .single_instance
+    };
+    assert_eq!(
+      single_instance(serde_json::json!(true)),
+      Some(SingleInstanceConfig::default())
+    );
+    assert_eq!(single_instance(serde_json::json!(false)), None);
+    assert_eq!(
+      single_instance(serde_json::json!({ "forwardArgs": false })),
+      Some(SingleInstanceConfig {
+        focus_existing: true,
+        forward_args: false,
+      })
+    );
+  }
+
+  #[test]
+  // the unitless window sizes are physical pixels before config version 2 and logical pixels after
+  fn window_sizes() {
+    let parse = |config_version: u64| {
+      let mut config: Config = serde_json::from_value(serde_json::json!({
+        "configVersion": config_version,
+        "tauri": { "windows": [{ "width": 1200, "height": "800lpx", "minWidth": "600lpx", "maxWidth": "2000px" }] }
+      }))
+      .unwrap();
+      config.pin_window_size_units();
+      config.tauri.windows.remove(0)
+    };
+
+    let window = parse(1);
+    assert_eq!(window.width, WindowSize::Physical(1200f64));
+    assert_eq!(window.height, WindowSize::Logical(800f64));
+    assert_eq!(window.min_width, Some(WindowSize::Logical(600f64)));
+    assert_eq!(window.max_width, Some(WindowSize::Physical(2000f64)));
+    assert_eq!(
+      parse(LOGICAL_WINDOW_SIZE_VERSION).width,
+      WindowSize::Logical(1200f64)
+    );
+
+    assert_eq!(WindowSize::Logical(600f64).to_physical(2f64), 1200f64);
+    assert_eq!(WindowSize::Physical(600f64).to_physical(2f64), 600f64);
+    assert!("-1px".parse::<WindowSize>().is_err());
+    assert!("80em".parse::<WindowSize>().is_err());
+    assert!("80%".parse::<WindowSize>().is_err());
+  }
+
+  #[test]
+  // the plugin configs can be any JSON value, and the missing ones are an empty object
+  fn plugin_config_values() {
+    let config: Config = serde_json::from_str(
+      r#"{
+        "plugins": {
+          "object": { "enabled": true },
+          "string": "~/.cache",
+          "array": ["assets", "docs"],
+          "number": 3,
+          "null": null
+        }
+      }"#,
+    )
+    .unwrap();
+    let plugins = config.plugins;
+    assert_eq!(
+      plugins.get("object"),
+      serde_json::json!({ "enabled": true })
+    );
+    assert_eq!(plugins.get("string"), serde_json::json!("~/.cache"));
+    assert_eq!(plugins.get("array"), serde_json::json!(["assets", "docs"]));
+    assert_eq!(plugins.get("number"), serde_json::json!(3));
+    assert_eq!(plugins.get("null"), JsonValue::Null);
+    assert_eq!(plugins.get("missing"), serde_json::json!({}));
+  }
+
+  #[test]
+  // the prototypes are only frozen when enabled, so the frameworks extending them keep working
+  fn freeze_prototype() {
+    let security = |json: &str| serde_json::from_str::<SecurityConfig>(json);
+    assert!(!security("{}").unwrap().freeze_prototype);
+    assert!(
+      !security(r#"{ "freezePrototype": false }"#)
+        .unwrap()
+        .freeze_prototype
+    );
+    assert!(
+      security(r#"{ "freezePrototype": true }"#)
+        .unwrap()
+        .freeze_prototype
+    );
+    assert!(security(r#"{ "freezePrototype": "yes" }"#).is_err());
+  }
+
   #[test]
   // test all of the default functions
   fn test_defaults() {
@@ -558,8 +1844,8 @@ mod test {
         url: WindowUrl::App,
         x: None,
         y: None,
-        width: 800f64,
-        height: 600f64,
+        width: WindowSize::Unitless(800f64),
+        height: WindowSize::Unitless(600f64),
         min_width: None,
         min_height: None,
         max_width: None,
@@ -572,22 +1858,69 @@ mod test {
         visible: true,
         decorations: true,
         always_on_top: false,
+        context_menu: None,
+        splashscreen: false,
+        remember_state: false,
+        zoom: ZoomConfig::default(),
+        with_global_tauri: None,
+        enable_tauri_api: None,
+        enabled_when: None,
       }],
       embedded_server: EmbeddedServerConfig {
         host: String::from("http://127.0.0.1"),
         port: Port::Random,
         public_path: "/".into(),
+        compression: EmbeddedServerCompression::Gzip,
+        headers: Vec::new(),
+        spa_fallback: false,
+        not_found_page: None,
+        error_page: None,
+        dangerous_allow_remote_host: false,
+        proxy: Vec::new(),
       },
       bundle: BundleConfig {
         identifier: String::from(""),
+        file_associations: Vec::new(),
+        deep_link: DeepLinkConfig { schemes: vec![] },
       },
       cli: None,
+      security: SecurityConfig {
+        dangerous_remote_domain_ipc_access: Vec::new(),
+        freeze_prototype: false,
+        ipc: IpcConfig::default(),
+        verify_resources: false,
+      },
+      updater: UpdaterConfig {
+        active: false,
+        windows: UpdaterWindowsConfig {
+          install_mode: WindowsUpdateInstallMode::Passive,
+          installer_args: Vec::new(),
+        },
+        relaunch: true,
+        endpoints: Vec::new(),
+        pubkey: None,
+        proxy: None,
+        headers: HashMap::new(),
+      },
+      single_instance: None,
+      global_shortcuts: HashMap::new(),
+      notification: NotificationConfig {
+        identifier: None,
+        default_permission: NotificationPermission::Prompt,
+      },
+      splashscreen: None,
+      allowlist: AllowlistConfig::default(),
+      paths: PathsConfig::default(),
+      power: PowerConfig::default(),
     };
 
     // create a build config
     let build = BuildConfig {
-      dev_path: String::from("http://localhost:8080"),
+      dev_path: AppUrl::Url(Url::parse("http://localhost:8080").unwrap()),
       dist_dir: String::from("../dist"),
+      injected_constants: HashMap::new(),
+      dev_server_timeout_ms: 30000,
+      with_global_tauri: false,
     };
 
     // test the configs
@@ -595,8 +1928,118 @@ mod test {
     assert_eq!(b_config, build);
     assert_eq!(de_server, tauri.embedded_server);
     assert_eq!(d_bundle, tauri.bundle);
-    assert_eq!(d_path, String::from("http://localhost:8080"));
+    assert_eq!(
+      d_path,
+      AppUrl::Url(Url::parse("http://localhost:8080").unwrap())
+    );
     assert_eq!(d_title, tauri.windows[0].title);
     assert_eq!(d_windows, tauri.windows);
   }
+
+  #[test]
+  fn window_scope() {
+    let config: TauriConfig = serde_json::from_value(serde_json::json!({
+      "windows": [{ "label": "main" }, { "label": "docs", "url": "https://tauri.studio" }],
+      "allowlist": {
+        "window": true,
+        "windowScope": {
+          "labels": ["settings"],
+          "urls": ["app", "settings.html", "https://*.tauri.studio/*"]
+        }
+      }
+    }))
+    .unwrap();
+    let custom = |url: &str| WindowUrl::Custom(url.to_string());
+    assert!(config
+      .check_window_scope("settings", &WindowUrl::App)
+      .is_ok());
+    assert!(config
+      .check_window_scope("settings", &custom("settings.html"))
+      .is_ok());
+    assert!(config
+      .check_window_scope("settings", &custom("https://docs.tauri.studio/api"))
+      .is_ok());
+    assert!(config
+      .check_window_scope("settings", &custom("https://evil.com/tauri.studio/"))
+      .is_err());
+    // the `*` can't match across the host and the path, nor the host labels
+    assert!(config
+      .check_window_scope("settings", &custom("https://evil.com/.tauri.studio/x"))
+      .is_err());
+    assert!(config
+      .check_window_scope("settings", &custom("https://evil.com?.tauri.studio/x"))
+      .is_err());
+    assert!(config
+      .check_window_scope("settings", &custom("https://docs.tauri.studio@evil.com/x"))
+      .is_err());
+    assert!(config
+      .check_window_scope("settings", &custom("https://docs.tauri.studio.evil.com/x"))
+      .is_err());
+    assert!(config
+      .check_window_scope("settings", &custom("http://docs.tauri.studio/x"))
+      .is_err());
+    assert!(config.check_window_scope("popup", &WindowUrl::App).is_err());
+    // the static windows are allowed, with their own URL too
+    assert!(config.check_window_scope("main", &WindowUrl::App).is_ok());
+    assert!(config
+      .check_window_scope("docs", &custom("https://tauri.studio"))
+      .is_ok());
+
+    assert!(TauriConfig::default()
+      .check_window_scope("popup", &custom("https://evil.com"))
+      .is_ok());
+  }
+
+  #[test]
+  fn url_pattern_match() {
+    use super::url_pattern_match;
+    assert!(url_pattern_match(
+      "https://tauri.studio",
+      "https://tauri.studio"
+    ));
+    assert!(url_pattern_match(
+      "https://tauri.studio",
+      "https://tauri.studio/"
+    ));
+    assert!(!url_pattern_match(
+      "https://tauri.studio",
+      "https://tauri.studio/docs"
+    ));
+    assert!(url_pattern_match(
+      "HTTPS://Tauri.Studio/*",
+      "https://tauri.studio/docs?q=1"
+    ));
+    assert!(url_pattern_match(
+      "https://*/*",
+      "https://a.b.example.com/x"
+    ));
+    assert!(!url_pattern_match(
+      "https://*.example.com/*",
+      "https://a.b.example.com/x"
+    ));
+    assert!(url_pattern_match(
+      "http://localhost:*/*",
+      "http://localhost:1430/"
+    ));
+    assert!(!url_pattern_match(
+      "http://localhost/*",
+      "http://localhost:1430/"
+    ));
+    assert!(!url_pattern_match("https://*.example.com/*", "not a url"));
+    assert!(url_pattern_match(
+      "settings*.html",
+      "settings-advanced.html"
+    ));
+  }
+
+  #[test]
+  fn windows_update_install_mode_args() {
+    assert_eq!(
+      WindowsUpdateInstallMode::default().msiexec_args(),
+      &["/passive"]
+    );
+    assert_eq!(WindowsUpdateInstallMode::Passive.nsis_args(), &["/S"]);
+    assert_eq!(WindowsUpdateInstallMode::Quiet.nsis_args(), &["/S"]);
+    assert!(WindowsUpdateInstallMode::BasicUi.nsis_args().is_empty());
+  }
 }
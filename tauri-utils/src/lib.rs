@@ -9,6 +9,8 @@ pub mod config;
 pub mod platform;
 /// Process helpers
 pub mod process;
+/// The manifest of the bundled resources.
+pub mod resources;
 
 /// Result type alias using the crate's error type.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,184 @@
+//! The manifest of the bundled resources, generated at bundle time and embedded in the app.
+
+use brotli::{enc::BrotliEncoderParams, CompressorReader, Decompressor};
+use flate2::read::{GzDecoder, GzEncoder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+  collections::HashMap,
+  fs::File,
+  io::{self, Read},
+  path::{Path, PathBuf},
+};
+
+/// The compression of the bundled resources.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourcesCompression {
+  /// The resources are bundled as is.
+  None,
+  /// The resources are compressed with gzip and bundled with the `.gz` extension.
+  Gzip,
+  /// The resources are compressed with brotli and bundled with the `.br` extension.
+  Brotli,
+}
+
+impl Default for ResourcesCompression {
+  fn default() -> Self {
+    Self::None
+  }
+}
+
+impl ResourcesCompression {
+  /// The extension appended to the bundled resource files.
+  pub fn extension(self) -> Option<&'static str> {
+    match self {
+      Self::None => None,
+      Self::Gzip => Some("gz"),
+      Self::Brotli => Some("br"),
+    }
+  }
+
+  /// Compresses the content.
+  pub fn compress<'a, R: Read + 'a>(self, content: R) -> Box<dyn Read + 'a> {
+    match self {
+      Self::None => Box::new(content),
+      Self::Gzip => Box::new(GzEncoder::new(content, flate2::Compression::best())),
+      Self::Brotli => Box::new(CompressorReader::with_params(
+        content,
+        4096,
+        &BrotliEncoderParams::default(),
+      )),
+    }
+  }
+
+  /// Decompresses the content.
+  pub fn decompress<'a, R: Read + 'a>(self, content: R) -> Box<dyn Read + 'a> {
+    match self {
+      Self::None => Box::new(content),
+      Self::Gzip => Box::new(GzDecoder::new(content)),
+      Self::Brotli => Box::new(Decompressor::new(content, 4096)),
+    }
+  }
+}
+
+/// The bundled resources, with the SHA-256 hash of their uncompressed content keyed by their path on the resource directory.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceManifest {
+  /// The compression of the bundled resources.
+  #[serde(default)]
+  pub compression: ResourcesCompression,
+  /// The resource hashes, keyed by their `/` separated path.
+  pub files: HashMap<String, String>,
+}
+
+impl ResourceManifest {
+  /// The key of the resource path on the manifest.
+  pub fn key(path: &Path) -> String {
+    path
+      .components()
+      .map(|component| component.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/")
+  }
+
+  /// Whether the resource is on the manifest.
+  pub fn contains(&self, path: &Path) -> bool {
+    self.files.contains_key(&Self::key(path))
+  }
+
+  /// The path of the bundled file of the resource, with the compression extension.
+  pub fn bundled_path(&self, resource_dir: &Path, path: &Path) -> PathBuf {
+    let path = resource_dir.join(path);
+    match self.compression.extension() {
+      Some(extension) => {
+        let mut path = path.into_os_string();
+        path.push(".");
+        path.push(extension);
+        path.into()
+      }
+      None => path,
+    }
+  }
+
+  /// Reads the uncompressed content of the resource.
+  pub fn read(&self, resource_dir: &Path, path: &Path) -> io::Result<Vec<u8>> {
+    let file = File::open(self.bundled_path(resource_dir, path))?;
+    let mut content = Vec::new();
+    self
+      .compression
+      .decompress(file)
+      .read_to_end(&mut content)?;
+    Ok(content)
+  }
+
+  /// Whether the content matches the hash of the resource on the manifest.
+  pub fn matches(&self, path: &Path, content: &[u8]) -> bool {
+    self.files.get(&Self::key(path)).map(String::as_str) == Some(hash(content).as_str())
+  }
+
+  /// Checks the bundled resources, returning the paths of the missing and modified ones.
+  pub fn verify(&self, resource_dir: &Path) -> Vec<String> {
+    let mut mismatches: Vec<String> = self
+      .files
+      .keys()
+      .filter(|key| {
+        let path = Path::new(key.as_str());
+        !matches!(self.read(resource_dir, path), Ok(content) if self.matches(path, &content))
+      })
+      .cloned()
+      .collect();
+    mismatches.sort();
+    mismatches
+  }
+}
+
+/// The hex encoded SHA-256 hash of the content.
+pub fn hash(content: &[u8]) -> String {
+  format!("{:x}", Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod test {
+  use super::{hash, ResourceManifest, ResourcesCompression};
+  use std::{fs, io::Read, path::Path};
+
+  #[test]
+  fn verifies_the_compressed_resources() {
+    let dir = tempfile::tempdir().unwrap();
+    let content = b"resource content".to_vec();
+    for compression in &[
+      ResourcesCompression::None,
+      ResourcesCompression::Gzip,
+      ResourcesCompression::Brotli,
+    ] {
+      let mut manifest = ResourceManifest {
+        compression: *compression,
+        ..Default::default()
+      };
+      manifest
+        .files
+        .insert("assets/data.json".into(), hash(&content));
+      let path = Path::new("assets").join("data.json");
+      let bundled = manifest.bundled_path(dir.path(), &path);
+      fs::create_dir_all(bundled.parent().unwrap()).unwrap();
+      let mut compressed = Vec::new();
+      compression
+        .compress(&content[..])
+        .read_to_end(&mut compressed)
+        .unwrap();
+      fs::write(&bundled, compressed).unwrap();
+
+      assert_eq!(manifest.read(dir.path(), &path).unwrap(), content);
+      assert!(manifest.verify(dir.path()).is_empty());
+
+      fs::write(&bundled, b"tampered").unwrap();
+      assert_eq!(
+        manifest.verify(dir.path()),
+        vec!["assets/data.json".to_string()]
+      );
+      fs::remove_file(&bundled).unwrap();
+    }
+  }
+}
@@ -1,9 +1,100 @@
 use cfg_aliases::cfg_aliases;
+use serde_json::Value;
+
+use std::env::var;
+
+/// The config sections that need a feature of this crate: the JSON pointer, its name on the messages and the feature.
+const CONFIG_FEATURES: &[(&str, &str, &str)] = &[
+  ("/tauri/cli", "tauri > cli", "cli"),
+  (
+    "/tauri/updater/active",
+    "tauri > updater > active",
+    "updater",
+  ),
+  (
+    "/tauri/power/idleTimeoutSeconds",
+    "tauri > power > idleTimeoutSeconds",
+    "power",
+  ),
+  (
+    "/tauri/power/emitResume",
+    "tauri > power > emitResume",
+    "power",
+  ),
+  (
+    "/tauri/power/preventSleep",
+    "tauri > power > preventSleep",
+    "power",
+  ),
+];
+
+fn feature_enabled(feature: &str) -> bool {
+  var(format!(
+    "CARGO_FEATURE_{}",
+    feature.to_uppercase().replace('-', "_")
+  ))
+  .is_ok()
+}
+
+/// Fails the build if the config uses a section this crate was built without,
+/// e.g. if the `tauri` dependency isn't an inline table the CLI can write the features to.
+/// The config is the one the CLI passes on `TAURI_CONFIG`, so the check is skipped on a plain `cargo build`.
+fn check_config_features() {
+  println!("cargo:rerun-if-env-changed=TAURI_CONFIG");
+  let config: Value = match var("TAURI_CONFIG") {
+    Ok(config) => match serde_json::from_str(&config) {
+      Ok(config) => config,
+      Err(e) => {
+        println!(
+          "cargo:warning=the config features aren't checked, `TAURI_CONFIG` isn't valid JSON: {}",
+          e
+        );
+        return;
+      }
+    },
+    Err(_) => return,
+  };
+  let mut missing = Vec::new();
+  for (pointer, section, feature) in CONFIG_FEATURES {
+    let used = match config.pointer(pointer) {
+      None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+      Some(_) => true,
+    };
+    if used && !feature_enabled(feature) {
+      missing.push(format!(
+        "the `{}` config requires the `{}` feature of the `tauri` crate",
+        section, feature
+      ));
+    }
+  }
+  let global_shortcuts = config
+    .pointer("/tauri/globalShortcuts")
+    .and_then(Value::as_object)
+    .map_or(false, |shortcuts| !shortcuts.is_empty());
+  if global_shortcuts && !feature_enabled("global-shortcut") && !feature_enabled("all-api") {
+    missing.push(
+      "the `tauri > globalShortcuts` config requires the `global-shortcut` feature of the `tauri` crate".into(),
+    );
+  }
+  if !missing.is_empty() {
+    for message in &missing {
+      println!("cargo:warning={}", message);
+    }
+    eprintln!(
+      "error: {}, which it was built without; enable them on the `tauri` dependency of your Cargo.toml, or add them to `build > forcedFeatures`",
+      missing.join(", ")
+    );
+    std::process::exit(1);
+  }
+}
 
 fn main() {
+  check_config_features();
+
   cfg_aliases! {
     embedded_server: { feature = "embedded-server" },
     dev: { not(feature = "embedded-server") },
+    trace_startup: { feature = "trace-startup" },
 
     all_api: { feature = "all-api" },
 
@@ -44,6 +135,19 @@ fn main() {
     notification: { any(all_api, feature = "notification") },
 
     // global shortcut
-    global_shortcut: { any(all_api, feature = "global_shortcut" )},
+    global_shortcut: { any(all_api, feature = "global-shortcut") },
+
+    // clipboard
+    clipboard_read_text: { any(all_api, feature = "clipboard-read-text") },
+    clipboard_write_text: { any(all_api, feature = "clipboard-write-text") },
+
+    // power
+    power: { feature = "power" },
+    power_set_prevent_sleep: { any(all_api, feature = "power-set-prevent-sleep") },
+
+    // process
+    process_relaunch: { any(all_api, feature = "process-relaunch") },
+    process_exit: { any(all_api, feature = "process-exit") },
+    process_relaunch_dangerous_allow_symlink_macos: { feature = "process-relaunch-dangerous-allow-symlink-macos" },
   }
 }
@@ -8,9 +8,21 @@ use crate::async_runtime::Mutex;
 use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 
 pub(crate) mod event;
+pub(crate) mod fs_scope;
+#[cfg(global_shortcut)]
+mod global_shortcuts;
+mod instance_channel;
+mod ipc_limits;
+pub(crate) mod metadata;
+#[cfg(power)]
+pub(crate) mod power;
+mod splashscreen;
+mod startup_trace;
 mod utils;
 pub(crate) mod webview;
 mod webview_manager;
+mod window_state;
+mod zoom;
 
 pub use crate::api::config::WindowUrl;
 pub use webview::{
@@ -29,12 +41,35 @@ pub struct Context {
   pub(crate) config: Config,
   pub(crate) tauri_script: &'static str,
   pub(crate) assets: &'static tauri_api::assets::Assets,
+  /// The paths the fs API can access.
+  pub(crate) fs_scope: fs_scope::FsScope,
 }
 
 impl Context {
   pub(crate) fn new<Context: AsTauriContext>() -> crate::Result<Self> {
+    startup_trace::init();
+    let _trace = startup_trace::span(startup_trace::CONFIG_PARSE, None);
+    // on development the CLI passes the current config, so its changes apply on restart without a rebuild
+    #[cfg(dev)]
+    let mut config: Config = match std::env::var("TAURI_CONFIG") {
+      Ok(config) => serde_json::from_str(&config)?,
+      Err(_) => serde_json::from_str(Context::raw_config())?,
+    };
+    #[cfg(not(dev))]
+    let mut config: Config = serde_json::from_str(Context::raw_config())?;
+    config.pin_window_size_units();
+    config.apply_feature_gates();
+    if let Some(manifest) = Context::raw_resource_manifest() {
+      tauri_api::path::set_resource_manifest(serde_json::from_str(manifest)?);
+    }
+    let app_dirs =
+      tauri_api::path::AppDirs::resolve(&config.tauri.paths, &config.tauri.bundle.identifier)?;
+    app_dirs.migrate(&config.tauri.paths)?;
+    tauri_api::path::set_app_dirs(app_dirs);
+    let fs_scope = fs_scope::FsScope::new(config.tauri.allowlist.fs_scope.as_ref())?;
     Ok(Self {
-      config: serde_json::from_str(Context::raw_config())?,
+      config,
+      fs_scope,
       tauri_script: Context::raw_tauri_script(),
       assets: Context::assets(),
     })
@@ -45,6 +80,29 @@ pub(crate) struct Webview<A: ApplicationExt> {
   pub(crate) builder: A::WebviewBuilder,
   pub(crate) label: String,
   pub(crate) url: WindowUrl,
+  /// The Tauri API injection overrides.
+  pub(crate) api: WebviewApi,
+}
+
+/// The per-window overrides of the Tauri API injection, and the window zoom.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct WebviewApi {
+  /// Overrides `build > withGlobalTauri`.
+  pub(crate) with_global_tauri: Option<bool>,
+  /// Whether the window gets the Tauri API and its IPC messages are handled. Defaults to `true`.
+  pub(crate) enable_tauri_api: Option<bool>,
+  /// The page zoom of the window.
+  pub(crate) zoom: crate::api::config::ZoomConfig,
+}
+
+impl From<&crate::api::config::WindowConfig> for WebviewApi {
+  fn from(config: &crate::api::config::WindowConfig) -> Self {
+    Self {
+      with_global_tauri: config.with_global_tauri,
+      enable_tauri_api: config.enable_tauri_api,
+      zoom: config.zoom,
+    }
+  }
 }
 
 /// The response for a JS `invoke` call.
@@ -73,29 +131,54 @@ pub struct App<A: ApplicationExt> {
   url: String,
   window_labels: Arc<Mutex<Vec<String>>>,
   plugin_initialization_script: String,
+  /// The startup splashscreen, if configured.
+  pub(crate) splashscreen: Option<Arc<splashscreen::Splashscreen>>,
+  /// The saved states of the windows that remember their state, read when the app runs.
+  pub(crate) window_states: Option<window_state::WindowStates>,
+  /// The page zoom factors of the windows.
+  pub(crate) zooms: zoom::Zooms,
 }
 
 impl<A: ApplicationExt + 'static> App<A> {
   /// Runs the app until it finishes.
   pub fn run(mut self) {
+    let webview_app = A::new().expect("failed to create application");
     {
       let mut window_labels = crate::async_runtime::block_on(self.window_labels.lock());
-      for window_config in self.context.config.tauri.windows.clone() {
+      let mut windows = self.context.config.tauri.windows.clone();
+      let window_states = window_state::WindowStates::load(webview_app.available_monitors());
+      for window in &mut windows {
+        window_states.restore(window);
+      }
+      self.window_states = Some(window_states);
+      self.splashscreen =
+        splashscreen::Splashscreen::new(&self.context.config.tauri, &mut windows).map(Arc::new);
+      for window_config in windows {
+        let is_splashscreen = window_config.splashscreen;
         let window_url = window_config.url.clone();
         let window_label = window_config.label.to_string();
+        let api = WebviewApi::from(&window_config);
         window_labels.push(window_label.to_string());
-        let webview = A::WebviewBuilder::from(webview::WindowConfig(window_config));
+        let window_config = webview::WindowConfig(window_config);
+        let webview = A::WebviewBuilder::from(window_config);
         let mut webviews = self.webviews.take().unwrap();
-        webviews.push(Webview {
+        let webview = Webview {
           label: window_label,
           builder: webview,
           url: window_url,
-        });
+          api,
+        };
+        // the splashscreen is shown before the windows added on the builder too
+        if is_splashscreen {
+          webviews.insert(0, webview);
+        } else {
+          webviews.push(webview);
+        }
         self.webviews = Some(webviews);
       }
     }
 
-    run(self).expect("failed to run application");
+    run(self, webview_app).expect("failed to run application");
   }
 
   /// Runs the invoke handler if defined.
@@ -258,6 +341,7 @@ impl<A: ApplicationExt + 'static, C: AsTauriContext> AppBuilder<A, C> {
       label,
       builder,
       url,
+      api: Default::default(),
     });
     Ok(self)
   }
@@ -269,6 +353,7 @@ impl<A: ApplicationExt + 'static, C: AsTauriContext> AppBuilder<A, C> {
       crate::async_runtime::block_on(crate::plugin::initialization_script(A::plugin_store()));
 
     let context = Context::new::<C>()?;
+    utils::verify_resources(&context)?;
     let url = utils::get_url(&context)?;
 
     Ok(App {
@@ -280,25 +365,63 @@ impl<A: ApplicationExt + 'static, C: AsTauriContext> AppBuilder<A, C> {
       url,
       window_labels: Arc::new(Mutex::new(window_labels)),
       plugin_initialization_script,
+      splashscreen: None,
+      window_states: None,
+      zooms: Default::default(),
     })
   }
 }
 
-fn run<A: ApplicationExt + 'static>(mut application: App<A>) -> crate::Result<()> {
+fn run<A: ApplicationExt + 'static>(
+  mut application: App<A>,
+  mut webview_app: A,
+) -> crate::Result<()> {
+  #[cfg(all(cli, dev))]
+  utils::validate_dev_cli_args(&application.context);
+  #[cfg(windows)]
+  utils::setup_webview2_fixed_runtime();
+  utils::setup_single_instance(&application)?;
+
   let plugin_config = application.context.config.plugins.clone();
-  crate::async_runtime::block_on(async move {
-    crate::plugin::initialize(A::plugin_store(), plugin_config).await
-  })?;
+  {
+    let _trace = startup_trace::span(startup_trace::PLUGIN_INIT, None);
+    crate::async_runtime::block_on(async move {
+      crate::plugin::initialize(A::plugin_store(), plugin_config).await
+    })?;
+  }
 
   #[cfg(embedded_server)]
   utils::spawn_server(application.url.to_string(), &application.context);
 
+  #[cfg(global_shortcut)]
+  global_shortcuts::register(&application);
+  #[cfg(not(global_shortcut))]
+  if !application.context.config.tauri.global_shortcuts.is_empty() {
+    return Err(crate::Error::FeatureNotEnabled(
+      "tauri > globalShortcuts".into(),
+      "global-shortcut".into(),
+    ));
+  }
+
+  #[cfg(power)]
+  power::start(&application)?;
+  #[cfg(not(power))]
+  {
+    let power = &application.context.config.tauri.power;
+    if power.idle_timeout_seconds.is_some() || power.emit_resume || power.prevent_sleep {
+      return Err(crate::Error::FeatureNotEnabled(
+        "tauri > power".into(),
+        "power".into(),
+      ));
+    }
+  }
+
   let webviews = application.webviews.take().unwrap();
 
   let application = Arc::new(application);
-  let mut webview_app = A::new()?;
 
   for webview in webviews {
+    let _trace = startup_trace::span(startup_trace::WINDOW_CREATION, Some(&webview.label));
     let webview_label = webview.label.to_string();
     let webview_manager = WebviewManager::new(
       application.clone(),
@@ -316,7 +439,22 @@ fn run<A: ApplicationExt + 'static>(mut application: App<A>) -> crate::Result<()
     ));
   }
 
+  if let Some(splashscreen) = application.splashscreen.clone() {
+    splashscreen.spawn_timeout(application.dispatchers.clone());
+  }
+
+  #[cfg(dev)]
+  utils::spawn_dev_server_reload(&application);
+
   webview_app.run();
 
+  // the app exited before its first IPC message
+  startup_trace::finish();
+
+  #[cfg(global_shortcut)]
+  global_shortcuts::unregister_all()?;
+  #[cfg(power)]
+  power::release();
+
   Ok(())
 }
@@ -0,0 +1,216 @@
+use tauri_api::{config::wildcard_match, path::expand_variables};
+
+use std::{
+  path::{Component, Path, PathBuf},
+  sync::Mutex,
+};
+
+/// The paths the fs API can access: the `tauri > allowlist > fsScope` patterns,
+/// plus the paths the user picked on the dialogs during this session.
+pub(crate) struct FsScope {
+  /// The patterns with their variables expanded and their leading directories canonicalized,
+  /// or `None` if any path can be accessed.
+  patterns: Option<Vec<PathBuf>>,
+  /// The canonicalized picked paths, and whether their contents can be accessed too.
+  allowed: Mutex<Vec<(PathBuf, bool)>>,
+}
+
+impl FsScope {
+  pub(crate) fn new(patterns: Option<&Vec<String>>) -> crate::Result<Self> {
+    let patterns = match patterns {
+      Some(patterns) => Some(
+        patterns
+          .iter()
+          .map(|pattern| expand_variables(pattern).map(|pattern| canonicalize_pattern(&pattern)))
+          .collect::<tauri_api::Result<Vec<PathBuf>>>()?,
+      ),
+      None => None,
+    };
+    Ok(Self {
+      patterns,
+      allowed: Default::default(),
+    })
+  }
+
+  /// Allows the picked file until the app exits.
+  pub(crate) fn allow_file(&self, path: PathBuf) {
+    self
+      .allowed
+      .lock()
+      .unwrap()
+      .push((canonicalize(&path), false));
+  }
+
+  /// Allows the picked directory and its contents until the app exits.
+  pub(crate) fn allow_directory(&self, path: PathBuf) {
+    self
+      .allowed
+      .lock()
+      .unwrap()
+      .push((canonicalize(&path), true));
+  }
+
+  /// Checks that the fs API can access the path. The paths with `..` components are rejected when there's a scope,
+  /// and the symlinks are resolved before matching, so they can't escape a scope directory.
+  pub(crate) fn check(&self, path: &Path) -> crate::Result<()> {
+    let patterns = match &self.patterns {
+      Some(patterns) => patterns,
+      None => return Ok(()),
+    };
+    let escapes = path
+      .components()
+      .any(|component| component == Component::ParentDir);
+    let allowed = !escapes && {
+      let resolved = canonicalize(path);
+      self
+        .allowed
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(allowed, recursive)| {
+          &resolved == allowed || (*recursive && resolved.starts_with(allowed))
+        })
+        || patterns
+          .iter()
+          .any(|pattern| path_pattern_match(pattern, &resolved))
+    };
+    if allowed {
+      Ok(())
+    } else {
+      Err(crate::Error::PathNotInScope(path.to_path_buf()))
+    }
+  }
+}
+
+/// Resolves the symlinks of the path. The part of the path that doesn't exist yet, e.g. a file about to be written,
+/// is appended to its canonicalized existing ancestor.
+fn canonicalize(path: &Path) -> PathBuf {
+  let mut missing = Vec::new();
+  let mut existing = path;
+  loop {
+    if let Ok(canonical) = existing.canonicalize() {
+      return missing
+        .iter()
+        .rev()
+        .fold(canonical, |path, component| path.join(component));
+    }
+    match (existing.parent(), existing.file_name()) {
+      (Some(parent), Some(name)) => {
+        missing.push(name);
+        existing = parent;
+      }
+      _ => return path.to_path_buf(),
+    }
+  }
+}
+
+/// Canonicalizes the leading directories of the pattern, up to its first component with a `*`.
+fn canonicalize_pattern(pattern: &Path) -> PathBuf {
+  let mut prefix = PathBuf::new();
+  let mut components = pattern.components();
+  for component in &mut components {
+    if component.as_os_str().to_string_lossy().contains('*') {
+      let prefix = canonicalize(&prefix).join(component);
+      return components.fold(prefix, |path, component| path.join(component));
+    }
+    prefix.push(component);
+  }
+  canonicalize(&prefix)
+}
+
+/// Whether the path matches the pattern, component by component: a `*` matches any characters of a single component,
+/// so `/data/my-app*` doesn't match `/data/my-app-other/notes.txt`, and a `**` component matches any number of components.
+fn path_pattern_match(pattern: &Path, path: &Path) -> bool {
+  let pattern: Vec<String> = pattern
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+    .collect();
+  let path: Vec<String> = path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy().into_owned())
+    .collect();
+  components_match(&pattern, &path)
+}
+
+fn components_match(pattern: &[String], path: &[String]) -> bool {
+  match pattern.split_first() {
+    None => path.is_empty(),
+    Some((first, rest)) if first == "**" => {
+      (0..=path.len()).any(|skipped| components_match(rest, &path[skipped..]))
+    }
+    Some((first, rest)) => match path.split_first() {
+      Some((component, path)) => wildcard_match(first, component) && components_match(rest, path),
+      None => false,
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{path_pattern_match, FsScope};
+  use std::path::Path;
+
+  #[test]
+  fn check() {
+    let scope = FsScope::new(Some(&vec!["/data/my-app/*".to_string()])).unwrap();
+    assert!(scope.check(Path::new("/data/my-app/notes.txt")).is_ok());
+    assert!(scope.check(Path::new("/data/my-app/../secret")).is_err());
+    assert!(scope.check(Path::new("/home/user/picked.txt")).is_err());
+    // a `*` doesn't match the nested paths
+    assert!(scope
+      .check(Path::new("/data/my-app/logs/today.txt"))
+      .is_err());
+
+    scope.allow_file("/home/user/picked.txt".into());
+    scope.allow_directory("/home/user/photos".into());
+    assert!(scope.check(Path::new("/home/user/picked.txt")).is_ok());
+    assert!(scope.check(Path::new("/home/user/picked.txt.bak")).is_err());
+    assert!(scope.check(Path::new("/home/user/photos/cat.png")).is_ok());
+
+    assert!(FsScope::new(None)
+      .unwrap()
+      .check(Path::new("/etc/passwd"))
+      .is_ok());
+  }
+
+  #[test]
+  fn path_pattern() {
+    assert!(path_pattern_match(
+      Path::new("/data/my-app*"),
+      Path::new("/data/my-app-logs")
+    ));
+    assert!(!path_pattern_match(
+      Path::new("/data/my-app*"),
+      Path::new("/data/my-app-other/notes.txt")
+    ));
+    assert!(path_pattern_match(
+      Path::new("/data/my-app/**"),
+      Path::new("/data/my-app/logs/today.txt")
+    ));
+    assert!(path_pattern_match(
+      Path::new("/data/**/*.txt"),
+      Path::new("/data/notes.txt")
+    ));
+    assert!(!path_pattern_match(
+      Path::new("/data/**/*.txt"),
+      Path::new("/data/logs/today.log")
+    ));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn symlinks_are_resolved() {
+    let root = std::env::temp_dir().join(format!("tauri-fs-scope-{}", std::process::id()));
+    let scope_dir = root.join("scope");
+    let outside = root.join("outside");
+    std::fs::create_dir_all(&scope_dir).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    std::os::unix::fs::symlink(&outside, scope_dir.join("link")).unwrap();
+
+    let scope = FsScope::new(Some(&vec![format!("{}/**", scope_dir.display())])).unwrap();
+    assert!(scope.check(&scope_dir.join("notes.txt")).is_ok());
+    assert!(scope.check(&scope_dir.join("link/secret.txt")).is_err());
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -0,0 +1,137 @@
+use super::{App, ApplicationExt, WebviewDispatcher};
+use crate::{
+  api::config::GlobalShortcutAction, async_runtime::Mutex,
+  endpoints::global_shortcut::manager_handle,
+};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+};
+
+/// The event emitted when a config shortcut can't be registered, e.g. because another app uses it.
+pub(super) const REGISTRATION_FAILED_EVENT: &str = "global-shortcut-registration-failed";
+
+/// The payload of the registration failure event.
+#[derive(Clone, Serialize)]
+pub(super) struct RegistrationFailure {
+  accelerator: String,
+  error: String,
+}
+
+/// The config shortcuts that failed to register, emitted once the first window is ready.
+static REGISTRATION_FAILURES: Lazy<std::sync::Mutex<Vec<RegistrationFailure>>> =
+  Lazy::new(Default::default);
+
+type Dispatchers<A> =
+  Arc<Mutex<HashMap<String, WebviewDispatcher<<A as ApplicationExt>::Dispatcher>>>>;
+
+// registers the `tauri.globalShortcuts` config, keeping the shortcuts that can't be registered for the failure event
+pub(super) fn register<A: ApplicationExt + 'static>(application: &App<A>) {
+  let config = &application.context.config.tauri;
+  if config.global_shortcuts.is_empty() {
+    return;
+  }
+  let main_window = config
+    .windows
+    .first()
+    .map(|window| window.label.clone())
+    .unwrap_or_else(|| "main".to_string());
+  // the window visibility toggled by the shortcuts, since the dispatchers can't read it
+  let visibility: Arc<HashMap<String, AtomicBool>> = Arc::new(
+    config
+      .windows
+      .iter()
+      .map(|window| (window.label.clone(), AtomicBool::new(window.visible)))
+      .collect(),
+  );
+
+  let mut manager = crate::async_runtime::block_on(manager_handle().lock());
+  for (accelerator, action) in &config.global_shortcuts {
+    let dispatchers = application.dispatchers.clone();
+    let visibility = visibility.clone();
+    let main_window = main_window.clone();
+    let shortcut = accelerator.clone();
+    let action = action.clone();
+    let result = manager.register(accelerator.clone(), move || {
+      run_action::<A>(&action, &shortcut, &dispatchers, &visibility, &main_window)
+    });
+    if let Err(e) = result {
+      REGISTRATION_FAILURES
+        .lock()
+        .unwrap()
+        .push(RegistrationFailure {
+          accelerator: accelerator.clone(),
+          error: e.to_string(),
+        });
+    }
+  }
+}
+
+/// Takes the config shortcuts that failed to register.
+pub(super) fn take_registration_failures() -> Vec<RegistrationFailure> {
+  std::mem::take(&mut *REGISTRATION_FAILURES.lock().unwrap())
+}
+
+/// Unregisters the global shortcuts, so they're released as soon as the app exits.
+pub(super) fn unregister_all() -> crate::Result<()> {
+  let mut manager = crate::async_runtime::block_on(manager_handle().lock());
+  manager.unregister_all()?;
+  Ok(())
+}
+
+fn run_action<A: ApplicationExt + 'static>(
+  action: &GlobalShortcutAction,
+  accelerator: &str,
+  dispatchers: &Dispatchers<A>,
+  visibility: &HashMap<String, AtomicBool>,
+  main_window: &str,
+) {
+  let set_visible = |label: &str, visible: bool| {
+    let dispatchers = crate::async_runtime::block_on(dispatchers.lock());
+    if let Some(dispatcher) = dispatchers.get(label) {
+      let _ = if visible {
+        dispatcher
+          .unminimize()
+          .and_then(|_| dispatcher.show())
+          .and_then(|_| dispatcher.set_focus())
+      } else {
+        dispatcher.hide()
+      };
+    }
+    if let Some(window_visible) = visibility.get(label) {
+      window_visible.store(visible, Ordering::SeqCst);
+    }
+  };
+  match action {
+    GlobalShortcutAction::ToggleWindow(label) => {
+      let visible = visibility
+        .get(label)
+        .map(|visible| visible.load(Ordering::SeqCst))
+        .unwrap_or(true);
+      set_visible(label, !visible);
+    }
+    GlobalShortcutAction::Show(label) => set_visible(label.as_deref().unwrap_or(main_window), true),
+    GlobalShortcutAction::Hide(label) => {
+      set_visible(label.as_deref().unwrap_or(main_window), false)
+    }
+    GlobalShortcutAction::Quit => {
+      unregister_all();
+      #[cfg(power)]
+      super::power::release();
+      std::process::exit(0);
+    }
+    GlobalShortcutAction::Emit(event) => {
+      let dispatchers = crate::async_runtime::block_on(dispatchers.lock());
+      for dispatcher in dispatchers.values() {
+        let _ = dispatcher.emit(event, Some(accelerator));
+      }
+    }
+  }
+}
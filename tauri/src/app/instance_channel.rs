@@ -0,0 +1,407 @@
+//! The channel the new instances of the app forward their launch to the running instance with.
+//!
+//! Only the processes of the same user can open it: it's a Unix domain socket in the user runtime directory,
+//! or in a private directory of the user on the temporary directory, whose peers are checked to be the same user,
+//! or a named pipe whose ACL only grants access to the user's SID on Windows.
+
+use std::{io, time::Duration};
+
+pub(super) use platform::{bind, connect, InstanceListener, InstanceStream};
+
+#[cfg(unix)]
+mod platform {
+  use std::{
+    fs::DirBuilder,
+    io,
+    os::unix::{
+      fs::{DirBuilderExt, FileTypeExt, MetadataExt},
+      io::AsRawFd,
+      net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+  };
+
+  /// A connection between the running instance and a new instance.
+  pub(in crate::app) type InstanceStream = UnixStream;
+
+  /// The channel of the running instance.
+  pub(in crate::app) struct InstanceListener {
+    listener: UnixListener,
+    path: PathBuf,
+  }
+
+  impl Drop for InstanceListener {
+    fn drop(&mut self) {
+      let _ = std::fs::remove_file(&self.path);
+    }
+  }
+
+  impl InstanceListener {
+    /// Waits for the next new instance, refusing the processes of the other users.
+    pub(in crate::app) fn accept(&mut self) -> io::Result<InstanceStream> {
+      let (stream, _) = self.listener.accept()?;
+      check_peer(&stream)?;
+      Ok(stream)
+    }
+  }
+
+  fn uid() -> libc::uid_t {
+    unsafe { libc::getuid() }
+  }
+
+  /// The user of the process on the other end of the connection.
+  #[cfg(any(target_os = "linux", target_os = "android"))]
+  pub(super) fn peer_uid(stream: &UnixStream) -> io::Result<libc::uid_t> {
+    let mut credentials: libc::ucred = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+      libc::getsockopt(
+        stream.as_raw_fd(),
+        libc::SOL_SOCKET,
+        libc::SO_PEERCRED,
+        &mut credentials as *mut libc::ucred as *mut libc::c_void,
+        &mut len,
+      )
+    };
+    if result != 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(credentials.uid)
+  }
+
+  /// The user of the process on the other end of the connection.
+  #[cfg(not(any(target_os = "linux", target_os = "android")))]
+  pub(super) fn peer_uid(stream: &UnixStream) -> io::Result<libc::uid_t> {
+    let mut uid = 0;
+    let mut gid = 0;
+    if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+      return Err(io::Error::last_os_error());
+    }
+    Ok(uid)
+  }
+
+  fn check_peer(stream: &UnixStream) -> io::Result<()> {
+    if peer_uid(stream)? == uid() {
+      Ok(())
+    } else {
+      Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "the other instance belongs to another user",
+      ))
+    }
+  }
+
+  /// Checks that the directory is only accessible by the user, so the other users can't replace the socket.
+  pub(super) fn check_private_dir(dir: &Path) -> io::Result<()> {
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if metadata.is_dir() && metadata.uid() == uid() && metadata.mode() & 0o077 == 0 {
+      Ok(())
+    } else {
+      Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("`{}` isn't a private directory of the user", dir.display()),
+      ))
+    }
+  }
+
+  // the runtime directory is private to the user, otherwise a private directory is created on the temporary directory,
+  // which might be shared with the other users
+  fn socket_path(identifier: &str) -> io::Result<PathBuf> {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR")
+      .map(PathBuf::from)
+      .filter(|dir| dir.is_dir())
+    {
+      Some(dir) => dir,
+      None => {
+        let dir = std::env::temp_dir().join(format!("tauri-{}", uid()));
+        match DirBuilder::new().mode(0o700).create(&dir) {
+          Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+          _ => {}
+        }
+        dir
+      }
+    };
+    check_private_dir(&dir)?;
+    Ok(dir.join(format!("{}.sock", identifier)))
+  }
+
+  fn listen(path: &Path) -> io::Result<InstanceListener> {
+    let listener = UnixListener::bind(path)?;
+    Ok(InstanceListener {
+      listener,
+      path: path.to_path_buf(),
+    })
+  }
+
+  /// Acquires the instance lock of the app, or returns `None` if another instance holds it.
+  pub(in crate::app) fn bind(identifier: &str) -> io::Result<Option<InstanceListener>> {
+    let path = socket_path(identifier)?;
+    match listen(&path) {
+      Ok(listener) => Ok(Some(listener)),
+      Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+        if UnixStream::connect(&path).is_ok() {
+          return Ok(None);
+        }
+        // the socket of an instance that didn't exit cleanly refuses the connections,
+        // and it's only replaced if it's a socket of the user
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if !metadata.file_type().is_socket() || metadata.uid() != uid() {
+          return Err(e);
+        }
+        std::fs::remove_file(&path)?;
+        listen(&path).map(Some)
+      }
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Connects to the running instance of the app, refusing the processes of the other users.
+  pub(in crate::app) fn connect(identifier: &str) -> io::Result<InstanceStream> {
+    let stream = UnixStream::connect(socket_path(identifier)?)?;
+    check_peer(&stream)?;
+    Ok(stream)
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use std::{
+    ffi::OsStr,
+    fs::{File, OpenOptions},
+    io,
+    os::windows::{ffi::OsStrExt, io::FromRawHandle},
+    ptr::null_mut,
+    time::Duration,
+  };
+
+  use winapi::{
+    shared::{
+      minwindef::DWORD,
+      sddl::{
+        ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW,
+        SDDL_REVISION_1,
+      },
+      winerror::{ERROR_ACCESS_DENIED, ERROR_PIPE_BUSY, ERROR_PIPE_CONNECTED},
+    },
+    um::{
+      handleapi::{CloseHandle, INVALID_HANDLE_VALUE},
+      minwinbase::SECURITY_ATTRIBUTES,
+      namedpipeapi::{ConnectNamedPipe, CreateNamedPipeW},
+      processthreadsapi::{GetCurrentProcess, OpenProcessToken},
+      securitybaseapi::GetTokenInformation,
+      winbase::{
+        LocalFree, FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX, PIPE_READMODE_BYTE,
+        PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+      },
+      winnt::{TokenUser, HANDLE, PSECURITY_DESCRIPTOR, TOKEN_QUERY, TOKEN_USER},
+    },
+  };
+
+  /// The buffer size of the pipe instances.
+  const PIPE_BUFFER_SIZE: DWORD = 4096;
+  /// The number of times a new instance retries to connect while the pipe instances are busy.
+  const CONNECT_ATTEMPTS: u32 = 20;
+  /// The time a new instance waits before retrying to connect.
+  const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+  /// A connection between the running instance and a new instance.
+  pub(in crate::app) type InstanceStream = File;
+
+  /// The channel of the running instance: the pipe instance waiting for the next new instance.
+  pub(in crate::app) struct InstanceListener {
+    name: Vec<u16>,
+    security_descriptor: PSECURITY_DESCRIPTOR,
+    pending: HANDLE,
+  }
+
+  // the handles and the security descriptor are only used by the thread owning the listener
+  unsafe impl Send for InstanceListener {}
+
+  impl Drop for InstanceListener {
+    fn drop(&mut self) {
+      unsafe {
+        CloseHandle(self.pending);
+        LocalFree(self.security_descriptor);
+      }
+    }
+  }
+
+  impl InstanceListener {
+    /// Waits for the next new instance.
+    pub(in crate::app) fn accept(&mut self) -> io::Result<InstanceStream> {
+      let error = if unsafe { ConnectNamedPipe(self.pending, null_mut()) } != 0 {
+        None
+      } else {
+        Some(io::Error::last_os_error())
+          .filter(|e| e.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32))
+      };
+      let next = create_pipe(&self.name, self.security_descriptor, false)?;
+      let pending = std::mem::replace(&mut self.pending, next);
+      let stream = unsafe { File::from_raw_handle(pending) };
+      match error {
+        Some(error) => Err(error),
+        None => Ok(stream),
+      }
+    }
+  }
+
+  fn wide(value: &str) -> Vec<u16> {
+    OsStr::new(value).encode_wide().chain(Some(0)).collect()
+  }
+
+  // the SID of the user running the app, e.g. `S-1-5-21-...`
+  fn user_sid() -> io::Result<String> {
+    unsafe {
+      let mut token = null_mut();
+      if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+        return Err(io::Error::last_os_error());
+      }
+      let mut size = 0;
+      GetTokenInformation(token, TokenUser, null_mut(), 0, &mut size);
+      // a `u64` buffer is aligned for the SID pointer
+      let mut buffer = vec![0u64; (size as usize + 7) / 8];
+      let queried =
+        GetTokenInformation(token, TokenUser, buffer.as_mut_ptr() as _, size, &mut size);
+      CloseHandle(token);
+      if queried == 0 {
+        return Err(io::Error::last_os_error());
+      }
+      let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+      let mut sid = null_mut();
+      if ConvertSidToStringSidW(user.User.Sid, &mut sid) == 0 {
+        return Err(io::Error::last_os_error());
+      }
+      let len = (0..).take_while(|i| *sid.offset(*i) != 0).count();
+      let value = String::from_utf16_lossy(std::slice::from_raw_parts(sid, len));
+      LocalFree(sid as _);
+      Ok(value)
+    }
+  }
+
+  // the pipes are global to the machine, so their name includes the user SID
+  fn pipe_name(identifier: &str, sid: &str) -> String {
+    format!(r"\\.\pipe\{}-{}", identifier, sid)
+  }
+
+  fn create_pipe(
+    name: &[u16],
+    security_descriptor: PSECURITY_DESCRIPTOR,
+    first: bool,
+  ) -> io::Result<HANDLE> {
+    let mut attributes = SECURITY_ATTRIBUTES {
+      nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as DWORD,
+      lpSecurityDescriptor: security_descriptor,
+      bInheritHandle: 0,
+    };
+    let open_mode = if first {
+      PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE
+    } else {
+      PIPE_ACCESS_DUPLEX
+    };
+    let handle = unsafe {
+      CreateNamedPipeW(
+        name.as_ptr(),
+        open_mode,
+        PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_UNLIMITED_INSTANCES,
+        PIPE_BUFFER_SIZE,
+        PIPE_BUFFER_SIZE,
+        0,
+        &mut attributes,
+      )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+      Err(io::Error::last_os_error())
+    } else {
+      Ok(handle)
+    }
+  }
+
+  /// Acquires the instance lock of the app, or returns `None` if another instance holds it.
+  pub(in crate::app) fn bind(identifier: &str) -> io::Result<Option<InstanceListener>> {
+    let sid = user_sid()?;
+    // a protected DACL only granting access to the user
+    let sddl = wide(&format!("D:P(A;;GA;;;{})", sid));
+    let mut security_descriptor = null_mut();
+    let converted = unsafe {
+      ConvertStringSecurityDescriptorToSecurityDescriptorW(
+        sddl.as_ptr(),
+        SDDL_REVISION_1 as DWORD,
+        &mut security_descriptor,
+        null_mut(),
+      )
+    };
+    if converted == 0 {
+      return Err(io::Error::last_os_error());
+    }
+    let name = wide(&pipe_name(identifier, &sid));
+    match create_pipe(&name, security_descriptor, true) {
+      Ok(pending) => Ok(Some(InstanceListener {
+        name,
+        security_descriptor,
+        pending,
+      })),
+      Err(e) => {
+        unsafe { LocalFree(security_descriptor) };
+        if e.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+          Ok(None)
+        } else {
+          Err(e)
+        }
+      }
+    }
+  }
+
+  /// Connects to the running instance of the app.
+  pub(in crate::app) fn connect(identifier: &str) -> io::Result<InstanceStream> {
+    let name = pipe_name(identifier, &user_sid()?);
+    let mut attempt = 0;
+    loop {
+      match OpenOptions::new().read(true).write(true).open(&name) {
+        Err(e)
+          if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) && attempt < CONNECT_ATTEMPTS =>
+        {
+          attempt += 1;
+          std::thread::sleep(CONNECT_RETRY_INTERVAL);
+        }
+        result => return result,
+      }
+    }
+  }
+}
+
+/// Sets the time the instance waits for the messages of the other instance.
+/// The pipe reads can't time out on Windows, but every new instance is handled on its own thread.
+pub(super) fn set_read_timeout(
+  #[allow(unused_variables)] stream: &InstanceStream,
+  #[allow(unused_variables)] timeout: Duration,
+) -> io::Result<()> {
+  #[cfg(unix)]
+  return stream.set_read_timeout(Some(timeout));
+  #[cfg(windows)]
+  Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod test {
+  use super::platform::{check_private_dir, peer_uid};
+  use std::os::unix::{fs::PermissionsExt, net::UnixStream};
+
+  #[test]
+  fn peer_is_the_user() {
+    let (stream, _) = UnixStream::pair().unwrap();
+    assert_eq!(peer_uid(&stream).unwrap(), unsafe { libc::getuid() });
+  }
+
+  #[test]
+  // the socket isn't created on a directory the other users can write to
+  fn socket_dir_is_private() {
+    let dir = std::env::temp_dir().join(format!("tauri-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(check_private_dir(&dir).is_err());
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700)).unwrap();
+    assert!(check_private_dir(&dir).is_ok());
+    std::fs::remove_dir(&dir).unwrap();
+  }
+}
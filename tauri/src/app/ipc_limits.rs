@@ -0,0 +1,262 @@
+use crate::api::config::IpcConfig;
+
+use serde::{Deserialize, Serialize};
+
+use std::{
+  collections::HashMap,
+  time::{Duration, Instant},
+};
+
+/// The event emitted when an IPC message exceeds a `tauri > security > ipc` limit.
+pub(super) const LIMIT_EXCEEDED_EVENT: &str = "ipc-limit-exceeded";
+
+/// The minimum interval between two limit events of a window, so a flooding page doesn't flood the listeners too.
+const EVENT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The exceeded limit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) enum Limit {
+  /// `maxPayloadBytes`.
+  PayloadSize,
+  /// `maxRequestsPerSecond`.
+  Rate,
+}
+
+/// The payload of the limit event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct LimitExceeded {
+  /// The label of the window that sent the message.
+  window: String,
+  /// The `cmd` of the message, unless it was rejected before being read.
+  command: Option<String>,
+  limit: Limit,
+  /// The configured limit, in bytes or messages per second.
+  max: u64,
+}
+
+impl LimitExceeded {
+  /// The error the message promise is rejected with.
+  pub(super) fn to_error(&self) -> crate::Error {
+    let limit = match self.limit {
+      Limit::PayloadSize => "maxPayloadBytes",
+      Limit::Rate => "maxRequestsPerSecond",
+    };
+    crate::Error::IpcLimitExceeded(limit.to_string(), self.command.clone())
+  }
+}
+
+/// The fields of a message read to reject it without deserializing its payload.
+#[derive(Deserialize)]
+pub(super) struct MessageHeader {
+  pub(super) callback: String,
+  pub(super) error: String,
+}
+
+/// A token bucket refilled at `rate` tokens per second, holding up to one second of tokens.
+struct TokenBucket {
+  rate: f64,
+  tokens: f64,
+  updated_at: Instant,
+}
+
+impl TokenBucket {
+  fn new(rate: u64, now: Instant) -> Self {
+    Self {
+      rate: rate as f64,
+      tokens: rate as f64,
+      updated_at: now,
+    }
+  }
+
+  /// Takes a token, returning `false` if the bucket is empty.
+  fn take(&mut self, now: Instant) -> bool {
+    let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+    self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+    self.updated_at = now;
+    if self.tokens >= 1.0 {
+      self.tokens -= 1.0;
+      true
+    } else {
+      false
+    }
+  }
+}
+
+/// The IPC limits of a window.
+pub(super) struct IpcLimiter {
+  window: String,
+  config: IpcConfig,
+  bucket: Option<TokenBucket>,
+  command_buckets: HashMap<String, TokenBucket>,
+  last_event: Option<Instant>,
+}
+
+/// Reads a limit, where `0` is unlimited.
+fn limit<T: Into<u64> + Copy>(value: Option<T>) -> Option<u64> {
+  value.map(Into::into).filter(|value| *value > 0)
+}
+
+impl IpcLimiter {
+  pub(super) fn new(window: String, config: IpcConfig) -> Self {
+    Self {
+      window,
+      config,
+      bucket: None,
+      command_buckets: HashMap::new(),
+      last_event: None,
+    }
+  }
+
+  /// Whether any limit is set, so the unlimited windows skip the checks.
+  pub(super) fn is_enabled(&self) -> bool {
+    limit(self.config.max_payload_bytes).is_some()
+      || limit(self.config.max_requests_per_second).is_some()
+      || !self.config.per_command_limits.is_empty()
+  }
+
+  fn exceeded(&self, command: Option<&str>, limit: Limit, max: u64) -> LimitExceeded {
+    LimitExceeded {
+      window: self.window.clone(),
+      command: command.map(ToString::to_string),
+      limit,
+      max,
+    }
+  }
+
+  /// Checks the window limits, before the message is deserialized.
+  pub(super) fn check_message(&mut self, size: usize, now: Instant) -> Result<(), LimitExceeded> {
+    if let Some(max) = limit(self.config.max_payload_bytes) {
+      if size as u64 > max {
+        return Err(self.exceeded(None, Limit::PayloadSize, max));
+      }
+    }
+    if let Some(rate) = limit(self.config.max_requests_per_second) {
+      let bucket = self
+        .bucket
+        .get_or_insert_with(|| TokenBucket::new(rate, now));
+      if !bucket.take(now) {
+        return Err(self.exceeded(None, Limit::Rate, rate));
+      }
+    }
+    Ok(())
+  }
+
+  /// Checks the limits of the message command.
+  pub(super) fn check_command(
+    &mut self,
+    command: &str,
+    size: usize,
+    now: Instant,
+  ) -> Result<(), LimitExceeded> {
+    let limits = match self.config.per_command_limits.get(command) {
+      Some(limits) => limits,
+      None => return Ok(()),
+    };
+    if let Some(max) = limit(limits.max_payload_bytes) {
+      if size as u64 > max {
+        return Err(self.exceeded(Some(command), Limit::PayloadSize, max));
+      }
+    }
+    if let Some(rate) = limit(limits.max_requests_per_second) {
+      let bucket = self
+        .command_buckets
+        .entry(command.to_string())
+        .or_insert_with(|| TokenBucket::new(rate, now));
+      if !bucket.take(now) {
+        return Err(self.exceeded(Some(command), Limit::Rate, rate));
+      }
+    }
+    Ok(())
+  }
+
+  /// Whether the limit event should be emitted, at most once per `EVENT_INTERVAL`.
+  pub(super) fn should_emit(&mut self, now: Instant) -> bool {
+    match self.last_event {
+      Some(last_event) if now.saturating_duration_since(last_event) < EVENT_INTERVAL => false,
+      _ => {
+        self.last_event.replace(now);
+        true
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{IpcLimiter, Limit, LimitExceeded};
+  use crate::api::config::{IpcCommandLimits, IpcConfig};
+
+  use std::time::{Duration, Instant};
+
+  fn limiter(config: IpcConfig) -> IpcLimiter {
+    IpcLimiter::new("main".into(), config)
+  }
+
+  #[test]
+  fn payload_size() {
+    let mut limiter = limiter(IpcConfig {
+      max_payload_bytes: Some(100),
+      ..Default::default()
+    });
+    let now = Instant::now();
+    assert!(limiter.check_message(100, now).is_ok());
+    assert_eq!(
+      limiter.check_message(101, now),
+      Err(LimitExceeded {
+        window: "main".into(),
+        command: None,
+        limit: Limit::PayloadSize,
+        max: 100,
+      })
+    );
+  }
+
+  #[test]
+  fn rate() {
+    let mut limiter = limiter(IpcConfig {
+      max_requests_per_second: Some(2),
+      ..Default::default()
+    });
+    let now = Instant::now();
+    assert!(limiter.check_message(1, now).is_ok());
+    assert!(limiter.check_message(1, now).is_ok());
+    assert!(limiter.check_message(1, now).is_err());
+    // half a second refills one token
+    let later = now + Duration::from_millis(500);
+    assert!(limiter.check_message(1, later).is_ok());
+    assert!(limiter.check_message(1, later).is_err());
+  }
+
+  #[test]
+  fn command_limits() {
+    let mut config = IpcConfig::default();
+    config.per_command_limits.insert(
+      "upload".into(),
+      IpcCommandLimits {
+        max_payload_bytes: Some(0),
+        max_requests_per_second: Some(1),
+      },
+    );
+    let mut limiter = limiter(config);
+    assert!(limiter.is_enabled());
+    let now = Instant::now();
+    // `0` is unlimited
+    assert!(limiter.check_command("upload", 1 << 20, now).is_ok());
+    let error = limiter.check_command("upload", 1, now).unwrap_err();
+    assert_eq!(error.command.as_deref(), Some("upload"));
+    assert_eq!(error.limit, Limit::Rate);
+    assert!(limiter.check_command("other", 1, now).is_ok());
+  }
+
+  #[test]
+  fn unlimited() {
+    let mut limiter = limiter(IpcConfig {
+      max_payload_bytes: Some(0),
+      ..Default::default()
+    });
+    assert!(!limiter.is_enabled());
+    assert!(limiter.check_message(usize::MAX, Instant::now()).is_ok());
+  }
+}
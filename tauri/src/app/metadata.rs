@@ -0,0 +1,74 @@
+use crate::api::config::Config;
+
+use serde_json::{Map, Value as JsonValue};
+
+/// The constants injected along with `build > injectedConstants`, which can't be overridden.
+const APP_VERSION: &str = "TAURI_APP_VERSION";
+const APP_NAME: &str = "TAURI_APP_NAME";
+const DEBUG: &str = "TAURI_DEBUG";
+const FEATURES: &str = "features";
+
+/// The `window.__TAURI_METADATA__` object: the `build > injectedConstants`, the app metadata and the `features` config.
+pub(crate) fn metadata(config: &Config) -> Map<String, JsonValue> {
+  let mut metadata: Map<String, JsonValue> = config
+    .build
+    .injected_constants
+    .iter()
+    .map(|(key, value)| (key.clone(), value.clone()))
+    .collect();
+  metadata.insert(APP_VERSION.into(), config.package.version.clone().into());
+  metadata.insert(APP_NAME.into(), config.package.product_name.clone().into());
+  metadata.insert(DEBUG.into(), cfg!(debug_assertions).into());
+  metadata.insert(
+    FEATURES.into(),
+    serde_json::to_value(&config.features).unwrap_or_default(),
+  );
+  metadata
+}
+
+/// Defines the frozen `window.__TAURI_METADATA__`.
+/// It's the first initialization script, so the page scripts and the other initialization scripts can read it.
+pub(super) fn metadata_script(config: &Config) -> String {
+  format!(
+    r#"
+      Object.defineProperty(window, '__TAURI_METADATA__', {{
+        value: (function freeze(value) {{
+          if (value && typeof value === 'object') {{
+            Object.getOwnPropertyNames(value).forEach(function (key) {{ freeze(value[key]) }})
+            Object.freeze(value)
+          }}
+          return value
+        }})({metadata}),
+        writable: false,
+        configurable: false
+      }})
+    "#,
+    metadata = JsonValue::Object(metadata(config))
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use super::metadata;
+  use crate::api::config::Config;
+
+  use serde_json::json;
+
+  #[test]
+  fn app_metadata_wins() {
+    let config: Config = serde_json::from_value(json!({
+      "package": { "productName": "app", "version": "1.2.0" },
+      "features": { "beta": true },
+      "build": {
+        "injectedConstants": { "API_URL": "https://example.com", "TAURI_APP_VERSION": "0.0.0" }
+      }
+    }))
+    .unwrap();
+    let metadata = metadata(&config);
+    assert_eq!(metadata["API_URL"], json!("https://example.com"));
+    assert_eq!(metadata["TAURI_APP_VERSION"], json!("1.2.0"));
+    assert_eq!(metadata["TAURI_APP_NAME"], json!("app"));
+    assert_eq!(metadata["TAURI_DEBUG"], json!(cfg!(debug_assertions)));
+    assert_eq!(metadata["features"], json!({ "beta": true }));
+  }
+}
@@ -0,0 +1,214 @@
+//! The `tauri > power` config: the idle, active and resume events and the wake lock.
+//!
+//! The platforms without an idle time, e.g. Linux without the X11 screensaver extension, never emit the idle events,
+//! and the ones without a wake lock fail to start with the `preventSleep` config.
+
+use super::{App, ApplicationExt, WebviewDispatcher};
+use crate::{api::power::WakeLock, async_runtime::Mutex};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use std::{
+  collections::HashMap,
+  sync::Arc,
+  time::{Duration, SystemTime},
+};
+
+/// The event emitted when the user is idle for `idleTimeoutSeconds`.
+const IDLE_EVENT: &str = "idle";
+/// The event emitted when the user is back after the idle event.
+const ACTIVE_EVENT: &str = "active";
+/// The event emitted when the system resumes after sleep.
+/// The sleep is only noticed once the system is awake again, so there's no event before it.
+const RESUME_EVENT: &str = "resume";
+
+/// How often the idle time and the clock are read.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The clock jump between two reads that's treated as a system sleep.
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// The wake lock, held while the frontend or the `preventSleep` config prevents the system from sleeping.
+static WAKE_LOCK: Lazy<std::sync::Mutex<Option<WakeLock>>> = Lazy::new(Default::default);
+
+type Dispatchers<A> =
+  Arc<Mutex<HashMap<String, WebviewDispatcher<<A as ApplicationExt>::Dispatcher>>>>;
+
+/// The payload of the idle event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IdlePayload {
+  idle_seconds: u64,
+}
+
+/// The payload of the resume event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumePayload {
+  suspended_seconds: u64,
+}
+
+/// An event of the power monitor.
+#[derive(Debug, PartialEq)]
+enum PowerEvent {
+  Idle(IdlePayload),
+  Active,
+  Resume(ResumePayload),
+}
+
+/// The idle and resume detection of the power monitor.
+struct MonitorState {
+  idle_timeout: Option<Duration>,
+  emit_resume: bool,
+  idle: bool,
+}
+
+impl MonitorState {
+  fn new(idle_timeout: Option<Duration>, emit_resume: bool) -> Self {
+    Self {
+      idle_timeout,
+      emit_resume,
+      idle: false,
+    }
+  }
+
+  /// The events of a read, given the wall clock time since the last read and the idle time, if it's known.
+  fn read(&mut self, elapsed: Duration, idle_time: Option<Duration>) -> Vec<PowerEvent> {
+    let mut events = Vec::new();
+    // the wall clock keeps running while the system is suspended, unlike the monitor thread
+    if self.emit_resume && elapsed > POLL_INTERVAL + SLEEP_THRESHOLD {
+      events.push(PowerEvent::Resume(ResumePayload {
+        suspended_seconds: (elapsed - POLL_INTERVAL).as_secs(),
+      }));
+    }
+
+    if let (Some(idle_timeout), Some(idle_time)) = (self.idle_timeout, idle_time) {
+      if !self.idle && idle_time >= idle_timeout {
+        self.idle = true;
+        events.push(PowerEvent::Idle(IdlePayload {
+          idle_seconds: idle_time.as_secs(),
+        }));
+      } else if self.idle && idle_time < idle_timeout {
+        self.idle = false;
+        events.push(PowerEvent::Active);
+      }
+    }
+    events
+  }
+}
+
+/// Takes or releases the wake lock.
+pub(crate) fn set_prevent_sleep(prevent_sleep: bool) -> crate::Result<()> {
+  let mut wake_lock = WAKE_LOCK.lock().unwrap();
+  if !prevent_sleep {
+    *wake_lock = None;
+  } else if wake_lock.is_none() {
+    *wake_lock = Some(WakeLock::acquire()?);
+  }
+  Ok(())
+}
+
+/// Releases the wake lock, so the system can sleep as soon as the app exits.
+pub(crate) fn release() {
+  WAKE_LOCK.lock().unwrap().take();
+}
+
+// takes the `preventSleep` wake lock and starts the idle and resume monitor
+pub(super) fn start<A: ApplicationExt + 'static>(application: &App<A>) -> crate::Result<()> {
+  let config = &application.context.config.tauri.power;
+  if config.prevent_sleep {
+    set_prevent_sleep(true)?;
+  }
+  let idle_timeout = config.idle_timeout_seconds.map(Duration::from_secs);
+  let emit_resume = config.emit_resume;
+  if idle_timeout.is_none() && !emit_resume {
+    return Ok(());
+  }
+  let dispatchers = application.dispatchers.clone();
+  std::thread::spawn(move || monitor::<A>(idle_timeout, emit_resume, dispatchers));
+  Ok(())
+}
+
+fn monitor<A: ApplicationExt + 'static>(
+  idle_timeout: Option<Duration>,
+  emit_resume: bool,
+  dispatchers: Dispatchers<A>,
+) {
+  let mut state = MonitorState::new(idle_timeout, emit_resume);
+  let mut last_read = SystemTime::now();
+  loop {
+    std::thread::sleep(POLL_INTERVAL);
+
+    let now = SystemTime::now();
+    let elapsed = now.duration_since(last_read).unwrap_or_default();
+    last_read = now;
+    let idle_time = idle_timeout.and_then(|_| crate::api::power::idle_time());
+    for event in state.read(elapsed, idle_time) {
+      match event {
+        PowerEvent::Idle(payload) => emit::<A, _>(&dispatchers, IDLE_EVENT, Some(payload)),
+        PowerEvent::Active => emit::<A, ()>(&dispatchers, ACTIVE_EVENT, None),
+        PowerEvent::Resume(payload) => emit::<A, _>(&dispatchers, RESUME_EVENT, Some(payload)),
+      }
+    }
+  }
+}
+
+fn emit<A: ApplicationExt + 'static, S: Serialize + Clone>(
+  dispatchers: &Dispatchers<A>,
+  event: &str,
+  payload: Option<S>,
+) {
+  let dispatchers = crate::async_runtime::block_on(dispatchers.lock());
+  for dispatcher in dispatchers.values() {
+    let _ = dispatcher.emit(event, payload.clone());
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{IdlePayload, MonitorState, PowerEvent, ResumePayload};
+  use std::time::Duration;
+
+  #[test]
+  fn idle_events() {
+    let mut state = MonitorState::new(Some(Duration::from_secs(300)), false);
+    let second = Duration::from_secs(1);
+    assert_eq!(state.read(second, Some(Duration::from_secs(299))), vec![]);
+    assert_eq!(
+      state.read(second, Some(Duration::from_secs(300))),
+      vec![PowerEvent::Idle(IdlePayload { idle_seconds: 300 })]
+    );
+    // the idle event is emitted once until the user is back
+    assert_eq!(state.read(second, Some(Duration::from_secs(301))), vec![]);
+    // the idle time is unknown, e.g. without the X11 screensaver extension
+    assert_eq!(state.read(second, None), vec![]);
+    assert_eq!(
+      state.read(second, Some(Duration::from_secs(0))),
+      vec![PowerEvent::Active]
+    );
+    assert_eq!(state.read(second, Some(Duration::from_secs(0))), vec![]);
+
+    // without `idleTimeoutSeconds` the idle time is ignored
+    let mut state = MonitorState::new(None, false);
+    assert_eq!(state.read(second, Some(Duration::from_secs(3600))), vec![]);
+  }
+
+  #[test]
+  fn resume_events() {
+    let mut state = MonitorState::new(None, true);
+    // the thread can be late without the system being suspended
+    assert_eq!(state.read(Duration::from_secs(6), None), vec![]);
+    assert_eq!(
+      state.read(Duration::from_secs(61), None),
+      vec![PowerEvent::Resume(ResumePayload {
+        suspended_seconds: 60
+      })]
+    );
+
+    let mut state = MonitorState::new(Some(Duration::from_secs(30)), false);
+    assert_eq!(
+      state.read(Duration::from_secs(61), Some(Duration::from_secs(60))),
+      vec![PowerEvent::Idle(IdlePayload { idle_seconds: 60 })]
+    );
+  }
+}
@@ -0,0 +1,202 @@
+use std::{
+  collections::HashMap,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
+
+use super::{ApplicationDispatcherExt, WebviewDispatcher};
+use crate::{
+  api::config::{TauriConfig, WindowConfig, WindowUrl},
+  async_runtime::Mutex,
+};
+
+/// The label of the window created from `tauri > splashscreen > html`.
+const SPLASHSCREEN_LABEL: &str = "splashscreen";
+/// The global function the splashscreen page calls when it's unloaded.
+pub(super) const CLOSED_CALLBACK: &str = "__TAURI_SPLASHSCREEN_CLOSED__";
+
+type Dispatchers<D> = Arc<Mutex<HashMap<String, WebviewDispatcher<D>>>>;
+
+/// Calls the closed callback when the splashscreen page is unloaded, so closing it shows the other windows right away.
+pub(super) fn unload_script() -> String {
+  format!(
+    "window.addEventListener('unload', function () {{ window.{}() }})",
+    CLOSED_CALLBACK
+  )
+}
+
+/// The startup splashscreen, shown while the other windows load hidden.
+pub(crate) struct Splashscreen {
+  /// The splashscreen window label.
+  label: String,
+  /// The windows shown when the splashscreen closes: the ones that would've been visible.
+  windows: Vec<String>,
+  minimum_duration: Duration,
+  timeout: Duration,
+  shown_at: Instant,
+  closed: AtomicBool,
+}
+
+impl Splashscreen {
+  /// Sets up the splashscreen of the window configs, if any.
+  /// The window flagged with `splashscreen`, or the one created from `tauri > splashscreen > html`, is moved first,
+  /// and the visible windows are hidden until it closes.
+  pub(super) fn new(config: &TauriConfig, windows: &mut Vec<WindowConfig>) -> Option<Self> {
+    let mut splashscreen = match windows.iter().position(|window| window.splashscreen) {
+      Some(position) => windows.remove(position),
+      None => {
+        let options = config.splashscreen.as_ref()?;
+        WindowConfig {
+          label: SPLASHSCREEN_LABEL.to_string(),
+          // inline, so it doesn't wait for the app assets
+          url: WindowUrl::Custom(format!(
+            "data:text/html;base64,{}",
+            base64::encode(options.html.as_ref()?)
+          )),
+          width: options.width.clone(),
+          height: options.height.clone(),
+          title: windows
+            .first()
+            .map(|window| window.title.clone())
+            .unwrap_or_else(|| WindowConfig::default().title),
+          resizable: false,
+          decorations: false,
+          ..Default::default()
+        }
+      }
+    };
+    splashscreen.splashscreen = true;
+    splashscreen.visible = true;
+    let mut visible = Vec::new();
+    for window in windows.iter_mut().filter(|window| window.visible) {
+      window.visible = false;
+      visible.push(window.label.clone());
+    }
+    let label = splashscreen.label.clone();
+    windows.insert(0, splashscreen);
+
+    let options = config.splashscreen.clone().unwrap_or_default();
+    Some(Self {
+      label,
+      windows: visible,
+      minimum_duration: Duration::from_millis(options.minimum_duration_ms),
+      timeout: Duration::from_millis(options.timeout_ms),
+      shown_at: Instant::now(),
+      closed: AtomicBool::new(false),
+    })
+  }
+
+  /// Whether the window with the given label is the splashscreen.
+  pub(super) fn is(&self, label: &str) -> bool {
+    self.label == label
+  }
+
+  /// Closes the splashscreen once the timeout elapses,
+  /// so the other windows show up even if the frontend never closes it, e.g. because the main window failed to load.
+  pub(super) fn spawn_timeout<D: ApplicationDispatcherExt + 'static>(
+    self: Arc<Self>,
+    dispatchers: Dispatchers<D>,
+  ) {
+    std::thread::spawn(move || {
+      std::thread::sleep(self.timeout);
+      if !self.closed.load(Ordering::SeqCst) {
+        self.close(dispatchers, true);
+      }
+    });
+  }
+
+  /// Shows the other windows and closes the splashscreen, once it's been shown for the minimum duration
+  /// unless `wait` is false, e.g. when the user closed it. Only the first call swaps the windows.
+  pub(crate) fn close<D: ApplicationDispatcherExt + 'static>(
+    &self,
+    dispatchers: Dispatchers<D>,
+    wait: bool,
+  ) {
+    if self.closed.swap(true, Ordering::SeqCst) {
+      return;
+    }
+    let delay = if wait {
+      self
+        .minimum_duration
+        .checked_sub(self.shown_at.elapsed())
+        .unwrap_or_default()
+    } else {
+      Duration::default()
+    };
+    let label = self.label.clone();
+    let windows = self.windows.clone();
+    std::thread::spawn(move || {
+      std::thread::sleep(delay);
+      let mut dispatchers = crate::async_runtime::block_on(dispatchers.lock());
+      for window in &windows {
+        if let Some(dispatcher) = dispatchers.get(window) {
+          let _ = dispatcher.show();
+        }
+      }
+      if let Some(dispatcher) = windows.first().and_then(|window| dispatchers.get(window)) {
+        let _ = dispatcher.set_focus();
+      }
+      // the user may have closed the splashscreen already
+      if let Some(dispatcher) = dispatchers.remove(&label) {
+        // the webview runtime can't destroy a window, so it's hidden when closing isn't supported
+        if dispatcher.close().is_err() {
+          let _ = dispatcher.hide();
+        }
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Splashscreen;
+  use crate::api::config::{Config, TauriConfig, WindowConfig, WindowUrl};
+
+  use serde_json::json;
+
+  #[test]
+  fn splashscreen_is_first_and_hides_the_windows() {
+    let config: Config = serde_json::from_value(json!({
+      "tauri": {
+        "windows": [
+          { "label": "main", "title": "App" },
+          { "label": "tray", "visible": false }
+        ],
+        "splashscreen": { "html": "<h1>Loading</h1>", "minimumDurationMs": 500 }
+      }
+    }))
+    .unwrap();
+    let mut windows = config.tauri.windows.clone();
+    let splashscreen = Splashscreen::new(&config.tauri, &mut windows).unwrap();
+    assert!(splashscreen.is("splashscreen"));
+    assert_eq!(splashscreen.windows, vec!["main".to_string()]);
+    assert_eq!(splashscreen.minimum_duration.as_millis(), 500);
+    assert_eq!(splashscreen.timeout.as_millis(), 10000);
+    assert_eq!(windows[0].label, "splashscreen");
+    assert_eq!(windows[0].title, "App");
+    match &windows[0].url {
+      WindowUrl::Custom(url) => assert!(url.starts_with("data:text/html;base64,")),
+      WindowUrl::App => panic!("the splashscreen must be inline"),
+    }
+    assert!(windows[1..].iter().all(|window| !window.visible));
+
+    let config: Config = serde_json::from_value(json!({
+      "tauri": {
+        "windows": [{ "label": "main" }, { "label": "loading", "splashscreen": true }]
+      }
+    }))
+    .unwrap();
+    let mut windows = config.tauri.windows.clone();
+    let splashscreen = Splashscreen::new(&config.tauri, &mut windows).unwrap();
+    assert!(splashscreen.is("loading"));
+    assert_eq!(windows[0].label, "loading");
+    assert!(windows[0].visible);
+
+    let mut windows = vec![WindowConfig::default()];
+    assert!(Splashscreen::new(&TauriConfig::default(), &mut windows).is_none());
+    assert!(windows[0].visible);
+  }
+}
@@ -0,0 +1,285 @@
+//! The startup phase timings, recorded when the app is built with the `trace-startup` feature
+//! and runs with the `TAURI_TRACE_STARTUP` environment variable.
+//!
+//! Without the feature every function here is an empty inline function, so the instrumentation compiles away.
+
+/// The startup phases.
+pub(crate) const CONFIG_PARSE: &str = "config parse";
+pub(crate) const PLUGIN_INIT: &str = "plugin init";
+pub(crate) const WINDOW_CREATION: &str = "window creation";
+pub(crate) const FIRST_NAVIGATION: &str = "first navigation";
+pub(crate) const FIRST_IPC_MESSAGE: &str = "first IPC message";
+
+#[cfg(trace_startup)]
+pub(crate) use recorder::{finish, init, mark_once, span, Span};
+
+#[cfg(not(trace_startup))]
+pub(crate) struct Span;
+
+/// Starts recording the startup if `TAURI_TRACE_STARTUP` is set.
+#[cfg(not(trace_startup))]
+#[inline(always)]
+pub(crate) fn init() {}
+
+/// Records a phase until the returned guard is dropped.
+#[cfg(not(trace_startup))]
+#[inline(always)]
+pub(crate) fn span(_name: &'static str, _label: Option<&str>) -> Span {
+  Span
+}
+
+/// Records the first occurrence of an instant phase.
+#[cfg(not(trace_startup))]
+#[inline(always)]
+pub(crate) fn mark_once(_name: &'static str, _label: Option<&str>) {}
+
+/// Writes the trace file and prints the summary, once.
+#[cfg(not(trace_startup))]
+#[inline(always)]
+pub(crate) fn finish() {}
+
+#[cfg(trace_startup)]
+mod recorder {
+  use once_cell::sync::OnceCell;
+  use serde_json::json;
+
+  use std::{
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+  };
+
+  static TRACE: OnceCell<Option<Mutex<Trace>>> = OnceCell::new();
+
+  struct Phase {
+    name: &'static str,
+    label: Option<String>,
+    start: Duration,
+    /// `None` for the instant phases.
+    duration: Option<Duration>,
+  }
+
+  struct Trace {
+    start: Instant,
+    output: PathBuf,
+    phases: Vec<Phase>,
+    finished: bool,
+  }
+
+  fn trace() -> Option<&'static Mutex<Trace>> {
+    TRACE.get().and_then(Option::as_ref)
+  }
+
+  /// The trace file: the `TAURI_TRACE_STARTUP` value, or a file on the temp dir if it's `1` or `true`.
+  fn output_path() -> Option<PathBuf> {
+    output_path_of(&std::env::var("TAURI_TRACE_STARTUP").ok()?)
+  }
+
+  fn output_path_of(value: &str) -> Option<PathBuf> {
+    match value {
+      "" | "0" | "false" => None,
+      "1" | "true" => {
+        Some(std::env::temp_dir().join(format!("tauri-startup-trace-{}.json", std::process::id())))
+      }
+      path => Some(PathBuf::from(path)),
+    }
+  }
+
+  pub(crate) fn init() {
+    TRACE.get_or_init(|| {
+      output_path().map(|output| {
+        Mutex::new(Trace {
+          start: Instant::now(),
+          output,
+          phases: Vec::new(),
+          finished: false,
+        })
+      })
+    });
+  }
+
+  pub(crate) struct Span {
+    name: &'static str,
+    label: Option<String>,
+    start: Instant,
+  }
+
+  impl Drop for Span {
+    fn drop(&mut self) {
+      if let Some(trace) = trace() {
+        let mut trace = trace.lock().unwrap();
+        let start = self.start.saturating_duration_since(trace.start);
+        trace.phases.push(Phase {
+          name: self.name,
+          label: self.label.take(),
+          start,
+          duration: Some(self.start.elapsed()),
+        });
+      }
+    }
+  }
+
+  pub(crate) fn span(name: &'static str, label: Option<&str>) -> Span {
+    Span {
+      name,
+      label: label.map(ToString::to_string),
+      start: Instant::now(),
+    }
+  }
+
+  pub(crate) fn mark_once(name: &'static str, label: Option<&str>) {
+    if let Some(trace) = trace() {
+      let mut trace = trace.lock().unwrap();
+      if !trace.phases.iter().any(|phase| phase.name == name) {
+        let start = trace.start.elapsed();
+        trace.phases.push(Phase {
+          name,
+          label: label.map(ToString::to_string),
+          start,
+          duration: None,
+        });
+      }
+    }
+  }
+
+  /// The phases on the Chrome trace event format, opened by `chrome://tracing` and Perfetto.
+  fn trace_events(phases: &[Phase], pid: u32) -> serde_json::Value {
+    let events: Vec<_> = phases
+      .iter()
+      .map(|phase| {
+        let mut event = json!({
+          "name": phase.name,
+          "cat": "startup",
+          "ph": if phase.duration.is_some() { "X" } else { "i" },
+          "ts": phase.start.as_micros() as u64,
+          "pid": pid,
+          "tid": 0,
+          "args": { "label": phase.label },
+        });
+        match phase.duration {
+          Some(duration) => event["dur"] = json!(duration.as_micros() as u64),
+          // the instant events span the whole process on the timeline
+          None => event["s"] = json!("p"),
+        }
+        event
+      })
+      .collect();
+    json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+  }
+
+  pub(crate) fn finish() {
+    let trace = match trace() {
+      Some(trace) => trace,
+      None => return,
+    };
+    let mut trace = trace.lock().unwrap();
+    if trace.finished {
+      return;
+    }
+    trace.finished = true;
+    trace.phases.sort_by_key(|phase| phase.start);
+
+    let events = trace_events(&trace.phases, std::process::id());
+    // the summary is the output of the `trace-startup` feature, so it's printed even if the file can't be written
+    match std::fs::write(&trace.output, events.to_string()) {
+      Ok(()) => eprintln!("[tauri] startup trace ({})", trace.output.display()),
+      Err(e) => eprintln!(
+        "[tauri] startup trace (failed to write `{}`: {})",
+        trace.output.display(),
+        e
+      ),
+    }
+    eprintln!(
+      "{:<40} {:>12} {:>15}",
+      "phase", "start (ms)", "duration (ms)"
+    );
+    for phase in &trace.phases {
+      let name = match &phase.label {
+        Some(label) => format!("{} ({})", phase.name, label),
+        None => phase.name.to_string(),
+      };
+      let duration = phase.duration.map_or_else(
+        || "-".to_string(),
+        |duration| format!("{:.2}", duration.as_secs_f64() * 1000.0),
+      );
+      eprintln!(
+        "{:<40} {:>12.2} {:>15}",
+        name,
+        phase.start.as_secs_f64() * 1000.0,
+        duration
+      );
+    }
+  }
+
+  #[cfg(test)]
+  mod test {
+    use super::{output_path_of, trace_events, Phase};
+    use serde_json::json;
+    use std::{path::PathBuf, time::Duration};
+
+    #[test]
+    fn output_paths() {
+      for value in &["", "0", "false"] {
+        assert_eq!(output_path_of(value), None, "{}", value);
+      }
+      for value in &["1", "true"] {
+        let path = output_path_of(value).unwrap();
+        assert!(path.starts_with(std::env::temp_dir()));
+        assert_eq!(
+          path.file_name().unwrap().to_str().unwrap(),
+          format!("tauri-startup-trace-{}.json", std::process::id())
+        );
+      }
+      assert_eq!(
+        output_path_of("trace.json"),
+        Some(PathBuf::from("trace.json"))
+      );
+    }
+
+    #[test]
+    fn chrome_trace_events() {
+      let phases = vec![
+        Phase {
+          name: "window creation",
+          label: Some("main".to_string()),
+          start: Duration::from_micros(1500),
+          duration: Some(Duration::from_micros(2000)),
+        },
+        Phase {
+          name: "first navigation",
+          label: None,
+          start: Duration::from_micros(4000),
+          duration: None,
+        },
+      ];
+      assert_eq!(
+        trace_events(&phases, 42),
+        json!({
+          "traceEvents": [
+            {
+              "name": "window creation",
+              "cat": "startup",
+              "ph": "X",
+              "ts": 1500,
+              "dur": 2000,
+              "pid": 42,
+              "tid": 0,
+              "args": { "label": "main" },
+            },
+            {
+              "name": "first navigation",
+              "cat": "startup",
+              "ph": "i",
+              "ts": 4000,
+              "s": "p",
+              "pid": 42,
+              "tid": 0,
+              "args": { "label": null },
+            }
+          ],
+          "displayTimeUnit": "ms"
+        })
+      );
+    }
+  }
+}
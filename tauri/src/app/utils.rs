@@ -1,13 +1,26 @@
+#[cfg(embedded_server)]
+use std::net::{Ipv4Addr, TcpListener};
 #[cfg(dev)]
-use std::io::Read;
-use std::sync::Arc;
+use std::{io::Read, net::TcpStream};
+use std::{
+  io::{BufRead, BufReader, Write},
+  path::PathBuf,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  },
+  time::{Duration, Instant},
+};
 
 #[cfg(dev)]
-use crate::api::assets::{AssetFetch, Assets};
+use crate::api::{
+  assets::{AssetFetch, Assets},
+  config::AppUrl,
+};
 
 use crate::{
   api::{
-    config::WindowUrl,
+    config::{RemoteDomainAccessScope, WindowUrl},
     rpc::{format_callback, format_callback_result},
   },
   app::InvokeResponse,
@@ -15,13 +28,21 @@ use crate::{
 };
 
 use super::{
-  webview::{Callback, WebviewBuilderExtPrivate},
-  App, Context, Webview, WebviewManager,
+  instance_channel::{self, InstanceListener, InstanceStream},
+  ipc_limits::{IpcLimiter, LimitExceeded, MessageHeader, LIMIT_EXCEEDED_EVENT},
+  metadata::metadata_script,
+  splashscreen,
+  webview::{ApplicationDispatcherExt, Callback, WebviewBuilderExtPrivate},
+  window_state, zoom, App, Context, Webview, WebviewManager,
 };
 #[cfg(embedded_server)]
 use crate::api::tcp::{get_available_port, port_is_available};
 
-use serde::Deserialize;
+/// The environment variable with the embedded server port, which might be chosen at runtime.
+#[cfg(embedded_server)]
+const EMBEDDED_SERVER_PORT_ENV: &str = "TAURI_EMBEDDED_SERVER_PORT";
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 #[derive(Debug, Deserialize)]
@@ -32,6 +53,9 @@ struct Message {
   error: String,
   #[serde(rename = "mainThread", default)]
   main_thread: bool,
+  /// The origin of the page sending the message.
+  #[serde(rename = "__origin")]
+  origin: Option<String>,
   #[serde(flatten)]
   inner: JsonValue,
 }
@@ -40,7 +64,7 @@ struct Message {
 #[cfg(dev)]
 pub(super) fn get_url(context: &Context) -> crate::Result<String> {
   let config = &context.config;
-  if config.build.dev_path.starts_with("http") {
+  if let AppUrl::Url(dev_url) = &config.build.dev_path {
     #[cfg(windows)]
     {
       let exempt_output = std::process::Command::new("CheckNetIsolation")
@@ -64,7 +88,7 @@ pub(super) fn get_url(context: &Context) -> crate::Result<String> {
           .expect("failed to run Loopback command");
       }
     }
-    Ok(config.build.dev_path.clone())
+    Ok(dev_url.to_string())
   } else {
     Ok(format!(
       "data:text/html;base64,{}",
@@ -85,11 +109,76 @@ pub(super) fn get_url(context: &Context) -> crate::Result<String> {
   }
 }
 
+/// The initial interval between the dev server checks, doubled after each failed check.
+#[cfg(dev)]
+const DEV_SERVER_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// The maximum interval between the dev server checks.
+#[cfg(dev)]
+const DEV_SERVER_MAX_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Reloads the windows of the dev server URL once it accepts connections if it didn't when the app started,
+/// e.g. when the app was run before the dev server listens, so their first navigation failed.
+/// It's checked until `build > devServerTimeoutMs` expires.
+#[cfg(dev)]
+pub(super) fn spawn_dev_server_reload<A: ApplicationExt + 'static>(application: &App<A>) {
+  let config = &application.context.config;
+  let url = match &config.build.dev_path {
+    AppUrl::Url(url) => url.clone(),
+    _ => return,
+  };
+  if dev_server_listens(&url) {
+    return;
+  }
+  let timeout = Duration::from_millis(config.build.dev_server_timeout_ms);
+  let windows: Vec<String> = config
+    .tauri
+    .windows
+    .iter()
+    .filter(|window| matches!(window.url, WindowUrl::App))
+    .map(|window| window.label.clone())
+    .collect();
+  let dispatchers = application.dispatchers.clone();
+  std::thread::spawn(move || {
+    let start = Instant::now();
+    let mut interval = DEV_SERVER_RETRY_INTERVAL;
+    while !dev_server_listens(&url) {
+      // the windows keep the page of their failed navigation
+      if start.elapsed() >= timeout {
+        return;
+      }
+      std::thread::sleep(interval);
+      interval = (interval * 2).min(DEV_SERVER_MAX_RETRY_INTERVAL);
+    }
+    let script = format!(
+      "window.location.replace({})",
+      JsonValue::String(url.to_string())
+    );
+    let dispatchers = crate::async_runtime::block_on(dispatchers.lock());
+    for label in &windows {
+      if let Some(dispatcher) = dispatchers.get(label) {
+        let _ = dispatcher.eval(&script);
+      }
+    }
+  });
+}
+
+/// Whether the dev server accepts connections.
+#[cfg(dev)]
+fn dev_server_listens(url: &url::Url) -> bool {
+  url
+    .socket_addrs(|| None)
+    .unwrap_or_default()
+    .iter()
+    .any(|address| TcpStream::connect_timeout(address, Duration::from_secs(1)).is_ok())
+}
+
 // setup content for embedded server
 #[cfg(embedded_server)]
 pub(super) fn get_url(context: &Context) -> crate::Result<String> {
   let (port, valid) = setup_port(&context);
   if valid {
+    // the processes spawned by the app, e.g. sidecars, inherit the port
+    std::env::set_var(EMBEDDED_SERVER_PORT_ENV, &port);
     Ok(setup_server_url(port, &context))
   } else {
     Err(crate::Error::PortNotAvailable(port))
@@ -102,7 +191,13 @@ pub(super) fn get_url(context: &Context) -> crate::Result<String> {
 fn setup_port(context: &Context) -> (String, bool) {
   let config = &context.config;
   match config.tauri.embedded_server.port {
-    tauri_api::config::Port::Random => match get_available_port() {
+    // `tauri dev` picks the random port, so the before dev command knows it
+    tauri_api::config::Port::Random => match std::env::var(EMBEDDED_SERVER_PORT_ENV)
+      .ok()
+      .and_then(|port| port.parse::<u16>().ok())
+      .filter(|port| cfg!(debug_assertions) && port_is_available(*port))
+      .or_else(get_available_port)
+    {
       Some(available_port) => (available_port.to_string(), true),
       None => ("0".to_string(), false),
     },
@@ -130,29 +225,57 @@ fn setup_server_url(port: String, context: &Context) -> String {
 pub(super) fn spawn_server(server_url: String, context: &Context) {
   let assets = context.assets;
   let public_path = context.config.tauri.embedded_server.public_path.clone();
+  let compression = context.config.tauri.embedded_server.compression;
+  let rules = crate::server::HeaderRules::new(&context.config.tauri.embedded_server.headers);
+  let spa_fallback = context.config.tauri.embedded_server.spa_fallback;
+  let pages = crate::server::ErrorPages::new(&context.config.tauri.embedded_server);
+  let proxy_rules = crate::server::ProxyRules::new(&context.config.tauri.embedded_server.proxy);
   std::thread::spawn(move || {
-    let server = tiny_http::Server::http(server_url.replace("http://", "").replace("https://", ""))
-      .expect("Unable to spawn server");
+    let mut cache = crate::server::AssetCache::default();
+    let address = server_url.replace("http://", "").replace("https://", "");
+    let server = if proxy_rules.is_empty() {
+      tiny_http::Server::http(address).expect("Unable to spawn server")
+    } else {
+      // the proxy owns the server address, forwarding the requests that don't match a rule to the assets server
+      let listener = TcpListener::bind(address).expect("Unable to spawn server");
+      let server =
+        tiny_http::Server::http((Ipv4Addr::LOCALHOST, 0)).expect("Unable to spawn server");
+      let assets_address = server.server_addr();
+      std::thread::spawn(move || proxy_rules.serve(listener, assets_address));
+      server
+    };
     for request in server.incoming_requests() {
       let url = request.url().replace(&server_url, "");
-      let url = match url.as_str() {
-        "/" => "/index.html",
+      // the SPA fallback only applies to the urls under the public path
+      let (url, spa_fallback) = match url.as_str() {
+        "/" => ("/index.html", false),
         url => {
           if url.starts_with(&public_path) {
-            &url[public_path.len() - 1..]
+            (&url[public_path.len() - 1..], spa_fallback)
           } else {
             eprintln!(
               "found url not matching public path.\nurl: {}\npublic path: {}",
               url, public_path
             );
-            url
+            (url, false)
           }
         }
-      }
-      .to_string();
-      request
-        .respond(crate::server::asset_response(&url, assets))
-        .expect("unable to setup response");
+      };
+      let response = match crate::server::resolve_asset(url, request.method(), spa_fallback, assets)
+      {
+        Some(path) => crate::server::asset_response(
+          &path,
+          request.headers(),
+          assets,
+          compression,
+          &rules,
+          &mut cache,
+        )
+        .unwrap_or_else(|_| crate::server::error_page(&pages, assets)),
+        None => crate::server::not_found(request.method(), &pages, assets),
+      };
+      // a client closing the connection mustn't stop the server
+      let _ = request.respond(response);
     }
   });
 }
@@ -171,6 +294,20 @@ pub(super) fn spawn_updater() {
   });
 }
 
+/// Refuses to start the app if `tauri > security > verifyResources` is enabled and a bundled resource was tampered with.
+pub(super) fn verify_resources(context: &Context) -> crate::Result<()> {
+  if !context.config.tauri.security.verify_resources {
+    return Ok(());
+  }
+  match tauri_api::path::verify_resources()? {
+    Some(mismatches) if !mismatches.is_empty() => Err(crate::Error::ResourceMismatch(mismatches)),
+    Some(_) => Ok(()),
+    // the CLI only embeds the manifest on `tauri build`
+    None if cfg!(dev) => Ok(()),
+    None => Err(crate::Error::ResourceManifestNotFound),
+  }
+}
+
 pub(super) fn initialization_script(
   plugin_initialization_script: &str,
   tauri_script: &str,
@@ -194,6 +331,44 @@ pub(super) fn initialization_script(
   )
 }
 
+/// Exposes the embedded server origin and port as `window.__TAURI_EMBEDDED_SERVER__`.
+/// It's an initialization script, so the page scripts can read it before the first load.
+#[cfg(embedded_server)]
+fn embedded_server_script(server_url: &str) -> String {
+  let port = url::Url::parse(server_url)
+    .ok()
+    .and_then(|url| url.port_or_known_default());
+  format!(
+    "window.__TAURI_EMBEDDED_SERVER__ = Object.freeze({})",
+    serde_json::json!({ "origin": server_url, "port": port })
+  )
+}
+
+/// Freezes the core prototypes and the `window.__TAURI__` object, so the page scripts can't patch `invoke`.
+const FREEZE_PROTOTYPE_SCRIPT: &str = r#"
+  (function () {
+    [Object, Function, Array, String, Number, Boolean, Promise, RegExp, Date, Error].forEach(function (constructor) {
+      Object.freeze(constructor.prototype)
+    })
+    if (window.__TAURI__) {
+      (function freeze(object) {
+        Object.getOwnPropertyNames(object).forEach(function (key) {
+          var value = object[key]
+          if (value && typeof value === 'object' && !Object.isFrozen(value)) {
+            freeze(value)
+          }
+        })
+        Object.freeze(object)
+      })(window.__TAURI__)
+      Object.defineProperty(window, '__TAURI__', {
+        value: window.__TAURI__,
+        writable: false,
+        configurable: false
+      })
+    }
+  })()
+"#;
+
 fn event_initialization_script() -> String {
   return format!(
     "
@@ -248,16 +423,86 @@ pub(super) fn build_webview<A: ApplicationExt + 'static>(
   plugin_initialization_script: &str,
   tauri_script: &str,
 ) -> crate::Result<BuiltWebview<A>> {
-  // TODO let debug = cfg!(debug_assertions);
   let webview_url = match &webview.url {
     WindowUrl::App => content_url.to_string(),
     WindowUrl::Custom(url) => url.to_string(),
   };
+  let freeze_prototype = application.context.config.tauri.security.freeze_prototype;
+  let is_app = webview.url == WindowUrl::App;
+  // the windows with the API disabled get neither the scripts nor the IPC handler, so their messages are refused
+  let api_enabled = webview.api.enable_tauri_api.unwrap_or(true);
+  let with_global_tauri = webview
+    .api
+    .with_global_tauri
+    .unwrap_or(application.context.config.build.with_global_tauri);
+  // the initialization scripts run again on every navigation, so the flag applies to every page of the window
+  let tauri_script = format!(
+    "window.__TAURI_WITH_GLOBAL_TAURI__ = {}\n{}",
+    with_global_tauri, tauri_script
+  );
+  // the remote domains allowed to send IPC messages on this window
+  let remote_access: Vec<RemoteDomainAccessScope> = application
+    .context
+    .config
+    .tauri
+    .security
+    .dangerous_remote_domain_ipc_access
+    .iter()
+    .filter(|scope| scope.windows.contains(&webview.label))
+    .cloned()
+    .collect();
+
+  let splashscreen_application = application
+    .splashscreen
+    .as_ref()
+    .filter(|splashscreen| splashscreen.is(&webview.label))
+    .map(|_| application.clone());
+
+  let window_state_application = application
+    .window_states
+    .as_ref()
+    .filter(|window_states| window_states.tracks(&webview.label))
+    .map(|_| application.clone());
+
+  // the window starts at its saved zoom factor if it remembers its state
+  let zoom = webview.api.zoom;
+  let saved_zoom = application
+    .window_states
+    .as_ref()
+    .and_then(|window_states| window_states.saved_zoom(&webview.label));
+  let zoom_factor = application.zooms.register(&webview.label, zoom, saved_zoom);
+  // without the hotkeys, a zoom factor or the `setZoom` command, the pages are left as they are
+  let zoom_application = if zoom.hotkeys_enabled || zoom::is_zoomed(zoom_factor) || cfg!(window) {
+    Some(application.clone())
+  } else {
+    None
+  };
 
-  let (webview_builder, callbacks) = if webview.url == WindowUrl::App {
-    let webview_builder = webview.builder.url(webview_url)
-        .initialization_script(&initialization_script(plugin_initialization_script, tauri_script))
-        .initialization_script(&format!(
+  let (mut webview_builder, mut callbacks) = if api_enabled && (is_app || !remote_access.is_empty())
+  {
+    let webview_builder = webview.builder.url(webview_url);
+    let webview_builder = if is_app {
+      webview_builder
+        .initialization_script(&metadata_script(&application.context.config))
+        .initialization_script(&initialization_script(
+          plugin_initialization_script,
+          &tauri_script,
+        ))
+    } else {
+      // remote pages only get the API to send messages, without running the app setup and plugins
+      webview_builder.initialization_script(&format!(
+        "{}\n{}",
+        tauri_script,
+        event_initialization_script()
+      ))
+    };
+    #[cfg(embedded_server)]
+    let webview_builder = if is_app {
+      webview_builder.initialization_script(&embedded_server_script(content_url))
+    } else {
+      webview_builder
+    };
+    let mut webview_builder = webview_builder.initialization_script(&format!(
           r#"
               window.__TAURI__.__windows = {window_labels_array}.map(function (label) {{ return {{ label: label }} }});
               window.__TAURI__.__currentWindow = {{ label: "{current_window_label}" }}
@@ -266,19 +511,57 @@ pub(super) fn build_webview<A: ApplicationExt + 'static>(
             serde_json::to_string(&window_labels).unwrap(),
           current_window_label = webview.label,
         ));
+    // the last initialization script, so the API object is complete when it's frozen
+    if freeze_prototype {
+      webview_builder = webview_builder.initialization_script(FREEZE_PROTOTYPE_SCRIPT);
+    }
 
     let webview_manager_ = webview_manager.clone();
+    let mut ipc_limiter = IpcLimiter::new(
+      webview.label.clone(),
+      application.context.config.tauri.security.ipc.clone(),
+    );
     let tauri_invoke_handler = crate::Callback::<A::Dispatcher> {
       name: "__TAURI_INVOKE_HANDLER__".to_string(),
       function: Box::new(move |_, _, arg| {
         let arg = arg.into_iter().next().unwrap_or_else(String::new);
         let webview_manager = webview_manager_.clone();
+        let now = Instant::now();
+        if ipc_limiter.is_enabled() {
+          if let Err(exceeded) = ipc_limiter.check_message(arg.len(), now) {
+            // only the callback names are read, skipping over the payload
+            let header = serde_json::from_str::<MessageHeader>(&arg).ok();
+            let emit = ipc_limiter.should_emit(now);
+            reject_limited(webview_manager, exceeded, header, emit);
+            return 0;
+          }
+        }
         match serde_json::from_str::<Message>(&arg) {
           Ok(message) => {
             let application = application.clone();
             let callback = message.callback.to_string();
             let error = message.error.to_string();
 
+            if !is_app {
+              if let Err(e) = check_remote_access(&remote_access, &message) {
+                crate::async_runtime::spawn(async move {
+                  execute_promise(&webview_manager, async move { Err(e) }, callback, error).await;
+                });
+                return 0;
+              }
+            }
+
+            if ipc_limiter.is_enabled() {
+              if let Some(command) = message.inner.get("cmd").and_then(JsonValue::as_str) {
+                if let Err(exceeded) = ipc_limiter.check_command(command, arg.len(), now) {
+                  let header = MessageHeader { callback, error };
+                  let emit = ipc_limiter.should_emit(now);
+                  reject_limited(webview_manager, exceeded, Some(header), emit);
+                  return 0;
+                }
+              }
+            }
+
             if message.main_thread {
               crate::async_runtime::block_on(async move {
                 execute_promise(
@@ -318,12 +601,141 @@ pub(super) fn build_webview<A: ApplicationExt + 'static>(
     };
     (webview_builder, vec![tauri_invoke_handler])
   } else {
-    (webview.builder.url(webview_url), Vec::new())
+    let mut webview_builder = webview.builder.url(webview_url);
+    if freeze_prototype {
+      webview_builder = webview_builder.initialization_script(FREEZE_PROTOTYPE_SCRIPT);
+    }
+    (webview_builder, Vec::new())
   };
 
+  // the windows that remember their state report it whenever it changes
+  if let Some(application) = window_state_application {
+    webview_builder = webview_builder.initialization_script(&window_state::report_script());
+    let label = webview.label.clone();
+    callbacks.push(crate::Callback::<A::Dispatcher> {
+      name: window_state::REPORT_CALLBACK.to_string(),
+      function: Box::new(move |_, _, args| {
+        if let (Some(window_states), Some(report)) = (&application.window_states, args.first()) {
+          // the state is saved again on the next report
+          let _ = window_states.update(&label, report, application.zooms.get(&label));
+        }
+        0
+      }),
+    });
+  }
+
+  // the zoom factor is applied again on every page load, and the hotkeys change it from the page
+  if let Some(application) = zoom_application {
+    webview_builder =
+      webview_builder.initialization_script(&zoom::report_script(zoom.hotkeys_enabled));
+    let label = webview.label.clone();
+    callbacks.push(crate::Callback::<A::Dispatcher> {
+      name: zoom::REPORT_CALLBACK.to_string(),
+      function: Box::new(move |dispatcher, _, args| {
+        let report = args
+          .first()
+          .and_then(|report| application.zooms.report(&label, report));
+        if let Some((factor, changed)) = report {
+          if changed || zoom::is_zoomed(factor) {
+            let _ = dispatcher.eval_script(zoom::apply_script(factor));
+          }
+          if let Some(window_states) = application.window_states.as_ref().filter(|_| changed) {
+            let _ = window_states.update_zoom(&label, factor);
+          }
+        }
+        0
+      }),
+    });
+  }
+
+  // closing the splashscreen shows the other windows right away
+  if let Some(application) = splashscreen_application {
+    webview_builder = webview_builder.initialization_script(&splashscreen::unload_script());
+    callbacks.push(crate::Callback::<A::Dispatcher> {
+      name: splashscreen::CLOSED_CALLBACK.to_string(),
+      function: Box::new(move |_, _, _| {
+        if let Some(splashscreen) = &application.splashscreen {
+          splashscreen.close(application.dispatchers.clone(), false);
+        }
+        0
+      }),
+    });
+  }
+
   Ok((webview_builder, callbacks))
 }
 
+/// Rejects a message that exceeded a `tauri > security > ipc` limit, emitting the limit event to the window and the Rust listeners.
+/// The message promise is rejected if its callback names could be read, otherwise the error is logged on the console.
+fn reject_limited<A: ApplicationExt + 'static>(
+  webview_manager: WebviewManager<A>,
+  exceeded: LimitExceeded,
+  header: Option<MessageHeader>,
+  emit: bool,
+) {
+  crate::async_runtime::spawn(async move {
+    let error = exceeded.to_error();
+    if emit {
+      if let Ok(payload) = serde_json::to_string(&exceeded) {
+        webview_manager.on_event(LIMIT_EXCEEDED_EVENT.to_string(), Some(payload));
+      }
+      if let Ok(dispatcher) = webview_manager.current_webview().await {
+        let _ = dispatcher.emit(LIMIT_EXCEEDED_EVENT, Some(exceeded));
+      }
+    }
+    match header {
+      Some(header) => {
+        execute_promise(
+          &webview_manager,
+          async move { Err(error) },
+          header.callback,
+          header.error,
+        )
+        .await
+      }
+      None => {
+        if let Ok(dispatcher) = webview_manager.current_webview().await {
+          let _ = dispatcher.eval(&format!(
+            r#"console.error({})"#,
+            JsonValue::String(error.to_string())
+          ));
+        }
+      }
+    }
+  });
+}
+
+/// Checks that the page origin of a remote window is allowed to send the message.
+/// The origin is read when the message is sent, so navigating away from an allowed domain revokes the access.
+/// Only the `https` origins are allowed, and the messages without an origin are rejected.
+fn check_remote_access(scopes: &[RemoteDomainAccessScope], message: &Message) -> crate::Result<()> {
+  let origin = message.origin.as_deref().unwrap_or_default();
+  let domain = url::Url::parse(origin)
+    .ok()
+    .filter(|url| url.scheme() == "https")
+    .and_then(|url| url.host_str().map(ToString::to_string));
+  let allowed = domain.map_or(false, |domain| {
+    scopes
+      .iter()
+      .filter(|scope| scope.domain == domain)
+      .any(|scope| match &message.tauri_module {
+        Some(_) => scope.enable_tauri_api,
+        None => message
+          .inner
+          .get("cmd")
+          .and_then(JsonValue::as_str)
+          .map_or(false, |cmd| {
+            scope.commands.iter().any(|command| command == cmd)
+          }),
+      })
+  });
+  if allowed {
+    Ok(())
+  } else {
+    Err(crate::Error::RemoteIpcNotAllowed(origin.to_string()))
+  }
+}
+
 /// Asynchronously executes the given task
 /// and evaluates its Result to the JS promise described by the `success_callback` and `error_callback` function names.
 ///
@@ -342,7 +754,7 @@ async fn execute_promise<
     task
       .await
       .and_then(|response| response.json)
-      .map_err(|err| err.to_string()),
+      .map_err(|err| err.to_js_value()),
     success_callback,
     error_callback.clone(),
   ) {
@@ -354,16 +766,301 @@ async fn execute_promise<
   }
 }
 
+/// Whether the files and deep links the app was opened with were already emitted.
+static LAUNCH_ARGS_EMITTED: AtomicBool = AtomicBool::new(false);
+
+/// Gets the files the app was opened with, i.e. the CLI arguments matching a file association.
+/// Always empty on macOS, where the files are delivered through Apple Events the webview runtime doesn't expose.
+fn opened_files(context: &Context) -> Vec<PathBuf> {
+  if cfg!(target_os = "macos") {
+    return Vec::new();
+  }
+  let extensions: Vec<String> = context
+    .config
+    .tauri
+    .bundle
+    .file_associations
+    .iter()
+    .flat_map(|association| association.ext.iter())
+    .map(|ext| ext.to_lowercase())
+    .collect();
+  std::env::args_os()
+    .skip(1)
+    .map(|arg| {
+      // files can also be given as `file://` URLs, e.g. by desktop entries handling deep links
+      arg
+        .to_str()
+        .filter(|arg| arg.starts_with("file://"))
+        .and_then(|arg| url::Url::parse(arg).ok())
+        .and_then(|url| url.to_file_path().ok())
+        .unwrap_or_else(|| PathBuf::from(arg))
+    })
+    .filter(|path| {
+      path.is_file()
+        && path
+          .extension()
+          .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+          .unwrap_or(false)
+    })
+    .collect()
+}
+
+/// Whether the argument is a URL using one of the deep link schemes.
+fn is_deep_link(arg: &str, schemes: &[String]) -> bool {
+  schemes.iter().any(|scheme| {
+    arg
+      .get(..scheme.len())
+      .map(|prefix| prefix.eq_ignore_ascii_case(scheme))
+      .unwrap_or(false)
+      && arg[scheme.len()..].starts_with(':')
+  })
+}
+
+/// Gets the deep links the app was opened with, i.e. the CLI arguments using one of the deep link schemes.
+fn deep_links(context: &Context) -> Vec<String> {
+  let schemes = &context.config.tauri.bundle.deep_link.schemes;
+  std::env::args_os()
+    .skip(1)
+    .filter_map(|arg| arg.into_string().ok())
+    .filter(|arg| is_deep_link(arg, schemes))
+    .collect()
+}
+
+/// The exit code of the app when its args don't match the CLI definition, read by `tauri dev`.
+#[cfg(all(cli, dev))]
+const INVALID_CLI_ARGS_EXIT_CODE: i32 = 2;
+
+/// Parses the args `tauri dev` passes through to the app against the CLI definition,
+/// exiting with the clap error so the mismatches fail on startup instead of on the first `getMatches` call.
+#[cfg(all(cli, dev))]
+pub(super) fn validate_dev_cli_args(context: &Context) {
+  let enabled = std::env::var("TAURI_CLI_ARGS_VALIDATION")
+    .map(|value| value == "true")
+    .unwrap_or(false);
+  if enabled && context.config.tauri.cli.is_some() {
+    if let Err(e) = tauri_api::cli::get_matches(&context.config) {
+      eprintln!("{}", e);
+      std::process::exit(INVALID_CLI_ARGS_EXIT_CODE);
+    }
+  }
+}
+
+/// The launch of a new instance, forwarded to the running instance.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstanceLaunch {
+  /// The CLI args, without the executable path. Empty if `forwardArgs` is disabled.
+  args: Vec<String>,
+  /// The working directory.
+  cwd: PathBuf,
+  /// The deep links the instance was opened with.
+  deep_links: Vec<String>,
+}
+
+/// The payload of the `second-instance` event.
+#[derive(Clone, Serialize)]
+struct SecondInstancePayload {
+  args: Vec<String>,
+  cwd: PathBuf,
+}
+
+// uses the WebView2 fixed runtime shipped next to the executable, if there's one
+#[cfg(windows)]
+pub(super) fn setup_webview2_fixed_runtime() {
+  if std::env::var_os("WEBVIEW2_BROWSER_EXECUTABLE_FOLDER").is_some() {
+    return;
+  }
+  if let Some(runtime_dir) = std::env::current_exe()
+    .ok()
+    .and_then(|exe| exe.parent().map(|dir| dir.join("WebView2Runtime")))
+    .filter(|dir| dir.is_dir())
+  {
+    std::env::set_var("WEBVIEW2_BROWSER_EXECUTABLE_FOLDER", runtime_dir);
+  }
+}
+
+// acquires the instance lock and listens for the launches of new instances,
+// or forwards the launch to the running instance and exits
+pub(super) fn setup_single_instance<A: ApplicationExt + 'static>(
+  application: &App<A>,
+) -> crate::Result<()> {
+  let context = &application.context;
+  let single_instance = context.config.tauri.single_instance.as_ref();
+  // the deep links are always forwarded to the running instance
+  if single_instance.is_none() && context.config.tauri.bundle.deep_link.schemes.is_empty() {
+    return Ok(());
+  }
+  let identifier = context.config.tauri.bundle.identifier.clone();
+  if identifier.is_empty() {
+    return Err(crate::Error::SingleInstance(
+      "the lock requires the `tauri > bundle > identifier` config".into(),
+    ));
+  }
+  let forward_args = single_instance
+    .map(|single_instance| single_instance.forward_args)
+    .unwrap_or(false);
+  let launch = InstanceLaunch {
+    args: if forward_args {
+      std::env::args().skip(1).collect()
+    } else {
+      Vec::new()
+    },
+    cwd: std::env::current_dir().unwrap_or_default(),
+    deep_links: deep_links(context),
+  };
+  match instance_channel::bind(&identifier) {
+    Ok(Some(listener)) => {
+      listen_instances(application, listener, identifier);
+      Ok(())
+    }
+    Ok(None) => {
+      if forward_launch(&identifier, &launch) {
+        std::process::exit(0);
+      }
+      Err(crate::Error::SingleInstance(
+        "failed to forward the launch to the running instance".into(),
+      ))
+    }
+    Err(e) => Err(crate::Error::SingleInstance(format!(
+      "failed to acquire the lock: {}",
+      e
+    ))),
+  }
+}
+
+/// Sends the launch to the running instance, returning whether it was received.
+fn forward_launch(identifier: &str, launch: &InstanceLaunch) -> bool {
+  let stream = match instance_channel::connect(identifier) {
+    Ok(stream) => stream,
+    Err(_) => return false,
+  };
+  let _ = instance_channel::set_read_timeout(&stream, INSTANCE_TIMEOUT);
+  // the running instance echoes the identifier, so the launches aren't sent to other apps
+  if writeln!(&stream, "{}", identifier).is_err() {
+    return false;
+  }
+  let mut reply = String::new();
+  if BufReader::new(&stream).read_line(&mut reply).is_err() || reply.trim_end() != identifier {
+    return false;
+  }
+  let launch = serde_json::to_string(launch).expect("failed to serialize the instance launch");
+  writeln!(&stream, "{}", launch).is_ok()
+}
+
+/// The time the instances wait for each other's messages.
+const INSTANCE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Reads the launch of a new instance, or returns `None` if the other process isn't an instance of the app.
+fn read_launch(stream: &InstanceStream, identifier: &str) -> Option<InstanceLaunch> {
+  let _ = instance_channel::set_read_timeout(stream, INSTANCE_TIMEOUT);
+  let mut reader = BufReader::new(stream);
+  let mut line = String::new();
+  // the first line identifies the app
+  if reader.read_line(&mut line).is_err() || line.trim_end() != identifier {
+    return None;
+  }
+  writeln!(&*stream, "{}", identifier).ok()?;
+  line.clear();
+  reader.read_line(&mut line).ok()?;
+  serde_json::from_str(&line).ok()
+}
+
+// emits the launches of the new instances to the windows,
+// reading each launch on its own thread so a slow instance doesn't hold the next ones
+fn listen_instances<A: ApplicationExt + 'static>(
+  application: &App<A>,
+  mut listener: InstanceListener,
+  identifier: String,
+) {
+  let dispatchers = application.dispatchers.clone();
+  let single_instance = application.context.config.tauri.single_instance.as_ref();
+  let focus_existing = single_instance
+    .map(|single_instance| single_instance.focus_existing)
+    .unwrap_or(false);
+  let single_instance = single_instance.is_some();
+  let main_window = application
+    .context
+    .config
+    .tauri
+    .windows
+    .first()
+    .map(|window| window.label.clone());
+  std::thread::spawn(move || loop {
+    let stream = match listener.accept() {
+      Ok(stream) => stream,
+      Err(_) => continue,
+    };
+    let dispatchers = dispatchers.clone();
+    let identifier = identifier.clone();
+    let main_window = main_window.clone();
+    std::thread::spawn(move || {
+      let launch = match read_launch(&stream, &identifier) {
+        Some(launch) => launch,
+        None => return,
+      };
+      let dispatchers = crate::async_runtime::block_on(dispatchers.lock());
+      for dispatcher in dispatchers.values() {
+        for url in &launch.deep_links {
+          let _ = super::event::emit(dispatcher, "deep-link", Some(url.clone()));
+        }
+        if single_instance {
+          let payload = SecondInstancePayload {
+            args: launch.args.clone(),
+            cwd: launch.cwd.clone(),
+          };
+          let _ = super::event::emit(dispatcher, "second-instance", Some(payload));
+        }
+      }
+      if focus_existing {
+        let main_window = main_window
+          .as_ref()
+          .and_then(|label| dispatchers.get(label))
+          .or_else(|| dispatchers.values().next());
+        if let Some(dispatcher) = main_window {
+          let _ = dispatcher.unminimize();
+          let _ = dispatcher.show();
+          let _ = dispatcher.set_focus();
+        }
+      }
+    });
+  });
+}
+
 async fn on_message<A: ApplicationExt + 'static>(
   application: Arc<App<A>>,
   webview_manager: WebviewManager<A>,
   message: Message,
 ) -> crate::Result<InvokeResponse> {
   if message.inner == serde_json::json!({ "cmd":"__initialized" }) {
+    super::startup_trace::mark_once(
+      super::startup_trace::FIRST_NAVIGATION,
+      Some(webview_manager.current_window_label()),
+    );
     application.run_setup(&webview_manager).await;
     crate::plugin::ready(A::plugin_store(), &webview_manager).await;
+    if !LAUNCH_ARGS_EMITTED.swap(true, Ordering::SeqCst) {
+      let dispatcher = webview_manager.current_webview().await?;
+      for path in opened_files(&application.context) {
+        dispatcher.emit("open-file", Some(path.display().to_string()))?;
+      }
+      for url in deep_links(&application.context) {
+        dispatcher.emit("deep-link", Some(url))?;
+      }
+      #[cfg(global_shortcut)]
+      for failure in super::global_shortcuts::take_registration_failures() {
+        dispatcher.emit(
+          super::global_shortcuts::REGISTRATION_FAILED_EVENT,
+          Some(failure),
+        )?;
+      }
+    }
     Ok(().into())
   } else {
+    super::startup_trace::mark_once(
+      super::startup_trace::FIRST_IPC_MESSAGE,
+      Some(webview_manager.current_window_label()),
+    );
+    super::startup_trace::finish();
     let response = if let Some(module) = &message.tauri_module {
       crate::endpoints::handle(
         &webview_manager,
@@ -413,20 +1110,131 @@ mod test {
 
     #[cfg(embedded_server)]
     match res {
-      Ok(u) => assert!(u.contains("http://")),
+      Ok(u) => {
+        assert!(u.contains("http://"));
+        let port = std::env::var(super::EMBEDDED_SERVER_PORT_ENV).unwrap();
+        assert!(u.ends_with(&format!(":{}", port)));
+      }
       _ => panic!("setup content failed"),
     }
 
     #[cfg(dev)]
     {
       let config = &context.config;
-      match res {
-        Ok(u) => assert_eq!(u, config.build.dev_path),
+      match (res, &config.build.dev_path) {
+        (Ok(u), tauri_api::config::AppUrl::Url(dev_url)) => assert_eq!(u, dev_url.to_string()),
+        (Ok(u), _) => assert!(u.starts_with("data:text/html;base64,")),
         _ => panic!("setup content failed"),
       }
     }
   }
 
+  #[test]
+  fn check_remote_access() {
+    let scopes = vec![crate::api::config::RemoteDomainAccessScope {
+      domain: "partner.example.com".to_string(),
+      windows: vec!["partner".to_string()],
+      enable_tauri_api: false,
+      commands: vec!["sync".to_string()],
+    }];
+    let allowed = |origin: Option<&str>, mut message: serde_json::Value| {
+      message["callback"] = "callback".into();
+      message["error"] = "error".into();
+      if let Some(origin) = origin {
+        message["__origin"] = origin.into();
+      }
+      let message: super::Message = serde_json::from_value(message).unwrap();
+      super::check_remote_access(&scopes, &message).is_ok()
+    };
+    assert!(allowed(
+      Some("https://partner.example.com"),
+      serde_json::json!({ "cmd": "sync" })
+    ));
+    assert!(!allowed(
+      Some("https://partner.example.com"),
+      serde_json::json!({ "cmd": "delete" })
+    ));
+    assert!(!allowed(
+      Some("https://partner.example.com"),
+      serde_json::json!({ "__tauriModule": "Fs", "cmd": "readTextFile" })
+    ));
+    // the window navigated to another domain
+    assert!(!allowed(
+      Some("https://evil.example.com"),
+      serde_json::json!({ "cmd": "sync" })
+    ));
+    // the scheme is checked too
+    assert!(!allowed(
+      Some("http://partner.example.com"),
+      serde_json::json!({ "cmd": "sync" })
+    ));
+    // the origin is unknown
+    for origin in &[None, Some("null"), Some("")] {
+      assert!(
+        !allowed(*origin, serde_json::json!({ "cmd": "sync" })),
+        "{:?}",
+        origin
+      );
+    }
+  }
+
+  #[test]
+  // the launches are only sent to the instances of the same app
+  fn forward_launch_checks_identifier() {
+    use std::io::{BufRead, BufReader, Write};
+    let launch = super::InstanceLaunch {
+      args: vec!["--flag".into()],
+      cwd: std::env::temp_dir(),
+      deep_links: vec![],
+    };
+    let identifier = format!("com.tauri.test{}", std::process::id());
+    let mut listener = super::instance_channel::bind(&identifier).unwrap().unwrap();
+    let expected = identifier.clone();
+    let instance = std::thread::spawn(move || {
+      let mut received = Vec::new();
+      for reply in &[expected.as_str(), "com.other.app"] {
+        let stream = listener.accept().unwrap();
+        let mut lines = BufReader::new(&stream).lines();
+        assert_eq!(lines.next().unwrap().unwrap(), expected);
+        writeln!(&stream, "{}", reply).unwrap();
+        received.push(lines.next().and_then(|line| line.ok()));
+      }
+      (received, listener)
+    });
+    assert!(super::forward_launch(&identifier, &launch));
+    assert!(!super::forward_launch(&identifier, &launch));
+    let (received, _listener) = instance.join().unwrap();
+    assert!(received[0].is_some());
+    assert!(received[1].is_none());
+    // the lock is held while the listener lives
+    assert!(super::instance_channel::bind(&identifier)
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  // the prototypes and the API object can't be patched or replaced by the page scripts
+  fn check_freeze_prototype_script() {
+    let script = super::FREEZE_PROTOTYPE_SCRIPT;
+    for constructor in &["Object", "Function", "Array", "Promise"] {
+      assert!(script.contains(&format!("{},", constructor)));
+    }
+    assert!(script.contains("Object.freeze(constructor.prototype)"));
+    assert!(script.contains("})(window.__TAURI__)"));
+    assert!(script.contains("writable: false"));
+    assert!(script.contains("configurable: false"));
+  }
+
+  #[test]
+  fn check_is_deep_link() {
+    let schemes = vec!["myapp".to_string()];
+    assert!(super::is_deep_link("myapp://auth/callback", &schemes));
+    assert!(super::is_deep_link("MyApp:open", &schemes));
+    assert!(!super::is_deep_link("myapp", &schemes));
+    assert!(!super::is_deep_link("myapplication://auth", &schemes));
+    assert!(!super::is_deep_link("--myapp://auth", &schemes));
+  }
+
   proptest! {
     #![proptest_config(ProptestConfig::with_cases(10000))]
     #[cfg(embedded_server)]
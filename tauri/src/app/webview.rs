@@ -32,6 +32,8 @@ pub enum Message {
   Show,
   /// Hides the window.
   Hide,
+  /// Focuses the window.
+  SetFocus,
   /// Updates the transparency flag.
   SetTransparent(bool),
   /// Updates the hasDecorations flag.
@@ -82,6 +84,57 @@ pub enum Message {
 
 pub struct WindowConfig(pub crate::api::config::WindowConfig);
 
+/// A monitor, which the saved window positions are clamped to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Monitor {
+  /// The horizontal position of the monitor's top left corner in physical pixels.
+  pub x: f64,
+  /// The vertical position of the monitor's top left corner in physical pixels.
+  pub y: f64,
+  /// The monitor width in physical pixels.
+  pub width: f64,
+  /// The monitor height in physical pixels.
+  pub height: f64,
+  /// The ratio of physical pixels to logical pixels.
+  pub scale_factor: f64,
+}
+
+/// The window sizes in physical pixels.
+#[derive(Debug, PartialEq)]
+pub(crate) struct PhysicalSizes {
+  pub(crate) width: f64,
+  pub(crate) height: f64,
+  pub(crate) min_width: Option<f64>,
+  pub(crate) min_height: Option<f64>,
+  pub(crate) max_width: Option<f64>,
+  pub(crate) max_height: Option<f64>,
+}
+
+impl WindowConfig {
+  /// Resolves the window sizes with the monitor scale factor.
+  /// All of them use the same scale factor, and the size is clamped to the resolved min and max sizes,
+  /// so the constraints can't conflict with the initial size.
+  pub(crate) fn physical_sizes(&self, scale_factor: f64) -> PhysicalSizes {
+    let resolve = |size: crate::api::config::WindowSize| size.to_physical(scale_factor);
+    let min_width = self.0.min_width.map(resolve);
+    let min_height = self.0.min_height.map(resolve);
+    let max_width = self.0.max_width.map(resolve);
+    let max_height = self.0.max_height.map(resolve);
+    let clamp = |size: f64, min: Option<f64>, max: Option<f64>| {
+      let size = max.map_or(size, |max| size.min(max));
+      min.map_or(size, |min| size.max(min))
+    };
+    PhysicalSizes {
+      width: clamp(resolve(self.0.width), min_width, max_width),
+      height: clamp(resolve(self.0.height), min_height, max_height),
+      min_width,
+      min_height,
+      max_width,
+      max_height,
+    }
+  }
+}
+
 pub trait WebviewBuilderExtPrivate: Sized {
   /// Sets the webview url.
   fn url(self, url: String) -> Self;
@@ -147,6 +200,9 @@ pub trait WebviewBuilderExt: Sized {
   /// Whether the window should always be on top of other windows.
   fn always_on_top(self, always_on_top: bool) -> Self;
 
+  /// Whether the webview should show its native context menu.
+  fn context_menu(self, context_menu: bool) -> Self;
+
   /// Builds the webview instance.
   fn finish(self) -> crate::Result<Self::Webview>;
 }
@@ -198,6 +254,12 @@ pub trait ApplicationDispatcherExt: Clone + Send + Sync + Sized {
   /// Hides the window.
   fn hide(&self) -> crate::Result<()>;
 
+  /// Closes the window.
+  fn close(&self) -> crate::Result<()>;
+
+  /// Brings the window to the front and focuses it.
+  fn set_focus(&self) -> crate::Result<()>;
+
   /// Updates the transparency flag.
   fn set_transparent(&self, resizable: bool) -> crate::Result<()>;
 
@@ -259,6 +321,9 @@ pub trait ApplicationExt: Sized {
   /// Creates a new application.
   fn new() -> crate::Result<Self>;
 
+  /// The available monitors, the primary one first.
+  fn available_monitors(&self) -> Vec<Monitor>;
+
   /// Creates a new webview.
   fn create_webview(
     &mut self,
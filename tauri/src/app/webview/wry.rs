@@ -1,5 +1,5 @@
 use super::{
-  ApplicationDispatcherExt, ApplicationExt, Callback, Icon, WebviewBuilderExt,
+  ApplicationDispatcherExt, ApplicationExt, Callback, Icon, Monitor, WebviewBuilderExt,
   WebviewBuilderExtPrivate, WindowConfig,
 };
 
@@ -36,27 +36,30 @@ impl WebviewBuilderExtPrivate for wry::Attributes {
 
 impl From<WindowConfig> for wry::Attributes {
   fn from(window_config: WindowConfig) -> Self {
+    // the webview runtime can't read the monitor scale factor, so the sizes are resolved without scaling
+    let sizes = window_config.physical_sizes(1f64);
     let mut webview = wry::Attributes::default()
       .title(window_config.0.title.to_string())
-      .width(window_config.0.width)
-      .height(window_config.0.height)
+      .width(sizes.width)
+      .height(sizes.height)
       .visible(window_config.0.visible)
       .resizable(window_config.0.resizable)
       .decorations(window_config.0.decorations)
       .maximized(window_config.0.maximized)
       .fullscreen(window_config.0.fullscreen)
       .transparent(window_config.0.transparent)
-      .always_on_top(window_config.0.always_on_top);
-    if let Some(min_width) = window_config.0.min_width {
+      .always_on_top(window_config.0.always_on_top)
+      .context_menu(window_config.0.context_menu.unwrap_or(true));
+    if let Some(min_width) = sizes.min_width {
       webview = webview.min_width(min_width);
     }
-    if let Some(min_height) = window_config.0.min_height {
+    if let Some(min_height) = sizes.min_height {
       webview = webview.min_height(min_height);
     }
-    if let Some(max_width) = window_config.0.max_width {
+    if let Some(max_width) = sizes.max_width {
       webview = webview.max_width(max_width);
     }
-    if let Some(max_height) = window_config.0.max_height {
+    if let Some(max_height) = sizes.max_height {
       webview = webview.max_height(max_height);
     }
     if let Some(x) = window_config.0.x {
@@ -163,6 +166,16 @@ impl WebviewBuilderExt for wry::Attributes {
     self
   }
 
+  fn context_menu(mut self, context_menu: bool) -> Self {
+    if !context_menu {
+      self.initialization_scripts.push(
+        "window.addEventListener('contextmenu', function (e) { e.preventDefault() }, true)"
+          .to_string(),
+      );
+    }
+    self
+  }
+
   fn finish(self) -> crate::Result<Self::Webview> {
     Ok(self)
   }
@@ -283,6 +296,14 @@ impl ApplicationDispatcherExt for WryDispatcher {
       .map_err(|_| crate::Error::FailedToSendMessage)
   }
 
+  fn close(&self) -> crate::Result<()> {
+    Err(crate::Error::UnsupportedWindowOption("close".into()))
+  }
+
+  fn set_focus(&self) -> crate::Result<()> {
+    Err(crate::Error::UnsupportedWindowOption("focus".into()))
+  }
+
   fn set_transparent(&self, transparent: bool) -> crate::Result<()> {
     self
       .0
@@ -429,6 +450,11 @@ impl ApplicationExt for WryApplication {
     Ok(Self { inner: app })
   }
 
+  fn available_monitors(&self) -> Vec<Monitor> {
+    // the webview runtime can't read the monitors, so the saved window positions aren't restored
+    Vec::new()
+  }
+
   fn create_webview(
     &mut self,
     webview_builder: Self::WebviewBuilder,
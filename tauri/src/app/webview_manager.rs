@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use super::{
-  App, ApplicationDispatcherExt, ApplicationExt, Icon, Webview, WebviewBuilderExt,
+  App, ApplicationDispatcherExt, ApplicationExt, Icon, Webview, WebviewApi, WebviewBuilderExt,
   WebviewInitializer,
 };
 use crate::{api::config::WindowUrl, async_runtime::Mutex};
@@ -96,6 +96,18 @@ impl<A: ApplicationDispatcherExt> WebviewDispatcher<A> {
     self.dispatcher.hide()
   }
 
+  /// Closes the window.
+  /// Fails with [`crate::Error::UnsupportedWindowOption`] on the current webview runtime, which can't destroy a window.
+  pub fn close(&self) -> crate::Result<()> {
+    self.dispatcher.close()
+  }
+
+  /// Brings the window to the front and focuses it.
+  /// Fails with [`crate::Error::UnsupportedWindowOption`] on the current webview runtime, which can't focus a window.
+  pub fn set_focus(&self) -> crate::Result<()> {
+    self.dispatcher.set_focus()
+  }
+
   /// Sets the window transparent flag.
   pub fn set_transparent(&self, transparent: bool) -> crate::Result<()> {
     self.dispatcher.set_transparent(transparent)
@@ -209,6 +221,11 @@ impl<A: ApplicationExt + 'static> WebviewManager<A> {
     &self.current_webview_window_label
   }
 
+  /// Whether the feature of the `features` config is enabled.
+  pub fn feature_enabled(&self, feature: &str) -> bool {
+    self.application.context.config.feature_enabled(feature)
+  }
+
   /// Gets the webview associated with the current context.
   pub async fn current_webview(&self) -> crate::Result<WebviewDispatcher<A::Dispatcher>> {
     self.get_webview(&self.current_webview_window_label).await
@@ -234,12 +251,28 @@ impl<A: ApplicationExt + 'static> WebviewManager<A> {
     label: String,
     url: WindowUrl,
     f: F,
+  ) -> crate::Result<WebviewDispatcher<A::Dispatcher>> {
+    self
+      .create_webview_with_api(label, url, Default::default(), f)
+      .await
+  }
+
+  /// Creates a new webview with the API it can call.
+  pub(crate) async fn create_webview_with_api<
+    F: FnOnce(A::WebviewBuilder) -> crate::Result<A::WebviewBuilder>,
+  >(
+    &self,
+    label: String,
+    url: WindowUrl,
+    api: WebviewApi,
+    f: F,
   ) -> crate::Result<WebviewDispatcher<A::Dispatcher>> {
     let builder = f(A::WebviewBuilder::new())?;
     let webview = Webview {
       url,
       label: label.to_string(),
       builder,
+      api,
     };
     self
       .application
@@ -306,6 +339,53 @@ impl<A: ApplicationExt + 'static> WebviewManager<A> {
     Ok(())
   }
 
+  /// Shows the other windows and closes the startup splashscreen, once it's been shown for its minimum duration.
+  /// Does nothing if the app has no splashscreen or if it's already closed.
+  pub fn close_splashscreen(&self) {
+    if let Some(splashscreen) = &self.application.splashscreen {
+      splashscreen.close(self.dispatchers.clone(), true);
+    }
+  }
+
+  /// Applies the saved state to the window config if the window remembers its state.
+  #[cfg(create_window)]
+  pub(crate) fn restore_window_state(&self, window: &mut crate::api::config::WindowConfig) {
+    if let Some(window_states) = &self.application.window_states {
+      window_states.restore(window);
+    }
+  }
+
+  /// The page zoom factor of the current window.
+  pub(crate) fn zoom(&self) -> f64 {
+    self
+      .application
+      .zooms
+      .get(self.current_window_label())
+      .unwrap_or(1.0)
+  }
+
+  /// Sets the page zoom factor of the current window, clamped to its `zoom` config,
+  /// and saves it if the window remembers its state. Returns the applied factor.
+  pub(crate) async fn set_zoom(&self, factor: f64) -> crate::Result<f64> {
+    if !factor.is_finite() || factor <= 0.0 {
+      return Err(crate::Error::InvalidZoomFactor(factor));
+    }
+    let label = self.current_window_label();
+    let factor = self
+      .application
+      .zooms
+      .set(label, factor)
+      .ok_or(crate::Error::WebviewNotFound)?;
+    self
+      .current_webview()
+      .await?
+      .eval(&super::zoom::apply_script(factor))?;
+    if let Some(window_states) = &self.application.window_states {
+      window_states.update_zoom(label, factor)?;
+    }
+    Ok(factor)
+  }
+
   /// Emits a global event from the webview.
   pub(crate) fn on_event(&self, event: String, data: Option<String>) {
     super::event::on_event(event, None, data)
@@ -0,0 +1,349 @@
+use std::{
+  collections::{HashMap, HashSet},
+  fs,
+  path::{Path, PathBuf},
+  sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::webview::Monitor;
+use crate::api::config::{WindowConfig, WindowSize};
+
+/// The file with the saved window states, on the app directory.
+const WINDOW_STATE_FILE: &str = ".window-state.json";
+/// The global function the windows that remember their state report it with.
+pub(super) const REPORT_CALLBACK: &str = "__TAURI_WINDOW_STATE__";
+
+/// Reports the window position, size, maximized and fullscreen state in physical pixels whenever it changes.
+/// The runtime has no window move events, so the position is polled.
+/// The hidden and minimized windows aren't reported, so they keep their last visible state.
+pub(super) fn report_script() -> String {
+  format!(
+    r#"
+      (function () {{
+        var last = null
+        function report() {{
+          if (document.visibilityState === 'hidden' || !window.outerWidth) {{
+            return
+          }}
+          var ratio = window.devicePixelRatio || 1
+          var fullscreen = window.innerWidth === screen.width && window.innerHeight === screen.height
+          var state = JSON.stringify({{
+            x: Math.round(window.screenX * ratio),
+            y: Math.round(window.screenY * ratio),
+            width: Math.round(window.outerWidth * ratio),
+            height: Math.round(window.outerHeight * ratio),
+            maximized: !fullscreen && window.outerWidth >= screen.availWidth && window.outerHeight >= screen.availHeight,
+            fullscreen: fullscreen
+          }})
+          if (state !== last) {{
+            last = state
+            window.{callback}(state)
+          }}
+        }}
+        window.addEventListener('resize', report)
+        window.addEventListener('pagehide', report)
+        setInterval(report, 1000)
+      }})()
+    "#,
+    callback = REPORT_CALLBACK
+  )
+}
+
+/// The saved state of a window, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct WindowState {
+  x: f64,
+  y: f64,
+  width: f64,
+  height: f64,
+  maximized: bool,
+  fullscreen: bool,
+  /// The page zoom factor, missing on the states saved before the window zoom.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  zoom: Option<f64>,
+}
+
+impl WindowState {
+  /// Keeps the window on the monitor with its center, or centers it on the primary monitor if its center isn't on any,
+  /// e.g. because the monitor it was on was unplugged. The window is shrunk to fit the monitor.
+  fn clamp(mut self, monitors: &[Monitor]) -> Self {
+    let (center_x, center_y) = (self.x + self.width / 2.0, self.y + self.height / 2.0);
+    let containing = monitors.iter().find(|monitor| {
+      center_x >= monitor.x
+        && center_x < monitor.x + monitor.width
+        && center_y >= monitor.y
+        && center_y < monitor.y + monitor.height
+    });
+    let (monitor, centered) = match (containing, monitors.first()) {
+      (Some(monitor), _) => (monitor, false),
+      (None, Some(primary)) => (primary, true),
+      (None, None) => return self,
+    };
+    self.width = self.width.min(monitor.width);
+    self.height = self.height.min(monitor.height);
+    if centered {
+      self.x = monitor.x + (monitor.width - self.width) / 2.0;
+      self.y = monitor.y + (monitor.height - self.height) / 2.0;
+    } else {
+      self.x = self
+        .x
+        .max(monitor.x)
+        .min(monitor.x + monitor.width - self.width);
+      self.y = self
+        .y
+        .max(monitor.y)
+        .min(monitor.y + monitor.height - self.height);
+    }
+    self
+  }
+}
+
+/// The window states, saved on the app directory whenever a window that remembers its state reports a change.
+pub(crate) struct WindowStates {
+  path: Option<PathBuf>,
+  monitors: Vec<Monitor>,
+  states: Mutex<HashMap<String, WindowState>>,
+  /// The labels of the windows that remember their state.
+  tracked: Mutex<HashSet<String>>,
+}
+
+impl WindowStates {
+  /// Reads the saved window states. A missing or invalid file gives no saved states.
+  pub(super) fn load(monitors: Vec<Monitor>) -> Self {
+    let path = tauri_api::path::app_dir().map(|dir| dir.join(WINDOW_STATE_FILE));
+    let states = path
+      .as_ref()
+      .and_then(|path| fs::read_to_string(path).ok())
+      .and_then(|states| serde_json::from_str(&states).ok())
+      .unwrap_or_default();
+    Self {
+      path,
+      monitors,
+      states: Mutex::new(states),
+      tracked: Default::default(),
+    }
+  }
+
+  /// Applies the saved state to the window if it remembers its state,
+  /// so its config position and size only apply on the first run.
+  /// The saved position is skipped when the monitors are unknown, since it can't be checked to be on-screen.
+  pub(crate) fn restore(&self, window: &mut WindowConfig) {
+    if !window.remember_state {
+      return;
+    }
+    self.tracked.lock().unwrap().insert(window.label.clone());
+    let state = match self.states.lock().unwrap().get(&window.label) {
+      Some(state) => state.clamp(&self.monitors),
+      None => return,
+    };
+    if !self.monitors.is_empty() {
+      window.x = Some(state.x);
+      window.y = Some(state.y);
+    }
+    window.width = WindowSize::Physical(state.width);
+    window.height = WindowSize::Physical(state.height);
+    window.maximized = state.maximized;
+    window.fullscreen = state.fullscreen;
+  }
+
+  /// The saved zoom factor of the window with the given label, if it remembers its state.
+  pub(super) fn saved_zoom(&self, label: &str) -> Option<f64> {
+    if !self.tracks(label) {
+      return None;
+    }
+    self
+      .states
+      .lock()
+      .unwrap()
+      .get(label)
+      .and_then(|state| state.zoom)
+  }
+
+  /// Whether the window with the given label remembers its state.
+  pub(super) fn tracks(&self, label: &str) -> bool {
+    self.tracked.lock().unwrap().contains(label)
+  }
+
+  /// Saves the reported window state, with the current zoom factor of the window.
+  /// An invalid report is ignored.
+  pub(super) fn update(&self, label: &str, report: &str, zoom: Option<f64>) -> std::io::Result<()> {
+    let report: WindowState = match serde_json::from_str(report) {
+      Ok(report) => report,
+      Err(_) => return Ok(()),
+    };
+    let mut states = self.states.lock().unwrap();
+    merge(&mut states, label, WindowState { zoom, ..report });
+    self.save(&states)
+  }
+
+  /// Saves the zoom factor of the window if it remembers its state.
+  /// The window that didn't report its state yet saves it with its first report.
+  pub(super) fn update_zoom(&self, label: &str, zoom: f64) -> std::io::Result<()> {
+    if !self.tracks(label) {
+      return Ok(());
+    }
+    let mut states = self.states.lock().unwrap();
+    match states.get_mut(label) {
+      Some(state) => {
+        state.zoom = Some(zoom);
+        self.save(&states)
+      }
+      None => Ok(()),
+    }
+  }
+
+  fn save(&self, states: &HashMap<String, WindowState>) -> std::io::Result<()> {
+    match &self.path {
+      Some(path) => save(path, states),
+      None => Ok(()),
+    }
+  }
+}
+
+fn save(path: &Path, states: &HashMap<String, WindowState>) -> std::io::Result<()> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  fs::write(path, serde_json::to_vec(states)?)
+}
+
+/// Updates the window state with the report.
+/// The maximized and fullscreen windows keep their previous bounds, so they're restored to them once unmaximized.
+fn merge(states: &mut HashMap<String, WindowState>, label: &str, report: WindowState) {
+  let state = states.entry(label.to_string()).or_insert(report);
+  if !report.maximized && !report.fullscreen {
+    state.x = report.x;
+    state.y = report.y;
+    state.width = report.width;
+    state.height = report.height;
+  }
+  state.maximized = report.maximized;
+  state.fullscreen = report.fullscreen;
+  if report.zoom.is_some() {
+    state.zoom = report.zoom;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{merge, Monitor, WindowState, WindowStates};
+  use crate::api::config::{WindowConfig, WindowSize};
+  use std::collections::HashMap;
+
+  fn state(x: f64, y: f64, width: f64, height: f64) -> WindowState {
+    WindowState {
+      x,
+      y,
+      width,
+      height,
+      maximized: false,
+      fullscreen: false,
+      zoom: None,
+    }
+  }
+
+  #[test]
+  fn clamps_to_the_monitors() {
+    let primary = Monitor {
+      x: 0.0,
+      y: 0.0,
+      width: 1920.0,
+      height: 1080.0,
+      scale_factor: 1.0,
+    };
+    let secondary = Monitor {
+      x: 1920.0,
+      width: 1280.0,
+      height: 720.0,
+      ..primary
+    };
+    let monitors = vec![primary, secondary];
+    // on screen
+    assert_eq!(
+      state(100.0, 100.0, 800.0, 600.0).clamp(&monitors),
+      state(100.0, 100.0, 800.0, 600.0)
+    );
+    // partially off the secondary monitor and larger than it
+    assert_eq!(
+      state(2500.0, 100.0, 1000.0, 900.0).clamp(&monitors),
+      state(2200.0, 0.0, 1000.0, 720.0)
+    );
+    // the secondary monitor was unplugged
+    assert_eq!(
+      state(2500.0, 100.0, 800.0, 600.0).clamp(&monitors[..1]),
+      state(560.0, 240.0, 800.0, 600.0)
+    );
+  }
+
+  #[test]
+  fn skips_the_position_without_monitors() {
+    let mut states = HashMap::new();
+    states.insert("main".to_string(), state(2500.0, 100.0, 800.0, 600.0));
+    let window_states = WindowStates {
+      path: None,
+      monitors: Vec::new(),
+      states: states.into(),
+      tracked: Default::default(),
+    };
+    let mut window = WindowConfig {
+      remember_state: true,
+      ..Default::default()
+    };
+    window_states.restore(&mut window);
+    assert_eq!(window.x, None);
+    assert_eq!(window.y, None);
+    assert_eq!(window.width, WindowSize::Physical(800.0));
+    assert_eq!(window.height, WindowSize::Physical(600.0));
+  }
+
+  #[test]
+  fn maximized_keeps_the_bounds() {
+    let mut states = HashMap::new();
+    merge(&mut states, "main", state(100.0, 100.0, 800.0, 600.0));
+    merge(
+      &mut states,
+      "main",
+      WindowState {
+        maximized: true,
+        ..state(0.0, 0.0, 1920.0, 1080.0)
+      },
+    );
+    assert_eq!(
+      states["main"],
+      WindowState {
+        maximized: true,
+        ..state(100.0, 100.0, 800.0, 600.0)
+      }
+    );
+  }
+
+  #[test]
+  fn keeps_the_zoom() {
+    // the states saved before the window zoom have no zoom factor
+    let saved: WindowState = serde_json::from_str(
+      r#"{"x":0,"y":0,"width":800,"height":600,"maximized":false,"fullscreen":false}"#,
+    )
+    .unwrap();
+    assert_eq!(saved, state(0.0, 0.0, 800.0, 600.0));
+
+    let mut states = HashMap::new();
+    merge(
+      &mut states,
+      "main",
+      WindowState {
+        zoom: Some(1.5),
+        ..state(100.0, 100.0, 800.0, 600.0)
+      },
+    );
+    merge(&mut states, "main", state(200.0, 100.0, 800.0, 600.0));
+    assert_eq!(
+      states["main"],
+      WindowState {
+        zoom: Some(1.5),
+        ..state(200.0, 100.0, 800.0, 600.0)
+      }
+    );
+  }
+}
@@ -0,0 +1,192 @@
+//! The `zoom` window config: the page zoom factor of the windows, their zoom hotkeys and the `setZoom` and `getZoom` commands.
+//!
+//! The runtime has no native zoom, so the factor is applied with the CSS `zoom` of the page, again on every page load.
+//! The hotkeys skip the key presses the page already handled, and the `Ctrl` + wheel events are left to the page.
+
+use crate::api::config::ZoomConfig;
+
+use std::{collections::HashMap, sync::Mutex};
+
+/// The global function the windows report their page loads and zoom hotkeys with.
+pub(super) const REPORT_CALLBACK: &str = "__TAURI_ZOOM__";
+/// The report of a page load, answered with the current factor.
+const LOAD: &str = "load";
+
+/// The zoom levels the hotkeys step through, as on the browsers.
+const LEVELS: &[f64] = &[
+  0.25, 0.33, 0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0, 5.0,
+];
+/// The difference under which a factor is considered on a level, so the rounding errors don't skip levels.
+const LEVEL_TOLERANCE: f64 = 0.001;
+
+/// Reports the page loads once the document is parsed, and the zoom hotkeys the page didn't handle if they're enabled.
+/// The listener is on the window, so it runs after the listeners of the page elements and document.
+pub(super) fn report_script(hotkeys_enabled: bool) -> String {
+  let mut script = format!(
+    "document.addEventListener('DOMContentLoaded', function () {{ window.{}('{}') }})",
+    REPORT_CALLBACK, LOAD
+  );
+  if hotkeys_enabled {
+    script.push_str(&format!(
+      r#"
+        ;(function () {{
+          var mac = navigator.platform.indexOf('Mac') === 0
+          window.addEventListener('keydown', function (event) {{
+            if (event.defaultPrevented || event.altKey || !(mac ? event.metaKey : event.ctrlKey)) {{
+              return
+            }}
+            var action
+            switch (event.key) {{
+              case '+':
+              case '=':
+                action = 'in'
+                break
+              case '-':
+              case '_':
+                action = 'out'
+                break
+              case '0':
+                action = 'reset'
+                break
+              default:
+                return
+            }}
+            event.preventDefault()
+            window.{callback}(action)
+          }})
+        }})()
+      "#,
+      callback = REPORT_CALLBACK
+    ));
+  }
+  script
+}
+
+/// Whether the page needs the zoom style for the factor.
+pub(super) fn is_zoomed(factor: f64) -> bool {
+  (factor - 1.0).abs() >= LEVEL_TOLERANCE
+}
+
+/// Applies the factor to the page, once the document element exists.
+/// The unzoomed page gets no `zoom` style, as without the zoom config.
+pub(crate) fn apply_script(factor: f64) -> String {
+  format!(
+    r#"
+      (function (factor) {{
+        function apply() {{
+          document.documentElement.style.zoom = factor === 1 ? '' : String(factor)
+        }}
+        if (document.documentElement) {{
+          apply()
+        }} else {{
+          document.addEventListener('DOMContentLoaded', apply)
+        }}
+      }})({})
+    "#,
+    factor
+  )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WindowZoom {
+  config: ZoomConfig,
+  factor: f64,
+}
+
+/// The zoom factors of the windows, by label.
+#[derive(Default)]
+pub(crate) struct Zooms {
+  windows: Mutex<HashMap<String, WindowZoom>>,
+}
+
+impl Zooms {
+  /// Registers the window zoom, starting at the saved factor or the default factor. Returns the starting factor.
+  pub(super) fn register(&self, label: &str, config: ZoomConfig, saved: Option<f64>) -> f64 {
+    let factor = config.clamp(saved.unwrap_or(config.default_factor));
+    self
+      .windows
+      .lock()
+      .unwrap()
+      .insert(label.to_string(), WindowZoom { config, factor });
+    factor
+  }
+
+  /// The zoom factor of the window.
+  pub(crate) fn get(&self, label: &str) -> Option<f64> {
+    self
+      .windows
+      .lock()
+      .unwrap()
+      .get(label)
+      .map(|window| window.factor)
+  }
+
+  /// Sets the zoom factor of the window, clamped to its `min` and `max`. Returns the applied factor.
+  pub(crate) fn set(&self, label: &str, factor: f64) -> Option<f64> {
+    let mut windows = self.windows.lock().unwrap();
+    let window = windows.get_mut(label)?;
+    window.factor = window.config.clamp(factor);
+    Some(window.factor)
+  }
+
+  /// Handles a report of the window script. Returns the current factor and whether the report changed it,
+  /// or `None` for an unknown window or report.
+  pub(super) fn report(&self, label: &str, action: &str) -> Option<(f64, bool)> {
+    let mut windows = self.windows.lock().unwrap();
+    let window = windows.get_mut(label)?;
+    let factor = match action {
+      LOAD => return Some((window.factor, false)),
+      "in" => LEVELS
+        .iter()
+        .copied()
+        .find(|level| *level > window.factor + LEVEL_TOLERANCE)
+        .unwrap_or(window.config.max),
+      "out" => LEVELS
+        .iter()
+        .rev()
+        .copied()
+        .find(|level| *level < window.factor - LEVEL_TOLERANCE)
+        .unwrap_or(window.config.min),
+      "reset" => window.config.default_factor,
+      _ => return None,
+    };
+    window.factor = window.config.clamp(factor);
+    Some((window.factor, true))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{is_zoomed, ZoomConfig, Zooms};
+
+  #[test]
+  fn steps_through_the_levels() {
+    let zooms = Zooms::default();
+    let factor = zooms.register(
+      "main",
+      ZoomConfig {
+        hotkeys_enabled: true,
+        min: 0.5,
+        max: 1.6,
+        ..Default::default()
+      },
+      Some(1.3),
+    );
+    assert_eq!(factor, 1.3);
+    assert_eq!(zooms.report("main", "load"), Some((1.3, false)));
+    assert_eq!(zooms.report("main", "in"), Some((1.5, true)));
+    // clamped to the max
+    assert_eq!(zooms.report("main", "in"), Some((1.6, true)));
+    assert_eq!(zooms.report("main", "in"), Some((1.6, true)));
+    assert_eq!(zooms.report("main", "out"), Some((1.5, true)));
+    assert_eq!(zooms.report("main", "reset"), Some((1.0, true)));
+    assert_eq!(zooms.report("main", "out"), Some((0.9, true)));
+    assert_eq!(zooms.report("main", "unknown"), None);
+    assert_eq!(zooms.report("other", "in"), None);
+
+    assert_eq!(zooms.set("main", 0.1), Some(0.5));
+    assert_eq!(zooms.report("main", "out"), Some((0.5, true)));
+    assert!(is_zoomed(0.5));
+    assert!(!is_zoomed(1.0));
+  }
+}
@@ -1,15 +1,20 @@
+mod app;
 mod cli;
+mod clipboard;
+#[allow(unused_imports)]
 mod dialog;
 mod event;
 #[allow(unused_imports)]
 mod file_system;
 #[cfg(global_shortcut)]
-mod global_shortcut;
+pub(crate) mod global_shortcut;
 #[cfg(http_request)]
 mod http;
 mod internal;
 #[cfg(notification)]
 mod notification;
+mod power;
+mod process;
 mod shell;
 mod window;
 
@@ -24,6 +29,7 @@ use serde_json::Value as JsonValue;
 #[derive(Deserialize)]
 #[serde(tag = "module", content = "message")]
 enum Module {
+  App(app::Cmd),
   Fs(file_system::Cmd),
   Window(window::Cmd),
   Shell(shell::Cmd),
@@ -34,6 +40,9 @@ enum Module {
   Notification(notification::Cmd),
   Http(http::Cmd),
   GlobalShortcut(global_shortcut::Cmd),
+  Clipboard(clipboard::Cmd),
+  Process(process::Cmd),
+  Power(power::Cmd),
 }
 
 impl Module {
@@ -43,16 +52,20 @@ impl Module {
     context: &Context,
   ) -> crate::Result<InvokeResponse> {
     match self {
-      Self::Fs(cmd) => cmd.run().await,
-      Self::Window(cmd) => cmd.run(webview_manager).await,
+      Self::App(cmd) => cmd.run(webview_manager, context).await,
+      Self::Fs(cmd) => cmd.run(context).await,
+      Self::Window(cmd) => cmd.run(webview_manager, context).await,
       Self::Shell(cmd) => cmd.run().await,
       Self::Event(cmd) => cmd.run(webview_manager).await,
       Self::Internal(cmd) => cmd.run().await,
-      Self::Dialog(cmd) => cmd.run().await,
+      Self::Dialog(cmd) => cmd.run(context).await,
       Self::Cli(cmd) => cmd.run(context).await,
       Self::Notification(cmd) => cmd.run(context).await,
-      Self::Http(cmd) => cmd.run().await,
+      Self::Http(cmd) => cmd.run(context).await,
       Self::GlobalShortcut(cmd) => cmd.run(webview_manager).await,
+      Self::Clipboard(cmd) => cmd.run().await,
+      Self::Process(cmd) => cmd.run().await,
+      Self::Power(cmd) => cmd.run().await,
     }
   }
 }
@@ -0,0 +1,31 @@
+use crate::app::{ApplicationExt, InvokeResponse};
+use serde::Deserialize;
+
+/// The API descriptor.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Gets the `window.__TAURI_METADATA__` object, for the scripts that can't read the window.
+  GetMetadata,
+  /// Gets the `features` config.
+  GetFeatures,
+  /// Shows the app windows and closes the startup splashscreen.
+  CloseSplashscreen,
+}
+
+impl Cmd {
+  pub async fn run<A: ApplicationExt + 'static>(
+    self,
+    webview_manager: &crate::WebviewManager<A>,
+    context: &crate::app::Context,
+  ) -> crate::Result<InvokeResponse> {
+    match self {
+      Self::GetMetadata => Ok(crate::app::metadata::metadata(&context.config).into()),
+      Self::GetFeatures => Ok(context.config.features.clone().into()),
+      Self::CloseSplashscreen => {
+        webview_manager.close_splashscreen();
+        Ok(().into())
+      }
+    }
+  }
+}
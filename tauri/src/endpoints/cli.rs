@@ -18,7 +18,19 @@ impl Cmd {
         #[cfg(cli)]
         return tauri_api::cli::get_matches(&context.config)
           .map_err(Into::into)
-          .map(Into::into);
+          .map(|matches| {
+            let legacy = context
+              .config
+              .tauri
+              .cli
+              .as_ref()
+              .map_or(false, |cli| cli.legacy_matches());
+            if legacy {
+              matches.to_legacy().into()
+            } else {
+              matches.into()
+            }
+          });
         #[cfg(not(cli))]
           Err(crate::Error::ApiNotEnabled(
             "CLI definition not set under tauri.conf.json > tauri > cli (https://tauri.studio/docs/api/config#tauri.cli)".to_string(),
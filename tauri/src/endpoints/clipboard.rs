@@ -0,0 +1,42 @@
+use crate::app::InvokeResponse;
+use serde::Deserialize;
+
+/// The API descriptor.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// The read clipboard text API.
+  ReadText,
+  /// The write clipboard text API.
+  WriteText { data: String },
+}
+
+impl Cmd {
+  pub async fn run(self) -> crate::Result<InvokeResponse> {
+    match self {
+      Self::ReadText => {
+        #[cfg(clipboard_read_text)]
+        return crate::api::clipboard::read_text()
+          .map(Into::into)
+          .map_err(Into::into);
+        #[cfg(not(clipboard_read_text))]
+        Err(crate::Error::ApiNotAllowlisted(
+          "clipboard > readText".to_string(),
+        ))
+      }
+      Self::WriteText { data } => {
+        #[cfg(clipboard_write_text)]
+        return crate::api::clipboard::write_text(data)
+          .map(Into::into)
+          .map_err(Into::into);
+        #[cfg(not(clipboard_write_text))]
+        {
+          let _ = data;
+          Err(crate::Error::ApiNotAllowlisted(
+            "clipboard > writeText".to_string(),
+          ))
+        }
+      }
+    }
+  }
+}
@@ -1,10 +1,14 @@
 use crate::{
-  api::dialog::{ask as ask_dialog, message as message_dialog, AskResponse, FileDialogBuilder},
-  app::InvokeResponse,
+  api::{
+    config::DialogAllowlistConfig,
+    dialog::{ask as ask_dialog, message as message_dialog, AskResponse, FileDialogBuilder},
+    path::expand_variables,
+  },
+  app::{Context, InvokeResponse},
 };
 use serde::Deserialize;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -63,21 +67,22 @@ pub enum Cmd {
 }
 
 impl Cmd {
-  pub async fn run(self) -> crate::Result<InvokeResponse> {
+  pub async fn run(self, context: &Context) -> crate::Result<InvokeResponse> {
     match self {
       Self::OpenDialog { options } => {
         #[cfg(open_dialog)]
-        return open(options);
+        return open(context, options);
         #[cfg(not(open_dialog))]
-        Err(crate::Error::ApiNotAllowlisted("title".to_string()));
+        Err(crate::Error::ApiNotAllowlisted("openDialog".to_string()))
       }
       Self::SaveDialog { options } => {
         #[cfg(save_dialog)]
-        return save(options);
+        return save(context, options);
         #[cfg(not(save_dialog))]
-        Err(crate::Error::ApiNotAllowlisted("saveDialog".to_string()));
+        Err(crate::Error::ApiNotAllowlisted("saveDialog".to_string()))
       }
       Self::MessageDialog { message } => {
+        check_message(&context.config.tauri.allowlist.dialog)?;
         let exe = std::env::current_exe()?;
         let app_name = exe
           .file_stem()
@@ -88,6 +93,7 @@ impl Cmd {
         Ok(().into())
       }
       Self::AskDialog { title, message } => {
+        check_message(&context.config.tauri.allowlist.dialog)?;
         let exe = std::env::current_exe()?;
         let answer = ask(
           title.unwrap_or_else(|| {
@@ -105,39 +111,97 @@ impl Cmd {
   }
 }
 
+/// Checks that the `tauri > allowlist > dialog > message` flag doesn't disable the message and ask dialogs.
+fn check_message(config: &DialogAllowlistConfig) -> crate::Result<()> {
+  if config.allows_message() {
+    Ok(())
+  } else {
+    Err(crate::Error::ApiNotAllowlisted(
+      "dialog > message".to_string(),
+    ))
+  }
+}
+
+/// Resolves the `defaultPath`, expanding its variable if the `tauri > allowlist > dialog > defaultPathVariables` allows it.
+#[cfg(any(open_dialog, save_dialog))]
+fn default_path(config: &DialogAllowlistConfig, path: &Path) -> crate::Result<PathBuf> {
+  let variable = path.to_str().map_or(false, |path| path.starts_with('$'));
+  if !variable {
+    Ok(path.to_path_buf())
+  } else if config.default_path_variables {
+    expand_variables(path).map_err(Into::into)
+  } else {
+    Err(crate::Error::ApiNotAllowlisted(
+      "dialog > defaultPathVariables".to_string(),
+    ))
+  }
+}
+
 /// Shows an open dialog.
 #[cfg(open_dialog)]
-pub fn open(options: OpenDialogOptions) -> crate::Result<InvokeResponse> {
+pub fn open(context: &Context, options: OpenDialogOptions) -> crate::Result<InvokeResponse> {
+  let config = &context.config.tauri.allowlist.dialog;
+  if options.directory && !config.allows_directory() {
+    return Err(crate::Error::ApiNotAllowlisted(
+      "dialog > directory".to_string(),
+    ));
+  }
+  if options.multiple && !config.allows_multiple() {
+    return Err(crate::Error::ApiNotAllowlisted(
+      "dialog > multiple".to_string(),
+    ));
+  }
   let mut dialog_builder = FileDialogBuilder::new();
-  if let Some(default_path) = options.default_path {
-    dialog_builder = dialog_builder.set_directory(default_path);
+  if let Some(path) = options.default_path {
+    dialog_builder = dialog_builder.set_directory(default_path(config, &path)?);
   }
   for filter in options.filters {
     let extensions: Vec<&str> = filter.extensions.iter().map(|s| &**s).collect();
     dialog_builder = dialog_builder.add_filter(filter.name, &extensions);
   }
+  let scope = &context.fs_scope;
+  let extend_scope = config.auto_extend_fs_scope;
   let response = if options.directory {
-    dialog_builder.pick_folder().into()
+    let path = dialog_builder.pick_folder();
+    if let (Some(path), true) = (&path, extend_scope) {
+      scope.allow_directory(path.clone());
+    }
+    path.into()
   } else if options.multiple {
-    dialog_builder.pick_files().into()
+    let paths = dialog_builder.pick_files();
+    if let (Some(paths), true) = (&paths, extend_scope) {
+      for path in paths {
+        scope.allow_file(path.clone());
+      }
+    }
+    paths.into()
   } else {
-    dialog_builder.pick_file().into()
+    let path = dialog_builder.pick_file();
+    if let (Some(path), true) = (&path, extend_scope) {
+      scope.allow_file(path.clone());
+    }
+    path.into()
   };
   Ok(response)
 }
 
 /// Shows a save dialog.
 #[cfg(save_dialog)]
-pub fn save(options: SaveDialogOptions) -> crate::Result<InvokeResponse> {
+pub fn save(context: &Context, options: SaveDialogOptions) -> crate::Result<InvokeResponse> {
+  let config = &context.config.tauri.allowlist.dialog;
   let mut dialog_builder = FileDialogBuilder::new();
-  if let Some(default_path) = options.default_path {
-    dialog_builder = dialog_builder.set_directory(default_path);
+  if let Some(path) = options.default_path {
+    dialog_builder = dialog_builder.set_directory(default_path(config, &path)?);
   }
   for filter in options.filters {
     let extensions: Vec<&str> = filter.extensions.iter().map(|s| &**s).collect();
     dialog_builder = dialog_builder.add_filter(filter.name, &extensions);
   }
-  Ok(dialog_builder.save_file().into())
+  let path = dialog_builder.save_file();
+  if let (Some(path), true) = (&path, config.auto_extend_fs_scope) {
+    context.fs_scope.allow_file(path.clone());
+  }
+  Ok(path.into())
 }
 
 /// Shows a dialog with a yes/no question.
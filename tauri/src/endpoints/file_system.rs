@@ -1,4 +1,8 @@
-use crate::{api::path::BaseDirectory, app::InvokeResponse, ApplicationDispatcherExt};
+use crate::{
+  api::path::BaseDirectory,
+  app::{fs_scope::FsScope, Context, InvokeResponse},
+  ApplicationDispatcherExt,
+};
 
 use serde::{Deserialize, Serialize};
 use tauri_api::{dir, file, path::resolve_path};
@@ -91,17 +95,24 @@ pub enum Cmd {
 }
 
 impl Cmd {
-  pub async fn run(self) -> crate::Result<InvokeResponse> {
+  pub async fn run(
+    self,
+    #[allow(unused_variables)] context: &Context,
+  ) -> crate::Result<InvokeResponse> {
     match self {
       Self::ReadTextFile { path, options } => {
         #[cfg(read_text_file)]
-        return read_text_file(path, options).await.map(Into::into);
+        return read_text_file(&context.fs_scope, path, options)
+          .await
+          .map(Into::into);
         #[cfg(not(read_text_file))]
         Err(crate::Error::ApiNotAllowlisted("readTextFile".to_string()))
       }
       Self::ReadBinaryFile { path, options } => {
         #[cfg(read_binary_file)]
-        return read_binary_file(path, options).await.map(Into::into);
+        return read_binary_file(&context.fs_scope, path, options)
+          .await
+          .map(Into::into);
         #[cfg(not(read_binary_file))]
         Err(crate::Error::ApiNotAllowlisted(
           "readBinaryFile".to_string(),
@@ -113,7 +124,9 @@ impl Cmd {
         options,
       } => {
         #[cfg(write_file)]
-        return write_file(path, contents, options).await.map(Into::into);
+        return write_file(&context.fs_scope, path, contents, options)
+          .await
+          .map(Into::into);
         #[cfg(not(write_file))]
         Err(crate::Error::ApiNotAllowlisted("writeFile".to_string()))
       }
@@ -123,7 +136,7 @@ impl Cmd {
         options,
       } => {
         #[cfg(write_binary_file)]
-        return write_binary_file(path, contents, options)
+        return write_binary_file(&context.fs_scope, path, contents, options)
           .await
           .map(Into::into);
         #[cfg(not(write_binary_file))]
@@ -133,7 +146,9 @@ impl Cmd {
       }
       Self::ReadDir { path, options } => {
         #[cfg(read_dir)]
-        return read_dir(path, options).await.map(Into::into);
+        return read_dir(&context.fs_scope, path, options)
+          .await
+          .map(Into::into);
         #[cfg(not(read_dir))]
         Err(crate::Error::ApiNotAllowlisted("readDir".to_string()))
       }
@@ -143,7 +158,7 @@ impl Cmd {
         options,
       } => {
         #[cfg(copy_file)]
-        return copy_file(source, destination, options)
+        return copy_file(&context.fs_scope, source, destination, options)
           .await
           .map(Into::into);
         #[cfg(not(copy_file))]
@@ -151,19 +166,25 @@ impl Cmd {
       }
       Self::CreateDir { path, options } => {
         #[cfg(create_dir)]
-        return create_dir(path, options).await.map(Into::into);
+        return create_dir(&context.fs_scope, path, options)
+          .await
+          .map(Into::into);
         #[cfg(not(create_dir))]
         Err(crate::Error::ApiNotAllowlisted("createDir".to_string()))
       }
       Self::RemoveDir { path, options } => {
         #[cfg(remove_dir)]
-        return remove_dir(path, options).await.map(Into::into);
+        return remove_dir(&context.fs_scope, path, options)
+          .await
+          .map(Into::into);
         #[cfg(not(remove_dir))]
         Err(crate::Error::ApiNotAllowlisted("removeDir".to_string()))
       }
       Self::RemoveFile { path, options } => {
         #[cfg(remove_file)]
-        return remove_file(path, options).await.map(Into::into);
+        return remove_file(&context.fs_scope, path, options)
+          .await
+          .map(Into::into);
         #[cfg(not(remove_file))]
         Err(crate::Error::ApiNotAllowlisted("removeFile".to_string()))
       }
@@ -173,7 +194,7 @@ impl Cmd {
         options,
       } => {
         #[cfg(rename_file)]
-        return rename_file(old_path, new_path, options)
+        return rename_file(&context.fs_scope, old_path, new_path, options)
           .await
           .map(Into::into);
         #[cfg(not(rename_file))]
@@ -192,6 +213,7 @@ impl Cmd {
 /// Reads a directory.
 #[cfg(read_dir)]
 pub async fn read_dir(
+  scope: &FsScope,
   path: PathBuf,
   options: Option<DirOperationOptions>,
 ) -> crate::Result<Vec<dir::DiskEntry>> {
@@ -200,12 +222,15 @@ pub async fn read_dir(
   } else {
     (false, None)
   };
-  dir::read_dir(resolve_path(path, dir)?, recursive).map_err(crate::Error::FailedToExecuteApi)
+  let resolved_path = resolve_path(path, dir)?;
+  scope.check(&resolved_path)?;
+  dir::read_dir(resolved_path, recursive).map_err(crate::Error::FailedToExecuteApi)
 }
 
 /// Copies a file.
 #[cfg(copy_file)]
 pub async fn copy_file(
+  scope: &FsScope,
   source: PathBuf,
   destination: PathBuf,
   options: Option<FileOperationOptions>,
@@ -217,19 +242,26 @@ pub async fn copy_file(
     ),
     None => (source, destination),
   };
+  scope.check(&src)?;
+  scope.check(&dest)?;
   fs::copy(src, dest)?;
   Ok(())
 }
 
 /// Creates a directory.
 #[cfg(create_dir)]
-pub async fn create_dir(path: PathBuf, options: Option<DirOperationOptions>) -> crate::Result<()> {
+pub async fn create_dir(
+  scope: &FsScope,
+  path: PathBuf,
+  options: Option<DirOperationOptions>,
+) -> crate::Result<()> {
   let (recursive, dir) = if let Some(options_value) = options {
     (options_value.recursive, options_value.dir)
   } else {
     (false, None)
   };
   let resolved_path = resolve_path(path, dir)?;
+  scope.check(&resolved_path)?;
   if recursive {
     fs::create_dir_all(resolved_path)?;
   } else {
@@ -241,13 +273,18 @@ pub async fn create_dir(path: PathBuf, options: Option<DirOperationOptions>) ->
 
 /// Removes a directory.
 #[cfg(remove_dir)]
-pub async fn remove_dir(path: PathBuf, options: Option<DirOperationOptions>) -> crate::Result<()> {
+pub async fn remove_dir(
+  scope: &FsScope,
+  path: PathBuf,
+  options: Option<DirOperationOptions>,
+) -> crate::Result<()> {
   let (recursive, dir) = if let Some(options_value) = options {
     (options_value.recursive, options_value.dir)
   } else {
     (false, None)
   };
   let resolved_path = resolve_path(path, dir)?;
+  scope.check(&resolved_path)?;
   if recursive {
     fs::remove_dir_all(resolved_path)?;
   } else {
@@ -260,10 +297,12 @@ pub async fn remove_dir(path: PathBuf, options: Option<DirOperationOptions>) ->
 /// Removes a file
 #[cfg(remove_file)]
 pub async fn remove_file(
+  scope: &FsScope,
   path: PathBuf,
   options: Option<FileOperationOptions>,
 ) -> crate::Result<()> {
   let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+  scope.check(&resolved_path)?;
   fs::remove_file(resolved_path)?;
   Ok(())
 }
@@ -271,6 +310,7 @@ pub async fn remove_file(
 /// Renames a file.
 #[cfg(rename_file)]
 pub async fn rename_file(
+  scope: &FsScope,
   old_path: PathBuf,
   new_path: PathBuf,
   options: Option<FileOperationOptions>,
@@ -282,17 +322,22 @@ pub async fn rename_file(
     ),
     None => (old_path, new_path),
   };
+  scope.check(&old)?;
+  scope.check(&new)?;
   fs::rename(old, new).map_err(crate::Error::Io)
 }
 
 /// Writes a text file.
 #[cfg(write_file)]
 pub async fn write_file(
+  scope: &FsScope,
   path: PathBuf,
   contents: String,
   options: Option<FileOperationOptions>,
 ) -> crate::Result<()> {
-  File::create(resolve_path(path, options.and_then(|o| o.dir))?)
+  let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+  scope.check(&resolved_path)?;
+  File::create(resolved_path)
     .map_err(crate::Error::Io)
     .and_then(|mut f| f.write_all(contents.as_bytes()).map_err(|err| err.into()))?;
   Ok(())
@@ -301,14 +346,17 @@ pub async fn write_file(
 /// Writes a binary file.
 #[cfg(write_binary_file)]
 pub async fn write_binary_file(
+  scope: &FsScope,
   path: PathBuf,
   contents: String,
   options: Option<FileOperationOptions>,
 ) -> crate::Result<()> {
+  let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+  scope.check(&resolved_path)?;
   base64::decode(contents)
     .map_err(crate::Error::Base64Decode)
     .and_then(|c| {
-      File::create(resolve_path(path, options.and_then(|o| o.dir))?)
+      File::create(resolved_path)
         .map_err(Into::into)
         .and_then(|mut f| f.write_all(&c).map_err(|err| err.into()))
     })?;
@@ -318,21 +366,25 @@ pub async fn write_binary_file(
 /// Reads a text file.
 #[cfg(read_text_file)]
 pub async fn read_text_file(
+  scope: &FsScope,
   path: PathBuf,
   options: Option<FileOperationOptions>,
 ) -> crate::Result<String> {
-  file::read_string(resolve_path(path, options.and_then(|o| o.dir))?)
-    .map_err(crate::Error::FailedToExecuteApi)
+  let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+  scope.check(&resolved_path)?;
+  file::read_string(resolved_path).map_err(crate::Error::FailedToExecuteApi)
 }
 
 /// Reads a binary file.
 #[cfg(read_binary_file)]
 pub async fn read_binary_file(
+  scope: &FsScope,
   path: PathBuf,
   options: Option<FileOperationOptions>,
 ) -> crate::Result<Vec<u8>> {
-  file::read_binary(resolve_path(path, options.and_then(|o| o.dir))?)
-    .map_err(crate::Error::FailedToExecuteApi)
+  let resolved_path = resolve_path(path, options.and_then(|o| o.dir))?;
+  scope.check(&resolved_path)?;
+  file::read_binary(resolved_path).map_err(crate::Error::FailedToExecuteApi)
 }
 
 pub async fn resolve_path_handler(
@@ -1,8 +1,11 @@
-use crate::{app::InvokeResponse, async_runtime::Mutex};
+use crate::{
+  app::{Context, InvokeResponse},
+  async_runtime::Mutex,
+};
 
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use tauri_api::http::{Client, ClientBuilder, HttpRequestBuilder, ResponseData};
+use tauri_api::http::{Client, ClientBuilder, HttpRequestBuilder, RequestLimits, ResponseData};
 
 use std::{collections::HashMap, sync::Arc};
 
@@ -30,10 +33,12 @@ pub enum Cmd {
 }
 
 impl Cmd {
-  pub async fn run(self) -> crate::Result<InvokeResponse> {
+  pub async fn run(self, context: &Context) -> crate::Result<InvokeResponse> {
     match self {
       Self::CreateClient { options } => {
-        let client = options.unwrap_or_default().build()?;
+        // the client options can only lower the `allowlist > http > defaults` limits
+        let limits = RequestLimits::from_config(&context.config.tauri.allowlist.http);
+        let client = options.unwrap_or_default().build_with_limits(limits)?;
         let mut store = clients().lock().await;
         let id = rand::random::<ClientId>();
         store.insert(id, client);
@@ -1,6 +1,9 @@
 use crate::app::InvokeResponse;
 use serde::Deserialize;
-use tauri_api::{config::Config, notification::Notification};
+use tauri_api::{
+  config::{Config, NotificationPermission},
+  notification::Notification,
+};
 
 /// The options for the notification API.
 #[derive(Deserialize)]
@@ -23,6 +26,8 @@ pub enum Cmd {
   RequestNotificationPermission,
   /// The notification permission check API.
   IsNotificationPermissionGranted,
+  /// The notification permission state API.
+  NotificationPermissionState,
 }
 
 impl Cmd {
@@ -36,13 +41,19 @@ impl Cmd {
       }
       Self::IsNotificationPermissionGranted => {
         #[cfg(notification)]
-        return is_permission_granted().await.map(Into::into);
+        return is_permission_granted(&context.config).await.map(Into::into);
         #[cfg(not(notification))]
         Err(crate::Error::ApiNotAllowlisted("notification".to_string()))
       }
       Self::RequestNotificationPermission => {
         #[cfg(notification)]
-        return request_permission().map(Into::into);
+        return request_permission(&context.config).map(Into::into);
+        #[cfg(not(notification))]
+        Err(crate::Error::ApiNotAllowlisted("notification".to_string()))
+      }
+      Self::NotificationPermissionState => {
+        #[cfg(notification)]
+        return permission_state(&context.config).map(Into::into);
         #[cfg(not(notification))]
         Err(crate::Error::ApiNotAllowlisted("notification".to_string()))
       }
@@ -51,7 +62,7 @@ impl Cmd {
 }
 
 pub async fn send(options: NotificationOptions, config: &Config) -> crate::Result<InvokeResponse> {
-  let identifier = config.tauri.bundle.identifier.clone();
+  let identifier = config.tauri.notification_identifier();
   let mut notification = Notification::new(identifier).title(options.title);
   if let Some(body) = options.body {
     notification = notification.body(body);
@@ -63,20 +74,43 @@ pub async fn send(options: NotificationOptions, config: &Config) -> crate::Resul
   Ok(().into())
 }
 
-pub async fn is_permission_granted() -> crate::Result<InvokeResponse> {
+// the permission the user answered, or the `notification > defaultPermission` config
+fn current_permission(config: &Config) -> crate::Result<Option<bool>> {
   let settings = crate::settings::read_settings()?;
-  if let Some(allow_notification) = settings.allow_notification {
+  Ok(
+    settings
+      .allow_notification
+      .or(match config.tauri.notification.default_permission {
+        NotificationPermission::Granted => Some(true),
+        NotificationPermission::Denied => Some(false),
+        NotificationPermission::Prompt => None,
+      }),
+  )
+}
+
+pub async fn is_permission_granted(config: &Config) -> crate::Result<InvokeResponse> {
+  if let Some(allow_notification) = current_permission(config)? {
     Ok(allow_notification.into())
   } else {
     Ok(().into())
   }
 }
 
-pub fn request_permission() -> crate::Result<String> {
+/// The permission state, matching the web `Notification.permission` values.
+pub fn permission_state(config: &Config) -> crate::Result<String> {
+  let state = match current_permission(config)? {
+    Some(true) => "granted",
+    Some(false) => "denied",
+    None => "default",
+  };
+  Ok(state.to_string())
+}
+
+pub fn request_permission(config: &Config) -> crate::Result<String> {
   let mut settings = crate::settings::read_settings()?;
   let granted = "granted".to_string();
   let denied = "denied".to_string();
-  if let Some(allow_notification) = settings.allow_notification {
+  if let Some(allow_notification) = current_permission(config)? {
     return Ok(if allow_notification { granted } else { denied });
   }
   let answer = tauri_api::dialog::ask(
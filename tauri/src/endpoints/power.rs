@@ -0,0 +1,29 @@
+use crate::app::InvokeResponse;
+use serde::Deserialize;
+
+/// The API descriptor.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Takes or releases the wake lock keeping the system and the display awake.
+  #[serde(rename_all = "camelCase")]
+  SetPreventSleep { prevent_sleep: bool },
+}
+
+impl Cmd {
+  pub async fn run(self) -> crate::Result<InvokeResponse> {
+    match self {
+      Self::SetPreventSleep { prevent_sleep } => {
+        #[cfg(power_set_prevent_sleep)]
+        return crate::app::power::set_prevent_sleep(prevent_sleep).map(Into::into);
+        #[cfg(not(power_set_prevent_sleep))]
+        {
+          let _ = prevent_sleep;
+          Err(crate::Error::ApiNotAllowlisted(
+            "power > setPreventSleep".to_string(),
+          ))
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,48 @@
+use crate::app::InvokeResponse;
+use serde::Deserialize;
+
+/// The API descriptor.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Relaunch the app.
+  Relaunch,
+  /// Exit the app with the given code.
+  #[serde(rename_all = "camelCase")]
+  Exit { exit_code: i32 },
+}
+
+impl Cmd {
+  pub async fn run(self) -> crate::Result<InvokeResponse> {
+    match self {
+      Self::Relaunch => {
+        #[cfg(process_relaunch)]
+        return crate::api::process::restart(
+          0,
+          cfg!(process_relaunch_dangerous_allow_symlink_macos),
+        )
+        .map(Into::into)
+        .map_err(Into::into);
+        #[cfg(not(process_relaunch))]
+        Err(crate::Error::ApiNotAllowlisted(
+          "process > relaunch".to_string(),
+        ))
+      }
+      Self::Exit { exit_code } => {
+        #[cfg(process_exit)]
+        {
+          #[cfg(power)]
+          crate::app::power::release();
+          std::process::exit(exit_code);
+        }
+        #[cfg(not(process_exit))]
+        {
+          let _ = exit_code;
+          Err(crate::Error::ApiNotAllowlisted(
+            "process > exit".to_string(),
+          ))
+        }
+      }
+    }
+  }
+}
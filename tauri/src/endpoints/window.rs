@@ -82,6 +82,10 @@ pub enum Cmd {
   SetIcon {
     icon: IconDto,
   },
+  SetZoom {
+    factor: f64,
+  },
+  GetZoom,
 }
 
 #[cfg(create_window)]
@@ -94,6 +98,7 @@ impl Cmd {
   pub async fn run<A: ApplicationExt + 'static>(
     self,
     webview_manager: &crate::WebviewManager<A>,
+    #[allow(unused_variables)] context: &crate::app::Context,
   ) -> crate::Result<InvokeResponse> {
     if cfg!(not(window)) {
       Err(crate::Error::ApiNotAllowlisted("setTitle".to_string()))
@@ -105,10 +110,19 @@ impl Cmd {
           return Err(crate::Error::ApiNotAllowlisted("createWindow".to_string()));
           #[cfg(create_window)]
           {
+            context
+              .config
+              .tauri
+              .check_window_scope(&options.label, &options.url)
+              .map_err(|reason| crate::Error::WindowNotInScope(options.label.clone(), reason))?;
+            let mut options = options;
+            webview_manager.restore_window_state(&mut options);
             let label = options.label.to_string();
+            let api = crate::app::WebviewApi::from(&options);
+            let options = crate::app::webview::WindowConfig(options);
             webview_manager
-              .create_webview(label.to_string(), options.url.clone(), |_| {
-                Ok(crate::app::webview::WindowConfig(options).into())
+              .create_webview_with_api(label.to_string(), options.0.url.clone(), api, |_| {
+                Ok(options.into())
               })
               .await?;
             webview_manager
@@ -149,6 +163,8 @@ impl Cmd {
         Self::SetPosition { x, y } => current_webview.set_position(x, y)?,
         Self::SetFullscreen { fullscreen } => current_webview.set_fullscreen(fullscreen)?,
         Self::SetIcon { icon } => current_webview.set_icon(icon.into())?,
+        Self::SetZoom { factor } => return Ok(webview_manager.set_zoom(factor).await?.into()),
+        Self::GetZoom => return Ok(webview_manager.zoom().into()),
       }
       Ok(().into())
     }
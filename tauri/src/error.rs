@@ -34,6 +34,9 @@ pub enum Error {
   /// Failed to decode base64.
   #[error("Failed to decode base64 string: {0}")]
   Base64Decode(#[from] base64::DecodeError),
+  /// The window option isn't supported on this platform or by the webview runtime.
+  #[error("the `{0}` window option isn't supported on this platform or by the webview runtime")]
+  UnsupportedWindowOption(String),
   /// Failed to load window icon.
   #[error("invalid icon: {0}")]
   InvalidIcon(String),
@@ -46,6 +49,88 @@ pub enum Error {
   /// API not whitelisted on tauri.conf.json
   #[error("'{0}' not on the allowlist (https://tauri.studio/docs/api/config#tauri.allowlist)")]
   ApiNotAllowlisted(String),
+  /// The remote page origin isn't allowed to send the IPC message.
+  #[error("'{0}' can't send this IPC message (https://tauri.studio/docs/api/config#tauri.security.dangerousRemoteDomainIpcAccess)")]
+  RemoteIpcNotAllowed(String),
+  /// The IPC message exceeded a `tauri > security > ipc` limit: the limit name and the message command.
+  #[error("the IPC message exceeded the `{0}` limit (https://tauri.studio/docs/api/config#tauri.security.ipc)")]
+  IpcLimitExceeded(String, Option<String>),
+  /// Invalid plugin config: the plugin name, the config path and the error message.
+  #[error("invalid config of the `{0}` plugin on `{1}`: {2}")]
+  PluginConfig(String, String, String),
+  /// The window isn't on the `tauri > allowlist > windowScope`: its label and the reason.
+  #[error("the `{0}` window can't be created: {1} (https://tauri.studio/docs/api/config#tauri.allowlist.windowScope)")]
+  WindowNotInScope(String, String),
+  /// The path isn't on the `tauri > allowlist > fsScope` nor was picked on a dialog.
+  #[error("the `{}` path isn't on the fs scope (https://tauri.studio/docs/api/config#tauri.allowlist.fsScope)", .0.display())]
+  PathNotInScope(std::path::PathBuf),
+  /// The bundled resources don't match the manifest embedded at build time: the missing and modified resources.
+  #[error("the bundled resources were modified or are missing: {}", .0.join(", "))]
+  ResourceMismatch(Vec<String>),
+  /// The app wasn't built by the Tauri CLI, so `tauri > security > verifyResources` has no manifest to check against.
+  #[error("the app wasn't built by the Tauri CLI, so its resources can't be verified (https://tauri.studio/docs/api/config#tauri.security.verifyResources)")]
+  ResourceManifestNotFound,
+  /// The config section requires a feature of the `tauri` crate it was built without: the section and the feature.
+  #[error("the `{0}` config requires the `{1}` feature of the `tauri` crate")]
+  FeatureNotEnabled(String, String),
+  /// The single instance lock can't be acquired, nor the launch forwarded to the running instance.
+  #[error("single instance: {0}")]
+  SingleInstance(String),
+  /// The `setZoom` factor isn't a positive number.
+  #[error("invalid zoom factor `{0}`: it must be a positive number")]
+  InvalidZoomFactor(f64),
+}
+
+impl Error {
+  /// The value the API promise rejects with: a `notAllowed` error object for the APIs that aren't on the allowlist,
+  /// a `windowNotInScope` one for the windows that aren't on the window scope,
+  /// a `pathNotInScope` one for the paths that aren't on the fs scope,
+  /// `urlNotInScope`, `methodNotAllowed` and `httpLimitExceeded` ones for the http API requests the config rejects,
+  /// so the frontend can tell them apart, and the error message otherwise.
+  pub(crate) fn to_js_value(&self) -> serde_json::Value {
+    match self {
+      Self::ApiNotAllowlisted(api) => serde_json::json!({
+        "code": "notAllowed",
+        "api": api,
+        "message": self.to_string(),
+      }),
+      Self::WindowNotInScope(label, reason) => serde_json::json!({
+        "code": "windowNotInScope",
+        "label": label,
+        "reason": reason,
+        "message": self.to_string(),
+      }),
+      Self::PathNotInScope(path) => serde_json::json!({
+        "code": "pathNotInScope",
+        "path": path,
+        "message": self.to_string(),
+      }),
+      Self::IpcLimitExceeded(limit, command) => serde_json::json!({
+        "code": "ipcLimitExceeded",
+        "limit": limit,
+        "command": command,
+        "message": self.to_string(),
+      }),
+      Self::FailedToExecuteApi(tauri_api::Error::HttpUrlNotInScope(url)) => serde_json::json!({
+        "code": "urlNotInScope",
+        "url": url,
+        "message": self.to_string(),
+      }),
+      Self::FailedToExecuteApi(tauri_api::Error::HttpMethodNotAllowed(method)) => {
+        serde_json::json!({
+          "code": "methodNotAllowed",
+          "method": method,
+          "message": self.to_string(),
+        })
+      }
+      Self::FailedToExecuteApi(tauri_api::Error::HttpLimitExceeded(limit)) => serde_json::json!({
+        "code": "httpLimitExceeded",
+        "limit": limit,
+        "message": self.to_string(),
+      }),
+      _ => serde_json::Value::String(self.to_string()),
+    }
+  }
 }
 
 impl From<serde_json::Error> for Error {
@@ -11,9 +11,17 @@ pub trait Plugin<A: ApplicationExt + 'static>: Send + Sync {
   /// The plugin name. Used as key on the plugin config object.
   fn name(&self) -> &'static str;
 
-  /// Initialize the plugin.
+  /// Validates the plugin config before [`Plugin::initialize`] is called,
+  /// so a typo on the config fails at startup instead of inside the plugin.
+  /// The config is an empty object if the plugin has no config section.
   #[allow(unused_variables)]
-  async fn initialize(&mut self, config: String) -> crate::Result<()> {
+  fn validate_config(&self, config: &JsonValue) -> Result<(), ConfigError> {
+    Ok(())
+  }
+
+  /// Initialize the plugin with its config value, an empty object if the plugin has no config section.
+  #[allow(unused_variables)]
+  async fn initialize(&mut self, config: JsonValue) -> crate::Result<()> {
     Ok(())
   }
 
@@ -45,6 +53,36 @@ pub trait Plugin<A: ApplicationExt + 'static>: Send + Sync {
   }
 }
 
+/// An invalid plugin config value.
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+  /// The JSON path of the invalid value relative to the plugin config, e.g. `servers[0].port`,
+  /// or empty for the config object itself.
+  pub path: String,
+  /// The error message.
+  pub message: String,
+}
+
+impl ConfigError {
+  /// Creates an error for the value on the given path.
+  pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      path: path.into(),
+      message: message.into(),
+    }
+  }
+}
+
+/// Maps the plugin config error to its path on the app config, e.g. `plugins.sql.servers[0].port`.
+fn config_error(name: &str, error: ConfigError) -> crate::Error {
+  let path = if error.path.is_empty() || error.path.starts_with('[') {
+    format!("plugins.{}{}", name, error.path)
+  } else {
+    format!("plugins.{}.{}", name, error.path)
+  };
+  crate::Error::PluginConfig(name.to_string(), path, error.message)
+}
+
 /// Plugin collection type.
 pub type PluginStore<A> = Arc<Mutex<Vec<Box<dyn Plugin<A> + Sync + Send>>>>;
 
@@ -62,6 +100,13 @@ pub(crate) async fn initialize<A: ApplicationExt + 'static>(
   plugins_config: PluginConfig,
 ) -> crate::Result<()> {
   let mut plugins = store.lock().await;
+  for plugin in plugins.iter() {
+    let name = plugin.name();
+    plugin
+      .validate_config(&plugins_config.get(name))
+      .map_err(|error| config_error(name, error))?;
+  }
+
   let mut futures = Vec::new();
   for plugin in plugins.iter_mut() {
     let plugin_config = plugins_config.get(plugin.name());
@@ -139,3 +184,47 @@ pub(crate) async fn extend_api<A: ApplicationExt + 'static>(
   }
   Ok(None)
 }
+
+#[cfg(test)]
+mod test {
+  use super::{config_error, ConfigError};
+  use crate::api::config::PluginConfig;
+  use serde_json::json;
+
+  #[test]
+  fn config_error_paths() {
+    let path = |error: ConfigError| match config_error("sql", error) {
+      crate::Error::PluginConfig(name, path, message) => {
+        assert_eq!(name, "sql");
+        assert_eq!(message, "invalid port");
+        path
+      }
+      e => panic!("unexpected error {:?}", e),
+    };
+    assert_eq!(path(ConfigError::new("", "invalid port")), "plugins.sql");
+    assert_eq!(
+      path(ConfigError::new("port", "invalid port")),
+      "plugins.sql.port"
+    );
+    assert_eq!(
+      path(ConfigError::new("servers[0].port", "invalid port")),
+      "plugins.sql.servers[0].port"
+    );
+    assert_eq!(
+      path(ConfigError::new("[0]", "invalid port")),
+      "plugins.sql[0]"
+    );
+    assert_eq!(
+      config_error("sql", ConfigError::new("port", "invalid port")).to_string(),
+      "invalid config of the `sql` plugin on `plugins.sql.port`: invalid port"
+    );
+  }
+
+  #[test]
+  fn validated_config() {
+    let config: PluginConfig = serde_json::from_value(json!({ "sql": { "port": 5432 } })).unwrap();
+    assert_eq!(config.get("sql"), json!({ "port": 5432 }));
+    // the plugins without a config section validate an empty object
+    assert_eq!(config.get("fs"), json!({}));
+  }
+}
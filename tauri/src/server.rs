@@ -1,39 +1,682 @@
-use std::io::Read;
-use tauri_api::assets::{AssetFetch, Assets};
-use tiny_http::{Response, StatusCode};
+use glob::Pattern;
+use std::{
+  collections::{hash_map::Entry, HashMap},
+  io::{self, Cursor, Read},
+};
+use tauri_api::{
+  assets::{AssetCompression, AssetFetch, Assets},
+  config::{AssetHeaders, EmbeddedServerCompression, EmbeddedServerConfig},
+};
+use tiny_http::{Header, Method, Response, StatusCode};
+
+mod proxy;
+
+pub use proxy::ProxyRules;
+
+/// A byte range requested with the `Range` header.
+#[derive(Debug, PartialEq)]
+enum Range {
+  /// The whole asset, when there's no valid single `bytes=` range.
+  Full,
+  /// The inclusive start and end offsets.
+  Partial(u64, u64),
+  /// A range outside of the asset.
+  Unsatisfiable,
+}
+
+/// The encoding of a response body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+  Identity,
+  Gzip,
+  Brotli,
+}
+
+impl Encoding {
+  fn name(self) -> &'static str {
+    match self {
+      Self::Identity => "identity",
+      Self::Gzip => "gzip",
+      Self::Brotli => "br",
+    }
+  }
+}
+
+/// The per-server cache of the brotli assets gzipped on request.
+#[derive(Default)]
+pub struct AssetCache {
+  gzip: HashMap<String, Vec<u8>>,
+}
+
+/// The configured response headers, applied to the assets matching their glob pattern.
+pub struct HeaderRules(Vec<(Pattern, Vec<Header>)>);
+
+impl HeaderRules {
+  /// Compiles the `tauri > embeddedServer > headers` config, validated by the CLI.
+  pub fn new(config: &[AssetHeaders]) -> Self {
+    Self(
+      config
+        .iter()
+        .map(|entry| {
+          let pattern = Pattern::new(&entry.pattern)
+            .unwrap_or_else(|_| panic!("Invalid header pattern {}", entry.pattern));
+          let headers = entry
+            .headers
+            .iter()
+            .map(|(name, value)| header(&format!("{}: {}", name, value)))
+            .collect();
+          (pattern, headers)
+        })
+        .collect(),
+    )
+  }
+
+  /// Sets the headers of the rules matching the asset path, in order, replacing the existing ones.
+  fn apply(&self, path: &str, headers: &mut Vec<Header>) {
+    let path = path.trim_start_matches('/');
+    for (pattern, rule_headers) in &self.0 {
+      if pattern.matches(path) {
+        for rule_header in rule_headers {
+          headers.retain(|header| header.field != rule_header.field);
+          headers.push(rule_header.clone());
+        }
+      }
+    }
+  }
+}
+
+/// The pages served on the error responses, relative to the dist directory.
+pub struct ErrorPages {
+  not_found: Option<String>,
+  error: Option<String>,
+}
+
+impl ErrorPages {
+  /// Reads the `tauri > embeddedServer > notFoundPage` and `errorPage` config, checked by the CLI.
+  pub fn new(config: &EmbeddedServerConfig) -> Self {
+    Self {
+      not_found: config.not_found_page.as_ref().map(Assets::format_key),
+      error: config.error_page.as_ref().map(Assets::format_key),
+    }
+  }
+}
 
 /// Returns the HTTP response of the given asset path.
-pub fn asset_response(path: &str, assets: &'static Assets) -> Response<impl Read> {
-  let (asset, _) = assets
-    .get(path, AssetFetch::Compress)
-    .unwrap_or_else(|| panic!("Could not read asset {}", path));
+///
+/// The body is sent with the stored compression if the `Accept-Encoding` header allows it,
+/// or gzipped on request with the `auto` compression, and decompressed otherwise.
+/// The `Range` header value is honored with a partial response of the decompressed asset, without negotiating the encoding.
+///
+/// The ETag is the content hash computed when the asset was embedded, suffixed with the response encoding
+/// so each encoding is cached separately. A matching `If-None-Match` header gets a `304 Not Modified` response.
+/// The `Last-Modified` header is the modification time of the asset file when it was embedded,
+/// and an `If-Modified-Since` header at or after it gets a `304 Not Modified` response without an `If-None-Match` header.
+///
+/// Returns an error if the asset can't be read, to be answered with [`error_page`].
+pub fn asset_response(
+  path: &str,
+  headers: &[Header],
+  assets: &'static Assets,
+  compression: EmbeddedServerCompression,
+  rules: &HeaderRules,
+  cache: &mut AssetCache,
+) -> io::Result<Response<Box<dyn Read>>> {
+  let (stored, content) = assets.get_raw(path).ok_or_else(|| read_error(path))?;
+  let size = assets.size(path).ok_or_else(|| read_error(path))?;
 
-  let mut headers = Vec::new();
+  let range = header_value(headers, "Range").map_or(Range::Full, |range| parse_range(range, size));
+  let encoding = response_encoding(
+    stored,
+    compression,
+    header_value(headers, "Accept-Encoding"),
+    &range,
+  );
 
-  // Content-Encoding
-  const CONTENT_ENCODING: &str = "Content-Encoding: gzip";
-  let content_encoding = CONTENT_ENCODING
-    .parse()
-    .unwrap_or_else(|_| panic!("Could not add {} header", CONTENT_ENCODING));
-  headers.push(content_encoding);
-
-  // Content-Type
-  let mime = if path.ends_with(".svg") {
-    "Content-Type: image/svg+xml"
-  } else if path.ends_with(".css") {
-    "Content-Type: text/css"
-  } else if path.ends_with(".html") {
-    "Content-Type: text/html"
-  } else if path.ends_with(".js") {
-    "Content-Type: text/javascript"
+  let hash = assets.hash(path).ok_or_else(|| read_error(path))?;
+  let etag = format!("\"{}-{}\"", hash, encoding.name());
+  let modified = assets.modified(path).ok_or_else(|| read_error(path))?;
+
+  let mut headers_ = vec![
+    header(mime_type(path)),
+    header("Accept-Ranges: bytes"),
+    header("Vary: Accept-Encoding"),
+    header(&format!("ETag: {}", etag)),
+    header(&format!("Last-Modified: {}", http_date(modified))),
+  ];
+  rules.apply(path, &mut headers_);
+
+  // `If-Modified-Since` is ignored when the request has an `If-None-Match` header
+  let not_modified = match header_value(headers, "If-None-Match") {
+    Some(if_none_match) => matches_etag(if_none_match, &etag),
+    None => header_value(headers, "If-Modified-Since")
+      .and_then(parse_http_date)
+      .map_or(false, |since| modified <= since),
+  };
+  if not_modified {
+    return Ok(Response::new(
+      StatusCode(304),
+      headers_,
+      Box::new(io::empty()),
+      Some(0),
+      None,
+    ));
+  }
+
+  match range {
+    Range::Full => {
+      let (body, length): (Box<dyn Read>, u64) = match (stored, encoding) {
+        (AssetCompression::Brotli, Encoding::Gzip) => {
+          let gzip = match cache.gzip.entry(path.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+              let (mut asset, _) = assets
+                .get(path, AssetFetch::Compress)
+                .ok_or_else(|| read_error(path))?;
+              let mut gzip = Vec::new();
+              asset.read_to_end(&mut gzip)?;
+              entry.insert(gzip)
+            }
+          };
+          (Box::new(Cursor::new(gzip.clone())), gzip.len() as u64)
+        }
+        (_, Encoding::Identity) => {
+          let (asset, _) = assets
+            .get(path, AssetFetch::Decompress)
+            .ok_or_else(|| read_error(path))?;
+          (asset, size)
+        }
+        _ => (Box::new(content), content.len() as u64),
+      };
+      if encoding != Encoding::Identity {
+        headers_.push(header(&format!("Content-Encoding: {}", encoding.name())));
+      }
+      Ok(Response::new(
+        StatusCode(200),
+        headers_,
+        body,
+        Some(length as usize),
+        None,
+      ))
+    }
+    Range::Partial(start, end) => {
+      let (mut asset, _) = assets
+        .get(path, AssetFetch::Decompress)
+        .ok_or_else(|| read_error(path))?;
+      // the asset is streamed, so skipping to the range start doesn't load it in memory
+      io::copy(&mut (&mut asset).take(start), &mut io::sink())?;
+      let length = end - start + 1;
+      headers_.push(header(&format!(
+        "Content-Range: bytes {}-{}/{}",
+        start, end, size
+      )));
+      Ok(Response::new(
+        StatusCode(206),
+        headers_,
+        Box::new(asset.take(length)),
+        Some(length as usize),
+        None,
+      ))
+    }
+    Range::Unsatisfiable => {
+      headers_.push(header(&format!("Content-Range: bytes */{}", size)));
+      Ok(Response::new(
+        StatusCode(416),
+        headers_,
+        Box::new(io::empty()),
+        Some(0),
+        None,
+      ))
+    }
+  }
+}
+
+fn read_error(path: &str) -> io::Error {
+  io::Error::new(
+    io::ErrorKind::NotFound,
+    format!("Could not read asset {}", path),
+  )
+}
+
+/// Resolves the asset path of a request path, without the query string and fragment.
+///
+/// With `spa_fallback`, the GET requests of missing paths without an extension,
+/// e.g. `/settings/profile`, are resolved to `/index.html`.
+/// Returns `None` if there's no asset to serve.
+pub fn resolve_asset(
+  path: &str,
+  method: &Method,
+  spa_fallback: bool,
+  assets: &'static Assets,
+) -> Option<String> {
+  let path = path
+    .split(|c| c == '?' || c == '#')
+    .next()
+    .unwrap_or_default();
+  if assets.get_raw(path).is_some() {
+    return Some(path.to_string());
+  }
+  let has_extension = path
+    .rsplit('/')
+    .next()
+    .map_or(false, |file_name| file_name.contains('.'));
+  if spa_fallback && *method == Method::Get && !has_extension {
+    Some("/index.html".to_string())
   } else {
-    "Content-Type: application/octet-stream"
+    None
+  }
+}
+
+/// The response of a request without an asset, with the `notFoundPage` on GET requests.
+pub fn not_found(
+  method: &Method,
+  pages: &ErrorPages,
+  assets: &'static Assets,
+) -> Response<Box<dyn Read>> {
+  let page = match method {
+    Method::Get => pages.not_found.as_deref(),
+    _ => None,
   };
+  page_response(404, page, assets)
+}
+
+/// The response of an asset that can't be read, with the `errorPage`.
+pub fn error_page(pages: &ErrorPages, assets: &'static Assets) -> Response<Box<dyn Read>> {
+  page_response(500, pages.error.as_deref(), assets)
+}
+
+/// A response with the given status and page.
+/// The body is empty if the page isn't set or can't be read, so a missing page doesn't fall back on itself.
+fn page_response(
+  status: u16,
+  page: Option<&str>,
+  assets: &'static Assets,
+) -> Response<Box<dyn Read>> {
+  let page = page.and_then(|page| {
+    let (asset, _) = assets.get(page, AssetFetch::Decompress)?;
+    let size = assets.size(page)?;
+    Some((page, asset, size))
+  });
+  match page {
+    Some((page, asset, size)) => Response::new(
+      StatusCode(status),
+      vec![header(mime_type(page))],
+      asset,
+      Some(size as usize),
+      None,
+    ),
+    None => Response::new(
+      StatusCode(status),
+      Vec::new(),
+      Box::new(io::empty()),
+      Some(0),
+      None,
+    ),
+  }
+}
 
-  let content_type = mime
+/// The encoding of the response body.
+/// The ranges are served from the decompressed asset, so they're never encoded and get its identity ETag.
+fn response_encoding(
+  stored: AssetCompression,
+  compression: EmbeddedServerCompression,
+  accept_encoding: Option<&str>,
+  range: &Range,
+) -> Encoding {
+  if *range != Range::Full {
+    return Encoding::Identity;
+  }
+  match stored {
+    AssetCompression::Gzip if accepts(accept_encoding, "gzip") => Encoding::Gzip,
+    AssetCompression::Brotli if accepts(accept_encoding, "br") => Encoding::Brotli,
+    AssetCompression::Brotli
+      if compression == EmbeddedServerCompression::Auto && accepts(accept_encoding, "gzip") =>
+    {
+      Encoding::Gzip
+    }
+    _ => Encoding::Identity,
+  }
+}
+
+/// Whether an `Accept-Encoding` header value accepts the encoding.
+/// A missing header only accepts the identity encoding.
+fn accepts(accept_encoding: Option<&str>, encoding: &str) -> bool {
+  let accept_encoding = match accept_encoding {
+    Some(accept_encoding) => accept_encoding,
+    None => return false,
+  };
+  let quality = |name: &str| {
+    accept_encoding.split(',').find_map(|entry| {
+      let mut parts = entry.split(';');
+      if parts.next()?.trim().eq_ignore_ascii_case(name) {
+        let quality = parts
+          .find_map(|param| param.trim().strip_prefix("q="))
+          .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+          .unwrap_or(0.0);
+        Some(quality)
+      } else {
+        None
+      }
+    })
+  };
+  quality(encoding).or_else(|| quality("*")).unwrap_or(0.0) > 0.0
+}
+
+/// Whether an `If-None-Match` header value matches the ETag.
+/// The tags are compared with the weak comparison, ignoring the `W/` prefix.
+fn matches_etag(if_none_match: &str, etag: &str) -> bool {
+  if_none_match.split(',').any(|tag| {
+    let tag = tag.trim();
+    tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag
+  })
+}
+
+/// The names of the months on the HTTP dates.
+const MONTHS: [&str; 12] = [
+  "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+/// The names of the days of the week on the HTTP dates.
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Formats the seconds since the Unix epoch as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn http_date(secs: u64) -> String {
+  let days = (secs / 86400) as i64;
+  let time = secs % 86400;
+  // the civil date of the days since the epoch, with the years starting in March
+  let era_days = days + 719_468;
+  let era = era_days.div_euclid(146_097);
+  let day_of_era = era_days.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 {
+    month_index + 2
+  } else {
+    month_index - 10
+  };
+  let year = year_of_era + era * 400 + if month < 2 { 1 } else { 0 };
+  format!(
+    "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+    // the epoch was a Thursday
+    WEEKDAYS[((days + 4) % 7) as usize],
+    day,
+    MONTHS[month as usize],
+    year,
+    time / 3600,
+    time % 3600 / 60,
+    time % 60
+  )
+}
+
+/// Parses an HTTP date in the `Sun, 06 Nov 1994 08:49:37 GMT` format to the seconds since the Unix epoch.
+/// The obsolete formats aren't sent by the browsers, which echo the `Last-Modified` value.
+fn parse_http_date(date: &str) -> Option<u64> {
+  let parts: Vec<&str> = date.split_whitespace().collect();
+  let (day, month, year, time) = match parts.as_slice() {
+    [_, day, month, year, time, "GMT"] => (day, month, year, time),
+    _ => return None,
+  };
+  let day: i64 = day.parse().ok()?;
+  let month = MONTHS.iter().position(|name| name == month)? as i64;
+  let year: i64 = year.parse().ok()?;
+  let mut time = time.split(':').map(|value| value.parse::<u64>().ok());
+  let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+  if time.next().is_some() || !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60
+  {
+    return None;
+  }
+  // the days since the epoch of the civil date, with the years starting in March
+  let (year, month) = if month < 2 {
+    (year - 1, month + 10)
+  } else {
+    (year, month - 2)
+  };
+  let era = year.div_euclid(400);
+  let year_of_era = year.rem_euclid(400);
+  let day_of_year = (153 * month + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  let days = era * 146_097 + day_of_era - 719_468;
+  if days < 0 {
+    return None;
+  }
+  Some(days as u64 * 86400 + hours * 3600 + minutes * 60 + seconds)
+}
+
+/// The value of a request header.
+fn header_value<'a>(headers: &'a [Header], name: &str) -> Option<&'a str> {
+  headers
+    .iter()
+    .find(|header| header.field.equiv(name))
+    .map(|header| header.value.as_str())
+}
+
+/// Parses a `Range` header value against the asset size.
+/// Multiple ranges and invalid values are ignored, so the whole asset is sent.
+fn parse_range(range: &str, size: u64) -> Range {
+  let range = match range.trim().strip_prefix("bytes=") {
+    Some(range) if !range.contains(',') => range,
+    _ => return Range::Full,
+  };
+  let (start, end) = match range.find('-') {
+    Some(i) => (range[..i].trim(), range[i + 1..].trim()),
+    None => return Range::Full,
+  };
+  let (start, end) = match (start.parse::<u64>(), end.parse::<u64>()) {
+    // `bytes=start-end`, the end being clamped to the asset size
+    (Ok(start), Ok(end)) if start <= end => (start, end.min(size.saturating_sub(1))),
+    // `bytes=start-`
+    (Ok(start), Err(_)) if end.is_empty() => (start, size.saturating_sub(1)),
+    // `bytes=-suffix`, the last `suffix` bytes
+    (Err(_), Ok(suffix)) if start.is_empty() => {
+      if suffix == 0 {
+        return Range::Unsatisfiable;
+      }
+      (size.saturating_sub(suffix), size.saturating_sub(1))
+    }
+    _ => return Range::Full,
+  };
+  if start >= size {
+    Range::Unsatisfiable
+  } else {
+    Range::Partial(start, end)
+  }
+}
+
+/// The `Content-Type` header of an asset path.
+fn mime_type(path: &str) -> &'static str {
+  let extension = path.rsplit('.').next().unwrap_or_default();
+  match extension {
+    "svg" => "Content-Type: image/svg+xml",
+    "css" => "Content-Type: text/css",
+    "html" => "Content-Type: text/html",
+    "js" => "Content-Type: text/javascript",
+    "mp4" => "Content-Type: video/mp4",
+    "webm" => "Content-Type: video/webm",
+    "mp3" => "Content-Type: audio/mpeg",
+    "ogg" => "Content-Type: audio/ogg",
+    "wav" => "Content-Type: audio/wav",
+    _ => "Content-Type: application/octet-stream",
+  }
+}
+
+fn header(header: &str) -> Header {
+  header
     .parse()
-    .unwrap_or_else(|_| panic!("Could not add {} header", mime));
-  headers.push(content_type);
+    .unwrap_or_else(|_| panic!("Could not add {} header", header))
+}
+
+#[cfg(test)]
+mod test {
+  use super::{
+    accepts, http_date, matches_etag, parse_http_date, parse_range, resolve_asset,
+    response_encoding, Encoding, HeaderRules, Range,
+  };
+  use tauri_api::{
+    assets::{phf, phf::phf_map, AssetCompression, Assets},
+    config::{AssetHeaders, EmbeddedServerCompression},
+  };
+  use tiny_http::Method;
+
+  static ASSETS: Assets = Assets::new(phf_map! {
+    "/index.html" => (AssetCompression::None, "", 0, b"<html></html>"),
+    "/about" => (AssetCompression::None, "", 0, b"about"),
+    "/assets/app.js" => (AssetCompression::None, "", 0, b"app()"),
+  });
+
+  #[test]
+  fn ranges() {
+    assert_eq!(parse_range("bytes=0-99", 1000), Range::Partial(0, 99));
+    assert_eq!(parse_range("bytes=500-", 1000), Range::Partial(500, 999));
+    assert_eq!(parse_range("bytes=-100", 1000), Range::Partial(900, 999));
+    assert_eq!(
+      parse_range("bytes=900-5000", 1000),
+      Range::Partial(900, 999)
+    );
+    assert_eq!(parse_range("bytes=-5000", 1000), Range::Partial(0, 999));
+    assert_eq!(parse_range("bytes=1000-", 1000), Range::Unsatisfiable);
+    assert_eq!(parse_range("bytes=-0", 1000), Range::Unsatisfiable);
+    assert_eq!(parse_range("bytes=0-1,5-9", 1000), Range::Full);
+    assert_eq!(parse_range("bytes=9-1", 1000), Range::Full);
+    assert_eq!(parse_range("items=0-1", 1000), Range::Full);
+  }
+
+  #[test]
+  fn spa_fallback() {
+    let resolve = |path, method, spa_fallback| resolve_asset(path, &method, spa_fallback, &ASSETS);
+    // the existing assets are served as is, without the query string and fragment
+    assert_eq!(
+      resolve("/assets/app.js?v=2#main", Method::Get, true).as_deref(),
+      Some("/assets/app.js")
+    );
+    assert_eq!(
+      resolve("/about", Method::Post, true).as_deref(),
+      Some("/about")
+    );
+    assert_eq!(
+      resolve("/settings/profile?tab=1", Method::Get, true).as_deref(),
+      Some("/index.html")
+    );
+    assert_eq!(
+      resolve("/settings.v2/profile", Method::Get, true).as_deref(),
+      Some("/index.html")
+    );
+    // the missing files, the other methods and the disabled fallback are not found
+    assert_eq!(resolve("/assets/missing.js", Method::Get, true), None);
+    assert_eq!(resolve("/settings/profile", Method::Post, true), None);
+    assert_eq!(resolve("/settings/profile", Method::Get, false), None);
+  }
+
+  #[test]
+  fn accept_encoding() {
+    assert!(accepts(Some("gzip, deflate, br"), "br"));
+    assert!(accepts(Some("gzip;q=0.5"), "gzip"));
+    assert!(!accepts(Some("gzip;q=0, br"), "gzip"));
+    assert!(accepts(Some("*"), "br"));
+    assert!(!accepts(Some("br, *;q=0"), "gzip"));
+    assert!(!accepts(Some("deflate"), "gzip"));
+    assert!(!accepts(None, "gzip"));
+  }
+
+  #[test]
+  // the ranges get the identity representation and its ETag
+  fn response_encodings() {
+    let gzip = Some("gzip, br");
+    assert_eq!(
+      response_encoding(
+        AssetCompression::Brotli,
+        EmbeddedServerCompression::Auto,
+        gzip,
+        &Range::Full
+      ),
+      Encoding::Brotli
+    );
+    assert_eq!(
+      response_encoding(
+        AssetCompression::Brotli,
+        EmbeddedServerCompression::Auto,
+        Some("gzip"),
+        &Range::Full
+      ),
+      Encoding::Gzip
+    );
+    assert_eq!(
+      response_encoding(
+        AssetCompression::Gzip,
+        EmbeddedServerCompression::Auto,
+        None,
+        &Range::Full
+      ),
+      Encoding::Identity
+    );
+    assert_eq!(
+      response_encoding(
+        AssetCompression::Gzip,
+        EmbeddedServerCompression::Auto,
+        gzip,
+        &Range::Partial(0, 9)
+      ),
+      Encoding::Identity
+    );
+    assert_eq!(
+      response_encoding(
+        AssetCompression::Brotli,
+        EmbeddedServerCompression::Auto,
+        gzip,
+        &Range::Unsatisfiable
+      ),
+      Encoding::Identity
+    );
+  }
+
+  #[test]
+  fn http_dates() {
+    assert_eq!(http_date(0), "Thu, 01 Jan 1970 00:00:00 GMT");
+    assert_eq!(http_date(784111777), "Sun, 06 Nov 1994 08:49:37 GMT");
+    assert_eq!(http_date(951782400), "Tue, 29 Feb 2000 00:00:00 GMT");
+    for secs in (0..4_000_000_000).step_by(7_777_777) {
+      assert_eq!(parse_http_date(&http_date(secs)), Some(secs));
+    }
+    // the obsolete formats and invalid dates are ignored
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49 GMT"), None);
+    assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 25:49:37 GMT"), None);
+  }
+
+  #[test]
+  fn if_none_match() {
+    let etag = "\"abc-gzip\"";
+    assert!(matches_etag("\"abc-gzip\"", etag));
+    assert!(matches_etag("\"xyz-br\", W/\"abc-gzip\"", etag));
+    assert!(matches_etag("*", etag));
+    assert!(!matches_etag("\"abc-br\"", etag));
+    assert!(!matches_etag("\"abc\"", etag));
+  }
+
+  #[test]
+  // later rules override the headers of earlier ones
+  fn header_rules() {
+    let rule = |pattern: &str, name: &str, value: &str| AssetHeaders {
+      pattern: pattern.into(),
+      headers: vec![(name.to_string(), value.to_string())]
+        .into_iter()
+        .collect(),
+    };
+    let rules = HeaderRules::new(&[
+      rule("**/*", "Cache-Control", "immutable"),
+      rule("index.html", "cache-control", "no-store"),
+      rule("*.wasm", "Cross-Origin-Opener-Policy", "same-origin"),
+    ]);
+
+    let mut headers = Vec::new();
+    rules.apply("/index.html", &mut headers);
+    let headers: Vec<String> = headers.iter().map(ToString::to_string).collect();
+    assert_eq!(headers, vec!["cache-control: no-store"]);
 
-  Response::new(StatusCode(200), headers, asset, None, None)
+    let mut headers = Vec::new();
+    rules.apply("/assets/app.js", &mut headers);
+    let headers: Vec<String> = headers.iter().map(ToString::to_string).collect();
+    assert_eq!(headers, vec!["Cache-Control: immutable"]);
+  }
 }
@@ -0,0 +1,553 @@
+use std::{
+  io::{self, BufRead, BufReader, Read, Write},
+  net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+  sync::Arc,
+  thread,
+  time::Duration,
+};
+use tauri_api::config::ProxyRule;
+use url::Url;
+
+/// The maximum size of a request or response head, so a client can't make the server buffer without end.
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+/// How long connecting to an upstream can take before the request fails with a 502.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// The headers of a connection, which aren't forwarded.
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+  "Connection",
+  "Keep-Alive",
+  "Proxy-Connection",
+  "Proxy-Authenticate",
+  "Proxy-Authorization",
+  "TE",
+  "Trailer",
+  "Upgrade",
+];
+
+/// The `tauri > embeddedServer > proxy` rules, sorted by the longest path prefix.
+pub struct ProxyRules(Vec<Rule>);
+
+struct Rule {
+  path: String,
+  upstream: Url,
+  strip_prefix: bool,
+  request_headers: Vec<(String, Option<String>)>,
+  response_headers: Vec<(String, Option<String>)>,
+}
+
+impl Rule {
+  /// Whether the request target is under the path prefix, on whole path segments.
+  fn matches(&self, target: &str) -> bool {
+    let path = target.split('?').next().unwrap_or_default();
+    path == self.path
+      || (path.starts_with(&self.path)
+        && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')))
+  }
+
+  /// The request target on the upstream: the request path appended to the upstream path.
+  fn target(&self, target: &str) -> String {
+    let target = if self.strip_prefix {
+      &target[self.path.trim_end_matches('/').len()..]
+    } else {
+      target
+    };
+    let separator = if target.starts_with('/') { "" } else { "/" };
+    format!(
+      "{}{}{}",
+      self.upstream.path().trim_end_matches('/'),
+      separator,
+      target
+    )
+  }
+
+  /// The `Host` header of the upstream requests.
+  fn host(&self) -> String {
+    let host = self.upstream.host_str().unwrap_or_default();
+    match self.upstream.port() {
+      Some(port) => format!("{}:{}", host, port),
+      None => host.to_string(),
+    }
+  }
+}
+
+impl ProxyRules {
+  /// Parses the rules, validated by the CLI.
+  pub fn new(config: &[ProxyRule]) -> Self {
+    let mut rules: Vec<Rule> = config
+      .iter()
+      .map(|rule| Rule {
+        path: rule.path.clone(),
+        upstream: Url::parse(&rule.upstream)
+          .unwrap_or_else(|_| panic!("Invalid proxy upstream {}", rule.upstream)),
+        strip_prefix: rule.strip_prefix,
+        request_headers: rule
+          .request_headers
+          .iter()
+          .map(|(name, value)| (name.clone(), value.clone()))
+          .collect(),
+        response_headers: rule
+          .response_headers
+          .iter()
+          .map(|(name, value)| (name.clone(), value.clone()))
+          .collect(),
+      })
+      .collect();
+    rules.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+    Self(rules)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  fn find(&self, target: &str) -> Option<&Rule> {
+    self.0.iter().find(|rule| rule.matches(target))
+  }
+
+  /// Accepts the connections of the embedded server address, forwarding the requests matching a rule to its upstream
+  /// and the other requests to the assets server.
+  /// Each connection is handled on its own thread, so the streamed bodies and the WebSockets don't block the others.
+  pub fn serve(self, listener: TcpListener, assets_address: SocketAddr) {
+    let rules = Arc::new(self);
+    for client in listener.incoming() {
+      let client = match client {
+        Ok(client) => client,
+        Err(_) => continue,
+      };
+      let rules = rules.clone();
+      // a failed connection only closes that connection
+      thread::spawn(move || {
+        let _ = handle(client, &rules, assets_address);
+      });
+    }
+  }
+}
+
+/// A HTTP/1.x request or response head.
+#[derive(Debug, PartialEq)]
+struct Head {
+  /// The request line or the status line.
+  start_line: String,
+  headers: Vec<(String, String)>,
+}
+
+impl Head {
+  /// Reads a head, returning `None` if the connection is closed before it starts.
+  fn read(reader: &mut impl BufRead) -> io::Result<Option<Self>> {
+    let mut lines = Vec::new();
+    let mut size = 0;
+    loop {
+      let mut line = String::new();
+      let read = reader
+        .by_ref()
+        .take((MAX_HEAD_SIZE - size) as u64 + 1)
+        .read_line(&mut line)?;
+      size += read;
+      if read == 0 {
+        if lines.is_empty() {
+          return Ok(None);
+        }
+        return Err(invalid_data(
+          "the connection closed before the end of the head",
+        ));
+      }
+      if size > MAX_HEAD_SIZE {
+        return Err(invalid_data("the head is too large"));
+      }
+      let line = line.trim_end_matches(&['\r', '\n'][..]).to_string();
+      if line.is_empty() {
+        // the empty lines before a request line are ignored, as RFC 7230 recommends
+        if lines.is_empty() {
+          continue;
+        }
+        break;
+      }
+      lines.push(line);
+    }
+
+    let mut lines = lines.into_iter();
+    let start_line = lines.next().unwrap_or_default();
+    let headers = lines
+      .map(|line| {
+        let mut parts = line.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+          (Some(name), Some(value)) => Ok((name.trim().to_string(), value.trim().to_string())),
+          _ => Err(invalid_data("invalid header line")),
+        }
+      })
+      .collect::<io::Result<_>>()?;
+    Ok(Some(Self {
+      start_line,
+      headers,
+    }))
+  }
+
+  fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(header, _)| header.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+
+  fn remove(&mut self, name: &str) {
+    self
+      .headers
+      .retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+  }
+
+  fn set(&mut self, name: &str, value: impl Into<String>) {
+    self.remove(name);
+    self.headers.push((name.to_string(), value.into()));
+  }
+
+  /// Sets or removes the configured headers.
+  fn rewrite(&mut self, headers: &[(String, Option<String>)]) {
+    for (name, value) in headers {
+      match value {
+        Some(value) => self.set(name, value.as_str()),
+        None => self.remove(name),
+      }
+    }
+  }
+
+  /// The request target, e.g. `/api/users?page=2`.
+  fn target(&self) -> &str {
+    self.start_line.split(' ').nth(1).unwrap_or("/")
+  }
+
+  /// The response status code.
+  fn status(&self) -> u16 {
+    self
+      .start_line
+      .split(' ')
+      .nth(1)
+      .and_then(|status| status.parse().ok())
+      .unwrap_or_default()
+  }
+
+  fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+    let mut head = format!("{}\r\n", self.start_line);
+    for (name, value) in &self.headers {
+      head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    writer.write_all(head.as_bytes())?;
+    writer.flush()
+  }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// The connection to an upstream.
+enum Upstream {
+  Plain(TcpStream),
+  Tls(Box<native_tls::TlsStream<TcpStream>>),
+}
+
+impl Upstream {
+  fn connect(url: &Url) -> io::Result<Self> {
+    let host = url
+      .host_str()
+      .ok_or_else(|| invalid_data("the upstream doesn't have a host"))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let address = (host, port)
+      .to_socket_addrs()?
+      .next()
+      .ok_or_else(|| invalid_data("the upstream host doesn't resolve to an address"))?;
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    if url.scheme() == "https" {
+      let connector = native_tls::TlsConnector::new()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+      let stream = connector
+        .connect(host, stream)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+      Ok(Self::Tls(Box::new(stream)))
+    } else {
+      Ok(Self::Plain(stream))
+    }
+  }
+}
+
+impl Read for Upstream {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    match self {
+      Self::Plain(stream) => stream.read(buf),
+      Self::Tls(stream) => stream.read(buf),
+    }
+  }
+}
+
+impl Write for Upstream {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    match self {
+      Self::Plain(stream) => stream.write(buf),
+      Self::Tls(stream) => stream.write(buf),
+    }
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    match self {
+      Self::Plain(stream) => stream.flush(),
+      Self::Tls(stream) => stream.flush(),
+    }
+  }
+}
+
+/// Handles a connection, which only carries one request so the next requests are matched against the rules too.
+fn handle(client: TcpStream, rules: &ProxyRules, assets_address: SocketAddr) -> io::Result<()> {
+  let mut reader = BufReader::new(client.try_clone()?);
+  let mut head = match Head::read(&mut reader)? {
+    Some(head) => head,
+    None => return Ok(()),
+  };
+  match rules.find(head.target()) {
+    Some(rule) => forward(reader, client, head, rule),
+    None => {
+      head.set("Connection", "close");
+      let assets = TcpStream::connect(assets_address)?;
+      head.write_to(&mut &assets)?;
+      let assets_reader = assets.try_clone()?;
+      // the request body, e.g. of a `POST` to an asset path, is streamed while the response is read
+      thread::spawn(move || {
+        let _ = io::copy(&mut reader, &mut &assets);
+        let _ = assets.shutdown(Shutdown::Write);
+      });
+      let result = io::copy(&mut &assets_reader, &mut &client);
+      let _ = client.shutdown(Shutdown::Both);
+      result.map(|_| ())
+    }
+  }
+}
+
+/// Forwards the request to the rule upstream and streams the response back,
+/// replying with a 502 JSON error if the upstream can't be reached.
+fn forward(
+  mut reader: BufReader<TcpStream>,
+  client: TcpStream,
+  mut head: Head,
+  rule: &Rule,
+) -> io::Result<()> {
+  let websocket = head
+    .header("Upgrade")
+    .map_or(false, |upgrade| upgrade.eq_ignore_ascii_case("websocket"));
+  let mut parts = head.start_line.splitn(3, ' ');
+  let method = parts.next().unwrap_or("GET").to_string();
+  let target = rule.target(parts.next().unwrap_or("/"));
+  head.start_line = format!("{} {} HTTP/1.1", method, target);
+  let client_host = head.header("Host").map(ToString::to_string);
+  for name in HOP_BY_HOP_HEADERS {
+    head.remove(name);
+  }
+  head.set("Host", rule.host());
+  if let Some(client_host) = client_host {
+    head.set("X-Forwarded-Host", client_host);
+  }
+  head.set("X-Forwarded-Proto", "http");
+  head.rewrite(&rule.request_headers);
+  if websocket {
+    head.set("Connection", "Upgrade");
+    head.set("Upgrade", "websocket");
+  } else {
+    head.set("Connection", "close");
+  }
+
+  if websocket && rule.upstream.scheme() != "http" {
+    return bad_gateway(
+      client,
+      rule,
+      "the WebSocket upgrades are only forwarded to `http` upstreams",
+    );
+  }
+  let mut upstream = match Upstream::connect(&rule.upstream) {
+    Ok(upstream) => upstream,
+    Err(e) => return bad_gateway(client, rule, &e.to_string()),
+  };
+  if let Err(e) = head.write_to(&mut upstream) {
+    return bad_gateway(client, rule, &e.to_string());
+  }
+
+  let upstream = match upstream {
+    // the request body is streamed while the response is read, which the WebSockets need
+    Upstream::Plain(stream) => {
+      let request_writer = stream.try_clone()?;
+      thread::spawn(move || {
+        let _ = io::copy(&mut reader, &mut &request_writer);
+        let _ = request_writer.shutdown(Shutdown::Write);
+      });
+      Upstream::Plain(stream)
+    }
+    // a TLS stream can't be split, so the request body is sent before the response is read
+    Upstream::Tls(mut stream) => {
+      if let Err(e) = copy_body(&head, &mut reader, &mut stream) {
+        return bad_gateway(client, rule, &e.to_string());
+      }
+      Upstream::Tls(stream)
+    }
+  };
+
+  let mut upstream = BufReader::new(upstream);
+  let mut response = match Head::read(&mut upstream) {
+    Ok(Some(response)) => response,
+    Ok(None) => {
+      return bad_gateway(
+        client,
+        rule,
+        "the upstream closed the connection without a response",
+      )
+    }
+    Err(e) => return bad_gateway(client, rule, &e.to_string()),
+  };
+  let upgraded = websocket && response.status() == 101;
+  for name in HOP_BY_HOP_HEADERS {
+    response.remove(name);
+  }
+  if upgraded {
+    response.set("Connection", "Upgrade");
+    response.set("Upgrade", "websocket");
+  } else {
+    response.set("Connection", "close");
+  }
+  response.rewrite(&rule.response_headers);
+  response.write_to(&mut &client)?;
+  // the response ends when the upstream closes the connection, so the body is streamed as it comes
+  let result = io::copy(&mut upstream, &mut &client);
+  let _ = client.shutdown(Shutdown::Both);
+  result.map(|_| ())
+}
+
+/// Copies a request body, delimited by its `Content-Length` or chunked `Transfer-Encoding`.
+fn copy_body(head: &Head, reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+  let chunked = head.header("Transfer-Encoding").map_or(false, |encoding| {
+    encoding.to_lowercase().contains("chunked")
+  });
+  if chunked {
+    loop {
+      let mut size_line = String::new();
+      reader.read_line(&mut size_line)?;
+      writer.write_all(size_line.as_bytes())?;
+      let size = size_line.split(';').next().unwrap_or_default().trim();
+      let size = u64::from_str_radix(size, 16).map_err(|_| invalid_data("invalid chunk size"))?;
+      if size == 0 {
+        // the trailers, up to the empty line
+        loop {
+          let mut line = String::new();
+          if reader.read_line(&mut line)? == 0 {
+            break;
+          }
+          writer.write_all(line.as_bytes())?;
+          if line.trim().is_empty() {
+            break;
+          }
+        }
+        break;
+      }
+      // the chunk and its CRLF
+      let copied = io::copy(&mut reader.by_ref().take(size + 2), writer)?;
+      if copied < size + 2 {
+        return Err(invalid_data(
+          "the request body ended before the end of a chunk",
+        ));
+      }
+    }
+  } else if let Some(length) = head
+    .header("Content-Length")
+    .and_then(|length| length.parse::<u64>().ok())
+  {
+    io::copy(&mut reader.by_ref().take(length), writer)?;
+  }
+  writer.flush()
+}
+
+/// Replies with a 502 status and a JSON body describing the upstream failure.
+fn bad_gateway(client: TcpStream, rule: &Rule, message: &str) -> io::Result<()> {
+  let body = serde_json::json!({
+    "error": "badGateway",
+    "upstream": rule.upstream.as_str(),
+    "message": message,
+  })
+  .to_string();
+  let response = format!(
+    "HTTP/1.1 502 Bad Gateway\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    body.len(),
+    body
+  );
+  let result = (&client).write_all(response.as_bytes());
+  let _ = client.shutdown(Shutdown::Both);
+  result
+}
+
+#[cfg(test)]
+mod test {
+  use super::{copy_body, Head, ProxyRules};
+  use tauri_api::config::ProxyRule;
+
+  use std::{collections::HashMap, io::Cursor};
+
+  fn rule(path: &str, upstream: &str, strip_prefix: bool) -> ProxyRule {
+    ProxyRule {
+      path: path.into(),
+      upstream: upstream.into(),
+      strip_prefix,
+      request_headers: HashMap::new(),
+      response_headers: HashMap::new(),
+    }
+  }
+
+  #[test]
+  fn longest_prefix_wins() {
+    let rules = ProxyRules::new(&[
+      rule("/api", "http://localhost:3000", false),
+      rule("/api/auth", "https://auth.example.com/v1", true),
+    ]);
+    assert!(rules.find("/apis").is_none());
+    assert!(rules.find("/index.html").is_none());
+    let api = rules.find("/api?page=2").unwrap();
+    assert_eq!(api.target("/api?page=2"), "/api?page=2");
+    assert_eq!(api.host(), "localhost:3000");
+    let auth = rules.find("/api/auth/login").unwrap();
+    assert_eq!(auth.target("/api/auth/login"), "/v1/login");
+    assert_eq!(auth.target("/api/auth?next=/"), "/v1/?next=/");
+    assert_eq!(auth.host(), "auth.example.com");
+  }
+
+  #[test]
+  fn heads() {
+    let mut reader = Cursor::new(
+      "\r\nGET /api/users HTTP/1.1\r\nHost: 127.0.0.1:4000\r\nUpgrade: websocket\r\n\r\nbody",
+    );
+    let mut head = Head::read(&mut reader).unwrap().unwrap();
+    assert_eq!(head.target(), "/api/users");
+    assert_eq!(head.header("host"), Some("127.0.0.1:4000"));
+    head.rewrite(&[
+      ("Upgrade".into(), None),
+      ("Authorization".into(), Some("Bearer token".into())),
+    ]);
+    let mut written = Vec::new();
+    head.write_to(&mut written).unwrap();
+    assert_eq!(
+      String::from_utf8(written).unwrap(),
+      "GET /api/users HTTP/1.1\r\nHost: 127.0.0.1:4000\r\nAuthorization: Bearer token\r\n\r\n"
+    );
+    assert!(Head::read(&mut Cursor::new("")).unwrap().is_none());
+    assert!(Head::read(&mut Cursor::new("GET / HTTP/1.1\r\nHost")).is_err());
+  }
+
+  #[test]
+  fn chunked_body() {
+    let head = Head {
+      start_line: "POST /api HTTP/1.1".into(),
+      headers: vec![("Transfer-Encoding".into(), "chunked".into())],
+    };
+    let body = "4\r\nwiki\r\n5\r\npedia\r\n0\r\n\r\nnext request";
+    let mut reader = Cursor::new(body);
+    let mut written = Vec::new();
+    copy_body(&head, &mut reader, &mut written).unwrap();
+    assert_eq!(
+      String::from_utf8(written).unwrap(),
+      "4\r\nwiki\r\n5\r\npedia\r\n0\r\n\r\n"
+    );
+  }
+}